@@ -0,0 +1,267 @@
+//! Journal des requêtes bloquées (`[privacy] block_log`), pour trouver la
+//! règle qui casse un site.
+//!
+//! Chaque requête bloquée ajoute une ligne au fichier : date UTC, page,
+//! URL bloquée et type de requête, séparés par des tabulations
+//! ([`format_line`]). Le thread principal ne fait qu'envoyer la ligne sur
+//! un canal : l'écriture, tamponnée, se fait sur un thread de fond qui vide
+//! le tampon toutes les [`FLUSH_INTERVAL`] et à la fermeture du journal.
+//!
+//! Au-delà de [`MAX_LOG_BYTES`], le fichier est renommé en `<nom>.1` (qui
+//! remplace l'ancien) et un nouveau fichier commence : le journal occupe au
+//! plus deux fois cette taille.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tracing::{info, warn};
+
+/// Taille au-delà de laquelle le fichier est renommé.
+pub const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Délai maximal entre une ligne et son écriture sur disque.
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Journal ouvert : les lignes partent vers le thread d'écriture.
+#[derive(Debug)]
+pub struct BlockLog {
+    lines: Option<Sender<String>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl BlockLog {
+    /// Ouvre `path` en ajout (dossiers créés au besoin) et lance le thread
+    /// d'écriture.
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let writer = LogWriter::open(path.clone(), MAX_LOG_BYTES)?;
+        let (lines, received) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("block-log".to_string())
+            .spawn(move || write_lines(writer, received))?;
+        info!(path = %path.display(), "Blocked requests logged");
+        Ok(Self {
+            lines: Some(lines),
+            writer: Some(handle),
+        })
+    }
+
+    /// Note une requête bloquée. Ne bloque jamais : si le thread d'écriture
+    /// s'est arrêté (erreur disque), la ligne est perdue.
+    pub fn record(&self, page_url: &str, url: &str, request_type: &str) {
+        if let Some(lines) = &self.lines {
+            let _ = lines.send(format_line(SystemTime::now(), page_url, url, request_type));
+        }
+    }
+}
+
+impl Drop for BlockLog {
+    /// Ferme le canal et attend que le thread ait vidé son tampon.
+    fn drop(&mut self) {
+        drop(self.lines.take());
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Boucle du thread d'écriture : jusqu'à la fermeture du canal ou à la
+/// première erreur d'écriture.
+fn write_lines(mut writer: LogWriter, lines: Receiver<String>) {
+    let mut last_flush = Instant::now();
+    loop {
+        let result = match lines.recv_timeout(FLUSH_INTERVAL) {
+            Ok(line) => writer.write_line(&line).and_then(|()| {
+                if last_flush.elapsed() < FLUSH_INTERVAL {
+                    return Ok(());
+                }
+                last_flush = Instant::now();
+                writer.flush()
+            }),
+            Err(RecvTimeoutError::Timeout) => {
+                last_flush = Instant::now();
+                writer.flush()
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                if let Err(e) = writer.flush() {
+                    warn!(path = %writer.path.display(), error = %e, "Cannot write block log");
+                }
+                return;
+            }
+        };
+        if let Err(e) = result {
+            warn!(path = %writer.path.display(), error = %e, "Cannot write block log, logging stopped");
+            return;
+        }
+    }
+}
+
+/// Fichier du journal, tamponné, avec sa taille courante.
+struct LogWriter {
+    path: PathBuf,
+    file: BufWriter<File>,
+    len: u64,
+    max_bytes: u64,
+}
+
+impl LogWriter {
+    fn open(path: PathBuf, max_bytes: u64) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file: BufWriter::new(file),
+            len,
+            max_bytes,
+        })
+    }
+
+    /// Ajoute `line`, après rotation si elle ferait dépasser la taille
+    /// maximale.
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if needs_rotation(self.len, line.len(), self.max_bytes) {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    /// Renomme le fichier en `<nom>.1` et en recommence un vide.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        fs::rename(&self.path, rotated_path(&self.path))?;
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.file = BufWriter::new(file);
+        self.len = 0;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// `true` si ajouter `line_len` octets à un fichier de `len` octets dépasse
+/// `max_bytes`. Un fichier vide reçoit toujours la ligne.
+fn needs_rotation(len: u64, line_len: usize, max_bytes: u64) -> bool {
+    len > 0 && len.saturating_add(line_len as u64) > max_bytes
+}
+
+/// Chemin du fichier renommé : `blocked.log` → `blocked.log.1`.
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+/// Ligne du journal : `date\tpage\turl\ttype\n`. Une page inconnue est
+/// notée `-`.
+pub fn format_line(time: SystemTime, page_url: &str, url: &str, request_type: &str) -> String {
+    let page_url = if page_url.is_empty() { "-" } else { page_url };
+    format!(
+        "{}\t{page_url}\t{url}\t{request_type}\n",
+        utc_timestamp(time)
+    )
+}
+
+/// Date ISO 8601 en UTC, à la seconde (`2026-10-16T08:05:09Z`).
+pub fn utc_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Jours depuis 1970 → date civile (algorithme de H. Hinnant)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_line_format() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_792_137_909);
+        assert_eq!(
+            format_line(
+                time,
+                "https://news.example/article",
+                "https://ads.example/banner.js",
+                "script"
+            ),
+            "2026-10-16T08:05:09Z\thttps://news.example/article\thttps://ads.example/banner.js\tscript\n"
+        );
+        assert_eq!(
+            format_line(UNIX_EPOCH, "", "https://ads.example/p.gif", "image"),
+            "1970-01-01T00:00:00Z\t-\thttps://ads.example/p.gif\timage\n"
+        );
+        // 29 février d'une année bissextile
+        assert!(
+            utc_timestamp(UNIX_EPOCH + Duration::from_secs(951_782_400)).starts_with("2000-02-29T")
+        );
+    }
+
+    #[test]
+    fn test_rotation_trigger() {
+        assert!(!needs_rotation(0, 50, 10));
+        assert!(!needs_rotation(60, 40, 100));
+        assert!(needs_rotation(60, 41, 100));
+        assert!(needs_rotation(MAX_LOG_BYTES, 1, MAX_LOG_BYTES));
+        assert_eq!(
+            rotated_path(Path::new("/logs/blocked.log")),
+            Path::new("/logs/blocked.log.1")
+        );
+    }
+
+    #[test]
+    fn test_rotation_keeps_one_previous_file() {
+        let dir = TempDir::new("block-log");
+        let path = dir.join("rotate.log");
+        let rotated = rotated_path(&path);
+
+        let mut writer = LogWriter::open(path.clone(), 10).unwrap();
+        writer.write_line("first\n").unwrap();
+        writer.write_line("second\n").unwrap();
+        writer.write_line("third\n").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(fs::read_to_string(&rotated).unwrap(), "second\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+    }
+
+    #[test]
+    fn test_lines_written_when_log_dropped() {
+        let dir = TempDir::new("block-log");
+        let path = dir.join("drop.log");
+        let log = BlockLog::open(path.clone()).unwrap();
+        log.record("https://a.example/", "https://ads.example/x.js", "script");
+        drop(log);
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.ends_with("\thttps://a.example/\thttps://ads.example/x.js\tscript\n"));
+    }
+}
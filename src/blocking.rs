@@ -0,0 +1,403 @@
+//! Type des requêtes filtrées par adblock et réponse à renvoyer quand elles
+//! sont bloquées.
+//!
+//! Annuler une sous-ressource casse la page : icône d'image cassée, erreur
+//! de script, mise en page effondrée. Les images, feuilles de style et
+//! scripts bloqués reçoivent donc une réponse 200 neutre (GIF transparent,
+//! CSS ou JavaScript vide) ; seuls les documents et les types inconnus sont
+//! annulés. Ce module ne dépend ni de Servo ni d'adblock.
+//!
+//! ## Requêtes hors `Sec-Fetch-Dest`
+//!
+//! Trois sortes de requêtes de suivi n'ont pas de destination propre :
+//!
+//! - WebSocket : reconnu au schéma `ws:`/`wss:` ou à `Upgrade: websocket`,
+//!   type `websocket`. Seule la poignée de main HTTP passe par
+//!   `load_web_resource` ; les messages échangés ensuite, non.
+//! - `<a ping>` : reconnu à `Content-Type: text/ping` ou à `Ping-To`, type
+//!   `ping`. Servo n'implémente pas l'audit des liens : aucun ping n'est
+//!   envoyé aujourd'hui, la règle vaut pour le jour où il le fera.
+//! - `navigator.sendBeacon()` : rien ne le distingue d'un `fetch()`
+//!   (`Sec-Fetch-Dest: empty`, pas d'en-tête propre ; `keepalive` n'est pas
+//!   exposé par Servo). Classé `xmlhttprequest` : les règles sans type le
+//!   bloquent, celles limitées à `$ping` ne le voient pas.
+//!
+//! Le mode économie de données ([`TypeBlocking`]) bloque en plus, sans liste
+//! de filtres, toutes les images, polices ou médias.
+
+use url::Url;
+
+/// GIF 1×1 transparent (43 octets) servi à la place d'une image bloquée.
+pub const TRANSPARENT_GIF: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xFF, 0xFF, 0xFF, 0x21, 0xF9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2C, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3B,
+];
+
+/// Type d'une requête, au sens des listes de filtres (`$image`, `$script`…).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestType {
+    /// Navigation de premier niveau.
+    Document,
+    /// Document d'une iframe.
+    Subdocument,
+    Script,
+    Stylesheet,
+    Image,
+    Font,
+    Media,
+    /// `fetch()`, `XMLHttpRequest`, `sendBeacon()`.
+    XmlHttpRequest,
+    /// Poignée de main d'une connexion WebSocket.
+    WebSocket,
+    /// Ping d'audit de lien (`<a ping>`).
+    Ping,
+    Other,
+}
+
+/// En-têtes d'une requête utiles à son classement.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestHeaders<'a> {
+    /// `Sec-Fetch-Dest`.
+    pub fetch_dest: Option<&'a str>,
+    pub accept: Option<&'a str>,
+    pub upgrade: Option<&'a str>,
+    pub content_type: Option<&'a str>,
+    /// `Ping-To` (ping d'un lien vers un autre site).
+    pub ping_to: Option<&'a str>,
+}
+
+impl RequestType {
+    /// Déduit le type d'une requête : premier niveau, puis WebSocket et
+    /// ping, puis destination (`Sec-Fetch-Dest`), puis en-tête `Accept`
+    /// (Servo l'adapte à la destination), puis extension de l'URL.
+    pub fn classify(url: &Url, headers: RequestHeaders<'_>, is_for_main_frame: bool) -> Self {
+        if is_for_main_frame {
+            return Self::Document;
+        }
+        let has_value = |value: Option<&str>, expected: &str| {
+            value.is_some_and(|value| {
+                value
+                    .split([',', ';'])
+                    .any(|part| part.trim().eq_ignore_ascii_case(expected))
+            })
+        };
+        if matches!(url.scheme(), "ws" | "wss") || has_value(headers.upgrade, "websocket") {
+            return Self::WebSocket;
+        }
+        if headers.ping_to.is_some() || has_value(headers.content_type, "text/ping") {
+            return Self::Ping;
+        }
+        if let Some(request_type) = headers.fetch_dest.and_then(Self::from_fetch_dest) {
+            return request_type;
+        }
+        let accept = headers.accept.unwrap_or("").trim().to_ascii_lowercase();
+        if accept.starts_with("text/html") {
+            return Self::Subdocument;
+        }
+        if accept.starts_with("text/css") {
+            return Self::Stylesheet;
+        }
+        if accept.starts_with("image/") {
+            return Self::Image;
+        }
+        if accept.starts_with("video/") || accept.starts_with("audio/") {
+            return Self::Media;
+        }
+        let extension = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|name| name.rsplit_once('.'))
+            .map(|(_, ext)| ext.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("js" | "mjs") => Self::Script,
+            Some("css") => Self::Stylesheet,
+            Some("png" | "jpg" | "jpeg" | "gif" | "webp" | "avif" | "svg" | "ico" | "bmp") => {
+                Self::Image
+            }
+            Some("woff" | "woff2" | "ttf" | "otf") => Self::Font,
+            Some("mp4" | "webm" | "mp3" | "ogg" | "m4a" | "wav") => Self::Media,
+            _ => Self::Other,
+        }
+    }
+
+    /// Type d'après la destination de la requête (`Sec-Fetch-Dest`), `None`
+    /// si elle ne tranche pas.
+    fn from_fetch_dest(dest: &str) -> Option<Self> {
+        let request_type = match dest.trim().to_ascii_lowercase().as_str() {
+            "document" => Self::Document,
+            "iframe" | "frame" => Self::Subdocument,
+            "script" | "worker" | "sharedworker" | "serviceworker" | "audioworklet"
+            | "paintworklet" => Self::Script,
+            "style" => Self::Stylesheet,
+            "image" => Self::Image,
+            "font" => Self::Font,
+            "audio" | "video" | "track" => Self::Media,
+            // Requête émise par un script, sans destination
+            "empty" => Self::XmlHttpRequest,
+            "websocket" => Self::WebSocket,
+            _ => return None,
+        };
+        Some(request_type)
+    }
+
+    /// Nom du type pour le moteur adblock.
+    pub fn as_adblock_str(self) -> &'static str {
+        match self {
+            Self::Document => "document",
+            Self::Subdocument => "subdocument",
+            Self::Script => "script",
+            Self::Stylesheet => "stylesheet",
+            Self::Image => "image",
+            Self::Font => "font",
+            Self::Media => "media",
+            Self::XmlHttpRequest => "xmlhttprequest",
+            Self::WebSocket => "websocket",
+            Self::Ping => "ping",
+            Self::Other => "other",
+        }
+    }
+
+    /// Réponse neutre `(Content-Type, corps)` à servir quand la requête est
+    /// bloquée, ou `None` pour l'annuler.
+    pub fn blocked_response(self) -> Option<(&'static str, &'static [u8])> {
+        match self {
+            Self::Image => Some(("image/gif", TRANSPARENT_GIF)),
+            Self::Stylesheet => Some(("text/css", b"")),
+            Self::Script => Some(("application/javascript", b"")),
+            _ => None,
+        }
+    }
+}
+
+/// Types bloqués d'office, quelle que soit la page (`[privacy] block_images`,
+/// `block_fonts`, `block_media`). Les exceptions des listes de filtres ne
+/// s'y appliquent pas : c'est un choix explicite de l'utilisateur.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeBlocking {
+    pub images: bool,
+    pub fonts: bool,
+    pub media: bool,
+}
+
+impl TypeBlocking {
+    /// `true` si une requête de ce type est bloquée. Une navigation de
+    /// premier niveau (image ouverte directement…) ne l'est jamais.
+    pub fn blocks(self, request_type: RequestType) -> bool {
+        match request_type {
+            RequestType::Image => self.images,
+            RequestType::Font => self.fonts,
+            RequestType::Media => self.media,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(url: &str, accept: Option<&str>) -> RequestType {
+        let headers = RequestHeaders {
+            accept,
+            ..RequestHeaders::default()
+        };
+        RequestType::classify(&Url::parse(url).unwrap(), headers, false)
+    }
+
+    #[test]
+    fn test_classify_by_accept_then_extension() {
+        let url = Url::parse("https://ads.example.com/track").unwrap();
+        assert_eq!(
+            RequestType::classify(
+                &url,
+                RequestHeaders {
+                    fetch_dest: Some("script"),
+                    accept: Some("image/png"),
+                    ..RequestHeaders::default()
+                },
+                true
+            ),
+            RequestType::Document
+        );
+        assert_eq!(
+            classify(
+                "https://ads.example.com/pixel",
+                Some("image/avif,image/webp,*/*")
+            ),
+            RequestType::Image
+        );
+        assert_eq!(
+            classify("https://ads.example.com/s", Some("text/css,*/*;q=0.1")),
+            RequestType::Stylesheet
+        );
+        assert_eq!(
+            classify("https://ads.example.com/frame", Some("text/html,*/*")),
+            RequestType::Subdocument
+        );
+        assert_eq!(
+            classify("https://ads.example.com/ad.JS?v=2", Some("*/*")),
+            RequestType::Script
+        );
+        assert_eq!(
+            classify("https://ads.example.com/f.woff2", None),
+            RequestType::Font
+        );
+        assert_eq!(
+            classify("https://ads.example.com/collect", Some("*/*")),
+            RequestType::Other
+        );
+    }
+
+    #[test]
+    fn test_fetch_dest_takes_precedence() {
+        let dest = |url: &str, dest: &str, accept: Option<&str>| {
+            let headers = RequestHeaders {
+                fetch_dest: Some(dest),
+                accept,
+                ..RequestHeaders::default()
+            };
+            RequestType::classify(&Url::parse(url).unwrap(), headers, false)
+        };
+        // Script sans extension ni `Accept` parlant
+        assert_eq!(
+            dest("https://ads.example.com/loader", "script", Some("*/*")),
+            RequestType::Script
+        );
+        // `fetch()` d'une URL en `.js` : pas un script
+        assert_eq!(
+            dest("https://ads.example.com/config.js", "empty", Some("*/*")),
+            RequestType::XmlHttpRequest
+        );
+        assert_eq!(
+            dest("https://ads.example.com/frame", "iframe", None),
+            RequestType::Subdocument
+        );
+        assert_eq!(
+            dest("https://ads.example.com/track.mp4", "video", None),
+            RequestType::Media
+        );
+        // Destination inconnue : repli sur `Accept` puis l'extension
+        assert_eq!(
+            dest("https://ads.example.com/s", "report", Some("text/css")),
+            RequestType::Stylesheet
+        );
+        assert_eq!(
+            RequestType::XmlHttpRequest.as_adblock_str(),
+            "xmlhttprequest"
+        );
+        assert_eq!(RequestType::XmlHttpRequest.blocked_response(), None);
+    }
+
+    #[test]
+    fn test_blocked_responses() {
+        let (content_type, body) = RequestType::Image.blocked_response().unwrap();
+        assert_eq!(content_type, "image/gif");
+        assert!(body.starts_with(b"GIF89a"));
+        assert_eq!(body.last(), Some(&0x3B));
+        assert_eq!(
+            RequestType::Script.blocked_response(),
+            Some(("application/javascript", &b""[..]))
+        );
+        assert_eq!(
+            RequestType::Stylesheet.blocked_response(),
+            Some(("text/css", &b""[..]))
+        );
+        assert_eq!(RequestType::Document.blocked_response(), None);
+        assert_eq!(RequestType::Subdocument.blocked_response(), None);
+        assert_eq!(RequestType::Other.blocked_response(), None);
+    }
+
+    #[test]
+    fn test_websocket_and_ping() {
+        let classify = |url: &str, headers: RequestHeaders<'_>| {
+            RequestType::classify(&Url::parse(url).unwrap(), headers, false)
+        };
+        let ws = classify(
+            "wss://live.tracker.example/socket",
+            RequestHeaders::default(),
+        );
+        assert_eq!(ws, RequestType::WebSocket);
+        assert_eq!(ws.as_adblock_str(), "websocket");
+        // Poignée de main en http(s), même avec `Sec-Fetch-Dest: empty`
+        assert_eq!(
+            classify(
+                "https://live.tracker.example/socket",
+                RequestHeaders {
+                    fetch_dest: Some("empty"),
+                    upgrade: Some("WebSocket"),
+                    ..RequestHeaders::default()
+                }
+            ),
+            RequestType::WebSocket
+        );
+
+        let ping = RequestHeaders {
+            fetch_dest: Some("empty"),
+            content_type: Some("text/ping"),
+            ..RequestHeaders::default()
+        };
+        assert_eq!(
+            classify("https://audit.example/click", ping),
+            RequestType::Ping
+        );
+        assert_eq!(RequestType::Ping.as_adblock_str(), "ping");
+        let ping_to = RequestHeaders {
+            ping_to: Some("https://news.example/"),
+            ..RequestHeaders::default()
+        };
+        assert_eq!(
+            classify("https://audit.example/click", ping_to),
+            RequestType::Ping
+        );
+
+        // Beacon : indiscernable d'un `fetch()`
+        let beacon = RequestHeaders {
+            fetch_dest: Some("empty"),
+            content_type: Some("text/plain;charset=UTF-8"),
+            ..RequestHeaders::default()
+        };
+        assert_eq!(
+            classify("https://audit.example/collect", beacon),
+            RequestType::XmlHttpRequest
+        );
+        // Aucun des deux n'a de réponse neutre : annulés
+        assert_eq!(RequestType::WebSocket.blocked_response(), None);
+        assert_eq!(RequestType::Ping.blocked_response(), None);
+    }
+
+    #[test]
+    fn test_type_blocking() {
+        let all = [
+            RequestType::Document,
+            RequestType::Subdocument,
+            RequestType::Script,
+            RequestType::Stylesheet,
+            RequestType::Image,
+            RequestType::Font,
+            RequestType::Media,
+            RequestType::XmlHttpRequest,
+            RequestType::WebSocket,
+            RequestType::Ping,
+            RequestType::Other,
+        ];
+        assert!(all.iter().all(|&t| !TypeBlocking::default().blocks(t)));
+
+        let images = TypeBlocking {
+            images: true,
+            ..TypeBlocking::default()
+        };
+        assert!(images.blocks(RequestType::Image));
+        assert!(!images.blocks(RequestType::Font));
+        assert!(!images.blocks(RequestType::Document));
+
+        let fonts_media = TypeBlocking {
+            images: false,
+            fonts: true,
+            media: true,
+        };
+        assert!(fonts_media.blocks(RequestType::Font));
+        assert!(fonts_media.blocks(RequestType::Media));
+        assert!(!fonts_media.blocks(RequestType::Image));
+    }
+}
@@ -0,0 +1,182 @@
+//! Favoris persistés dans `bookmarks.json`, à côté de `config.toml`.
+//!
+//! Le fichier est une simple liste JSON de `{ url, title, added_at }`. Il est
+//! relu au démarrage et réécrit en entier (écriture atomique) à chaque
+//! modification : quelques centaines d'entrées tiennent en quelques Ko.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config;
+
+/// Nom du fichier de favoris dans [`config::data_dir`].
+pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// Un favori.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub url: String,
+    pub title: String,
+    /// Date d'ajout (secondes depuis l'epoch Unix).
+    pub added_at: u64,
+}
+
+/// Liste des favoris et fichier associé.
+#[derive(Debug, Default)]
+pub struct BookmarkStore {
+    path: PathBuf,
+    bookmarks: Vec<Bookmark>,
+}
+
+impl BookmarkStore {
+    /// Charge les favoris depuis l'emplacement par défaut.
+    pub fn load_default() -> Self {
+        Self::load(config::data_dir().join(BOOKMARKS_FILE))
+    }
+
+    /// Charge les favoris depuis `path`. Un fichier absent donne une liste
+    /// vide ; un fichier invalide aussi, avec un avertissement.
+    pub fn load(path: PathBuf) -> Self {
+        let bookmarks = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "Invalid bookmarks file, starting empty");
+                Vec::new()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Cannot read bookmarks");
+                Vec::new()
+            }
+        };
+        Self { path, bookmarks }
+    }
+
+    /// Écrit les favoris sur disque (écriture atomique).
+    pub fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(&self.bookmarks).map_err(io::Error::other)?;
+        config::write_atomic(&self.path, &json)?;
+        info!(path = %self.path.display(), count = self.bookmarks.len(), "Bookmarks saved");
+        Ok(())
+    }
+
+    /// Chemin du fichier de favoris.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Tous les favoris, dans l'ordre d'ajout.
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    /// `true` si `url` est dans les favoris.
+    pub fn contains(&self, url: &str) -> bool {
+        self.bookmarks.iter().any(|b| b.url == url)
+    }
+
+    /// Ajoute un favori. Retourne `false` (sans rien changer) s'il existe déjà.
+    pub fn add(&mut self, url: &str, title: &str) -> bool {
+        if self.contains(url) {
+            return false;
+        }
+        let added_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.bookmarks.push(Bookmark {
+            url: url.to_string(),
+            title: title.to_string(),
+            added_at,
+        });
+        true
+    }
+
+    /// Retire un favori. Retourne `false` s'il n'existait pas.
+    pub fn remove(&mut self, url: &str) -> bool {
+        let before = self.bookmarks.len();
+        self.bookmarks.retain(|b| b.url != url);
+        self.bookmarks.len() != before
+    }
+
+    /// Ajoute ou retire `url` ; retourne `true` si la page est désormais en favori.
+    pub fn toggle(&mut self, url: &str, title: &str) -> bool {
+        if self.remove(url) {
+            false
+        } else {
+            self.add(url, title)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_add_and_contains() {
+        let mut store = BookmarkStore::default();
+        assert!(store.add("https://example.com/", "Example"));
+        assert!(store.contains("https://example.com/"));
+        assert!(!store.contains("https://example.org/"));
+        assert_eq!(store.bookmarks()[0].title, "Example");
+    }
+
+    #[test]
+    fn test_add_duplicate_is_ignored() {
+        let mut store = BookmarkStore::default();
+        assert!(store.add("https://example.com/", "Example"));
+        assert!(!store.add("https://example.com/", "Other title"));
+        assert_eq!(store.bookmarks().len(), 1);
+        assert_eq!(store.bookmarks()[0].title, "Example");
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut store = BookmarkStore::default();
+        store.add("https://example.com/", "Example");
+        assert!(store.remove("https://example.com/"));
+        assert!(!store.remove("https://example.com/"));
+        assert!(store.bookmarks().is_empty());
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut store = BookmarkStore::default();
+        assert!(store.toggle("https://example.com/", "Example"));
+        assert!(!store.toggle("https://example.com/", "Example"));
+        assert!(!store.contains("https://example.com/"));
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let dir = TempDir::new("bookmarks");
+        let path = dir.join("roundtrip.json");
+        let mut store = BookmarkStore::load(path.clone());
+        store.add("https://example.com/", "Example");
+        store.add("https://servo.org/", "Servo");
+        store.save().unwrap();
+
+        let reloaded = BookmarkStore::load(path);
+        assert_eq!(reloaded.bookmarks(), store.bookmarks());
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let dir = TempDir::new("bookmarks");
+        let store = BookmarkStore::load(dir.join("missing.json"));
+        assert!(store.bookmarks().is_empty());
+    }
+
+    #[test]
+    fn test_load_invalid_file_is_empty() {
+        let dir = TempDir::new("bookmarks");
+        let path = dir.join("invalid.json");
+        fs::write(&path, "{ not json").unwrap();
+        assert!(BookmarkStore::load(path).bookmarks().is_empty());
+    }
+}
@@ -8,7 +8,7 @@
 //! à deux états :
 //!
 //! ```text
-//! App::Initial(Waker)  →  [resumed() appelé]  →  App::Running(Rc<AppState>)
+//! App::Initial { waker, url, config }  →  [resumed()]  →  App::Running(Rc<AppState>)
 //! ```
 //!
 //! ## Flux de communication Winit ↔ Servo
@@ -33,7 +33,7 @@
 //! ```text
 //! Window (1280x800)
 //! ┌──────────────────────────────────────┐
-//! │ Chrome (40px) — GL direct sur window │
+//! │ Chrome (40px par défaut) — GL direct │
 //! ├──────────────────────────────────────┤
 //! │ Servo WebView — OffscreenRenderCtx   │
 //! │ blitté dans la zone restante         │
@@ -41,110 +41,2723 @@
 //! ```
 
 use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
 
+use embedder_traits::MediaSessionActionType;
 use euclid::Scale;
+use ipc_channel::ipc::IpcSender;
+use servo::AuthenticationRequest;
+use servo::ContextMenu as ServoContextMenu;
+use servo::FilterPattern;
+use servo::StorageType;
+use servo::{AlertResponse, ConfirmResponse, PermissionRequest, PromptResponse, SimpleDialog};
+use servo::{CompositionEvent, CompositionState, Cursor, ImeEvent, WebViewId};
 use servo::{InputEvent, WheelDelta, WheelEvent, WheelMode};
-use servo::{MouseButton as ServoMouseButton, MouseButtonAction, MouseButtonEvent};
-use servo::{MouseLeftViewportEvent, MouseMoveEvent};
 use servo::{
-    OffscreenRenderingContext, RenderingContext, Servo, ServoBuilder, WebView, WebViewBuilder,
-    WindowRenderingContext,
+    JSValue, OffscreenRenderingContext, RenderingContext, Servo, ServoBuilder, WebView,
+    WebViewBuilder, WindowRenderingContext,
 };
+use servo::{MouseButton as ServoMouseButton, MouseButtonAction, MouseButtonEvent};
+use servo::{MouseLeftViewportEvent, MouseMoveEvent};
+use servo::{TouchEvent, TouchEventType, TouchId};
 use url::Url;
-use webrender_api::units::DevicePoint;
+use webrender_api::units::{DeviceIntRect, DeviceIntSize, DevicePoint};
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
-use winit::event::{ElementState, MouseButton as WinitMouseButton, MouseScrollDelta, WindowEvent};
-use winit::event_loop::EventLoop;
+use winit::event::{
+    ElementState, Ime, MouseButton as WinitMouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+};
+use winit::event_loop::{ControlFlow, EventLoop};
 use winit::keyboard::{Key, NamedKey};
 use winit::raw_window_handle::{HasDisplayHandle, HasWindowHandle};
-use winit::window::Window;
+use winit::window::{Theme, Window};
+
+use crate::block_log::BlockLog;
+use crate::bookmarks::BookmarkStore;
+use crate::browsing_data;
+use crate::cert_error::{self, CertErrorReport, CertExceptions, PendingException};
+use crate::chrome::{ChromeButton, ChromeFrame, ChromeHit, ChromeRenderer, SiteSecurity};
+use crate::clipboard::Clipboard;
+use crate::config::{self as config_file, ChromeConfig, Config, FilterListConfig, WindowConfig};
+use crate::config_reload;
+use crate::console::ConsoleLimiter;
+use crate::context_menu::{ContextAction, ContextMenu, ContextTarget};
+use crate::crash::{self, FrameWatchdog};
+use crate::delegate_guard::PanicStats;
+use crate::dialog::JsDialog;
+use crate::download::{self, DownloadResult, Downloads, Polled};
+use crate::error_page::{self, NetErrorKind, NetErrorReport};
+use crate::export;
+use crate::external;
+use crate::favicon::FaviconStore;
+use crate::file_picker;
+use crate::find::{self, FindBar, FindMatches};
+use crate::fullscreen::Fullscreen;
+use crate::https_only::{self, HttpsUpgrades};
+use crate::keybindings::{Action, Keybindings};
+use crate::load_state::LoadState;
+use crate::media::{self, MediaAction, MediaKey, MediaSessions};
+use crate::menu::{Menu, MenuItem};
+use crate::navigation::PageDisplay;
+use crate::no_js;
+use crate::pages::{self, InternalPage};
+use crate::permissions::{Decision, Outcome, PermissionStore};
+use crate::preferences::{apply_devtools, apply_private_mode, build_servo_preferences};
+use crate::privacy::{
+    self, AdblockEngine, AdblockStatus, BlockCounters, CompiledEngine, EngineSlot, FilterUpdater,
+    StatusAction,
+};
+use crate::privacy_signals::PrivacySignals;
+use crate::referrer;
+use crate::rendering;
+use crate::safe_browsing::{self, HostBlocklist, SessionExceptions};
+use crate::servo_glue::{Waker, WakerEvent, cursor_icon};
+use crate::settings;
+use crate::text_input::TextInput;
+use crate::third_party::SiteRoles;
+use crate::urlbar::UrlBar;
+use crate::webview_list::WebViewList;
+use crate::window_state::{MonitorRect, WindowState};
+use crate::zoom::{self, DEFAULT_ZOOM, WheelZoom, ZoomStore};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// AppState : état partagé entre Winit et Servo
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// État partagé de l'application, créé lors de `resumed()`.
+///
+/// Encapsulé dans `Rc` car :
+/// - `WebViewDelegate` attend un `Rc<dyn WebViewDelegate>`
+/// - Servo et l'App doivent référencer les mêmes données
+/// - Tout vit sur le thread principal (pas besoin d'`Arc`)
+pub struct AppState {
+    /// Handle de la fenêtre Winit.
+    pub window: Window,
+
+    /// Instance du moteur Servo.
+    pub servo: Servo,
+
+    /// Contexte de rendu OpenGL plein écran (surface fenêtre).
+    /// Utilisé pour le chrome (barre d'URL) et le blit du FBO.
+    pub window_rendering_context: Rc<WindowRenderingContext>,
+
+    /// Contexte de rendu offscreen (FBO) pour le WebView.
+    /// Servo peint dedans via `webview.paint()`.
+    pub offscreen_context: Rc<OffscreenRenderingContext>,
+
+    /// WebViews actives.
+    pub webviews: RefCell<WebViewList<WebView>>,
+
+    /// Position courante du curseur en device pixels.
+    pub cursor_position: Cell<DevicePoint>,
+
+    /// Contacts tactiles en cours (id Winit → zone où ils ont commencé).
+    pub touches: RefCell<HashMap<u64, TouchTarget>>,
+
+    /// État des modificateurs clavier (Ctrl, Shift, Alt, Meta).
+    pub modifiers: Cell<winit::keyboard::ModifiersState>,
+
+    /// Moteur adblock, remplacé quand les listes de filtres sont mises à jour
+    /// (reconstruit sur un thread de fond, voir [`EngineSlot`]). Vide au
+    /// démarrage, le temps que le thread de fond le compile.
+    pub adblock_engine: EngineSlot,
+
+    /// URL et titre (balise `<title>`) de la page affichée.
+    pub page: RefCell<PageDisplay>,
+
+    /// Favoris persistés (`bookmarks.json`).
+    pub bookmarks: RefCell<BookmarkStore>,
+
+    /// `true` si la page courante est dans les favoris (étoile pleine).
+    /// Recalculé à chaque `notify_url_changed`.
+    pub is_bookmarked: Cell<bool>,
+
+    /// Indicateur de sécurité (cadenas / avertissement) de la page courante.
+    /// Recalculé à chaque `notify_url_changed`.
+    pub site_security: Cell<SiteSecurity>,
+
+    /// Chargement et historique de chaque webview (delegates Servo). Celui
+    /// de la webview active pilote le bouton recharger ↔ arrêter.
+    pub load_states: RefCell<HashMap<WebViewId, LoadState>>,
+
+    /// Compteurs de l'adblock : session, et page de chaque webview (remis à
+    /// zéro à chaque navigation de son main frame). Indépendants du moteur,
+    /// ils survivent à sa reconstruction.
+    pub block_counters: BlockCounters<WebViewId>,
+
+    /// Favicon de chaque webview, avec sa génération : la texture du chrome
+    /// est ré-uploadée au prochain redraw quand la génération de la webview
+    /// active diffère (l'upload GL exige le contexte de la fenêtre actif).
+    pub favicons: RefCell<FaviconStore<WebViewId>>,
+
+    /// Menu déroulant du bouton « ≡ ».
+    pub menu: RefCell<Menu>,
+
+    /// Barre de recherche dans la page (Ctrl+F).
+    pub find: RefCell<FindBar>,
+
+    /// Pages visitées pendant la session (page « History » du menu).
+    pub visited: RefCell<Vec<Url>>,
+
+    /// Bouton du chrome sous le pointeur (état de survol).
+    pub hovered_button: Cell<Option<ChromeButton>>,
+
+    /// Cible du lien survolé (bulle de statut), `None` hors survol.
+    pub status_text: RefCell<Option<String>>,
+
+    /// État de la barre d'URL.
+    pub urlbar: RefCell<UrlBar>,
+
+    /// Renderer GL pour le chrome (barre d'URL). `None` si sa création a
+    /// échoué (driver GL récalcitrant) : le navigateur tourne alors sans chrome.
+    pub chrome: RefCell<Option<ChromeRenderer>>,
+
+    /// Hauteur effective du chrome : `[chrome] height`, ou 0 sans renderer.
+    /// Voir [`AppState::visible_chrome_height`] pour la hauteur affichée.
+    pub chrome_height: u32,
+
+    /// Plein écran demandé par l'utilisateur (F11) et/ou par la page.
+    pub fullscreen: Cell<Fullscreen>,
+
+    /// Configuration de l'application.
+    pub config: Config,
+
+    /// Navigation privée (`--private`) : ni cache HTTP ni stockage persistant
+    /// côté Servo, pas d'historique de session, et les zooms par site et
+    /// permissions mémorisés ne sont pas enregistrés sur disque.
+    pub private: bool,
+
+    /// Raccourcis globaux (défauts + section `[keybindings]`).
+    pub keybindings: Keybindings,
+
+    /// Waker de la boucle Winit, pour les threads de fond (exports…).
+    pub waker: Waker,
+
+    /// Section `[chrome]` courante — remplacée à chaud quand `config.toml`
+    /// change, pour que thème et palettes suivent les éditions sans redémarrer.
+    pub chrome_config: RefCell<ChromeConfig>,
+
+    /// Dernière version de `config.toml` appliquée par le hot-reload
+    /// (`[general] watch_config`) : base du diff suivant, et source des
+    /// réglages lus à chaque usage (défilement). `config` garde les valeurs
+    /// du démarrage.
+    pub live_config: RefCell<Config>,
+
+    /// Zoom de la page courante (1.0 = 100 %).
+    pub zoom: Cell<f32>,
+
+    /// Zooms mémorisés par hôte (`zoom.json`).
+    pub zoom_levels: RefCell<ZoomStore>,
+
+    /// Fractions de cran accumulées par Ctrl+molette.
+    pub wheel_zoom: RefCell<WheelZoom>,
+
+    /// Partie « page » du titre de la fenêtre (titre, URL…), conservée pour
+    /// recomposer le titre quand le zoom change.
+    pub page_label: RefCell<String>,
+
+    /// Servo a composité une nouvelle frame : `webview.paint()` au prochain redraw.
+    pub webview_dirty: Cell<bool>,
+
+    /// Seul le chrome a changé (frappe, survol…) : le FBO est re-blitté tel quel.
+    pub chrome_dirty: Cell<bool>,
+
+    /// Un redraw est demandé à Winit et pas encore présenté : les demandes
+    /// suivantes n'en ajoutent pas d'autre.
+    pub frame_pending: Cell<bool>,
+
+    /// Arrêt en cours (Ctrl+Q, menu, fermeture de la fenêtre) : la boucle
+    /// sort quand Servo a fini de s'éteindre.
+    pub shutting_down: Cell<bool>,
+
+    /// Limite de l'arrêt de Servo : passé ce délai, la boucle sort quand même.
+    pub shutdown_deadline: Cell<Option<Instant>>,
+
+    /// Fichiers déposés sur la fenêtre, ouverts dans `about_to_wait` une fois
+    /// tout le lot reçu (Winit émet un `DroppedFile` par fichier).
+    pub dropped_files: RefCell<Vec<Url>>,
+
+    /// `true` pendant une composition IME dans un champ de la page (entre le
+    /// `Start` et le `End` envoyés à Servo).
+    pub ime_composing: Cell<bool>,
+
+    /// Fenêtre entièrement recouverte (`WindowEvent::Occluded`).
+    pub occluded: Cell<bool>,
+
+    /// Fenêtre réduite dans la barre des tâches.
+    pub minimized: Cell<bool>,
+
+    /// Instant du dernier `spin_event_loop`, pour espacer les spins tant que
+    /// la fenêtre est cachée.
+    pub last_spin: Cell<Instant>,
+
+    /// Réveil Servo reporté (fenêtre cachée), traité dans `about_to_wait`.
+    pub spin_pending: Cell<bool>,
+
+    /// Webviews dont la page anime (`notify_animating_changed`) ; seule
+    /// celle affichée règle la cadence de la boucle.
+    pub animating: RefCell<HashSet<WebViewId>>,
+
+    /// Instant de la dernière frame d'animation.
+    pub last_frame: Cell<Instant>,
+
+    /// Clics successifs dans le chrome (double-clic sur le fond).
+    pub chrome_clicks: RefCell<ClickCounter>,
+
+    /// Curseur demandé par la page (`notify_cursor_changed`).
+    pub page_cursor: Cell<Cursor>,
+
+    /// `true` si le pointeur est sur la page (ni chrome, ni menu ouvert).
+    pub pointer_over_page: Cell<bool>,
+
+    /// Dialogue JavaScript ou demande de permission en attente de réponse
+    /// (un seul à la fois : le script qui l'a ouvert est bloqué jusqu'à la
+    /// réponse).
+    pub dialog: RefCell<Option<PendingDialog>>,
+
+    /// Décisions de permission mémorisées par site (`permissions.json`).
+    pub permissions: RefCell<PermissionStore>,
+
+    /// Certificats invalides acceptés pour la session (jamais sur disque).
+    pub cert_exceptions: RefCell<CertExceptions>,
+
+    /// Navigations réécrites en https et hôtes autorisés en http pour la
+    /// session (`[privacy] https_only`).
+    pub https_upgrades: RefCell<HttpsUpgrades>,
+
+    /// Liste de sécurité (`[privacy] security_blocklist`), relue quand le
+    /// thread de mise à jour la réécrit.
+    pub security_blocklist: RefCell<HostBlocklist>,
+
+    /// Sites ouverts malgré l'avertissement de la liste de sécurité (session
+    /// seulement).
+    pub security_exceptions: RefCell<SessionExceptions>,
+
+    /// Thread de mise à jour des listes de filtres et de sécurité.
+    pub filter_updater: FilterUpdater,
+
+    /// Jeton de session des liens d'action des pages internes (voir
+    /// [`crate::token::has_token`]) : une page web ne peut pas les forger.
+    pub session_token: String,
+
+    /// Hôtes sans JavaScript (`[privacy] no_js_hosts`), modifiés par
+    /// Ctrl+Shift+J sans redémarrage.
+    pub no_js_hosts: RefCell<Vec<String>>,
+
+    /// Webviews dont la page courante est sur un hôte sans JavaScript : leurs
+    /// requêtes de script sont bloquées.
+    pub no_js_pages: RefCell<HashSet<WebViewId>>,
+
+    /// Sites visités et sites inclus comme ressource tierce pendant la
+    /// session (`[privacy] block_third_party_cookies`).
+    pub site_roles: RefCell<SiteRoles>,
+
+    /// Webviews dont la page courante est exemptée par une règle
+    /// `$document` : ni requêtes filtrées ni masquage cosmétique.
+    pub adblock_excepted_pages: RefCell<HashSet<WebViewId>>,
+
+    /// Webviews dont le document est parsé et qui n'ont pas encore produit
+    /// de frame : au-delà du délai, la page est déclarée figée.
+    pub frame_watchdog: RefCell<FrameWatchdog<WebViewId>>,
+
+    /// Plafond des messages de console journalisés (`log_console`).
+    pub console_limiter: RefCell<ConsoleLimiter>,
+
+    /// Panics rattrapées dans les callbacks du delegate, par callback.
+    pub delegate_panics: RefCell<PanicStats>,
+
+    /// Sessions média des pages ; la webview en lecture la plus récente
+    /// reçoit les touches média.
+    pub media_sessions: RefCell<MediaSessions<WebViewId>>,
+
+    /// Téléchargements en cours (indicateur du chrome, annulation).
+    pub downloads: RefCell<Downloads>,
+
+    /// Webviews affichant la réponse d'un formulaire (`POST`) : elle n'est
+    /// pas relue pour être téléchargée.
+    pub posted_pages: RefCell<HashSet<WebViewId>>,
+
+    /// Relectures en cours, par identifiant de téléchargement.
+    download_streams: RefCell<HashMap<u64, DownloadStream>>,
+
+    /// Menu contextuel ouvert par un clic droit sur la page.
+    pub context_menu: RefCell<Option<PendingContextMenu>>,
+
+    /// Presse-papiers système, unique pour la page, la barre d'URL et le
+    /// menu contextuel.
+    pub clipboard: RefCell<Clipboard>,
+}
+
+/// Menu contextuel affiché et la requête Servo qu'il remplace.
+pub struct PendingContextMenu {
+    pub ui: ContextMenu,
+    pub request: ServoContextMenu,
+}
+
+/// Réponse relue par sa page pour un téléchargement : la webview qui la lit
+/// et le canal des relevés vers le thread d'écriture.
+struct DownloadStream {
+    webview: WebViewId,
+    url: Url,
+    polls: mpsc::Sender<Option<Polled>>,
+}
+
+/// Dialogue affiché et la requête Servo à laquelle il répond.
+pub struct PendingDialog {
+    pub ui: JsDialog,
+    pub request: DialogRequest,
+}
+
+/// Requête Servo en attente derrière un dialogue du chrome.
+pub enum DialogRequest {
+    /// `alert()`, `confirm()` ou `prompt()`.
+    Script(SimpleDialog),
+    /// Permission demandée par `origin` ; la réponse est mémorisée.
+    Permission {
+        request: PermissionRequest,
+        origin: String,
+        feature: String,
+    },
+    /// Identifiants HTTP. Abandonner la requête sans répondre laisse Servo
+    /// afficher la réponse 401 du serveur.
+    Authentication(AuthenticationRequest),
+    /// Lien vers une application externe, ouvert seulement si accepté.
+    External(Url),
+}
+
+impl AppState {
+    /// Change la partie « page » du titre de la fenêtre.
+    pub fn set_page_label(&self, label: &str) {
+        label.clone_into(&mut self.page_label.borrow_mut());
+        self.refresh_title();
+    }
+
+    /// Recompose la partie « page » du titre depuis la navigation engagée
+    /// (voir [`PageDisplay::label`]).
+    pub fn refresh_page_label(&self) {
+        let Some(label) = self.page.borrow().label(self.load_state().loading) else {
+            return;
+        };
+        self.set_page_label(&label);
+    }
+
+    /// Recompose le titre de la fenêtre (page + zoom courant).
+    pub fn refresh_title(&self) {
+        let title = window_title(
+            &self.config.general.window_title,
+            &self.page_label.borrow(),
+            self.zoom.get(),
+        );
+        self.window.set_title(&title);
+    }
+
+    /// Applique le curseur de la page si le pointeur est dessus, sinon la flèche.
+    pub fn apply_cursor(&self) {
+        let cursor = if self.pointer_over_page.get() {
+            self.page_cursor.get()
+        } else {
+            Cursor::Default
+        };
+        match cursor_icon(cursor) {
+            Some(icon) => {
+                self.window.set_cursor_visible(true);
+                self.window.set_cursor(icon);
+            }
+            None => self.window.set_cursor_visible(false),
+        }
+    }
+
+    /// Affiche le dialogue JavaScript demandé par la page. Un dialogue encore
+    /// ouvert (autre webview) est annulé d'abord.
+    pub fn show_dialog(&self, request: SimpleDialog) {
+        self.cancel_dialog();
+        // Le dialogue est dessiné par le chrome : il doit être visible
+        self.exit_page_fullscreen();
+        let ui = match &request {
+            SimpleDialog::Alert { message, .. } => JsDialog::alert(message),
+            SimpleDialog::Confirm { message, .. } => JsDialog::confirm(message),
+            SimpleDialog::Prompt {
+                message, default, ..
+            } => JsDialog::prompt(message, default),
+        };
+        *self.dialog.borrow_mut() = Some(PendingDialog {
+            ui,
+            request: DialogRequest::Script(request),
+        });
+        self.redraw_chrome();
+    }
+
+    /// Traite une demande de permission de `origin` : décision mémorisée,
+    /// sinon question dans le chrome (`[privacy] permission_prompts`), sinon
+    /// refus. `origin` vaut `None` pour une origine opaque (toujours refusée).
+    pub fn handle_permission_request(&self, request: PermissionRequest, origin: Option<String>) {
+        let feature = format!("{:?}", request.feature());
+        let Some(origin) = origin else {
+            tracing::info!(%feature, "Permission denied (opaque origin)");
+            request.deny();
+            return;
+        };
+        let prompts = self.config.privacy.permission_prompts;
+        match self
+            .permissions
+            .borrow()
+            .resolve(&origin, &feature, prompts)
+        {
+            Outcome::Allow => {
+                tracing::info!(%origin, %feature, "Permission granted (remembered)");
+                request.allow();
+            }
+            Outcome::Deny => {
+                tracing::info!(%origin, %feature, "Permission denied");
+                request.deny();
+            }
+            Outcome::Ask => {
+                self.cancel_dialog();
+                self.exit_page_fullscreen();
+                *self.dialog.borrow_mut() = Some(PendingDialog {
+                    ui: JsDialog::permission(&origin, &feature),
+                    request: DialogRequest::Permission {
+                        request,
+                        origin,
+                        feature,
+                    },
+                });
+                self.redraw_chrome();
+            }
+        }
+    }
+
+    /// Lien vers une application externe (`mailto:`, `magnet:`…) : ouvert
+    /// directement si son schéma est dans `[privacy] external_scheme_allowlist`,
+    /// sinon après confirmation dans le chrome.
+    pub fn open_external(&self, url: Url) {
+        if external::is_allowlisted(&url, &self.config.privacy.external_scheme_allowlist) {
+            tracing::info!(scheme = url.scheme(), "Opening external link (allowlisted)");
+            external::open_in_background(url);
+            return;
+        }
+        self.cancel_dialog();
+        self.exit_page_fullscreen();
+        *self.dialog.borrow_mut() = Some(PendingDialog {
+            ui: JsDialog::external(&external::prompt_message(&url)),
+            request: DialogRequest::External(url),
+        });
+        self.redraw_chrome();
+    }
+
+    /// Demande un identifiant pour une page protégée par une authentification
+    /// HTTP. Servo garde les identifiants acceptés dans son cache (par
+    /// origine) : les ressources suivantes du site ne redemandent rien, et le
+    /// navigateur ne tient pas de table (hôte, realm) de son côté.
+    /// `AuthenticationRequest` ne transmet pas l'en-tête `WWW-Authenticate` :
+    /// le realm n'est pas connu ici et la fenêtre n'affiche que l'hôte.
+    pub fn show_authentication(&self, request: AuthenticationRequest) {
+        self.cancel_dialog();
+        self.exit_page_fullscreen();
+        let url = request.url();
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        *self.dialog.borrow_mut() = Some(PendingDialog {
+            ui: JsDialog::authentication(&host, request.for_proxy()),
+            request: DialogRequest::Authentication(request),
+        });
+        self.redraw_chrome();
+    }
+
+    /// Affiche l'avertissement d'erreur de certificat dans `webview`. Un
+    /// certificat déjà accepté pour cet hôte pendant la session est autorisé
+    /// sans redemander.
+    pub fn show_cert_error(&self, webview: &WebView, report: CertErrorReport) {
+        let host = report.host();
+        // Sans certificat lisible, rien ne peut être accepté : Servo garde
+        // sa propre page
+        let Some(fingerprint) = report.fingerprint() else {
+            tracing::warn!(%host, reason = %report.reason, "Certificate error without certificate");
+            return;
+        };
+        tracing::warn!(%host, reason = %report.reason, %fingerprint, "Certificate error");
+        let allowed = self
+            .cert_exceptions
+            .borrow()
+            .is_allowed(&host, &fingerprint);
+        if !allowed && self.offer_http_fallback(webview, &report.url, &report.reason) {
+            return;
+        }
+        let html = if allowed {
+            cert_error::allow_html(&report)
+        } else {
+            let pending = PendingException {
+                report,
+                host,
+                fingerprint,
+            };
+            let token = self.cert_exceptions.borrow_mut().propose(pending.clone());
+            cert_error::interstitial_html(&pending, &token)
+        };
+        if let Some(url) = pages::data_url(&html) {
+            webview.load(url);
+        }
+    }
+
+    /// Envoie une touche média à la session de la page en lecture.
+    /// Retourne `false` sans session : la touche suit alors le chemin
+    /// clavier habituel. Le relâchement est absorbé avec l'appui.
+    pub fn handle_media_key(&self, key: MediaKey, pressed: bool) -> bool {
+        let Some((id, playback)) = self.media_sessions.borrow().target() else {
+            return false;
+        };
+        let Some(webview) = self.webview_by_id(id) else {
+            self.media_sessions.borrow_mut().remove(id);
+            return false;
+        };
+        if pressed {
+            let action = media::action_for(key, playback);
+            tracing::debug!(?action, "Media key sent to media session");
+            webview.notify_media_session_action_event(media_session_action(action));
+        }
+        true
+    }
+
+    /// Remplace le contenu de `webview` par la page « This page crashed ».
+    /// Seul cet onglet est touché : le chrome et les autres webviews
+    /// continuent normalement.
+    pub fn show_crash_page(&self, webview: &WebView, reason: &str, backtrace: Option<&str>) {
+        let url = webview.url();
+        tracing::error!(
+            url = url.as_ref().map_or("", |u| u.as_str()),
+            reason,
+            backtrace = backtrace.unwrap_or(""),
+            "Page crashed"
+        );
+        self.frame_watchdog.borrow_mut().frame_ready(webview.id());
+        self.update_load_state(webview, |state| state.set_loading(false));
+        if let Some(page) = pages::data_url(&crash::crashed_html(url.as_ref(), reason)) {
+            webview.load(page);
+        }
+    }
+
+    /// Affiche la page d'erreur réseau (nom introuvable, connexion refusée…)
+    /// de la page qui n'a pas pu être chargée.
+    pub fn show_net_error(&self, webview: &WebView, report: NetErrorReport) {
+        let kind = report.kind();
+        tracing::warn!(url = %report.url, reason = %report.reason, ?kind, "Page failed to load");
+        self.update_load_state(webview, |state| state.set_loading(false));
+        // Hôte introuvable : il ne l'est pas davantage en http
+        if kind != NetErrorKind::NameNotResolved
+            && self.offer_http_fallback(webview, &report.url, &report.reason)
+        {
+            return;
+        }
+        if let Some(page) = pages::data_url(&error_page::generate(&report.url, kind)) {
+            webview.load(page);
+        }
+    }
+
+    /// La page `failed` n'a pas pu être chargée : si c'est une navigation
+    /// réécrite en https, affiche l'avertissement qui propose de continuer en
+    /// http à la place de la page d'erreur. Retourne `true` s'il est affiché.
+    fn offer_http_fallback(&self, webview: &WebView, failed: &Url, reason: &str) -> bool {
+        let Some(http_url) = self.https_upgrades.borrow_mut().fallback_for(failed) else {
+            return false;
+        };
+        self.show_https_interstitial(webview, http_url, reason);
+        true
+    }
+
+    /// Affiche l'avertissement HTTPS uniquement pour `http_url`, dont la
+    /// version https a échoué (`reason`).
+    pub fn show_https_interstitial(&self, webview: &WebView, http_url: Url, reason: &str) {
+        tracing::warn!(url = %http_url, reason, "HTTPS upgrade failed");
+        let token = self.https_upgrades.borrow_mut().propose(http_url.clone());
+        if let Some(page) =
+            pages::data_url(&https_only::interstitial_html(&http_url, reason, &token))
+        {
+            webview.load(page);
+        }
+    }
+
+    /// « Continue to the HTTP site » : l'hôte est autorisé en http pour la
+    /// session et la page s'ouvre. Un jeton inconnu (lien forgé par une page,
+    /// avertissement trop ancien) est ignoré.
+    pub fn continue_over_http(&self, webview: &WebView, token: &str) {
+        let Some(url) = self.https_upgrades.borrow_mut().accept(token) else {
+            tracing::warn!("Unknown HTTPS-only token, ignored");
+            return;
+        };
+        tracing::warn!(
+            host = url.host_str().unwrap_or_default(),
+            "Plain HTTP allowed for this session"
+        );
+        webview.load(url);
+    }
+
+    /// « Accept risk for this session » : mémorise l'exception et répond
+    /// « autoriser » à Servo, qui recharge la page. Un jeton inconnu (lien
+    /// forgé par une page, avertissement trop ancien) est ignoré.
+    pub fn accept_cert_exception(&self, webview: &WebView, token: &str) {
+        let Some(pending) = self.cert_exceptions.borrow_mut().accept(token) else {
+            tracing::warn!("Unknown certificate exception token, ignored");
+            return;
+        };
+        tracing::warn!(
+            host = %pending.host,
+            fingerprint = %pending.fingerprint,
+            "Certificate accepted for this session"
+        );
+        if let Some(url) = pages::data_url(&cert_error::allow_html(&pending.report)) {
+            webview.load(url);
+        }
+    }
+
+    /// Document chargé dans `webview` : s'il n'est pas affichable (archive,
+    /// PDF…), il est téléchargé (voir [`crate::download`]).
+    pub fn check_download(&self, webview: &WebView) {
+        let Some(url) = webview.url() else {
+            return;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return;
+        }
+        let id = webview.id();
+        let waker = self.waker.clone();
+        webview.evaluate_javascript(download::CONTENT_TYPE_SCRIPT, move |result| {
+            if let Ok(JSValue::String(content_type)) = result
+                && !download::is_renderable(&content_type)
+            {
+                waker.send(WakerEvent::DownloadResponse { webview: id, url });
+            }
+        });
+    }
+
+    /// Enregistre le document `url` de la webview `id` dans le dossier de
+    /// téléchargement : la page relit la réponse en flux, relevée par le
+    /// thread d'écriture (voir [`crate::download`]).
+    pub fn start_download(&self, id: WebViewId, url: Url) {
+        let Some(webview) = self.webview_by_id(id) else {
+            return;
+        };
+        if webview.url().as_ref() != Some(&url) {
+            return;
+        }
+        if self.posted_pages.borrow().contains(&id) {
+            tracing::warn!(%url, "Form response cannot be downloaded");
+            return;
+        }
+        let Some(dir) = self.config.general.download_dir() else {
+            tracing::warn!(%url, "No download directory, response not saved");
+            return;
+        };
+        let (download, cancel) = self
+            .downloads
+            .borrow_mut()
+            .start(download::file_name(None, &url));
+        tracing::info!(%url, "Download started");
+        self.redraw_chrome();
+        webview.evaluate_javascript(download::start_script(), |_| {});
+        let (polls, received) = mpsc::channel();
+        self.download_streams.borrow_mut().insert(
+            download,
+            DownloadStream {
+                webview: id,
+                url: url.clone(),
+                polls,
+            },
+        );
+        spawn_download(self.waker.clone(), download, url, dir, cancel, received);
+    }
+
+    /// Relève les morceaux reçus par la page du téléchargement `id` pour
+    /// son thread d'écriture. Page fermée ou partie ailleurs : le canal est
+    /// fermé, le téléchargement échoue.
+    pub fn poll_download(&self, id: u64) {
+        let streams = self.download_streams.borrow();
+        let Some(stream) = streams.get(&id) else {
+            return;
+        };
+        let webview = self
+            .webview_by_id(stream.webview)
+            .filter(|webview| webview.url().as_ref() == Some(&stream.url));
+        let polls = stream.polls.clone();
+        drop(streams);
+        let Some(webview) = webview else {
+            self.download_streams.borrow_mut().remove(&id);
+            return;
+        };
+        webview.evaluate_javascript(download::poll_script(), move |result| {
+            let polled = match result {
+                Ok(JSValue::String(json)) => Polled::parse(&json),
+                _ => None,
+            };
+            let _ = polls.send(polled);
+        });
+    }
+
+    /// Téléchargement `id` terminé, annulé ou échoué : la relecture est
+    /// interrompue si la page est toujours là.
+    fn end_download_stream(&self, id: u64) {
+        let Some(stream) = self.download_streams.borrow_mut().remove(&id) else {
+            return;
+        };
+        if let Some(webview) = self
+            .webview_by_id(stream.webview)
+            .filter(|webview| webview.url().as_ref() == Some(&stream.url))
+        {
+            webview.evaluate_javascript(download::ABORT_SCRIPT, |_| {});
+        }
+    }
+
+    /// Répond au dialogue en cours et le ferme : `accepted` pour Entrée,
+    /// `false` pour Échap (un `alert` n'a que « OK »).
+    pub fn answer_dialog(&self, accepted: bool) {
+        self.close_dialog(accepted, true);
+    }
+
+    /// Annule le dialogue en cours, remplacé par un nouveau : une permission
+    /// est refusée sans que ce refus soit mémorisé.
+    fn cancel_dialog(&self) {
+        self.close_dialog(false, false);
+    }
+
+    fn close_dialog(&self, accepted: bool, remember: bool) {
+        let Some(PendingDialog { ui, request }) = self.dialog.borrow_mut().take() else {
+            return;
+        };
+        let request = match request {
+            DialogRequest::Script(request) => request,
+            DialogRequest::Authentication(request) => {
+                match ui.credentials() {
+                    Some((username, password)) if accepted => {
+                        request.authenticate(username, password);
+                    }
+                    // Annulé : la requête abandonnée rend la page 401
+                    _ => drop(request),
+                }
+                self.redraw_chrome();
+                return;
+            }
+            DialogRequest::External(url) => {
+                if accepted {
+                    tracing::info!(scheme = url.scheme(), "Opening external link");
+                    external::open_in_background(url);
+                }
+                self.redraw_chrome();
+                return;
+            }
+            DialogRequest::Permission {
+                request,
+                origin,
+                feature,
+            } => {
+                let decision = if accepted {
+                    request.allow();
+                    Decision::Allow
+                } else {
+                    request.deny();
+                    Decision::Deny
+                };
+                if remember {
+                    let mut permissions = self.permissions.borrow_mut();
+                    permissions.set(&origin, &feature, decision);
+                    // Navigation privée : décision gardée pour la session seulement
+                    if !self.private
+                        && let Err(e) = permissions.save()
+                    {
+                        tracing::warn!(error = %e, "Failed to save permissions");
+                    }
+                }
+                self.redraw_chrome();
+                return;
+            }
+        };
+        let sent = match request {
+            SimpleDialog::Alert {
+                response_sender, ..
+            } => response_sender.send(AlertResponse::Ok),
+            SimpleDialog::Confirm {
+                response_sender, ..
+            } => response_sender.send(if accepted {
+                ConfirmResponse::Ok
+            } else {
+                ConfirmResponse::Cancel
+            }),
+            SimpleDialog::Prompt {
+                response_sender, ..
+            } => response_sender.send(match ui.input() {
+                Some(input) if accepted => PromptResponse::Ok(input.text().to_string()),
+                _ => PromptResponse::Cancel,
+            }),
+        };
+        if let Err(e) = sent {
+            // La page a pu être fermée entre-temps
+            tracing::debug!(error = ?e, "JavaScript dialog response not delivered");
+        }
+        self.redraw_chrome();
+    }
+
+    /// État de chargement de la webview active.
+    pub fn load_state(&self) -> LoadState {
+        self.active_webview()
+            .and_then(|webview| self.load_states.borrow().get(&webview.id()).copied())
+            .unwrap_or_default()
+    }
+
+    /// Listes de filtres enregistrées depuis les paramètres : transmises au
+    /// thread de mise à jour avec la liste de sécurité, qui télécharge tout
+    /// de suite celles qui manquent.
+    pub fn set_filter_subscriptions(&self, filter_lists: &[FilterListConfig]) {
+        let mut lists = filter_lists.to_vec();
+        lists.extend(safe_browsing::subscription(
+            &self.config.privacy.security_blocklist,
+        ));
+        if !self.filter_updater.set_subscriptions(lists) {
+            tracing::warn!("Filter list updater not running, new lists are not downloaded");
+        }
+    }
+
+    /// Reconstruit le moteur adblock depuis les listes mises à jour et
+    /// `user-filters.txt` (aussi à la demande, `reload_filters`). Les listes
+    /// activées sont celles de la session (`live_config`, tenue à jour par
+    /// les paramètres et la relecture de `config.toml`) : variables
+    /// d'environnement et listes régionales de la navigation privée
+    /// comprises, comme au démarrage.
+    ///
+    /// La compilation se fait sur un thread de fond ; le moteur arrive par
+    /// `WakerEvent::AdblockRebuilt` et l'ancien filtre jusque-là.
+    pub fn reload_adblock_engine(&self) {
+        let generation = self.adblock_engine.begin_rebuild();
+        let lists = self.live_config.borrow().privacy.filter_lists.clone();
+        let waker = self.waker.clone();
+        let spawned = std::thread::Builder::new()
+            .name("adblock-rebuild".to_string())
+            .spawn(move || {
+                let compiled = CompiledEngine::new(&lists).map(Box::new);
+                waker.send(WakerEvent::AdblockRebuilt {
+                    generation,
+                    compiled,
+                });
+            });
+        if let Err(e) = spawned {
+            tracing::warn!(error = %e, "Cannot start adblock rebuild thread, keeping the current engine");
+        }
+    }
+
+    /// Met en service le moteur de la reconstruction `generation`. Si les
+    /// listes n'ont pas donné de moteur, l'ancien reste en place ; une
+    /// reconstruction dépassée par une plus récente est ignorée.
+    pub fn install_rebuilt_engine(&self, generation: u64, compiled: Option<CompiledEngine>) {
+        let Some(compiled) = compiled else {
+            tracing::warn!("Updated filter lists unusable, keeping the current engine");
+            return;
+        };
+        let mut engine = AdblockEngine::from_compiled(compiled);
+        if !self.adblock_engine.is_loaded() {
+            engine = engine.with_block_log(self.open_block_log());
+        }
+        if self.adblock_engine.swap(generation, engine) {
+            tracing::info!("Adblock engine rebuilt");
+        } else {
+            tracing::debug!("Outdated adblock rebuild dropped");
+        }
+    }
+
+    /// Relit la liste de sécurité après son téléchargement.
+    pub fn reload_security_blocklist(&self) {
+        *self.security_blocklist.borrow_mut() =
+            load_security_blocklist(&self.config.privacy.security_blocklist);
+    }
+
+    /// Entrée de la liste de sécurité qui couvre l'hôte de `url`. `None` s'il
+    /// n'est pas listé ou si l'avertissement a été ignoré pendant la session.
+    pub fn security_blocklist_entry(&self, url: &Url) -> Option<String> {
+        let host = url.host_str()?;
+        if self.security_exceptions.borrow().is_allowed(host) {
+            return None;
+        }
+        self.security_blocklist
+            .borrow()
+            .matched(host)
+            .map(str::to_string)
+    }
+
+    /// Navigation principale vers `url` : son site est noté comme visité,
+    /// puis les cookies des sites qui n'ont été que des ressources tierces
+    /// sont effacés (`[privacy] block_third_party_cookies`, voir
+    /// [`crate::third_party`]).
+    pub fn purge_third_party_cookies(&self, url: &Url) {
+        let mut roles = self.site_roles.borrow_mut();
+        roles.visit(url);
+        let site_data = self.servo.site_data_manager();
+        let with_cookies = site_data.site_data(StorageType::Cookies);
+        let sites = roles.to_clear(with_cookies.iter().map(|site| site.name()));
+        if !sites.is_empty() {
+            tracing::debug!(?sites, "Clearing third-party cookies");
+            site_data.clear_site_data(&sites, StorageType::Cookies);
+        }
+    }
+
+    /// Navigation principale de `webview` vers `url` : ses scripts sont
+    /// bloqués jusqu'à la navigation suivante si l'hôte est dans
+    /// `[privacy] no_js_hosts`.
+    pub fn update_scripts_blocked(&self, webview: &WebView, url: &Url) {
+        let blocked = url
+            .host_str()
+            .is_some_and(|host| no_js::matched(&self.no_js_hosts.borrow(), host).is_some());
+        let mut pages = self.no_js_pages.borrow_mut();
+        let changed = if blocked {
+            pages.insert(webview.id())
+        } else {
+            pages.remove(&webview.id())
+        };
+        drop(pages);
+        if changed && self.is_active(webview) {
+            self.redraw_chrome();
+        }
+    }
+
+    /// Navigation principale de `webview` vers `url` : la page est exemptée
+    /// de filtrage jusqu'à la navigation suivante si une règle `$document`
+    /// la couvre. Sans moteur chargé, rien n'est exempté.
+    pub fn update_adblock_exception(&self, webview: &WebView, url: &Url) {
+        let excepted = self
+            .adblock_engine
+            .get()
+            .is_some_and(|engine| engine.is_document_excepted(url.as_str()));
+        let mut pages = self.adblock_excepted_pages.borrow_mut();
+        let changed = if excepted {
+            pages.insert(webview.id())
+        } else {
+            pages.remove(&webview.id())
+        };
+        drop(pages);
+        if changed {
+            tracing::debug!(%url, excepted, "Document-level adblock exception");
+            if self.is_active(webview) {
+                self.redraw_chrome();
+            }
+        }
+    }
+
+    /// `true` si la page de `webview` est exemptée de filtrage (`$document`).
+    pub fn is_adblock_excepted(&self, webview: WebViewId) -> bool {
+        self.adblock_excepted_pages.borrow().contains(&webview)
+    }
+
+    /// `true` si les scripts de la page active sont bloqués.
+    pub fn scripts_blocked(&self) -> bool {
+        self.active_webview()
+            .is_some_and(|webview| self.no_js_pages.borrow().contains(&webview.id()))
+    }
+
+    /// Affiche l'avertissement de la liste de sécurité à la place de `url`,
+    /// dont la navigation a été annulée.
+    pub fn show_security_warning(&self, webview: &WebView, url: Url, entry: &str) {
+        tracing::warn!(%url, entry, "Navigation to a blocklisted host");
+        self.update_load_state(webview, |state| state.set_loading(false));
+        let token = self.security_exceptions.borrow_mut().propose(url.clone());
+        if let Some(page) = pages::data_url(&safe_browsing::interstitial_html(&url, entry, &token))
+        {
+            webview.load(page);
+        }
+    }
+
+    /// « Visit this unsafe site anyway » : l'hôte est autorisé pour la session
+    /// et la page s'ouvre. Un jeton inconnu (lien forgé par une page,
+    /// avertissement trop ancien) est ignoré.
+    pub fn proceed_to_blocked_site(&self, webview: &WebView, token: &str) {
+        let Some(url) = self.security_exceptions.borrow_mut().accept(token) else {
+            tracing::warn!("Unknown security warning token, ignored");
+            return;
+        };
+        tracing::warn!(
+            host = url.host_str().unwrap_or_default(),
+            "Security warning ignored for this session"
+        );
+        webview.load(url);
+    }
+
+    /// Met en place le moteur compilé au démarrage. Un moteur déjà présent
+    /// (listes rechargées entre-temps) est plus récent : il est gardé.
+    pub fn install_adblock_engine(&self, compiled: CompiledEngine) {
+        if self
+            .adblock_engine
+            .install_compiled(compiled, || self.open_block_log())
+        {
+            tracing::info!("Adblock engine ready");
+        } else {
+            tracing::info!("Adblock engine already rebuilt, startup engine dropped");
+        }
+    }
+
+    /// Ouvre le journal des requêtes bloquées si `[privacy] block_log` est
+    /// défini. Une erreur désactive le journal sans empêcher le filtrage.
+    /// Jamais en navigation privée : les URL visitées n'y sont pas écrites.
+    fn open_block_log(&self) -> Option<BlockLog> {
+        if self.private {
+            return None;
+        }
+        let path = self.config.privacy.block_log_path()?;
+        BlockLog::open(path.clone())
+            .inspect_err(|e| {
+                tracing::warn!(path = %path.display(), error = %e, "Cannot open block log");
+            })
+            .ok()
+    }
+
+    /// En-tête de la page parsé : `[privacy] referrer_policy` appliquée au
+    /// document (voir [`crate::referrer`]).
+    pub fn apply_referrer_policy(&self, webview: &WebView) {
+        let Some(content) = referrer::meta_content(self.config.privacy.referrer_policy) else {
+            return;
+        };
+        if !webview
+            .url()
+            .is_some_and(|url| matches!(url.scheme(), "http" | "https"))
+        {
+            return;
+        }
+        webview.evaluate_javascript(&referrer::policy_script(content), |_| {});
+    }
+
+    /// En-tête du document parsé : expose GPC / DNT à la page (`[privacy]
+    /// send_gpc`, `send_dnt`, voir [`crate::privacy_signals`]).
+    pub fn apply_privacy_signals(&self, webview: &WebView) {
+        let Some(script) = PrivacySignals::from_config(&self.config.privacy).navigator_script()
+        else {
+            return;
+        };
+        if webview
+            .url()
+            .is_some_and(|url| matches!(url.scheme(), "http" | "https"))
+        {
+            webview.evaluate_javascript(&script, |_| {});
+        }
+    }
+
+    /// En-tête parsé, puis page chargée : relève les classes et ids du
+    /// document pour le filtrage cosmétique (`[privacy] cosmetic_filtering`).
+    /// La suite se fait dans [`Self::apply_cosmetic_filters`], via
+    /// `WakerEvent::CosmeticTargets` ; la seconde feuille de style remplace
+    /// la première.
+    pub fn collect_cosmetic_targets(&self, webview: &WebView) {
+        if !self.config.privacy.cosmetic_filtering
+            || !self.adblock_engine.is_loaded()
+            || self.is_adblock_excepted(webview.id())
+        {
+            return;
+        }
+        let Some(url) = webview.url() else {
+            return;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return;
+        }
+        let id = webview.id();
+        let waker = self.waker.clone();
+        webview.evaluate_javascript(crate::privacy::COLLECT_CLASS_ID_SCRIPT, move |result| {
+            let page = match result {
+                Ok(JSValue::String(json)) => crate::privacy::PageClassIds::parse(&json),
+                _ => None,
+            };
+            let Some(page) = page else {
+                tracing::debug!("Cannot read page classes, cosmetic filtering skipped");
+                return;
+            };
+            waker.send(WakerEvent::CosmeticTargets {
+                webview: id,
+                url,
+                page,
+            });
+        });
+    }
+
+    /// Injecte la feuille de style de masquage dans la webview `id`, si elle
+    /// affiche toujours `url`.
+    pub fn apply_cosmetic_filters(
+        &self,
+        id: WebViewId,
+        url: &Url,
+        page: &crate::privacy::PageClassIds,
+    ) {
+        let Some(webview) = self.webview_by_id(id) else {
+            return;
+        };
+        if webview.url().as_ref() != Some(url) || self.is_adblock_excepted(id) {
+            return;
+        }
+        let css = match self.adblock_engine.get() {
+            Some(engine) => engine.hiding_css(url.as_str(), page),
+            None => return,
+        };
+        if css.is_empty() {
+            return;
+        }
+        webview.evaluate_javascript(crate::privacy::inject_style_script(&css), |_| {});
+    }
+
+    /// Panics rattrapées dans les callbacks du delegate, par nom de callback.
+    pub fn delegate_panic_stats(&self) -> Vec<(&'static str, u32)> {
+        self.delegate_panics.borrow().snapshot()
+    }
+
+    /// Webview active : celle qui reçoit l'entrée et qui est affichée.
+    pub fn active_webview(&self) -> Option<WebView> {
+        self.webviews.borrow().active().cloned()
+    }
+
+    /// `true` si `webview` est la webview active : seules ses notifications
+    /// touchent à la barre d'URL, au titre et au curseur.
+    pub fn is_active(&self, webview: &WebView) -> bool {
+        self.webviews
+            .borrow()
+            .active()
+            .is_some_and(|active| active.id() == webview.id())
+    }
+
+    /// `true` si la page de la webview affichée anime : relu à chaque tour
+    /// de boucle, donc à jour dès un changement d'onglet.
+    pub fn active_animating(&self) -> bool {
+        self.webviews
+            .borrow()
+            .active()
+            .is_some_and(|active| self.animating.borrow().contains(&active.id()))
+    }
+
+    /// Webview d'identifiant `id`, active ou non.
+    pub fn webview_by_id(&self, id: WebViewId) -> Option<WebView> {
+        self.webviews
+            .borrow()
+            .find(|webview| webview.id() == id)
+            .cloned()
+    }
+
+    /// Rend `webview` active (changement d'onglet, focus donné par Servo).
+    pub fn activate_webview(&self, webview: &WebView) {
+        let mut webviews = self.webviews.borrow_mut();
+        let Some(index) = webviews.position(|w| w.id() == webview.id()) else {
+            return;
+        };
+        if webviews.activate(index) {
+            drop(webviews);
+            self.show_active(webview);
+        }
+    }
+
+    /// Donne le focus à la webview active `webview` et y resynchronise la
+    /// barre d'URL et le titre (ses notifications étaient ignorées tant
+    /// qu'elle était en arrière-plan).
+    pub fn show_active(&self, webview: &WebView) {
+        tracing::debug!(webview = ?webview.id(), "Active webview changed");
+        webview.focus();
+        let url = webview.url();
+        if let Some(url) = &url {
+            self.urlbar.borrow_mut().set_url(url);
+            self.site_security.set(SiteSecurity::for_url(url));
+            self.is_bookmarked
+                .set(self.bookmarks.borrow().contains(url.as_str()));
+        }
+        self.page.borrow_mut().show(url, webview.page_title());
+        self.refresh_page_label();
+        self.redraw_webview();
+        self.redraw_chrome();
+    }
+
+    /// Retire `webview` de la liste (fermée par Servo ou par la page).
+    pub fn remove_webview(&self, webview: &WebView) {
+        let mut webviews = self.webviews.borrow_mut();
+        if let Some(index) = webviews.position(|w| w.id() == webview.id()) {
+            webviews.remove(index);
+        }
+        drop(webviews);
+        self.load_states.borrow_mut().remove(&webview.id());
+        self.no_js_pages.borrow_mut().remove(&webview.id());
+        self.adblock_excepted_pages
+            .borrow_mut()
+            .remove(&webview.id());
+        self.frame_watchdog.borrow_mut().frame_ready(webview.id());
+        self.media_sessions.borrow_mut().remove(webview.id());
+        self.block_counters.reset_page(webview.id());
+        self.posted_pages.borrow_mut().remove(&webview.id());
+        self.animating.borrow_mut().remove(&webview.id());
+        self.redraw_webview();
+        self.redraw_chrome();
+    }
+
+    /// Position fenêtre → point dans la webview, à l'échelle HiDPI actuelle
+    /// de la fenêtre (voir [`to_webview_point`]).
+    fn webview_point(&self, pos: DevicePoint, chrome_h: f32) -> WebViewPos {
+        to_webview_point(pos, chrome_h, self.window.scale_factor() as f32)
+    }
+
+    /// Met à jour l'état de chargement de `webview` et redessine le chrome
+    /// s'il a changé.
+    pub fn update_load_state(
+        &self,
+        webview: &WebView,
+        update: impl FnOnce(&mut LoadState) -> bool,
+    ) {
+        let changed = update(
+            self.load_states
+                .borrow_mut()
+                .entry(webview.id())
+                .or_default(),
+        );
+        if changed {
+            self.redraw_chrome();
+        }
+    }
+
+    /// `true` si la fenêtre est réduite ou recouverte : rien n'est visible.
+    pub fn is_hidden(&self) -> bool {
+        self.occluded.get() || self.minimized.get()
+    }
+
+    /// Demande un redraw du chrome seul, sans recomposition Servo.
+    /// Fenêtre cachée : marque seulement le chrome à repeindre.
+    pub fn redraw_chrome(&self) {
+        self.chrome_dirty.set(true);
+        if !self.is_hidden() {
+            self.request_redraw();
+        }
+    }
+
+    /// Demande un redraw complet : Servo repeint la webview dans le FBO.
+    /// Fenêtre cachée : marque seulement la webview à repeindre.
+    pub fn redraw_webview(&self) {
+        self.webview_dirty.set(true);
+        if !self.is_hidden() {
+            self.request_redraw();
+        }
+    }
+
+    /// `request_redraw` Winit, une seule fois par frame : les demandes
+    /// suivantes (rafale de `notify_new_frame_ready`) sont fusionnées
+    /// jusqu'à la présentation.
+    fn request_redraw(&self) {
+        if claim_redraw(&self.frame_pending) {
+            self.window.request_redraw();
+        }
+    }
+
+    /// Frame présentée : les prochaines demandes de redraw repassent. Ce qui
+    /// a été marqué à repeindre pendant la frame en déclenche une nouvelle.
+    fn finish_frame(&self) {
+        self.frame_pending.set(false);
+        if self.webview_dirty.get() || self.chrome_dirty.get() {
+            self.request_redraw();
+        }
+    }
+
+    /// Hauteur du chrome à l'écran : 0 pendant le plein écran d'une page.
+    pub fn visible_chrome_height(&self) -> u32 {
+        if self.fullscreen.get().hides_chrome() {
+            0
+        } else {
+            self.chrome_height
+        }
+    }
+
+    /// Applique un nouvel état de plein écran : fenêtre sans bordure, chrome
+    /// masqué ou rendu, zone webview redimensionnée en conséquence.
+    pub fn set_fullscreen(&self, fullscreen: Fullscreen) {
+        let before = self.fullscreen.replace(fullscreen);
+        if before.window_fullscreen() != fullscreen.window_fullscreen() {
+            // Winit rend la taille et l'état maximisé d'avant en sortant
+            self.window.set_fullscreen(
+                fullscreen
+                    .window_fullscreen()
+                    .then_some(winit::window::Fullscreen::Borderless(None)),
+            );
+        }
+        if before.hides_chrome() != fullscreen.hides_chrome() {
+            if fullscreen.hides_chrome() {
+                // Plus rien du chrome n'est visible : il rend le clavier
+                self.urlbar.borrow_mut().unfocus();
+                self.find.borrow_mut().unfocus();
+                self.menu.borrow_mut().close();
+                self.hovered_button.set(None);
+            }
+            // Pas de `Resized` si la fenêtre était déjà en plein écran (F11)
+            let size = webview_size(self.window.inner_size(), self.visible_chrome_height());
+            self.offscreen_context.resize(size);
+            self.redraw_webview();
+        }
+    }
+
+    /// Ouvre le menu contextuel de la page sous le pointeur. Pendant le plein
+    /// écran d'une page, le chrome est masqué : la demande est ignorée.
+    pub fn open_context_menu(&self, request: ServoContextMenu, target: ContextTarget) {
+        self.close_context_menu();
+        if self.fullscreen.get().hides_chrome() {
+            request.dismiss();
+            return;
+        }
+        self.menu.borrow_mut().close();
+        let pos = self.cursor_position.get();
+        let ui = ContextMenu::new(target, (pos.x, pos.y), self.load_state());
+        *self.context_menu.borrow_mut() = Some(PendingContextMenu { ui, request });
+        self.redraw_chrome();
+    }
+
+    /// Ferme le menu contextuel et retourne son état (entrée sélectionnée,
+    /// cible), ou `None` s'il n'était pas ouvert.
+    pub fn close_context_menu(&self) -> Option<ContextMenu> {
+        let PendingContextMenu { ui, request } = self.context_menu.borrow_mut().take()?;
+        request.dismiss();
+        self.redraw_chrome();
+        Some(ui)
+    }
+
+    /// Ouvre le sélecteur de fichiers natif pour un `<input type="file">`.
+    /// La boîte tourne sur un thread dédié : la boucle d'événements (et donc
+    /// Servo) continue pendant que l'utilisateur choisit. Les chemins choisis,
+    /// ou `None` si la boîte est annulée, sont renvoyés à Servo par
+    /// `response_sender`.
+    pub fn show_file_picker(
+        &self,
+        patterns: &[FilterPattern],
+        multiple: bool,
+        response_sender: IpcSender<Option<Vec<std::path::PathBuf>>>,
+    ) {
+        // Une boîte native par-dessus une fenêtre plein écran sans bordure
+        // peut rester cachée derrière elle
+        self.exit_page_fullscreen();
+        let extensions = file_picker::accept_extensions(patterns.iter().map(|p| p.0.as_str()));
+        let directory = self.config.general.download_dir();
+        let spawned = std::thread::Builder::new()
+            .name("file-picker".to_string())
+            .spawn(move || {
+                // Version asynchrone : sur macOS, rfd renvoie elle-même la
+                // boîte sur le thread principal
+                let mut dialog = rfd::AsyncFileDialog::new().set_title(if multiple {
+                    "Choose Files"
+                } else {
+                    "Choose File"
+                });
+                if !extensions.is_empty() {
+                    dialog = dialog.add_filter("Accepted Files", &extensions);
+                }
+                if let Some(dir) = directory {
+                    dialog = dialog.set_directory(dir);
+                }
+                let paths = if multiple {
+                    futures_executor::block_on(dialog.pick_files())
+                        .map(|files| files.iter().map(|file| file.path().to_path_buf()).collect())
+                } else {
+                    futures_executor::block_on(dialog.pick_file())
+                        .map(|file| vec![file.path().to_path_buf()])
+                };
+                if let Err(e) = response_sender.send(paths) {
+                    // La page a été fermée entre-temps
+                    tracing::debug!(error = %e, "File selection dropped");
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!(error = %e, "Cannot start file picker thread");
+        }
+    }
+
+    /// Sort du plein écran de la page (Échap, dialogue à afficher…).
+    pub fn exit_page_fullscreen(&self) {
+        let mut fullscreen = self.fullscreen.get();
+        if !fullscreen.is_page() {
+            return;
+        }
+        if let Some(webview) = self.active_webview() {
+            webview.exit_fullscreen();
+        }
+        fullscreen.set_page(false);
+        self.set_fullscreen(fullscreen);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// App : enum deux phases
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Application à deux phases de vie.
+#[allow(clippy::large_enum_variant)]
+pub enum App {
+    /// Phase pré-initialisation : on attend que Winit appelle `resumed()`.
+    Initial {
+        waker: Waker,
+        initial_url: Url,
+        config: Config,
+        /// Navigation privée (`--private`).
+        private: bool,
+    },
+
+    /// Phase opérationnelle : le navigateur est actif.
+    Running(Rc<AppState>),
+}
+
+impl App {
+    /// Crée l'application dans son état initial avec l'URL à charger.
+    /// `private` : navigation privée (voir [`AppState::private`]).
+    pub fn new(
+        event_loop: &EventLoop<WakerEvent>,
+        initial_url: Url,
+        config: Config,
+        private: bool,
+    ) -> Self {
+        Self::Initial {
+            waker: Waker::new(event_loop),
+            initial_url,
+            config,
+            private,
+        }
+    }
+}
+
+/// Compile le moteur adblock sur un thread de fond ; il est mis en place à
+/// la réception de `WakerEvent::AdblockReady`. D'ici là, les requêtes
+/// passent sans filtrage.
+fn spawn_adblock_build(config: &Config, waker: &Waker) {
+    let lists = config.privacy.filter_lists.clone();
+    let waker = waker.clone();
+    let spawned = std::thread::Builder::new()
+        .name("adblock-engine".to_string())
+        .spawn(move || {
+            if let Some(compiled) = CompiledEngine::new(&lists) {
+                waker.send(WakerEvent::AdblockReady(Box::new(compiled)));
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!(error = %e, "Cannot start adblock engine thread, ad-blocking disabled");
+    }
+}
+
+/// Liste de sécurité du réglage `setting` : fichier local ou copie
+/// téléchargée. Vide si elle est désactivée, pas encore téléchargée ou
+/// illisible.
+fn load_security_blocklist(setting: &str) -> HostBlocklist {
+    let path = match safe_browsing::subscription(setting) {
+        Some(list) => crate::privacy::list_path(&list),
+        None => safe_browsing::local_path(setting, &config_file::data_dir()),
+    };
+    let Some(path) = path else {
+        return HostBlocklist::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(content) => {
+            let list = HostBlocklist::parse(&content);
+            tracing::info!(path = %path.display(), hosts = list.len(), "Security blocklist loaded");
+            list
+        }
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Cannot read security blocklist");
+            HostBlocklist::default()
+        }
+    }
+}
+
+/// Ouvre une page interne tapée dans la barre d'URL (`suri://…`).
+fn open_internal_page(state: &AppState, page: InternalPage) {
+    match page {
+        InternalPage::Adblock => open_adblock_status(state, None),
+    }
+}
+
+/// Charge la page d'état de l'adblock (`suri://adblock`), avec `notice` en
+/// tête après un de ses boutons.
+fn open_adblock_status(state: &AppState, notice: Option<&str>) {
+    let engine = state.adblock_engine.get();
+    let status = AdblockStatus {
+        stats: engine.as_deref().map(|engine| {
+            state
+                .block_counters
+                .stats(state.active_webview().map(|w| w.id()), engine.match_cost())
+        }),
+        source: engine.as_deref().map(AdblockEngine::source),
+        lists: privacy::list_statuses(&state.live_config.borrow().privacy.filter_lists),
+        allowlisted: std::fs::read_to_string(
+            config_file::data_dir().join(privacy::USER_FILTERS_FILE),
+        )
+        .map(|content| privacy::allowlisted_sites(&content))
+        .unwrap_or_default(),
+        notice: notice.map(str::to_string),
+    };
+    load_html(state, &privacy::status_html(&status, &state.session_token));
+}
+
+/// Bouton de la page d'état de l'adblock, puis page d'état rafraîchie.
+fn run_status_action(state: &AppState, action: StatusAction) {
+    let notice = match action {
+        StatusAction::UpdateLists if state.filter_updater.check_now() => {
+            "Checking the enabled lists for updates. The engine is rebuilt if one \
+             changed: open suri://adblock again in a moment."
+        }
+        StatusAction::UpdateLists => "The list updater is not running: no filters folder.",
+        StatusAction::RebuildEngine => {
+            state.reload_adblock_engine();
+            "Rebuilding the engine in the background. Blocking goes on with the \
+             current one until then: open suri://adblock again in a moment."
+        }
+    };
+    open_adblock_status(state, Some(notice));
+}
+
+/// Nouvelle section `[chrome]` : palette, marges, taille du texte (l'atlas
+/// est reconstruit à la prochaine frame). La hauteur reste celle du
+/// démarrage.
+fn apply_chrome_config(state: &AppState, mut chrome: ChromeConfig) {
+    if state.private {
+        chrome.tint_private();
+    }
+    let palette = chrome.palette(prefers_dark(state.window.theme())).clone();
+    if let Some(renderer) = state.chrome.borrow_mut().as_mut() {
+        renderer.set_colors(&palette);
+        renderer.set_spacing(&chrome);
+    }
+    *state.chrome_config.borrow_mut() = chrome;
+    state.redraw_chrome();
+}
+
+/// Applique un `config.toml` relu (`[general] watch_config`) : réglages
+/// modifiables à chaud remplacés, les autres signalés comme demandant un
+/// redémarrage (voir [`config_reload`]).
+fn apply_reloaded_config(state: &AppState, config: Config) {
+    let changes = config_reload::diff(&state.live_config.borrow(), &config);
+    if changes.is_empty() {
+        return;
+    }
+    for key in &changes.restart {
+        tracing::warn!(key, "Setting changed in config.toml, restart to apply it");
+    }
+    if changes.touches("chrome") {
+        apply_chrome_config(state, config.chrome.clone());
+    }
+    if changes.touches("search.engine_url") {
+        state
+            .urlbar
+            .borrow_mut()
+            .set_search_url(config.search.engine_url.clone());
+    }
+    if changes.touches("privacy.no_js_hosts") {
+        *state.no_js_hosts.borrow_mut() = config.privacy.no_js_hosts.clone();
+    }
+    // Défilement : lu dans `live_config` à chaque événement de molette
+    if !changes.live.is_empty() {
+        tracing::info!(keys = ?changes.live, "Settings reloaded from config.toml");
+    }
+    *state.live_config.borrow_mut() = config;
+    if changes.touches("privacy.filter_lists") {
+        // Listes activées ou désactivées : moteur reconstruit avec les
+        // listes déjà présentes, lues dans `live_config`
+        state.reload_adblock_engine();
+    }
+}
+
+/// Charge la page de paramètres dans la webview active (Ctrl+, ou engrenage).
+/// Le formulaire montre la config du disque, celle que l'enregistrement
+/// modifie : les variables `SURIBROWS_*` n'y entrent pas.
+fn open_settings(state: &AppState) {
+    load_html(
+        state,
+        &settings::generate_settings_html(&Config::load(), &state.session_token),
+    );
+}
+
+/// Ajoute ou retire l'hôte de la page courante de `[privacy] no_js_hosts`
+/// (Ctrl+Shift+J), enregistre `config.toml` (sauf en navigation privée)
+/// puis recharge la page.
+fn toggle_javascript(state: &AppState) {
+    let Some(host) = state
+        .page
+        .borrow()
+        .url()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .and_then(|url| url.host_str().map(str::to_string))
+    else {
+        return;
+    };
+    let mut hosts = state.no_js_hosts.borrow_mut();
+    let blocked = no_js::toggle(&mut hosts, &host);
+    drop(hosts);
+    // Navigation privée : le choix ne vaut que pour la session
+    if !state.private {
+        // Liste du disque, sans les variables d'environnement ; les autres
+        // réglages restent tels quels. Fichier invalide : pas remplacé
+        match Config::load_saved() {
+            Ok(mut config) => {
+                no_js::set(&mut config.privacy.no_js_hosts, &host, blocked);
+                if let Err(e) = config.save() {
+                    tracing::warn!(error = %e, "Failed to save no_js_hosts");
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Config not loaded, no_js_hosts not saved"),
+        }
+    }
+    tracing::info!(host, blocked, "Scripts toggled for host");
+    if let Some(webview) = state.active_webview() {
+        webview.reload();
+    }
+}
+
+/// Efface cache HTTP, cookies et stockage des sites (Ctrl+Shift+Suppr ou
+/// bouton des paramètres), recharge les onglets ouverts puis affiche le
+/// résumé dans un nouvel onglet.
+fn clear_browsing_data(state: &Rc<AppState>) {
+    let site_data = state.servo.site_data_manager();
+    let mut sites: Vec<String> = site_data
+        .site_data(StorageType::all())
+        .iter()
+        .map(|site| site.name().to_string())
+        .collect();
+    sites.sort();
+    sites.dedup();
+    let names: Vec<&str> = sites.iter().map(String::as_str).collect();
+    site_data.clear_site_data(&names, StorageType::all());
+    site_data.clear_cookies();
+    drop(site_data);
+    state.servo.network_manager().clear_cache();
+
+    // Clonées d'abord : un rechargement peut rappeler le delegate
+    let webviews: Vec<WebView> = state.webviews.borrow().iter().cloned().collect();
+    for webview in &webviews {
+        webview.reload();
+    }
+    tracing::info!(
+        sites = sites.len(),
+        tabs = webviews.len(),
+        "Browsing data cleared"
+    );
+    let report = browsing_data::ClearReport {
+        sites,
+        tabs_reloaded: webviews.len(),
+    };
+    if let Some(url) = pages::data_url(&browsing_data::report_html(&report)) {
+        open_webview(state, url);
+    }
+}
+
+/// Charge la page d'accueil (`[general] homepage`, à défaut `default_url`)
+/// dans la webview active.
+fn go_home(state: &AppState) {
+    match Url::parse(state.config.general.homepage_url()) {
+        Ok(url) => {
+            if let Some(webview) = state.active_webview() {
+                webview.load(url);
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "Invalid homepage URL in config"),
+    }
+}
+
+/// Clic (ou tap) dans la bande du chrome : déclenche le bouton visé, sinon
+/// focus la barre d'URL. Premier clic : tout sélectionner ; clic dans une
+/// barre déjà focusée : placer le curseur. Double-clic sur le fond
+/// (`clicks` = rang du clic) : agrandir/restaurer, comme une barre de titre.
+fn click_chrome(state: &AppState, pos: DevicePoint, clicks: u32) {
+    let Some(hit) = state
+        .chrome
+        .borrow()
+        .as_ref()
+        .map(|c| c.hit_test(pos.x, pos.y))
+    else {
+        return;
+    };
+    match hit {
+        ChromeHit::Button(ChromeButton::Reload) => reload_or_stop(state),
+        ChromeHit::Button(ChromeButton::Home) => go_home(state),
+        // Réservé au futur panneau d'infos du site
+        ChromeHit::Button(ChromeButton::SiteInfo) => {}
+        ChromeHit::Button(ChromeButton::Bookmark) => toggle_bookmark(state),
+        ChromeHit::Button(ChromeButton::Settings) => open_settings(state),
+        ChromeHit::Button(ChromeButton::Menu) => {
+            state.menu.borrow_mut().toggle();
+            state.redraw_chrome();
+        }
+        ChromeHit::Background if clicks == 2 => {
+            state.window.set_maximized(!state.window.is_maximized());
+        }
+        ChromeHit::UrlBar | ChromeHit::Badge | ChromeHit::Background => {
+            state.find.borrow_mut().unfocus();
+            let mut urlbar = state.urlbar.borrow_mut();
+            if urlbar.is_focused() {
+                if let Some(chrome) = state.chrome.borrow().as_ref() {
+                    urlbar.set_cursor_char_offset(chrome.url_char_offset_at(pos.x));
+                }
+            } else {
+                urlbar.focus();
+            }
+            drop(urlbar);
+            state.redraw_chrome();
+        }
+    }
+}
+
+/// Touche d'édition dans un champ de saisie sur une ligne (curseur,
+/// effacement, Ctrl+A, caractères).
+fn edit_text_input(input: &mut TextInput, key: &Key, mods: winit::keyboard::ModifiersState) {
+    match key {
+        Key::Named(NamedKey::Backspace) => input.backspace(),
+        Key::Named(NamedKey::Delete) => input.delete(),
+        Key::Named(NamedKey::ArrowLeft) => input.move_cursor_left(),
+        Key::Named(NamedKey::ArrowRight) => input.move_cursor_right(),
+        Key::Named(NamedKey::Home) => input.home(),
+        Key::Named(NamedKey::End) => input.end(),
+        Key::Named(NamedKey::Space) => input.insert_char(' '),
+        Key::Character(c) => {
+            if mods.control_key() && c.eq_ignore_ascii_case("a") {
+                input.select_all();
+            } else if !mods.control_key() && !mods.alt_key() {
+                for ch in c.chars() {
+                    input.insert_char(ch);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Délai maximal entre deux clics d'un double-clic.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
+/// Déplacement maximal (pixels) entre deux clics d'un double-clic.
+const DOUBLE_CLICK_DISTANCE: f32 = 4.0;
+
+/// Compte les clics rapprochés dans le temps et l'espace (double-clic).
+#[derive(Debug, Default)]
+pub struct ClickCounter {
+    last: Option<(Instant, DevicePoint)>,
+    count: u32,
+}
+
+impl ClickCounter {
+    /// Enregistre un appui et retourne son rang dans la série (1 = clic simple).
+    pub fn press(&mut self, now: Instant, pos: DevicePoint) -> u32 {
+        let chained = self.last.is_some_and(|(at, last_pos)| {
+            now.duration_since(at) <= DOUBLE_CLICK_TIME
+                && (pos - last_pos).length() <= DOUBLE_CLICK_DISTANCE
+        });
+        self.count = if chained { self.count + 1 } else { 1 };
+        self.last = Some((now, pos));
+        self.count
+    }
+}
+
+/// Composition IME : édite la barre d'URL ou de recherche si elle a le focus,
+/// sinon passe au champ focusé de la page via les événements de composition.
+fn handle_ime(state: &Rc<AppState>, ime: Ime) {
+    // Dialogue `prompt` : seul le texte validé compte, comme la recherche
+    if let Some(dialog) = state.dialog.borrow_mut().as_mut() {
+        if let (Ime::Commit(text), Some(input)) = (&ime, dialog.ui.input_mut()) {
+            input.insert_str(text);
+            state.redraw_chrome();
+        }
+        return;
+    }
+    if state.urlbar.borrow().is_focused() {
+        let mut urlbar = state.urlbar.borrow_mut();
+        match ime {
+            Ime::Preedit(text, cursor) => urlbar.set_preedit(text, cursor.map(|(start, _)| start)),
+            Ime::Commit(text) => urlbar.insert_str(&text),
+            Ime::Disabled => urlbar.set_preedit(String::new(), None),
+            Ime::Enabled => {}
+        }
+        let cursor = urlbar.composed().cursor;
+        drop(urlbar);
+        // Fenêtre de candidats sous le curseur (position de la dernière frame)
+        let cursor_x = state
+            .chrome
+            .borrow()
+            .as_ref()
+            .and_then(|chrome| chrome.url_x_of(cursor));
+        if let Some(x) = cursor_x {
+            set_ime_area(state, x, 0.0, state.visible_chrome_height() as f32);
+        }
+        state.redraw_chrome();
+        return;
+    }
+
+    if state.find.borrow().is_focused() {
+        // Pas de pré-édition dans la barre de recherche : seul le texte validé compte
+        if let Ime::Commit(text) = ime {
+            state.find.borrow_mut().input_mut().insert_str(&text);
+            run_find(state, 0);
+            state.redraw_chrome();
+        }
+        return;
+    }
+
+    let Some(webview) = state.active_webview() else {
+        return;
+    };
+    let composition = |phase, data| {
+        InputEvent::Ime(ImeEvent::Composition(CompositionEvent {
+            state: phase,
+            data,
+        }))
+    };
+    match ime {
+        Ime::Enabled => {
+            // Servo ne connaît pas la position du curseur texte : on vise le pointeur
+            let pos = state.cursor_position.get();
+            set_ime_area(state, pos.x, pos.y, 20.0);
+        }
+        Ime::Preedit(text, _) => {
+            if text.is_empty() && !state.ime_composing.get() {
+                return;
+            }
+            if !state.ime_composing.replace(true) {
+                webview.notify_input_event(composition(CompositionState::Start, String::new()));
+            }
+            webview.notify_input_event(composition(CompositionState::Update, text));
+        }
+        Ime::Commit(text) => {
+            // Un commit direct (sans pré-édition) reste une composition complète
+            if !state.ime_composing.replace(false) {
+                webview.notify_input_event(composition(CompositionState::Start, String::new()));
+            }
+            webview.notify_input_event(composition(CompositionState::End, text));
+        }
+        Ime::Disabled => {
+            if state.ime_composing.replace(false) {
+                webview.notify_input_event(InputEvent::Ime(ImeEvent::Dismissed));
+            }
+        }
+    }
+}
+
+/// Place la fenêtre de candidats de l'IME sous la zone `(x, y, height)`
+/// (coordonnées physiques de la fenêtre).
+fn set_ime_area(state: &AppState, x: f32, y: f32, height: f32) {
+    state.window.set_ime_cursor_area(
+        winit::dpi::PhysicalPosition::new(x as f64, y as f64),
+        PhysicalSize::new(1.0, height as f64),
+    );
+}
+
+/// Bouton recharger/arrêter : annule le chargement en cours, sinon recharge.
+fn reload_or_stop(state: &AppState) {
+    if let Some(webview) = state.active_webview() {
+        if state.load_state().loading {
+            webview.stop();
+        } else {
+            webview.reload();
+        }
+    }
+}
+
+/// Titre de la fenêtre : `App — page`, suivi du zoom s'il n'est pas à 100 %.
+///
+/// `{title}` dans `app_title` place le titre de la page ; sans page, le
+/// marqueur disparaît avec les séparateurs qui l'entourent.
+fn window_title(app_title: &str, page: &str, zoom_level: f32) -> String {
+    let mut title = if app_title.contains("{title}") {
+        let separators: &[char] = &[' ', '—', '-', '|', ':', '·'];
+        app_title
+            .replace("{title}", page)
+            .trim_matches(separators)
+            .replace("  ", " ")
+    } else if page.is_empty() {
+        app_title.to_string()
+    } else {
+        format!("{app_title} — {page}")
+    };
+    let percent = zoom::zoom_percent(zoom_level);
+    if percent != 100 {
+        title.push_str(&format!(" ({percent} %)"));
+    }
+    title
+}
+
+/// Applique un niveau de zoom à la webview active et le mémorise pour l'hôte
+/// de la page courante.
+fn set_zoom(state: &AppState, level: f32) {
+    apply_zoom(state, level);
+    remember_zoom(state);
+}
+
+/// Applique un niveau de zoom à la webview active, sans l'écrire sur disque
+/// (appelé à chaque étape d'un pincement).
+fn apply_zoom(state: &AppState, level: f32) {
+    if state.zoom.replace(level) == level {
+        return;
+    }
+    if let Some(webview) = state.active_webview() {
+        webview.set_zoom(level);
+    }
+    state.refresh_title();
+}
+
+/// Zoom centré sur le curseur (pincement, Ctrl+molette) : comme
+/// [`apply_zoom`], puis la page est défilée pour garder sous le curseur le
+/// point qu'il survolait. `cursor` : position dans la webview, en pixels CSS
+/// à 100 %.
+fn apply_zoom_at(state: &AppState, level: f32, cursor: (f32, f32)) {
+    let old = state.zoom.get();
+    apply_zoom(state, level);
+    if old != level
+        && let Some(webview) = state.active_webview()
+    {
+        webview.evaluate_javascript(zoom::anchor_script(cursor, old, level), |_| {});
+    }
+}
+
+/// Mémorise le zoom courant pour l'hôte de la page courante.
+fn remember_zoom(state: &AppState) {
+    let level = state.zoom.get();
+    let host = state
+        .page
+        .borrow()
+        .url()
+        .and_then(|url| url.host_str().map(str::to_string));
+    if let Some(host) = host {
+        let mut levels = state.zoom_levels.borrow_mut();
+        levels.set(&host, level);
+        if state.private {
+            return;
+        }
+        if let Err(e) = levels.save() {
+            tracing::warn!(error = %e, "Failed to save zoom levels");
+        }
+    }
+}
+
+/// Lance la recherche dans la page (`step` : 0 = nouvelle saisie, ±1 =
+/// occurrence suivante/précédente). Le compteur est mis à jour quand le
+/// script répond.
+fn run_find(state: &Rc<AppState>, step: i32) {
+    let query = state.find.borrow().input().text().to_string();
+    let Some(webview) = state.active_webview() else {
+        return;
+    };
+    if query.is_empty() {
+        webview.evaluate_javascript(find::CLEAR_SCRIPT, |_| {});
+        state.find.borrow_mut().set_matches(None);
+        state.redraw_chrome();
+        return;
+    }
+    let weak_state = Rc::downgrade(state);
+    webview.evaluate_javascript(find::find_script(&query, step), move |result| {
+        let Some(state) = weak_state.upgrade() else {
+            return;
+        };
+        let matches = match result {
+            Ok(JSValue::String(counter)) => FindMatches::parse(&counter),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::debug!(error = ?e, "Find-in-page script failed");
+                None
+            }
+        };
+        state.find.borrow_mut().set_matches(matches);
+        state.redraw_chrome();
+    });
+}
+
+/// Ferme la barre de recherche et retire le surlignage de la page.
+fn close_find(state: &AppState) {
+    state.find.borrow_mut().close();
+    if let Some(webview) = state.active_webview() {
+        webview.evaluate_javascript(find::CLEAR_SCRIPT, |_| {});
+    }
+    state.redraw_chrome();
+}
+
+/// Charge une page HTML générée dans la webview active (URL `data:`).
+fn load_html(state: &AppState, html: &str) {
+    if let Some(url) = pages::data_url(html)
+        && let Some(webview) = state.active_webview()
+    {
+        webview.load(url);
+    }
+}
+
+/// Crée une webview chargeant `url` ; elle devient la webview active.
+fn open_webview(state: &Rc<AppState>, url: Url) {
+    let scale_factor = state.window.scale_factor() as f32;
+    let webview = WebViewBuilder::new(
+        &state.servo,
+        state.offscreen_context.clone() as Rc<dyn RenderingContext>,
+    )
+    .url(url)
+    .hidpi_scale_factor(Scale::new(scale_factor))
+    .delegate(state.clone())
+    .build();
+    state.webviews.borrow_mut().push(webview, true);
+}
+
+/// Exécute l'entrée choisie dans le menu contextuel.
+fn run_context_action(state: &Rc<AppState>, action: ContextAction, target: ContextTarget) {
+    match action {
+        ContextAction::Back => {
+            if let Some(webview) = state.active_webview() {
+                webview.go_back(1);
+            }
+        }
+        ContextAction::Forward => {
+            if let Some(webview) = state.active_webview() {
+                webview.go_forward(1);
+            }
+        }
+        ContextAction::Reload => {
+            if let Some(webview) = state.active_webview() {
+                webview.reload();
+            }
+        }
+        ContextAction::CopyLink => {
+            if let Some(link) = target.link {
+                copy_to_clipboard(state, link.as_str());
+            }
+        }
+        ContextAction::OpenLinkInNewTab => {
+            if let Some(link) = target.link {
+                open_webview(state, link);
+            }
+        }
+        ContextAction::SaveImageAs => {
+            if let Some(image) = target.image {
+                save_image(state, image);
+            }
+        }
+        ContextAction::ViewSource => view_source(state),
+    }
+}
+
+/// Copie `text` dans le presse-papiers système.
+fn copy_to_clipboard(state: &AppState, text: &str) {
+    if let Err(e) = state.clipboard.borrow_mut().set_text(text) {
+        tracing::warn!(error = %e, "Cannot write to the clipboard");
+    }
+}
+
+/// Exécute l'entrée choisie dans le menu « ≡ ».
+fn run_menu_item(
+    state: &Rc<AppState>,
+    item: MenuItem,
+    event_loop: &winit::event_loop::ActiveEventLoop,
+) {
+    match item {
+        MenuItem::NewTab => {
+            if let Ok(url) = Url::parse(&state.config.general.default_url) {
+                open_webview(state, url);
+            }
+        }
+        MenuItem::History => load_html(state, &pages::history_html(&state.visited.borrow())),
+        MenuItem::Bookmarks => {
+            load_html(
+                state,
+                &pages::bookmarks_html(state.bookmarks.borrow().bookmarks()),
+            );
+        }
+        MenuItem::Settings => open_settings(state),
+        MenuItem::About => load_html(state, &pages::about_html()),
+        MenuItem::Quit => begin_shutdown(state, event_loop),
+    }
+    state.redraw_chrome();
+}
+
+/// Fait tourner la boucle Servo ; quitte quand Servo a fini de s'éteindre.
+fn spin_servo(state: &AppState, event_loop: &winit::event_loop::ActiveEventLoop) {
+    state.last_spin.set(Instant::now());
+    state.spin_pending.set(false);
+    if !state.servo.spin_event_loop() {
+        event_loop.exit();
+    }
+}
+
+/// Espacement minimal des spins Servo tant que la fenêtre est cachée.
+const HIDDEN_SPIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Prochain spin autorisé : tout de suite si la fenêtre est visible (ou
+/// pendant l'arrêt, qui doit aller au bout), sinon `HIDDEN_SPIN_INTERVAL`
+/// après le précédent.
+fn next_spin_at(hidden: bool, shutting_down: bool, last_spin: Instant) -> Option<Instant> {
+    (hidden && !shutting_down).then(|| last_spin + HIDDEN_SPIN_INTERVAL)
+}
+
+/// Intervalle entre deux frames pendant une animation (~60 Hz).
+const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+
+/// Attente de la boucle Winit : jusqu'à la plus proche échéance (spin
+/// reporté, prochaine frame d'animation, limite d'arrêt), sinon au repos
+/// complet.
+fn control_flow(deadlines: &[Option<Instant>]) -> ControlFlow {
+    match deadlines.iter().flatten().min() {
+        Some(&at) => ControlFlow::WaitUntil(at),
+        None => ControlFlow::Wait,
+    }
+}
+
+/// Réveil Servo : spin immédiat, ou reporté si la fenêtre est cachée.
+fn wake_servo(state: &AppState, event_loop: &winit::event_loop::ActiveEventLoop) {
+    match next_spin_at(
+        state.is_hidden(),
+        state.shutting_down.get(),
+        state.last_spin.get(),
+    ) {
+        Some(at) if at > Instant::now() => state.spin_pending.set(true),
+        _ => spin_servo(state, event_loop),
+    }
+}
+
+/// Applique un changement de visibilité (occultation, réduction). Le webview
+/// caché est ralenti par Servo ; au retour on rattrape le spin reporté et on
+/// repeint tout.
+fn set_visibility(
+    state: &AppState,
+    event_loop: &winit::event_loop::ActiveEventLoop,
+    update: impl FnOnce(&AppState),
+) {
+    let was_hidden = state.is_hidden();
+    update(state);
+    let hidden = state.is_hidden();
+    if hidden == was_hidden {
+        return;
+    }
+    tracing::debug!(hidden, "Window visibility changed");
+    if let Some(webview) = state.active_webview() {
+        webview.set_throttled(hidden);
+    }
+    if !hidden {
+        if state.spin_pending.get() {
+            spin_servo(state, event_loop);
+        }
+        // Un redraw demandé avant de cacher la fenêtre a pu ne jamais arriver
+        state.frame_pending.set(false);
+        state.chrome_dirty.set(true);
+        state.redraw_webview();
+    }
+}
 
-use crate::chrome::ChromeRenderer;
-use crate::config::Config;
-use crate::preferences::build_servo_preferences;
-use crate::rendering;
-use crate::servo_glue::{Waker, WakerEvent};
-use crate::settings;
-use crate::urlbar::UrlBar;
+/// Arrêt ordonné, commun à Ctrl+Q, au menu « Quitter » et au bouton de
+/// fermeture : mémorise la géométrie de la fenêtre, ferme les webviews puis
+/// demande à Servo de s'éteindre. La boucle Winit sort quand
+/// `spin_event_loop` le signale (voir [`spin_servo`]).
+fn begin_shutdown(state: &AppState, event_loop: &winit::event_loop::ActiveEventLoop) {
+    if state.shutting_down.replace(true) {
+        return;
+    }
+    tracing::info!("Shutting down");
+    state
+        .shutdown_deadline
+        .set(Some(Instant::now() + SHUTDOWN_TIMEOUT));
 
-// ─────────────────────────────────────────────────────────────────────────────
-// AppState : état partagé entre Winit et Servo
-// ─────────────────────────────────────────────────────────────────────────────
+    // zoom.json et bookmarks.json sont écrits à chaque modification : seule
+    // la géométrie de la fenêtre reste à enregistrer.
+    let window_state = capture_window_state(&state.window, WindowState::load_default());
+    if let Err(e) = window_state.save_default() {
+        tracing::warn!(error = %e, "Failed to save window state");
+    }
 
-/// État partagé de l'application, créé lors de `resumed()`.
-///
-/// Encapsulé dans `Rc` car :
-/// - `WebViewDelegate` attend un `Rc<dyn WebViewDelegate>`
-/// - Servo et l'App doivent référencer les mêmes données
-/// - Tout vit sur le thread principal (pas besoin d'`Arc`)
-pub struct AppState {
-    /// Handle de la fenêtre Winit.
-    pub window: Window,
+    state.webviews.borrow_mut().clear();
+    state.servo.start_shutting_down();
+    spin_servo(state, event_loop);
+}
 
-    /// Instance du moteur Servo.
-    pub servo: Servo,
+/// Délai laissé à Servo pour s'arrêter (constellation bloquée…).
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
-    /// Contexte de rendu OpenGL plein écran (surface fenêtre).
-    /// Utilisé pour le chrome (barre d'URL) et le blit du FBO.
-    pub window_rendering_context: Rc<WindowRenderingContext>,
+/// Ctrl+P : capture la zone webview, demande où l'enregistrer puis écrit le
+/// PDF sur un thread de fond (résultat via `WakerEvent::ExportFinished`).
+fn print_to_pdf(state: &AppState) {
+    let size = webview_size(state.window.inner_size(), state.visible_chrome_height());
+    let rect = DeviceIntRect::from_size(DeviceIntSize::new(size.width as i32, size.height as i32));
+    let Some(capture) = state.offscreen_context.read_to_image(rect) else {
+        tracing::warn!("Cannot capture the page for printing");
+        return;
+    };
+    let Some(path) = ask_save_path(state, "Print to PDF", ("PDF", "pdf")) else {
+        return;
+    };
+    spawn_export(state.waker.clone(), move || {
+        export::write_pdf(&capture, &path).map(|()| path)
+    });
+}
 
-    /// Contexte de rendu offscreen (FBO) pour le WebView.
-    /// Servo peint dedans via `webview.paint()`.
-    pub offscreen_context: Rc<OffscreenRenderingContext>,
+/// Ctrl+S : récupère le HTML sérialisé de la page puis l'enregistre.
+fn save_page(state: &Rc<AppState>) {
+    let Some(webview) = state.active_webview() else {
+        return;
+    };
+    let weak_state = Rc::downgrade(state);
+    webview.evaluate_javascript(export::SERIALIZE_SCRIPT, move |result| {
+        let Some(state) = weak_state.upgrade() else {
+            return;
+        };
+        let html = match result {
+            Ok(JSValue::String(html)) => html,
+            Ok(other) => {
+                tracing::warn!(value = ?other, "Unexpected page source, not saving");
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(error = ?e, "Cannot read the page source");
+                return;
+            }
+        };
+        let Some(path) = ask_save_path(&state, "Save Page", ("HTML", "html")) else {
+            return;
+        };
+        spawn_export(state.waker.clone(), move || {
+            export::write_html(&html, &path).map(|()| path)
+        });
+    });
+}
 
-    /// WebViews actives.
-    pub webviews: RefCell<Vec<WebView>>,
+/// Instructions affichées par F12 dans la bulle de statut. Le serveur
+/// devtools de Servo ne démarre qu'au lancement : F12 n'affiche (ou masque)
+/// que la marche à suivre.
+fn devtools_hint(port: u16) -> String {
+    if port == 0 {
+        "DevTools disabled — set [general] devtools_port and restart".to_string()
+    } else {
+        format!("DevTools: Firefox → about:debugging → connect to localhost:{port}")
+    }
+}
 
-    /// Position courante du curseur en device pixels.
-    pub cursor_position: Cell<DevicePoint>,
+/// Ctrl+U : affiche le source de la page dans un nouvel onglet.
+fn view_source(state: &Rc<AppState>) {
+    let Some(webview) = state.active_webview() else {
+        return;
+    };
+    let Some(url) = state.page.borrow().url().cloned() else {
+        return;
+    };
+    let weak_state = Rc::downgrade(state);
+    webview.evaluate_javascript(pages::FETCH_SOURCE_SCRIPT, move |result| {
+        let Some(state) = weak_state.upgrade() else {
+            return;
+        };
+        let source = match result {
+            Ok(JSValue::String(source)) => source,
+            Ok(other) => {
+                tracing::warn!(value = ?other, "Unexpected page source");
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(error = ?e, "Cannot read the page source");
+                return;
+            }
+        };
+        if let Some(data_url) = pages::data_url(&pages::view_source_html(url.as_str(), &source)) {
+            open_webview(&state, data_url);
+        }
+    });
+}
 
-    /// État des modificateurs clavier (Ctrl, Shift, Alt, Meta).
-    pub modifiers: Cell<winit::keyboard::ModifiersState>,
+/// « Save Image As… » : la page relit l'image (cache, cookies, adblock),
+/// puis l'utilisateur choisit où l'écrire ; l'écriture se fait sur un thread
+/// de fond.
+fn save_image(state: &Rc<AppState>, url: Url) {
+    let Some(webview) = state.active_webview() else {
+        return;
+    };
+    let weak_state = Rc::downgrade(state);
+    webview.evaluate_javascript(export::fetch_bytes_script(&url), move |result| {
+        let Some(state) = weak_state.upgrade() else {
+            return;
+        };
+        let bytes = match result {
+            Ok(JSValue::String(text)) => export::bytes_from_script(&text),
+            Ok(other) => {
+                tracing::warn!(%url, value = ?other, "Cannot download the image");
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(%url, error = ?e, "Cannot download the image");
+                return;
+            }
+        };
+        let file_name = export::image_file_name(&url, &bytes);
+        let extension = file_name.rsplit_once('.').map_or("", |(_, ext)| ext);
+        let Some(path) = pick_save_path(&state, "Save Image As", &file_name, ("Image", extension))
+        else {
+            return;
+        };
+        spawn_export(state.waker.clone(), move || {
+            export::write_bytes(&bytes, &path).map(|()| path)
+        });
+    });
+}
 
-    /// Moteur adblock.
-    pub adblock_engine: Option<crate::privacy::AdblockEngine>,
+/// Boîte « Enregistrer sous » modale, ouverte dans le dossier de
+/// téléchargement avec un nom tiré du titre de la page. `filter` donne le
+/// libellé et l'extension du type de fichier.
+fn ask_save_path(
+    state: &AppState,
+    title: &str,
+    (label, extension): (&str, &str),
+) -> Option<std::path::PathBuf> {
+    let host = state
+        .page
+        .borrow()
+        .url()
+        .and_then(|url| url.host_str().map(str::to_string));
+    let file_name =
+        export::suggested_file_name(state.page.borrow().title(), host.as_deref(), extension);
+    pick_save_path(state, title, &file_name, (label, extension))
+}
 
-    /// URL courante de la page.
-    pub current_url: RefCell<Option<Url>>,
+/// Boîte « Enregistrer sous » modale proposant `file_name` dans le dossier
+/// de téléchargement.
+fn pick_save_path(
+    state: &AppState,
+    title: &str,
+    file_name: &str,
+    (label, extension): (&str, &str),
+) -> Option<std::path::PathBuf> {
+    // Dialogue sur le thread principal (exigé par macOS)
+    let mut dialog = rfd::FileDialog::new()
+        .set_title(title)
+        .set_file_name(file_name)
+        .add_filter(label, &[extension]);
+    if let Some(dir) = state.config.general.download_dir() {
+        dialog = dialog.set_directory(dir);
+    }
+    dialog.save_file()
+}
 
-    /// État de la barre d'URL.
-    pub urlbar: RefCell<UrlBar>,
+/// Écrit un export sur un thread de fond ; le résultat revient par
+/// `WakerEvent::ExportFinished`.
+fn spawn_export(
+    waker: Waker,
+    write: impl FnOnce() -> std::io::Result<std::path::PathBuf> + Send + 'static,
+) {
+    let spawned = std::thread::Builder::new()
+        .name("export".to_string())
+        .spawn(move || {
+            waker.send(WakerEvent::ExportFinished(
+                write().map_err(|e| e.to_string()),
+            ));
+        });
+    if let Err(e) = spawned {
+        tracing::warn!(error = %e, "Cannot start export thread");
+    }
+}
+
+/// Action de session média Servo correspondant à `action`.
+fn media_session_action(action: MediaAction) -> MediaSessionActionType {
+    match action {
+        MediaAction::Play => MediaSessionActionType::Play,
+        MediaAction::Pause => MediaSessionActionType::Pause,
+        MediaAction::NextTrack => MediaSessionActionType::NextTrack,
+        MediaAction::PreviousTrack => MediaSessionActionType::PreviousTrack,
+        MediaAction::Stop => MediaSessionActionType::Stop,
+    }
+}
 
-    /// Renderer GL pour le chrome (barre d'URL).
-    pub chrome: RefCell<ChromeRenderer>,
+/// Intervalle entre deux relevés d'un téléchargement quand le précédent
+/// n'a rien apporté.
+const DOWNLOAD_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
-    /// Configuration de l'application.
-    pub config: Config,
+/// Écrit la réponse `url` dans `dir` sur un thread de fond, au fil des
+/// relevés demandés par `WakerEvent::DownloadPoll` et reçus par `polls` ;
+/// avancement et résultat reviennent par `WakerEvent::Download*`.
+fn spawn_download(
+    waker: Waker,
+    id: u64,
+    url: Url,
+    dir: PathBuf,
+    cancel: Arc<AtomicBool>,
+    polls: mpsc::Receiver<Option<Polled>>,
+) {
+    let spawned = std::thread::Builder::new()
+        .name("download".to_string())
+        .spawn(move || {
+            let mut idle = false;
+            let next = || {
+                if idle {
+                    std::thread::sleep(DOWNLOAD_POLL_INTERVAL);
+                }
+                waker.send(WakerEvent::DownloadPoll { id });
+                let polled = polls.recv().ok().flatten()?;
+                idle = polled.data.is_empty();
+                Some(polled)
+            };
+            let result = download::receive(
+                &dir,
+                &url,
+                &cancel,
+                next,
+                |name| waker.send(WakerEvent::DownloadStarted { id, name }),
+                |progress| waker.send(WakerEvent::DownloadProgress { id, progress }),
+            );
+            waker.send(WakerEvent::DownloadFinished { id, result });
+        });
+    if let Err(e) = spawned {
+        tracing::warn!(error = %e, "Cannot start download thread");
+    }
 }
 
-// ─────────────────────────────────────────────────────────────────────────────
-// App : enum deux phases
-// ─────────────────────────────────────────────────────────────────────────────
+/// Exécute l'action d'un raccourci clavier global.
+fn run_action(
+    state: &Rc<AppState>,
+    action: Action,
+    event_loop: &winit::event_loop::ActiveEventLoop,
+) {
+    match action {
+        Action::FocusUrlBar => {
+            state.find.borrow_mut().unfocus();
+            state.urlbar.borrow_mut().focus();
+            state.redraw_chrome();
+        }
+        Action::Find => {
+            state.urlbar.borrow_mut().unfocus();
+            state.find.borrow_mut().open();
+            state.redraw_chrome();
+        }
+        Action::ToggleBookmark => toggle_bookmark(state),
+        Action::OpenSettings => open_settings(state),
+        Action::ZoomIn => set_zoom(state, zoom::zoom_in(state.zoom.get())),
+        Action::ZoomOut => set_zoom(state, zoom::zoom_out(state.zoom.get())),
+        Action::ZoomReset => set_zoom(state, DEFAULT_ZOOM),
+        Action::Reload => {
+            if let Some(webview) = state.active_webview() {
+                webview.reload();
+            }
+        }
+        Action::GoHome => go_home(state),
+        Action::GoBack => {
+            if let Some(webview) = state.active_webview() {
+                webview.go_back(1);
+            }
+        }
+        Action::GoForward => {
+            if let Some(webview) = state.active_webview() {
+                webview.go_forward(1);
+            }
+        }
+        Action::PrintPdf => print_to_pdf(state),
+        Action::SavePage => save_page(state),
+        Action::ViewSource => view_source(state),
+        Action::DevtoolsInfo => {
+            let hint = devtools_hint(state.config.general.devtools_port);
+            let mut status = state.status_text.borrow_mut();
+            *status = if status.as_deref() == Some(hint.as_str()) {
+                None
+            } else {
+                Some(hint)
+            };
+            drop(status);
+            state.redraw_chrome();
+        }
+        Action::ToggleFullscreen => {
+            let mut fullscreen = state.fullscreen.get();
+            if fullscreen.toggle_user()
+                && let Some(webview) = state.active_webview()
+            {
+                webview.exit_fullscreen();
+            }
+            state.set_fullscreen(fullscreen);
+        }
+        Action::ReloadFilters => state.reload_adblock_engine(),
+        Action::ClearBrowsingData => clear_browsing_data(state),
+        Action::ToggleJavaScript => toggle_javascript(state),
+        Action::Quit => begin_shutdown(state, event_loop),
+    }
+}
 
-/// Application à deux phases de vie.
-#[allow(clippy::large_enum_variant)]
-pub enum App {
-    /// Phase pré-initialisation : on attend que Winit appelle `resumed()`.
-    Initial {
-        waker: Waker,
-        initial_url: Url,
-        config: Config,
-    },
+/// Ajoute ou retire la page courante des favoris (Ctrl+D ou étoile),
+/// puis réécrit `bookmarks.json`.
+fn toggle_bookmark(state: &AppState) {
+    let Some(url) = state.page.borrow().url().cloned() else {
+        return;
+    };
+    let title = state
+        .page
+        .borrow()
+        .title()
+        .map_or_else(|| url.to_string(), str::to_string);
 
-    /// Phase opérationnelle : le navigateur est actif.
-    Running(Rc<AppState>),
+    let mut bookmarks = state.bookmarks.borrow_mut();
+    let bookmarked = bookmarks.toggle(url.as_str(), &title);
+    if let Err(e) = bookmarks.save() {
+        tracing::warn!(error = %e, "Failed to save bookmarks");
+    }
+    state.is_bookmarked.set(bookmarked);
+    state.redraw_chrome();
 }
 
-impl App {
-    /// Crée l'application dans son état initial avec l'URL à charger.
-    pub fn new(event_loop: &EventLoop<WakerEvent>, initial_url: Url, config: Config) -> Self {
-        Self::Initial {
-            waker: Waker::new(event_loop),
-            initial_url,
-            config,
+/// Traduit le thème OS rapporté par Winit (`None` = inconnu).
+fn prefers_dark(theme: Option<Theme>) -> Option<bool> {
+    theme.map(|t| t == Theme::Dark)
+}
+
+/// Hauteur réservée au chrome : celle de la config, ou rien s'il n'a pas pu
+/// être initialisé (le webview prend alors toute la fenêtre).
+fn effective_chrome_height(has_chrome: bool, configured: u32) -> u32 {
+    if has_chrome { configured } else { 0 }
+}
+
+/// `true` si le redraw doit repeindre la webview (`webview.paint()`).
+///
+/// Un redraw demandé par l'OS (exposition, resize) n'a levé aucun drapeau :
+/// on repeint alors tout par prudence. Seul le cas « chrome uniquement »
+/// réutilise le contenu du FBO.
+fn needs_webview_paint(webview_dirty: bool, chrome_dirty: bool) -> bool {
+    webview_dirty || !chrome_dirty
+}
+
+/// Marque un redraw comme demandé. Retourne `true` seulement pour la
+/// première demande depuis la dernière présentation.
+fn claim_redraw(frame_pending: &Cell<bool>) -> bool {
+    !frame_pending.replace(true)
+}
+
+/// Zone où un contact tactile a commencé : il y reste rattaché jusqu'à sa
+/// fin, même s'il glisse ensuite dans l'autre zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchTarget {
+    Chrome,
+    WebView,
+}
+
+/// Met à jour le suivi des contacts et retourne la zone du contact `id`
+/// (`None` pour un contact inconnu, par ex. commencé hors de la fenêtre).
+fn track_touch(
+    touches: &mut HashMap<u64, TouchTarget>,
+    id: u64,
+    phase: TouchPhase,
+    in_chrome: bool,
+) -> Option<TouchTarget> {
+    match phase {
+        TouchPhase::Started => {
+            let target = if in_chrome {
+                TouchTarget::Chrome
+            } else {
+                TouchTarget::WebView
+            };
+            touches.insert(id, target);
+            Some(target)
         }
+        TouchPhase::Moved => touches.get(&id).copied(),
+        TouchPhase::Ended | TouchPhase::Cancelled => touches.remove(&id),
+    }
+}
+
+/// Type d'événement tactile Servo correspondant à une phase Winit.
+fn servo_touch_type(phase: TouchPhase) -> TouchEventType {
+    match phase {
+        TouchPhase::Started => TouchEventType::Down,
+        TouchPhase::Moved => TouchEventType::Move,
+        TouchPhase::Ended => TouchEventType::Up,
+        TouchPhase::Cancelled => TouchEventType::Cancel,
+    }
+}
+
+/// Sens de navigation associé aux boutons latéraux de la souris
+/// (-1 = retour, 1 = avant), `None` pour les autres boutons.
+fn history_step(button: WinitMouseButton) -> Option<i32> {
+    match button {
+        WinitMouseButton::Back => Some(-1),
+        WinitMouseButton::Forward => Some(1),
+        _ => None,
+    }
+}
+
+/// Réglages appliqués à un événement de molette.
+#[derive(Debug, Clone, Copy)]
+struct ScrollSettings {
+    /// Pixels par cran de molette (`[general] scroll_line_height`).
+    line_height: f32,
+    /// Défilement « naturel » (`[general] invert_scroll`).
+    invert: bool,
+    /// Shift enfoncé : défilement horizontal.
+    shift: bool,
+}
+
+/// Convertit un delta de molette Winit en `WheelDelta` Servo.
+///
+/// Les deux axes sont transmis tels quels (scroll diagonal des pavés
+/// tactiles), avec le même facteur pour les crans de molette. Avec Shift, une
+/// molette purement verticale défile horizontalement ; si le système a déjà
+/// basculé l'axe (macOS), le delta n'est pas retourné une seconde fois.
+fn wheel_delta(delta: MouseScrollDelta, settings: ScrollSettings) -> WheelDelta {
+    let (mut x, mut y, mode) = match delta {
+        MouseScrollDelta::LineDelta(dx, dy) => (
+            (dx * settings.line_height) as f64,
+            (dy * settings.line_height) as f64,
+            WheelMode::DeltaLine,
+        ),
+        MouseScrollDelta::PixelDelta(delta) => (delta.x, delta.y, WheelMode::DeltaPixel),
+    };
+    if settings.shift && x == 0.0 {
+        (x, y) = (y, 0.0);
+    }
+    if settings.invert {
+        (x, y) = (-x, -y);
+    }
+    WheelDelta { x, y, z: 0.0, mode }
+}
+
+/// URL `file://` d'un fichier déposé sur la fenêtre. Les dossiers sont
+/// refusés : Servo ne sait pas les afficher.
+fn dropped_file_url(path: &std::path::Path) -> Result<Url, &'static str> {
+    if path.is_dir() {
+        return Err("directories cannot be opened");
+    }
+    // Encode espaces et caractères non ASCII ; exige un chemin absolu
+    Url::from_file_path(path).map_err(|()| "not an absolute path")
+}
+
+/// Géométrie courante de la fenêtre. Maximisée, sa taille n'est pas celle à
+/// restaurer : on garde alors la taille et la position précédentes.
+fn capture_window_state(window: &Window, previous: Option<WindowState>) -> WindowState {
+    let maximized = window.is_maximized();
+    if maximized && let Some(previous) = previous {
+        return WindowState {
+            maximized,
+            ..previous
+        };
+    }
+    let size = window.inner_size();
+    // Wayland ne donne pas la position : (0, 0), ignorée à la restauration
+    let position = window.outer_position().unwrap_or_default();
+    WindowState {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized,
+    }
+}
+
+/// Taille minimale de la fenêtre en pixels physiques : `[window] min_width`
+/// / `min_height`, et au moins une ligne de webview sous le chrome.
+fn min_window_size(config: &WindowConfig, chrome_height: u32, scale: f64) -> PhysicalSize<u32> {
+    let logical = winit::dpi::LogicalSize::new(config.min_width, config.min_height);
+    let physical: PhysicalSize<u32> = logical.to_physical(scale);
+    PhysicalSize::new(
+        physical.width.max(1),
+        physical.height.max(chrome_height + 1),
+    )
+}
+
+/// Taille de fenêtre bornée à `min` sur chaque axe.
+fn clamp_window_size(size: PhysicalSize<u32>, min: PhysicalSize<u32>) -> PhysicalSize<u32> {
+    PhysicalSize::new(size.width.max(min.width), size.height.max(min.height))
+}
+
+/// Point de la webview, dans les deux espaces dont le navigateur a besoin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WebViewPos {
+    /// Pixels device relatifs au coin de la webview : ce qu'attendent les
+    /// événements d'entrée Servo, qui appliquent eux-mêmes le facteur HiDPI
+    /// passé à `hidpi_scale_factor`.
+    device: DevicePoint,
+    /// Pixels CSS à 100 % (device ÷ facteur HiDPI, zoom de page non
+    /// compris) : coordonnées des scripts injectés dans la page.
+    css: (f32, f32),
+}
+
+/// Position fenêtre (pixels physiques Winit) → point dans la webview, à
+/// l'échelle HiDPI `scale` de la fenêtre. Le chrome est dessiné en pixels
+/// physiques : sa hauteur est retirée avant la division.
+fn to_webview_point(pos: DevicePoint, chrome_h: f32, scale: f32) -> WebViewPos {
+    let device = DevicePoint::new(pos.x, pos.y - chrome_h);
+    WebViewPos {
+        device,
+        css: (device.x / scale, device.y / scale),
     }
 }
 
@@ -156,6 +2769,22 @@ fn webview_size(window_size: PhysicalSize<u32>, chrome_height: u32) -> PhysicalS
     )
 }
 
+/// Attributs de la fenêtre tirés de la config stockée dans `App::Initial` :
+/// titre, taille minimale et taille de `[window]`. `resumed()` remplace la
+/// taille par la géométrie sauvegardée du dernier lancement s'il y en a une.
+fn initial_window_attributes(config: &Config) -> winit::window::WindowAttributes {
+    Window::default_attributes()
+        .with_title(window_title(&config.general.window_title, "", 1.0))
+        .with_min_inner_size(winit::dpi::LogicalSize::new(
+            config.window.min_width as f64,
+            config.window.min_height as f64,
+        ))
+        .with_inner_size(winit::dpi::LogicalSize::new(
+            config.window.width as f64,
+            config.window.height as f64,
+        ))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // ApplicationHandler : dispatch des événements Winit
 // ─────────────────────────────────────────────────────────────────────────────
@@ -163,30 +2792,54 @@ fn webview_size(window_size: PhysicalSize<u32>, chrome_height: u32) -> PhysicalS
 impl ApplicationHandler<WakerEvent> for App {
     /// Appelé une fois par Winit quand l'application est prête à créer des fenêtres.
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        let (waker, initial_url, config) = match self {
+        let (waker, initial_url, mut config, private) = match self {
             Self::Initial {
                 waker,
                 initial_url,
                 config,
-            } => (waker.clone(), initial_url.clone(), config.clone()),
+                private,
+            } => (waker.clone(), initial_url.clone(), config.clone(), *private),
             Self::Running(_) => return,
         };
+        // Chrome teinté de violet : une fenêtre privée se reconnaît d'un coup d'œil
+        if private {
+            config.chrome.tint_private();
+        }
+
+        // Moteur adblock compilé pendant le démarrage de Servo
+        spawn_adblock_build(&config, &waker);
 
         // ── 1. Créer la fenêtre Winit ──────────────────────────────────
         let display_handle = event_loop
             .display_handle()
             .expect("Impossible d'obtenir le DisplayHandle");
 
-        let window_attributes = Window::default_attributes()
-            .with_title(&config.general.window_title)
-            .with_inner_size(winit::dpi::LogicalSize::new(
-                config.window.width as f64,
-                config.window.height as f64,
-            ));
+        let mut window_attributes = initial_window_attributes(&config);
+        // Géométrie du dernier lancement, sinon la taille de `[window]`
+        if let Some(saved) = WindowState::load_default() {
+            let monitors: Vec<MonitorRect> = event_loop
+                .available_monitors()
+                .map(|monitor| MonitorRect {
+                    x: monitor.position().x,
+                    y: monitor.position().y,
+                    width: monitor.size().width,
+                    height: monitor.size().height,
+                })
+                .collect();
+            window_attributes = window_attributes
+                .with_inner_size(PhysicalSize::new(saved.width, saved.height))
+                .with_maximized(saved.maximized);
+            if let Some((x, y)) = saved.position_on(&monitors) {
+                window_attributes =
+                    window_attributes.with_position(winit::dpi::PhysicalPosition::new(x, y));
+            }
+        }
 
         let window = event_loop
             .create_window(window_attributes)
             .expect("Impossible de créer la fenêtre Winit");
+        // Saisie CJK : les compositions arrivent en `WindowEvent::Ime`
+        window.set_ime_allowed(true);
 
         let window_handle = window
             .window_handle()
@@ -197,61 +2850,326 @@ impl ApplicationHandler<WakerEvent> for App {
         let window_rendering_context =
             rendering::create_rendering_context(display_handle, window_handle, window.inner_size());
 
+        // ── 3. Initialiser le chrome renderer ───────────────────────────
+        // Un échec GL n'est pas fatal : on log et on continue sans chrome.
+        let gl = window_rendering_context.glow_gl_api();
+        let chrome_renderer = match unsafe { ChromeRenderer::new(gl, &config.chrome) } {
+            Ok(mut renderer) => {
+                renderer.set_colors(config.chrome.palette(prefers_dark(window.theme())));
+                Some(renderer)
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Cannot initialize chrome renderer, running without chrome");
+                None
+            }
+        };
+        let chrome_height =
+            effective_chrome_height(chrome_renderer.is_some(), config.chrome.height);
+
         // Contexte offscreen (FBO) — Servo peint dedans.
         let inner_size = window.inner_size();
-        let wv_size = webview_size(inner_size, config.chrome.height);
+        let wv_size = webview_size(inner_size, chrome_height);
         let offscreen_context = Rc::new(window_rendering_context.offscreen_context(wv_size));
 
-        // ── 3. Initialiser le chrome renderer ───────────────────────────
-        let gl = window_rendering_context.glow_gl_api();
-        let chrome_renderer = unsafe { ChromeRenderer::new(gl, &config.chrome) };
-
         // ── 4. Construire l'instance Servo ──────────────────────────────
+        let mut servo_preferences = build_servo_preferences(&config.servo, &config.privacy);
+        apply_devtools(&mut servo_preferences, config.general.devtools_port);
+        if private {
+            apply_private_mode(&mut servo_preferences);
+        }
         let servo = ServoBuilder::default()
-            .preferences(build_servo_preferences(&config.servo, &config.privacy))
-            .event_loop_waker(Box::new(waker))
+            .preferences(servo_preferences)
+            .event_loop_waker(Box::new(waker.clone()))
             .build();
 
         // ── 5. Encapsuler dans AppState ─────────────────────────────────
-        let adblock_engine = crate::privacy::AdblockEngine::new();
+        let security_blocklist = load_security_blocklist(&config.privacy.security_blocklist);
+        // Mise à jour des listes de filtres et de sécurité
+        let security = safe_browsing::subscription(&config.privacy.security_blocklist);
+        let mut lists = config.privacy.filter_lists.clone();
+        lists.extend(security.clone());
+        let update_waker = waker.clone();
+        let filter_updater = privacy::spawn_filter_updater(
+            lists,
+            config.privacy.filter_update_hours,
+            move |updated| {
+                let (security_updated, filters_updated): (Vec<_>, Vec<_>) = updated
+                    .iter()
+                    .partition(|list| security.as_ref().is_some_and(|s| s.url == list.url));
+                (security_updated.is_empty()
+                    || update_waker.send(WakerEvent::SecurityBlocklistUpdated))
+                    && (filters_updated.is_empty()
+                        || update_waker.send(WakerEvent::FilterListsUpdated))
+            },
+        );
         let app_state = Rc::new(AppState {
             window,
             servo,
             window_rendering_context,
-            offscreen_context: offscreen_context.clone(),
-            webviews: RefCell::new(Vec::new()),
+            offscreen_context,
+            webviews: RefCell::new(WebViewList::default()),
             cursor_position: Cell::new(DevicePoint::zero()),
+            touches: RefCell::new(HashMap::new()),
             modifiers: Cell::new(winit::keyboard::ModifiersState::default()),
-            adblock_engine,
-            current_url: RefCell::new(None),
+            adblock_engine: EngineSlot::default(),
+            page: RefCell::new(PageDisplay::default()),
+            bookmarks: RefCell::new(BookmarkStore::load_default()),
+            is_bookmarked: Cell::new(false),
+            load_states: RefCell::new(HashMap::new()),
+            site_security: Cell::new(SiteSecurity::default()),
+            block_counters: BlockCounters::default(),
+            favicons: RefCell::new(FaviconStore::default()),
+            menu: RefCell::new(Menu::default()),
+            find: RefCell::new(FindBar::default()),
+            visited: RefCell::new(Vec::new()),
+            hovered_button: Cell::new(None),
+            status_text: RefCell::new(None),
             urlbar: RefCell::new(UrlBar::new(config.search.engine_url.clone())),
             chrome: RefCell::new(chrome_renderer),
+            chrome_height,
+            zoom: Cell::new(DEFAULT_ZOOM),
+            zoom_levels: RefCell::new(ZoomStore::load_default()),
+            wheel_zoom: RefCell::new(WheelZoom::default()),
+            page_label: RefCell::new(String::new()),
+            webview_dirty: Cell::new(true),
+            chrome_dirty: Cell::new(true),
+            frame_pending: Cell::new(false),
+            shutting_down: Cell::new(false),
+            shutdown_deadline: Cell::new(None),
+            ime_composing: Cell::new(false),
+            occluded: Cell::new(false),
+            minimized: Cell::new(false),
+            last_spin: Cell::new(Instant::now()),
+            spin_pending: Cell::new(false),
+            animating: RefCell::new(HashSet::new()),
+            last_frame: Cell::new(Instant::now()),
+            chrome_clicks: RefCell::new(ClickCounter::default()),
+            page_cursor: Cell::new(Cursor::Default),
+            pointer_over_page: Cell::new(false),
+            fullscreen: Cell::new(Fullscreen::default()),
+            dialog: RefCell::new(None),
+            permissions: RefCell::new(PermissionStore::load_default()),
+            cert_exceptions: RefCell::new(CertExceptions::default()),
+            https_upgrades: RefCell::new(HttpsUpgrades::default()),
+            security_blocklist: RefCell::new(security_blocklist),
+            security_exceptions: RefCell::new(SessionExceptions::default()),
+            filter_updater,
+            session_token: crate::token::new_token(),
+            no_js_hosts: RefCell::new(config.privacy.no_js_hosts.clone()),
+            no_js_pages: RefCell::new(HashSet::new()),
+            site_roles: RefCell::new(SiteRoles::default()),
+            adblock_excepted_pages: RefCell::new(HashSet::new()),
+            frame_watchdog: RefCell::new(FrameWatchdog::default()),
+            console_limiter: RefCell::new(ConsoleLimiter::default()),
+            delegate_panics: RefCell::new(PanicStats::default()),
+            media_sessions: RefCell::new(MediaSessions::default()),
+            downloads: RefCell::new(Downloads::default()),
+            posted_pages: RefCell::new(HashSet::new()),
+            download_streams: RefCell::new(HashMap::new()),
+            context_menu: RefCell::new(None),
+            clipboard: RefCell::new(Clipboard::system()),
+            dropped_files: RefCell::new(Vec::new()),
+            chrome_config: RefCell::new(config.chrome.clone()),
+            live_config: RefCell::new(config.clone()),
+            keybindings: Keybindings::new(&config.keybindings),
+            waker: waker.clone(),
             config,
+            private,
         });
 
-        // ── 6. Créer la WebView initiale ────────────────────────────────
-        let url = initial_url;
-        let scale_factor = app_state.window.scale_factor() as f32;
-
-        let webview = WebViewBuilder::new(
-            &app_state.servo,
-            offscreen_context as Rc<dyn RenderingContext>,
-        )
-        .url(url)
-        .hidpi_scale_factor(Scale::new(scale_factor))
-        .delegate(app_state.clone())
-        .build();
+        // ── 5b. Hot-reload : couleurs du chrome, ou tout le fichier ─────
+        if let Some(path) = config_file::find_config_path() {
+            let reload_waker = waker.clone();
+            let watch_config = app_state.config.general.watch_config;
+            crate::config_watcher::watch_file(
+                path,
+                crate::config_watcher::POLL_INTERVAL,
+                move |content| {
+                    // Les variables SURIBROWS_* priment toujours sur le
+                    // fichier ; leurs erreurs ont été signalées au démarrage
+                    let config = config_file::parse_config(content).map(|mut config| {
+                        let _ = config.apply_env_overrides(config_file::env_vars());
+                        config
+                    });
+                    match config {
+                        Ok(config) if watch_config => {
+                            reload_waker.send(WakerEvent::ConfigReloaded(Box::new(config)))
+                        }
+                        Ok(config) => reload_waker
+                            .send(WakerEvent::ChromeConfigReloaded(Box::new(config.chrome))),
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Invalid config.toml, keeping current settings");
+                            true
+                        }
+                    }
+                },
+            );
+        }
 
-        app_state.webviews.borrow_mut().push(webview);
+        // ── 6. Créer la WebView initiale ────────────────────────────────
+        open_webview(&app_state, initial_url);
 
         // ── 7. Transition Initial → Running ─────────────────────────────
         *self = Self::Running(app_state);
     }
 
-    /// Appelé quand un `WakerEvent` arrive depuis les threads Servo.
-    fn user_event(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop, _event: WakerEvent) {
+    /// Sortie de la boucle : libère les ressources du compositeur Servo
+    /// (toujours sur le thread principal, contexte GL encore vivant).
+    /// Après un arrêt expiré, Servo est bloqué : `deinit` attendrait lui aussi.
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
         if let Self::Running(state) = self {
-            state.servo.spin_event_loop();
+            let timed_out = state
+                .shutdown_deadline
+                .get()
+                .is_some_and(|at| at <= Instant::now());
+            if !timed_out {
+                state.servo.deinit();
+            }
+        }
+    }
+
+    /// Fin d'un lot d'événements : ouvre les fichiers déposés. Le premier
+    /// remplace la page active, les suivants s'ouvrent chacun dans un nouvel
+    /// onglet.
+    ///
+    /// Planifie aussi le prochain réveil : spin Servo reporté (fenêtre
+    /// cachée) ou frame suivante d'une page qui anime.
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let Self::Running(state) = self else {
+            return;
+        };
+        if state.spin_pending.get() {
+            wake_servo(state, event_loop);
+        }
+        let shutdown_at = state.shutdown_deadline.get();
+        if shutdown_at.is_some_and(|at| at <= Instant::now()) {
+            tracing::warn!("Servo did not shut down in time, exiting anyway");
+            event_loop.exit();
+            return;
+        }
+        let mut frame_at = (state.active_animating() && !state.is_hidden())
+            .then(|| state.last_frame.get() + FRAME_INTERVAL);
+        let now = Instant::now();
+        if frame_at.is_some_and(|at| at <= now) {
+            state.last_frame.set(now);
+            spin_servo(state, event_loop);
+            state.redraw_webview();
+            frame_at = Some(now + FRAME_INTERVAL);
+        }
+        let spin_at = state
+            .spin_pending
+            .get()
+            .then(|| state.last_spin.get() + HIDDEN_SPIN_INTERVAL);
+        if state.is_hidden() {
+            state.frame_watchdog.borrow_mut().postpone(now);
+        }
+        let frozen = state.frame_watchdog.borrow_mut().expired(now);
+        for id in frozen {
+            if let Some(webview) = state.webview_by_id(id) {
+                state.show_crash_page(&webview, crash::UNRESPONSIVE_REASON, None);
+            }
+        }
+        let watchdog_at = state.frame_watchdog.borrow().deadline();
+        event_loop.set_control_flow(control_flow(&[spin_at, frame_at, shutdown_at, watchdog_at]));
+        if state.shutting_down.get() {
+            return;
+        }
+        let dropped = std::mem::take(&mut *state.dropped_files.borrow_mut());
+        let mut dropped = dropped.into_iter();
+        if let Some(first) = dropped.next() {
+            match state.active_webview() {
+                Some(webview) => webview.load(first),
+                None => open_webview(state, first),
+            }
+        }
+        for url in dropped {
+            open_webview(state, url);
+        }
+    }
+
+    /// Appelé quand un `WakerEvent` arrive depuis les threads Servo
+    /// ou depuis un thread de l'embedder.
+    fn user_event(&mut self, event_loop: &winit::event_loop::ActiveEventLoop, event: WakerEvent) {
+        let Self::Running(state) = self else {
+            return;
+        };
+        match event {
+            WakerEvent::Servo => wake_servo(state, event_loop),
+            WakerEvent::ExportFinished(Ok(path)) => {
+                tracing::info!(path = %path.display(), "Page exported");
+            }
+            WakerEvent::ExportFinished(Err(e)) => {
+                tracing::warn!(error = %e, "Page export failed");
+            }
+            WakerEvent::DownloadResponse { webview, url } => state.start_download(webview, url),
+            WakerEvent::DownloadPoll { id } => state.poll_download(id),
+            WakerEvent::DownloadStarted { id, name } => {
+                state.downloads.borrow_mut().rename(id, name);
+                state.redraw_chrome();
+            }
+            WakerEvent::DownloadProgress { id, progress } => {
+                if state.downloads.borrow_mut().update(id, progress) {
+                    state.redraw_chrome();
+                }
+            }
+            WakerEvent::DownloadFinished { id, result } => {
+                state.downloads.borrow_mut().finish(id);
+                state.end_download_stream(id);
+                state.redraw_chrome();
+                match result {
+                    DownloadResult::Saved(path) => {
+                        tracing::info!(path = %path.display(), "Download finished");
+                    }
+                    DownloadResult::Cancelled => tracing::info!("Download cancelled"),
+                    DownloadResult::Failed(e) => tracing::warn!(error = %e, "Download failed"),
+                }
+            }
+            WakerEvent::AdblockReady(compiled) => state.install_adblock_engine(*compiled),
+            WakerEvent::FilterListsUpdated => state.reload_adblock_engine(),
+            WakerEvent::AdblockRebuilt {
+                generation,
+                compiled,
+            } => state.install_rebuilt_engine(generation, compiled.map(|compiled| *compiled)),
+            WakerEvent::AdblockStatusAction(action) => run_status_action(state, action),
+            WakerEvent::SecurityBlocklistUpdated => state.reload_security_blocklist(),
+            WakerEvent::ClearBrowsingData => clear_browsing_data(state),
+            WakerEvent::CosmeticTargets { webview, url, page } => {
+                state.apply_cosmetic_filters(webview, &url, &page)
+            }
+            WakerEvent::LastWebViewClosed => {
+                if !state.webviews.borrow().is_empty() {
+                    return;
+                }
+                if state.config.general.exit_on_last_close {
+                    begin_shutdown(state, event_loop);
+                    return;
+                }
+                match Url::parse(state.config.general.homepage_url()) {
+                    Ok(url) => open_webview(state, url),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Invalid homepage URL in config");
+                        begin_shutdown(state, event_loop);
+                    }
+                }
+            }
+            WakerEvent::FaviconDecoded {
+                webview,
+                token,
+                favicon,
+            } => {
+                if state
+                    .favicons
+                    .borrow_mut()
+                    .finish_decode(webview, token, favicon)
+                {
+                    state.redraw_chrome();
+                }
+            }
+            WakerEvent::ChromeConfigReloaded(chrome) => {
+                apply_chrome_config(state, *chrome);
+                tracing::info!("Chrome colors reloaded from config.toml");
+            }
+            WakerEvent::ConfigReloaded(config) => apply_reloaded_config(state, *config),
         }
     }
 
@@ -264,19 +3182,26 @@ impl ApplicationHandler<WakerEvent> for App {
     ) {
         // Toujours faire tourner la boucle Servo en premier.
         if let Self::Running(state) = self {
-            state.servo.spin_event_loop();
+            spin_servo(state, event_loop);
         }
 
-        let chrome_h = if let Self::Running(state) = self {
-            state.config.chrome.height as f32
-        } else {
-            40.0
+        // Hauteur configurée (`[chrome] height`), jamais la constante par défaut :
+        // sinon les clics seraient décalés quand l'utilisateur la modifie.
+        // Sans renderer ou pendant le plein écran d'une page, elle vaut 0 et
+        // tout va au webview.
+        let chrome_h = match self {
+            Self::Running(state) => state.visible_chrome_height() as f32,
+            Self::Initial { config, .. } => config.chrome.height as f32,
         };
 
         match event {
             // ── Fermeture de la fenêtre ────────────────────────────────
             WindowEvent::CloseRequested => {
-                event_loop.exit();
+                if let Self::Running(state) = self {
+                    begin_shutdown(state, event_loop);
+                } else {
+                    event_loop.exit();
+                }
             }
 
             // ── Redraw : blit FBO + chrome ─────────────────────────────
@@ -284,47 +3209,114 @@ impl ApplicationHandler<WakerEvent> for App {
                 if let Self::Running(state) = self {
                     let inner_size = state.window.inner_size();
 
-                    // 1. Servo peint dans le FBO offscreen
-                    if let Some(webview) = state.webviews.borrow().last() {
+                    // 1. Servo peint dans le FBO offscreen — sauf si seul le
+                    //    chrome a changé : le FBO garde alors la frame précédente.
+                    let webview_dirty = state.webview_dirty.replace(false);
+                    let chrome_dirty = state.chrome_dirty.replace(false);
+                    let active = state.active_webview();
+                    if needs_webview_paint(webview_dirty, chrome_dirty)
+                        && let Some(webview) = &active
+                    {
                         webview.paint();
                     }
 
-                    // 2. Préparer le contexte fenêtre et blitter le FBO
+                    // 2. Préparer le contexte fenêtre et re-blitter le FBO
+                    //    (le back buffer n'est pas conservé entre deux présentations)
                     state.window_rendering_context.prepare_for_rendering();
 
-                    if let Some(blit) = state.offscreen_context.render_to_parent_callback() {
+                    // Sans webview (la dernière vient de se fermer), le FBO
+                    // contient encore la page fermée : ne pas la re-blitter.
+                    if active.is_some()
+                        && let Some(blit) = state.offscreen_context.render_to_parent_callback()
+                    {
                         let gl = state.window_rendering_context.glow_gl_api();
                         // GL coords: (0,0) = bottom-left
-                        // Blit to bottom portion: y=0 to y=height-40 (leaves top 40px for chrome)
+                        // Blit to bottom portion: y=0 to y=height-chrome_height (leaves the top for chrome)
                         let target_rect = euclid::default::Rect::new(
                             euclid::default::Point2D::new(0, 0),
                             euclid::default::Size2D::new(
                                 inner_size.width as i32,
-                                inner_size.height.saturating_sub(state.config.chrome.height) as i32,
+                                inner_size
+                                    .height
+                                    .saturating_sub(state.visible_chrome_height())
+                                    as i32,
                             ),
                         );
                         blit(&gl, target_rect);
                     }
 
-                    // 3. Dessiner le chrome (barre d'URL) dans les 40px du haut
+                    // 3. Dessiner le chrome (barre d'URL) dans la bande du haut
+                    let mut chrome = state.chrome.borrow_mut();
+                    let Some(chrome) = chrome
+                        .as_mut()
+                        .filter(|_| !state.fullscreen.get().hides_chrome())
+                    else {
+                        state.window_rendering_context.present();
+                        state.finish_frame();
+                        return;
+                    };
+                    // Taille du texte changée par le hot-reload
+                    let font_size = state.chrome_config.borrow().font_size;
+                    if chrome.font_size() != font_size {
+                        unsafe {
+                            chrome.set_font_size(font_size);
+                        }
+                    }
+                    let active = state.active_webview().map(|webview| webview.id());
+                    let favicons = state.favicons.borrow();
+                    let (favicon, generation) = match &active {
+                        Some(id) => favicons.get(id),
+                        None => (None, 0),
+                    };
+                    if chrome.favicon_generation() != generation {
+                        unsafe {
+                            chrome.set_favicon(favicon, generation);
+                        }
+                    }
+                    drop(favicons);
                     let urlbar = state.urlbar.borrow();
-                    let cursor_offset = if urlbar.is_focused() {
-                        Some(urlbar.cursor_char_offset())
-                    } else {
-                        None
+                    let status_text = state.status_text.borrow();
+                    let menu = state.menu.borrow();
+                    let find = state.find.borrow();
+                    let dialog = state.dialog.borrow();
+                    let context_menu = state.context_menu.borrow();
+                    let download_text = state.downloads.borrow().indicator();
+                    // Pendant l'édition : texte saisi + composition IME en cours
+                    let composed = urlbar.is_focused().then(|| urlbar.composed());
+                    let frame = ChromeFrame {
+                        url_text: composed
+                            .as_ref()
+                            .map_or(urlbar.display_text(), |c| c.text.as_str()),
+                        is_focused: urlbar.is_focused(),
+                        cursor_char_offset: composed.as_ref().map(|c| c.cursor),
+                        preedit: composed.as_ref().and_then(|c| c.preedit.clone()),
+                        blocked_count: state.adblock_engine.is_loaded().then(|| {
+                            state
+                                .active_webview()
+                                .map_or(0, |w| state.block_counters.page_blocked(w.id()))
+                        }),
+                        status_text: status_text.as_deref(),
+                        hovered_button: state.hovered_button.get(),
+                        is_bookmarked: state.is_bookmarked.get(),
+                        is_loading: state.load_state().loading,
+                        security: state.site_security.get(),
+                        menu: &menu,
+                        find: &find,
+                        dialog: dialog.as_ref().map(|d| &d.ui),
+                        context_menu: context_menu.as_ref().map(|m| &m.ui),
+                        download_text: download_text.as_deref(),
+                        scripts_blocked: state.scripts_blocked(),
+                        adblock_excepted: state
+                            .active_webview()
+                            .is_some_and(|webview| state.is_adblock_excepted(webview.id())),
                     };
                     unsafe {
-                        state.chrome.borrow().draw(
-                            inner_size.width,
-                            inner_size.height,
-                            urlbar.display_text(),
-                            urlbar.is_focused(),
-                            cursor_offset,
-                        );
+                        chrome.draw(inner_size.width, inner_size.height, &frame);
                     }
 
                     // 4. Présenter
                     state.window_rendering_context.present();
+                    state.finish_frame();
                 }
             }
 
@@ -332,41 +3324,96 @@ impl ApplicationHandler<WakerEvent> for App {
             WindowEvent::MouseWheel { delta, .. } => {
                 if let Self::Running(state) = self {
                     let pos = state.cursor_position.get();
+                    // Ctrl+molette : paliers de zoom, comme Ctrl+= / Ctrl+-,
+                    // centrés sur le curseur.
+                    if state.modifiers.get().control_key() {
+                        if pos.y >= chrome_h {
+                            let notches = match delta {
+                                MouseScrollDelta::LineDelta(_, y) => y as f64,
+                                MouseScrollDelta::PixelDelta(p) => p.y / zoom::PIXELS_PER_NOTCH,
+                            };
+                            let steps = state.wheel_zoom.borrow_mut().scroll(notches);
+                            if steps != 0 {
+                                let cursor = state.webview_point(pos, chrome_h).css;
+                                apply_zoom_at(state, zoom::step(state.zoom.get(), steps), cursor);
+                                remember_zoom(state);
+                            }
+                        }
+                        return;
+                    }
                     // Ne forwarde le scroll que si le curseur est dans la zone webview
                     if pos.y >= chrome_h
-                        && let Some(webview) = state.webviews.borrow().last()
+                        && let Some(webview) = state.active_webview()
                     {
-                        let (delta_x, delta_y, mode) = match delta {
-                            MouseScrollDelta::LineDelta(dx, dy) => {
-                                ((dx * 76.0) as f64, (dy * 76.0) as f64, WheelMode::DeltaLine)
-                            }
-                            MouseScrollDelta::PixelDelta(delta) => {
-                                (delta.x, delta.y, WheelMode::DeltaPixel)
-                            }
+                        let live_config = state.live_config.borrow();
+                        let general = &live_config.general;
+                        let scroll = ScrollSettings {
+                            line_height: general.scroll_line_height,
+                            invert: general.invert_scroll,
+                            shift: state.modifiers.get().shift_key(),
                         };
-
-                        let adjusted = DevicePoint::new(pos.x, pos.y - chrome_h);
+                        let adjusted = state.webview_point(pos, chrome_h).device;
                         webview.notify_input_event(InputEvent::Wheel(WheelEvent::new(
-                            WheelDelta {
-                                x: delta_x,
-                                y: delta_y,
-                                z: 0.0,
-                                mode,
-                            },
+                            wheel_delta(delta, scroll),
                             adjusted.into(),
                         )));
                     }
                 }
             }
 
-            // ── Redimensionnement de la fenêtre ────────────────────────
-            WindowEvent::Resized(new_size) => {
+            // ── Redimensionnement de la fenêtre ────────────────────────
+            WindowEvent::Resized(new_size) => {
+                if let Self::Running(state) = self {
+                    // Certains compositeurs ignorent la taille minimale : on
+                    // ne descend jamais sous elle, ni à un FBO vide.
+                    let min_size = min_window_size(
+                        &state.config.window,
+                        state.chrome_height,
+                        state.window.scale_factor(),
+                    );
+                    // Réduite : Windows annonce une taille nulle
+                    let minimized = state
+                        .window
+                        .is_minimized()
+                        .unwrap_or(new_size.width == 0 || new_size.height == 0);
+                    set_visibility(state, event_loop, |s| s.minimized.set(minimized));
+                    let new_size = clamp_window_size(new_size, min_size);
+                    // Redimensionner le contexte fenêtre
+                    state.window_rendering_context.resize(new_size);
+                    // Redimensionner le FBO offscreen (zone webview)
+                    let wv_size = webview_size(new_size, state.visible_chrome_height());
+                    state.offscreen_context.resize(wv_size);
+                }
+            }
+
+            // ── Fenêtre recouverte ou à nouveau visible ────────────────
+            WindowEvent::Occluded(occluded) => {
+                if let Self::Running(state) = self {
+                    set_visibility(state, event_loop, |s| s.occluded.set(occluded));
+                }
+            }
+
+            // ── Changement d'écran ou de mise à l'échelle ─────────────
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                if let Self::Running(state) = self {
+                    // Sans cela, une fenêtre déplacée vers un écran d'une autre
+                    // densité garde l'ancien ratio : clics et rendu décalés.
+                    for webview in state.webviews.borrow().iter() {
+                        webview.set_hidpi_scale_factor(Scale::new(scale_factor as f32));
+                    }
+                    state.redraw_webview();
+                }
+            }
+
+            // ── Thème OS clair/sombre ─────────────────────────────────
+            WindowEvent::ThemeChanged(theme) => {
                 if let Self::Running(state) = self {
-                    // Redimensionner le contexte fenêtre
-                    state.window_rendering_context.resize(new_size);
-                    // Redimensionner le FBO offscreen (zone webview)
-                    let wv_size = webview_size(new_size, state.config.chrome.height);
-                    state.offscreen_context.resize(wv_size);
+                    let chrome_config = state.chrome_config.borrow();
+                    let palette = chrome_config.palette(prefers_dark(Some(theme)));
+                    if let Some(renderer) = state.chrome.borrow_mut().as_mut() {
+                        renderer.set_colors(palette);
+                    }
+                    state.redraw_chrome();
                 }
             }
 
@@ -383,13 +3430,40 @@ impl ApplicationHandler<WakerEvent> for App {
                     let point = DevicePoint::new(position.x as f32, position.y as f32);
                     state.cursor_position.set(point);
 
+                    // État de survol des boutons du chrome et des menus ouverts
+                    let (hovered, hovered_item, hovered_row) = match state.chrome.borrow().as_ref()
+                    {
+                        Some(chrome) => (
+                            chrome.button_at(point.x, point.y),
+                            chrome.menu_item_at(point.x, point.y),
+                            chrome.context_item_at(point.x, point.y),
+                        ),
+                        None => (None, None, None),
+                    };
+                    let menu_changed = state.menu.borrow_mut().hover(hovered_item);
+                    let context_changed = state
+                        .context_menu
+                        .borrow_mut()
+                        .as_mut()
+                        .is_some_and(|menu| menu.ui.hover(hovered_row));
+                    if state.hovered_button.replace(hovered) != hovered
+                        || menu_changed
+                        || context_changed
+                    {
+                        state.redraw_chrome();
+                    }
+
                     // Ne forwarde que si le curseur est dans la zone webview
-                    if position.y >= chrome_h as f64 {
-                        let adjusted = DevicePoint::new(
-                            position.x as f32,
-                            (position.y - chrome_h as f64) as f32,
-                        );
-                        if let Some(webview) = state.webviews.borrow().last() {
+                    // (et pas au-dessus d'un menu qui la recouvre)
+                    let over_page = position.y >= chrome_h as f64
+                        && hovered_item.is_none()
+                        && hovered_row.is_none();
+                    if state.pointer_over_page.replace(over_page) != over_page {
+                        state.apply_cursor();
+                    }
+                    if over_page {
+                        let adjusted = state.webview_point(point, chrome_h).device;
+                        if let Some(webview) = state.active_webview() {
                             webview.notify_input_event(InputEvent::MouseMove(MouseMoveEvent::new(
                                 adjusted.into(),
                             )));
@@ -400,12 +3474,85 @@ impl ApplicationHandler<WakerEvent> for App {
 
             // ── Curseur quitte la fenêtre ─────────────────────────────
             WindowEvent::CursorLeft { .. } => {
-                if let Self::Running(state) = self
-                    && let Some(webview) = state.webviews.borrow().last()
-                {
-                    webview.notify_input_event(InputEvent::MouseLeftViewport(
-                        MouseLeftViewportEvent::default(),
-                    ));
+                if let Self::Running(state) = self {
+                    if state.hovered_button.take().is_some() {
+                        state.redraw_chrome();
+                    }
+                    if let Some(webview) = state.active_webview() {
+                        webview.notify_input_event(InputEvent::MouseLeftViewport(
+                            MouseLeftViewportEvent::default(),
+                        ));
+                    }
+                }
+            }
+
+            // ── Fichier glissé depuis le gestionnaire de fichiers ─────
+            WindowEvent::DroppedFile(path) => {
+                if let Self::Running(state) = self {
+                    match dropped_file_url(&path) {
+                        Ok(url) => state.dropped_files.borrow_mut().push(url),
+                        Err(reason) => {
+                            tracing::warn!(path = %path.display(), reason, "Ignoring dropped file");
+                        }
+                    }
+                }
+            }
+
+            // ── Pincement sur le pavé tactile (ex-`TouchpadMagnify`) ───
+            // Même chemin que le zoom clavier : bornes 25–500 % et mémoire
+            // par hôte, écrite une seule fois à la fin du geste. Centré sur
+            // le curseur.
+            WindowEvent::PinchGesture { delta, phase, .. } => {
+                if let Self::Running(state) = self {
+                    let cursor = state
+                        .webview_point(state.cursor_position.get(), chrome_h)
+                        .css;
+                    apply_zoom_at(state, zoom::pinch(state.zoom.get(), delta), cursor);
+                    if matches!(phase, TouchPhase::Ended | TouchPhase::Cancelled) {
+                        remember_zoom(state);
+                    }
+                }
+            }
+
+            // ── Écran tactile ─────────────────────────────────────────
+            // Servo gère lui-même le pan (défilement) et le tap (clic) à
+            // partir des événements bruts ; on ne fait que router.
+            WindowEvent::Touch(touch) => {
+                if let Self::Running(state) = self {
+                    let pos = DevicePoint::new(touch.location.x as f32, touch.location.y as f32);
+                    let target = track_touch(
+                        &mut state.touches.borrow_mut(),
+                        touch.id,
+                        touch.phase,
+                        pos.y < chrome_h,
+                    );
+                    match target {
+                        // Tap dans le chrome : comme un clic (bouton ou barre d'URL)
+                        Some(TouchTarget::Chrome) => {
+                            if touch.phase == TouchPhase::Ended {
+                                click_chrome(state, pos, 1);
+                            }
+                        }
+                        Some(TouchTarget::WebView) => {
+                            if touch.phase == TouchPhase::Started
+                                && state.urlbar.borrow().is_focused()
+                            {
+                                state.urlbar.borrow_mut().unfocus();
+                                state.redraw_chrome();
+                            }
+                            let adjusted = state.webview_point(pos, chrome_h).device;
+                            if let Some(webview) = state.active_webview() {
+                                webview.notify_input_event(InputEvent::Touch(TouchEvent::new(
+                                    servo_touch_type(touch.phase),
+                                    // Les ids Winit sont uniques par contact ; la
+                                    // troncature ne mélange pas deux doigts simultanés.
+                                    TouchId(touch.id as i32),
+                                    adjusted.into(),
+                                )));
+                            }
+                        }
+                        None => {}
+                    }
                 }
             }
 
@@ -418,24 +3565,125 @@ impl ApplicationHandler<WakerEvent> for App {
                 if let Self::Running(state) = self {
                     let pos = state.cursor_position.get();
 
+                    // Menu contextuel ouvert : un clic le referme (en exécutant
+                    // l'entrée cliquée), sans atteindre la page. Les relâchés
+                    // sont avalés aussi, dont celui du clic droit qui l'a ouvert.
+                    if state.context_menu.borrow().is_some() {
+                        if btn_state == ElementState::Pressed {
+                            let row = state
+                                .chrome
+                                .borrow()
+                                .as_ref()
+                                .and_then(|c| c.context_item_at(pos.x, pos.y));
+                            // Une ligne grisée ferme le menu sans rien faire
+                            if let Some(menu) = state.close_context_menu()
+                                && button == WinitMouseButton::Left
+                                && let Some(&(action, true)) =
+                                    row.and_then(|row| menu.items().get(row))
+                            {
+                                run_context_action(state, action, menu.target().clone());
+                            }
+                        }
+                        return;
+                    }
+
+                    // Menu ouvert : le clic le referme, sans atteindre la page
+                    if state.menu.borrow().is_open() {
+                        if btn_state == ElementState::Pressed {
+                            let item = state
+                                .chrome
+                                .borrow()
+                                .as_ref()
+                                .and_then(|c| c.menu_item_at(pos.x, pos.y));
+                            state.menu.borrow_mut().close();
+                            state.redraw_chrome();
+                            if let Some(item) = item
+                                && button == WinitMouseButton::Left
+                            {
+                                run_menu_item(state, item, event_loop);
+                            }
+                        }
+                        return;
+                    }
+
+                    // Dialogue JavaScript ouvert : la page attend sa réponse
+                    if pos.y >= chrome_h && state.dialog.borrow().is_some() {
+                        return;
+                    }
+
                     if pos.y < chrome_h {
-                        // Clic dans la zone chrome → focus la barre d'URL
+                        // Clic sur un bouton → action, sans toucher au focus de la barre
+                        // Clic ailleurs dans la zone chrome → focus la barre d'URL
                         if btn_state == ElementState::Pressed && button == WinitMouseButton::Left {
-                            state.urlbar.borrow_mut().focus();
-                            state.window.request_redraw();
+                            let clicks =
+                                state.chrome_clicks.borrow_mut().press(Instant::now(), pos);
+                            click_chrome(state, pos, clicks);
                         }
                     } else {
-                        // Clic dans la zone webview → unfocus urlbar + forward
+                        // Boutons latéraux de la souris → historique, sans les
+                        // transmettre à la page (`mouse_history_buttons = false`
+                        // rétablit la transmission brute).
+                        if state.config.general.mouse_history_buttons
+                            && let Some(step) = history_step(button)
+                        {
+                            if btn_state == ElementState::Pressed
+                                && let Some(webview) = state.active_webview()
+                            {
+                                if step < 0 {
+                                    webview.go_back(1);
+                                } else {
+                                    webview.go_forward(1);
+                                }
+                            }
+                            return;
+                        }
+
+                        // Clic sur l'indicateur de téléchargement → annulation
+                        let on_download = state
+                            .chrome
+                            .borrow()
+                            .as_ref()
+                            .is_some_and(|c| c.download_indicator_at(pos.x, pos.y));
+                        if on_download {
+                            if btn_state == ElementState::Pressed
+                                && button == WinitMouseButton::Left
+                                && state.downloads.borrow_mut().cancel_latest()
+                            {
+                                state.redraw_chrome();
+                            }
+                            return;
+                        }
+
+                        // Clic sur la barre de recherche → elle reprend le clavier
+                        let on_find_bar = state
+                            .chrome
+                            .borrow()
+                            .as_ref()
+                            .is_some_and(|c| c.find_bar_contains(pos.x, pos.y));
+                        if on_find_bar {
+                            if btn_state == ElementState::Pressed {
+                                state.urlbar.borrow_mut().unfocus();
+                                state.find.borrow_mut().focus();
+                                state.redraw_chrome();
+                            }
+                            return;
+                        }
+
+                        // Clic dans la zone webview → unfocus urlbar/recherche + forward
                         if btn_state == ElementState::Pressed {
                             let was_focused = state.urlbar.borrow().is_focused();
                             if was_focused {
                                 state.urlbar.borrow_mut().unfocus();
-                                state.window.request_redraw();
+                                state.redraw_chrome();
+                            }
+                            if state.find.borrow().is_focused() {
+                                state.find.borrow_mut().unfocus();
+                                state.redraw_chrome();
                             }
                         }
 
-                        let adjusted = DevicePoint::new(pos.x, pos.y - chrome_h);
-                        if let Some(webview) = state.webviews.borrow().last() {
+                        let adjusted = state.webview_point(pos, chrome_h).device;
+                        if let Some(webview) = state.active_webview() {
                             let servo_button = match button {
                                 WinitMouseButton::Left => ServoMouseButton::Left,
                                 WinitMouseButton::Right => ServoMouseButton::Right,
@@ -456,75 +3704,143 @@ impl ApplicationHandler<WakerEvent> for App {
                 }
             }
 
+            // ── Composition IME (CJK) ─────────────────────────────────
+            WindowEvent::Ime(ime) => {
+                if let Self::Running(state) = self {
+                    handle_ime(state, ime);
+                }
+            }
+
             // ── Saisie clavier ────────────────────────────────────────
             WindowEvent::KeyboardInput { event, .. } => {
                 if let Self::Running(state) = self {
                     let mods = state.modifiers.get();
 
-                    // ── Raccourcis globaux (toujours actifs) ──────────
-                    if event.state == ElementState::Pressed {
-                        // Ctrl+L : focus barre d'URL
-                        if mods.control_key()
-                            && let Key::Character(ref c) = event.logical_key
-                            && (c.as_str() == "l" || c.as_str() == "L")
-                        {
-                            state.urlbar.borrow_mut().focus();
-                            state.window.request_redraw();
-                            return;
+                    // ── Touches média → session média de la page en lecture ──
+                    if let Key::Named(named) = event.logical_key
+                        && let Some(key) = MediaKey::from_named(named)
+                        && state.handle_media_key(
+                            key,
+                            event.state == ElementState::Pressed && !event.repeat,
+                        )
+                    {
+                        return;
+                    }
+
+                    // ── Dialogue JavaScript ouvert → il prend le clavier ──
+                    if state.dialog.borrow().is_some() {
+                        if event.state == ElementState::Pressed {
+                            match &event.logical_key {
+                                Key::Named(NamedKey::Enter) if !event.repeat => {
+                                    state.answer_dialog(true);
+                                }
+                                Key::Named(NamedKey::Escape) => state.answer_dialog(false),
+                                Key::Named(NamedKey::Tab) => {
+                                    if let Some(dialog) = state.dialog.borrow_mut().as_mut() {
+                                        dialog.ui.focus_next();
+                                    }
+                                    state.redraw_chrome();
+                                }
+                                key => {
+                                    let mut dialog = state.dialog.borrow_mut();
+                                    if let Some(input) =
+                                        dialog.as_mut().and_then(|d| d.ui.input_mut())
+                                    {
+                                        edit_text_input(input, key, mods);
+                                    }
+                                    drop(dialog);
+                                    state.redraw_chrome();
+                                }
+                            }
                         }
+                        return;
+                    }
 
-                        // Ctrl+, : open settings
-                        if mods.control_key()
-                            && let Key::Character(ref c) = event.logical_key
-                            && c.as_str() == ","
-                        {
-                            let html = settings::generate_settings_html(&state.config);
-                            let encoded = settings::url_encode(&html);
-                            let data_url = format!("data:text/html;charset=utf-8,{encoded}");
-                            if let Ok(url) = Url::parse(&data_url)
-                                && let Some(webview) = state.webviews.borrow().last()
-                            {
-                                webview.load(url);
+                    // ── Menu contextuel ouvert → navigation clavier ───
+                    if state.context_menu.borrow().is_some() {
+                        if event.state == ElementState::Pressed {
+                            match event.logical_key {
+                                Key::Named(NamedKey::Enter) if !event.repeat => {
+                                    if let Some(menu) = state.close_context_menu()
+                                        && let Some(action) = menu.selected_action()
+                                    {
+                                        run_context_action(state, action, menu.target().clone());
+                                    }
+                                }
+                                Key::Named(NamedKey::Escape) => {
+                                    state.close_context_menu();
+                                }
+                                Key::Named(NamedKey::ArrowDown) => {
+                                    if let Some(menu) = state.context_menu.borrow_mut().as_mut() {
+                                        menu.ui.select_next();
+                                    }
+                                    state.redraw_chrome();
+                                }
+                                Key::Named(NamedKey::ArrowUp) => {
+                                    if let Some(menu) = state.context_menu.borrow_mut().as_mut() {
+                                        menu.ui.select_prev();
+                                    }
+                                    state.redraw_chrome();
+                                }
+                                _ => {}
                             }
-                            return;
                         }
+                        return;
+                    }
 
-                        // Ctrl+R : recharger
-                        if mods.control_key()
-                            && let Key::Character(ref c) = event.logical_key
-                            && (c.as_str() == "r" || c.as_str() == "R")
-                        {
-                            if let Some(webview) = state.webviews.borrow().last() {
-                                webview.reload();
+                    // ── Menu « ≡ » ouvert → navigation clavier ───────
+                    if state.menu.borrow().is_open() {
+                        if event.state == ElementState::Pressed {
+                            let mut menu = state.menu.borrow_mut();
+                            match event.logical_key {
+                                Key::Named(NamedKey::ArrowDown) => menu.select_next(),
+                                Key::Named(NamedKey::ArrowUp) => menu.select_prev(),
+                                Key::Named(NamedKey::Enter) => {
+                                    let item = menu.selected_item();
+                                    menu.close();
+                                    drop(menu);
+                                    if let Some(item) = item {
+                                        run_menu_item(state, item, event_loop);
+                                    }
+                                }
+                                Key::Named(NamedKey::Escape) => menu.close(),
+                                _ => {}
                             }
-                            return;
+                            state.redraw_chrome();
                         }
+                        return;
+                    }
 
-                        // F5 : recharger
-                        if let Key::Named(NamedKey::F5) = event.logical_key {
-                            if let Some(webview) = state.webviews.borrow().last() {
-                                webview.reload();
+                    // ── Raccourcis globaux (toujours actifs) ──────────
+                    if event.state == ElementState::Pressed {
+                        let text_focused =
+                            state.urlbar.borrow().is_focused() || state.find.borrow().is_focused();
+                        if let Some(action) = state.keybindings.action_for(mods, &event.logical_key)
+                            && !(text_focused && action.yields_to_text_input())
+                        {
+                            // Touche maintenue : répétée seulement si l'action l'accepte
+                            if !event.repeat || action.allows_repeat() {
+                                run_action(state, action, event_loop);
                             }
                             return;
                         }
 
-                        // Alt+Left : retour
-                        if mods.alt_key()
-                            && let Key::Named(NamedKey::ArrowLeft) = event.logical_key
+                        // Échap : sortir du plein écran de la page (vidéo…)
+                        if let Key::Named(NamedKey::Escape) = event.logical_key
+                            && state.fullscreen.get().is_page()
                         {
-                            if let Some(webview) = state.webviews.borrow().last() {
-                                webview.go_back(1);
-                            }
+                            state.exit_page_fullscreen();
                             return;
                         }
 
-                        // Alt+Right : avant
-                        if mods.alt_key()
-                            && let Key::Named(NamedKey::ArrowRight) = event.logical_key
+                        // Échap (barre d'URL non focusée) : arrêter le chargement
+                        if let Key::Named(NamedKey::Escape) = event.logical_key
+                            && !event.repeat
+                            && state.load_state().loading
+                            && !state.urlbar.borrow().is_focused()
+                            && !state.find.borrow().is_focused()
                         {
-                            if let Some(webview) = state.webviews.borrow().last() {
-                                webview.go_forward(1);
-                            }
+                            reload_or_stop(state);
                             return;
                         }
                     }
@@ -532,12 +3848,20 @@ impl ApplicationHandler<WakerEvent> for App {
                     // ── URL bar focusée → consommer les touches ──────
                     if state.urlbar.borrow().is_focused() && event.state == ElementState::Pressed {
                         let mut urlbar = state.urlbar.borrow_mut();
+                        // Pendant une composition, les touches appartiennent à l'IME
+                        if urlbar.is_composing() {
+                            return;
+                        }
 
                         match &event.logical_key {
+                            // Une seule navigation par appui, même maintenu
+                            Key::Named(NamedKey::Enter) if event.repeat => {}
                             Key::Named(NamedKey::Enter) => {
                                 if let Some(url) = urlbar.submit() {
                                     drop(urlbar);
-                                    if let Some(webview) = state.webviews.borrow().last() {
+                                    if let Some(page) = pages::internal_page(&url) {
+                                        open_internal_page(state, page);
+                                    } else if let Some(webview) = state.active_webview() {
                                         webview.load(url);
                                     }
                                 }
@@ -563,24 +3887,90 @@ impl ApplicationHandler<WakerEvent> for App {
                             Key::Named(NamedKey::End) => {
                                 urlbar.end();
                             }
+                            Key::Character(c) if mods.control_key() => {
+                                match c.to_ascii_lowercase().as_str() {
+                                    "a" => urlbar.select_all(),
+                                    // Copier/couper : tout le texte s'il est sélectionné
+                                    "c" | "x" => {
+                                        if let Some(text) = urlbar.selected_text() {
+                                            copy_to_clipboard(state, text);
+                                            if c.eq_ignore_ascii_case("x") {
+                                                urlbar.delete();
+                                            }
+                                        }
+                                    }
+                                    "v" => match state.clipboard.borrow_mut().get_text() {
+                                        Ok(text) => urlbar.paste(&text),
+                                        Err(e) => tracing::debug!(error = %e, "Nothing to paste"),
+                                    },
+                                    _ => {}
+                                }
+                            }
+                            Key::Character(c) => {
+                                if !mods.alt_key() {
+                                    for ch in c.chars() {
+                                        urlbar.insert_char(ch);
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+
+                        state.redraw_chrome();
+                        return;
+                    }
+
+                    // ── Barre de recherche focusée → consommer les touches ──
+                    if state.find.borrow().is_focused() && event.state == ElementState::Pressed {
+                        let mut find = state.find.borrow_mut();
+                        let input = find.input_mut();
+                        // `Some(step)` : relancer la recherche
+                        let mut search = None;
+
+                        match &event.logical_key {
+                            Key::Named(NamedKey::Enter) => {
+                                search = Some(if mods.shift_key() { -1 } else { 1 });
+                            }
+                            Key::Named(NamedKey::Escape) => {
+                                drop(find);
+                                close_find(state);
+                                return;
+                            }
+                            Key::Named(NamedKey::Backspace) => {
+                                input.backspace();
+                                search = Some(0);
+                            }
+                            Key::Named(NamedKey::Delete) => {
+                                input.delete();
+                                search = Some(0);
+                            }
+                            Key::Named(NamedKey::ArrowLeft) => input.move_cursor_left(),
+                            Key::Named(NamedKey::ArrowRight) => input.move_cursor_right(),
+                            Key::Named(NamedKey::Home) => input.home(),
+                            Key::Named(NamedKey::End) => input.end(),
                             Key::Character(c) => {
                                 if mods.control_key() && (c.as_str() == "a" || c.as_str() == "A") {
-                                    urlbar.select_all();
+                                    input.select_all();
                                 } else if !mods.control_key() && !mods.alt_key() {
                                     for ch in c.chars() {
-                                        urlbar.insert_char(ch);
+                                        input.insert_char(ch);
                                     }
+                                    search = Some(0);
                                 }
                             }
                             _ => {}
                         }
 
-                        state.window.request_redraw();
+                        drop(find);
+                        if let Some(step) = search {
+                            run_find(state, step);
+                        }
+                        state.redraw_chrome();
                         return;
                     }
 
                     // ── Passer à Servo (URL bar pas focusée) ─────────
-                    if let Some(webview) = state.webviews.borrow().last() {
+                    if let Some(webview) = state.active_webview() {
                         let keyboard_event =
                             crate::keyutils::keyboard_event_from_winit(&event, mods);
                         webview.notify_input_event(InputEvent::Keyboard(keyboard_event));
@@ -629,12 +4019,351 @@ mod tests {
         assert_eq!(result, PhysicalSize::new(100, 0));
     }
 
+    #[test]
+    fn test_webview_size_uses_configured_height() {
+        // Régression : `height = 56` ne doit pas laisser la webview commencer à y=40
+        let mut config = Config::default();
+        config.chrome.height = 56;
+        let result = webview_size(PhysicalSize::new(1280, 800), config.chrome.height);
+        assert_eq!(result, PhysicalSize::new(1280, 744));
+    }
+
+    #[test]
+    fn test_webview_size_without_chrome_uses_full_window() {
+        let height = effective_chrome_height(false, TEST_CHROME_HEIGHT);
+        assert_eq!(height, 0);
+        let result = webview_size(PhysicalSize::new(1280, 800), height);
+        assert_eq!(result, PhysicalSize::new(1280, 800));
+    }
+
+    #[test]
+    fn test_effective_chrome_height_with_chrome() {
+        assert_eq!(effective_chrome_height(true, 56), 56);
+    }
+
+    #[test]
+    fn test_window_title_shows_zoom() {
+        assert_eq!(
+            window_title("SuriBrows", "Example", 1.0),
+            "SuriBrows — Example"
+        );
+        assert_eq!(
+            window_title("SuriBrows", "Example", 1.25),
+            "SuriBrows — Example (125 %)"
+        );
+        assert_eq!(window_title("SuriBrows", "", 0.5), "SuriBrows (50 %)");
+    }
+
+    #[test]
+    fn test_window_title_placeholder() {
+        assert_eq!(
+            window_title("{title} | Work Browser", "Example", 1.0),
+            "Example | Work Browser"
+        );
+        assert_eq!(
+            window_title("{title} | Work Browser", "", 1.0),
+            "Work Browser"
+        );
+        assert_eq!(
+            window_title("Work: {title}", "Example", 1.5),
+            "Work: Example (150 %)"
+        );
+    }
+
+    #[test]
+    fn test_hidden_window_spaces_servo_spins() {
+        let last = Instant::now();
+        assert_eq!(next_spin_at(false, false, last), None);
+        assert_eq!(
+            next_spin_at(true, false, last),
+            Some(last + HIDDEN_SPIN_INTERVAL)
+        );
+        // L'arrêt n'est jamais ralenti
+        assert_eq!(next_spin_at(true, true, last), None);
+    }
+
+    #[test]
+    fn test_control_flow_picks_nearest_deadline() {
+        let now = Instant::now();
+        let frame = now + FRAME_INTERVAL;
+        let spin = now + HIDDEN_SPIN_INTERVAL;
+        assert_eq!(control_flow(&[None, None]), ControlFlow::Wait);
+        assert_eq!(
+            control_flow(&[Some(spin), None]),
+            ControlFlow::WaitUntil(spin)
+        );
+        assert_eq!(
+            control_flow(&[Some(spin), Some(frame)]),
+            ControlFlow::WaitUntil(frame)
+        );
+    }
+
+    #[test]
+    fn test_initial_window_attributes_use_window_config() {
+        let mut config = Config::default();
+        config.window.width = 1024;
+        config.window.height = 700;
+        config.window.min_width = 400;
+        config.window.min_height = 300;
+        let attributes = initial_window_attributes(&config);
+        assert_eq!(
+            attributes.inner_size,
+            Some(winit::dpi::LogicalSize::new(1024.0, 700.0).into())
+        );
+        assert_eq!(
+            attributes.min_inner_size,
+            Some(winit::dpi::LogicalSize::new(400.0, 300.0).into())
+        );
+    }
+
+    #[test]
+    fn test_to_webview_point_at_scale_factors() {
+        const CHROME_H: f32 = 40.0;
+        // Curseur à (150, 115) pixels physiques, chrome de 40 px au-dessus
+        let cases = [
+            (1.0, DevicePoint::new(150.0, 75.0), (150.0, 75.0)),
+            (1.5, DevicePoint::new(150.0, 75.0), (100.0, 50.0)),
+            (2.0, DevicePoint::new(150.0, 75.0), (75.0, 37.5)),
+        ];
+        for (scale, device, css) in cases {
+            let point = to_webview_point(DevicePoint::new(150.0, 115.0), CHROME_H, scale);
+            assert_eq!(point.device, device, "scale {scale}");
+            assert_eq!(point.css, css, "scale {scale}");
+        }
+        // Au-dessus de la webview : ordonnée négative, pas de saturation
+        let point = to_webview_point(DevicePoint::new(10.0, 20.0), CHROME_H, 2.0);
+        assert_eq!(point.device, DevicePoint::new(10.0, -20.0));
+        assert_eq!(point.css, (5.0, -10.0));
+    }
+
+    #[test]
+    fn test_click_counter_detects_double_click() {
+        let mut clicks = ClickCounter::default();
+        let t0 = Instant::now();
+        let pos = DevicePoint::new(100.0, 10.0);
+        assert_eq!(clicks.press(t0, pos), 1);
+        assert_eq!(clicks.press(t0 + Duration::from_millis(200), pos), 2);
+        assert_eq!(clicks.press(t0 + Duration::from_millis(400), pos), 3);
+        // Trop tard : nouvelle série
+        assert_eq!(clicks.press(t0 + Duration::from_secs(2), pos), 1);
+        // Trop loin : nouvelle série
+        let far = DevicePoint::new(150.0, 10.0);
+        assert_eq!(clicks.press(t0 + Duration::from_millis(2100), far), 1);
+    }
+
+    #[test]
+    fn test_touch_stays_with_its_starting_zone() {
+        let mut touches = HashMap::new();
+        assert_eq!(
+            track_touch(&mut touches, 1, TouchPhase::Started, false),
+            Some(TouchTarget::WebView)
+        );
+        // Glisse dans le chrome : reste rattaché à la webview
+        assert_eq!(
+            track_touch(&mut touches, 1, TouchPhase::Moved, true),
+            Some(TouchTarget::WebView)
+        );
+        assert_eq!(
+            track_touch(&mut touches, 1, TouchPhase::Ended, true),
+            Some(TouchTarget::WebView)
+        );
+        assert!(touches.is_empty());
+    }
+
+    #[test]
+    fn test_multi_touch_ids_are_distinct() {
+        let mut touches = HashMap::new();
+        track_touch(&mut touches, 1, TouchPhase::Started, true);
+        track_touch(&mut touches, 2, TouchPhase::Started, false);
+        assert_eq!(
+            track_touch(&mut touches, 2, TouchPhase::Moved, true),
+            Some(TouchTarget::WebView)
+        );
+        assert_eq!(
+            track_touch(&mut touches, 1, TouchPhase::Cancelled, false),
+            Some(TouchTarget::Chrome)
+        );
+        assert_eq!(touches.len(), 1);
+        // Contact inconnu (commencé hors de la fenêtre)
+        assert_eq!(track_touch(&mut touches, 9, TouchPhase::Moved, false), None);
+    }
+
+    #[test]
+    fn test_servo_touch_type_mapping() {
+        assert_eq!(servo_touch_type(TouchPhase::Started), TouchEventType::Down);
+        assert_eq!(servo_touch_type(TouchPhase::Moved), TouchEventType::Move);
+        assert_eq!(servo_touch_type(TouchPhase::Ended), TouchEventType::Up);
+        assert_eq!(
+            servo_touch_type(TouchPhase::Cancelled),
+            TouchEventType::Cancel
+        );
+    }
+
+    #[test]
+    fn test_history_step_for_thumb_buttons() {
+        assert_eq!(history_step(WinitMouseButton::Back), Some(-1));
+        assert_eq!(history_step(WinitMouseButton::Forward), Some(1));
+        assert_eq!(history_step(WinitMouseButton::Left), None);
+        assert_eq!(history_step(WinitMouseButton::Other(8)), None);
+    }
+
+    // ── wheel_delta ───────────────────────────────────────────────────
+
+    const SCROLL: ScrollSettings = ScrollSettings {
+        line_height: 76.0,
+        invert: false,
+        shift: false,
+    };
+    const SHIFT: ScrollSettings = ScrollSettings {
+        shift: true,
+        ..SCROLL
+    };
+
+    #[test]
+    fn test_wheel_delta_keeps_both_axes() {
+        let delta = wheel_delta(MouseScrollDelta::LineDelta(0.5, -1.0), SCROLL);
+        assert_eq!((delta.x, delta.y), (38.0, -76.0));
+        assert!(matches!(delta.mode, WheelMode::DeltaLine));
+
+        // Scroll diagonal à deux doigts : pas de facteur sur les pixels
+        let pixels = winit::dpi::PhysicalPosition::new(12.0, -30.0);
+        let delta = wheel_delta(MouseScrollDelta::PixelDelta(pixels), SCROLL);
+        assert_eq!((delta.x, delta.y), (12.0, -30.0));
+        assert!(matches!(delta.mode, WheelMode::DeltaPixel));
+    }
+
+    #[test]
+    fn test_wheel_delta_shift_scrolls_horizontally() {
+        let delta = wheel_delta(MouseScrollDelta::LineDelta(0.0, -1.0), SHIFT);
+        assert_eq!((delta.x, delta.y), (-76.0, 0.0));
+
+        let pixels = winit::dpi::PhysicalPosition::new(0.0, 40.0);
+        let delta = wheel_delta(MouseScrollDelta::PixelDelta(pixels), SHIFT);
+        assert_eq!((delta.x, delta.y), (40.0, 0.0));
+    }
+
+    #[test]
+    fn test_wheel_delta_shift_already_horizontal() {
+        // macOS bascule déjà l'axe avec Shift : ne pas le remettre à la verticale
+        let delta = wheel_delta(MouseScrollDelta::LineDelta(-1.0, 0.0), SHIFT);
+        assert_eq!((delta.x, delta.y), (-76.0, 0.0));
+    }
+
+    #[test]
+    fn test_wheel_delta_line_height_and_invert() {
+        let settings = ScrollSettings {
+            line_height: 38.0,
+            invert: true,
+            ..SCROLL
+        };
+        let delta = wheel_delta(MouseScrollDelta::LineDelta(0.5, -2.0), settings);
+        assert_eq!((delta.x, delta.y), (-19.0, 76.0));
+
+        // L'inversion s'applique aussi aux pixels du pavé tactile
+        let pixels = winit::dpi::PhysicalPosition::new(3.0, -4.0);
+        let delta = wheel_delta(MouseScrollDelta::PixelDelta(pixels), settings);
+        assert_eq!((delta.x, delta.y), (-3.0, 4.0));
+    }
+
+    // ── dropped_file_url ──────────────────────────────────────────────
+
+    #[test]
+    fn test_dropped_file_url_encodes_path() {
+        let path = std::env::temp_dir().join("mon fichier été.html");
+        let url = dropped_file_url(&path).unwrap();
+        assert_eq!(url.scheme(), "file");
+        assert!(url.path().ends_with("/mon%20fichier%20%C3%A9t%C3%A9.html"));
+        assert_eq!(url.to_file_path().unwrap(), path);
+    }
+
+    #[test]
+    fn test_dropped_directory_rejected() {
+        assert!(dropped_file_url(&std::env::temp_dir()).is_err());
+        assert!(dropped_file_url(std::path::Path::new("relative.html")).is_err());
+    }
+
+    #[test]
+    fn test_devtools_hint_mentions_port() {
+        assert!(devtools_hint(6080).contains("localhost:6080"));
+        assert!(devtools_hint(0).contains("devtools_port"));
+    }
+
+    #[test]
+    fn test_needs_webview_paint() {
+        // Frappe dans la barre d'URL : pas de recomposition Servo
+        assert!(!needs_webview_paint(false, true));
+        // Nouvelle frame Servo, seule ou avec le chrome
+        assert!(needs_webview_paint(true, false));
+        assert!(needs_webview_paint(true, true));
+        // Redraw demandé par l'OS (aucun drapeau)
+        assert!(needs_webview_paint(false, false));
+    }
+
+    #[test]
+    fn test_claim_redraw_coalesces_until_presented() {
+        let pending = Cell::new(false);
+        // Rafale de frames Servo : un seul request_redraw
+        assert!(claim_redraw(&pending));
+        assert!(!claim_redraw(&pending));
+        assert!(!claim_redraw(&pending));
+        // Après présentation, la frame suivante redemande un redraw
+        pending.set(false);
+        assert!(claim_redraw(&pending));
+    }
+
     #[test]
     fn test_webview_size_zero() {
         let result = webview_size(PhysicalSize::new(0, 0), TEST_CHROME_HEIGHT);
         assert_eq!(result, PhysicalSize::new(0, 0));
     }
 
+    // ── taille minimale ───────────────────────────────────────────────
+
+    #[test]
+    fn test_min_window_size_scales_with_dpi() {
+        let config = WindowConfig::default();
+        let min = min_window_size(&config, TEST_CHROME_HEIGHT, 1.0);
+        assert_eq!(min, PhysicalSize::new(320, 240));
+        let min = min_window_size(&config, TEST_CHROME_HEIGHT, 2.0);
+        assert_eq!(min, PhysicalSize::new(640, 480));
+    }
+
+    #[test]
+    fn test_min_window_size_keeps_webview_visible() {
+        let config = WindowConfig {
+            min_width: 0,
+            min_height: 0,
+            ..WindowConfig::default()
+        };
+        let min = min_window_size(&config, TEST_CHROME_HEIGHT, 1.0);
+        assert_eq!(min, PhysicalSize::new(1, TEST_CHROME_HEIGHT + 1));
+    }
+
+    #[test]
+    fn test_degenerate_resize_never_empties_webview() {
+        let min = min_window_size(&WindowConfig::default(), TEST_CHROME_HEIGHT, 1.0);
+        for size in [
+            PhysicalSize::new(0, 0),
+            PhysicalSize::new(100, 20),
+            PhysicalSize::new(5000, 0),
+            PhysicalSize::new(0, TEST_CHROME_HEIGHT),
+        ] {
+            let clamped = clamp_window_size(size, min);
+            let webview = webview_size(clamped, TEST_CHROME_HEIGHT);
+            assert!(webview.width > 0 && webview.height > 0, "{size:?}");
+        }
+        // Une taille normale n'est pas touchée
+        let size = PhysicalSize::new(1280, 800);
+        assert_eq!(clamp_window_size(size, min), size);
+    }
+
+    #[test]
+    fn test_prefers_dark() {
+        assert_eq!(prefers_dark(Some(Theme::Dark)), Some(true));
+        assert_eq!(prefers_dark(Some(Theme::Light)), Some(false));
+        assert_eq!(prefers_dark(None), None);
+    }
+
     #[test]
     fn test_default_config_chrome_height_is_40() {
         let config = Config::default();
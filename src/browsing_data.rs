@@ -0,0 +1,88 @@
+//! Effacement des données de navigation : cache HTTP, cookies et stockage
+//! des sites (`localStorage`, `sessionStorage`).
+//!
+//! Déclenché par Ctrl+Shift+Suppr ou par le bouton « Clear Browsing Data »
+//! des paramètres, dont le lien `http://suribrows.settings/clear-data` est
+//! intercepté dans `load_web_resource` comme l'enregistrement. L'effacement
+//! passe par les gestionnaires de données de Servo ; les onglets ouverts
+//! sont ensuite rechargés (une page chargée garde sinon en mémoire ce
+//! qu'elle avait lu) et [`report_html`] résume ce qui a été effacé.
+//!
+//! Rien n'est à supprimer sur disque : SuriBrows ne donne pas de dossier de
+//! profil à Servo, qui garde cache, cookies et stockage en mémoire.
+
+use crate::pages;
+use crate::settings::html_escape;
+
+/// Ce qu'un effacement a supprimé.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClearReport {
+    /// Sites qui avaient des cookies ou du stockage, triés.
+    pub sites: Vec<String>,
+    /// Onglets rechargés après l'effacement.
+    pub tabs_reloaded: usize,
+}
+
+/// Page de confirmation : ce qui a été effacé, et les sites concernés.
+pub fn report_html(report: &ClearReport) -> String {
+    let sites = if report.sites.is_empty() {
+        "<p>No site had cookies or stored data.</p>".to_string()
+    } else {
+        let items: String = report
+            .sites
+            .iter()
+            .map(|site| format!("<li>{}</li>", html_escape(site)))
+            .collect();
+        format!(
+            "<p>Cookies and stored data removed for {count} {noun}:</p>\n<ul>{items}</ul>",
+            count = report.sites.len(),
+            noun = if report.sites.len() == 1 {
+                "site"
+            } else {
+                "sites"
+            },
+        )
+    };
+    let tabs = match report.tabs_reloaded {
+        0 => String::new(),
+        1 => "<p>1 open tab was reloaded.</p>".to_string(),
+        n => format!("<p>{n} open tabs were reloaded.</p>"),
+    };
+    pages::shell(
+        "Browsing Data Cleared",
+        "
+h1 { color: #6a9eff; }
+li { font-family: monospace; }
+p { color: #888; }
+",
+        &format!(
+            "<h1>Browsing data cleared</h1>\n<p>The HTTP cache was emptied.</p>\n{sites}\n{tabs}"
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_escaped_sites() {
+        let report = ClearReport {
+            sites: vec!["example.com".to_string(), "<evil>.test".to_string()],
+            tabs_reloaded: 3,
+        };
+        let html = report_html(&report);
+        assert!(html.contains("for 2 sites:"));
+        assert!(html.contains("<li>example.com</li>"));
+        assert!(html.contains("<li>&lt;evil&gt;.test</li>"));
+        assert!(html.contains("3 open tabs were reloaded."));
+    }
+
+    #[test]
+    fn test_report_without_site_data() {
+        let html = report_html(&ClearReport::default());
+        assert!(html.contains("No site had cookies or stored data."));
+        assert!(html.contains("The HTTP cache was emptied."));
+        assert!(!html.contains("reloaded"));
+    }
+}
@@ -0,0 +1,366 @@
+//! Page d'erreur de certificat TLS et exceptions acceptées pendant la session.
+//!
+//! Servo remplace une page au certificat invalide par sa ressource
+//! `badcert.html`, où il substitue la raison (`${reason}`), le certificat en
+//! base64 (`${bytes}`) et le secret qui autorise la requête `chrome:allowcert`
+//! (`${secret}`). SuriBrows fournit à la place [`BOUNCE_HTML`], qui renvoie ces
+//! valeurs vers `http://suribrows.certerror/report` : la requête est
+//! interceptée dans `load_web_resource` et remplacée par l'avertissement
+//! généré ici (hôte, type d'erreur, empreinte SHA-256).
+//!
+//! Les exceptions acceptées (hôte, empreinte) ne vivent qu'en mémoire : elles
+//! ne sont jamais écrites sur disque et disparaissent à la fermeture.
+
+use std::collections::HashSet;
+
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::pages::{self, js_string};
+use crate::settings::html_escape;
+use crate::token::{self, PendingTokens};
+
+/// Domaine des requêtes interceptées (rapport d'erreur, acceptation).
+const DOMAIN: &str = "suribrows.certerror";
+
+/// Ressource `badcert.html` servie à Servo : transmet l'erreur à l'embedder.
+/// `location.replace` évite une entrée d'historique supplémentaire.
+pub const BOUNCE_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Certificate error</title>
+</head>
+<body style="background: #1a1a1a">
+<div id="reason" hidden>${reason}</div>
+<div id="bytes" hidden>${bytes}</div>
+<div id="secret" hidden>${secret}</div>
+<script>
+const text = (id) => document.getElementById(id).textContent;
+const params = new URLSearchParams({
+    url: location.href,
+    reason: text("reason"),
+    cert: text("bytes"),
+    secret: text("secret"),
+});
+location.replace("http://suribrows.certerror/report?" + params);
+</script>
+</body>
+</html>"#;
+
+/// Catégorie d'erreur affichée à l'utilisateur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertErrorKind {
+    /// Expiré ou pas encore valide.
+    Expired,
+    /// Autosigné ou émis par une autorité inconnue.
+    SelfSigned,
+    /// Émis pour un autre nom d'hôte.
+    NameMismatch,
+    Other,
+}
+
+impl CertErrorKind {
+    /// Catégorie d'après la raison donnée par Servo (erreur rustls, ex.
+    /// `invalid peer certificate: UnknownIssuer`).
+    pub fn classify(reason: &str) -> Self {
+        let reason = reason.to_ascii_lowercase();
+        if reason.contains("expired") || reason.contains("notvalidyet") {
+            CertErrorKind::Expired
+        } else if reason.contains("unknownissuer")
+            || reason.contains("self-signed")
+            || reason.contains("selfsigned")
+        {
+            CertErrorKind::SelfSigned
+        } else if reason.contains("notvalidforname") || reason.contains("name mismatch") {
+            CertErrorKind::NameMismatch
+        } else {
+            CertErrorKind::Other
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CertErrorKind::Expired => "The certificate has expired or is not yet valid.",
+            CertErrorKind::SelfSigned => {
+                "The certificate is self-signed or issued by an unknown authority."
+            }
+            CertErrorKind::NameMismatch => "The certificate was issued for a different host.",
+            CertErrorKind::Other => "The certificate is invalid.",
+        }
+    }
+}
+
+/// Erreur transmise par [`BOUNCE_HTML`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertErrorReport {
+    /// Page demandée.
+    pub url: Url,
+    pub reason: String,
+    /// Certificat DER encodé en base64, tel que Servo le fournit.
+    pub certificate: String,
+    /// Secret à renvoyer à `chrome:allowcert`.
+    pub secret: String,
+}
+
+impl CertErrorReport {
+    /// Lit le rapport d'une URL `http://suribrows.certerror/report?…`.
+    pub fn parse(url: &Url) -> Option<Self> {
+        if url.host_str() != Some(DOMAIN) || url.path() != "/report" {
+            return None;
+        }
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        Some(Self {
+            url: Url::parse(&param("url")?).ok()?,
+            reason: param("reason").unwrap_or_default(),
+            certificate: param("cert")?,
+            secret: param("secret")?,
+        })
+    }
+
+    /// Hôte de la page (avec le port s'il n'est pas celui par défaut).
+    pub fn host(&self) -> String {
+        let host = self.url.host_str().unwrap_or_default();
+        match self.url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        }
+    }
+
+    /// Empreinte SHA-256 du certificat, `None` s'il est absent ou illisible.
+    pub fn fingerprint(&self) -> Option<String> {
+        let der = base64::engine::general_purpose::STANDARD
+            .decode(self.certificate.trim())
+            .ok()
+            .filter(|der| !der.is_empty())?;
+        Some(fingerprint(&der))
+    }
+}
+
+/// Empreinte SHA-256 d'un certificat DER (`AB:CD:…`).
+pub fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Jeton d'un lien `http://suribrows.certerror/accept?token=…`.
+pub fn accept_token(url: &Url) -> Option<String> {
+    token::url_token(url, DOMAIN, "/accept")
+}
+
+/// Avertissement affiché, en attente d'une acceptation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingException {
+    pub report: CertErrorReport,
+    pub host: String,
+    pub fingerprint: String,
+}
+
+/// Exceptions de la session. Une exception vaut pour un hôte et un
+/// certificat précis : un autre certificat sur le même hôte redemande.
+#[derive(Debug, Default)]
+pub struct CertExceptions {
+    allowed: HashSet<(String, String)>,
+    /// Avertissements affichés, par jeton du lien « Accept risk ».
+    pending: PendingTokens<PendingException>,
+}
+
+impl CertExceptions {
+    /// `true` si l'utilisateur a accepté ce certificat pour cet hôte.
+    pub fn is_allowed(&self, host: &str, fingerprint: &str) -> bool {
+        self.allowed.contains(&key(host, fingerprint))
+    }
+
+    /// Enregistre un avertissement affiché ; retourne le jeton de son lien.
+    pub fn propose(&mut self, pending: PendingException) -> String {
+        self.pending.propose(pending)
+    }
+
+    /// L'utilisateur accepte le risque : l'exception est mémorisée pour la
+    /// session. `None` pour un jeton inconnu.
+    pub fn accept(&mut self, token: &str) -> Option<PendingException> {
+        let pending = self.pending.take(token)?;
+        self.allowed
+            .insert(key(&pending.host, &pending.fingerprint));
+        Some(pending)
+    }
+}
+
+/// Clé normalisée : hôte en minuscules sans point final, empreinte en
+/// hexadécimal majuscule sans séparateurs.
+fn key(host: &str, fingerprint: &str) -> (String, String) {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let fingerprint = fingerprint
+        .chars()
+        .filter(char::is_ascii_hexdigit)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    (host, fingerprint)
+}
+
+/// Page d'avertissement : hôte, type d'erreur, empreinte, bouton « Go back »
+/// et lien d'acceptation pour la session.
+pub fn interstitial_html(pending: &PendingException, token: &str) -> String {
+    let kind = CertErrorKind::classify(&pending.report.reason);
+    pages::shell(
+        "Certificate error",
+        "h1 { color: #ff6a6a; }",
+        &format!(
+            r#"<h1>Your connection to {host} is not secure</h1>
+<p>{label}</p>
+<p class="detail">Error: <code>{reason}</code></p>
+<p class="detail">SHA-256 fingerprint: <code>{fingerprint}</code></p>
+<div class="actions">
+<button onclick="if (history.length > 2) history.go(-2); else location.replace('about:blank')">Go back</button>
+<a href="http://{DOMAIN}/accept?token={token}">Accept risk for this session</a>
+</div>"#,
+            host = html_escape(&pending.host),
+            label = kind.label(),
+            reason = html_escape(&pending.report.reason),
+            fingerprint = html_escape(&pending.fingerprint),
+            token = html_escape(token),
+        ),
+    )
+}
+
+/// Page qui répond « autoriser » à Servo (`chrome:allowcert`) puis recharge
+/// la page demandée.
+pub fn allow_html(report: &CertErrorReport) -> String {
+    pages::shell(
+        "Loading…",
+        "",
+        &format!(
+            r#"<script>
+const xhr = new XMLHttpRequest();
+xhr.open("POST", "chrome:allowcert");
+xhr.onloadend = () => location.replace({url});
+xhr.send({secret} + "&" + {certificate});
+</script>"#,
+            url = js_string(report.url.as_str()),
+            secret = js_string(&report.secret),
+            certificate = js_string(&report.certificate),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> CertErrorReport {
+        CertErrorReport {
+            url: Url::parse("https://Self-Signed.example:8443/login").unwrap(),
+            reason: "invalid peer certificate: UnknownIssuer".to_string(),
+            certificate: "AQID".to_string(),
+            secret: "1234".to_string(),
+        }
+    }
+
+    fn pending() -> PendingException {
+        let report = report();
+        PendingException {
+            host: report.host(),
+            fingerprint: report.fingerprint().unwrap(),
+            report,
+        }
+    }
+
+    #[test]
+    fn test_classify_reasons() {
+        assert_eq!(
+            CertErrorKind::classify("invalid peer certificate: Expired"),
+            CertErrorKind::Expired
+        );
+        assert_eq!(
+            CertErrorKind::classify("invalid peer certificate: UnknownIssuer"),
+            CertErrorKind::SelfSigned
+        );
+        assert_eq!(
+            CertErrorKind::classify("invalid peer certificate: NotValidForName"),
+            CertErrorKind::NameMismatch
+        );
+        assert_eq!(CertErrorKind::classify("BadEncoding"), CertErrorKind::Other);
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let mut url = Url::parse("http://suribrows.certerror/report").unwrap();
+        url.query_pairs_mut()
+            .append_pair("url", "https://self-signed.example:8443/login")
+            .append_pair("reason", "invalid peer certificate: UnknownIssuer")
+            .append_pair("cert", "AQID")
+            .append_pair("secret", "1234");
+        let report = CertErrorReport::parse(&url).unwrap();
+        assert_eq!(report.host(), "self-signed.example:8443");
+        assert_eq!(report.secret, "1234");
+        // SHA-256 de [1, 2, 3]
+        assert_eq!(
+            report.fingerprint().unwrap(),
+            "03:90:58:C6:F2:C0:CB:49:2C:53:3B:0A:4D:14:EF:77:\
+             CC:0F:78:AB:CC:CE:D5:28:7D:84:A1:A2:01:1C:FB:81"
+        );
+
+        let other = Url::parse("http://example.com/report?url=x&cert=y&secret=z").unwrap();
+        assert_eq!(CertErrorReport::parse(&other), None);
+    }
+
+    #[test]
+    fn test_exception_matches_host_and_fingerprint() {
+        let mut exceptions = CertExceptions::default();
+        let pending = pending();
+        let fingerprint = pending.fingerprint.clone();
+        assert!(!exceptions.is_allowed("self-signed.example:8443", &fingerprint));
+
+        let token = exceptions.propose(pending);
+        assert!(!exceptions.is_allowed("self-signed.example:8443", &fingerprint));
+        assert!(exceptions.accept(&token).is_some());
+
+        assert!(exceptions.is_allowed("self-signed.example:8443", &fingerprint));
+        // Hôte insensible à la casse, empreinte sans séparateurs
+        assert!(exceptions.is_allowed(
+            "SELF-SIGNED.Example:8443",
+            &fingerprint.replace(':', "").to_lowercase()
+        ));
+        // Un autre certificat ou un autre hôte redemande
+        assert!(!exceptions.is_allowed("self-signed.example:8443", "00:11"));
+        assert!(!exceptions.is_allowed("self-signed.example", &fingerprint));
+    }
+
+    #[test]
+    fn test_accept_requires_known_token() {
+        let mut exceptions = CertExceptions::default();
+        let token = exceptions.propose(pending());
+        assert_eq!(exceptions.accept("forged"), None);
+        assert!(exceptions.accept(&token).is_some());
+        // Un jeton ne sert qu'une fois
+        assert_eq!(exceptions.accept(&token), None);
+        assert_eq!(
+            accept_token(&Url::parse("http://suribrows.certerror/accept?token=abc").unwrap()),
+            Some("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pages_escape_report() {
+        let mut pending = pending();
+        pending.report.reason = "<script>".to_string();
+        let html = interstitial_html(&pending, "tok");
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("self-signed.example:8443"));
+        assert!(html.contains("/accept?token=tok"));
+
+        let mut report = report();
+        report.secret = "\"</script>".to_string();
+        let html = allow_html(&report);
+        assert!(!html.contains("\"</script>"));
+        assert!(html.contains("https://self-signed.example:8443/login"));
+    }
+}
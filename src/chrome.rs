@@ -1,18 +1,87 @@
 //! Rendu GPU du chrome navigateur (barre d'URL).
 //!
 //! Utilise `glow` pour les appels OpenGL et `fontdue` pour la rastérisation
-//! CPU des glyphes. Les glyphes sont pré-rendus dans un atlas texture au
-//! démarrage, puis dessinés comme des quads texturés à chaque frame.
+//! CPU des glyphes. Les glyphes ASCII sont pré-rendus dans un atlas texture au
+//! démarrage ; les autres caractères y sont ajoutés à la volée (voir
+//! [`crate::glyph_atlas`]), puis tout est dessiné comme des quads texturés.
 
-use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 
 use glow::HasContext;
+use url::Url;
+
+use crate::context_menu::ContextMenu;
+use crate::dialog::{JsDialog, PASSWORD_MASK};
+use crate::favicon::Favicon;
+use crate::find::FindBar;
+use crate::glyph_atlas::{AtlasUpdate, FONT_BYTES, GlyphAtlas};
+use crate::menu::{Menu, MenuItem};
 
 /// Hauteur du chrome en pixels physiques (default value, used by tests).
 pub const CHROME_HEIGHT: u32 = 40;
 
-const FONT_BYTES: &[u8] = include_bytes!("../resources/fonts/Inter-Regular.ttf");
+/// Caractère affiché à la place de la fin d'une URL trop longue.
+const ELLIPSIS: char = '…';
+const ELLIPSIS_STR: &str = "…";
+
+/// Largeur du curseur de saisie (pixels).
+const CURSOR_WIDTH: f32 = 2.0;
+
+/// Marge intérieure horizontale du badge adblock (pixels).
+const BADGE_PAD: f32 = 6.0;
+
+/// Espace entre le bouclier et le compteur (pixels).
+const BADGE_GAP: f32 = 4.0;
+
+/// Texte du badge sur une page exemptée de filtrage (règle `$document`).
+const BADGE_OFF_LABEL: &str = "off";
+
+/// Opacité du bouclier sur une page exemptée.
+const BADGE_OFF_ALPHA: f32 = 0.4;
+
+/// Indicateur barré au bout de la barre d'URL quand les scripts de la page
+/// sont bloqués (`[privacy] no_js_hosts`).
+const NO_JS_LABEL: &str = "JS";
+
+/// Marge intérieure de la bulle de statut (pixels).
+const STATUS_PAD: f32 = 6.0;
+
+/// Rayon des coins arrondis de la bulle de statut (pixels).
+const STATUS_RADIUS: f32 = 4.0;
+
+/// Nombre de segments par quart de cercle pour les coins arrondis.
+const CORNER_SEGMENTS: usize = 4;
+
+/// Côté du favicon dessiné dans la barre d'URL (pixels).
+const FAVICON_DRAW_SIZE: f32 = 16.0;
+
+/// Côté de l'indicateur de sécurité (cadenas / avertissement), en pixels.
+const SECURITY_ICON_SIZE: f32 = 14.0;
+
+/// Espace entre le favicon et le texte de l'URL (pixels).
+const FAVICON_GAP: f32 = 6.0;
+
+/// Marge horizontale du texte dans le menu déroulant (pixels).
+const MENU_PAD: f32 = 12.0;
+
+/// Marge verticale de chaque ligne du menu (pixels).
+const MENU_ROW_PAD: f32 = 6.0;
+
+/// Largeur minimale du menu déroulant (pixels).
+const MENU_MIN_WIDTH: f32 = 160.0;
+
+/// Largeur maximale de la barre de recherche dans la page (pixels).
+const FIND_WIDTH: f32 = 300.0;
+
+/// Marge entre la barre de recherche et les bords (fenêtre, chrome).
+const FIND_MARGIN: f32 = 8.0;
+
+/// Largeur maximale d'un dialogue JavaScript (pixels).
+const DIALOG_WIDTH: f32 = 480.0;
+
+/// Nombre de dents de l'icône d'engrenage.
+const GEAR_TEETH: usize = 8;
 
 /// Vertex shader GLES 300 es.
 const VERTEX_SHADER: &str = r#"#version 300 es
@@ -21,22 +90,49 @@ layout(location = 0) in vec2 a_position;
 layout(location = 1) in vec2 a_uv;
 uniform mat4 u_projection;
 out vec2 v_uv;
+out vec2 v_pos;
 void main() {
     gl_Position = u_projection * vec4(a_position, 0.0, 1.0);
     v_uv = a_uv;
+    v_pos = a_position;
 }
 "#;
 
 /// Fragment shader GLES 300 es.
+///
+/// Le chemin `u_sdf` dessine un rectangle arrondi par distance signée :
+/// `u_rect` (x, y, w, h) et `u_radius` en pixels fenêtre, bordure de
+/// `u_border_width` pixels en `u_border_color`, bords antialiasés sur 1 px.
 const FRAGMENT_SHADER: &str = r#"#version 300 es
 precision mediump float;
 in vec2 v_uv;
+in vec2 v_pos;
 uniform sampler2D u_texture;
 uniform vec4 u_color;
 uniform bool u_use_texture;
+uniform bool u_rgba;
+uniform bool u_sdf;
+uniform vec4 u_rect;
+uniform float u_radius;
+uniform float u_border_width;
+uniform vec4 u_border_color;
 out vec4 fragColor;
+float rounded_rect_distance(vec2 p, vec2 half_size, float radius) {
+    vec2 q = abs(p) - half_size + radius;
+    return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - radius;
+}
 void main() {
-    if (u_use_texture) {
+    if (u_sdf) {
+        vec2 half_size = u_rect.zw * 0.5;
+        float d = rounded_rect_distance(v_pos - (u_rect.xy + half_size), half_size, u_radius);
+        float outside = clamp(0.5 - d, 0.0, 1.0);
+        float inside = clamp(0.5 - (d + u_border_width), 0.0, 1.0);
+        vec4 color = mix(u_border_color, u_color, inside);
+        fragColor = vec4(color.rgb, color.a * outside);
+    } else if (u_rgba) {
+        vec4 texel = texture(u_texture, v_uv);
+        fragColor = vec4(texel.rgb, texel.a * u_color.a);
+    } else if (u_use_texture) {
         float alpha = texture(u_texture, v_uv).r;
         fragColor = vec4(u_color.rgb, u_color.a * alpha);
     } else {
@@ -45,112 +141,129 @@ void main() {
 }
 "#;
 
-/// Informations par glyphe dans l'atlas.
-struct GlyphInfo {
-    /// Position X dans l'atlas (pixels).
-    atlas_x: u32,
-    /// Position Y dans l'atlas (pixels).
-    atlas_y: u32,
-    /// Largeur du glyphe (pixels).
-    width: u32,
-    /// Hauteur du glyphe (pixels).
-    height: u32,
-    /// Avance horizontale (pixels).
-    advance_x: f32,
-    /// Offset X depuis la position de base.
-    offset_x: f32,
-    /// Offset Y depuis la ligne de base (positif = vers le haut).
-    offset_y: f32,
-}
-
-/// Atlas de glyphes pré-rendus.
-struct GlyphAtlas {
-    width: u32,
-    height: u32,
-    glyphs: HashMap<char, GlyphInfo>,
-    pixels: Vec<u8>,
-}
-
-impl GlyphAtlas {
-    fn build(font: &fontdue::Font, font_size: f32) -> Self {
-        let chars: Vec<char> = (32u8..=126).map(|b| b as char).collect();
-
-        // Premier passage : rastériser tous les glyphes pour calculer la taille
-        let mut rasterized: Vec<(char, fontdue::Metrics, Vec<u8>)> = Vec::new();
-        for &c in &chars {
-            let (metrics, bitmap) = font.rasterize(c, font_size);
-            rasterized.push((c, metrics, bitmap));
-        }
-
-        // Packing simple : rangées de gauche à droite
-        let atlas_width: u32 = 512;
-        let mut atlas_height: u32 = 64;
-        let mut glyphs = HashMap::new();
-
-        let mut x: u32 = 0;
-        let mut y: u32 = 0;
-        let mut row_height: u32 = 0;
-
-        for &(c, ref metrics, _) in &rasterized {
-            let w = metrics.width as u32;
-            let h = metrics.height as u32;
-
-            if x + w > atlas_width {
-                x = 0;
-                y += row_height + 1;
-                row_height = 0;
-            }
+/// Boutons cliquables du chrome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChromeButton {
+    /// Flèche circulaire (recharger), ou « × » (arrêter) pendant un chargement.
+    Reload,
+    /// Maison, à gauche de la barre : charge la page d'accueil.
+    Home,
+    /// Étoile dans la barre d'URL : ajoute/retire la page des favoris.
+    Bookmark,
+    /// Engrenage : ouvre la page de paramètres.
+    Settings,
+    /// « ≡ » : ouvre le menu déroulant.
+    Menu,
+    /// Indicateur de sécurité devant l'URL. Réservé au futur panneau
+    /// d'informations sur le site : le clic ne fait rien pour l'instant.
+    SiteInfo,
+}
 
-            if y + h > atlas_height {
-                atlas_height = (atlas_height * 2).max(y + h + 1);
-            }
+/// Élément du chrome sous un point, pour le dispatch des clics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChromeHit {
+    Button(ChromeButton),
+    /// Barre de saisie, hors de ses boutons.
+    UrlBar,
+    /// Badge adblock.
+    Badge,
+    /// Fond du chrome, hors de tout élément : fait office de barre de titre.
+    Background,
+}
 
-            row_height = row_height.max(h);
-
-            glyphs.insert(
-                c,
-                GlyphInfo {
-                    atlas_x: x,
-                    atlas_y: y,
-                    width: w,
-                    height: h,
-                    advance_x: metrics.advance_width,
-                    offset_x: metrics.xmin as f32,
-                    offset_y: metrics.ymin as f32,
-                },
-            );
+/// Niveau de sécurité de la page, affiché devant l'URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SiteSecurity {
+    /// Connexion chiffrée (https, wss) : cadenas.
+    Secure,
+    /// Connexion en clair (http, ws) — y compris après un downgrade
+    /// https → http : panneau d'avertissement.
+    Insecure,
+    /// Page locale ou interne (`data:`, `file:`, `about:`…) : pas d'indicateur.
+    #[default]
+    Internal,
+}
 
-            x += w + 1;
+impl SiteSecurity {
+    /// Déduit l'état depuis le schéma de l'URL. Les infos de certificat
+    /// viendront affiner `Secure` quand Servo les exposera.
+    pub fn for_url(url: &Url) -> Self {
+        match url.scheme() {
+            "https" | "wss" => Self::Secure,
+            "http" | "ws" => Self::Insecure,
+            _ => Self::Internal,
         }
+    }
+}
 
-        atlas_height = (y + row_height + 1).next_power_of_two().max(64);
+/// Données dynamiques d'une frame du chrome, fournies par `browser.rs`.
+pub struct ChromeFrame<'a> {
+    /// Texte affiché dans la barre d'URL.
+    pub url_text: &'a str,
+    /// `true` si la barre d'URL a le focus clavier.
+    pub is_focused: bool,
+    /// Position du curseur (en caractères) quand la barre est focusée.
+    pub cursor_char_offset: Option<usize>,
+    /// Caractères de `url_text` en cours de composition IME (soulignés).
+    pub preedit: Option<Range<usize>>,
+    /// Requêtes bloquées sur la page courante (`None` = adblock désactivé).
+    pub blocked_count: Option<u32>,
+    /// Cible du lien survolé, affichée en bas à gauche (`None` = pas de bulle).
+    pub status_text: Option<&'a str>,
+    /// Bouton sous le pointeur (surligné).
+    pub hovered_button: Option<ChromeButton>,
+    /// `true` si la page courante est dans les favoris (étoile pleine).
+    pub is_bookmarked: bool,
+    /// `true` pendant un chargement : le bouton recharger devient « × ».
+    pub is_loading: bool,
+    /// Indicateur affiché devant l'URL.
+    pub security: SiteSecurity,
+    /// Menu déroulant, dessiné par-dessus la webview s'il est ouvert.
+    pub menu: &'a Menu,
+    /// Barre de recherche dans la page, sous le chrome à droite si ouverte.
+    pub find: &'a FindBar,
+    /// Dialogue JavaScript en attente de réponse, centré sous le chrome.
+    pub dialog: Option<&'a JsDialog>,
+    /// Menu contextuel de la page (clic droit), à l'endroit du clic.
+    pub context_menu: Option<&'a ContextMenu>,
+    /// Téléchargement en cours, affiché en bas à droite (un clic l'annule).
+    pub download_text: Option<&'a str>,
+    /// `true` si les scripts de la page sont bloqués : « JS » barré au bout
+    /// de la barre d'URL.
+    pub scripts_blocked: bool,
+    /// `true` si la page est exemptée de filtrage par une règle `$document` :
+    /// badge « off », bouclier estompé.
+    pub adblock_excepted: bool,
+}
 
-        // Remplir le buffer de pixels
-        let mut pixels = vec![0u8; (atlas_width * atlas_height) as usize];
-        for (c, _metrics, bitmap) in &rasterized {
-            let info = &glyphs[c];
-            for row in 0..info.height {
-                for col in 0..info.width {
-                    let src_idx = (row * info.width + col) as usize;
-                    let dst_x = info.atlas_x + col;
-                    let dst_y = info.atlas_y + row;
-                    let dst_idx = (dst_y * atlas_width + dst_x) as usize;
-                    if src_idx < bitmap.len() && dst_idx < pixels.len() {
-                        pixels[dst_idx] = bitmap[src_idx];
-                    }
-                }
-            }
-        }
+/// Échec de création du [`ChromeRenderer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChromeError {
+    /// Le driver a refusé de compiler un shader (`stage` = vertex/fragment).
+    ShaderCompile { stage: &'static str, log: String },
+    /// L'édition de liens du programme a échoué.
+    ProgramLink(String),
+    /// Uniform introuvable après le link (optimisé par le driver ?).
+    MissingUniform(&'static str),
+    /// Création d'une ressource GL (texture, buffer…) ou de la police impossible.
+    Resource { what: &'static str, reason: String },
+}
 
-        Self {
-            width: atlas_width,
-            height: atlas_height,
-            glyphs,
-            pixels,
+impl std::fmt::Display for ChromeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ShaderCompile { stage, log } => {
+                write!(f, "{stage} shader compilation failed: {log}")
+            }
+            Self::ProgramLink(log) => write!(f, "shader program link failed: {log}"),
+            Self::MissingUniform(name) => write!(f, "uniform `{name}` not found"),
+            Self::Resource { what, reason } => write!(f, "cannot create {what}: {reason}"),
         }
     }
 }
 
+impl std::error::Error for ChromeError {}
+
 /// Renderer OpenGL pour le chrome du navigateur (barre d'URL).
 pub struct ChromeRenderer {
     gl: Arc<glow::Context>,
@@ -159,10 +272,34 @@ pub struct ChromeRenderer {
     vbo: glow::Buffer,
     atlas_texture: glow::Texture,
     atlas: GlyphAtlas,
+    /// Police conservée pour rastériser les glyphes manquants à la volée.
+    font: fontdue::Font,
     u_projection: glow::UniformLocation,
     u_color: glow::UniformLocation,
     u_use_texture: glow::UniformLocation,
+    u_rgba: glow::UniformLocation,
+    u_sdf: glow::UniformLocation,
+    u_rect: glow::UniformLocation,
+    u_radius: glow::UniformLocation,
+    u_border_width: glow::UniformLocation,
+    u_border_color: glow::UniformLocation,
     u_texture: glow::UniformLocation,
+    /// Texture RGBA du favicon de la page (`None` = globe par défaut).
+    favicon_texture: Option<glow::Texture>,
+    /// Génération ([`crate::favicon::FaviconStore`]) du favicon uploadé.
+    favicon_generation: u64,
+    /// Disposition de la dernière frame, pour le hit-test des clics.
+    layout: ChromeLayout,
+    /// Lignes du menu déroulant dans la dernière frame (vide s'il est fermé).
+    menu_rows: Vec<(MenuItem, ChromeRect)>,
+    /// Lignes du menu contextuel dans la dernière frame (vide s'il est fermé).
+    context_rows: Vec<ChromeRect>,
+    /// Indicateur de téléchargement à la dernière frame (`None` = absent).
+    download_rect: Option<ChromeRect>,
+    /// Texte de l'URL dessiné à la dernière frame, pour placer le curseur au clic.
+    url_hit: UrlTextHit,
+    /// Zone de la barre de recherche à la dernière frame (`None` = fermée).
+    find_rect: Option<ChromeRect>,
     // Runtime theme values (from config)
     bg_color: [f32; 4],
     bg_focused_color: [f32; 4],
@@ -170,9 +307,13 @@ pub struct ChromeRenderer {
     cursor_color: [f32; 4],
     bar_bg_color: [f32; 4],
     bar_border_color: [f32; 4],
+    badge_color: [f32; 4],
+    secure_color: [f32; 4],
+    insecure_color: [f32; 4],
     text_left_pad: f32,
     bar_margin: f32,
     bar_h_pad: f32,
+    corner_radius: f32,
     chrome_height: u32,
     font_size: f32,
 }
@@ -181,44 +322,70 @@ pub struct ChromeRenderer {
 impl ChromeRenderer {
     /// Crée le renderer. Doit être appelé avec un contexte GL actif.
     ///
+    /// Échoue (sans paniquer) si le driver refuse un shader ou une ressource
+    /// GL : l'appelant peut alors continuer sans chrome.
+    ///
     /// # Safety
     /// Appelle des fonctions OpenGL.
-    pub unsafe fn new(gl: Arc<glow::Context>, config: &crate::config::ChromeConfig) -> Self {
+    pub unsafe fn new(
+        gl: Arc<glow::Context>,
+        config: &crate::config::ChromeConfig,
+    ) -> Result<Self, ChromeError> {
         // ── Compiler les shaders ─────────────────────────────────────────
-        let vs = gl.create_shader(glow::VERTEX_SHADER).unwrap();
-        gl.shader_source(vs, VERTEX_SHADER);
-        gl.compile_shader(vs);
-        if !gl.get_shader_compile_status(vs) {
-            panic!("Vertex shader error: {}", gl.get_shader_info_log(vs));
-        }
-
-        let fs = gl.create_shader(glow::FRAGMENT_SHADER).unwrap();
-        gl.shader_source(fs, FRAGMENT_SHADER);
-        gl.compile_shader(fs);
-        if !gl.get_shader_compile_status(fs) {
-            panic!("Fragment shader error: {}", gl.get_shader_info_log(fs));
-        }
+        let vs = compile_shader(&gl, "vertex", glow::VERTEX_SHADER, VERTEX_SHADER)?;
+        let fs = match compile_shader(&gl, "fragment", glow::FRAGMENT_SHADER, FRAGMENT_SHADER) {
+            Ok(fs) => fs,
+            Err(e) => {
+                gl.delete_shader(vs);
+                return Err(e);
+            }
+        };
 
-        let program = gl.create_program().unwrap();
+        let program = gl
+            .create_program()
+            .map_err(|reason| ChromeError::Resource {
+                what: "program",
+                reason,
+            })?;
         gl.attach_shader(program, vs);
         gl.attach_shader(program, fs);
         gl.link_program(program);
-        if !gl.get_program_link_status(program) {
-            panic!("Shader link error: {}", gl.get_program_info_log(program));
-        }
         gl.delete_shader(vs);
         gl.delete_shader(fs);
+        if !gl.get_program_link_status(program) {
+            let log = gl.get_program_info_log(program);
+            gl.delete_program(program);
+            return Err(ChromeError::ProgramLink(log));
+        }
 
-        let u_projection = gl.get_uniform_location(program, "u_projection").unwrap();
-        let u_color = gl.get_uniform_location(program, "u_color").unwrap();
-        let u_use_texture = gl.get_uniform_location(program, "u_use_texture").unwrap();
-        let u_texture = gl.get_uniform_location(program, "u_texture").unwrap();
+        let uniform = |name: &'static str| {
+            gl.get_uniform_location(program, name)
+                .ok_or(ChromeError::MissingUniform(name))
+        };
+        let u_projection = uniform("u_projection")?;
+        let u_color = uniform("u_color")?;
+        let u_use_texture = uniform("u_use_texture")?;
+        let u_rgba = uniform("u_rgba")?;
+        let u_sdf = uniform("u_sdf")?;
+        let u_rect = uniform("u_rect")?;
+        let u_radius = uniform("u_radius")?;
+        let u_border_width = uniform("u_border_width")?;
+        let u_border_color = uniform("u_border_color")?;
+        let u_texture = uniform("u_texture")?;
 
         // ── VAO / VBO ────────────────────────────────────────────────────
-        let vao = gl.create_vertex_array().unwrap();
+        let vao = gl
+            .create_vertex_array()
+            .map_err(|reason| ChromeError::Resource {
+                what: "vertex array",
+                reason,
+            })?;
         gl.bind_vertex_array(Some(vao));
 
-        let vbo = gl.create_buffer().unwrap();
+        let vbo = gl.create_buffer().map_err(|reason| ChromeError::Resource {
+            what: "vertex buffer",
+            reason,
+        })?;
         gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
 
         // Vertex layout: [x, y, u, v] x 6 vertices (2 triangles)
@@ -241,11 +408,19 @@ impl ChromeRenderer {
 
         // ── Atlas de glyphes ─────────────────────────────────────────────
         let font = fontdue::Font::from_bytes(FONT_BYTES, fontdue::FontSettings::default())
-            .expect("Impossible de charger la police Inter");
+            .map_err(|reason| ChromeError::Resource {
+                what: "font",
+                reason: reason.to_string(),
+            })?;
 
         let atlas = GlyphAtlas::build(&font, config.font_size);
 
-        let atlas_texture = gl.create_texture().unwrap();
+        let atlas_texture = gl
+            .create_texture()
+            .map_err(|reason| ChromeError::Resource {
+                what: "glyph atlas texture",
+                reason,
+            })?;
         gl.bind_texture(glow::TEXTURE_2D, Some(atlas_texture));
         gl.tex_parameter_i32(
             glow::TEXTURE_2D,
@@ -267,56 +442,245 @@ impl ChromeRenderer {
             glow::TEXTURE_WRAP_T,
             glow::CLAMP_TO_EDGE as i32,
         );
-        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
-        gl.tex_image_2d(
-            glow::TEXTURE_2D,
-            0,
-            glow::R8 as i32,
-            atlas.width as i32,
-            atlas.height as i32,
-            0,
-            glow::RED,
-            glow::UNSIGNED_BYTE,
-            glow::PixelUnpackData::Slice(Some(&atlas.pixels)),
-        );
+        upload_atlas(&gl, &atlas);
 
-        Self {
+        Ok(Self {
             gl,
             program,
             vao,
             vbo,
             atlas_texture,
             atlas,
+            font,
             u_projection,
             u_color,
             u_use_texture,
+            u_rgba,
+            u_sdf,
+            u_rect,
+            u_radius,
+            u_border_width,
+            u_border_color,
             u_texture,
+            favicon_texture: None,
+            favicon_generation: 0,
+            layout: ChromeLayout::default(),
+            menu_rows: Vec::new(),
+            context_rows: Vec::new(),
+            download_rect: None,
+            url_hit: UrlTextHit::default(),
+            find_rect: None,
             bg_color: config.colors.background,
             bg_focused_color: config.colors.background_focused,
             text_color: config.colors.text,
             cursor_color: config.colors.cursor,
             bar_bg_color: config.colors.bar_background,
             bar_border_color: config.colors.bar_border,
+            badge_color: config.colors.badge,
+            secure_color: config.colors.secure,
+            insecure_color: config.colors.insecure,
             text_left_pad: config.text_left_pad,
             bar_margin: config.bar_margin,
             bar_h_pad: config.bar_h_pad,
+            corner_radius: config.corner_radius,
             chrome_height: config.height,
             font_size: config.font_size,
+        })
+    }
+
+    /// Remplace la palette du chrome (changement de thème OS, hot-reload).
+    /// Prend effet à la prochaine frame.
+    pub fn set_colors(&mut self, colors: &crate::config::ChromeColors) {
+        self.bg_color = colors.background;
+        self.bg_focused_color = colors.background_focused;
+        self.text_color = colors.text;
+        self.cursor_color = colors.cursor;
+        self.bar_bg_color = colors.bar_background;
+        self.bar_border_color = colors.bar_border;
+        self.badge_color = colors.badge;
+        self.secure_color = colors.secure;
+        self.insecure_color = colors.insecure;
+    }
+
+    /// Marges et arrondi de la barre (hot-reload). Prend effet à la
+    /// prochaine frame ; la taille du texte passe par [`Self::set_font_size`].
+    pub fn set_spacing(&mut self, config: &crate::config::ChromeConfig) {
+        self.text_left_pad = config.text_left_pad;
+        self.bar_margin = config.bar_margin;
+        self.bar_h_pad = config.bar_h_pad;
+        self.corner_radius = config.corner_radius;
+    }
+
+    /// Taille du texte du chrome.
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// Change la taille du texte : l'atlas de glyphes est reconstruit.
+    ///
+    /// # Safety
+    /// Appelle des fonctions OpenGL ; le contexte de la fenêtre doit être actif.
+    pub unsafe fn set_font_size(&mut self, font_size: f32) {
+        self.font_size = font_size;
+        self.atlas = GlyphAtlas::build(&self.font, font_size);
+        self.gl
+            .bind_texture(glow::TEXTURE_2D, Some(self.atlas_texture));
+        upload_atlas(&self.gl, &self.atlas);
+    }
+
+    /// Génération du favicon actuellement dans la texture.
+    pub fn favicon_generation(&self) -> u64 {
+        self.favicon_generation
+    }
+
+    /// Remplace le favicon affiché dans la barre (`None` = globe par défaut).
+    ///
+    /// # Safety
+    /// Appelle des fonctions OpenGL ; le contexte de la fenêtre doit être actif.
+    pub unsafe fn set_favicon(&mut self, favicon: Option<&Favicon>, generation: u64) {
+        self.favicon_generation = generation;
+        let Some(favicon) = favicon else {
+            if let Some(texture) = self.favicon_texture.take() {
+                self.gl.delete_texture(texture);
+            }
+            return;
+        };
+
+        let texture = match self.favicon_texture {
+            Some(texture) => texture,
+            None => {
+                let Ok(texture) = self.gl.create_texture() else {
+                    return;
+                };
+                self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                for (param, value) in [
+                    (glow::TEXTURE_MIN_FILTER, glow::LINEAR),
+                    (glow::TEXTURE_MAG_FILTER, glow::LINEAR),
+                    (glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+                    (glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+                ] {
+                    self.gl
+                        .tex_parameter_i32(glow::TEXTURE_2D, param, value as i32);
+                }
+                self.favicon_texture = Some(texture);
+                texture
+            }
+        };
+
+        self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        self.gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+        self.gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            favicon.width as i32,
+            favicon.height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(Some(&favicon.rgba)),
+        );
+    }
+
+    /// Ajoute à l'atlas les caractères de `text` qui n'y sont pas encore,
+    /// et répercute les changements sur la texture GL.
+    ///
+    /// # Safety
+    /// Appelle des fonctions OpenGL.
+    unsafe fn ensure_glyphs(&mut self, text: &str) {
+        let mut resized = false;
+        for c in text.chars() {
+            match self.atlas.ensure_glyph(&self.font, c) {
+                AtlasUpdate::None => {}
+                AtlasUpdate::Resized => resized = true,
+                AtlasUpdate::Region {
+                    x,
+                    y,
+                    width,
+                    height,
+                } if !resized => {
+                    let pixels = self.atlas.region_pixels(x, y, width, height);
+                    self.gl
+                        .bind_texture(glow::TEXTURE_2D, Some(self.atlas_texture));
+                    self.gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+                    self.gl.tex_sub_image_2d(
+                        glow::TEXTURE_2D,
+                        0,
+                        x as i32,
+                        y as i32,
+                        width as i32,
+                        height as i32,
+                        glow::RED,
+                        glow::UNSIGNED_BYTE,
+                        glow::PixelUnpackData::Slice(Some(&pixels)),
+                    );
+                }
+                // Un ré-upload complet est déjà prévu : inutile d'uploader la région.
+                AtlasUpdate::Region { .. } => {}
+            }
+        }
+        if resized {
+            self.gl
+                .bind_texture(glow::TEXTURE_2D, Some(self.atlas_texture));
+            upload_atlas(&self.gl, &self.atlas);
         }
     }
 
-    /// Dessine la barre d'URL.
+    /// Dessine la barre d'URL et le badge de blocage.
     ///
     /// # Safety
     /// Appelle des fonctions OpenGL.
-    pub unsafe fn draw(
-        &self,
-        window_width: u32,
-        window_height: u32,
-        url_text: &str,
-        is_focused: bool,
-        cursor_char_offset: Option<usize>,
-    ) {
+    pub unsafe fn draw(&mut self, window_width: u32, window_height: u32, frame: &ChromeFrame) {
+        let url_text = frame.url_text;
+        let is_focused = frame.is_focused;
+        let cursor_char_offset = frame.cursor_char_offset;
+        let badge_text = badge_text(frame.blocked_count, frame.adblock_excepted);
+
+        // Rastériser les caractères inconnus avant de dessiner quoi que ce soit.
+        self.ensure_glyphs(url_text);
+        self.ensure_glyphs(ELLIPSIS_STR);
+        if frame.scripts_blocked {
+            self.ensure_glyphs(NO_JS_LABEL);
+        }
+        if let Some(ref label) = badge_text {
+            self.ensure_glyphs(label);
+        }
+        let status_text = frame.status_text.filter(|text| !text.is_empty());
+        if let Some(text) = status_text {
+            self.ensure_glyphs(text);
+        }
+        if let Some(text) = frame.download_text {
+            self.ensure_glyphs(text);
+        }
+        if frame.menu.is_open() {
+            for item in MenuItem::ALL {
+                self.ensure_glyphs(item.label());
+            }
+        }
+        if let Some(menu) = frame.context_menu {
+            for (action, _) in menu.items() {
+                self.ensure_glyphs(action.label());
+            }
+        }
+        let find_counter = frame.find.matches().map(|m| m.label());
+        if frame.find.is_open() {
+            self.ensure_glyphs(frame.find.input().text());
+            self.ensure_glyphs(find_counter.as_deref().unwrap_or_default());
+        }
+        let dialog_lines = frame.dialog.map(JsDialog::message_lines);
+        if let (Some(dialog), Some(lines)) = (frame.dialog, &dialog_lines) {
+            for line in lines {
+                self.ensure_glyphs(line);
+            }
+            self.ensure_glyphs(dialog.hint());
+            if let Some(input) = dialog.input() {
+                self.ensure_glyphs(input.text());
+            }
+            if dialog.password().is_some() {
+                self.ensure_glyphs(&PASSWORD_MASK.to_string());
+            }
+        }
+
         let gl = &self.gl;
         let w = window_width as f32;
         let h = window_height as f32;
@@ -349,6 +713,8 @@ impl ChromeRenderer {
         ];
         gl.uniform_matrix_4_f32_slice(Some(&self.u_projection), false, &projection);
         gl.uniform_1_i32(Some(&self.u_texture), 0);
+        gl.uniform_1_i32(Some(&self.u_rgba), 0);
+        gl.uniform_1_i32(Some(&self.u_sdf), 0);
 
         gl.bind_vertex_array(Some(self.vao));
 
@@ -360,85 +726,225 @@ impl ChromeRenderer {
         };
         self.draw_rect(0.0, 0.0, w, ch, bg);
 
+        // Boutons et badge occupent l'extrémité droite ; la barre prend le reste.
+        let shield_w = (self.font_size * 0.75).round();
+        let badge_w = badge_text.as_deref().map_or(0.0, |label| {
+            let text_w: f32 = label.chars().map(|c| self.advance_of(c)).sum();
+            BADGE_PAD * 2.0 + shield_w + BADGE_GAP + text_w
+        });
+        let mut chrome_layout = layout_chrome(
+            w,
+            ch,
+            self.bar_margin,
+            badge_w,
+            &[ChromeButton::Reload, ChromeButton::Home],
+            &[ChromeButton::Bookmark],
+            &[ChromeButton::Settings, ChromeButton::Menu],
+        );
+
         // ── 2. Barre de saisie (input field) ─────────────────────────────
-        let bar_x = self.bar_margin;
-        let bar_y = self.bar_margin;
-        let bar_w = w - self.bar_margin * 2.0;
-        let bar_h = ch - self.bar_margin * 2.0;
-
-        // Bordure
-        self.draw_rect(bar_x, bar_y, bar_w, bar_h, self.bar_border_color);
-        // Fond intérieur
-        self.draw_rect(
-            bar_x + 1.0,
-            bar_y + 1.0,
-            bar_w - 2.0,
-            bar_h - 2.0,
+        let bar = chrome_layout.bar;
+
+        // Fond + bordure 1 px, coins arrondis antialiasés
+        self.draw_sdf_rounded_rect(
+            bar,
+            self.corner_radius,
             self.bar_bg_color,
+            self.bar_border_color,
+            1.0,
         );
 
-        // ── 3. Texte de l'URL ────────────────────────────────────────────
-        let text_x = bar_x + self.bar_h_pad + self.text_left_pad;
+        // ── 3. Indicateur de sécurité, puis favicon (ou globe par défaut) ─
+        let mut icon_x = bar.x + self.bar_h_pad + self.text_left_pad;
+        let site_info =
+            (frame.security != SiteSecurity::Internal).then(|| security_icon_rect(icon_x, ch));
+        if let Some(rect) = site_info {
+            match frame.security {
+                SiteSecurity::Secure => {
+                    self.draw_padlock(rect, self.secure_color, self.bar_bg_color)
+                }
+                _ => self.draw_warning(rect, self.insecure_color, self.bar_bg_color),
+            }
+            icon_x += SECURITY_ICON_SIZE + FAVICON_GAP;
+        }
+        let icon_y = ((ch - FAVICON_DRAW_SIZE) / 2.0).round();
+        gl.active_texture(glow::TEXTURE0);
+        match self.favicon_texture {
+            Some(texture) => {
+                gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                self.draw_image(icon_x, icon_y, FAVICON_DRAW_SIZE, FAVICON_DRAW_SIZE);
+            }
+            None => self.draw_globe(icon_x, icon_y, FAVICON_DRAW_SIZE),
+        }
+
+        // ── 4. Texte de l'URL ────────────────────────────────────────────
+        let text_x = icon_x + FAVICON_DRAW_SIZE + FAVICON_GAP;
         // Centrer verticalement : baseline ≈ milieu du chrome
         let text_baseline_y = ch / 2.0 + self.font_size / 3.0;
 
-        gl.active_texture(glow::TEXTURE0);
         gl.bind_texture(glow::TEXTURE_2D, Some(self.atlas_texture));
 
+        let mut max_text_x = chrome_layout.text_end - self.bar_h_pad;
+        // « JS » barré calé à droite : l'URL s'arrête avant
+        let no_js_label = frame.scripts_blocked.then(|| {
+            let label_w: f32 = NO_JS_LABEL.chars().map(|c| self.advance_of(c)).sum();
+            let label_x = max_text_x - label_w;
+            max_text_x = label_x - FAVICON_GAP;
+            (label_x, label_w)
+        });
+        let advances = self.kerned_advances(url_text);
+        let layout = layout_text(
+            &advances,
+            max_text_x - text_x,
+            self.advance_of(ELLIPSIS),
+            cursor_char_offset.filter(|_| is_focused),
+        );
+
         let mut pen_x = text_x;
-        let max_text_x = bar_x + bar_w - self.bar_h_pad;
-        let mut cursor_x: Option<f32> = None;
+        for (c, advance) in url_text
+            .chars()
+            .zip(&advances)
+            .skip(layout.start)
+            .take(layout.end - layout.start)
+        {
+            self.draw_glyph(c, pen_x, text_baseline_y);
+            pen_x += advance;
+        }
+        if layout.ellipsis {
+            self.draw_glyph(ELLIPSIS, pen_x, text_baseline_y);
+        }
+        // Composition IME soulignée (partie visible seulement)
+        if let Some(preedit) = &frame.preedit {
+            let start = preedit.start.clamp(layout.start, layout.end);
+            let end = preedit.end.clamp(layout.start, layout.end);
+            if start < end {
+                let x = text_x + advances[layout.start..start].iter().sum::<f32>();
+                let w: f32 = advances[start..end].iter().sum();
+                self.draw_rect(x, text_baseline_y + 2.0, w, 1.0, self.text_color);
+            }
+        }
+        if let Some((label_x, label_w)) = no_js_label {
+            let mut pen_x = label_x;
+            for c in NO_JS_LABEL.chars() {
+                pen_x += self.draw_glyph(c, pen_x, text_baseline_y);
+            }
+            let strike_y = (text_baseline_y - self.font_size * 0.3).round();
+            self.draw_rect(
+                label_x - 1.0,
+                strike_y,
+                label_w + 2.0,
+                1.5,
+                self.insecure_color,
+            );
+        }
+        self.url_hit = UrlTextHit {
+            x: text_x,
+            start: layout.start,
+            advances: advances[layout.start..layout.end].to_vec(),
+        };
 
-        // Si le curseur est au début
-        if cursor_char_offset == Some(0) {
-            cursor_x = Some(pen_x);
+        let cursor_x = cursor_char_offset
+            .filter(|&offset| offset >= layout.start && offset <= layout.end)
+            .map(|offset| text_x + advances[layout.start..offset].iter().sum::<f32>());
+
+        // ── 5. Curseur (si focusé) ───────────────────────────────────────
+        if is_focused && let Some(cx) = cursor_x {
+            let cursor_h = self.font_size + 4.0;
+            let cursor_y = (ch - cursor_h) / 2.0;
+            self.draw_rect(cx, cursor_y, CURSOR_WIDTH, cursor_h, self.cursor_color);
         }
 
-        for (char_idx, c) in url_text.chars().enumerate() {
-            if pen_x > max_text_x {
-                break;
+        // ── 6. Badge adblock (bouclier + compteur) ───────────────────────
+        if let (Some(label), Some(badge)) = (&badge_text, chrome_layout.badge) {
+            let shield_x = badge.x + BADGE_PAD;
+            let shield_h = (self.font_size * 0.9).round();
+            let shield_y = (ch - shield_h) / 2.0;
+            let mut shield_color = self.badge_color;
+            if frame.adblock_excepted {
+                shield_color[3] *= BADGE_OFF_ALPHA;
             }
+            self.draw_shield(shield_x, shield_y, shield_w, shield_h, shield_color);
 
-            if let Some(glyph) = self.atlas.glyphs.get(&c) {
-                if glyph.width > 0 && glyph.height > 0 {
-                    let gx = pen_x + glyph.offset_x;
-                    // offset_y from fontdue is the bottom edge relative to baseline
-                    // We need to position from top-left
-                    let gy = text_baseline_y - glyph.offset_y - glyph.height as f32;
-
-                    self.draw_textured_rect(
-                        gx,
-                        gy,
-                        glyph.width as f32,
-                        glyph.height as f32,
-                        glyph.atlas_x,
-                        glyph.atlas_y,
-                        glyph.width,
-                        glyph.height,
-                    );
-                }
-                pen_x += glyph.advance_x;
-            } else {
-                // Caractère non présent dans l'atlas — avancer d'un espace
-                if let Some(space) = self.atlas.glyphs.get(&' ') {
-                    pen_x += space.advance_x;
-                } else {
-                    pen_x += self.font_size * 0.5;
-                }
+            let mut pen_x = shield_x + shield_w + BADGE_GAP;
+            for c in label.chars() {
+                pen_x += self.draw_glyph(c, pen_x, text_baseline_y);
             }
+        }
 
-            // Vérifier si le curseur est après ce caractère
-            if cursor_char_offset == Some(char_idx + 1) {
-                cursor_x = Some(pen_x);
+        // ── 7. Boutons (favori, réglages, …) ──────────────────────────────────
+        for &(button, rect) in &chrome_layout.buttons {
+            let hovered = frame.hovered_button == Some(button);
+            let backdrop = if hovered { self.bar_border_color } else { bg };
+            if hovered {
+                self.draw_rounded_rect(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    STATUS_RADIUS,
+                    self.bar_border_color,
+                );
+            }
+            match button {
+                ChromeButton::Bookmark => {
+                    self.draw_star(rect, frame.is_bookmarked, backdrop);
+                }
+                ChromeButton::Reload if frame.is_loading => self.draw_cross(rect, self.text_color),
+                ChromeButton::Reload => self.draw_reload(rect, self.text_color),
+                ChromeButton::Home => self.draw_house(rect, self.text_color, backdrop),
+                ChromeButton::Settings => self.draw_gear(rect, self.text_color, backdrop),
+                ChromeButton::Menu => self.draw_hamburger(rect, self.text_color),
+                // Dessiné à l'étape 3, avec l'URL
+                ChromeButton::SiteInfo => {}
             }
         }
+        if let Some(rect) = site_info {
+            chrome_layout.buttons.push((ChromeButton::SiteInfo, rect));
+        }
 
-        // ── 4. Curseur (si focusé) ───────────────────────────────────────
-        if is_focused && let Some(cx) = cursor_x {
-            let cursor_h = self.font_size + 4.0;
-            let cursor_y = (ch - cursor_h) / 2.0;
-            self.draw_rect(cx, cursor_y, 2.0, cursor_h, self.cursor_color);
+        // ── 8. Barre de recherche dans la page, sous le chrome à droite ──
+        self.find_rect = frame
+            .find
+            .is_open()
+            .then(|| find_bar_rect(w, ch, self.font_size));
+        if let Some(rect) = self.find_rect {
+            self.draw_find_bar(frame.find, find_counter.as_deref(), rect);
+        }
+
+        // ── 8a. Dialogue JavaScript, centré sous le chrome ──────────────
+        if let (Some(dialog), Some(lines)) = (frame.dialog, &dialog_lines) {
+            self.draw_dialog(dialog, lines, w, ch);
+        }
+
+        // ── 8b. Menu déroulant, sous le bouton « ≡ » ─────────────────────
+        let menu_anchor = chrome_layout
+            .buttons
+            .iter()
+            .find(|(button, _)| *button == ChromeButton::Menu)
+            .map(|&(_, rect)| rect);
+        if frame.menu.is_open()
+            && let Some(anchor) = menu_anchor
+        {
+            self.menu_rows = self.draw_menu(frame.menu, anchor, w, ch, text_baseline_y - ch / 2.0);
+        } else {
+            self.menu_rows.clear();
+        }
+
+        // ── 8c. Menu contextuel, à l'endroit du clic droit ───────────────
+        self.context_rows = match frame.context_menu {
+            Some(menu) => self.draw_context_menu(menu, w, h, text_baseline_y - ch / 2.0),
+            None => Vec::new(),
+        };
+        self.layout = chrome_layout;
+
+        // ── 9. Bulle de statut (lien survolé), en bas à gauche ───────────
+        if let Some(text) = status_text {
+            self.draw_status_bubble(text, w, h, false);
         }
+        // ── 10. Téléchargement en cours, en bas à droite ─────────────────
+        self.download_rect = frame
+            .download_text
+            .and_then(|text| self.draw_status_bubble(text, w, h, true));
 
         // ── Restaurer l'état GL ──────────────────────────────────────────
         gl.bind_vertex_array(None);
@@ -455,6 +961,431 @@ impl ChromeRenderer {
         }
     }
 
+    /// Dessine la barre de recherche : champ de saisie, curseur s'il a le
+    /// focus, et compteur d'occurrences (« 3/17 ») aligné à droite.
+    unsafe fn draw_find_bar(&self, find: &FindBar, counter: Option<&str>, rect: ChromeRect) {
+        self.draw_sdf_rounded_rect(
+            rect,
+            self.corner_radius,
+            self.bar_bg_color,
+            self.bar_border_color,
+            1.0,
+        );
+        let baseline = rect.y + rect.h / 2.0 + self.font_size / 3.0;
+
+        // Compteur à droite ; le texte prend la place restante.
+        let counter_w = counter.map_or(0.0, |label| {
+            label.chars().map(|c| self.advance_of(c)).sum::<f32>()
+        });
+        let text_x = rect.x + STATUS_PAD * 2.0;
+        let text_end = rect.x + rect.w - STATUS_PAD * 2.0 - counter_w - BADGE_GAP;
+        if let Some(label) = counter {
+            let mut pen_x = rect.x + rect.w - STATUS_PAD * 2.0 - counter_w;
+            for c in label.chars() {
+                pen_x += self.draw_glyph(c, pen_x, baseline);
+            }
+        }
+
+        let cursor = find.is_focused().then(|| find.input().cursor_char_offset());
+        self.draw_text_line(
+            find.input().text(),
+            text_x,
+            baseline,
+            text_end - text_x,
+            cursor,
+        );
+    }
+
+    /// Dessine un dialogue JavaScript : message, champs de saisie (`prompt`,
+    /// identifiants) et rappel des touches. Le mot de passe est masqué.
+    unsafe fn draw_dialog(&self, dialog: &JsDialog, lines: &[String], w: f32, chrome_h: f32) {
+        let password_focused = dialog.is_password_focused();
+        let mut fields = Vec::new();
+        if let Some(input) = dialog.input() {
+            let cursor = (!password_focused).then(|| input.cursor_char_offset());
+            fields.push((input.text().to_string(), cursor));
+        }
+        if let Some(password) = dialog.password() {
+            let masked = PASSWORD_MASK
+                .to_string()
+                .repeat(password.text().chars().count());
+            let cursor = password_focused.then(|| password.cursor_char_offset());
+            fields.push((masked, cursor));
+        }
+        let rows = lines.len() + 1 + fields.len();
+        let rect = dialog_rect(w, chrome_h, self.font_size, rows);
+        if rect.w <= STATUS_PAD * 4.0 {
+            return;
+        }
+        self.draw_sdf_rounded_rect(
+            rect,
+            self.corner_radius,
+            self.bar_bg_color,
+            self.bar_border_color,
+            1.0,
+        );
+        let line_h = dialog_line_height(self.font_size);
+        let text_x = rect.x + STATUS_PAD * 2.0;
+        let text_w = rect.w - STATUS_PAD * 4.0;
+        let baseline_offset = line_h / 2.0 + self.font_size / 3.0;
+        let mut row_y = rect.y + STATUS_PAD * 2.0;
+
+        for line in lines {
+            self.draw_text_line(line, text_x, row_y + baseline_offset, text_w, None);
+            row_y += line_h;
+        }
+        for (text, cursor) in &fields {
+            let field = ChromeRect {
+                x: text_x - STATUS_PAD,
+                y: row_y,
+                w: text_w + STATUS_PAD * 2.0,
+                h: line_h,
+            };
+            self.draw_sdf_rounded_rect(
+                field,
+                self.corner_radius,
+                self.bg_focused_color,
+                self.bar_border_color,
+                1.0,
+            );
+            self.draw_text_line(text, text_x, row_y + baseline_offset, text_w, *cursor);
+            row_y += line_h;
+        }
+        self.draw_text_line(dialog.hint(), text_x, row_y + baseline_offset, text_w, None);
+    }
+
+    /// Dessine `text` sur une ligne à partir de `x`, tronqué par « … » au-delà
+    /// de `available` pixels, avec le curseur de saisie s'il est donné.
+    unsafe fn draw_text_line(
+        &self,
+        text: &str,
+        text_x: f32,
+        baseline: f32,
+        available: f32,
+        cursor: Option<usize>,
+    ) {
+        let advances = self.kerned_advances(text);
+        let layout = layout_text(
+            &advances,
+            available.max(0.0),
+            self.advance_of(ELLIPSIS),
+            cursor,
+        );
+        let mut pen_x = text_x;
+        for (c, advance) in text
+            .chars()
+            .zip(&advances)
+            .skip(layout.start)
+            .take(layout.end - layout.start)
+        {
+            self.draw_glyph(c, pen_x, baseline);
+            pen_x += advance;
+        }
+        if layout.ellipsis {
+            self.draw_glyph(ELLIPSIS, pen_x, baseline);
+        }
+
+        if let Some(offset) = cursor.filter(|&o| o >= layout.start && o <= layout.end) {
+            let cursor_x = text_x + advances[layout.start..offset].iter().sum::<f32>();
+            let cursor_h = self.font_size + 4.0;
+            self.draw_rect(
+                cursor_x,
+                baseline - self.font_size / 3.0 - cursor_h / 2.0,
+                CURSOR_WIDTH,
+                cursor_h,
+                self.cursor_color,
+            );
+        }
+    }
+
+    /// Dessine une bulle ancrée en bas à gauche de la fenêtre (`right` : à
+    /// droite), par-dessus la webview, et retourne sa zone. Les textes trop
+    /// longs finissent par « … ».
+    unsafe fn draw_status_bubble(
+        &self,
+        text: &str,
+        w: f32,
+        h: f32,
+        right: bool,
+    ) -> Option<ChromeRect> {
+        let advances = self.kerned_advances(text);
+        let text_w: f32 = advances.iter().sum();
+        let (x, y, bubble_w, bubble_h) =
+            status_bubble_rect(w, h, text_w, self.font_size, self.chrome_height as f32);
+        if bubble_w <= STATUS_PAD * 2.0 {
+            return None;
+        }
+        let x = if right { w - x - bubble_w } else { x };
+
+        self.draw_rounded_rect(
+            x,
+            y,
+            bubble_w,
+            bubble_h,
+            STATUS_RADIUS,
+            self.bar_border_color,
+        );
+        self.draw_rounded_rect(
+            x + 1.0,
+            y + 1.0,
+            bubble_w - 2.0,
+            bubble_h - 2.0,
+            STATUS_RADIUS - 1.0,
+            self.bar_bg_color,
+        );
+
+        let layout = layout_text(
+            &advances,
+            bubble_w - STATUS_PAD * 2.0,
+            self.advance_of(ELLIPSIS),
+            None,
+        );
+        let baseline = y + bubble_h / 2.0 + self.font_size / 3.0;
+        let mut pen_x = x + STATUS_PAD;
+        for (c, advance) in text
+            .chars()
+            .zip(&advances)
+            .skip(layout.start)
+            .take(layout.end - layout.start)
+        {
+            self.draw_glyph(c, pen_x, baseline);
+            pen_x += advance;
+        }
+        if layout.ellipsis {
+            self.draw_glyph(ELLIPSIS, pen_x, baseline);
+        }
+        Some(ChromeRect {
+            x,
+            y,
+            w: bubble_w,
+            h: bubble_h,
+        })
+    }
+
+    /// Bouton du chrome sous le point `(x, y)` (pixels fenêtre), d'après la
+    /// disposition de la dernière frame dessinée.
+    pub fn button_at(&self, x: f32, y: f32) -> Option<ChromeButton> {
+        self.layout
+            .buttons
+            .iter()
+            .find(|(_, rect)| rect.contains(x, y))
+            .map(|&(button, _)| button)
+    }
+
+    /// Élément sous `(x, y)` d'après la dernière frame dessinée.
+    pub fn hit_test(&self, x: f32, y: f32) -> ChromeHit {
+        self.layout.hit(x, y)
+    }
+
+    /// Position (en caractères) dans le texte de la barre d'URL la plus
+    /// proche de l'abscisse `x`, d'après la dernière frame dessinée.
+    pub fn url_char_offset_at(&self, x: f32) -> usize {
+        let hit = &self.url_hit;
+        hit.start + char_offset_at(&hit.advances, x - hit.x)
+    }
+
+    /// Abscisse du caractère `offset` de la barre d'URL dans la dernière
+    /// frame (`None` s'il n'était pas visible). Sert à placer la fenêtre de
+    /// candidats de l'IME.
+    pub fn url_x_of(&self, offset: usize) -> Option<f32> {
+        let hit = &self.url_hit;
+        let visible = offset.checked_sub(hit.start)?;
+        (visible <= hit.advances.len()).then(|| hit.x + hit.advances[..visible].iter().sum::<f32>())
+    }
+
+    /// `true` si `(x, y)` tombe sur la barre de recherche ouverte.
+    pub fn find_bar_contains(&self, x: f32, y: f32) -> bool {
+        self.find_rect.is_some_and(|rect| rect.contains(x, y))
+    }
+
+    /// Entrée du menu déroulant sous le point `(x, y)`, s'il est ouvert.
+    pub fn menu_item_at(&self, x: f32, y: f32) -> Option<MenuItem> {
+        self.menu_rows
+            .iter()
+            .find(|(_, rect)| rect.contains(x, y))
+            .map(|&(item, _)| item)
+    }
+
+    /// `true` si `(x, y)` est sur l'indicateur de téléchargement.
+    pub fn download_indicator_at(&self, x: f32, y: f32) -> bool {
+        self.download_rect.is_some_and(|rect| rect.contains(x, y))
+    }
+
+    /// Index de la ligne du menu contextuel sous le point `(x, y)`.
+    pub fn context_item_at(&self, x: f32, y: f32) -> Option<usize> {
+        self.context_rows
+            .iter()
+            .position(|rect| rect.contains(x, y))
+    }
+
+    /// Dessine le menu contextuel à sa position (décalé pour rester dans la
+    /// fenêtre) et retourne ses lignes pour le hit-test. Les entrées
+    /// indisponibles sont estompées.
+    unsafe fn draw_context_menu(
+        &self,
+        menu: &ContextMenu,
+        window_w: f32,
+        window_h: f32,
+        baseline_offset: f32,
+    ) -> Vec<ChromeRect> {
+        let label_w = menu
+            .items()
+            .iter()
+            .map(|(action, _)| {
+                action
+                    .label()
+                    .chars()
+                    .map(|c| self.advance_of(c))
+                    .sum::<f32>()
+            })
+            .fold(0.0, f32::max);
+        let row_h = (self.font_size + MENU_ROW_PAD * 2.0).round();
+        let rows = layout_context_menu(
+            menu.position(),
+            window_w,
+            window_h,
+            label_w + MENU_PAD * 2.0,
+            row_h,
+            menu.items().len(),
+        );
+
+        let (first, last) = (rows[0], rows[rows.len() - 1]);
+        let panel_h = last.y + last.h - first.y;
+        self.draw_rounded_rect(
+            first.x - 1.0,
+            first.y - 1.0,
+            first.w + 2.0,
+            panel_h + 2.0,
+            STATUS_RADIUS,
+            self.bar_border_color,
+        );
+        self.draw_rounded_rect(
+            first.x,
+            first.y,
+            first.w,
+            panel_h,
+            STATUS_RADIUS,
+            self.bar_bg_color,
+        );
+
+        let [r, g, b, _] = self.bar_bg_color;
+        for (i, (&(action, enabled), rect)) in menu.items().iter().zip(&rows).enumerate() {
+            if menu.selected() == Some(i) {
+                self.draw_rect(rect.x, rect.y, rect.w, rect.h, self.bar_border_color);
+            }
+            let baseline = rect.y + rect.h / 2.0 + baseline_offset;
+            let mut pen_x = rect.x + MENU_PAD;
+            for c in action.label().chars() {
+                pen_x += self.draw_glyph(c, pen_x, baseline);
+            }
+            if !enabled {
+                self.draw_rect(rect.x, rect.y, rect.w, rect.h, [r, g, b, 0.6]);
+            }
+        }
+        rows
+    }
+
+    /// Dessine le panneau du menu aligné à droite sur `anchor`, juste sous
+    /// le chrome, et retourne les lignes pour le hit-test.
+    /// `baseline_offset` place le texte dans chaque ligne.
+    unsafe fn draw_menu(
+        &self,
+        menu: &Menu,
+        anchor: ChromeRect,
+        window_w: f32,
+        chrome_h: f32,
+        baseline_offset: f32,
+    ) -> Vec<(MenuItem, ChromeRect)> {
+        let label_w = MenuItem::ALL
+            .iter()
+            .map(|item| {
+                item.label()
+                    .chars()
+                    .map(|c| self.advance_of(c))
+                    .sum::<f32>()
+            })
+            .fold(0.0, f32::max);
+        let row_h = (self.font_size + MENU_ROW_PAD * 2.0).round();
+        let rows = layout_menu(anchor, window_w, chrome_h, label_w + MENU_PAD * 2.0, row_h);
+
+        let (first, last) = (rows[0], rows[rows.len() - 1]);
+        let panel_h = last.y + last.h - first.y;
+        self.draw_rounded_rect(
+            first.x - 1.0,
+            first.y - 1.0,
+            first.w + 2.0,
+            panel_h + 2.0,
+            STATUS_RADIUS,
+            self.bar_border_color,
+        );
+        self.draw_rounded_rect(
+            first.x,
+            first.y,
+            first.w,
+            panel_h,
+            STATUS_RADIUS,
+            self.bar_bg_color,
+        );
+
+        let mut hit_rows = Vec::with_capacity(rows.len());
+        for (i, (item, rect)) in MenuItem::ALL.into_iter().zip(rows).enumerate() {
+            if menu.selected() == Some(i) {
+                self.draw_rect(rect.x, rect.y, rect.w, rect.h, self.bar_border_color);
+            }
+            let baseline = rect.y + rect.h / 2.0 + baseline_offset;
+            let mut pen_x = rect.x + MENU_PAD;
+            for c in item.label().chars() {
+                pen_x += self.draw_glyph(c, pen_x, baseline);
+            }
+            hit_rows.push((item, rect));
+        }
+        hit_rows
+    }
+
+    /// Avances de chaque caractère de `text`, crénage avec le suivant inclus.
+    fn kerned_advances(&self, text: &str) -> Vec<f32> {
+        let chars: Vec<char> = text.chars().collect();
+        kerned_advances(&chars, |c| self.advance_of(c), |a, b| self.atlas.kern(a, b))
+    }
+
+    /// Avance horizontale d'un caractère (l'atlas doit déjà le contenir).
+    fn advance_of(&self, c: char) -> f32 {
+        match self.atlas.glyphs.get(&c) {
+            Some(glyph) => glyph.advance_x,
+            // Ne devrait pas arriver après `ensure_glyphs` — avancer d'un espace
+            None => self
+                .atlas
+                .glyphs
+                .get(&' ')
+                .map_or(self.font_size * 0.5, |space| space.advance_x),
+        }
+    }
+
+    /// Dessine un glyphe à `pen_x` sur la ligne de base et retourne son avance.
+    unsafe fn draw_glyph(&self, c: char, pen_x: f32, baseline_y: f32) -> f32 {
+        let Some(glyph) = self.atlas.glyphs.get(&c) else {
+            return self.advance_of(c);
+        };
+        if glyph.width > 0 && glyph.height > 0 {
+            let gx = pen_x + glyph.offset_x;
+            // offset_y from fontdue is the bottom edge relative to baseline
+            // We need to position from top-left
+            let gy = baseline_y - glyph.offset_y - glyph.height as f32;
+
+            self.draw_textured_rect(
+                gx,
+                gy,
+                glyph.width as f32,
+                glyph.height as f32,
+                glyph.atlas_x,
+                glyph.atlas_y,
+                glyph.width,
+                glyph.height,
+            );
+        }
+        glyph.advance_x
+    }
+
     /// Dessine un rectangle de couleur unie.
     unsafe fn draw_rect(&self, x: f32, y: f32, w: f32, h: f32, color: [f32; 4]) {
         let gl = &self.gl;
@@ -482,8 +1413,325 @@ impl ChromeRenderer {
         gl.draw_arrays(glow::TRIANGLES, 0, 6);
     }
 
-    /// Dessine un rectangle texturé depuis l'atlas de glyphes.
-    #[allow(clippy::too_many_arguments)]
+    /// Dessine un rectangle arrondi antialiasé via le chemin SDF du shader :
+    /// fond `fill`, bordure intérieure de `border_width` pixels en `border`.
+    unsafe fn draw_sdf_rounded_rect(
+        &self,
+        rect: ChromeRect,
+        radius: f32,
+        fill: [f32; 4],
+        border: [f32; 4],
+        border_width: f32,
+    ) {
+        let gl = &self.gl;
+        gl.uniform_1_i32(Some(&self.u_sdf), 1);
+        gl.uniform_4_f32(Some(&self.u_rect), rect.x, rect.y, rect.w, rect.h);
+        gl.uniform_1_f32(
+            Some(&self.u_radius),
+            clamp_corner_radius(radius, rect.w, rect.h),
+        );
+        gl.uniform_1_f32(Some(&self.u_border_width), border_width);
+        gl.uniform_4_f32_slice(Some(&self.u_border_color), &border);
+        self.draw_rect(rect.x, rect.y, rect.w, rect.h, fill);
+        gl.uniform_1_i32(Some(&self.u_sdf), 0);
+    }
+
+    /// Dessine un rectangle aux coins arrondis : une croix de rectangles
+    /// plus un éventail de triangles par coin.
+    unsafe fn draw_rounded_rect(&self, x: f32, y: f32, w: f32, h: f32, r: f32, color: [f32; 4]) {
+        let r = r.clamp(0.0, w.min(h) / 2.0);
+        self.draw_rect(x + r, y, w - r * 2.0, h, color);
+        self.draw_rect(x, y + r, r, h - r * 2.0, color);
+        self.draw_rect(x + w - r, y + r, r, h - r * 2.0, color);
+        if r <= 0.0 {
+            return;
+        }
+
+        let corners = [
+            (x + r, y + r, std::f32::consts::PI),
+            (x + w - r, y + r, std::f32::consts::PI * 1.5),
+            (x + w - r, y + h - r, 0.0),
+            (x + r, y + h - r, std::f32::consts::FRAC_PI_2),
+        ];
+        let step = std::f32::consts::FRAC_PI_2 / CORNER_SEGMENTS as f32;
+        for (cx, cy, start) in corners {
+            for i in 0..CORNER_SEGMENTS {
+                let a0 = start + step * i as f32;
+                let a1 = a0 + step;
+                self.draw_triangle(
+                    [cx, cy],
+                    [cx + r * a0.cos(), cy + r * a0.sin()],
+                    [cx + r * a1.cos(), cy + r * a1.sin()],
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Dessine un disque plein (éventail de triangles).
+    unsafe fn draw_circle(&self, cx: f32, cy: f32, r: f32, color: [f32; 4]) {
+        let segments = CORNER_SEGMENTS * 4;
+        let step = std::f32::consts::TAU / segments as f32;
+        for i in 0..segments {
+            let a0 = step * i as f32;
+            let a1 = a0 + step;
+            self.draw_triangle(
+                [cx, cy],
+                [cx + r * a0.cos(), cy + r * a0.sin()],
+                [cx + r * a1.cos(), cy + r * a1.sin()],
+                color,
+            );
+        }
+    }
+
+    /// Icône de globe affichée quand la page n'a pas de favicon :
+    /// un cercle avec équateur et méridien.
+    unsafe fn draw_globe(&self, x: f32, y: f32, size: f32) {
+        let r = size / 2.0;
+        let (cx, cy) = (x + r, y + r);
+        self.draw_circle(cx, cy, r, self.text_color);
+        self.draw_circle(cx, cy, r - 1.5, self.bar_bg_color);
+        self.draw_rect(x + 1.0, cy - 0.5, size - 2.0, 1.0, self.text_color);
+        self.draw_rect(cx - 0.5, y + 1.0, 1.0, size - 2.0, self.text_color);
+    }
+
+    /// Icône de maison : toit triangulaire, murs, porte évidée.
+    unsafe fn draw_house(&self, rect: ChromeRect, color: [f32; 4], backdrop: [f32; 4]) {
+        let size = (rect.w.min(rect.h) * 0.55).round();
+        let x = (rect.x + (rect.w - size) / 2.0).round();
+        let y = (rect.y + (rect.h - size) / 2.0).round();
+        let roof_h = (size * 0.45).round();
+        self.draw_triangle(
+            [x, y + roof_h],
+            [x + size / 2.0, y],
+            [x + size, y + roof_h],
+            color,
+        );
+        let wall_x = x + (size * 0.15).round();
+        let wall_w = size - (size * 0.15).round() * 2.0;
+        self.draw_rect(wall_x, y + roof_h, wall_w, size - roof_h, color);
+        let door_w = (size * 0.25).round().max(2.0);
+        let door_h = ((size - roof_h) * 0.6).round();
+        self.draw_rect(
+            x + (size - door_w) / 2.0,
+            y + size - door_h,
+            door_w,
+            door_h,
+            backdrop,
+        );
+    }
+
+    /// Icône « recharger » : arc de cercle ouvert en haut à droite, terminé
+    /// par une pointe de flèche.
+    unsafe fn draw_reload(&self, rect: ChromeRect, color: [f32; 4]) {
+        let (cx, cy) = (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0);
+        let r = (rect.w.min(rect.h) * 0.27).round();
+        let thickness = 2.0;
+        let (inner, outer) = (r - thickness / 2.0, r + thickness / 2.0);
+        let point =
+            |radius: f32, angle: f32| [cx + radius * angle.cos(), cy + radius * angle.sin()];
+        // Trois quarts de tour, de -45° (haut droite) dans le sens horaire
+        let start = -std::f32::consts::FRAC_PI_4;
+        let sweep = std::f32::consts::TAU * 0.75;
+        let segments = CORNER_SEGMENTS * 3;
+        let step = sweep / segments as f32;
+        for i in 0..segments {
+            let (a0, a1) = (start + step * i as f32, start + step * (i + 1) as f32);
+            self.draw_triangle(point(inner, a0), point(outer, a0), point(outer, a1), color);
+            self.draw_triangle(point(inner, a0), point(outer, a1), point(inner, a1), color);
+        }
+        // Pointe de flèche à l'extrémité de départ, orientée dans le sens
+        // antihoraire (vers le haut) : la fin du tracé « revient » au début.
+        let head = r * 0.6;
+        let tip = point(r, start - 0.5);
+        self.draw_triangle(point(r - head, start), point(r + head, start), tip, color);
+    }
+
+    /// Icône « × » (arrêter le chargement) : deux traits en diagonale.
+    unsafe fn draw_cross(&self, rect: ChromeRect, color: [f32; 4]) {
+        let (cx, cy) = (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0);
+        let half = (rect.w.min(rect.h) * 0.22).round();
+        self.draw_line([cx - half, cy - half], [cx + half, cy + half], 2.0, color);
+        self.draw_line([cx - half, cy + half], [cx + half, cy - half], 2.0, color);
+    }
+
+    /// Trait de `from` à `to` d'épaisseur `width` (deux triangles).
+    unsafe fn draw_line(&self, from: [f32; 2], to: [f32; 2], width: f32, color: [f32; 4]) {
+        let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len == 0.0 {
+            return;
+        }
+        // Normale unitaire × demi-épaisseur
+        let (nx, ny) = (-dy / len * width / 2.0, dx / len * width / 2.0);
+        let a = [from[0] + nx, from[1] + ny];
+        let b = [from[0] - nx, from[1] - ny];
+        let c = [to[0] - nx, to[1] - ny];
+        let d = [to[0] + nx, to[1] + ny];
+        self.draw_triangle(a, b, c, color);
+        self.draw_triangle(a, c, d, color);
+    }
+
+    /// Cadenas fermé : anse en demi-anneau au-dessus d'un corps plein,
+    /// avec un trou de serrure évidé (couleur `backdrop`).
+    unsafe fn draw_padlock(&self, rect: ChromeRect, color: [f32; 4], backdrop: [f32; 4]) {
+        let body_h = (rect.h * 0.55).round();
+        let body_y = rect.y + rect.h - body_h;
+        let cx = rect.x + rect.w / 2.0;
+        let r = (rect.w * 0.3).round();
+        let shackle_cy = body_y;
+        let (inner, outer) = (r - 1.0, r + 1.0);
+        let point = |radius: f32, angle: f32| {
+            [cx + radius * angle.cos(), shackle_cy + radius * angle.sin()]
+        };
+        let segments = CORNER_SEGMENTS * 2;
+        let step = std::f32::consts::PI / segments as f32;
+        for i in 0..segments {
+            // De π à 2π : moitié supérieure (y vers le bas)
+            let a0 = std::f32::consts::PI + step * i as f32;
+            let a1 = a0 + step;
+            self.draw_triangle(point(inner, a0), point(outer, a0), point(outer, a1), color);
+            self.draw_triangle(point(inner, a0), point(outer, a1), point(inner, a1), color);
+        }
+        self.draw_rounded_rect(rect.x, body_y, rect.w, body_h, 1.5, color);
+        self.draw_rect(cx - 1.0, body_y + body_h * 0.3, 2.0, body_h * 0.4, backdrop);
+    }
+
+    /// Panneau d'avertissement : triangle plein et « ! » évidé.
+    unsafe fn draw_warning(&self, rect: ChromeRect, color: [f32; 4], backdrop: [f32; 4]) {
+        let cx = rect.x + rect.w / 2.0;
+        self.draw_triangle(
+            [rect.x, rect.y + rect.h],
+            [cx, rect.y],
+            [rect.x + rect.w, rect.y + rect.h],
+            color,
+        );
+        let bar_top = rect.y + rect.h * 0.35;
+        let bar_h = rect.h * 0.35;
+        self.draw_rect(cx - 1.0, bar_top, 2.0, bar_h, backdrop);
+        self.draw_rect(cx - 1.0, rect.y + rect.h * 0.78, 2.0, 2.0, backdrop);
+    }
+
+    /// Icône « ≡ » : trois barres horizontales centrées dans `rect`.
+    unsafe fn draw_hamburger(&self, rect: ChromeRect, color: [f32; 4]) {
+        let bar_w = (rect.w * 0.5).round();
+        let x = (rect.x + (rect.w - bar_w) / 2.0).round();
+        let cy = (rect.y + rect.h / 2.0).round();
+        let gap = (rect.h * 0.18).round();
+        for dy in [-gap, 0.0, gap] {
+            self.draw_rect(x, cy + dy - 1.0, bar_w, 2.0, color);
+        }
+    }
+
+    /// Icône d'engrenage centrée dans `rect` : dents, corps plein, moyeu évidé
+    /// (peint avec la couleur du fond `backdrop`).
+    unsafe fn draw_gear(&self, rect: ChromeRect, color: [f32; 4], backdrop: [f32; 4]) {
+        let (cx, cy) = (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0);
+        let r = (rect.w.min(rect.h) * 0.3).round();
+        let tooth = std::f32::consts::TAU / (GEAR_TEETH * 2) as f32;
+        for i in 0..GEAR_TEETH {
+            let a = tooth * 2.0 * i as f32;
+            let (inner, outer) = (r * 0.7, r);
+            let point =
+                |radius: f32, angle: f32| [cx + radius * angle.cos(), cy + radius * angle.sin()];
+            let (a0, a1) = (a - tooth / 2.0, a + tooth / 2.0);
+            self.draw_triangle(point(inner, a0), point(outer, a0), point(outer, a1), color);
+            self.draw_triangle(point(inner, a0), point(outer, a1), point(inner, a1), color);
+        }
+        self.draw_circle(cx, cy, r * 0.75, color);
+        self.draw_circle(cx, cy, r * 0.32, backdrop);
+    }
+
+    /// Étoile à cinq branches centrée dans `rect` : pleine (couleur d'accent)
+    /// si la page est en favori, creuse sinon.
+    unsafe fn draw_star(&self, rect: ChromeRect, filled: bool, backdrop: [f32; 4]) {
+        let (cx, cy) = (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0);
+        let r = (rect.w.min(rect.h) * 0.32).round();
+        if filled {
+            self.draw_star_shape(cx, cy, r, self.cursor_color);
+        } else {
+            self.draw_star_shape(cx, cy, r, self.text_color);
+            self.draw_star_shape(cx, cy + 0.5, r - 2.5, backdrop);
+        }
+    }
+
+    /// Étoile pleine : un triangle par branche plus le pentagone central.
+    unsafe fn draw_star_shape(&self, cx: f32, cy: f32, r: f32, color: [f32; 4]) {
+        let inner = r * 0.4;
+        let point = |radius: f32, i: usize| {
+            // Pointe vers le haut ; les sommets alternent extérieur/intérieur.
+            let angle = -std::f32::consts::FRAC_PI_2 + std::f32::consts::PI / 5.0 * i as f32;
+            [cx + radius * angle.cos(), cy + radius * angle.sin()]
+        };
+        for tip in 0..5 {
+            let i = tip * 2;
+            self.draw_triangle(point(inner, i + 9), point(r, i), point(inner, i + 1), color);
+            self.draw_triangle([cx, cy], point(inner, i + 1), point(inner, i + 3), color);
+        }
+    }
+
+    /// Dessine un bouclier : rectangle en haut, pointe triangulaire en bas.
+    unsafe fn draw_shield(&self, x: f32, y: f32, w: f32, h: f32, color: [f32; 4]) {
+        let body_h = (h * 0.55).round();
+        self.draw_rect(x, y, w, body_h, color);
+        self.draw_triangle(
+            [x, y + body_h],
+            [x + w, y + body_h],
+            [x + w / 2.0, y + h],
+            color,
+        );
+    }
+
+    /// Dessine un triangle de couleur unie.
+    unsafe fn draw_triangle(&self, a: [f32; 2], b: [f32; 2], c: [f32; 2], color: [f32; 4]) {
+        let gl = &self.gl;
+        gl.uniform_1_i32(Some(&self.u_use_texture), 0);
+        gl.uniform_4_f32_slice(Some(&self.u_color), &color);
+
+        #[rustfmt::skip]
+        let vertices: [f32; 12] = [
+            a[0], a[1], 0.0, 0.0,
+            b[0], b[1], 0.0, 0.0,
+            c[0], c[1], 0.0, 0.0,
+        ];
+
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+        gl.buffer_data_u8_slice(
+            glow::ARRAY_BUFFER,
+            bytemuck_cast_slice(&vertices),
+            glow::DYNAMIC_DRAW,
+        );
+        gl.draw_arrays(glow::TRIANGLES, 0, 3);
+    }
+
+    /// Dessine la texture RGBA actuellement liée (favicon) sur tout le quad.
+    unsafe fn draw_image(&self, x: f32, y: f32, w: f32, h: f32) {
+        let gl = &self.gl;
+        gl.uniform_1_i32(Some(&self.u_rgba), 1);
+        gl.uniform_4_f32_slice(Some(&self.u_color), &[1.0, 1.0, 1.0, 1.0]);
+
+        #[rustfmt::skip]
+        let vertices: [f32; 24] = [
+            x,     y,     0.0, 0.0,
+            x + w, y,     1.0, 0.0,
+            x + w, y + h, 1.0, 1.0,
+            x,     y,     0.0, 0.0,
+            x + w, y + h, 1.0, 1.0,
+            x,     y + h, 0.0, 1.0,
+        ];
+
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+        gl.buffer_data_u8_slice(
+            glow::ARRAY_BUFFER,
+            bytemuck_cast_slice(&vertices),
+            glow::DYNAMIC_DRAW,
+        );
+        gl.draw_arrays(glow::TRIANGLES, 0, 6);
+        gl.uniform_1_i32(Some(&self.u_rgba), 0);
+    }
+
+    /// Dessine un rectangle texturé depuis l'atlas de glyphes.
+    #[allow(clippy::too_many_arguments)]
     unsafe fn draw_textured_rect(
         &self,
         x: f32,
@@ -526,6 +1774,432 @@ impl ChromeRenderer {
     }
 }
 
+/// Rectangle en pixels fenêtre (origine en haut à gauche).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct ChromeRect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl ChromeRect {
+    fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.x + self.w && y >= self.y && y < self.y + self.h
+    }
+}
+
+/// Disposition horizontale du chrome, calculée par [`layout_chrome`].
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ChromeLayout {
+    /// Barre de saisie.
+    bar: ChromeRect,
+    /// Abscisse où s'arrête la zone de texte de la barre (avant ses boutons).
+    text_end: f32,
+    /// Badge adblock, si affiché.
+    badge: Option<ChromeRect>,
+    /// Boutons, de gauche à droite.
+    buttons: Vec<(ChromeButton, ChromeRect)>,
+}
+
+impl ChromeLayout {
+    /// Élément sous `(x, y)`. Les boutons passent avant la barre, qui
+    /// contient certains d'entre eux.
+    fn hit(&self, x: f32, y: f32) -> ChromeHit {
+        if let Some(&(button, _)) = self.buttons.iter().find(|(_, rect)| rect.contains(x, y)) {
+            ChromeHit::Button(button)
+        } else if self.bar.contains(x, y) {
+            ChromeHit::UrlBar
+        } else if self.badge.is_some_and(|badge| badge.contains(x, y)) {
+            ChromeHit::Badge
+        } else {
+            ChromeHit::Background
+        }
+    }
+}
+
+/// Répartit la largeur du chrome : les `left_buttons` (carrés de la hauteur
+/// de la barre) sont calés à gauche, les `buttons` à droite avec le badge à
+/// leur gauche, et la barre de saisie prend tout l'espace restant. Les
+/// `bar_buttons` se placent à l'intérieur de la barre, contre son bord
+/// droit, et réduisent la zone de texte.
+fn layout_chrome(
+    window_w: f32,
+    chrome_h: f32,
+    margin: f32,
+    badge_w: f32,
+    left_buttons: &[ChromeButton],
+    bar_buttons: &[ChromeButton],
+    buttons: &[ChromeButton],
+) -> ChromeLayout {
+    let side = (chrome_h - margin * 2.0).max(0.0);
+    let mut left = margin;
+    let mut right = window_w - margin;
+
+    let left_placed: Vec<(ChromeButton, ChromeRect)> = left_buttons
+        .iter()
+        .map(|&button| {
+            let rect = ChromeRect {
+                x: left,
+                y: margin,
+                w: side,
+                h: side,
+            };
+            left += side + margin;
+            (button, rect)
+        })
+        .collect();
+
+    let mut placed: Vec<(ChromeButton, ChromeRect)> = buttons
+        .iter()
+        .rev()
+        .map(|&button| {
+            right -= side;
+            let rect = ChromeRect {
+                x: right,
+                y: margin,
+                w: side,
+                h: side,
+            };
+            right -= margin;
+            (button, rect)
+        })
+        .collect();
+    placed.reverse();
+    placed.splice(0..0, left_placed);
+
+    let badge = (badge_w > 0.0).then(|| {
+        right -= badge_w;
+        let rect = ChromeRect {
+            x: right,
+            y: margin,
+            w: badge_w,
+            h: side,
+        };
+        right -= margin;
+        rect
+    });
+
+    let bar = ChromeRect {
+        x: left,
+        y: margin,
+        w: (right - left).max(0.0),
+        h: side,
+    };
+
+    let mut text_end = bar.x + bar.w;
+    for &button in bar_buttons.iter().rev() {
+        text_end -= side;
+        placed.push((
+            button,
+            ChromeRect {
+                x: text_end,
+                y: margin,
+                w: side,
+                h: side,
+            },
+        ));
+    }
+
+    ChromeLayout {
+        bar,
+        text_end: text_end.max(bar.x),
+        badge,
+        buttons: placed,
+    }
+}
+
+/// Rayon effectif : jamais négatif ni plus grand que la demi-hauteur
+/// (ou demi-largeur), sinon la forme SDF dégénère.
+fn clamp_corner_radius(radius: f32, w: f32, h: f32) -> f32 {
+    radius.clamp(0.0, (w.min(h) / 2.0).max(0.0))
+}
+
+/// Lignes du menu déroulant : un panneau de `width` pixels aligné à droite
+/// sur `anchor` (sans déborder à gauche de la fenêtre), collé sous le chrome.
+fn layout_menu(
+    anchor: ChromeRect,
+    window_w: f32,
+    chrome_h: f32,
+    width: f32,
+    row_h: f32,
+) -> Vec<ChromeRect> {
+    let width = width.max(MENU_MIN_WIDTH).min(window_w);
+    let x = (anchor.x + anchor.w - width).max(0.0);
+    (0..MenuItem::ALL.len())
+        .map(|i| ChromeRect {
+            x,
+            y: chrome_h + MENU_ROW_PAD / 2.0 + row_h * i as f32,
+            w: width,
+            h: row_h,
+        })
+        .collect()
+}
+
+/// Lignes du menu contextuel : `count` lignes de `width` pixels à partir de
+/// `(x, y)`, décalées vers la gauche ou vers le haut si le panneau déborde
+/// de la fenêtre.
+fn layout_context_menu(
+    (x, y): (f32, f32),
+    window_w: f32,
+    window_h: f32,
+    width: f32,
+    row_h: f32,
+    count: usize,
+) -> Vec<ChromeRect> {
+    let width = width.max(MENU_MIN_WIDTH).min(window_w);
+    let panel_h = row_h * count as f32;
+    let x = x.min(window_w - width).max(0.0);
+    let y = y.min(window_h - panel_h).max(0.0);
+    (0..count)
+        .map(|i| ChromeRect {
+            x,
+            y: y + row_h * i as f32,
+            w: width,
+            h: row_h,
+        })
+        .collect()
+}
+
+/// Texte du badge adblock : compteur de la page, « off » si elle est
+/// exemptée, rien si l'adblock est désactivé.
+fn badge_text(blocked_count: Option<u32>, excepted: bool) -> Option<String> {
+    let count = blocked_count?;
+    Some(if excepted {
+        BADGE_OFF_LABEL.to_string()
+    } else {
+        badge_label(count)
+    })
+}
+
+/// Texte du badge adblock (plafonné pour garder une largeur raisonnable).
+fn badge_label(count: u32) -> String {
+    if count > 999 {
+        "999+".to_string()
+    } else {
+        count.to_string()
+    }
+}
+
+/// Position et taille `(x, y, largeur, hauteur)` de la bulle de statut.
+///
+/// Ancrée en bas à gauche, elle occupe au plus la moitié de la largeur de
+/// la fenêtre (comme Firefox) et ne remonte jamais dans le chrome.
+fn status_bubble_rect(
+    window_w: f32,
+    window_h: f32,
+    text_w: f32,
+    font_size: f32,
+    chrome_h: f32,
+) -> (f32, f32, f32, f32) {
+    let height = (font_size + STATUS_PAD * 2.0).min((window_h - chrome_h).max(0.0));
+    let width = (text_w + STATUS_PAD * 2.0).min((window_w / 2.0).floor());
+    (0.0, window_h - height, width.max(0.0), height)
+}
+
+/// Zone de la barre de recherche : sous le chrome, alignée à droite, jamais
+/// plus large que la fenêtre.
+fn find_bar_rect(window_w: f32, chrome_h: f32, font_size: f32) -> ChromeRect {
+    let w = FIND_WIDTH.min((window_w - FIND_MARGIN * 2.0).max(0.0));
+    ChromeRect {
+        x: window_w - w - FIND_MARGIN,
+        y: chrome_h + FIND_MARGIN,
+        w,
+        h: (font_size + STATUS_PAD * 3.0).round(),
+    }
+}
+
+/// Hauteur d'une ligne de texte dans un dialogue JavaScript.
+fn dialog_line_height(font_size: f32) -> f32 {
+    (font_size * 1.6).round()
+}
+
+/// Zone d'un dialogue JavaScript de `rows` lignes : centré sous le chrome,
+/// jamais plus large que la fenêtre.
+fn dialog_rect(window_w: f32, chrome_h: f32, font_size: f32, rows: usize) -> ChromeRect {
+    let w = DIALOG_WIDTH.min((window_w - FIND_MARGIN * 2.0).max(0.0));
+    ChromeRect {
+        x: ((window_w - w) / 2.0).round(),
+        y: chrome_h + FIND_MARGIN,
+        w,
+        h: dialog_line_height(font_size) * rows as f32 + STATUS_PAD * 4.0,
+    }
+}
+
+/// Avance de chaque caractère, ajustée du crénage avec le caractère suivant.
+///
+/// Dessin du texte, position du curseur et hit-test des clics partagent ces
+/// mêmes valeurs : le curseur tombe donc exactement là où l'on a cliqué.
+fn kerned_advances(
+    chars: &[char],
+    advance: impl Fn(char) -> f32,
+    kern: impl Fn(char, char) -> f32,
+) -> Vec<f32> {
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| advance(c) + chars.get(i + 1).map_or(0.0, |&next| kern(c, next)))
+        .collect()
+}
+
+/// Index (en caractères) de la frontière de glyphe la plus proche de `x`,
+/// mesuré depuis le début du texte. Les clics hors du texte sont bornés.
+fn char_offset_at(advances: &[f32], x: f32) -> usize {
+    let mut pen = 0.0;
+    for (i, advance) in advances.iter().enumerate() {
+        if x < pen + advance / 2.0 {
+            return i;
+        }
+        pen += advance;
+    }
+    advances.len()
+}
+
+/// Texte de l'URL tel que dessiné : origine, premier caractère visible et
+/// avances (crénage inclus) des caractères visibles.
+#[derive(Debug, Default)]
+struct UrlTextHit {
+    x: f32,
+    start: usize,
+    advances: Vec<f32>,
+}
+
+/// Portion visible du texte de la barre, calculée par [`layout_text`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TextLayout {
+    /// Index (en caractères) du premier caractère dessiné.
+    start: usize,
+    /// Index (exclusif) du dernier caractère dessiné.
+    end: usize,
+    /// `true` si le texte est tronqué à droite et doit finir par « … ».
+    ellipsis: bool,
+}
+
+/// Calcule quels caractères tiennent dans `available` pixels.
+///
+/// Le texte trop long est tronqué au dernier glyphe entier qui laisse la place
+/// à l'ellipse, au lieu d'être coupé au milieu d'un glyphe. Si un curseur est
+/// fourni, le texte défile horizontalement (par caractères entiers) pour que
+/// le curseur reste toujours visible et ne soit jamais recouvert par « … ».
+fn layout_text(
+    advances: &[f32],
+    available: f32,
+    ellipsis_width: f32,
+    cursor: Option<usize>,
+) -> TextLayout {
+    let total: f32 = advances.iter().sum();
+    if total <= available {
+        return TextLayout {
+            start: 0,
+            end: advances.len(),
+            ellipsis: false,
+        };
+    }
+
+    // Positions cumulées : prefix[i] = x du début du caractère i.
+    let mut prefix = Vec::with_capacity(advances.len() + 1);
+    prefix.push(0.0f32);
+    for advance in advances {
+        prefix.push(prefix.last().copied().unwrap_or(0.0) + advance);
+    }
+
+    // Défilement : garder le curseur à gauche de la zone réservée à l'ellipse.
+    let usable = (available - ellipsis_width - CURSOR_WIDTH).max(0.0);
+    let mut start = 0;
+    if let Some(cursor) = cursor.map(|c| c.min(advances.len())) {
+        let overflow = prefix[cursor] - usable;
+        if overflow > 0.0 {
+            start = prefix.iter().position(|&x| x >= overflow).unwrap_or(cursor);
+            start = start.min(cursor);
+        }
+    }
+    let scroll = prefix[start];
+
+    // Le reste tient-il sans ellipse ?
+    if total - scroll <= available {
+        return TextLayout {
+            start,
+            end: advances.len(),
+            ellipsis: false,
+        };
+    }
+
+    let limit = scroll + available - ellipsis_width;
+    let mut end = start;
+    while end < advances.len() && prefix[end + 1] <= limit {
+        end += 1;
+    }
+    // Le curseur doit rester dans la portion dessinée.
+    if let Some(cursor) = cursor {
+        end = end.max(cursor.min(advances.len()));
+    }
+
+    TextLayout {
+        start,
+        end,
+        ellipsis: end < advances.len(),
+    }
+}
+
+/// Compile un shader ; en cas d'échec il est supprimé et le log retourné.
+///
+/// # Safety
+/// Appelle des fonctions OpenGL.
+unsafe fn compile_shader(
+    gl: &glow::Context,
+    stage: &'static str,
+    kind: u32,
+    source: &str,
+) -> Result<glow::Shader, ChromeError> {
+    unsafe {
+        let shader = gl
+            .create_shader(kind)
+            .map_err(|reason| ChromeError::Resource {
+                what: "shader",
+                reason,
+            })?;
+        gl.shader_source(shader, source);
+        gl.compile_shader(shader);
+        if !gl.get_shader_compile_status(shader) {
+            let log = gl.get_shader_info_log(shader);
+            gl.delete_shader(shader);
+            return Err(ChromeError::ShaderCompile { stage, log });
+        }
+        Ok(shader)
+    }
+}
+
+/// Zone de l'indicateur de sécurité, centrée verticalement dans le chrome.
+fn security_icon_rect(x: f32, chrome_h: f32) -> ChromeRect {
+    ChromeRect {
+        x,
+        y: ((chrome_h - SECURITY_ICON_SIZE) / 2.0).round(),
+        w: SECURITY_ICON_SIZE,
+        h: SECURITY_ICON_SIZE,
+    }
+}
+
+/// Uploade l'intégralité de l'atlas dans la texture actuellement liée.
+///
+/// # Safety
+/// Appelle des fonctions OpenGL ; la texture de l'atlas doit être liée.
+unsafe fn upload_atlas(gl: &glow::Context, atlas: &GlyphAtlas) {
+    unsafe {
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::R8 as i32,
+            atlas.width as i32,
+            atlas.height as i32,
+            0,
+            glow::RED,
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(Some(&atlas.pixels)),
+        );
+    }
+}
+
 /// Cast safe d'un slice `[f32]` vers `[u8]` pour l'upload GL.
 fn bytemuck_cast_slice(data: &[f32]) -> &[u8] {
     unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }
@@ -535,125 +2209,471 @@ fn bytemuck_cast_slice(data: &[f32]) -> &[u8] {
 mod tests {
     use super::*;
 
-    fn build_test_atlas() -> GlyphAtlas {
-        let font = fontdue::Font::from_bytes(FONT_BYTES, fontdue::FontSettings::default())
-            .expect("Failed to load Inter font");
-        GlyphAtlas::build(&font, 16.0)
+    #[test]
+    fn test_bytemuck_cast_slice_length() {
+        let data: [f32; 2] = [1.0, 2.0];
+        let bytes = bytemuck_cast_slice(&data);
+        assert_eq!(bytes.len(), 8); // 2 * 4 bytes
     }
 
     #[test]
-    fn test_atlas_contains_all_ascii_printable() {
-        let atlas = build_test_atlas();
-        for b in 32u8..=126 {
-            let c = b as char;
-            assert!(
-                atlas.glyphs.contains_key(&c),
-                "Atlas missing char '{}' ({})",
-                c,
-                b
-            );
-        }
+    fn test_chrome_error_messages() {
+        let err = ChromeError::ShaderCompile {
+            stage: "vertex",
+            log: "0:3: syntax error".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "vertex shader compilation failed: 0:3: syntax error"
+        );
+        assert_eq!(
+            ChromeError::ProgramLink("boom".to_string()).to_string(),
+            "shader program link failed: boom"
+        );
+        assert_eq!(
+            ChromeError::MissingUniform("u_color").to_string(),
+            "uniform `u_color` not found"
+        );
+        let err = ChromeError::Resource {
+            what: "glyph atlas texture",
+            reason: "out of memory".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "cannot create glyph atlas texture: out of memory"
+        );
+        // Utilisable comme `Box<dyn Error>`
+        let boxed: Box<dyn std::error::Error> = Box::new(err);
+        assert!(boxed.to_string().contains("out of memory"));
     }
 
     #[test]
-    fn test_atlas_width_is_512() {
-        let atlas = build_test_atlas();
-        assert_eq!(atlas.width, 512);
+    fn test_chrome_height_is_40() {
+        assert_eq!(CHROME_HEIGHT, 40);
     }
 
     #[test]
-    fn test_atlas_height_valid() {
-        let atlas = build_test_atlas();
-        assert!(atlas.height > 0);
-        assert!(atlas.height >= 64, "Atlas height should be >= 64");
+    fn test_badge_label_small_counts() {
+        assert_eq!(badge_label(0), "0");
+        assert_eq!(badge_label(42), "42");
+        assert_eq!(badge_label(999), "999");
     }
 
     #[test]
-    fn test_atlas_pixel_buffer_size() {
-        let atlas = build_test_atlas();
-        assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+    fn test_badge_off_on_excepted_page() {
+        assert_eq!(badge_text(Some(3), false).as_deref(), Some("3"));
+        assert_eq!(badge_text(Some(0), true).as_deref(), Some("off"));
+        assert_eq!(badge_text(None, true), None);
     }
 
     #[test]
-    fn test_glyph_advance_positive() {
-        let atlas = build_test_atlas();
-        for (&c, glyph) in &atlas.glyphs {
-            assert!(
-                glyph.advance_x > 0.0,
-                "Glyph '{}' has non-positive advance_x: {}",
-                c,
-                glyph.advance_x
-            );
-        }
+    fn test_badge_label_capped() {
+        assert_eq!(badge_label(1000), "999+");
+        assert_eq!(badge_label(u32::MAX), "999+");
     }
 
+    // ── layout_chrome ─────────────────────────────────────────────────
+
     #[test]
-    fn test_glyphs_within_atlas_bounds() {
-        let atlas = build_test_atlas();
-        for (&c, glyph) in &atlas.glyphs {
-            assert!(
-                glyph.atlas_x + glyph.width <= atlas.width,
-                "Glyph '{}' exceeds atlas width: {} + {} > {}",
-                c,
-                glyph.atlas_x,
-                glyph.width,
-                atlas.width
-            );
-            assert!(
-                glyph.atlas_y + glyph.height <= atlas.height,
-                "Glyph '{}' exceeds atlas height: {} + {} > {}",
-                c,
-                glyph.atlas_y,
-                glyph.height,
-                atlas.height
-            );
-        }
+    fn test_layout_chrome_without_badge() {
+        let layout = layout_chrome(1000.0, 40.0, 6.0, 0.0, &[], &[], &[ChromeButton::Settings]);
+        let (button, gear) = layout.buttons[0];
+        assert_eq!(button, ChromeButton::Settings);
+        assert_eq!(
+            gear,
+            ChromeRect {
+                x: 966.0,
+                y: 6.0,
+                w: 28.0,
+                h: 28.0
+            }
+        );
+        assert_eq!(layout.badge, None);
+        // La barre s'arrête une marge avant le bouton
+        assert_eq!(layout.bar.x + layout.bar.w, 960.0);
     }
 
     #[test]
-    fn test_no_overlapping_glyphs() {
-        let atlas = build_test_atlas();
-        let glyphs: Vec<_> = atlas.glyphs.iter().collect();
-        for i in 0..glyphs.len() {
-            for j in (i + 1)..glyphs.len() {
-                let (&c1, g1) = glyphs[i];
-                let (&c2, g2) = glyphs[j];
-                // Skip zero-size glyphs (like space)
-                if g1.width == 0 || g1.height == 0 || g2.width == 0 || g2.height == 0 {
-                    continue;
-                }
-                let overlap_x =
-                    g1.atlas_x < g2.atlas_x + g2.width && g2.atlas_x < g1.atlas_x + g1.width;
-                let overlap_y =
-                    g1.atlas_y < g2.atlas_y + g2.height && g2.atlas_y < g1.atlas_y + g1.height;
-                assert!(
-                    !(overlap_x && overlap_y),
-                    "Glyphs '{}' and '{}' overlap",
-                    c1,
-                    c2
-                );
+    fn test_layout_hit_regions() {
+        let layout = layout_chrome(
+            1000.0,
+            40.0,
+            6.0,
+            50.0,
+            &[],
+            &[ChromeButton::Bookmark],
+            &[ChromeButton::Settings],
+        );
+        assert_eq!(
+            layout.hit(980.0, 20.0),
+            ChromeHit::Button(ChromeButton::Settings)
+        );
+        assert_eq!(layout.hit(100.0, 20.0), ChromeHit::UrlBar);
+        let badge = layout.badge.unwrap();
+        assert_eq!(layout.hit(badge.x + 1.0, 20.0), ChromeHit::Badge);
+        // Marges autour de la barre : fond
+        assert_eq!(layout.hit(100.0, 2.0), ChromeHit::Background);
+        assert_eq!(layout.hit(2.0, 20.0), ChromeHit::Background);
+    }
+
+    #[test]
+    fn test_layout_chrome_badge_between_bar_and_buttons() {
+        let layout = layout_chrome(1000.0, 40.0, 6.0, 50.0, &[], &[], &[ChromeButton::Settings]);
+        let badge = layout.badge.unwrap();
+        assert_eq!(badge.x + badge.w, 960.0);
+        assert_eq!(layout.bar.x + layout.bar.w, badge.x - 6.0);
+    }
+
+    #[test]
+    fn test_layout_chrome_tiny_window_keeps_bar_non_negative() {
+        let layout = layout_chrome(
+            20.0,
+            40.0,
+            6.0,
+            50.0,
+            &[],
+            &[ChromeButton::Bookmark],
+            &[ChromeButton::Settings],
+        );
+        assert_eq!(layout.bar.w, 0.0);
+        assert_eq!(layout.text_end, layout.bar.x);
+    }
+
+    #[test]
+    fn test_layout_chrome_left_buttons_push_bar_right() {
+        let layout = layout_chrome(1000.0, 40.0, 6.0, 0.0, &[ChromeButton::Home], &[], &[]);
+        let (button, home) = layout.buttons[0];
+        assert_eq!(button, ChromeButton::Home);
+        assert_eq!(
+            home,
+            ChromeRect {
+                x: 6.0,
+                y: 6.0,
+                w: 28.0,
+                h: 28.0
             }
+        );
+        assert_eq!(layout.bar.x, 40.0);
+        assert_eq!(layout.bar.x + layout.bar.w, 994.0);
+    }
+
+    /// Avances factices : 10 px par caractère, « AV » et « To » rapprochés.
+    fn kerned_sample(text: &str) -> Vec<f32> {
+        let chars: Vec<char> = text.chars().collect();
+        kerned_advances(
+            &chars,
+            |_| 10.0,
+            |a, b| match (a, b) {
+                ('A', 'V') | ('V', 'A') => -3.0,
+                ('T', 'o') => -2.0,
+                _ => 0.0,
+            },
+        )
+    }
+
+    #[test]
+    fn test_kerned_advances_apply_pairs() {
+        assert_eq!(kerned_sample("AVA"), vec![7.0, 7.0, 10.0]);
+        assert_eq!(kerned_sample("To"), vec![8.0, 10.0]);
+        assert_eq!(kerned_sample("ab"), vec![10.0, 10.0]);
+        assert!(kerned_sample("").is_empty());
+    }
+
+    #[test]
+    fn test_cursor_offset_roundtrip_with_kerning() {
+        let advances = kerned_sample("AVATo.AV");
+        for offset in 0..=advances.len() {
+            // Position du curseur comme dans `draw`
+            let cursor_x: f32 = advances[..offset].iter().sum();
+            assert_eq!(char_offset_at(&advances, cursor_x), offset);
+            // Un clic légèrement décalé tombe sur la même frontière
+            assert_eq!(char_offset_at(&advances, cursor_x + 1.0), offset);
+            assert_eq!(char_offset_at(&advances, cursor_x - 1.0), offset);
         }
     }
 
     #[test]
-    fn test_space_has_zero_dimensions() {
-        let atlas = build_test_atlas();
-        let space = atlas.glyphs.get(&' ').expect("Space glyph missing");
-        assert_eq!(space.width, 0, "Space should have width 0");
-        assert_eq!(space.height, 0, "Space should have height 0");
-        assert!(space.advance_x > 0.0, "Space should have positive advance");
+    fn test_char_offset_at_uses_kerned_positions() {
+        // Sans crénage, x = 14 serait dans le premier glyphe (frontière à 10) ;
+        // avec « AV » rapprochés, la frontière 1 est à 7 et la 2 à 14.
+        let advances = kerned_sample("AVA");
+        assert_eq!(char_offset_at(&advances, 14.0), 2);
+        assert_eq!(char_offset_at(&[10.0, 10.0, 10.0], 14.0), 1);
     }
 
     #[test]
-    fn test_bytemuck_cast_slice_length() {
-        let data: [f32; 2] = [1.0, 2.0];
-        let bytes = bytemuck_cast_slice(&data);
-        assert_eq!(bytes.len(), 8); // 2 * 4 bytes
+    fn test_char_offset_at_clamps() {
+        let advances = kerned_sample("To");
+        assert_eq!(char_offset_at(&advances, -50.0), 0);
+        assert_eq!(char_offset_at(&advances, 500.0), 2);
+        assert_eq!(char_offset_at(&[], 12.0), 0);
     }
 
     #[test]
-    fn test_chrome_height_is_40() {
-        assert_eq!(CHROME_HEIGHT, 40);
+    fn test_find_bar_rect_below_chrome_on_the_right() {
+        let rect = find_bar_rect(1000.0, 40.0, 16.0);
+        assert_eq!(rect.y, 48.0);
+        assert_eq!(rect.x + rect.w, 992.0);
+        assert_eq!(rect.w, FIND_WIDTH);
+        assert!(rect.h > 16.0);
+    }
+
+    #[test]
+    fn test_dialog_rect_centered_below_chrome() {
+        let rect = dialog_rect(1000.0, 40.0, 16.0, 3);
+        assert_eq!(rect.w, DIALOG_WIDTH);
+        assert_eq!(rect.x, 260.0);
+        assert_eq!(rect.y, 40.0 + FIND_MARGIN);
+        assert_eq!(rect.h, 26.0 * 3.0 + STATUS_PAD * 4.0);
+        // Fenêtre étroite : le dialogue rétrécit sans déborder
+        let rect = dialog_rect(300.0, 40.0, 16.0, 2);
+        assert_eq!(rect.x, FIND_MARGIN);
+        assert_eq!(rect.x + rect.w, 300.0 - FIND_MARGIN);
+    }
+
+    #[test]
+    fn test_find_bar_rect_narrow_window() {
+        let rect = find_bar_rect(200.0, 40.0, 16.0);
+        assert_eq!(rect.x, FIND_MARGIN);
+        assert_eq!(rect.w, 200.0 - FIND_MARGIN * 2.0);
+        assert_eq!(find_bar_rect(4.0, 40.0, 16.0).w, 0.0);
+    }
+
+    #[test]
+    fn test_site_security_from_scheme() {
+        let security = |url: &str| SiteSecurity::for_url(&Url::parse(url).unwrap());
+        assert_eq!(security("https://example.com/"), SiteSecurity::Secure);
+        assert_eq!(security("wss://example.com/socket"), SiteSecurity::Secure);
+        assert_eq!(security("http://example.com/"), SiteSecurity::Insecure);
+        assert_eq!(security("data:text/html,hi"), SiteSecurity::Internal);
+        assert_eq!(security("file:///tmp/page.html"), SiteSecurity::Internal);
+        assert_eq!(SiteSecurity::default(), SiteSecurity::Internal);
+    }
+
+    #[test]
+    fn test_security_icon_rect_centered() {
+        let rect = security_icon_rect(20.0, 40.0);
+        assert_eq!(rect.x, 20.0);
+        assert_eq!(rect.y, 13.0);
+        assert!(rect.contains(27.0, 20.0));
+    }
+
+    #[test]
+    fn test_layout_chrome_reload_before_home() {
+        let layout = layout_chrome(
+            1000.0,
+            40.0,
+            6.0,
+            0.0,
+            &[ChromeButton::Reload, ChromeButton::Home],
+            &[],
+            &[],
+        );
+        let (reload_button, reload) = layout.buttons[0];
+        let (home_button, home) = layout.buttons[1];
+        assert_eq!(reload_button, ChromeButton::Reload);
+        assert_eq!(home_button, ChromeButton::Home);
+        assert!(reload.x + reload.w <= home.x);
+        assert!(home.x + home.w <= layout.bar.x);
+    }
+
+    #[test]
+    fn test_layout_chrome_bar_buttons_inside_bar() {
+        let layout = layout_chrome(
+            1000.0,
+            40.0,
+            6.0,
+            0.0,
+            &[],
+            &[ChromeButton::Bookmark],
+            &[ChromeButton::Settings],
+        );
+        let (_, star) = layout
+            .buttons
+            .iter()
+            .copied()
+            .find(|&(b, _)| b == ChromeButton::Bookmark)
+            .unwrap();
+        // L'étoile est calée contre le bord droit de la barre
+        assert_eq!(star.x + star.w, layout.bar.x + layout.bar.w);
+        assert_eq!(layout.text_end, star.x);
+    }
+
+    #[test]
+    fn test_chrome_rect_contains() {
+        let rect = ChromeRect {
+            x: 10.0,
+            y: 10.0,
+            w: 20.0,
+            h: 20.0,
+        };
+        assert!(rect.contains(10.0, 10.0));
+        assert!(rect.contains(29.9, 29.9));
+        assert!(!rect.contains(30.0, 15.0));
+        assert!(!rect.contains(5.0, 15.0));
+    }
+
+    // ── clamp_corner_radius ───────────────────────────────────────────
+
+    #[test]
+    fn test_clamp_corner_radius() {
+        assert_eq!(clamp_corner_radius(6.0, 200.0, 28.0), 6.0);
+        // Pilule au plus : demi-hauteur
+        assert_eq!(clamp_corner_radius(50.0, 200.0, 28.0), 14.0);
+        assert_eq!(clamp_corner_radius(-3.0, 200.0, 28.0), 0.0);
+        assert_eq!(clamp_corner_radius(6.0, 0.0, 0.0), 0.0);
+    }
+
+    // ── layout_menu ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_layout_menu_right_aligned_below_chrome() {
+        let anchor = ChromeRect {
+            x: 966.0,
+            y: 6.0,
+            w: 28.0,
+            h: 28.0,
+        };
+        let rows = layout_menu(anchor, 1000.0, 40.0, 200.0, 28.0);
+        assert_eq!(rows.len(), MenuItem::ALL.len());
+        assert_eq!(rows[0].x + rows[0].w, 994.0);
+        assert!(rows[0].y >= 40.0);
+        assert_eq!(rows[1].y - rows[0].y, 28.0);
+    }
+
+    #[test]
+    fn test_layout_menu_clamped_to_window() {
+        let anchor = ChromeRect {
+            x: 50.0,
+            y: 6.0,
+            w: 28.0,
+            h: 28.0,
+        };
+        let rows = layout_menu(anchor, 120.0, 40.0, 200.0, 28.0);
+        assert_eq!(rows[0].x, 0.0);
+        assert_eq!(rows[0].w, 120.0);
+    }
+
+    #[test]
+    fn test_layout_menu_min_width() {
+        let anchor = ChromeRect {
+            x: 966.0,
+            y: 6.0,
+            w: 28.0,
+            h: 28.0,
+        };
+        let rows = layout_menu(anchor, 1000.0, 40.0, 10.0, 28.0);
+        assert_eq!(rows[0].w, MENU_MIN_WIDTH);
+    }
+
+    // ── layout_context_menu ───────────────────────────────────────────
+
+    #[test]
+    fn test_layout_context_menu_at_click() {
+        let rows = layout_context_menu((100.0, 200.0), 1000.0, 800.0, 180.0, 28.0, 4);
+        assert_eq!(rows.len(), 4);
+        assert_eq!((rows[0].x, rows[0].y), (100.0, 200.0));
+        assert_eq!(rows[3].y, 200.0 + 3.0 * 28.0);
+    }
+
+    #[test]
+    fn test_layout_context_menu_stays_in_window() {
+        // Clic en bas à droite : le panneau passe à gauche et au-dessus
+        let rows = layout_context_menu((990.0, 790.0), 1000.0, 800.0, 180.0, 28.0, 4);
+        assert_eq!(rows[0].x + rows[0].w, 1000.0);
+        assert_eq!(rows[3].y + rows[3].h, 800.0);
+        // Fenêtre plus petite que le panneau : collé en haut à gauche
+        let rows = layout_context_menu((50.0, 50.0), 100.0, 60.0, 180.0, 28.0, 4);
+        assert_eq!((rows[0].x, rows[0].y, rows[0].w), (0.0, 0.0, 100.0));
+    }
+
+    // ── status_bubble_rect ────────────────────────────────────────────
+
+    #[test]
+    fn test_status_bubble_anchored_bottom_left() {
+        let (x, y, w, h) = status_bubble_rect(1280.0, 800.0, 100.0, 14.0, 40.0);
+        assert_eq!(x, 0.0);
+        assert_eq!(h, 14.0 + STATUS_PAD * 2.0);
+        assert_eq!(y, 800.0 - h);
+        assert_eq!(w, 100.0 + STATUS_PAD * 2.0);
+    }
+
+    #[test]
+    fn test_status_bubble_capped_at_half_width() {
+        let (_, _, w, _) = status_bubble_rect(1280.0, 800.0, 5000.0, 14.0, 40.0);
+        assert_eq!(w, 640.0);
+    }
+
+    #[test]
+    fn test_status_bubble_never_covers_chrome() {
+        // Fenêtre réduite à la hauteur du chrome : bulle de hauteur nulle
+        let (_, y, _, h) = status_bubble_rect(1280.0, 40.0, 100.0, 14.0, 40.0);
+        assert_eq!(h, 0.0);
+        assert_eq!(y, 40.0);
+    }
+
+    // ── layout_text ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_layout_short_text_fits() {
+        let layout = layout_text(&[10.0; 5], 100.0, 8.0, None);
+        assert_eq!(
+            layout,
+            TextLayout {
+                start: 0,
+                end: 5,
+                ellipsis: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_layout_long_text_ellipsized_on_whole_glyph() {
+        // 20 glyphs × 10px in 100px, ellipsis 8px → 9 glyphs (90 + 8 <= 100)
+        let layout = layout_text(&[10.0; 20], 100.0, 8.0, None);
+        assert_eq!(layout.start, 0);
+        assert_eq!(layout.end, 9);
+        assert!(layout.ellipsis);
+    }
+
+    #[test]
+    fn test_layout_cursor_at_end_scrolls_into_view() {
+        let layout = layout_text(&[10.0; 20], 100.0, 8.0, Some(20));
+        assert!(layout.start > 0, "Text should scroll left");
+        assert_eq!(layout.end, 20);
+        assert!(!layout.ellipsis);
+        let visible: f32 = 10.0 * (20 - layout.start) as f32;
+        assert!(visible <= 100.0);
+    }
+
+    #[test]
+    fn test_layout_cursor_never_hidden_by_ellipsis() {
+        for cursor in 0..=20 {
+            let layout = layout_text(&[10.0; 20], 100.0, 8.0, Some(cursor));
+            assert!(
+                cursor >= layout.start && cursor <= layout.end,
+                "cursor {cursor} outside drawn range {layout:?}"
+            );
+            let cursor_x = 10.0 * (cursor - layout.start) as f32;
+            let reserved = if layout.ellipsis { 8.0 } else { 0.0 };
+            assert!(
+                cursor_x + CURSOR_WIDTH <= 100.0 - reserved + f32::EPSILON,
+                "cursor {cursor} at {cursor_x} clipped in {layout:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_layout_cursor_at_start_keeps_beginning() {
+        let layout = layout_text(&[10.0; 20], 100.0, 8.0, Some(0));
+        assert_eq!(layout.start, 0);
+        assert!(layout.ellipsis);
+    }
+
+    #[test]
+    fn test_layout_empty_text() {
+        let layout = layout_text(&[], 100.0, 8.0, Some(0));
+        assert_eq!(layout.end, 0);
+        assert!(!layout.ellipsis);
     }
 }
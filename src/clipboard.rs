@@ -0,0 +1,126 @@
+//! Presse-papiers système, partagé par la page (copier/coller dans Servo), la
+//! barre d'URL et le menu contextuel.
+//!
+//! Une seule instance `arboard` vit pendant toute la session : sur X11 et
+//! Wayland, le texte copié n'existe que tant que son propriétaire est vivant,
+//! et plusieurs instances se disputent la possession de la sélection. Le
+//! système est derrière [`ClipboardBackend`] pour tester sans serveur
+//! d'affichage.
+
+use tracing::warn;
+
+/// Accès au presse-papiers. Texte brut seulement pour l'instant ; arboard
+/// sait aussi écrire du HTML (`set_html`), à ajouter ici quand Servo le
+/// transmettra.
+pub trait ClipboardBackend {
+    fn get_text(&mut self) -> Result<String, String>;
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+    fn clear(&mut self) -> Result<(), String>;
+}
+
+impl ClipboardBackend for arboard::Clipboard {
+    fn get_text(&mut self) -> Result<String, String> {
+        arboard::Clipboard::get_text(self).map_err(|e| e.to_string())
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        arboard::Clipboard::set_text(self, text).map_err(|e| e.to_string())
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        arboard::Clipboard::clear(self).map_err(|e| e.to_string())
+    }
+}
+
+/// Presse-papiers de la session. Sans backend (pas de serveur d'affichage),
+/// chaque accès échoue avec un message d'erreur.
+pub struct Clipboard {
+    backend: Option<Box<dyn ClipboardBackend>>,
+}
+
+impl Clipboard {
+    /// Presse-papiers du système (avertissement s'il est inaccessible).
+    pub fn system() -> Self {
+        let backend = arboard::Clipboard::new()
+            .inspect_err(|e| warn!(error = %e, "Clipboard unavailable"))
+            .ok()
+            .map(|clipboard| Box::new(clipboard) as Box<dyn ClipboardBackend>);
+        Self { backend }
+    }
+
+    pub fn with_backend(backend: impl ClipboardBackend + 'static) -> Self {
+        Self {
+            backend: Some(Box::new(backend)),
+        }
+    }
+
+    /// Texte du presse-papiers.
+    pub fn get_text(&mut self) -> Result<String, String> {
+        self.backend()?.get_text()
+    }
+
+    /// Remplace le contenu par `text`.
+    pub fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.backend()?.set_text(text)
+    }
+
+    /// Vide le presse-papiers.
+    pub fn clear(&mut self) -> Result<(), String> {
+        self.backend()?.clear()
+    }
+
+    fn backend(&mut self) -> Result<&mut (dyn ClipboardBackend + 'static), String> {
+        self.backend
+            .as_deref_mut()
+            .ok_or_else(|| "Clipboard unavailable".to_string())
+    }
+}
+
+/// Presse-papiers en mémoire, pour les tests (feature `mock-clipboard` hors
+/// des tests du crate).
+#[cfg(any(test, feature = "mock-clipboard"))]
+#[derive(Debug, Default)]
+pub struct MockClipboard {
+    text: Option<String>,
+}
+
+#[cfg(any(test, feature = "mock-clipboard"))]
+impl ClipboardBackend for MockClipboard {
+    fn get_text(&mut self) -> Result<String, String> {
+        self.text
+            .clone()
+            .ok_or_else(|| "Clipboard empty".to_string())
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.text = Some(text.to_string());
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.text = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_get_clear() {
+        let mut clipboard = Clipboard::with_backend(MockClipboard::default());
+        assert!(clipboard.get_text().is_err());
+        clipboard.set_text("https://example.com/").unwrap();
+        assert_eq!(clipboard.get_text().unwrap(), "https://example.com/");
+        clipboard.clear().unwrap();
+        assert!(clipboard.get_text().is_err());
+    }
+
+    #[test]
+    fn test_unavailable_clipboard_errors() {
+        let mut clipboard = Clipboard { backend: None };
+        assert_eq!(clipboard.get_text().unwrap_err(), "Clipboard unavailable");
+        assert!(clipboard.set_text("x").is_err());
+    }
+}
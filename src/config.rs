@@ -6,18 +6,34 @@
 //!
 //! ## Config file search order
 //!
+//! 0. `--config <path>` command-line flag: the file must exist and parse,
+//!    otherwise startup fails ([`Config::load_from`]). Settings are saved
+//!    back to it.
 //! 1. `SURIBROWS_CONFIG` environment variable (explicit override)
 //! 2. Next to the executable (`<exe_dir>/config.toml`)
 //! 3. Platform config directory (`%APPDATA%\SuriBrows\config.toml` on Windows)
 //! 4. Current working directory (`./config.toml`)
 //! 5. No file found → `Config::default()`
+//!
+//! ## Environment overrides
+//!
+//! Single values can then be overridden without a file, for containers and
+//! CI: `SURIBROWS_<SECTION>_<KEY>` variables (`SURIBROWS_WINDOW_WIDTH=1920`,
+//! `SURIBROWS_PRIVACY_DISABLE_WEBRTC=false`,
+//! `SURIBROWS_CHROME_COLORS_TEXT=1,1,1,1`) win over the file and are never
+//! saved to it ([`Config::apply_env_overrides`]).
 
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fmt;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use serde::{Deserialize, Serialize};
-use tracing::{info, warn};
+use serde::{Deserialize, Deserializer, Serialize};
+use tracing::{debug, info, warn};
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Config structs
@@ -33,14 +49,87 @@ pub struct Config {
     pub search: SearchConfig,
     pub servo: ServoConfig,
     pub privacy: PrivacyConfig,
+    /// Shortcut overrides, action name → chord(s). Actions not listed keep
+    /// their default shortcuts (see `keybindings::Action::default_chords`).
+    pub keybindings: BTreeMap<String, KeyChords>,
+}
+
+/// One or several key chords bound to an action in `[keybindings]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyChords {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyChords {
+    /// Chords as strings, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let chords: &[String] = match self {
+            KeyChords::One(chord) => std::slice::from_ref(chord),
+            KeyChords::Many(chords) => chords,
+        };
+        chords.iter().map(String::as_str)
+    }
 }
 
 /// General application settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct GeneralConfig {
+    /// Page loaded at startup when no URL is given on the command line.
     pub default_url: String,
+    /// Page opened by the home button and Alt+Home.
+    pub homepage: String,
+    /// Window title. `{title}` is replaced by the page title; without it the
+    /// page title is appended as `"<window_title> — <page>"`.
     pub window_title: String,
+    /// Mouse thumb buttons (back/forward) navigate history instead of being
+    /// passed to the page.
+    pub mouse_history_buttons: bool,
+    /// Pixels scrolled per mouse wheel notch (line-based deltas only;
+    /// touchpad pixel deltas are used as-is).
+    pub scroll_line_height: f32,
+    /// Reverse both scroll axes ("natural" scrolling).
+    pub invert_scroll: bool,
+    /// Folder offered by save dialogs. Empty = the system Downloads folder.
+    pub download_dir: String,
+    /// Port of Servo's devtools server (Firefox DevTools). 0 = disabled.
+    /// Read at startup only.
+    pub devtools_port: u16,
+    /// Forward page console messages (`console.log`, JS errors) to the log.
+    pub log_console: bool,
+    /// Quit when the last page closes itself (`window.close()`) instead of
+    /// opening the homepage.
+    pub exit_on_last_close: bool,
+    /// Locale used to pick regional filter lists (`fr-FR`, `de`). Empty =
+    /// the system locale (`LC_ALL`, `LC_MESSAGES`, `LANG`).
+    pub locale: String,
+    /// Reload the whole of `config.toml` when it changes: settings that can
+    /// change at runtime apply at once, the others are logged as needing a
+    /// restart. Without it only the chrome colors are reloaded.
+    pub watch_config: bool,
+}
+
+impl GeneralConfig {
+    /// Page opened by the home button and Alt+Home: `homepage`, or
+    /// `default_url` when no homepage is configured.
+    pub fn homepage_url(&self) -> &str {
+        match self.homepage.trim() {
+            "" => &self.default_url,
+            homepage => homepage,
+        }
+    }
+
+    /// Folder offered by save dialogs: `download_dir` if set, otherwise the
+    /// platform Downloads folder (`None` if it cannot be determined).
+    pub fn download_dir(&self) -> Option<PathBuf> {
+        if self.download_dir.trim().is_empty() {
+            platform_download_dir()
+        } else {
+            Some(PathBuf::from(self.download_dir.trim()))
+        }
+    }
 }
 
 /// Window dimensions.
@@ -49,6 +138,10 @@ pub struct GeneralConfig {
 pub struct WindowConfig {
     pub width: u32,
     pub height: u32,
+    /// Smallest inner width the window can be resized to (logical pixels).
+    pub min_width: u32,
+    /// Smallest inner height the window can be resized to (logical pixels).
+    pub min_height: u32,
 }
 
 /// Chrome (URL bar area) appearance.
@@ -60,7 +153,26 @@ pub struct ChromeConfig {
     pub text_left_pad: f32,
     pub bar_margin: f32,
     pub bar_h_pad: f32,
+    /// Corner radius of the URL bar, in pixels (0 = square corners).
+    pub corner_radius: f32,
+    /// Palette selection: follow the OS, or force dark/light.
+    pub theme: ChromeTheme,
+    /// Dark palette (also used when the OS theme is unknown).
     pub colors: ChromeColors,
+    /// Light palette. Missing keys fall back to the built-in light values.
+    #[serde(deserialize_with = "deserialize_light_colors")]
+    pub light_colors: ChromeColors,
+}
+
+/// Which chrome palette to use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChromeTheme {
+    /// Follow the OS light/dark preference, switching at runtime.
+    #[default]
+    Auto,
+    Dark,
+    Light,
 }
 
 /// RGBA colors for the chrome UI (values 0.0–1.0).
@@ -73,6 +185,12 @@ pub struct ChromeColors {
     pub cursor: [f32; 4],
     pub bar_background: [f32; 4],
     pub bar_border: [f32; 4],
+    /// Shield badge showing the number of blocked requests.
+    pub badge: [f32; 4],
+    /// Padlock shown before the URL of https pages.
+    pub secure: [f32; 4],
+    /// Warning sign shown before the URL of plain http pages.
+    pub insecure: [f32; 4],
 }
 
 /// Search engine configuration.
@@ -106,6 +224,139 @@ pub struct PrivacyConfig {
     pub disable_bluetooth: bool,
     pub disable_notifications: bool,
     pub disable_webrtc: bool,
+    /// Ask before granting a site permission (camera, notifications, …).
+    /// When false, requests without a remembered decision are denied.
+    pub permission_prompts: bool,
+    /// Hide ad elements with the `##` rules of the filter lists, in addition
+    /// to blocking ad requests.
+    pub cosmetic_filtering: bool,
+    /// Load `http://` pages over HTTPS; when that fails, offer to continue
+    /// over plain HTTP for the host (remembered for the session only).
+    pub https_only: bool,
+    /// Before each navigation, delete the cookies of sites that were only
+    /// ever loaded inside other sites' pages this session, so embedded
+    /// trackers can't follow the user from site to site. Sites opened in a
+    /// tab this session keep theirs, but a site logged into in an earlier
+    /// session loses its cookies (and login) if it is embedded somewhere
+    /// before being opened again. Off by default.
+    pub block_third_party_cookies: bool,
+    /// Tell sites not to sell or share the user's data (Global Privacy
+    /// Control, exposed to pages as `navigator.globalPrivacyControl`).
+    pub send_gpc: bool,
+    /// Send the older Do Not Track signal (`navigator.doNotTrack`).
+    /// Off by default: few sites honour it and it adds fingerprinting surface.
+    pub send_dnt: bool,
+    /// Low-bandwidth mode: block every image (a transparent placeholder is
+    /// served so layout holds). Applies even on allowlisted sites.
+    pub block_images: bool,
+    /// Block web fonts; pages fall back to system fonts.
+    pub block_fonts: bool,
+    /// Block audio and video.
+    pub block_media: bool,
+    /// Link schemes (`mailto`, `magnet`, …) handed to the system without
+    /// asking first. Any other external scheme always asks.
+    pub external_scheme_allowlist: Vec<String>,
+    /// Hours between checks for new versions of `filter_lists`. 0 = never
+    /// (missing lists are still downloaded once).
+    pub filter_update_hours: u32,
+    /// Adblock filter list subscriptions (`[[privacy.filter_lists]]`). Only
+    /// enabled lists are loaded; files of other lists are left alone.
+    pub filter_lists: Vec<FilterListConfig>,
+    /// Subscribe to the regional lists matching the locale (EasyList
+    /// Germany, Liste FR…) at every startup. Without it this only happens
+    /// on the first run, before any `config.toml` exists.
+    pub auto_regional_lists: bool,
+    /// Phishing/malware host list: a file path (relative to the config
+    /// directory) or an http(s) URL, downloaded and refreshed like the filter
+    /// lists. Navigations to a listed host show a warning page. Empty =
+    /// disabled.
+    pub security_blocklist: String,
+    /// Extra query parameters removed from page URLs, on top of the built-in
+    /// tracking list (`utm_*`, `fbclid`, `gclid`, …). A trailing `*` matches
+    /// a prefix.
+    pub strip_params: Vec<String>,
+    /// Referrer policy forced on every page, over its own `<meta>` tags and
+    /// `Referrer-Policy` header: `"strict-origin"` (origin only, none from
+    /// https to http), `"no-referrer"` (full referrer to the same origin,
+    /// none to others), or `"default"` (page decides).
+    pub referrer_policy: ReferrerPolicy,
+    /// Hosts (and their subdomains) whose pages load without external
+    /// scripts. Toggled for the current host with Ctrl+Shift+J.
+    pub no_js_hosts: Vec<String>,
+    /// File receiving one line per blocked request (time, page, blocked URL,
+    /// request type), for debugging filter lists. Relative paths are
+    /// resolved against the config directory. Empty = disabled.
+    pub block_log: String,
+}
+
+/// Referrer sent with cross-origin requests (`[privacy] referrer_policy`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReferrerPolicy {
+    /// Leave the page's own referrer policy alone.
+    Default,
+    #[default]
+    StrictOrigin,
+    NoReferrer,
+}
+
+/// One adblock filter list subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterListConfig {
+    /// Name shown on the settings page.
+    pub name: String,
+    /// Download URL. Empty for a local list that is never updated.
+    pub url: String,
+    pub enabled: bool,
+    /// File name in `resources/filters/`. Defaults to the last segment of
+    /// `url`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Language code this regional list was added for automatically
+    /// (see `auto_regional_lists`). Absent for lists added by hand.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_enabled_for: Option<String>,
+}
+
+impl FilterListConfig {
+    fn new(name: &str, url: &str, enabled: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            enabled,
+            file: None,
+            auto_enabled_for: None,
+        }
+    }
+}
+
+impl Default for FilterListConfig {
+    fn default() -> Self {
+        Self::new("", "", true)
+    }
+}
+
+/// Subscriptions of a new profile: EasyList and EasyPrivacy enabled, the
+/// annoyance list offered but disabled.
+pub fn default_filter_lists() -> Vec<FilterListConfig> {
+    vec![
+        FilterListConfig::new(
+            "EasyList",
+            "https://easylist.to/easylist/easylist.txt",
+            true,
+        ),
+        FilterListConfig::new(
+            "EasyPrivacy",
+            "https://easylist.to/easylist/easyprivacy.txt",
+            true,
+        ),
+        FilterListConfig::new(
+            "Fanboy's Annoyance List",
+            "https://easylist.to/easylist/fanboy-annoyance.txt",
+            false,
+        ),
+    ]
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -119,7 +370,17 @@ impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
             default_url: "https://example.com".to_string(),
+            homepage: "https://example.com".to_string(),
             window_title: "SuriBrows".to_string(),
+            mouse_history_buttons: true,
+            scroll_line_height: 38.0,
+            invert_scroll: false,
+            download_dir: String::new(),
+            devtools_port: 0,
+            log_console: false,
+            exit_on_last_close: false,
+            locale: String::new(),
+            watch_config: false,
         }
     }
 }
@@ -129,6 +390,8 @@ impl Default for WindowConfig {
         Self {
             width: 1280,
             height: 800,
+            min_width: 320,
+            min_height: 240,
         }
     }
 }
@@ -141,7 +404,10 @@ impl Default for ChromeConfig {
             text_left_pad: 12.0,
             bar_margin: 6.0,
             bar_h_pad: 8.0,
+            corner_radius: 6.0,
+            theme: ChromeTheme::Auto,
             colors: ChromeColors::default(),
+            light_colors: ChromeColors::light(),
         }
     }
 }
@@ -155,10 +421,97 @@ impl Default for ChromeColors {
             cursor: [1.0, 1.0, 1.0, 1.0],
             bar_background: [0.13, 0.13, 0.13, 1.0],
             bar_border: [0.3, 0.3, 0.3, 1.0],
+            badge: [0.98, 0.45, 0.18, 1.0],
+            secure: [0.45, 0.82, 0.5, 1.0],
+            insecure: [0.96, 0.36, 0.32, 1.0],
         }
     }
 }
 
+impl ChromeColors {
+    /// Built-in light palette, used when the OS (or the config) asks for it.
+    pub fn light() -> Self {
+        Self {
+            background: [0.93, 0.93, 0.93, 1.0],
+            background_focused: [0.97, 0.97, 0.97, 1.0],
+            text: [0.1, 0.1, 0.1, 1.0],
+            cursor: [0.0, 0.0, 0.0, 1.0],
+            bar_background: [1.0, 1.0, 1.0, 1.0],
+            bar_border: [0.75, 0.75, 0.75, 1.0],
+            badge: [0.9, 0.38, 0.1, 1.0],
+            secure: [0.12, 0.55, 0.22, 1.0],
+            insecure: [0.8, 0.16, 0.12, 1.0],
+        }
+    }
+
+    /// Purple-tinted copy used by private windows (`--private`): the chrome
+    /// backgrounds and borders are mixed with purple, text and icons keep
+    /// their colors.
+    pub fn private_tint(&self) -> Self {
+        const PURPLE: [f32; 4] = [0.45, 0.2, 0.65, 1.0];
+        const AMOUNT: f32 = 0.35;
+        let mix = |color: [f32; 4]| {
+            let mut mixed = color;
+            for (channel, purple) in mixed.iter_mut().zip(PURPLE).take(3) {
+                *channel += (purple - *channel) * AMOUNT;
+            }
+            mixed
+        };
+        Self {
+            background: mix(self.background),
+            background_focused: mix(self.background_focused),
+            bar_background: mix(self.bar_background),
+            bar_border: mix(self.bar_border),
+            ..self.clone()
+        }
+    }
+}
+
+/// Deserializes `[chrome.light_colors]` on top of [`ChromeColors::light`], so a
+/// partial table only overrides the keys it names (the struct-level
+/// `#[serde(default)]` would otherwise fill the gaps with the dark palette).
+fn deserialize_light_colors<'de, D>(deserializer: D) -> Result<ChromeColors, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let overrides = toml::Table::deserialize(deserializer)?;
+    let mut merged = match toml::Value::try_from(ChromeColors::light()).map_err(D::Error::custom)? {
+        toml::Value::Table(table) => table,
+        _ => return Err(D::Error::custom("light palette is not a table")),
+    };
+    merged.extend(overrides);
+    toml::Value::Table(merged)
+        .try_into()
+        .map_err(D::Error::custom)
+}
+
+impl ChromeConfig {
+    /// Returns the palette to use given the OS preference
+    /// (`None` when the platform can't tell).
+    pub fn palette(&self, os_prefers_dark: Option<bool>) -> &ChromeColors {
+        let dark = match self.theme {
+            ChromeTheme::Dark => true,
+            ChromeTheme::Light => false,
+            // Unknown OS theme keeps the historical dark chrome.
+            ChromeTheme::Auto => os_prefers_dark.unwrap_or(true),
+        };
+        if dark {
+            &self.colors
+        } else {
+            &self.light_colors
+        }
+    }
+
+    /// Tints both palettes for a private window (see
+    /// [`ChromeColors::private_tint`]).
+    pub fn tint_private(&mut self) {
+        self.colors = self.colors.private_tint();
+        self.light_colors = self.light_colors.private_tint();
+    }
+}
+
 impl Default for SearchConfig {
     fn default() -> Self {
         Self {
@@ -178,6 +531,17 @@ impl Default for ServoConfig {
     }
 }
 
+impl PrivacyConfig {
+    /// Path of `block_log`, relative paths resolved against the config
+    /// directory. `None` if logging is disabled.
+    pub fn block_log_path(&self) -> Option<PathBuf> {
+        match self.block_log.trim() {
+            "" => None,
+            path => Some(data_dir().join(path)),
+        }
+    }
+}
+
 impl Default for PrivacyConfig {
     fn default() -> Self {
         Self {
@@ -187,6 +551,24 @@ impl Default for PrivacyConfig {
             disable_bluetooth: true,
             disable_notifications: true,
             disable_webrtc: true,
+            permission_prompts: false,
+            cosmetic_filtering: true,
+            https_only: true,
+            block_third_party_cookies: false,
+            send_gpc: true,
+            send_dnt: false,
+            block_images: false,
+            block_fonts: false,
+            block_media: false,
+            external_scheme_allowlist: Vec::new(),
+            filter_update_hours: 24,
+            filter_lists: default_filter_lists(),
+            auto_regional_lists: false,
+            security_blocklist: String::new(),
+            strip_params: Vec::new(),
+            referrer_policy: ReferrerPolicy::default(),
+            no_js_hosts: Vec::new(),
+            block_log: String::new(),
         }
     }
 }
@@ -195,7 +577,100 @@ impl Default for PrivacyConfig {
 // Config loading and saving
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// Variable naming the config file (search order step 1).
+const CONFIG_ENV_VAR: &str = "SURIBROWS_CONFIG";
+
+/// Config file named by `--config`, set once at startup by
+/// [`set_config_path`]. Wins over the search order for every later load,
+/// save and watch.
+static CONFIG_FLAG: OnceLock<PathBuf> = OnceLock::new();
+
+/// Error loading an explicitly named config file.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// `--config` given without a path.
+    MissingPath,
+    /// The file is missing or cannot be read.
+    Unreadable { path: PathBuf, source: io::Error },
+    /// The file is not valid TOML for [`Config`].
+    Invalid {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPath => write!(f, "--config needs a file path (--config <path>)"),
+            Self::Unreadable { path, source } => {
+                write!(f, "cannot read config file {}: {source}", path.display())
+            }
+            Self::Invalid { path, source } => {
+                write!(f, "invalid config file {}: {source}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::MissingPath => None,
+            Self::Unreadable { source, .. } => Some(source),
+            Self::Invalid { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Value of the `--config <path>` or `--config=<path>` flag in `args`
+/// (program name excluded). The last occurrence wins.
+pub fn config_flag(args: &[String]) -> Result<Option<PathBuf>, ConfigError> {
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            match args.next() {
+                Some(value) if !value.starts_with("--") => path = Some(PathBuf::from(value)),
+                _ => return Err(ConfigError::MissingPath),
+            }
+        } else if let Some(value) = arg.strip_prefix("--config=") {
+            if value.is_empty() {
+                return Err(ConfigError::MissingPath);
+            }
+            path = Some(PathBuf::from(value));
+        }
+    }
+    Ok(path)
+}
+
+/// Makes `path` (from `--config`) the config file of the session. Only the
+/// first call has an effect.
+pub fn set_config_path(path: PathBuf) {
+    let _ = CONFIG_FLAG.set(path);
+}
+
 impl Config {
+    /// Loads the file named by `--config` when there is one: unlike
+    /// [`Config::load`], a missing, unreadable or invalid file is an error,
+    /// not a silent fallback to defaults. Without it, same as
+    /// [`Config::load`].
+    pub fn load_from(explicit: Option<&Path>) -> Result<Self, ConfigError> {
+        let Some(path) = explicit else {
+            return Ok(Self::load());
+        };
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Unreadable {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let config = parse_config(&content).map_err(|source| ConfigError::Invalid {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        info!(path = %path.display(), "Configuration loaded");
+        Ok(config)
+    }
+
     /// Loads configuration from a TOML file. Never panics — returns defaults
     /// if no file is found or if parsing fails.
     pub fn load() -> Self {
@@ -223,28 +698,99 @@ impl Config {
         }
     }
 
-    /// Saves configuration to the platform config directory.
-    /// Creates the directory if it doesn't exist.
-    pub fn save(&self) -> io::Result<()> {
-        let path = save_path();
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
+    /// Loads the file [`Config::save`] writes, as the base of a
+    /// read-modify-write (see [`Config::load_existing`]).
+    pub fn load_saved() -> Result<Self, ConfigError> {
+        Self::load_existing(&save_path())
+    }
+
+    /// Loads `path` without environment overrides. A missing file gives the
+    /// defaults; an unreadable or invalid one is an error, so the caller
+    /// does not overwrite it with defaults.
+    pub fn load_existing(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
         }
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Unreadable {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        parse_config(&content).map_err(|source| ConfigError::Invalid {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Saves configuration to the `--config` file, or else to the platform
+    /// config directory. Creates the directory if it doesn't exist.
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(&save_path())
+    }
+
+    /// Saves configuration as TOML to `path`, creating its directory. The
+    /// file is replaced atomically (see [`write_atomic`]).
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
         let content = toml::to_string_pretty(self).map_err(io::Error::other)?;
-        fs::write(&path, content)?;
+        write_atomic(path, content.as_bytes())?;
         info!(path = %path.display(), "Configuration saved");
         Ok(())
     }
 }
 
+/// Filter lists of the running session once the settings page has saved
+/// `after` over `before`: the saved lists, plus the session's own lists
+/// that were never on disk (environment override, regional lists added in
+/// private mode).
+pub fn session_filter_lists(
+    session: &[FilterListConfig],
+    before: &[FilterListConfig],
+    after: &[FilterListConfig],
+) -> Vec<FilterListConfig> {
+    let same = |a: &FilterListConfig, b: &FilterListConfig| {
+        a.url == b.url && (!a.url.is_empty() || a.name == b.name)
+    };
+    let mut lists = after.to_vec();
+    lists.extend(
+        session
+            .iter()
+            .filter(|list| !before.iter().chain(after).any(|saved| same(saved, list)))
+            .cloned(),
+    );
+    lists
+}
+
+/// Parses edited config file contents. Like [`parse_chrome_section`],
+/// invalid TOML is an error rather than a fallback to defaults.
+pub fn parse_config(content: &str) -> Result<Config, toml::de::Error> {
+    toml::from_str(content)
+}
+
+/// Re-parses the `[chrome]` section from edited config file contents.
+///
+/// Unlike [`Config::load`], invalid TOML is reported as an error so the
+/// caller can keep the current palette instead of falling back to defaults.
+pub fn parse_chrome_section(content: &str) -> Result<ChromeConfig, toml::de::Error> {
+    toml::from_str::<Config>(content).map(|config| config.chrome)
+}
+
+/// Explicitly named config file: the `--config` flag, else the
+/// `SURIBROWS_CONFIG` variable if it names an existing file. The flag's
+/// path is returned even if missing: loading it reports the error.
+fn explicit_config_path(flag: Option<&Path>, env: Option<&OsStr>) -> Option<PathBuf> {
+    if let Some(path) = flag {
+        return Some(path.to_path_buf());
+    }
+    env.map(PathBuf::from).filter(|path| path.is_file())
+}
+
 /// Searches for a config file in the standard locations.
-fn find_config_path() -> Option<PathBuf> {
-    // 1. Explicit env var override
-    if let Ok(path) = std::env::var("SURIBROWS_CONFIG") {
-        let p = PathBuf::from(path);
-        if p.is_file() {
-            return Some(p);
-        }
+pub fn find_config_path() -> Option<PathBuf> {
+    // 0–1. `--config` flag, then env var override
+    let env = std::env::var_os(CONFIG_ENV_VAR);
+    if let Some(path) =
+        explicit_config_path(CONFIG_FLAG.get().map(PathBuf::as_path), env.as_deref())
+    {
+        return Some(path);
     }
 
     // 2. Next to the executable
@@ -275,13 +821,57 @@ fn find_config_path() -> Option<PathBuf> {
     None
 }
 
-/// Returns the platform-specific save path for the config file.
+/// Returns the save path for the config file: the `--config` file, or the
+/// platform-specific location.
 fn save_path() -> PathBuf {
+    if let Some(path) = CONFIG_FLAG.get() {
+        return path.clone();
+    }
     platform_config_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("config.toml")
 }
 
+/// Directory holding `config.toml` and the browser's data files
+/// (bookmarks, …): the one of the config file in use, or the save location.
+pub fn data_dir() -> PathBuf {
+    find_config_path()
+        .as_deref()
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| platform_config_dir().unwrap_or_else(|| PathBuf::from(".")))
+}
+
+/// Directory for files the browser can rebuild at any time (compiled adblock
+/// engine, …): `%LOCALAPPDATA%\SuriBrows\cache` on Windows,
+/// `$XDG_CACHE_HOME/suribrows` or `~/.cache/suribrows` elsewhere. Falls back
+/// to a `cache` folder in [`data_dir`].
+pub fn cache_dir() -> PathBuf {
+    platform_cache_dir().unwrap_or_else(|| data_dir().join("cache"))
+}
+
+/// Writes `contents` to `path` atomically: a sibling temp file is written
+/// then renamed over the target, so a crash never leaves a half-written file.
+/// Each call uses its own temp name: concurrent writers (threads or other
+/// instances) never write into each other's temp file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    static NEXT_TMP: AtomicUsize = AtomicUsize::new(0);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
 /// Returns the platform config directory without adding a dependency.
 fn platform_config_dir() -> Option<PathBuf> {
     #[cfg(windows)]
@@ -299,6 +889,209 @@ fn platform_config_dir() -> Option<PathBuf> {
     }
 }
 
+/// Platform cache directory, like [`platform_config_dir`].
+fn platform_cache_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    {
+        std::env::var("LOCALAPPDATA")
+            .ok()
+            .map(|local| PathBuf::from(local).join("SuriBrows").join("cache"))
+    }
+    #[cfg(not(windows))]
+    {
+        std::env::var("XDG_CACHE_HOME")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .or_else(|| std::env::var("HOME").ok().map(|h| format!("{h}/.cache")))
+            .map(|dir| PathBuf::from(dir).join("suribrows"))
+    }
+}
+
+/// Platform Downloads folder: `%USERPROFILE%\Downloads` on Windows,
+/// `$XDG_DOWNLOAD_DIR` or `~/Downloads` elsewhere.
+fn platform_download_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    {
+        std::env::var("USERPROFILE")
+            .ok()
+            .map(|home| PathBuf::from(home).join("Downloads"))
+    }
+    #[cfg(not(windows))]
+    {
+        std::env::var("XDG_DOWNLOAD_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var("HOME")
+                    .ok()
+                    .map(|h| PathBuf::from(h).join("Downloads"))
+            })
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Environment variable overrides
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Prefix of the per-key override variables (`SURIBROWS_WINDOW_WIDTH`).
+pub const ENV_PREFIX: &str = "SURIBROWS_";
+
+/// A `SURIBROWS_*` variable that could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvOverrideError {
+    /// No config key matches the variable name.
+    UnknownKey { var: String },
+    /// The value does not parse as the key's type, or the key cannot be set
+    /// from a variable (`filter_lists`).
+    InvalidValue { var: String, value: String },
+}
+
+impl fmt::Display for EnvOverrideError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKey { var } => write!(f, "{var}: no such config key"),
+            Self::InvalidValue { var, value } => {
+                write!(f, "{var}: invalid value {value:?} for this config key")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvOverrideError {}
+
+/// Environment variables with UTF-8 names and values, for
+/// [`Config::apply_env_overrides`].
+pub fn env_vars() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+}
+
+impl Config {
+    /// Applies the `SURIBROWS_<SECTION>_<KEY>` variables of `vars` on top of
+    /// this config (`SURIBROWS_CHROME_COLORS_TEXT` for a nested key). Values
+    /// are parsed after the key's type: `true`/`false` (also `1`/`0`,
+    /// `yes`/`no`, `on`/`off`), numbers, strings as-is, and comma-separated
+    /// lists (`0.1,0.2,0.3,1.0` for a color). Variables that don't apply are
+    /// skipped and returned; `SURIBROWS_CONFIG` is not a key.
+    pub fn apply_env_overrides<I>(&mut self, vars: I) -> Vec<EnvOverrideError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut vars: Vec<(String, String)> = vars
+            .into_iter()
+            .filter(|(var, _)| var.starts_with(ENV_PREFIX) && var != CONFIG_ENV_VAR)
+            .collect();
+        let mut errors = Vec::new();
+        if vars.is_empty() {
+            return errors;
+        }
+        vars.sort();
+        // `Config` always serializes
+        let Ok(mut root) = toml::Value::try_from(&*self) else {
+            return errors;
+        };
+        for (var, value) in vars {
+            let name = var[ENV_PREFIX.len()..].to_ascii_lowercase();
+            let Some(path) = root.as_table().and_then(|table| env_key_path(table, &name)) else {
+                errors.push(EnvOverrideError::UnknownKey { var });
+                continue;
+            };
+            // Parsed after the current value's type, then checked by
+            // deserializing: ranges (`u32`), enum names (`theme`)…
+            let applied = path
+                .iter()
+                .try_fold(&root, |node, key| node.get(key))
+                .and_then(|current| parse_env_value(current, &value))
+                .and_then(|parsed| {
+                    let mut candidate = root.clone();
+                    let slot = path
+                        .iter()
+                        .try_fold(&mut candidate, |node, key| node.get_mut(key))?;
+                    *slot = parsed;
+                    let config = candidate.clone().try_into::<Config>().ok()?;
+                    Some((candidate, config))
+                });
+            match applied {
+                Some((candidate, config)) => {
+                    root = candidate;
+                    *self = config;
+                    debug!(key = path.join("."), "Config value overridden by {var}");
+                }
+                None => errors.push(EnvOverrideError::InvalidValue { var, value }),
+            }
+        }
+        errors
+    }
+}
+
+/// Path of the value key named by `name` (`window_width` → `window.width`)
+/// in `table`. Key names contain underscores too, so each key is tried as a
+/// prefix.
+fn env_key_path(table: &toml::Table, name: &str) -> Option<Vec<String>> {
+    for (key, value) in table {
+        match value {
+            toml::Value::Table(inner) => {
+                let Some(rest) = name
+                    .strip_prefix(key.as_str())
+                    .and_then(|rest| rest.strip_prefix('_'))
+                else {
+                    continue;
+                };
+                if let Some(mut path) = env_key_path(inner, rest) {
+                    path.insert(0, key.clone());
+                    return Some(path);
+                }
+            }
+            _ if key == name => return Some(vec![key.clone()]),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses `raw` as the same type as `current`.
+fn parse_env_value(current: &toml::Value, raw: &str) -> Option<toml::Value> {
+    use toml::Value;
+    match current {
+        Value::Boolean(_) => match raw.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Some(Value::Boolean(true)),
+            "false" | "0" | "no" | "off" => Some(Value::Boolean(false)),
+            _ => None,
+        },
+        Value::Integer(_) => raw.trim().parse().ok().map(Value::Integer),
+        Value::Float(_) => raw
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .map(Value::Float),
+        Value::String(_) => Some(Value::String(raw.to_string())),
+        Value::Array(items) => {
+            // Element type from the current list; an empty one holds strings
+            let item_type = items
+                .first()
+                .cloned()
+                .unwrap_or_else(|| Value::String(String::new()));
+            if matches!(item_type, Value::Table(_) | Value::Array(_)) {
+                return None;
+            }
+            let list = raw.trim();
+            let list = list
+                .strip_prefix('[')
+                .and_then(|list| list.strip_suffix(']'))
+                .unwrap_or(list);
+            list.split(',')
+                .map(|item| item.trim().trim_matches('"'))
+                .filter(|item| !item.is_empty())
+                .map(|item| parse_env_value(&item_type, item))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array)
+        }
+        Value::Datetime(_) | Value::Table(_) => None,
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Query param serialization (for HTML settings save)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -308,7 +1101,14 @@ impl Config {
     /// Unknown keys are silently ignored; missing keys use defaults.
     pub fn from_query_params(query: &str) -> Self {
         let mut config = Config::default();
+        config.apply_query_params(query);
+        config
+    }
 
+    /// Applies URL query parameters on top of this config. Settings the form
+    /// does not cover (keybindings, colors, …) keep their current values.
+    pub fn apply_query_params(&mut self, query: &str) {
+        let config = self;
         for pair in query.split('&') {
             let mut parts = pair.splitn(2, '=');
             let key = parts.next().unwrap_or("");
@@ -317,7 +1117,28 @@ impl Config {
 
             match key {
                 "default_url" => config.general.default_url = value,
+                "homepage" => config.general.homepage = value,
                 "window_title" => config.general.window_title = value,
+                "download_dir" => config.general.download_dir = value,
+                "devtools_port" => {
+                    if let Ok(v) = value.parse() {
+                        config.general.devtools_port = v;
+                    }
+                }
+                "mouse_history_buttons" => {
+                    config.general.mouse_history_buttons = value == "true";
+                }
+                "scroll_line_height" => {
+                    if let Ok(v) = value.parse::<f32>()
+                        && v.is_finite()
+                        && v > 0.0
+                    {
+                        config.general.scroll_line_height = v;
+                    }
+                }
+                "invert_scroll" => config.general.invert_scroll = value == "true",
+                "log_console" => config.general.log_console = value == "true",
+                "exit_on_last_close" => config.general.exit_on_last_close = value == "true",
                 "window_width" => {
                     if let Ok(v) = value.parse() {
                         config.window.width = v;
@@ -357,11 +1178,36 @@ impl Config {
                 "disable_bluetooth" => config.privacy.disable_bluetooth = value == "true",
                 "disable_notifications" => config.privacy.disable_notifications = value == "true",
                 "disable_webrtc" => config.privacy.disable_webrtc = value == "true",
+                "permission_prompts" => config.privacy.permission_prompts = value == "true",
+                "cosmetic_filtering" => config.privacy.cosmetic_filtering = value == "true",
+                "https_only" => config.privacy.https_only = value == "true",
+                "block_third_party_cookies" => {
+                    config.privacy.block_third_party_cookies = value == "true"
+                }
+                "send_gpc" => config.privacy.send_gpc = value == "true",
+                "send_dnt" => config.privacy.send_dnt = value == "true",
+                "block_images" => config.privacy.block_images = value == "true",
+                "block_fonts" => config.privacy.block_fonts = value == "true",
+                "block_media" => config.privacy.block_media = value == "true",
+                "auto_regional_lists" => config.privacy.auto_regional_lists = value == "true",
+                "filter_update_hours" => {
+                    if let Ok(v) = value.parse() {
+                        config.privacy.filter_update_hours = v;
+                    }
+                }
+                // Indexes of the enabled lists, comma-separated
+                "filter_lists_enabled" => {
+                    let enabled: Vec<usize> = value
+                        .split(',')
+                        .filter_map(|index| index.trim().parse().ok())
+                        .collect();
+                    for (index, list) in config.privacy.filter_lists.iter_mut().enumerate() {
+                        list.enabled = enabled.contains(&index);
+                    }
+                }
                 _ => {}
             }
         }
-
-        config
     }
 }
 
@@ -397,14 +1243,23 @@ fn hex_val(b: u8) -> Option<u8> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::TempDir;
 
     #[test]
     fn test_default_matches_original_values() {
         let c = Config::default();
         assert_eq!(c.general.default_url, "https://example.com");
         assert_eq!(c.general.window_title, "SuriBrows");
+        assert!(c.general.mouse_history_buttons);
+        assert_eq!(c.general.scroll_line_height, 38.0);
+        assert!(!c.general.invert_scroll);
+        assert_eq!(c.general.devtools_port, 0);
+        assert!(!c.general.log_console);
+        assert!(!c.general.exit_on_last_close);
         assert_eq!(c.window.width, 1280);
         assert_eq!(c.window.height, 800);
+        assert_eq!(c.window.min_width, 320);
+        assert_eq!(c.window.min_height, 240);
         assert_eq!(c.chrome.height, 40);
         assert_eq!(c.chrome.font_size, 16.0);
         assert_eq!(c.search.engine_url, "https://duckduckgo.com/?q=");
@@ -412,6 +1267,20 @@ mod tests {
         assert!(c.servo.user_agent.is_empty());
         assert!(c.privacy.enforce_tls);
         assert!(c.privacy.disable_webrtc);
+        assert!(!c.privacy.permission_prompts);
+        assert!(c.privacy.cosmetic_filtering);
+        assert!(c.privacy.https_only);
+        assert!(!c.privacy.block_third_party_cookies);
+        assert!(c.privacy.send_gpc);
+        assert!(!c.privacy.send_dnt);
+        assert!(!c.privacy.block_images);
+        assert!(!c.privacy.block_fonts);
+        assert!(!c.privacy.block_media);
+        assert!(!c.privacy.auto_regional_lists);
+        assert!(c.general.locale.is_empty());
+        assert!(!c.general.watch_config);
+        assert_eq!(c.privacy.filter_update_hours, 24);
+        assert_eq!(c.privacy.filter_lists, default_filter_lists());
     }
 
     #[test]
@@ -422,6 +1291,38 @@ mod tests {
         assert!(config.privacy.enforce_tls);
     }
 
+    #[test]
+    fn test_download_dir_override() {
+        let mut general = GeneralConfig {
+            download_dir: " /tmp/saved ".to_string(),
+            ..GeneralConfig::default()
+        };
+        assert_eq!(general.download_dir(), Some(PathBuf::from("/tmp/saved")));
+        general.download_dir.clear();
+        // Empty falls back to the platform folder (whatever it is here)
+        assert_ne!(general.download_dir(), Some(PathBuf::from("")));
+    }
+
+    #[test]
+    fn test_cache_dir_separate_from_data_dir() {
+        assert_ne!(cache_dir(), data_dir());
+    }
+
+    #[test]
+    fn test_keybindings_parse_string_or_list() {
+        let toml = r#"
+[keybindings]
+focus_url_bar = "Ctrl+K"
+reload = ["Ctrl+R", "F5"]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let chords: Vec<&str> = config.keybindings["focus_url_bar"].iter().collect();
+        assert_eq!(chords, ["Ctrl+K"]);
+        let chords: Vec<&str> = config.keybindings["reload"].iter().collect();
+        assert_eq!(chords, ["Ctrl+R", "F5"]);
+        assert!(Config::default().keybindings.is_empty());
+    }
+
     #[test]
     fn test_partial_toml_fills_defaults() {
         let toml = r#"
@@ -434,6 +1335,234 @@ width = 1920
         assert_eq!(config.chrome.height, 40); // default
     }
 
+    #[test]
+    fn test_external_scheme_allowlist_parse() {
+        let toml = "[privacy]\nexternal_scheme_allowlist = [\"mailto\", \"magnet\"]";
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            config.privacy.external_scheme_allowlist,
+            ["mailto", "magnet"]
+        );
+        assert!(config.privacy.enforce_tls); // default
+        assert!(
+            Config::default()
+                .privacy
+                .external_scheme_allowlist
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_security_blocklist_parse() {
+        assert!(Config::default().privacy.security_blocklist.is_empty());
+        let toml = "[privacy]\nsecurity_blocklist = \"https://lists.example/phishing.txt\"";
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            config.privacy.security_blocklist,
+            "https://lists.example/phishing.txt"
+        );
+    }
+
+    #[test]
+    fn test_strip_params_parse() {
+        assert!(Config::default().privacy.strip_params.is_empty());
+        let toml = "[privacy]\nstrip_params = [\"ref\", \"aff_*\"]";
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.privacy.strip_params, ["ref", "aff_*"]);
+    }
+
+    #[test]
+    fn test_referrer_policy_parse() {
+        assert_eq!(
+            Config::default().privacy.referrer_policy,
+            ReferrerPolicy::StrictOrigin
+        );
+        let toml = "[privacy]\nreferrer_policy = \"no-referrer\"";
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.privacy.referrer_policy, ReferrerPolicy::NoReferrer);
+        assert!(toml::from_str::<Config>("[privacy]\nreferrer_policy = \"unsafe-url\"").is_err());
+    }
+
+    #[test]
+    fn test_no_js_hosts_parse() {
+        assert!(Config::default().privacy.no_js_hosts.is_empty());
+        let toml = "[privacy]\nno_js_hosts = [\"example.com\", \"news.test\"]";
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.privacy.no_js_hosts, ["example.com", "news.test"]);
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_config_flag_forms() {
+        let path = |args_: &[&str]| config_flag(&args(args_)).unwrap();
+        assert_eq!(path(&["https://example.com", "--private"]), None);
+        assert_eq!(
+            path(&["--config", "/etc/suri.toml", "example.com"]),
+            Some(PathBuf::from("/etc/suri.toml"))
+        );
+        assert_eq!(
+            path(&["--private", "--config=my config.toml"]),
+            Some(PathBuf::from("my config.toml"))
+        );
+        // Last occurrence wins
+        assert_eq!(
+            path(&["--config=a.toml", "--config", "b.toml"]),
+            Some(PathBuf::from("b.toml"))
+        );
+        for missing in [
+            &["--config"][..],
+            &["--config", "--private"],
+            &["--config="],
+        ] {
+            assert!(matches!(
+                config_flag(&args(missing)),
+                Err(ConfigError::MissingPath)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_config_flag_wins_over_env_var() {
+        let dir = TempDir::new("config-flag");
+        let from_env = dir.join("env.toml");
+        fs::write(&from_env, "").unwrap();
+        let from_flag = dir.join("flag.toml");
+        let missing = dir.join("missing.toml");
+
+        assert_eq!(
+            explicit_config_path(Some(&from_flag), Some(from_env.as_os_str())),
+            Some(from_flag.clone())
+        );
+        // The flag's file is kept even when missing: loading it fails loudly
+        assert_eq!(
+            explicit_config_path(Some(&missing), Some(from_env.as_os_str())),
+            Some(missing.clone())
+        );
+        assert_eq!(
+            explicit_config_path(None, Some(from_env.as_os_str())),
+            Some(from_env)
+        );
+        // A missing env var file is skipped, as before
+        assert_eq!(explicit_config_path(None, Some(missing.as_os_str())), None);
+        assert_eq!(explicit_config_path(None, None), None);
+    }
+
+    #[test]
+    fn test_explicit_config_file_errors() {
+        let dir = TempDir::new("config-load");
+
+        let missing = dir.join("missing.toml");
+        let err = Config::load_from(Some(&missing)).unwrap_err();
+        assert!(matches!(err, ConfigError::Unreadable { .. }));
+        assert!(err.to_string().contains("missing.toml"), "{err}");
+
+        let invalid = dir.join("invalid.toml");
+        fs::write(&invalid, "[window]\nwidth = \"wide\"").unwrap();
+        let err = Config::load_from(Some(&invalid)).unwrap_err();
+        assert!(matches!(err, ConfigError::Invalid { .. }));
+        assert!(err.to_string().starts_with("invalid config file"), "{err}");
+
+        let valid = dir.join("valid.toml");
+        fs::write(&valid, "[window]\nwidth = 1600").unwrap();
+        assert_eq!(Config::load_from(Some(&valid)).unwrap().window.width, 1600);
+    }
+
+    #[test]
+    fn test_block_log_parse() {
+        assert_eq!(Config::default().privacy.block_log_path(), None);
+        let absolute = std::env::temp_dir().join("suribrows-blocked.log");
+        let config = Config {
+            privacy: PrivacyConfig {
+                block_log: absolute.display().to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(config.privacy.block_log_path(), Some(absolute));
+        let toml = "[privacy]\nblock_log = \"blocked.log\"";
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            config.privacy.block_log_path(),
+            Some(data_dir().join("blocked.log"))
+        );
+    }
+
+    #[test]
+    fn test_default_filter_lists() {
+        let lists = default_filter_lists();
+        let enabled: Vec<&str> = lists
+            .iter()
+            .filter(|list| list.enabled)
+            .map(|list| list.name.as_str())
+            .collect();
+        assert_eq!(enabled, ["EasyList", "EasyPrivacy"]);
+        assert!(lists.iter().all(|list| list.url.starts_with("https://")));
+        assert!(lists.iter().all(|list| list.file.is_none()));
+
+        // Written as `[[privacy.filter_lists]]` and read back unchanged
+        let serialized = toml::to_string_pretty(&Config::default()).unwrap();
+        assert!(serialized.contains("[[privacy.filter_lists]]"));
+        assert!(!serialized.contains("file ="));
+        let config: Config = toml::from_str(&serialized).unwrap();
+        assert_eq!(config.privacy.filter_lists, lists);
+    }
+
+    #[test]
+    fn test_filter_lists_parse() {
+        let toml = r#"
+[privacy]
+filter_update_hours = 12
+
+[[privacy.filter_lists]]
+name = "EasyList"
+url = "https://easylist.to/easylist/easylist.txt"
+enabled = false
+
+[[privacy.filter_lists]]
+name = "My rules"
+file = "custom.txt"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let lists = &config.privacy.filter_lists;
+        assert_eq!(lists.len(), 2);
+        assert!(!lists[0].enabled);
+        assert_eq!(lists[0].file, None);
+        // Missing fields: enabled, local list without URL
+        assert!(lists[1].enabled);
+        assert!(lists[1].url.is_empty());
+        assert_eq!(lists[1].file.as_deref(), Some("custom.txt"));
+        assert_eq!(config.privacy.filter_update_hours, 12);
+    }
+
+    #[test]
+    fn test_filter_lists_enabled_query_param() {
+        let mut config = Config::default();
+        config.apply_query_params("filter_lists_enabled=1,2");
+        let enabled: Vec<bool> = config
+            .privacy
+            .filter_lists
+            .iter()
+            .map(|list| list.enabled)
+            .collect();
+        assert_eq!(enabled, [false, true, true]);
+
+        config.apply_query_params("filter_lists_enabled=");
+        assert!(config.privacy.filter_lists.iter().all(|list| !list.enabled));
+        // Absent: unchanged
+        config.apply_query_params("enforce_tls=true");
+        assert!(config.privacy.filter_lists.iter().all(|list| !list.enabled));
+    }
+
+    #[test]
+    fn test_corner_radius_parse() {
+        let config: Config = toml::from_str("[chrome]\ncorner_radius = 0.0").unwrap();
+        assert_eq!(config.chrome.corner_radius, 0.0);
+        assert_eq!(Config::default().chrome.corner_radius, 6.0);
+    }
+
     #[test]
     fn test_color_arrays_parse() {
         let toml = r#"
@@ -446,6 +1575,98 @@ background = [0.1, 0.2, 0.3, 1.0]
         assert_eq!(config.chrome.colors.text, [0.93, 0.93, 0.93, 1.0]);
     }
 
+    #[test]
+    fn test_security_colors_parse() {
+        let toml = r#"
+[chrome.colors]
+insecure = [1.0, 0.0, 0.0, 1.0]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.chrome.colors.insecure, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(config.chrome.colors.secure, ChromeColors::default().secure);
+    }
+
+    #[test]
+    fn test_theme_defaults_to_auto() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.chrome.theme, ChromeTheme::Auto);
+    }
+
+    #[test]
+    fn test_theme_parses_lowercase() {
+        let config: Config = toml::from_str("[chrome]\ntheme = \"light\"").unwrap();
+        assert_eq!(config.chrome.theme, ChromeTheme::Light);
+        assert!(toml::from_str::<Config>("[chrome]\ntheme = \"sepia\"").is_err());
+    }
+
+    #[test]
+    fn test_partial_light_colors_keep_light_defaults() {
+        let toml = r#"
+[chrome.light_colors]
+text = [0.2, 0.0, 0.0, 1.0]
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.chrome.light_colors.text, [0.2, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            config.chrome.light_colors.background,
+            ChromeColors::light().background
+        );
+    }
+
+    #[test]
+    fn test_palette_selection() {
+        let mut chrome = ChromeConfig::default();
+        let dark = chrome.colors.background;
+        let light = chrome.light_colors.background;
+        assert_eq!(chrome.palette(Some(true)).background, dark);
+        assert_eq!(chrome.palette(Some(false)).background, light);
+        assert_eq!(chrome.palette(None).background, dark);
+
+        chrome.theme = ChromeTheme::Light;
+        assert_eq!(chrome.palette(Some(true)).background, light);
+        chrome.theme = ChromeTheme::Dark;
+        assert_eq!(chrome.palette(Some(false)).background, dark);
+    }
+
+    #[test]
+    fn test_private_tint_leans_purple() {
+        let mut chrome = ChromeConfig::default();
+        let normal = chrome.clone();
+        chrome.tint_private();
+        for (tinted, base) in [
+            (&chrome.colors, &normal.colors),
+            (&chrome.light_colors, &normal.light_colors),
+        ] {
+            let [r, g, b, a] = tinted.background;
+            assert_ne!(tinted.background, base.background);
+            assert!(
+                b > g && r > g,
+                "background should lean purple: {:?}",
+                tinted.background
+            );
+            assert_eq!(a, base.background[3]);
+            assert_eq!(tinted.text, base.text);
+            assert_eq!(tinted.secure, base.secure);
+        }
+    }
+
+    #[test]
+    fn test_parse_chrome_section_valid() {
+        let toml = r#"
+[chrome.colors]
+background = [0.5, 0.5, 0.5, 1.0]
+"#;
+        let chrome = parse_chrome_section(toml).unwrap();
+        assert_eq!(chrome.colors.background, [0.5, 0.5, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_parse_chrome_section_invalid_is_error() {
+        // Half-typed edit: must not silently become the default palette
+        assert!(parse_chrome_section("[chrome.colors]\nbackground = [0.5, ").is_err());
+        assert!(parse_chrome_section("[chrome.colors]\nbackground = \"red\"").is_err());
+    }
+
     #[test]
     fn test_full_toml_roundtrip() {
         let config = Config::default();
@@ -454,6 +1675,12 @@ background = [0.1, 0.2, 0.3, 1.0]
         assert_eq!(deserialized.window.width, config.window.width);
         assert_eq!(deserialized.chrome.height, config.chrome.height);
         assert_eq!(deserialized.search.engine_url, config.search.engine_url);
+        assert_eq!(deserialized.general.homepage, config.general.homepage);
+        assert_eq!(deserialized.chrome.theme, config.chrome.theme);
+        assert_eq!(
+            deserialized.chrome.light_colors.background,
+            config.chrome.light_colors.background
+        );
     }
 
     #[test]
@@ -466,9 +1693,12 @@ background = [0.1, 0.2, 0.3, 1.0]
 
     #[test]
     fn test_from_query_params_booleans() {
-        let config = Config::from_query_params("enforce_tls=false&disable_webrtc=true");
+        let config = Config::from_query_params(
+            "enforce_tls=false&disable_webrtc=true&permission_prompts=true",
+        );
         assert!(!config.privacy.enforce_tls);
         assert!(config.privacy.disable_webrtc);
+        assert!(config.privacy.permission_prompts);
     }
 
     #[test]
@@ -478,12 +1708,151 @@ background = [0.1, 0.2, 0.3, 1.0]
         assert_eq!(config.search.engine_url, "https://google.com/?q=");
     }
 
+    #[test]
+    fn test_empty_homepage_falls_back_to_default_url() {
+        let mut general = GeneralConfig {
+            default_url: "https://servo.org".to_string(),
+            homepage: "  ".to_string(),
+            ..GeneralConfig::default()
+        };
+        assert_eq!(general.homepage_url(), "https://servo.org");
+        general.homepage = "https://example.org".to_string();
+        assert_eq!(general.homepage_url(), "https://example.org");
+    }
+
+    #[test]
+    fn test_homepage_separate_from_default_url() {
+        let toml = r#"
+[general]
+homepage = "https://servo.org"
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.general.homepage, "https://servo.org");
+        assert_eq!(config.general.default_url, "https://example.com");
+
+        let config = Config::from_query_params("homepage=https%3A%2F%2Fservo.org%2F");
+        assert_eq!(config.general.homepage, "https://servo.org/");
+    }
+
     #[test]
     fn test_from_query_params_unknown_keys_ignored() {
         let config = Config::from_query_params("unknown_key=value&window_width=999");
         assert_eq!(config.window.width, 999);
     }
 
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_overrides_each_type() {
+        let mut config = Config::default();
+        let errors = config.apply_env_overrides(env(&[
+            ("SURIBROWS_PRIVACY_DISABLE_WEBRTC", "false"),
+            ("SURIBROWS_PRIVACY_SEND_DNT", "YES"),
+            ("SURIBROWS_WINDOW_WIDTH", "1920"),
+            ("SURIBROWS_SERVO_CACHE_SIZE", "-1"),
+            ("SURIBROWS_GENERAL_SCROLL_LINE_HEIGHT", "60.5"),
+            ("SURIBROWS_CHROME_FONT_SIZE", "16"),
+            ("SURIBROWS_SEARCH_ENGINE_URL", "https://search.example/?q="),
+            ("SURIBROWS_SERVO_USER_AGENT", "Agent/1.0 (X11, Linux)"),
+            ("SURIBROWS_CHROME_COLORS_TEXT", "1, 0.5, 0, 1"),
+            (
+                "SURIBROWS_CHROME_LIGHT_COLORS_BADGE",
+                "[0.1, 0.2, 0.3, 1.0]",
+            ),
+            (
+                "SURIBROWS_PRIVACY_NO_JS_HOSTS",
+                "example.com,\"news.example\"",
+            ),
+            ("SURIBROWS_CHROME_THEME", "light"),
+            ("SURIBROWS_PRIVACY_REFERRER_POLICY", "no-referrer"),
+        ]));
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(!config.privacy.disable_webrtc);
+        assert!(config.privacy.send_dnt);
+        assert_eq!(config.window.width, 1920);
+        assert_eq!(config.servo.cache_size, -1);
+        assert_eq!(config.general.scroll_line_height, 60.5);
+        assert_eq!(config.chrome.font_size, 16.0);
+        assert_eq!(config.search.engine_url, "https://search.example/?q=");
+        assert_eq!(config.servo.user_agent, "Agent/1.0 (X11, Linux)");
+        assert_eq!(config.chrome.colors.text, [1.0, 0.5, 0.0, 1.0]);
+        assert_eq!(config.chrome.light_colors.badge, [0.1, 0.2, 0.3, 1.0]);
+        assert_eq!(config.privacy.no_js_hosts, ["example.com", "news.example"]);
+        assert_eq!(config.chrome.theme, ChromeTheme::Light);
+        assert_eq!(config.privacy.referrer_policy, ReferrerPolicy::NoReferrer);
+        // Untouched keys keep their values
+        assert_eq!(config.window.height, Config::default().window.height);
+    }
+
+    #[test]
+    fn test_env_overrides_win_over_file() {
+        let mut config: Config = toml::from_str(
+            "[window]\nwidth = 800\nheight = 600\n[privacy]\ndisable_webrtc = true\n",
+        )
+        .unwrap();
+        let errors = config.apply_env_overrides(env(&[
+            ("SURIBROWS_WINDOW_WIDTH", "1920"),
+            ("SURIBROWS_PRIVACY_DISABLE_WEBRTC", "0"),
+            // Not config keys
+            ("SURIBROWS_CONFIG", "/etc/suribrows.toml"),
+            ("HOME", "/root"),
+        ]));
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(config.window.width, 1920);
+        assert_eq!(config.window.height, 600);
+        assert!(!config.privacy.disable_webrtc);
+    }
+
+    #[test]
+    fn test_env_overrides_reject_unknown_and_invalid() {
+        let mut config = Config::default();
+        let errors = config.apply_env_overrides(env(&[
+            ("SURIBROWS_WINDOW_DEPTH", "3"),
+            ("SURIBROWS_CHROME_COLORS", "1,1,1,1"),
+            ("SURIBROWS_KEYBINDINGS_RELOAD", "F5"),
+            ("SURIBROWS_WINDOW_WIDTH", "wide"),
+            ("SURIBROWS_WINDOW_HEIGHT", "-5"),
+            ("SURIBROWS_PRIVACY_ENFORCE_TLS", "maybe"),
+            ("SURIBROWS_GENERAL_SCROLL_LINE_HEIGHT", "inf"),
+            ("SURIBROWS_CHROME_COLORS_TEXT", "1,red,0,1"),
+            ("SURIBROWS_CHROME_THEME", "sepia"),
+            ("SURIBROWS_PRIVACY_FILTER_LISTS", ""),
+            ("SURIBROWS_SERVO_CACHE_SIZE", "1024"),
+        ]));
+        let unknown: Vec<String> = errors
+            .iter()
+            .filter_map(|e| match e {
+                EnvOverrideError::UnknownKey { var } => Some(var.clone()),
+                EnvOverrideError::InvalidValue { .. } => None,
+            })
+            .collect();
+        assert_eq!(
+            unknown,
+            [
+                "SURIBROWS_CHROME_COLORS",
+                "SURIBROWS_KEYBINDINGS_RELOAD",
+                "SURIBROWS_WINDOW_DEPTH"
+            ]
+        );
+        assert_eq!(errors.len(), 10);
+        assert!(errors.contains(&EnvOverrideError::InvalidValue {
+            var: "SURIBROWS_WINDOW_HEIGHT".to_string(),
+            value: "-5".to_string(),
+        }));
+        // Rejected values leave the config as it was; valid ones still apply
+        let defaults = Config::default();
+        assert_eq!(config.window.width, defaults.window.width);
+        assert_eq!(config.window.height, defaults.window.height);
+        assert_eq!(config.chrome.colors.text, defaults.chrome.colors.text);
+        assert_eq!(config.chrome.theme, ChromeTheme::Auto);
+        assert_eq!(config.privacy.filter_lists, defaults.privacy.filter_lists);
+        assert_eq!(config.servo.cache_size, 1024);
+    }
+
     #[test]
     fn test_url_decode() {
         assert_eq!(url_decode("hello+world"), "hello world");
@@ -494,6 +1863,73 @@ background = [0.1, 0.2, 0.3, 1.0]
         assert_eq!(url_decode("no+encoding+needed"), "no encoding needed");
     }
 
+    #[test]
+    fn test_write_atomic_concurrent_writers() {
+        let dir = TempDir::new("write-atomic");
+        let path = dir.join("state.json");
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    write_atomic(&path, format!("{i}").repeat(1000).as_bytes())
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+        // One writer wins, in full; no temp file is left behind
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.len(), 1000);
+        let first = &contents[..1];
+        assert_eq!(contents, first.repeat(1000));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_session_filter_lists_keep_session_only_lists() {
+        let easylist = FilterListConfig::new("EasyList", "https://a.example/easylist.txt", true);
+        let regional = FilterListConfig::new("Liste FR", "https://a.example/liste_fr.txt", true);
+        let local = FilterListConfig::new("Local", "", true);
+        let before = vec![easylist.clone(), local.clone()];
+        let session = vec![easylist.clone(), local.clone(), regional.clone()];
+        // EasyList unchecked and the local list removed on the settings page
+        let after = vec![FilterListConfig {
+            enabled: false,
+            ..easylist.clone()
+        }];
+        assert_eq!(
+            session_filter_lists(&session, &before, &after),
+            [after[0].clone(), regional]
+        );
+    }
+
+    #[test]
+    fn test_load_existing_refuses_invalid_file() {
+        let dir = TempDir::new("config-existing");
+        let path = dir.join("config.toml");
+        assert_eq!(
+            Config::load_existing(&path).unwrap().general.default_url,
+            "https://example.com"
+        );
+
+        fs::write(
+            &path,
+            "[general]\ndefault_url = \"https://start.example\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config::load_existing(&path).unwrap().general.default_url,
+            "https://start.example"
+        );
+
+        fs::write(&path, "[general\ndefault_url = ").unwrap();
+        assert!(matches!(
+            Config::load_existing(&path),
+            Err(ConfigError::Invalid { .. })
+        ));
+    }
+
     #[test]
     fn test_save_path_not_empty() {
         let path = save_path();
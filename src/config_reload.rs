@@ -0,0 +1,203 @@
+//! Rechargement à chaud de tout `config.toml` (`[general] watch_config`).
+//!
+//! À chaque modification détectée par [`crate::config_watcher`], le fichier
+//! est relu en entier et comparé à la dernière version appliquée :
+//! [`diff`] liste les clés modifiées (`chrome.colors.text`,
+//! `search.engine_url`…) et les range selon [`reload_kind`]. Les réglages
+//! lus à chaque usage ou faciles à remplacer sur le thread principal
+//! ([`LIVE_KEYS`]) prennent effet aussitôt ; pour les autres (taille de la
+//! fenêtre, préférences Servo…), un avertissement indique qu'il faut
+//! redémarrer.
+//!
+//! Sans `watch_config`, seules les couleurs du chrome sont rechargées.
+
+use toml::Value;
+
+use crate::config::Config;
+
+/// Clés (ou sections) appliquées sans redémarrage. Une clé est couverte par
+/// une entrée égale ou parente (`chrome.colors` couvre
+/// `chrome.colors.text`).
+pub const LIVE_KEYS: &[&str] = &[
+    "chrome.colors",
+    "chrome.light_colors",
+    "chrome.theme",
+    "chrome.font_size",
+    "chrome.text_left_pad",
+    "chrome.bar_margin",
+    "chrome.bar_h_pad",
+    "chrome.corner_radius",
+    "search.engine_url",
+    "general.scroll_line_height",
+    "general.invert_scroll",
+    "privacy.filter_lists",
+    "privacy.no_js_hosts",
+];
+
+/// Prise en compte d'une clé modifiée.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadKind {
+    /// Appliquée aussitôt.
+    Live,
+    /// Lue au démarrage seulement.
+    Restart,
+}
+
+/// Prise en compte de la clé `key` (chemin pointé).
+pub fn reload_kind(key: &str) -> ReloadKind {
+    let covered = LIVE_KEYS.iter().any(|live| {
+        key.strip_prefix(live)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    });
+    if covered {
+        ReloadKind::Live
+    } else {
+        ReloadKind::Restart
+    }
+}
+
+/// Clés modifiées entre deux configurations, rangées par prise en compte.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    pub live: Vec<String>,
+    pub restart: Vec<String>,
+}
+
+impl ConfigChanges {
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty() && self.restart.is_empty()
+    }
+
+    /// `true` si une clé appliquée à chaud est `prefix` ou se trouve dessous.
+    pub fn touches(&self, prefix: &str) -> bool {
+        self.live.iter().any(|key| {
+            key.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    }
+}
+
+/// Compare `old` et `new` clé par clé. Un tableau (`filter_lists`…) compte
+/// comme une seule clé.
+pub fn diff(old: &Config, new: &Config) -> ConfigChanges {
+    let mut keys = Vec::new();
+    match (Value::try_from(old), Value::try_from(new)) {
+        (Ok(old), Ok(new)) => changed_keys("", &old, &new, &mut keys),
+        // Ne devrait pas arriver : `Config` se sérialise toujours
+        _ => keys.push(String::new()),
+    }
+    let mut changes = ConfigChanges::default();
+    for key in keys {
+        match reload_kind(&key) {
+            ReloadKind::Live => changes.live.push(key),
+            ReloadKind::Restart => changes.restart.push(key),
+        }
+    }
+    changes
+}
+
+/// Ajoute à `keys` les chemins où `old` et `new` diffèrent.
+fn changed_keys(path: &str, old: &Value, new: &Value, keys: &mut Vec<String>) {
+    let (Value::Table(old), Value::Table(new)) = (old, new) else {
+        if old != new {
+            keys.push(path.to_string());
+        }
+        return;
+    };
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let key = if path.is_empty() {
+            name.clone()
+        } else {
+            format!("{path}.{name}")
+        };
+        match (old.get(name), new.get(name)) {
+            (Some(old), Some(new)) => changed_keys(&key, old, new, keys),
+            // Option absente d'un côté (`Option` non sérialisée)
+            _ => keys.push(key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classification_table() {
+        let table = [
+            ("chrome.colors.background", ReloadKind::Live),
+            ("chrome.light_colors.text", ReloadKind::Live),
+            ("chrome.theme", ReloadKind::Live),
+            ("chrome.font_size", ReloadKind::Live),
+            ("chrome.corner_radius", ReloadKind::Live),
+            ("chrome.height", ReloadKind::Restart),
+            ("search.engine_url", ReloadKind::Live),
+            ("general.scroll_line_height", ReloadKind::Live),
+            ("general.invert_scroll", ReloadKind::Live),
+            ("general.default_url", ReloadKind::Restart),
+            ("general.watch_config", ReloadKind::Restart),
+            ("window.width", ReloadKind::Restart),
+            ("window.height", ReloadKind::Restart),
+            ("servo.cache_size", ReloadKind::Restart),
+            ("servo.user_agent", ReloadKind::Restart),
+            ("privacy.filter_lists", ReloadKind::Live),
+            ("privacy.no_js_hosts", ReloadKind::Live),
+            ("privacy.enforce_tls", ReloadKind::Restart),
+            // Préfixe de nom sans être une section parente
+            ("chrome.colors_extra", ReloadKind::Restart),
+            ("", ReloadKind::Restart),
+        ];
+        for (key, kind) in table {
+            assert_eq!(reload_kind(key), kind, "{key}");
+        }
+    }
+
+    #[test]
+    fn test_diff_lists_changed_keys() {
+        let old = Config::default();
+        assert!(diff(&old, &old.clone()).is_empty());
+
+        let mut new = old.clone();
+        new.chrome.colors.text = [1.0, 0.0, 0.0, 1.0];
+        new.search.engine_url = "https://search.example/?q=".to_string();
+        new.window.width = 1600;
+        new.servo.cache_size = 1;
+        new.privacy.filter_lists[2].enabled = true;
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes.live,
+            [
+                "chrome.colors.text",
+                "privacy.filter_lists",
+                "search.engine_url"
+            ]
+        );
+        assert_eq!(changes.restart, ["servo.cache_size", "window.width"]);
+        assert!(changes.touches("chrome"));
+        assert!(changes.touches("search.engine_url"));
+        assert!(!changes.touches("general"));
+    }
+
+    #[test]
+    fn test_diff_of_parsed_files() {
+        let old = crate::config::parse_config("[general]\nscroll_line_height = 38.0\n").unwrap();
+        let new = crate::config::parse_config(
+            "[general]\nscroll_line_height = 60.0\ninvert_scroll = true\n\
+             [privacy]\nno_js_hosts = [\"example.com\"]\n",
+        )
+        .unwrap();
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes.live,
+            [
+                "general.invert_scroll",
+                "general.scroll_line_height",
+                "privacy.no_js_hosts"
+            ]
+        );
+        assert!(changes.restart.is_empty());
+    }
+}
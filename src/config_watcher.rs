@@ -0,0 +1,161 @@
+//! Surveillance de `config.toml` pour le rechargement à chaud.
+//!
+//! Un thread d'arrière-plan relit périodiquement le fichier de configuration
+//! résolu au démarrage et compare son empreinte (date de modification, taille
+//! et hash du contenu). Quand elle change, le contenu est transmis au
+//! callback, qui le renvoie typiquement vers la boucle Winit via le
+//! [`crate::servo_glue::Waker`].
+//!
+//! La date seule ne suffit pas : certains systèmes de fichiers ne la
+//! retiennent qu'à la seconde près, et deux enregistrements dans la même
+//! seconde passeraient inaperçus. Le hash du contenu les distingue.
+//!
+//! On utilise du polling plutôt qu'un watcher natif (inotify, ReadDirectoryChangesW) :
+//! relire quelques kilo-octets toutes les 500 ms est négligeable, et cela évite
+//! une dépendance de plus ainsi que les subtilités des éditeurs qui remplacent
+//! le fichier (écriture atomique via renommage) au lieu de le modifier.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use tracing::{info, warn};
+
+/// Intervalle de polling par défaut.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Lance un thread qui appelle `on_change` avec le nouveau contenu du fichier
+/// à chaque modification détectée.
+///
+/// Le callback retourne `false` pour arrêter la surveillance (par exemple
+/// quand la boucle d'événements est fermée).
+pub fn watch_file<F>(path: PathBuf, interval: Duration, mut on_change: F)
+where
+    F: FnMut(&str) -> bool + Send + 'static,
+{
+    let spawned = thread::Builder::new()
+        .name("config-watcher".to_string())
+        .spawn(move || {
+            info!(path = %path.display(), "Watching config file for changes");
+            let mut last = fs::read(&path)
+                .ok()
+                .map(|content| Fingerprint::of(&path, &content));
+            loop {
+                thread::sleep(interval);
+                // Fichier absent (remplacement en cours) : attendre qu'il revienne.
+                let Ok(content) = fs::read(&path) else {
+                    continue;
+                };
+                let fingerprint = Fingerprint::of(&path, &content);
+                if last.as_ref() == Some(&fingerprint) {
+                    continue;
+                }
+                last = Some(fingerprint);
+
+                match String::from_utf8(content) {
+                    Ok(content) => {
+                        if !on_change(&content) {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        warn!(path = %path.display(), error = %e, "Cannot re-read config");
+                    }
+                }
+            }
+        });
+
+    if let Err(e) = spawned {
+        warn!(error = %e, "Cannot start config watcher, hot-reload disabled");
+    }
+}
+
+/// Empreinte du fichier : un changement de l'un des trois champs signale une
+/// modification.
+#[derive(Debug, PartialEq, Eq)]
+struct Fingerprint {
+    modified: Option<SystemTime>,
+    len: usize,
+    hash: u64,
+}
+
+impl Fingerprint {
+    fn of(path: &Path, content: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        Self {
+            modified: modified_time(path),
+            len: content.len(),
+            hash: hasher.finish(),
+        }
+    }
+}
+
+/// Date de dernière modification, `None` si le fichier est inaccessible.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_modified_time_missing_file() {
+        assert!(modified_time(Path::new("definitely/not/here/config.toml")).is_none());
+    }
+
+    #[test]
+    fn test_modified_time_existing_file() {
+        let dir = TempDir::new("watch");
+        let path = dir.join("exists.toml");
+        fs::write(&path, "").unwrap();
+        assert!(modified_time(&path).is_some());
+    }
+
+    #[test]
+    fn test_fingerprint_sees_same_length_edit() {
+        let dir = TempDir::new("watch");
+        let path = dir.join("same.toml");
+        fs::write(&path, "old").unwrap();
+        let before = Fingerprint::of(&path, b"old");
+        fs::write(&path, "new").unwrap();
+        assert_ne!(before, Fingerprint::of(&path, b"new"));
+        assert_eq!(
+            Fingerprint::of(&path, b"new"),
+            Fingerprint::of(&path, b"new")
+        );
+    }
+
+    #[test]
+    fn test_watch_file_reports_new_content() {
+        let dir = TempDir::new("watch");
+        let path = dir.join("watched.toml");
+        fs::write(&path, "old").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        watch_file(path.clone(), Duration::from_millis(20), move |content| {
+            tx.send(content.to_string()).is_ok()
+        });
+
+        // Laisser le thread enregistrer l'empreinte initiale, puis modifier
+        // deux fois dans la même seconde, à taille égale
+        thread::sleep(Duration::from_millis(100));
+        fs::write(&path, "new").unwrap();
+        let received = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("watcher should report the change");
+        assert_eq!(received, "new");
+
+        fs::write(&path, "now").unwrap();
+        let received = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("watcher should report the second change");
+        assert_eq!(received, "now");
+    }
+}
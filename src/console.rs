@@ -0,0 +1,179 @@
+//! Messages de la console JavaScript des pages, transmis à `tracing`.
+//!
+//! Activé par `[general] log_console`. `console.log/warn/error` et les
+//! exceptions non rattrapées sont journalisés au niveau correspondant, avec
+//! l'URL source et la ligne en champs. Un plafond par seconde évite qu'une
+//! page en boucle noie le journal. Ce module ne dépend pas de Servo.
+
+use std::time::{Duration, Instant};
+
+/// Nombre maximal de messages journalisés par seconde, toutes pages
+/// confondues. Les suivants sont comptés puis résumés en un seul message.
+pub const MAX_MESSAGES_PER_SECOND: u32 = 50;
+
+/// Longueur maximale d'un message journalisé (caractères), au-delà tronqué.
+pub const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// Cible `tracing` des messages de console (filtrable via `RUST_LOG`).
+pub const TARGET: &str = "suribrows::console";
+
+/// Niveau d'un message de console, calqué sur celui de Servo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleLevel {
+    Log,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Trace,
+}
+
+impl ConsoleLevel {
+    /// Niveau `tracing` correspondant : `console.log` est informatif.
+    pub fn tracing_level(self) -> tracing::Level {
+        match self {
+            Self::Trace => tracing::Level::TRACE,
+            Self::Debug => tracing::Level::DEBUG,
+            Self::Log | Self::Info => tracing::Level::INFO,
+            Self::Warn => tracing::Level::WARN,
+            Self::Error => tracing::Level::ERROR,
+        }
+    }
+}
+
+/// Emplacement `url:ligne[:colonne]` en fin de message, tel que Servo le
+/// place après une exception (« … at https://a.com/app.js:12:5 »).
+pub fn source_location(message: &str) -> Option<(&str, u32)> {
+    let token = message.split_whitespace().last()?;
+    let token = token.trim_start_matches('(').trim_end_matches(')');
+    let (rest, last) = token.rsplit_once(':')?;
+    let last: u32 = last.parse().ok()?;
+    // Avec colonne, la ligne est le nombre précédent
+    let (url, line) = match rest.rsplit_once(':') {
+        Some((url, line)) if line.parse::<u32>().is_ok() => (url, line.parse().ok()?),
+        _ => (rest, last),
+    };
+    url.contains("://").then_some((url, line))
+}
+
+/// Résultat de [`ConsoleLimiter::admit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admit {
+    /// Journaliser le message. `suppressed` messages ont été écartés dans
+    /// la seconde précédente (à signaler s'il y en a).
+    Log { suppressed: u32 },
+    /// Plafond atteint pour cette seconde : message écarté.
+    Drop,
+}
+
+/// Plafond de [`MAX_MESSAGES_PER_SECOND`] messages par fenêtre d'une seconde.
+#[derive(Debug, Default)]
+pub struct ConsoleLimiter {
+    /// Début de la fenêtre en cours.
+    window: Option<Instant>,
+    /// Messages journalisés dans la fenêtre en cours.
+    count: u32,
+    /// Messages écartés depuis le dernier rapport.
+    suppressed: u32,
+}
+
+impl ConsoleLimiter {
+    /// Décide si un message arrivé à `now` est journalisé.
+    pub fn admit(&mut self, now: Instant) -> Admit {
+        let expired = self
+            .window
+            .is_none_or(|start| now.saturating_duration_since(start) >= Duration::from_secs(1));
+        if expired {
+            self.window = Some(now);
+            self.count = 0;
+        }
+        if self.count >= MAX_MESSAGES_PER_SECOND {
+            self.suppressed = self.suppressed.saturating_add(1);
+            return Admit::Drop;
+        }
+        self.count += 1;
+        Admit::Log {
+            suppressed: std::mem::take(&mut self.suppressed),
+        }
+    }
+}
+
+/// Tronque `message` à [`MAX_MESSAGE_CHARS`] caractères.
+pub fn truncate(message: &str) -> &str {
+    match message.char_indices().nth(MAX_MESSAGE_CHARS) {
+        Some((end, _)) => &message[..end],
+        None => message,
+    }
+}
+
+/// Journalise un message de console de la page `page_url`. L'URL et la
+/// ligne du script sont reprises du message quand il les contient.
+pub fn log(level: ConsoleLevel, page_url: &str, message: &str) {
+    let (url, line) = match source_location(message) {
+        Some((url, line)) => (url, Some(line)),
+        None => (page_url, None),
+    };
+    let message = truncate(message);
+    match level.tracing_level() {
+        tracing::Level::ERROR => tracing::error!(target: TARGET, url, line, "{message}"),
+        tracing::Level::WARN => tracing::warn!(target: TARGET, url, line, "{message}"),
+        tracing::Level::INFO => tracing::info!(target: TARGET, url, line, "{message}"),
+        tracing::Level::DEBUG => tracing::debug!(target: TARGET, url, line, "{message}"),
+        _ => tracing::trace!(target: TARGET, url, line, "{message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_mapping() {
+        assert_eq!(ConsoleLevel::Log.tracing_level(), tracing::Level::INFO);
+        assert_eq!(ConsoleLevel::Info.tracing_level(), tracing::Level::INFO);
+        assert_eq!(ConsoleLevel::Warn.tracing_level(), tracing::Level::WARN);
+        assert_eq!(ConsoleLevel::Error.tracing_level(), tracing::Level::ERROR);
+        assert_eq!(ConsoleLevel::Debug.tracing_level(), tracing::Level::DEBUG);
+        assert_eq!(ConsoleLevel::Trace.tracing_level(), tracing::Level::TRACE);
+    }
+
+    #[test]
+    fn test_source_location() {
+        assert_eq!(
+            source_location("TypeError: x is undefined at https://a.com/app.js:12:5"),
+            Some(("https://a.com/app.js", 12))
+        );
+        assert_eq!(
+            source_location("boom (http://localhost:8080/main.js:7)"),
+            Some(("http://localhost:8080/main.js", 7))
+        );
+        assert_eq!(source_location("hello world"), None);
+        assert_eq!(source_location("ratio 16:9"), None);
+        assert_eq!(source_location(""), None);
+    }
+
+    #[test]
+    fn test_limiter_caps_per_second_and_reports() {
+        let start = Instant::now();
+        let mut limiter = ConsoleLimiter::default();
+        for _ in 0..MAX_MESSAGES_PER_SECOND {
+            assert_eq!(limiter.admit(start), Admit::Log { suppressed: 0 });
+        }
+        assert_eq!(limiter.admit(start), Admit::Drop);
+        assert_eq!(
+            limiter.admit(start + Duration::from_millis(900)),
+            Admit::Drop
+        );
+
+        let next = start + Duration::from_secs(1);
+        assert_eq!(limiter.admit(next), Admit::Log { suppressed: 2 });
+        assert_eq!(limiter.admit(next), Admit::Log { suppressed: 0 });
+    }
+
+    #[test]
+    fn test_truncate_on_char_boundary() {
+        assert_eq!(truncate("short"), "short");
+        let long = "é".repeat(MAX_MESSAGE_CHARS + 10);
+        assert_eq!(truncate(&long).chars().count(), MAX_MESSAGE_CHARS);
+    }
+}
@@ -0,0 +1,228 @@
+//! Menu contextuel de la page (clic droit).
+//!
+//! Servo indique ce qui se trouve sous le pointeur (lien, image) ; ce module
+//! en déduit les entrées et garde l'état du menu ouvert (position, ligne
+//! sélectionnée). Le dessin est fait par [`crate::chrome::ChromeRenderer`] et
+//! les actions sont dispatchées par `browser.rs`.
+
+use url::Url;
+
+use crate::load_state::LoadState;
+
+/// Entrées possibles du menu contextuel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextAction {
+    Back,
+    Forward,
+    Reload,
+    CopyLink,
+    OpenLinkInNewTab,
+    SaveImageAs,
+    ViewSource,
+}
+
+impl ContextAction {
+    /// Libellé affiché dans le panneau.
+    pub fn label(self) -> &'static str {
+        match self {
+            ContextAction::Back => "Back",
+            ContextAction::Forward => "Forward",
+            ContextAction::Reload => "Reload",
+            ContextAction::CopyLink => "Copy Link",
+            ContextAction::OpenLinkInNewTab => "Open Link in New Tab",
+            ContextAction::SaveImageAs => "Save Image As…",
+            ContextAction::ViewSource => "View Source",
+        }
+    }
+}
+
+/// Ce qui se trouve sous le pointeur au moment du clic droit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextTarget {
+    pub link: Option<Url>,
+    pub image: Option<Url>,
+}
+
+/// Menu contextuel ouvert.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextMenu {
+    target: ContextTarget,
+    /// Coin haut gauche demandé, en pixels fenêtre (position du clic).
+    position: (f32, f32),
+    /// Entrées affichées et leur disponibilité (`false` = grisée).
+    items: Vec<(ContextAction, bool)>,
+    /// Ligne surlignée (souris ou flèches), index dans `items`.
+    selected: Option<usize>,
+}
+
+impl ContextMenu {
+    /// Menu pour `target`, ouvert en `position`. Précédent/suivant sont
+    /// grisés selon l'historique de la webview.
+    pub fn new(target: ContextTarget, position: (f32, f32), history: LoadState) -> Self {
+        let mut items = vec![
+            (ContextAction::Back, history.can_go_back),
+            (ContextAction::Forward, history.can_go_forward),
+            (ContextAction::Reload, true),
+        ];
+        if target.link.is_some() {
+            items.push((ContextAction::CopyLink, true));
+            items.push((ContextAction::OpenLinkInNewTab, true));
+        }
+        if target.image.is_some() {
+            items.push((ContextAction::SaveImageAs, true));
+        }
+        items.push((ContextAction::ViewSource, true));
+        Self {
+            target,
+            position,
+            items,
+            selected: None,
+        }
+    }
+
+    pub fn target(&self) -> &ContextTarget {
+        &self.target
+    }
+
+    pub fn position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    /// Entrées de haut en bas, avec leur disponibilité.
+    pub fn items(&self) -> &[(ContextAction, bool)] {
+        &self.items
+    }
+
+    /// Index de la ligne surlignée.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Entrée surlignée, si elle est disponible.
+    pub fn selected_action(&self) -> Option<ContextAction> {
+        let (action, enabled) = self.items[self.selected?];
+        enabled.then_some(action)
+    }
+
+    /// Surligne la ligne `index` (survol souris). Les lignes grisées et
+    /// l'extérieur du panneau gardent la sélection. Retourne `true` si elle
+    /// a changé.
+    pub fn hover(&mut self, index: Option<usize>) -> bool {
+        let index = index.filter(|&i| self.items.get(i).is_some_and(|&(_, enabled)| enabled));
+        let changed = index.is_some() && self.selected != index;
+        if index.is_some() {
+            self.selected = index;
+        }
+        changed
+    }
+
+    /// Flèche bas : entrée disponible suivante, en bouclant.
+    pub fn select_next(&mut self) {
+        self.step(1);
+    }
+
+    /// Flèche haut : entrée disponible précédente, en bouclant.
+    pub fn select_prev(&mut self) {
+        self.step(self.items.len() - 1);
+    }
+
+    /// Avance de `offset` lignes (modulo) jusqu'à une entrée disponible.
+    fn step(&mut self, offset: usize) {
+        let len = self.items.len();
+        let mut index = match self.selected {
+            Some(i) => i,
+            // Rien de sélectionné : la première (ou dernière) ligne est la suivante
+            None if offset == 1 => len - 1,
+            None => 0,
+        };
+        for _ in 0..len {
+            index = (index + offset) % len;
+            if self.items[index].1 {
+                self.selected = Some(index);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions(menu: &ContextMenu) -> Vec<ContextAction> {
+        menu.items().iter().map(|&(action, _)| action).collect()
+    }
+
+    fn history(can_go_back: bool, can_go_forward: bool) -> LoadState {
+        LoadState {
+            loading: false,
+            can_go_back,
+            can_go_forward,
+        }
+    }
+
+    #[test]
+    fn test_items_depend_on_target() {
+        let menu = ContextMenu::new(ContextTarget::default(), (0.0, 0.0), history(true, true));
+        assert_eq!(
+            actions(&menu),
+            [
+                ContextAction::Back,
+                ContextAction::Forward,
+                ContextAction::Reload,
+                ContextAction::ViewSource
+            ]
+        );
+
+        let target = ContextTarget {
+            link: Url::parse("https://example.com/a").ok(),
+            image: Url::parse("https://example.com/a.png").ok(),
+        };
+        let menu = ContextMenu::new(target, (0.0, 0.0), history(true, true));
+        assert_eq!(
+            actions(&menu),
+            [
+                ContextAction::Back,
+                ContextAction::Forward,
+                ContextAction::Reload,
+                ContextAction::CopyLink,
+                ContextAction::OpenLinkInNewTab,
+                ContextAction::SaveImageAs,
+                ContextAction::ViewSource
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keyboard_skips_disabled_items() {
+        let mut menu =
+            ContextMenu::new(ContextTarget::default(), (0.0, 0.0), history(false, false));
+        menu.select_next();
+        assert_eq!(menu.selected_action(), Some(ContextAction::Reload));
+        menu.select_next();
+        assert_eq!(menu.selected_action(), Some(ContextAction::ViewSource));
+        // Boucle par-dessus Back/Forward grisés
+        menu.select_next();
+        assert_eq!(menu.selected_action(), Some(ContextAction::Reload));
+        menu.select_prev();
+        assert_eq!(menu.selected_action(), Some(ContextAction::ViewSource));
+    }
+
+    #[test]
+    fn test_select_prev_from_nothing_picks_last() {
+        let mut menu = ContextMenu::new(ContextTarget::default(), (0.0, 0.0), history(true, true));
+        menu.select_prev();
+        assert_eq!(menu.selected_action(), Some(ContextAction::ViewSource));
+    }
+
+    #[test]
+    fn test_hover_ignores_disabled_rows() {
+        let mut menu = ContextMenu::new(ContextTarget::default(), (0.0, 0.0), history(false, true));
+        assert!(!menu.hover(Some(0)));
+        assert_eq!(menu.selected(), None);
+        assert!(menu.hover(Some(1)));
+        assert!(!menu.hover(None));
+        assert!(!menu.hover(Some(42)));
+        assert_eq!(menu.selected_action(), Some(ContextAction::Forward));
+    }
+}
@@ -0,0 +1,152 @@
+//! Page plantée ou figée : détection et page d'erreur.
+//!
+//! Servo signale les plantages (panic du script ou du layout) via
+//! `notify_crashed`. Une page figée, elle, ne signale rien : le
+//! [`FrameWatchdog`] relève les webviews dont le document est parsé mais qui
+//! ne produisent plus de frame. Dans les deux cas, l'onglet affiche
+//! [`crashed_html`] ; les autres onglets et le chrome ne sont pas touchés.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use crate::pages::{self, js_string};
+use crate::settings::html_escape;
+
+/// Délai sans nouvelle frame au-delà duquel une page est déclarée figée.
+pub const UNRESPONSIVE_AFTER: Duration = Duration::from_secs(20);
+
+/// Raison affichée pour une page figée (pas de plantage signalé par Servo).
+pub const UNRESPONSIVE_REASON: &str = "The page stopped responding.";
+
+/// Chien de garde des frames : une webview qui attend une frame depuis plus
+/// de [`UNRESPONSIVE_AFTER`] est considérée figée.
+#[derive(Debug)]
+pub struct FrameWatchdog<K> {
+    /// Webviews en attente d'une frame, avec le début de l'attente.
+    waiting: HashMap<K, Instant>,
+}
+
+impl<K> Default for FrameWatchdog<K> {
+    fn default() -> Self {
+        Self {
+            waiting: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> FrameWatchdog<K> {
+    /// `key` doit produire une frame (document parsé). Une attente déjà en
+    /// cours garde son point de départ.
+    pub fn expect_frame(&mut self, key: K, now: Instant) {
+        self.waiting.entry(key).or_insert(now);
+    }
+
+    /// `key` a produit une frame : elle répond.
+    pub fn frame_ready(&mut self, key: K) {
+        self.waiting.remove(&key);
+    }
+
+    /// Repart de zéro pour toutes les attentes en cours. Appelé tant que la
+    /// fenêtre est cachée : Servo y ralentit, ce n'est pas un blocage.
+    pub fn postpone(&mut self, now: Instant) {
+        for since in self.waiting.values_mut() {
+            *since = now;
+        }
+    }
+
+    /// Prochaine échéance, pour réveiller la boucle d'événements.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.waiting
+            .values()
+            .min()
+            .map(|since| *since + UNRESPONSIVE_AFTER)
+    }
+
+    /// Retire et retourne les webviews figées à l'instant `now`.
+    pub fn expired(&mut self, now: Instant) -> Vec<K> {
+        let expired: Vec<K> = self
+            .waiting
+            .iter()
+            .filter(|&(_, since)| now.saturating_duration_since(*since) >= UNRESPONSIVE_AFTER)
+            .map(|(key, _)| *key)
+            .collect();
+        for key in &expired {
+            self.waiting.remove(key);
+        }
+        expired
+    }
+}
+
+/// Page « This page crashed » : raison, URL et bouton Reload qui recharge
+/// la page d'origine (absent si elle n'est pas rechargeable).
+pub fn crashed_html(url: Option<&Url>, reason: &str) -> String {
+    let reloadable = url.filter(|url| matches!(url.scheme(), "http" | "https" | "file"));
+    let reload = match reloadable {
+        Some(url) => format!(
+            r#"<div class="actions"><button onclick="location.replace({})">Reload</button></div>"#,
+            html_escape(&js_string(url.as_str()))
+        ),
+        None => String::new(),
+    };
+    let address = url.map_or(String::new(), |url| {
+        format!(
+            r#"<p class="detail"><code>{}</code></p>"#,
+            html_escape(url.as_str())
+        )
+    });
+    pages::shell(
+        "Page crashed",
+        "h1 { color: #ff6a6a; }",
+        &format!(
+            "<h1>This page crashed</h1>\n<p>{reason}</p>\n{address}\n{reload}",
+            reason = html_escape(reason),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_expires_without_frame() {
+        let start = Instant::now();
+        let mut watchdog = FrameWatchdog::default();
+        watchdog.expect_frame(1, start);
+        watchdog.expect_frame(2, start);
+        watchdog.frame_ready(2);
+        // Une nouvelle attente ne repousse pas l'échéance
+        watchdog.expect_frame(1, start + Duration::from_secs(5));
+        assert_eq!(watchdog.deadline(), Some(start + UNRESPONSIVE_AFTER));
+        assert!(watchdog.expired(start + Duration::from_secs(19)).is_empty());
+        assert_eq!(watchdog.expired(start + UNRESPONSIVE_AFTER), vec![1]);
+        assert_eq!(watchdog.deadline(), None);
+    }
+
+    #[test]
+    fn test_watchdog_postpone_while_hidden() {
+        let start = Instant::now();
+        let mut watchdog = FrameWatchdog::default();
+        watchdog.expect_frame(1, start);
+        let shown = start + Duration::from_secs(60);
+        watchdog.postpone(shown);
+        assert!(watchdog.expired(shown).is_empty());
+        assert_eq!(watchdog.deadline(), Some(shown + UNRESPONSIVE_AFTER));
+    }
+
+    #[test]
+    fn test_crashed_page_escapes_and_reloads() {
+        let url = Url::parse("https://example.com/a?q=</script>'").unwrap();
+        let html = crashed_html(Some(&url), "<panic> at layout");
+        assert!(html.contains("&lt;panic&gt; at layout"));
+        assert!(html.contains("Reload"));
+        assert!(!html.contains("</script>'"));
+
+        let data = Url::parse("data:text/html,hi").unwrap();
+        assert!(!crashed_html(Some(&data), UNRESPONSIVE_REASON).contains("Reload"));
+        assert!(!crashed_html(None, UNRESPONSIVE_REASON).contains("Reload"));
+    }
+}
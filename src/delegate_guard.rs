@@ -0,0 +1,136 @@
+//! Protection des callbacks `WebViewDelegate` contre les panics.
+//!
+//! Une panic qui traverse la frontière avec Servo est un comportement
+//! indéfini : chaque callback est exécuté sous `catch_unwind` (V-4). Les
+//! panics rattrapées ne sont pas pour autant ignorées : le message est
+//! journalisé avec le nom du callback, et un compteur par callback signale
+//! d'un avertissement unique celui qui panique de façon répétée (double
+//! emprunt de `RefCell`, panic d'adblock…). Ce module ne dépend pas de Servo.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+/// Nombre de panics d'un même callback à partir duquel un avertissement
+/// unique signale un bug récurrent.
+pub const REPEATED_PANIC_THRESHOLD: u32 = 5;
+
+/// Message d'une panic (`panic!("…")` ou `panic!("{x}")`), à défaut un
+/// libellé générique.
+pub fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&'static str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "non-string panic payload"
+    }
+}
+
+/// Nombre de panics rattrapées, par callback.
+#[derive(Debug, Default)]
+pub struct PanicStats {
+    counts: BTreeMap<&'static str, u32>,
+}
+
+impl PanicStats {
+    /// Compte une panic de `callback` et retourne son total.
+    pub fn record(&mut self, callback: &'static str) -> u32 {
+        let count = self.counts.entry(callback).or_insert(0);
+        *count = count.saturating_add(1);
+        *count
+    }
+
+    /// Panics rattrapées pour `callback`.
+    pub fn count(&self, callback: &str) -> u32 {
+        self.counts.get(callback).copied().unwrap_or(0)
+    }
+
+    /// Compteurs non nuls, par nom de callback.
+    pub fn snapshot(&self) -> Vec<(&'static str, u32)> {
+        self.counts
+            .iter()
+            .map(|(name, count)| (*name, *count))
+            .collect()
+    }
+}
+
+/// Exécute le corps du callback `callback` en rattrapant une panic :
+/// journalisée, comptée dans `stats`, puis `None`.
+pub fn guard<R>(
+    stats: &RefCell<PanicStats>,
+    callback: &'static str,
+    body: impl FnOnce() -> R,
+) -> Option<R> {
+    let payload = match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(result) => return Some(result),
+        Err(payload) => payload,
+    };
+    let message = panic_message(payload.as_ref());
+    // `stats` n'est emprunté qu'ici : une panic de double emprunt dans le
+    // corps ne peut pas venir de lui.
+    let count = stats.borrow_mut().record(callback);
+    tracing::error!(callback, count, "Delegate callback panicked: {message}");
+    if count == REPEATED_PANIC_THRESHOLD {
+        tracing::warn!(
+            callback,
+            "Delegate callback keeps panicking ({count} times); the browser \
+             state may be inconsistent, please report this bug"
+        );
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_returns_result_without_panic() {
+        let stats = RefCell::new(PanicStats::default());
+        assert_eq!(guard(&stats, "notify_url_changed", || 42), Some(42));
+        assert!(stats.borrow().snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_guard_counts_panics_per_callback() {
+        let stats = RefCell::new(PanicStats::default());
+        for _ in 0..REPEATED_PANIC_THRESHOLD + 1 {
+            assert_eq!(
+                guard(&stats, "load_web_resource", || -> u8 { panic!("adblock") }),
+                None
+            );
+        }
+        let shared = RefCell::new(0);
+        guard(&stats, "notify_closed", || {
+            let _first = shared.borrow_mut();
+            let _second = shared.borrow_mut();
+        });
+
+        let stats = stats.borrow();
+        assert_eq!(
+            stats.count("load_web_resource"),
+            REPEATED_PANIC_THRESHOLD + 1
+        );
+        assert_eq!(stats.count("notify_crashed"), 0);
+        assert_eq!(
+            stats.snapshot(),
+            vec![
+                ("load_web_resource", REPEATED_PANIC_THRESHOLD + 1),
+                ("notify_closed", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let payload = catch_unwind(|| panic!("static")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "static");
+        let n = 3;
+        let payload = catch_unwind(|| panic!("formatted {n}")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "formatted 3");
+        let payload = catch_unwind(|| std::panic::panic_any(7_u32)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "non-string panic payload");
+    }
+}
@@ -0,0 +1,272 @@
+//! Boîtes de dialogue JavaScript (`alert`, `confirm`, `prompt`), demandes
+//! de permission d'un site et identifiants HTTP (authentification basique ou
+//! digest).
+//!
+//! Le dialogue est dessiné par le chrome, centré sous la barre d'URL, et ne
+//! bloque pas la boucle d'événements : le script de la page attend sa réponse
+//! pendant que Winit et Servo continuent de tourner. Ce module ne contient que
+//! l'état affiché ; la réponse à Servo est envoyée par `browser.rs`.
+
+use crate::text_input::TextInput;
+
+/// Nombre maximal de lignes du message affichées (les suivantes sont coupées).
+pub const MAX_MESSAGE_LINES: usize = 6;
+
+/// Type de dialogue demandé par la page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogKind {
+    Alert,
+    Confirm,
+    Prompt,
+    /// Demande de permission (caméra, notifications…) : autoriser ou refuser.
+    Permission,
+    /// Authentification HTTP : nom d'utilisateur et mot de passe.
+    Authentication,
+    /// Lien à confier à une application externe : ouvrir ou annuler.
+    External,
+}
+
+/// Caractère affiché à la place de chaque caractère du mot de passe.
+pub const PASSWORD_MASK: char = '•';
+
+/// Dialogue JavaScript en attente de réponse.
+#[derive(Debug)]
+pub struct JsDialog {
+    kind: DialogKind,
+    message: String,
+    /// Champ de saisie (`prompt`, ou nom d'utilisateur), prérempli et
+    /// sélectionné.
+    input: TextInput,
+    /// Mot de passe (authentification seulement).
+    password: TextInput,
+    /// `true` si le mot de passe a le focus (Tab bascule entre les champs).
+    password_focused: bool,
+}
+
+impl JsDialog {
+    pub fn alert(message: &str) -> Self {
+        Self::new(DialogKind::Alert, message, "")
+    }
+
+    pub fn confirm(message: &str) -> Self {
+        Self::new(DialogKind::Confirm, message, "")
+    }
+
+    pub fn prompt(message: &str, default: &str) -> Self {
+        Self::new(DialogKind::Prompt, message, default)
+    }
+
+    /// Demande de `permission` par `origin`, posée à l'utilisateur.
+    pub fn permission(origin: &str, permission: &str) -> Self {
+        let message = format!("{origin} asks for permission: {permission}");
+        Self::new(DialogKind::Permission, &message, "")
+    }
+
+    /// Identifiants demandés par `host` (`for_proxy` : par le proxy).
+    pub fn authentication(host: &str, for_proxy: bool) -> Self {
+        let message = if for_proxy {
+            format!("The proxy {host} requires a username and password")
+        } else {
+            format!("{host} requires a username and password")
+        };
+        Self::new(DialogKind::Authentication, &message, "")
+    }
+
+    /// Confirmation avant d'ouvrir un lien dans une application externe.
+    pub fn external(message: &str) -> Self {
+        Self::new(DialogKind::External, message, "")
+    }
+
+    fn new(kind: DialogKind, message: &str, default: &str) -> Self {
+        let mut input = TextInput::default();
+        input.set_text(default.to_string());
+        input.select_all();
+        Self {
+            kind,
+            message: message.to_string(),
+            input,
+            password: TextInput::default(),
+            password_focused: false,
+        }
+    }
+
+    pub fn kind(&self) -> DialogKind {
+        self.kind
+    }
+
+    /// Lignes du message à afficher : tabulations et caractères de contrôle
+    /// remplacés par des espaces, au plus [`MAX_MESSAGE_LINES`] lignes (la
+    /// dernière finit par « … » si le message est coupé).
+    pub fn message_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .message
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| if c.is_control() { ' ' } else { c })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        if lines.len() > MAX_MESSAGE_LINES {
+            lines.truncate(MAX_MESSAGE_LINES);
+            if let Some(last) = lines.last_mut() {
+                last.push('…');
+            }
+        }
+        lines
+    }
+
+    /// Champ de saisie : valeur du `prompt`, ou nom d'utilisateur.
+    pub fn input(&self) -> Option<&TextInput> {
+        matches!(self.kind, DialogKind::Prompt | DialogKind::Authentication).then_some(&self.input)
+    }
+
+    /// Mot de passe (authentification seulement).
+    pub fn password(&self) -> Option<&TextInput> {
+        (self.kind == DialogKind::Authentication).then_some(&self.password)
+    }
+
+    /// `true` si le mot de passe a le focus plutôt que [`Self::input`].
+    pub fn is_password_focused(&self) -> bool {
+        self.password_focused
+    }
+
+    /// Champ qui reçoit la frappe.
+    pub fn input_mut(&mut self) -> Option<&mut TextInput> {
+        match self.kind {
+            DialogKind::Authentication if self.password_focused => Some(&mut self.password),
+            DialogKind::Prompt | DialogKind::Authentication => Some(&mut self.input),
+            DialogKind::Alert
+            | DialogKind::Confirm
+            | DialogKind::Permission
+            | DialogKind::External => None,
+        }
+    }
+
+    /// Tab : passe au champ suivant (nom d'utilisateur ↔ mot de passe).
+    pub fn focus_next(&mut self) {
+        if self.kind == DialogKind::Authentication {
+            self.password_focused = !self.password_focused;
+        }
+    }
+
+    /// Nom d'utilisateur et mot de passe saisis (authentification seulement).
+    pub fn credentials(&self) -> Option<(String, String)> {
+        (self.kind == DialogKind::Authentication).then(|| {
+            (
+                self.input.text().to_string(),
+                self.password.text().to_string(),
+            )
+        })
+    }
+
+    /// Rappel des touches, affiché en bas du dialogue.
+    pub fn hint(&self) -> &'static str {
+        match self.kind {
+            DialogKind::Alert => "Enter: OK",
+            DialogKind::Confirm | DialogKind::Prompt => "Enter: OK · Esc: Cancel",
+            DialogKind::Permission => "Enter: Allow · Esc: Deny",
+            DialogKind::External => "Enter: Open · Esc: Cancel",
+            DialogKind::Authentication => "Enter: Sign in · Tab: Next field · Esc: Cancel",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_prefills_and_selects_default() {
+        let mut dialog = JsDialog::prompt("Name?", "Ada");
+        assert_eq!(dialog.kind(), DialogKind::Prompt);
+        assert_eq!(dialog.input().unwrap().text(), "Ada");
+        // La première frappe remplace la valeur proposée
+        dialog.input_mut().unwrap().insert_char('B');
+        assert_eq!(dialog.input().unwrap().text(), "B");
+    }
+
+    #[test]
+    fn test_only_prompt_has_input() {
+        assert!(JsDialog::alert("Hi").input().is_none());
+        assert!(JsDialog::confirm("Sure?").input().is_none());
+        assert_eq!(JsDialog::alert("Hi").hint(), "Enter: OK");
+        assert_eq!(JsDialog::confirm("Sure?").hint(), "Enter: OK · Esc: Cancel");
+    }
+
+    #[test]
+    fn test_permission_dialog() {
+        let dialog = JsDialog::permission("https://example.com", "Notifications");
+        assert_eq!(dialog.kind(), DialogKind::Permission);
+        assert!(dialog.input().is_none());
+        assert_eq!(
+            dialog.message_lines(),
+            ["https://example.com asks for permission: Notifications"]
+        );
+    }
+
+    #[test]
+    fn test_external_dialog_shows_target() {
+        let dialog = JsDialog::external("Open this link in an external application?\nmailto:a@b.c");
+        assert_eq!(dialog.kind(), DialogKind::External);
+        assert!(dialog.input().is_none());
+        assert_eq!(
+            dialog.message_lines(),
+            ["Open this link in an external application?", "mailto:a@b.c"]
+        );
+        assert_eq!(dialog.hint(), "Enter: Open · Esc: Cancel");
+    }
+
+    #[test]
+    fn test_authentication_fields() {
+        let mut dialog = JsDialog::authentication("intranet.example", false);
+        assert_eq!(
+            dialog.message_lines(),
+            ["intranet.example requires a username and password"]
+        );
+        for c in "ada".chars() {
+            dialog.input_mut().unwrap().insert_char(c);
+        }
+        // Tab : la frappe va au mot de passe
+        dialog.focus_next();
+        assert!(dialog.is_password_focused());
+        for c in "s3cret".chars() {
+            dialog.input_mut().unwrap().insert_char(c);
+        }
+        assert_eq!(
+            dialog.credentials(),
+            Some(("ada".to_string(), "s3cret".to_string()))
+        );
+        assert!(JsDialog::prompt("Name?", "").credentials().is_none());
+        assert!(JsDialog::prompt("Name?", "").password().is_none());
+    }
+
+    #[test]
+    fn test_authentication_names_proxy() {
+        let dialog = JsDialog::authentication("proxy.lan:3128", true);
+        assert_eq!(
+            dialog.message_lines(),
+            ["The proxy proxy.lan:3128 requires a username and password"]
+        );
+    }
+
+    #[test]
+    fn test_message_lines() {
+        let dialog = JsDialog::alert("a\tb\r\nsecond  \n");
+        assert_eq!(dialog.message_lines(), ["a b", "second"]);
+        assert_eq!(JsDialog::alert("").message_lines(), [""]);
+
+        let long = (1..=10)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let lines = JsDialog::alert(&long).message_lines();
+        assert_eq!(lines.len(), MAX_MESSAGE_LINES);
+        assert_eq!(lines.last().unwrap(), "6…");
+    }
+}
@@ -0,0 +1,804 @@
+//! Téléchargements : réponses que Servo ne sait pas afficher (archives, PDF,
+//! exécutables…), enregistrées dans le dossier de téléchargement.
+//!
+//! libservo ne montre pas la réponse à l'embedder et n'a pas d'API de
+//! téléchargement : Servo charge la navigation comme n'importe quelle page
+//! et affiche « type de contenu inconnu » pour ce qu'il ne sait pas rendre.
+//! Une fois le document chargé, `browser.rs` lit son type
+//! ([`CONTENT_TYPE_SCRIPT`]) ; s'il n'est pas affichable ([`is_renderable`]),
+//! la page relit la réponse en flux ([`start_script`]) par la pile réseau
+//! de Servo (cookies, proxy, exceptions de certificat, adblock), sans
+//! bloquer son thread de script. Un thread de fond relève les morceaux reçus
+//! ([`poll_script`]) et les écrit au fil de l'eau ([`receive`]) :
+//! l'avancement est celui du réseau, la mémoire bornée par
+//! `MAX_QUEUED_BYTES`, et l'annulation interrompt la requête
+//! ([`ABORT_SCRIPT`]). L'onglet reste sur la page de Servo.
+//!
+//! Limites :
+//! - la relecture est servie par le cache HTTP de Servo quand la réponse y
+//!   est ; sinon elle repart sur le réseau, et un lien à usage unique
+//!   (jeton consommé par le premier chargement) échoue ;
+//! - le résultat d'un formulaire (`POST`) n'est pas rejoué : il reste
+//!   affiché tel quel ;
+//! - une page HTML servie en `Content-Disposition: attachment` est affichée,
+//!   l'en-tête n'étant lu qu'au moment de relire la réponse.
+//!
+//! Ce module choisit le nom et le chemin du fichier, écrit le flux
+//! ([`DownloadWriter`], [`receive`]) et garde l'état affiché par le chrome
+//! ([`Downloads`]).
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use base64::Engine as _;
+use serde::Deserialize;
+use url::Url;
+
+use crate::export::percent_decode;
+
+/// Nom utilisé quand ni l'en-tête ni l'URL n'en donnent un.
+const FALLBACK_NAME: &str = "download";
+
+/// Longueur maximale d'un nom de fichier, en caractères.
+const MAX_NAME_CHARS: usize = 200;
+
+/// Nombre maximal de suffixes « (n) » essayés avant d'abandonner.
+const MAX_COLLISIONS: u32 = 1000;
+
+/// Octets au plus rendus par un relevé de [`POLL_SCRIPT`] (avant base64).
+const MAX_POLL_BYTES: usize = 4 << 20;
+
+/// Octets reçus au plus en attente dans la page : au-delà, la lecture du
+/// flux attend le relevé suivant.
+const MAX_QUEUED_BYTES: usize = 16 << 20;
+
+/// Script renvoyant le type MIME du document affiché. Servo garde celui de
+/// la réponse, même quand il affiche sa page « type de contenu inconnu ».
+pub const CONTENT_TYPE_SCRIPT: &str = "document.contentType";
+
+/// Script lançant la relecture en flux du document affiché (`fetch()`
+/// asynchrone, cache HTTP de Servo d'abord). Les morceaux reçus attendent
+/// dans la page d'être relevés par [`POLL_SCRIPT`].
+pub fn start_script() -> String {
+    format!(
+        r#"(function() {{
+  const state = {{
+    headers: false, disposition: null, total: null, chunks: [], queued: 0,
+    done: false, error: null, controller: new AbortController(),
+  }};
+  window.__suribrowsDownload = state;
+  const fail = (e) => {{ state.error = String(e); }};
+  fetch(location.href, {{
+    credentials: "include", cache: "force-cache", signal: state.controller.signal,
+  }}).then((response) => {{
+    if (!response.ok) {{
+      throw new Error("HTTP " + response.status);
+    }}
+    state.disposition = response.headers.get("Content-Disposition");
+    const length = parseInt(response.headers.get("Content-Length"), 10);
+    state.total = Number.isNaN(length) ? null : length;
+    state.headers = true;
+    const reader = response.body.getReader();
+    const pump = () => {{
+      if (state.queued >= {MAX_QUEUED_BYTES}) {{
+        setTimeout(pump, 50);
+        return;
+      }}
+      reader.read().then(({{ done, value }}) => {{
+        if (done) {{
+          state.done = true;
+          return;
+        }}
+        state.chunks.push(value);
+        state.queued += value.length;
+        pump();
+      }}).catch(fail);
+    }};
+    pump();
+  }}).catch(fail);
+}})()"#
+    )
+}
+
+/// Script relevant les morceaux reçus par [`start_script`] : JSON lu par
+/// [`Polled::parse`], `null` si la page n'a pas de téléchargement en cours.
+pub fn poll_script() -> String {
+    format!(
+        r#"(function() {{
+  const state = window.__suribrowsDownload;
+  if (!state) {{
+    return null;
+  }}
+  let binary = "";
+  while (state.chunks.length > 0 && binary.length < {MAX_POLL_BYTES}) {{
+    const chunk = state.chunks.shift();
+    state.queued -= chunk.length;
+    for (let i = 0; i < chunk.length; i += 0x8000) {{
+      binary += String.fromCharCode.apply(null, chunk.subarray(i, i + 0x8000));
+    }}
+  }}
+  return JSON.stringify({{
+    headers: state.headers,
+    disposition: state.disposition,
+    total: state.total,
+    data: btoa(binary),
+    done: state.done && state.chunks.length === 0,
+    error: state.error,
+  }});
+}})()"#
+    )
+}
+
+/// Script interrompant la relecture et oubliant ses morceaux.
+pub const ABORT_SCRIPT: &str = r#"(function() {
+  const state = window.__suribrowsDownload;
+  if (state) {
+    state.controller.abort();
+    delete window.__suribrowsDownload;
+  }
+})()"#;
+
+/// `true` si Servo sait afficher un document de ce type (page, texte, XML,
+/// image ou média) ; le reste est téléchargé.
+pub fn is_renderable(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let Some((kind, subtype)) = mime.split_once('/') else {
+        return false;
+    };
+    match kind {
+        "image" | "audio" | "video" => true,
+        "text" => matches!(subtype, "html" | "plain" | "xml"),
+        "application" => {
+            matches!(subtype, "xhtml+xml" | "xml" | "json") || subtype.ends_with("+xml")
+        }
+        _ => false,
+    }
+}
+
+/// Relevé de [`poll_script`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Polled {
+    /// En-têtes reçus : `disposition` et `total` sont connus.
+    pub headers: bool,
+    /// En-tête `Content-Disposition`, s'il y en a un.
+    pub disposition: Option<String>,
+    /// Taille annoncée (`Content-Length`).
+    pub total: Option<u64>,
+    /// Octets reçus depuis le relevé précédent.
+    pub data: Vec<u8>,
+    /// Réponse entièrement relevée.
+    pub done: bool,
+    /// Échec de la requête ou de la lecture du flux.
+    pub error: Option<String>,
+}
+
+impl Polled {
+    /// Lit le JSON renvoyé par [`poll_script`].
+    pub fn parse(json: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Raw {
+            headers: bool,
+            disposition: Option<String>,
+            total: Option<u64>,
+            data: String,
+            done: bool,
+            error: Option<String>,
+        }
+        let raw: Raw = serde_json::from_str(json).ok()?;
+        Some(Self {
+            headers: raw.headers,
+            disposition: raw.disposition,
+            total: raw.total,
+            data: base64::engine::general_purpose::STANDARD
+                .decode(raw.data)
+                .ok()?,
+            done: raw.done,
+            error: raw.error,
+        })
+    }
+}
+
+/// Nom du fichier : `filename*` ou `filename` de `Content-Disposition`,
+/// sinon dernier segment du chemin de l'URL, nettoyé pour le système de
+/// fichiers.
+pub fn file_name(disposition: Option<&str>, url: &Url) -> String {
+    disposition
+        .and_then(disposition_file_name)
+        .and_then(|name| sanitize(&name))
+        .or_else(|| {
+            url.path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .and_then(|name| sanitize(&percent_decode(name)))
+        })
+        .unwrap_or_else(|| FALLBACK_NAME.to_string())
+}
+
+/// Nom de fichier d'un en-tête `Content-Disposition` (RFC 6266). `filename*`
+/// (encodé UTF-8) l'emporte sur `filename`.
+fn disposition_file_name(header: &str) -> Option<String> {
+    let mut plain = None;
+    for param in header.split(';').skip(1) {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                // `UTF-8''nom%20encod%C3%A9`
+                if let Some((charset, rest)) = value.split_once('\'')
+                    && let Some((_, encoded)) = rest.split_once('\'')
+                    && charset.eq_ignore_ascii_case("utf-8")
+                {
+                    return Some(percent_decode(encoded));
+                }
+            }
+            "filename" => plain = Some(value.trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    plain
+}
+
+/// Retire ce qui pourrait sortir du dossier ou gêner le système de
+/// fichiers : séparateurs, caractères de contrôle et réservés, points en
+/// tête. `None` si rien ne reste.
+fn sanitize(name: &str) -> Option<String> {
+    // Un nom avec chemin (`../../etc/passwd`) : seul le dernier composant compte
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| match c {
+            ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .take(MAX_NAME_CHARS)
+        .collect();
+    let cleaned = cleaned
+        .trim()
+        .trim_start_matches('.')
+        .trim_end_matches(['.', ' ']);
+    (!cleaned.is_empty()).then(|| cleaned.to_string())
+}
+
+/// `name` avec le suffixe « (n) » avant l'extension : `archive (1).zip`,
+/// `sources (2).tar.gz`.
+fn numbered(name: &str, n: u32) -> String {
+    let lower = name.to_ascii_lowercase();
+    let split = [".tar.gz", ".tar.bz2", ".tar.xz", ".tar.zst"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext) && lower.len() > ext.len())
+        .map(|ext| name.len() - ext.len())
+        .or_else(|| name.rfind('.').filter(|&i| i > 0));
+    match split {
+        Some(i) => format!("{} ({n}){}", &name[..i], &name[i..]),
+        None => format!("{name} ({n})"),
+    }
+}
+
+/// Fin d'un téléchargement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadResult {
+    /// Fichier complet enregistré.
+    Saved(PathBuf),
+    /// Annulé par l'utilisateur (fichier partiel supprimé).
+    Cancelled,
+    Failed(String),
+}
+
+/// Avancement d'un téléchargement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub received: u64,
+    /// Taille annoncée (`Content-Length`), si connue.
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    /// Pourcentage reçu, si la taille est connue.
+    pub fn percent(self) -> Option<u8> {
+        let total = self.total.filter(|&t| t > 0)?;
+        Some((self.received.min(total) * 100 / total) as u8)
+    }
+}
+
+/// Fichier en cours d'écriture. Abandonné (erreur, annulation) sans
+/// [`DownloadWriter::finish`], le fichier partiel est supprimé.
+#[derive(Debug)]
+pub struct DownloadWriter {
+    path: PathBuf,
+    file: Option<BufWriter<File>>,
+    progress: Progress,
+}
+
+impl DownloadWriter {
+    /// Crée `name` dans `dir`, avec un suffixe « (n) » si le nom est déjà
+    /// pris. La création exclusive évite d'écraser un fichier créé entre-temps.
+    pub fn create(dir: &Path, name: &str, total: Option<u64>) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        for n in 0..=MAX_COLLISIONS {
+            let candidate = if n == 0 {
+                name.to_string()
+            } else {
+                numbered(name, n)
+            };
+            let path = dir.join(candidate);
+            match File::create_new(&path) {
+                Ok(file) => {
+                    return Ok(Self {
+                        path,
+                        file: Some(BufWriter::new(file)),
+                        progress: Progress { received: 0, total },
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("too many files named {name}"),
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn progress(&self) -> Progress {
+        self.progress
+    }
+
+    /// Ajoute un morceau reçu.
+    pub fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()> {
+        let file = self.file.as_mut().ok_or(io::ErrorKind::BrokenPipe)?;
+        file.write_all(chunk)?;
+        self.progress.received += chunk.len() as u64;
+        Ok(())
+    }
+
+    /// Termine l'écriture et garde le fichier.
+    pub fn finish(mut self) -> io::Result<PathBuf> {
+        if let Some(file) = self.file.take() {
+            let file = file.into_inner().map_err(io::IntoInnerError::into_error)?;
+            if let Err(e) = file.sync_all() {
+                // Le fichier partiel est supprimé par `Drop`
+                self.file = None;
+                return Err(e);
+            }
+        }
+        let path = std::mem::take(&mut self.path);
+        Ok(path)
+    }
+}
+
+impl Drop for DownloadWriter {
+    fn drop(&mut self) {
+        // Chemin vidé par `finish` : le fichier est complet
+        if self.path.as_os_str().is_empty() {
+            return;
+        }
+        drop(self.file.take());
+        if let Err(e) = fs::remove_file(&self.path) {
+            tracing::warn!(path = %self.path.display(), error = %e, "Cannot remove partial download");
+        }
+    }
+}
+
+/// Boucle du thread de fond : relève la réponse de `url` par `next` (un
+/// relevé de la page, `None` si elle a disparu) et l'écrit dans `dir` au fil
+/// de l'eau. `started` reçoit le nom retenu (suffixe « (n) » compris),
+/// `progress` l'avancement après chaque relevé. Annulé ou échoué, le fichier
+/// partiel est supprimé.
+pub fn receive(
+    dir: &Path,
+    url: &Url,
+    cancel: &AtomicBool,
+    mut next: impl FnMut() -> Option<Polled>,
+    started: impl FnOnce(String),
+    mut progress: impl FnMut(Progress),
+) -> DownloadResult {
+    const PAGE_GONE: &str = "the page was closed";
+
+    // En-têtes d'abord : ils donnent le nom et la taille
+    let mut polled = loop {
+        if cancel.load(Ordering::Relaxed) {
+            return DownloadResult::Cancelled;
+        }
+        let Some(polled) = next() else {
+            return DownloadResult::Failed(PAGE_GONE.to_string());
+        };
+        if let Some(e) = polled.error {
+            return DownloadResult::Failed(e);
+        }
+        if polled.headers {
+            break polled;
+        }
+    };
+    let name = file_name(polled.disposition.as_deref(), url);
+    let mut writer = match DownloadWriter::create(dir, &name, polled.total) {
+        Ok(writer) => writer,
+        Err(e) => return DownloadResult::Failed(e.to_string()),
+    };
+    started(
+        writer
+            .path()
+            .file_name()
+            .map_or(name, |n| n.to_string_lossy().into_owned()),
+    );
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return DownloadResult::Cancelled;
+        }
+        if let Err(e) = writer.write_chunk(&polled.data) {
+            return DownloadResult::Failed(e.to_string());
+        }
+        if !polled.data.is_empty() {
+            progress(writer.progress());
+        }
+        if let Some(e) = polled.error {
+            return DownloadResult::Failed(e);
+        }
+        if polled.done {
+            break;
+        }
+        polled = match next() {
+            Some(polled) => polled,
+            None => return DownloadResult::Failed(PAGE_GONE.to_string()),
+        };
+    }
+    match writer.finish() {
+        Ok(path) => DownloadResult::Saved(path),
+        Err(e) => DownloadResult::Failed(e.to_string()),
+    }
+}
+
+/// Téléchargement en cours, vu du thread principal.
+#[derive(Debug)]
+struct ActiveDownload {
+    id: u64,
+    name: String,
+    progress: Progress,
+    cancel: Arc<AtomicBool>,
+}
+
+/// Téléchargements en cours, pour l'indicateur du chrome.
+#[derive(Debug, Default)]
+pub struct Downloads {
+    active: Vec<ActiveDownload>,
+    next_id: u64,
+}
+
+impl Downloads {
+    /// Enregistre un nouveau téléchargement ; retourne son identifiant et le
+    /// drapeau d'annulation à surveiller par le thread de fond.
+    pub fn start(&mut self, name: String) -> (u64, Arc<AtomicBool>) {
+        self.next_id += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.active.push(ActiveDownload {
+            id: self.next_id,
+            name,
+            progress: Progress::default(),
+            cancel: cancel.clone(),
+        });
+        (self.next_id, cancel)
+    }
+
+    /// Nom définitif du fichier, connu après les en-têtes.
+    pub fn rename(&mut self, id: u64, name: String) {
+        if let Some(download) = self.active.iter_mut().find(|d| d.id == id) {
+            download.name = name;
+        }
+    }
+
+    /// Met à jour l'avancement ; retourne `true` si l'indicateur change.
+    pub fn update(&mut self, id: u64, progress: Progress) -> bool {
+        let Some(download) = self.active.iter_mut().find(|d| d.id == id) else {
+            return false;
+        };
+        let before = download.progress;
+        download.progress = progress;
+        match progress.total {
+            Some(_) => before.percent() != progress.percent(),
+            // Taille inconnue : l'indicateur affiche les mégaoctets reçus
+            None => before.received >> 20 != progress.received >> 20,
+        }
+    }
+
+    /// Retire un téléchargement terminé, échoué ou annulé.
+    pub fn finish(&mut self, id: u64) {
+        self.active.retain(|d| d.id != id);
+    }
+
+    /// Annule le téléchargement affiché (le plus récent). Retourne `false`
+    /// s'il n'y en a pas.
+    pub fn cancel_latest(&mut self) -> bool {
+        let Some(download) = self.active.last() else {
+            return false;
+        };
+        download.cancel.store(true, Ordering::Relaxed);
+        let id = download.id;
+        self.finish(id);
+        true
+    }
+
+    /// Texte de l'indicateur (téléchargement le plus récent, et combien
+    /// d'autres sont en cours), `None` sans téléchargement.
+    pub fn indicator(&self) -> Option<String> {
+        let latest = self.active.last()?;
+        let amount = match latest.progress.percent() {
+            Some(percent) => format!("{percent}%"),
+            None => format!("{:.1} MB", latest.progress.received as f64 / 1_048_576.0),
+        };
+        let others = match self.active.len() - 1 {
+            0 => String::new(),
+            n => format!(" (+{n})"),
+        };
+        Some(format!(
+            "Downloading {}… {amount}{others} — click to cancel",
+            latest.name
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_is_renderable() {
+        assert!(is_renderable("text/html; charset=utf-8"));
+        assert!(is_renderable("image/png"));
+        assert!(is_renderable("image/svg+xml"));
+        assert!(is_renderable("application/json"));
+        assert!(is_renderable("video/mp4"));
+        assert!(!is_renderable("application/zip"));
+        assert!(!is_renderable("application/pdf"));
+        assert!(!is_renderable("application/octet-stream"));
+        assert!(!is_renderable("text/csv"));
+        assert!(!is_renderable(""));
+    }
+
+    #[test]
+    fn test_parse_polled() {
+        let polled = Polled::parse(
+            r#"{"headers":true,"disposition":"attachment; filename=a.bin","total":3,"data":"YWL/","done":false,"error":null}"#,
+        )
+        .unwrap();
+        assert!(polled.headers);
+        assert_eq!(
+            polled.disposition.as_deref(),
+            Some("attachment; filename=a.bin")
+        );
+        assert_eq!(polled.total, Some(3));
+        assert_eq!(polled.data, [b'a', b'b', 0xFF]);
+        let polled = Polled::parse(
+            r#"{"headers":false,"disposition":null,"total":null,"data":"","done":false,"error":"HTTP 404"}"#,
+        )
+        .unwrap();
+        assert_eq!(polled.error.as_deref(), Some("HTTP 404"));
+        assert_eq!(Polled::parse("null"), None);
+    }
+
+    /// Relevés successifs, `None` une fois épuisés (page disparue).
+    fn polls(polls: Vec<Polled>) -> impl FnMut() -> Option<Polled> {
+        let mut polls = polls.into_iter();
+        move || polls.next()
+    }
+
+    fn data(bytes: &[u8]) -> Polled {
+        Polled {
+            headers: true,
+            data: bytes.to_vec(),
+            ..Polled::default()
+        }
+    }
+
+    #[test]
+    fn test_receive_streams_to_file() {
+        let dir = TempDir::new("dl-receive");
+        fs::write(dir.join("a.zip"), "old").unwrap();
+        let cancel = AtomicBool::new(false);
+        let mut name = String::new();
+        let mut reports = Vec::new();
+        let result = receive(
+            dir.path(),
+            &url("https://example.com/a.zip"),
+            &cancel,
+            polls(vec![
+                Polled::default(),
+                Polled {
+                    headers: true,
+                    total: Some(4),
+                    ..Polled::default()
+                },
+                data(b"ab"),
+                Polled {
+                    done: true,
+                    ..data(b"cd")
+                },
+            ]),
+            |n| name = n,
+            |p| reports.push(p.percent()),
+        );
+        assert_eq!(result, DownloadResult::Saved(dir.join("a (1).zip")));
+        assert_eq!(name, "a (1).zip");
+        assert_eq!(reports, [Some(50), Some(100)]);
+        assert_eq!(fs::read(dir.join("a (1).zip")).unwrap(), b"abcd");
+    }
+
+    #[test]
+    fn test_receive_failure_removes_partial_file() {
+        let dir = TempDir::new("dl-receive-fail");
+        let cancel = AtomicBool::new(false);
+        let page = url("https://example.com/a.zip");
+        // Page fermée en cours de route
+        let result = receive(
+            dir.path(),
+            &page,
+            &cancel,
+            polls(vec![data(b"ab")]),
+            |_| {},
+            |_| {},
+        );
+        assert!(matches!(result, DownloadResult::Failed(_)));
+        assert!(!dir.join("a.zip").exists());
+
+        let failed = Polled {
+            error: Some("HTTP 410".to_string()),
+            ..Polled::default()
+        };
+        let result = receive(
+            dir.path(),
+            &page,
+            &cancel,
+            polls(vec![failed]),
+            |_| {},
+            |_| {},
+        );
+        assert_eq!(result, DownloadResult::Failed("HTTP 410".to_string()));
+    }
+
+    #[test]
+    fn test_cancelled_receive_removes_file() {
+        let dir = TempDir::new("dl-cancel");
+        let cancel = AtomicBool::new(false);
+        let mut next = polls(vec![data(b"ab"), data(b"cd")]);
+        let result = receive(
+            dir.path(),
+            &url("https://example.com/a.zip"),
+            &cancel,
+            || {
+                let polled = next();
+                cancel.store(true, Ordering::Relaxed);
+                polled
+            },
+            |_| {},
+            |_| {},
+        );
+        assert_eq!(result, DownloadResult::Cancelled);
+        assert!(!dir.join("a.zip").exists());
+    }
+
+    #[test]
+    fn test_file_name_from_disposition_and_url() {
+        let page = url("https://example.com/dl/get.php?id=4");
+        assert_eq!(
+            file_name(Some(r#"attachment; filename="report 2024.pdf""#), &page),
+            "report 2024.pdf"
+        );
+        // `filename*` l'emporte
+        assert_eq!(
+            file_name(
+                Some("attachment; filename=fallback.txt; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"),
+                &page
+            ),
+            "résumé.pdf"
+        );
+        assert_eq!(
+            file_name(None, &url("https://example.com/a/My%20App.zip")),
+            "My App.zip"
+        );
+        assert_eq!(file_name(None, &url("https://example.com/")), "download");
+    }
+
+    #[test]
+    fn test_file_name_is_sanitized() {
+        let page = url("https://example.com/");
+        assert_eq!(
+            file_name(Some(r#"attachment; filename="../../.bashrc""#), &page),
+            "bashrc"
+        );
+        assert_eq!(
+            file_name(Some(r#"attachment; filename="a:b?.zip""#), &page),
+            "a_b_.zip"
+        );
+        assert_eq!(
+            file_name(Some(r#"attachment; filename="..""#), &page),
+            "download"
+        );
+    }
+
+    #[test]
+    fn test_numbered_names() {
+        assert_eq!(numbered("archive.zip", 1), "archive (1).zip");
+        assert_eq!(numbered("sources.tar.gz", 2), "sources (2).tar.gz");
+        assert_eq!(numbered("README", 1), "README (1)");
+        assert_eq!(numbered(".hidden", 1), ".hidden (1)");
+    }
+
+    #[test]
+    fn test_collisions_get_suffix() {
+        let dir = TempDir::new("dl-collide");
+        let first = DownloadWriter::create(dir.path(), "a.zip", None).unwrap();
+        let first = first.finish().unwrap();
+        let second = DownloadWriter::create(dir.path(), "a.zip", None).unwrap();
+        assert_eq!(second.path(), dir.join("a (1).zip"));
+        assert_eq!(first, dir.join("a.zip"));
+    }
+
+    #[test]
+    fn test_abandoned_download_removes_partial_file() {
+        let dir = TempDir::new("dl-partial");
+        let mut writer = DownloadWriter::create(dir.path(), "big.iso", Some(10)).unwrap();
+        writer.write_chunk(b"12345").unwrap();
+        assert_eq!(writer.progress().percent(), Some(50));
+        let path = writer.path().to_path_buf();
+        assert!(path.exists());
+        drop(writer);
+        assert!(!path.exists());
+
+        let mut writer = DownloadWriter::create(dir.path(), "small.zip", Some(3)).unwrap();
+        writer.write_chunk(b"abc").unwrap();
+        let path = writer.finish().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_indicator_and_cancel() {
+        let mut downloads = Downloads::default();
+        assert_eq!(downloads.indicator(), None);
+        let (first, _) = downloads.start("a.zip".to_string());
+        let (second, cancel) = downloads.start("b.pdf".to_string());
+        assert!(downloads.update(
+            second,
+            Progress {
+                received: 42,
+                total: Some(100)
+            }
+        ));
+        assert_eq!(
+            downloads.indicator().unwrap(),
+            "Downloading b.pdf… 42% (+1) — click to cancel"
+        );
+
+        assert!(downloads.cancel_latest());
+        assert!(cancel.load(Ordering::Relaxed));
+        downloads.update(
+            first,
+            Progress {
+                received: 3 << 20,
+                total: None,
+            },
+        );
+        assert_eq!(
+            downloads.indicator().unwrap(),
+            "Downloading a.zip… 3.0 MB — click to cancel"
+        );
+        downloads.finish(first);
+        assert!(!downloads.cancel_latest());
+    }
+}
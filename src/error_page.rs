@@ -0,0 +1,223 @@
+//! Page d'erreur réseau (nom introuvable, connexion refusée, échec TLS,
+//! délai dépassé).
+//!
+//! Servo remplace une page qui n'a pas pu être chargée par sa ressource
+//! `neterror.html`, où il substitue la raison (`${reason}`) : une page
+//! quasi vide, sans l'URL ni moyen de réessayer. SuriBrows fournit à la place
+//! [`BOUNCE_HTML`], qui renvoie l'URL et la raison vers
+//! `http://suribrows.neterror/report` : la requête est interceptée dans
+//! `load_web_resource` et la webview charge la page générée par
+//! [`generate`] (URL `data:`).
+
+use url::Url;
+
+use crate::pages::{self, js_string};
+use crate::settings::html_escape;
+
+/// Domaine des rapports d'erreur interceptés.
+const DOMAIN: &str = "suribrows.neterror";
+
+/// Ressource `neterror.html` servie à Servo : transmet l'erreur à l'embedder.
+/// La page d'erreur est affichée à l'URL qui a échoué : `location.href` est
+/// l'adresse demandée. `location.replace` évite une entrée d'historique.
+pub const BOUNCE_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Error loading page</title>
+</head>
+<body style="background: #1a1a1a">
+<div id="reason" hidden>${reason}</div>
+<script>
+const params = new URLSearchParams({
+    url: location.href,
+    reason: document.getElementById("reason").textContent,
+});
+location.replace("http://suribrows.neterror/report?" + params);
+</script>
+</body>
+</html>"#;
+
+/// Catégorie d'erreur affichée à l'utilisateur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetErrorKind {
+    /// Le nom d'hôte n'a pas pu être résolu (DNS).
+    NameNotResolved,
+    ConnectionRefused,
+    /// Négociation TLS échouée (hors certificat invalide, voir `cert_error`).
+    TlsFailure,
+    TimedOut,
+    Other,
+}
+
+impl NetErrorKind {
+    /// Catégorie d'après la raison donnée par Servo (erreur hyper ou
+    /// système, ex. `dns error: failed to lookup address information`).
+    pub fn classify(reason: &str) -> Self {
+        let reason = reason.to_ascii_lowercase();
+        if reason.contains("dns")
+            || reason.contains("lookup address")
+            || reason.contains("name or service not known")
+            || reason.contains("no such host")
+            || reason.contains("nodename nor servname")
+        {
+            NetErrorKind::NameNotResolved
+        } else if reason.contains("connection refused") || reason.contains("connectionrefused") {
+            NetErrorKind::ConnectionRefused
+        } else if reason.contains("timed out") || reason.contains("timeout") {
+            NetErrorKind::TimedOut
+        } else if reason.contains("tls")
+            || reason.contains("ssl")
+            || reason.contains("handshake")
+            || reason.contains("certificate")
+        {
+            NetErrorKind::TlsFailure
+        } else {
+            NetErrorKind::Other
+        }
+    }
+
+    /// Titre de la page.
+    pub fn title(self) -> &'static str {
+        match self {
+            NetErrorKind::NameNotResolved => "Server not found",
+            NetErrorKind::ConnectionRefused => "Connection refused",
+            NetErrorKind::TlsFailure => "Secure connection failed",
+            NetErrorKind::TimedOut => "Connection timed out",
+            NetErrorKind::Other => "Unable to load the page",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NetErrorKind::NameNotResolved => {
+                "The address could not be resolved. Check it for typos, or check your network connection."
+            }
+            NetErrorKind::ConnectionRefused => {
+                "The server refused the connection. It may be down or not accepting connections on this port."
+            }
+            NetErrorKind::TlsFailure => {
+                "A secure connection to the server could not be established."
+            }
+            NetErrorKind::TimedOut => "The server took too long to respond.",
+            NetErrorKind::Other => "The page could not be loaded.",
+        }
+    }
+}
+
+/// Erreur transmise par [`BOUNCE_HTML`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetErrorReport {
+    /// Page demandée.
+    pub url: Url,
+    pub reason: String,
+}
+
+impl NetErrorReport {
+    /// Lit le rapport d'une URL `http://suribrows.neterror/report?…`.
+    pub fn parse(url: &Url) -> Option<Self> {
+        if url.host_str() != Some(DOMAIN) || url.path() != "/report" {
+            return None;
+        }
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        Some(Self {
+            url: Url::parse(&param("url")?).ok()?,
+            reason: param("reason").unwrap_or_default(),
+        })
+    }
+
+    pub fn kind(&self) -> NetErrorKind {
+        NetErrorKind::classify(&self.reason)
+    }
+}
+
+/// Page d'erreur pour `url` : catégorie, adresse et bouton Retry qui
+/// recharge la page (absent si elle n'est pas rechargeable).
+pub fn generate(url: &Url, kind: NetErrorKind) -> String {
+    let retry = if matches!(url.scheme(), "http" | "https" | "file") {
+        format!(
+            r#"<div class="actions"><button onclick="location.replace({})">Retry</button></div>"#,
+            html_escape(&js_string(url.as_str()))
+        )
+    } else {
+        String::new()
+    };
+    pages::shell(
+        kind.title(),
+        "h1 { color: #ff6a6a; }",
+        &format!(
+            "<h1>{title}</h1>\n<p>{label}</p>\n\
+             <p class=\"detail\"><code>{url}</code></p>\n{retry}",
+            title = kind.title(),
+            label = kind.label(),
+            url = html_escape(url.as_str()),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_reasons() {
+        let cases = [
+            (
+                "error trying to connect: dns error: failed to lookup address information: Name or service not known",
+                NetErrorKind::NameNotResolved,
+            ),
+            (
+                "tcp connect error: Connection refused (os error 111)",
+                NetErrorKind::ConnectionRefused,
+            ),
+            ("operation timed out", NetErrorKind::TimedOut),
+            (
+                "received fatal alert: HandshakeFailure",
+                NetErrorKind::TlsFailure,
+            ),
+            ("connection reset by peer", NetErrorKind::Other),
+        ];
+        for (reason, kind) in cases {
+            assert_eq!(NetErrorKind::classify(reason), kind, "{reason}");
+        }
+    }
+
+    #[test]
+    fn test_report_parse() {
+        let url = Url::parse(
+            "http://suribrows.neterror/report?url=https%3A%2F%2Fnope.invalid%2F&reason=dns+error",
+        )
+        .unwrap();
+        let report = NetErrorReport::parse(&url).unwrap();
+        assert_eq!(report.url.as_str(), "https://nope.invalid/");
+        assert_eq!(report.kind(), NetErrorKind::NameNotResolved);
+
+        let other = Url::parse("http://suribrows.neterror/other?url=https%3A%2F%2Fa.com").unwrap();
+        assert_eq!(NetErrorReport::parse(&other), None);
+        let missing = Url::parse("http://suribrows.neterror/report?reason=x").unwrap();
+        assert_eq!(NetErrorReport::parse(&missing), None);
+    }
+
+    #[test]
+    fn test_generate_escapes_url_and_offers_retry() {
+        let url = Url::parse("https://a.com/?a=1&b=2").unwrap();
+        let html = generate(&url, NetErrorKind::ConnectionRefused);
+        assert!(html.contains("<h1>Connection refused</h1>"));
+        assert!(html.contains("<title>Connection refused</title>"));
+        assert!(html.contains("<code>https://a.com/?a=1&amp;b=2</code>"));
+        assert!(html.contains(
+            r#"onclick="location.replace(&quot;https://a.com/?a=1&amp;b=2&quot;)">Retry</button>"#
+        ));
+
+        let html = generate(
+            &Url::parse("data:text/html,hi").unwrap(),
+            NetErrorKind::Other,
+        );
+        assert!(!html.contains("Retry"));
+        assert!(html.contains("Unable to load the page"));
+    }
+}
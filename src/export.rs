@@ -0,0 +1,317 @@
+//! Export de la page courante sur disque (impression PDF, enregistrement HTML,
+//! images du menu contextuel).
+//!
+//! libservo ne sait pas encore paginer une page pour l'impression : le PDF
+//! contient une seule page, la capture de la zone webview telle qu'affichée,
+//! encodée en JPEG. L'enregistrement HTML écrit le DOM sérialisé par
+//! [`SERIALIZE_SCRIPT`]. Les images sont relues par la page elle-même
+//! ([`fetch_bytes_script`]), avec ses cookies et l'adblock. Ce module ne dépend ni de Servo ni de GL — il reçoit
+//! une image ou un texte déjà obtenus et l'écrit depuis un thread de fond.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, RgbaImage};
+use url::Url;
+
+use crate::config;
+use crate::pages::js_string;
+
+/// Qualité JPEG de la capture embarquée dans le PDF.
+const JPEG_QUALITY: u8 = 90;
+
+/// Longueur maximale d'un nom de fichier proposé (sans l'extension).
+const MAX_FILE_STEM: usize = 100;
+
+/// Nom de fichier proposé pour la page : le titre, à défaut l'hôte, nettoyé
+/// des caractères interdits par Windows, macOS ou Linux.
+pub fn suggested_file_name(title: Option<&str>, host: Option<&str>, extension: &str) -> String {
+    let source = title
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .or(host)
+        .unwrap_or("page");
+    let mut stem = String::new();
+    for c in source.chars() {
+        let c = match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() || c.is_whitespace() => ' ',
+            c => c,
+        };
+        // Espaces consécutifs fusionnés
+        if !(c == ' ' && stem.ends_with(' ')) {
+            stem.push(c);
+        }
+    }
+    let mut stem: String = stem.chars().take(MAX_FILE_STEM).collect();
+    // Windows refuse les noms finissant par un point ou une espace
+    stem.truncate(stem.trim_end_matches(['.', ' ']).len());
+    let stem = stem.trim_start_matches(['.', ' ']);
+    let stem = if stem.is_empty() { "page" } else { stem };
+    format!("{stem}.{extension}")
+}
+
+/// Script renvoyant le document courant sérialisé (doctype compris).
+pub const SERIALIZE_SCRIPT: &str = r#"(function() {
+  const doctype = document.doctype
+    ? new XMLSerializer().serializeToString(document.doctype) + "\n"
+    : "";
+  return doctype + document.documentElement.outerHTML;
+})()"#;
+
+/// Script relisant `url` depuis la page (XHR synchrone, comme
+/// [`crate::pages::FETCH_SOURCE_SCRIPT`]). Le jeu de caractères
+/// `x-user-defined` donne un caractère par octet ; `null` en cas d'échec.
+pub fn fetch_bytes_script(url: &Url) -> String {
+    let url = js_string(url.as_str());
+    format!(
+        r#"(function() {{
+  try {{
+    const request = new XMLHttpRequest();
+    request.open("GET", {url}, false);
+    request.overrideMimeType("text/plain; charset=x-user-defined");
+    request.send();
+    if (request.status === 0 || (request.status >= 200 && request.status < 400)) {{
+      return request.responseText;
+    }}
+  }} catch (e) {{}}
+  return null;
+}})()"#
+    )
+}
+
+/// Octets d'une réponse lue par [`fetch_bytes_script`] : `x-user-defined`
+/// place les octets 0x80–0xFF en U+F780–U+F7FF, l'octet de poids faible
+/// est l'octet d'origine.
+pub fn bytes_from_script(text: &str) -> Vec<u8> {
+    text.chars().map(|c| (c as u32 & 0xFF) as u8).collect()
+}
+
+/// Nom de fichier proposé pour l'image `url` : dernier segment du chemin,
+/// extension d'origine ou, à défaut, devinée depuis `bytes`.
+pub fn image_file_name(url: &Url, bytes: &[u8]) -> String {
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default();
+    let segment = percent_decode(segment);
+    let (stem, extension) = match segment.rsplit_once('.') {
+        Some((stem, ext)) if !ext.is_empty() && ext.len() <= 5 => (stem, ext.to_lowercase()),
+        _ => {
+            let guessed = image::guess_format(bytes)
+                .ok()
+                .and_then(|format| format.extensions_str().first().copied())
+                .unwrap_or("bin");
+            (segment.as_str(), guessed.to_string())
+        }
+    };
+    suggested_file_name(Some(stem), Some("image"), &extension)
+}
+
+/// Décode les `%XX` d'un segment d'URL (séquences invalides laissées telles
+/// quelles).
+pub(crate) fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Écrit des octets bruts (image…) dans `path`.
+pub fn write_bytes(bytes: &[u8], path: &Path) -> io::Result<()> {
+    config::write_atomic(path, bytes)
+}
+
+/// Écrit le HTML sérialisé de la page dans `path`.
+pub fn write_html(html: &str, path: &Path) -> io::Result<()> {
+    config::write_atomic(path, html.as_bytes())
+}
+
+/// Encode la capture et l'écrit dans `path` sous forme de PDF d'une page.
+pub fn write_pdf(capture: &RgbaImage, path: &Path) -> io::Result<()> {
+    let rgb = DynamicImage::ImageRgba8(capture.clone()).to_rgb8();
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+        .encode_image(&rgb)
+        .map_err(io::Error::other)?;
+    let pdf = single_page_pdf(&jpeg, capture.width(), capture.height())?;
+    config::write_atomic(path, &pdf)
+}
+
+/// PDF minimal d'une page contenant l'image JPEG `jpeg` en pleine page.
+/// La page fait la taille de l'image à 96 ppp (un pixel CSS = 0,75 pt).
+pub fn single_page_pdf(jpeg: &[u8], width: u32, height: u32) -> io::Result<Vec<u8>> {
+    let page_w = width as f32 * 0.75;
+    let page_h = height as f32 * 0.75;
+    let content = format!("q {page_w:.2} 0 0 {page_h:.2} 0 0 cm /Im0 Do Q\n");
+
+    let mut pdf = Vec::new();
+    let mut offsets = Vec::new();
+    pdf.write_all(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n")?;
+
+    offsets.push(pdf.len());
+    pdf.write_all(b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n")?;
+
+    offsets.push(pdf.len());
+    pdf.write_all(b"2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n")?;
+
+    offsets.push(pdf.len());
+    write!(
+        pdf,
+        "3 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {page_w:.2} {page_h:.2}] \
+         /Resources << /XObject << /Im0 4 0 R >> >> /Contents 5 0 R >>\nendobj\n"
+    )?;
+
+    offsets.push(pdf.len());
+    write!(
+        pdf,
+        "4 0 obj\n<< /Type /XObject /Subtype /Image /Width {width} /Height {height} \
+         /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+        jpeg.len()
+    )?;
+    pdf.write_all(jpeg)?;
+    pdf.write_all(b"\nendstream\nendobj\n")?;
+
+    offsets.push(pdf.len());
+    write!(
+        pdf,
+        "5 0 obj\n<< /Length {} >>\nstream\n{content}endstream\nendobj\n",
+        content.len()
+    )?;
+
+    let xref = pdf.len();
+    write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1)?;
+    for offset in &offsets {
+        writeln!(pdf, "{offset:010} 00000 n ")?;
+    }
+    write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        offsets.len() + 1
+    )?;
+    Ok(pdf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    /// Offset annoncé par `startxref`.
+    fn startxref(pdf: &[u8]) -> usize {
+        let text = String::from_utf8_lossy(pdf);
+        let tail = text.rsplit("startxref\n").next().unwrap();
+        tail.lines().next().unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_suggested_file_name() {
+        assert_eq!(
+            suggested_file_name(Some("Servo: the <web> engine?"), None, "pdf"),
+            "Servo_ the _web_ engine_.pdf"
+        );
+        assert_eq!(
+            suggested_file_name(Some("  "), Some("example.com"), "html"),
+            "example.com.html"
+        );
+        assert_eq!(suggested_file_name(None, None, "pdf"), "page.pdf");
+        assert_eq!(
+            suggested_file_name(Some("a\n\tb  c..."), None, "pdf"),
+            "a b c.pdf"
+        );
+        assert_eq!(suggested_file_name(Some("..."), None, "pdf"), "page.pdf");
+
+        let long = "é".repeat(300);
+        let name = suggested_file_name(Some(&long), None, "pdf");
+        assert_eq!(name.chars().count(), MAX_FILE_STEM + ".pdf".len());
+    }
+
+    #[test]
+    fn test_image_file_name() {
+        let url = Url::parse("https://example.com/img/Cat%20photo.JPG?w=200").unwrap();
+        assert_eq!(image_file_name(&url, &[]), "Cat photo.jpg");
+        // Sans extension : devinée depuis les octets
+        let url = Url::parse("https://example.com/avatar").unwrap();
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\0";
+        assert_eq!(image_file_name(&url, png), "avatar.png");
+        let url = Url::parse("https://example.com/").unwrap();
+        assert_eq!(image_file_name(&url, b"??"), "image.bin");
+    }
+
+    #[test]
+    fn test_bytes_from_script() {
+        // 0x00, 0x7F, puis 0x80 et 0xFF tels que les donne `x-user-defined`
+        assert_eq!(
+            bytes_from_script("\u{0}\u{7F}\u{F780}\u{F7FF}"),
+            [0x00, 0x7F, 0x80, 0xFF]
+        );
+        let script = fetch_bytes_script(&Url::parse("https://example.com/a\"b.png").unwrap());
+        assert!(script.contains(r#"request.open("GET", "https://example.com/a%22b.png", false)"#));
+    }
+
+    #[test]
+    fn test_pdf_structure() {
+        let pdf = single_page_pdf(b"JPEGDATA", 1280, 800).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/MediaBox [0 0 960.00 600.00]"));
+        assert!(text.contains("/Width 1280 /Height 800"));
+        assert!(text.contains("/Length 8 >>\nstream\nJPEGDATA\nendstream"));
+        assert!(pdf[startxref(&pdf)..].starts_with(b"xref\n0 6\n"));
+    }
+
+    #[test]
+    fn test_pdf_xref_offsets_point_to_objects() {
+        let pdf = single_page_pdf(&[0xFF, 0xD8, 0xFF, 0xD9], 10, 10).unwrap();
+        let xref = startxref(&pdf);
+        let table = String::from_utf8_lossy(&pdf[xref..]);
+        for (n, line) in table.lines().skip(3).take(5).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            let header = format!("{} 0 obj", n + 1);
+            assert!(pdf[offset..].starts_with(header.as_bytes()), "{header}");
+        }
+    }
+
+    #[test]
+    fn test_write_html() {
+        let dir = TempDir::new("export");
+        let path = dir.join("page.html");
+        write_html("<!DOCTYPE html>\n<html>é</html>", &path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "<!DOCTYPE html>\n<html>é</html>"
+        );
+    }
+
+    #[test]
+    fn test_write_pdf_from_capture() {
+        let dir = TempDir::new("export");
+        let path = dir.join("capture.pdf");
+        let capture = RgbaImage::from_pixel(4, 3, image::Rgba([200, 10, 10, 255]));
+        write_pdf(&capture, &path).unwrap();
+
+        let pdf = std::fs::read(&path).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+        // L'image embarquée est un JPEG valide
+        let start = pdf.windows(2).position(|w| w == [0xFF, 0xD8]).unwrap();
+        assert!(image::load_from_memory(&pdf[start..]).is_ok());
+    }
+}
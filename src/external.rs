@@ -0,0 +1,150 @@
+//! Liens vers des applications externes (`mailto:`, `magnet:`, `irc:`…).
+//!
+//! Servo ne sait pas charger ces schémas : la navigation est refusée et,
+//! après confirmation de l'utilisateur, l'URL est confiée au système
+//! (`xdg-open`, `open`, `ShellExecuteW`) sur un thread de fond. Les schémas
+//! de `[privacy] external_scheme_allowlist` sont ouverts sans question ;
+//! aucun autre ne l'est jamais sans confirmation.
+
+use url::Url;
+
+/// Schémas chargés par Servo lui-même.
+const INTERNAL_SCHEMES: &[&str] = &[
+    "http",
+    "https",
+    "file",
+    "data",
+    "about",
+    "blob",
+    "javascript",
+    "ws",
+    "wss",
+    "chrome",
+    "resource",
+];
+
+/// `true` si `url` relève d'une application externe.
+pub fn is_external(url: &Url) -> bool {
+    !INTERNAL_SCHEMES.contains(&url.scheme())
+}
+
+/// `true` si le schéma de `url` figure dans `allowlist` (`"mailto"` ou
+/// `"mailto:"`, casse ignorée).
+pub fn is_allowlisted(url: &Url, allowlist: &[String]) -> bool {
+    allowlist.iter().any(|scheme| {
+        scheme
+            .trim()
+            .trim_end_matches(':')
+            .eq_ignore_ascii_case(url.scheme())
+    })
+}
+
+/// Question posée avant d'ouvrir `url`, avec la cible complète.
+pub fn prompt_message(url: &Url) -> String {
+    format!("Open this link in an external application?\n{url}")
+}
+
+/// Confie `url` au système sur un thread de fond ; un échec est journalisé.
+pub fn open_in_background(url: Url) {
+    std::thread::spawn(move || {
+        if let Err(e) = launch(&url) {
+            tracing::warn!(scheme = url.scheme(), error = %e, "Failed to open external link");
+        }
+    });
+}
+
+/// Ouvre `url` avec le gestionnaire du système. L'URL est passée en un seul
+/// argument, jamais interprétée par un shell.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn launch(url: &Url) -> std::io::Result<()> {
+    std::process::Command::new("xdg-open")
+        .arg(url.as_str())
+        .status()
+        .and_then(exit_status)
+}
+
+#[cfg(target_os = "macos")]
+fn launch(url: &Url) -> std::io::Result<()> {
+    std::process::Command::new("open")
+        .arg(url.as_str())
+        .status()
+        .and_then(exit_status)
+}
+
+#[cfg(unix)]
+fn exit_status(status: std::process::ExitStatus) -> std::io::Result<()> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "opener exited with {status}"
+        )))
+    }
+}
+
+#[cfg(windows)]
+fn launch(url: &Url) -> std::io::Result<()> {
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let wide = |s: &str| s.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+    let verb = wide("open");
+    let target = wide(url.as_str());
+    // SAFETY: chaînes UTF-16 terminées par un zéro, vivantes pendant l'appel
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            verb.as_ptr(),
+            target.as_ptr(),
+            std::ptr::null(),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // Valeur > 32 : succès (convention de ShellExecute)
+    if result as isize > 32 {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "ShellExecuteW failed ({})",
+            result as isize
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_external_schemes() {
+        assert!(is_external(&url("mailto:someone@example.com")));
+        assert!(is_external(&url("magnet:?xt=urn:btih:abc")));
+        assert!(is_external(&url("irc://irc.libera.chat/rust")));
+        assert!(!is_external(&url("https://example.com/")));
+        assert!(!is_external(&url("data:text/html,hi")));
+        assert!(!is_external(&url("about:blank")));
+    }
+
+    #[test]
+    fn test_allowlist_ignores_case_and_colon() {
+        let allowlist = vec!["MailTo:".to_string(), " irc ".to_string()];
+        assert!(is_allowlisted(&url("mailto:a@b.c"), &allowlist));
+        assert!(is_allowlisted(&url("irc://irc.libera.chat"), &allowlist));
+        assert!(!is_allowlisted(&url("magnet:?xt=urn:btih:abc"), &allowlist));
+        assert!(!is_allowlisted(&url("mailto:a@b.c"), &[]));
+    }
+
+    #[test]
+    fn test_prompt_shows_full_target() {
+        let message = prompt_message(&url("magnet:?xt=urn:btih:abc&dn=file"));
+        assert_eq!(
+            message,
+            "Open this link in an external application?\nmagnet:?xt=urn:btih:abc&dn=file"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Favicons de la page courante, prêts à être uploadés dans une texture GL.
+//!
+//! Les icônes arrivent soit sous forme de fichier encodé (PNG, ICO), décodé
+//! avec le crate `image`, soit déjà décodées par Servo. Dans les deux cas on
+//! les normalise en RGBA 8 bits et on les réduit à [`FAVICON_SIZE`] pixels :
+//! un `.ico` peut embarquer du 256×256, inutile de garder ça en mémoire GPU.
+//! Au-delà de [`MAX_SOURCE_SIZE`], l'icône est ignorée.
+//!
+//! La conversion tourne sur un thread de fond ; [`FaviconStore`] garde le
+//! résultat par webview avec un numéro de génération, que le chrome compare
+//! à celui de sa texture pour savoir quand la ré-uploader.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io::Cursor;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader, RgbaImage};
+
+/// Côté (en pixels) des favicons stockés.
+pub const FAVICON_SIZE: u32 = 32;
+
+/// Côté maximal (en pixels) d'une icône source acceptée.
+pub const MAX_SOURCE_SIZE: u32 = 256;
+
+/// Raison pour laquelle une icône est ignorée.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaviconError {
+    /// Format inconnu, données invalides ou buffer de la mauvaise taille.
+    Unsupported,
+    /// Icône plus grande que [`MAX_SOURCE_SIZE`].
+    TooLarge { width: u32, height: u32 },
+}
+
+impl fmt::Display for FaviconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaviconError::Unsupported => write!(f, "unsupported or invalid image"),
+            FaviconError::TooLarge { width, height } => {
+                write!(f, "icon too large ({width}×{height})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FaviconError {}
+
+fn check_size(width: u32, height: u32) -> Result<(), FaviconError> {
+    if width > MAX_SOURCE_SIZE || height > MAX_SOURCE_SIZE {
+        return Err(FaviconError::TooLarge { width, height });
+    }
+    Ok(())
+}
+
+/// Favicon décodé en RGBA 8 bits (non prémultiplié).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Favicon {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Favicon {
+    /// Décode un favicon PNG ou ICO. Les dimensions sont vérifiées avant
+    /// de décoder les pixels.
+    pub fn decode(bytes: &[u8]) -> Result<Self, FaviconError> {
+        let reader = ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(|_| FaviconError::Unsupported)?;
+        if reader.format().is_none() {
+            return Err(FaviconError::Unsupported);
+        }
+        let decoder = reader
+            .into_decoder()
+            .map_err(|_| FaviconError::Unsupported)?;
+        let (width, height) = decoder.dimensions();
+        check_size(width, height)?;
+        let image = DynamicImage::from_decoder(decoder).map_err(|_| FaviconError::Unsupported)?;
+        Ok(Self::from_image(image.into_rgba8()))
+    }
+
+    /// Construit un favicon depuis des pixels RGBA déjà décodés.
+    pub fn from_rgba(width: u32, height: u32, rgba: Vec<u8>) -> Result<Self, FaviconError> {
+        check_size(width, height)?;
+        let image = RgbaImage::from_raw(width, height, rgba).ok_or(FaviconError::Unsupported)?;
+        Ok(Self::from_image(image))
+    }
+
+    /// Réduit l'image à `FAVICON_SIZE` si nécessaire (jamais d'agrandissement).
+    fn from_image(image: RgbaImage) -> Self {
+        let image = if image.width() > FAVICON_SIZE || image.height() > FAVICON_SIZE {
+            DynamicImage::ImageRgba8(image)
+                .resize(FAVICON_SIZE, FAVICON_SIZE, FilterType::Triangle)
+                .into_rgba8()
+        } else {
+            image
+        };
+        Self {
+            width: image.width(),
+            height: image.height(),
+            rgba: image.into_raw(),
+        }
+    }
+}
+
+/// Favicon d'une webview.
+#[derive(Debug, Default)]
+struct Slot {
+    favicon: Option<Favicon>,
+    /// Change à chaque remplacement de `favicon`.
+    generation: u64,
+    /// Jeton du décodage en cours (0 = aucun) ; un résultat portant un autre
+    /// jeton est périmé.
+    pending: u64,
+}
+
+/// Favicons par webview (`K` = identifiant de webview).
+///
+/// Les générations viennent d'un compteur commun à toutes les webviews :
+/// passer d'une webview à l'autre change donc aussi la génération affichée.
+/// 0 signifie « jamais de favicon ».
+#[derive(Debug)]
+pub struct FaviconStore<K> {
+    slots: HashMap<K, Slot>,
+    counter: u64,
+}
+
+impl<K> Default for FaviconStore<K> {
+    fn default() -> Self {
+        Self {
+            slots: HashMap::new(),
+            counter: 0,
+        }
+    }
+}
+
+impl<K: Eq + Hash> FaviconStore<K> {
+    fn next(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    /// Favicon de `key` et sa génération.
+    pub fn get(&self, key: &K) -> (Option<&Favicon>, u64) {
+        self.slots
+            .get(key)
+            .map_or((None, 0), |slot| (slot.favicon.as_ref(), slot.generation))
+    }
+
+    /// Début d'un décodage pour `key` : retourne le jeton à rendre à
+    /// [`finish_decode`](Self::finish_decode). Un décodage précédent encore
+    /// en cours devient périmé.
+    pub fn start_decode(&mut self, key: K) -> u64 {
+        let token = self.next();
+        self.slots.entry(key).or_default().pending = token;
+        token
+    }
+
+    /// Résultat d'un décodage (`None` = icône ignorée → favicon par défaut).
+    /// Retourne `true` si le favicon de `key` a changé.
+    pub fn finish_decode(&mut self, key: K, token: u64, favicon: Option<Favicon>) -> bool {
+        let generation = self.next();
+        let Some(slot) = self.slots.get_mut(&key) else {
+            return false;
+        };
+        if slot.pending != token {
+            return false;
+        }
+        slot.pending = 0;
+        if slot.favicon.is_none() && favicon.is_none() {
+            return false;
+        }
+        slot.favicon = favicon;
+        slot.generation = generation;
+        true
+    }
+
+    /// Oublie le favicon de `key` (nouvelle page) et annule un décodage en
+    /// cours. Retourne `true` si un favicon était affiché.
+    pub fn clear(&mut self, key: &K) -> bool {
+        let generation = self.next();
+        let Some(slot) = self.slots.get_mut(key) else {
+            return false;
+        };
+        slot.pending = 0;
+        if slot.favicon.take().is_none() {
+            return false;
+        }
+        slot.generation = generation;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(image: &RgbaImage, format: image::ImageFormat) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        image.write_to(&mut bytes, format).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn test_decode_png() {
+        let image = RgbaImage::from_pixel(16, 16, image::Rgba([255, 0, 0, 255]));
+        let favicon = Favicon::decode(&encode(&image, image::ImageFormat::Png)).unwrap();
+        assert_eq!((favicon.width, favicon.height), (16, 16));
+        assert_eq!(&favicon.rgba[..4], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_decode_ico() {
+        let image = RgbaImage::from_pixel(16, 16, image::Rgba([0, 0, 255, 128]));
+        let favicon = Favicon::decode(&encode(&image, image::ImageFormat::Ico)).unwrap();
+        assert_eq!((favicon.width, favicon.height), (16, 16));
+        assert_eq!(favicon.rgba.len(), 16 * 16 * 4);
+    }
+
+    #[test]
+    fn test_decode_garbage_is_unsupported() {
+        assert_eq!(
+            Favicon::decode(b"<html>not an icon</html>"),
+            Err(FaviconError::Unsupported)
+        );
+        assert_eq!(Favicon::decode(&[]), Err(FaviconError::Unsupported));
+    }
+
+    #[test]
+    fn test_oversized_icon_skipped() {
+        let image = RgbaImage::new(300, 16);
+        assert_eq!(
+            Favicon::decode(&encode(&image, image::ImageFormat::Png)),
+            Err(FaviconError::TooLarge {
+                width: 300,
+                height: 16
+            })
+        );
+        assert_eq!(
+            Favicon::from_rgba(16, 512, vec![0; 16 * 512 * 4]),
+            Err(FaviconError::TooLarge {
+                width: 16,
+                height: 512
+            })
+        );
+    }
+
+    #[test]
+    fn test_large_icon_downscaled() {
+        let favicon = Favicon::from_rgba(256, 128, vec![255; 256 * 128 * 4]).unwrap();
+        assert_eq!(
+            (favicon.width, favicon.height),
+            (FAVICON_SIZE, FAVICON_SIZE / 2)
+        );
+        assert_eq!(
+            favicon.rgba.len(),
+            (favicon.width * favicon.height * 4) as usize
+        );
+    }
+
+    #[test]
+    fn test_from_rgba_size_mismatch() {
+        assert_eq!(
+            Favicon::from_rgba(4, 4, vec![0; 10]),
+            Err(FaviconError::Unsupported)
+        );
+    }
+
+    fn icon() -> Favicon {
+        Favicon::from_rgba(1, 1, vec![255; 4]).unwrap()
+    }
+
+    #[test]
+    fn test_store_generation_changes_with_favicon() {
+        let mut store = FaviconStore::default();
+        assert_eq!(store.get(&1), (None, 0));
+
+        let token = store.start_decode(1);
+        assert!(store.finish_decode(1, token, Some(icon())));
+        let (favicon, first) = store.get(&1);
+        assert!(favicon.is_some());
+        assert_ne!(first, 0);
+
+        // Nouvelle page : retour au globe, nouvelle génération
+        assert!(store.clear(&1));
+        let (favicon, second) = store.get(&1);
+        assert!(favicon.is_none());
+        assert_ne!(second, first);
+        assert!(!store.clear(&1));
+
+        // Une icône ignorée sans favicon affiché ne change rien
+        let token = store.start_decode(1);
+        assert!(!store.finish_decode(1, token, None));
+        assert_eq!(store.get(&1).1, second);
+    }
+
+    #[test]
+    fn test_store_drops_stale_decodes() {
+        let mut store = FaviconStore::default();
+        let old = store.start_decode(1);
+        let new = store.start_decode(1);
+        assert!(!store.finish_decode(1, old, Some(icon())));
+        assert!(store.finish_decode(1, new, Some(icon())));
+
+        // Navigation pendant le décodage : le résultat arrive trop tard
+        let token = store.start_decode(2);
+        store.clear(&2);
+        assert!(!store.finish_decode(2, token, Some(icon())));
+        assert!(store.get(&2).0.is_none());
+        // Webview inconnue
+        assert!(!store.finish_decode(3, 42, Some(icon())));
+    }
+}
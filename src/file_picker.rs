@@ -0,0 +1,110 @@
+//! Filtres du sélecteur de fichiers (`<input type="file" accept="…">`).
+//!
+//! Servo transmet les motifs de l'attribut `accept` tels quels : extensions
+//! (`.png`) ou types MIME (`image/png`, `image/*`). Les boîtes de dialogue
+//! natives ne connaissent que les extensions : ce module fait la conversion.
+//! La boîte elle-même est ouverte par `browser.rs`.
+
+/// Extensions courantes des familles de types MIME (`image/*`…).
+const WILDCARDS: &[(&str, &[&str])] = &[
+    (
+        "image",
+        &[
+            "png", "jpg", "jpeg", "gif", "webp", "avif", "bmp", "svg", "ico",
+        ],
+    ),
+    ("video", &["mp4", "webm", "ogv", "mov", "mkv"]),
+    (
+        "audio",
+        &["mp3", "ogg", "oga", "opus", "wav", "flac", "m4a"],
+    ),
+    ("text", &["txt", "csv", "html", "htm", "css", "md"]),
+];
+
+/// Extensions des types MIME courants.
+const MIME_TYPES: &[(&str, &[&str])] = &[
+    ("image/png", &["png"]),
+    ("image/jpeg", &["jpg", "jpeg"]),
+    ("image/gif", &["gif"]),
+    ("image/webp", &["webp"]),
+    ("image/avif", &["avif"]),
+    ("image/svg+xml", &["svg"]),
+    ("video/mp4", &["mp4"]),
+    ("video/webm", &["webm"]),
+    ("audio/mpeg", &["mp3"]),
+    ("audio/ogg", &["ogg", "oga", "opus"]),
+    ("text/plain", &["txt"]),
+    ("text/csv", &["csv"]),
+    ("text/html", &["html", "htm"]),
+    ("application/pdf", &["pdf"]),
+    ("application/json", &["json"]),
+    ("application/zip", &["zip"]),
+];
+
+/// Extensions (sans point, en minuscules, sans doublon) acceptées par les
+/// motifs `accept`. Vide = pas de filtre : aucun motif, ou un motif qu'on
+/// ne sait pas traduire (mieux vaut tout proposer que bloquer un fichier
+/// valide).
+pub fn accept_extensions<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut extensions: Vec<String> = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        let found: Vec<&str> = if let Some(ext) = pattern.strip_prefix('.') {
+            vec![ext]
+        } else if let Some(family) = pattern.strip_suffix("/*") {
+            lookup(WILDCARDS, family)
+        } else if pattern.contains('/') {
+            lookup(MIME_TYPES, &pattern)
+        } else {
+            // Servo peut aussi transmettre l'extension sans le point
+            vec![pattern.as_str()]
+        };
+        if found.is_empty() || found.iter().any(|ext| ext.is_empty()) {
+            return Vec::new();
+        }
+        for ext in found {
+            if !extensions.iter().any(|e| e == ext) {
+                extensions.push(ext.to_string());
+            }
+        }
+    }
+    extensions
+}
+
+fn lookup(table: &[(&str, &'static [&'static str])], key: &str) -> Vec<&'static str> {
+    table
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map_or_else(Vec::new, |(_, extensions)| extensions.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extensions_and_mime_types() {
+        assert_eq!(
+            accept_extensions([".PNG", "image/jpeg", "pdf"]),
+            ["png", "jpg", "jpeg", "pdf"]
+        );
+        // Doublons retirés
+        assert_eq!(accept_extensions([".png", "image/png"]), ["png"]);
+    }
+
+    #[test]
+    fn test_wildcard_families() {
+        let extensions = accept_extensions(["image/*"]);
+        assert!(extensions.contains(&"webp".to_string()));
+        assert!(extensions.contains(&"svg".to_string()));
+        assert!(!extensions.contains(&"mp4".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_pattern_disables_filter() {
+        assert!(accept_extensions([".png", "application/x-unknown"]).is_empty());
+        assert!(accept_extensions(["*/*"]).is_empty());
+        assert!(accept_extensions(["."]).is_empty());
+        assert!(accept_extensions(std::iter::empty()).is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+//! Recherche dans la page (Ctrl+F) — état de la barre et script de recherche.
+//!
+//! libservo n'expose pas de recherche native : la barre injecte un petit
+//! script via `WebView::evaluate_javascript`. Le script parcourt les nœuds
+//! texte du document, mémorise les occurrences et sélectionne la courante
+//! (la sélection sert de surlignage), puis renvoie le compteur `"3/17"`.
+
+use crate::pages::js_string;
+use crate::text_input::TextInput;
+
+/// Résultat de la dernière recherche : occurrence courante (1-based) et total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FindMatches {
+    pub current: usize,
+    pub total: usize,
+}
+
+impl FindMatches {
+    /// Compteur affiché à côté du champ (`"3/17"`).
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.current, self.total)
+    }
+
+    /// Lit le compteur renvoyé par [`find_script`].
+    pub fn parse(counter: &str) -> Option<Self> {
+        let (current, total) = counter.split_once('/')?;
+        Some(Self {
+            current: current.trim().parse().ok()?,
+            total: total.trim().parse().ok()?,
+        })
+    }
+}
+
+/// État de la barre de recherche.
+#[derive(Debug, Default)]
+pub struct FindBar {
+    open: bool,
+    /// `true` si la barre reçoit le clavier. Un clic dans la page la garde
+    /// ouverte mais rend le clavier à Servo.
+    focused: bool,
+    input: TextInput,
+    matches: Option<FindMatches>,
+}
+
+impl FindBar {
+    /// Ouvre (ou refocuse) la barre ; le texte précédent est sélectionné.
+    pub fn open(&mut self) {
+        self.open = true;
+        self.focused = true;
+        self.input.select_all();
+    }
+
+    /// Ferme la barre. Le texte est conservé pour la prochaine ouverture.
+    pub fn close(&mut self) {
+        self.open = false;
+        self.focused = false;
+        self.matches = None;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.open && self.focused
+    }
+
+    /// Redonne le clavier à la barre si elle est ouverte (clic dessus).
+    pub fn focus(&mut self) {
+        self.focused = self.open;
+    }
+
+    /// Rend le clavier à la page sans fermer la barre.
+    pub fn unfocus(&mut self) {
+        self.focused = false;
+    }
+
+    pub fn input(&self) -> &TextInput {
+        &self.input
+    }
+
+    pub fn input_mut(&mut self) -> &mut TextInput {
+        &mut self.input
+    }
+
+    pub fn matches(&self) -> Option<FindMatches> {
+        self.matches
+    }
+
+    pub fn set_matches(&mut self, matches: Option<FindMatches>) {
+        self.matches = matches;
+    }
+}
+
+/// Script de recherche de `query`. `step` vaut 0 pour une nouvelle saisie
+/// (première occurrence), 1 pour « suivant », -1 pour « précédent ».
+///
+/// La requête est injectée comme littéral JSON : aucune saisie ne peut
+/// sortir de la chaîne. La recherche ignore la casse.
+pub fn find_script(query: &str, step: i32) -> String {
+    let query = js_string(query);
+    format!(
+        r#"(function(query, step) {{
+  const selection = window.getSelection();
+  let state = window.__suribrowsFind;
+  if (!state || state.query !== query) {{
+    const needle = query.toLowerCase();
+    const ranges = [];
+    if (needle && document.body) {{
+      const walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT);
+      for (let node = walker.nextNode(); node; node = walker.nextNode()) {{
+        const text = node.data.toLowerCase();
+        for (let i = text.indexOf(needle); i !== -1; i = text.indexOf(needle, i + needle.length)) {{
+          const range = document.createRange();
+          range.setStart(node, i);
+          range.setEnd(node, i + needle.length);
+          ranges.push(range);
+        }}
+      }}
+    }}
+    state = window.__suribrowsFind = {{ query: query, ranges: ranges, index: 0 }};
+  }} else if (state.ranges.length) {{
+    state.index = (state.index + step + state.ranges.length) % state.ranges.length;
+  }}
+  selection.removeAllRanges();
+  if (!state.ranges.length) {{
+    return "0/0";
+  }}
+  const range = state.ranges[state.index];
+  selection.addRange(range);
+  const element = range.startContainer.parentElement;
+  if (element) {{
+    element.scrollIntoView({{ block: "center" }});
+  }}
+  return (state.index + 1) + "/" + state.ranges.length;
+}})({query}, {step})"#
+    )
+}
+
+/// Script qui retire le surlignage et oublie les occurrences.
+pub const CLEAR_SCRIPT: &str =
+    "window.getSelection().removeAllRanges(); window.__suribrowsFind = undefined;";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_label_and_parse() {
+        let matches = FindMatches {
+            current: 3,
+            total: 17,
+        };
+        assert_eq!(matches.label(), "3/17");
+        assert_eq!(FindMatches::parse("3/17"), Some(matches));
+        assert_eq!(
+            FindMatches::parse("0/0"),
+            Some(FindMatches {
+                current: 0,
+                total: 0
+            })
+        );
+        assert_eq!(FindMatches::parse("oops"), None);
+        assert_eq!(FindMatches::parse("1/x"), None);
+    }
+
+    #[test]
+    fn test_open_selects_previous_query() {
+        let mut bar = FindBar::default();
+        bar.open();
+        bar.input_mut().insert_char('a');
+        bar.close();
+        assert!(!bar.is_open());
+        assert_eq!(bar.input().text(), "a");
+
+        bar.open();
+        assert!(bar.is_focused());
+        bar.input_mut().insert_char('b');
+        assert_eq!(bar.input().text(), "b");
+    }
+
+    #[test]
+    fn test_unfocus_keeps_bar_open() {
+        let mut bar = FindBar::default();
+        bar.open();
+        bar.unfocus();
+        assert!(bar.is_open());
+        assert!(!bar.is_focused());
+        bar.focus();
+        assert!(bar.is_focused());
+
+        // Une barre fermée ne peut pas prendre le focus
+        bar.close();
+        bar.focus();
+        assert!(!bar.is_focused());
+    }
+
+    #[test]
+    fn test_close_clears_matches() {
+        let mut bar = FindBar::default();
+        bar.open();
+        bar.set_matches(FindMatches::parse("1/2"));
+        bar.close();
+        assert_eq!(bar.matches(), None);
+    }
+
+    #[test]
+    fn test_find_script_escapes_query() {
+        let script = find_script("\"); alert(1); (\"", 1);
+        assert!(script.contains(r#"("\"); alert(1); (\"", 1)"#));
+        assert!(find_script("a\nb", 0).contains(r#"("a\nb", 0)"#));
+    }
+}
@@ -0,0 +1,97 @@
+//! Plein écran demandé par l'utilisateur (F11) ou par la page (vidéo HTML5,
+//! `element.requestFullscreen()`).
+//!
+//! Les deux demandes coexistent sans se contredire : la fenêtre reste en
+//! plein écran tant que l'une d'elles est active, et seul le plein écran de
+//! la page masque le chrome. Quitter celui de la page (Échap, ou la page
+//! elle-même) rend la fenêtre dans l'état voulu par l'utilisateur ; F11
+//! pendant une vidéo en plein écran quitte les deux.
+
+/// Demandes de plein écran en cours.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fullscreen {
+    /// Basculé par F11.
+    user: bool,
+    /// Un élément de la page est en plein écran.
+    page: bool,
+}
+
+impl Fullscreen {
+    /// `true` si la fenêtre doit être en plein écran (sans bordure).
+    pub fn window_fullscreen(self) -> bool {
+        self.user || self.page
+    }
+
+    /// `true` si le chrome est masqué : la page occupe toute la fenêtre.
+    pub fn hides_chrome(self) -> bool {
+        self.page
+    }
+
+    /// `true` si un élément de la page est en plein écran.
+    pub fn is_page(self) -> bool {
+        self.page
+    }
+
+    /// La page entre en plein écran ou en sort.
+    pub fn set_page(&mut self, page: bool) {
+        self.page = page;
+    }
+
+    /// F11. Retourne `true` si la page était en plein écran : il faut alors
+    /// lui demander d'en sortir.
+    pub fn toggle_user(&mut self) -> bool {
+        if self.page {
+            *self = Self::default();
+            return true;
+        }
+        self.user = !self.user;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_fullscreen_hides_chrome() {
+        let mut fullscreen = Fullscreen::default();
+        fullscreen.set_page(true);
+        assert!(fullscreen.window_fullscreen());
+        assert!(fullscreen.hides_chrome());
+        fullscreen.set_page(false);
+        assert_eq!(fullscreen, Fullscreen::default());
+    }
+
+    #[test]
+    fn test_user_fullscreen_keeps_chrome() {
+        let mut fullscreen = Fullscreen::default();
+        assert!(!fullscreen.toggle_user());
+        assert!(fullscreen.window_fullscreen());
+        assert!(!fullscreen.hides_chrome());
+        assert!(!fullscreen.toggle_user());
+        assert!(!fullscreen.window_fullscreen());
+    }
+
+    #[test]
+    fn test_page_exit_restores_user_fullscreen() {
+        let mut fullscreen = Fullscreen::default();
+        fullscreen.toggle_user();
+        fullscreen.set_page(true);
+        assert!(fullscreen.hides_chrome());
+        // La vidéo quitte le plein écran : la fenêtre reste en F11
+        fullscreen.set_page(false);
+        assert!(fullscreen.window_fullscreen());
+        assert!(!fullscreen.hides_chrome());
+    }
+
+    #[test]
+    fn test_f11_during_page_fullscreen_leaves_both() {
+        let mut fullscreen = Fullscreen::default();
+        fullscreen.toggle_user();
+        fullscreen.set_page(true);
+        assert!(fullscreen.toggle_user());
+        assert!(!fullscreen.window_fullscreen());
+        assert!(!fullscreen.is_page());
+    }
+}
@@ -0,0 +1,659 @@
+//! Atlas de glyphes dynamique pour le chrome.
+//!
+//! Les caractères ASCII imprimables sont rastérisés au démarrage. Tout autre
+//! caractère (accents, CJK, emoji…) est rastérisé à la demande par `fontdue`
+//! la première fois que le chrome le rencontre, puis mis en cache.
+//!
+//! Ce module est purement CPU : il ne fait aucun appel OpenGL. Chaque ajout
+//! retourne un [`AtlasUpdate`] que `ChromeRenderer` répercute sur la texture
+//! (`tex_sub_image_2d` pour une région, ré-upload complet si l'atlas a grandi).
+//!
+//! Les paires de crénage (table `kern` de la police) sont relevées en même
+//! temps que les glyphes, pour que le chrome n'ait jamais à consulter `fontdue`.
+
+use std::collections::HashMap;
+
+/// Police embarquée utilisée pour le texte du chrome.
+pub(crate) const FONT_BYTES: &[u8] = include_bytes!("../resources/fonts/Inter-Regular.ttf");
+
+/// Largeur fixe de l'atlas (pixels). Seule la hauteur grandit.
+const ATLAS_WIDTH: u32 = 512;
+
+/// Hauteur initiale de l'atlas (pixels).
+const MIN_ATLAS_HEIGHT: u32 = 64;
+
+/// Espace vide entre deux glyphes pour éviter le bleeding du filtrage linéaire.
+const GLYPH_PADDING: u32 = 1;
+
+/// Caractère de substitution quand la police ne possède pas le glyphe.
+const MISSING_GLYPH_CHAR: char = '□';
+
+/// Informations par glyphe dans l'atlas.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GlyphInfo {
+    /// Position X dans l'atlas (pixels).
+    pub(crate) atlas_x: u32,
+    /// Position Y dans l'atlas (pixels).
+    pub(crate) atlas_y: u32,
+    /// Largeur du glyphe (pixels).
+    pub(crate) width: u32,
+    /// Hauteur du glyphe (pixels).
+    pub(crate) height: u32,
+    /// Avance horizontale (pixels).
+    pub(crate) advance_x: f32,
+    /// Offset X depuis la position de base.
+    pub(crate) offset_x: f32,
+    /// Offset Y depuis la ligne de base (positif = vers le haut).
+    pub(crate) offset_y: f32,
+}
+
+/// Modification de l'atlas à répercuter sur la texture GL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AtlasUpdate {
+    /// Glyphe déjà présent (ou sans pixels) — rien à uploader.
+    None,
+    /// Une région a été écrite — upload partiel via `tex_sub_image_2d`.
+    Region {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    /// L'atlas a grandi — la texture doit être ré-uploadée entièrement.
+    Resized,
+}
+
+/// Atlas de glyphes rastérisés, packés en rangées (shelf packing).
+pub(crate) struct GlyphAtlas {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) glyphs: HashMap<char, GlyphInfo>,
+    pub(crate) pixels: Vec<u8>,
+    font_size: f32,
+    /// Curseur de packing pour les glyphes ajoutés à la demande.
+    shelf: Shelf,
+    /// Glyphe de substitution, rastérisé au premier caractère manquant.
+    missing: Option<GlyphInfo>,
+    /// Ajustement d'avance (pixels, négatif = rapprocher) entre deux
+    /// caractères de l'atlas. Seules les paires non nulles sont stockées.
+    kerning: HashMap<(char, char), f32>,
+}
+
+/// Curseur de shelf packing : les glyphes sont posés de gauche à droite en
+/// rangées, une nouvelle rangée commençant sous le glyphe le plus haut.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Shelf {
+    /// Prochaine position libre dans la rangée courante.
+    pen_x: u32,
+    /// Haut de la rangée courante.
+    pen_y: u32,
+    /// Hauteur du glyphe le plus haut de la rangée courante.
+    row_height: u32,
+}
+
+impl Shelf {
+    /// Réserve un emplacement `w × h` dans une rangée de `atlas_width` pixels.
+    fn place(&mut self, w: u32, h: u32, atlas_width: u32) -> (u32, u32) {
+        if self.pen_x + w > atlas_width {
+            self.pen_x = 0;
+            self.pen_y += self.row_height + GLYPH_PADDING;
+            self.row_height = 0;
+        }
+        let position = (self.pen_x, self.pen_y);
+        self.pen_x += w + GLYPH_PADDING;
+        self.row_height = self.row_height.max(h);
+        position
+    }
+
+    /// Hauteur occupée par les rangées posées jusqu'ici (padding compris).
+    fn used_height(&self) -> u32 {
+        self.pen_y + self.row_height + GLYPH_PADDING
+    }
+}
+
+/// Hauteur d'atlas (puissance de deux, au moins `MIN_ATLAS_HEIGHT`)
+/// suffisante pour `used` pixels de rangées.
+fn atlas_height_for(used: u32) -> u32 {
+    used.next_power_of_two().max(MIN_ATLAS_HEIGHT)
+}
+
+impl GlyphAtlas {
+    /// Construit l'atlas avec les caractères ASCII imprimables (32–126).
+    ///
+    /// Packing en deux passes : tous les glyphes sont d'abord rastérisés et
+    /// placés, puis la hauteur finale est fixée une seule fois avant d'écrire
+    /// le moindre pixel — aucune rangée n'est posée contre une hauteur
+    /// provisoire, quelle que soit la taille de police.
+    pub(crate) fn build(font: &fontdue::Font, font_size: f32) -> Self {
+        // ── Passe 1 : rastériser, mesurer, placer ───────────────────────
+        let rasterized: Vec<(char, fontdue::Metrics, Vec<u8>)> = (32u8..=126)
+            .map(char::from)
+            .filter(|&c| font.lookup_glyph_index(c) != 0)
+            .map(|c| {
+                let (metrics, bitmap) = font.rasterize(c, font_size);
+                (c, metrics, bitmap)
+            })
+            .filter(|(_, metrics, _)| metrics.width as u32 <= ATLAS_WIDTH)
+            .collect();
+
+        let mut shelf = Shelf::default();
+        let positions: Vec<(u32, u32)> = rasterized
+            .iter()
+            .map(|(_, m, _)| {
+                let (w, h) = (m.width as u32, m.height as u32);
+                if w == 0 || h == 0 {
+                    (0, 0)
+                } else {
+                    shelf.place(w, h, ATLAS_WIDTH)
+                }
+            })
+            .collect();
+
+        // ── Passe 2 : hauteur définitive, puis copie des pixels ─────────
+        let height = atlas_height_for(shelf.used_height());
+        let mut atlas = Self {
+            width: ATLAS_WIDTH,
+            height,
+            glyphs: HashMap::new(),
+            pixels: vec![0u8; (ATLAS_WIDTH * height) as usize],
+            font_size,
+            shelf,
+            missing: None,
+            kerning: HashMap::new(),
+        };
+        for ((c, metrics, bitmap), (x, y)) in rasterized.iter().zip(positions) {
+            let (w, h) = (metrics.width as u32, metrics.height as u32);
+            atlas.blit(x, y, w, h, bitmap);
+            atlas.glyphs.insert(
+                *c,
+                GlyphInfo {
+                    atlas_x: x,
+                    atlas_y: y,
+                    width: w,
+                    height: h,
+                    advance_x: metrics.advance_width,
+                    offset_x: metrics.xmin as f32,
+                    offset_y: metrics.ymin as f32,
+                },
+            );
+            atlas.record_kerning(font, *c);
+        }
+
+        // Caractères écartés (absents de la police, trop larges) :
+        // substitution via le chemin incrémental.
+        for b in 32u8..=126 {
+            atlas.ensure_glyph(font, char::from(b));
+        }
+        atlas
+    }
+
+    /// Garantit que `c` est présent dans l'atlas, en le rastérisant si besoin.
+    ///
+    /// Si la police ne possède pas le glyphe, `c` est associé au glyphe de
+    /// substitution « □ » pour qu'il reste visible à l'écran.
+    pub(crate) fn ensure_glyph(&mut self, font: &fontdue::Font, c: char) -> AtlasUpdate {
+        if self.glyphs.contains_key(&c) {
+            return AtlasUpdate::None;
+        }
+
+        if font.lookup_glyph_index(c) == 0 {
+            let (info, update) = self.missing_glyph(font);
+            self.glyphs.insert(c, info);
+            return update;
+        }
+
+        let (metrics, bitmap) = font.rasterize(c, self.font_size);
+        if metrics.width as u32 > self.width {
+            // Glyphe plus large que l'atlas (taille de police absurde) — substitution.
+            let (info, update) = self.missing_glyph(font);
+            self.glyphs.insert(c, info);
+            return update;
+        }
+
+        let (info, update) = self.insert_bitmap(
+            metrics.width as u32,
+            metrics.height as u32,
+            &bitmap,
+            metrics.advance_width,
+            metrics.xmin as f32,
+            metrics.ymin as f32,
+        );
+        self.glyphs.insert(c, info);
+        self.record_kerning(font, c);
+        update
+    }
+
+    /// Ajustement de crénage entre `left` et `right` (0 si aucune paire).
+    pub(crate) fn kern(&self, left: char, right: char) -> f32 {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0.0)
+    }
+
+    /// Relève les paires de crénage entre `c` (qui vient d'entrer dans
+    /// l'atlas) et tous les caractères déjà présents, dans les deux sens.
+    fn record_kerning(&mut self, font: &fontdue::Font, c: char) {
+        let mut found = Vec::new();
+        for &other in self.glyphs.keys() {
+            for pair in [(c, other), (other, c)] {
+                if let Some(kern) = font.horizontal_kern(pair.0, pair.1, self.font_size)
+                    && kern != 0.0
+                {
+                    found.push((pair, kern));
+                }
+            }
+        }
+        self.kerning.extend(found);
+    }
+
+    /// Retourne le glyphe de substitution, en le créant au premier appel.
+    fn missing_glyph(&mut self, font: &fontdue::Font) -> (GlyphInfo, AtlasUpdate) {
+        if let Some(info) = self.missing {
+            return (info, AtlasUpdate::None);
+        }
+
+        let (info, update) = if font.lookup_glyph_index(MISSING_GLYPH_CHAR) != 0 {
+            let (metrics, bitmap) = font.rasterize(MISSING_GLYPH_CHAR, self.font_size);
+            self.insert_bitmap(
+                metrics.width as u32,
+                metrics.height as u32,
+                &bitmap,
+                metrics.advance_width,
+                metrics.xmin as f32,
+                metrics.ymin as f32,
+            )
+        } else {
+            // La police n'a même pas « □ » : on dessine un carré creux nous-mêmes.
+            let (w, h, bitmap) = hollow_box_bitmap(self.font_size);
+            self.insert_bitmap(w, h, &bitmap, w as f32 + 2.0, 1.0, 0.0)
+        };
+
+        self.missing = Some(info);
+        (info, update)
+    }
+
+    /// Place un bitmap dans l'atlas et retourne ses infos + la mise à jour GL.
+    fn insert_bitmap(
+        &mut self,
+        w: u32,
+        h: u32,
+        bitmap: &[u8],
+        advance_x: f32,
+        offset_x: f32,
+        offset_y: f32,
+    ) -> (GlyphInfo, AtlasUpdate) {
+        let mut info = GlyphInfo {
+            atlas_x: 0,
+            atlas_y: 0,
+            width: w,
+            height: h,
+            advance_x,
+            offset_x,
+            offset_y,
+        };
+
+        // Glyphes sans pixels (espace) : aucune place à réserver.
+        if w == 0 || h == 0 {
+            return (info, AtlasUpdate::None);
+        }
+
+        let (x, y, grew) = self.allocate(w, h);
+        info.atlas_x = x;
+        info.atlas_y = y;
+        self.blit(x, y, w, h, bitmap);
+
+        let update = if grew {
+            AtlasUpdate::Resized
+        } else {
+            AtlasUpdate::Region {
+                x,
+                y,
+                width: w,
+                height: h,
+            }
+        };
+        (info, update)
+    }
+
+    /// Réserve un emplacement `w × h`. Retourne `(x, y, atlas_agrandi)`.
+    ///
+    /// La largeur est fixe, donc agrandir la hauteur ne fait qu'ajouter des
+    /// lignes en fin de buffer : les glyphes déjà placés gardent leurs
+    /// coordonnées en pixels (seules les UV normalisées changent).
+    fn allocate(&mut self, w: u32, h: u32) -> (u32, u32, bool) {
+        let (x, y) = self.shelf.place(w, h, self.width);
+
+        let needed = atlas_height_for(self.shelf.used_height());
+        let grew = needed > self.height;
+        if grew {
+            self.height = needed;
+            self.pixels.resize((self.width * self.height) as usize, 0);
+        }
+        (x, y, grew)
+    }
+
+    /// Copie un bitmap `w × h` à la position `(x, y)` de l'atlas.
+    fn blit(&mut self, x: u32, y: u32, w: u32, h: u32, bitmap: &[u8]) {
+        for row in 0..h {
+            for col in 0..w {
+                let src_idx = (row * w + col) as usize;
+                let dst_idx = ((y + row) * self.width + x + col) as usize;
+                if src_idx < bitmap.len() && dst_idx < self.pixels.len() {
+                    self.pixels[dst_idx] = bitmap[src_idx];
+                }
+            }
+        }
+    }
+
+    /// Copie les pixels d'une région de l'atlas (pour `tex_sub_image_2d`).
+    pub(crate) fn region_pixels(&self, x: u32, y: u32, width: u32, height: u32) -> Vec<u8> {
+        let mut out = Vec::with_capacity((width * height) as usize);
+        for row in y..y + height {
+            let start = (row * self.width + x) as usize;
+            out.extend_from_slice(&self.pixels[start..start + width as usize]);
+        }
+        out
+    }
+}
+
+/// Bitmap d'un carré creux (« tofu ») proportionnel à la taille de police.
+fn hollow_box_bitmap(font_size: f32) -> (u32, u32, Vec<u8>) {
+    let w = (font_size * 0.5).round().max(3.0) as u32;
+    let h = (font_size * 0.7).round().max(3.0) as u32;
+    let stroke = ((font_size / 16.0).round() as u32).clamp(1, w / 2);
+    let mut bitmap = vec![0u8; (w * h) as usize];
+    for row in 0..h {
+        for col in 0..w {
+            let on_edge = row < stroke || row >= h - stroke || col < stroke || col >= w - stroke;
+            if on_edge {
+                bitmap[(row * w + col) as usize] = 255;
+            }
+        }
+    }
+    (w, h, bitmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_font() -> fontdue::Font {
+        fontdue::Font::from_bytes(FONT_BYTES, fontdue::FontSettings::default())
+            .expect("Failed to load Inter font")
+    }
+
+    fn build_test_atlas() -> GlyphAtlas {
+        GlyphAtlas::build(&test_font(), 16.0)
+    }
+
+    fn assert_no_overlap(atlas: &GlyphAtlas) {
+        let glyphs: Vec<_> = atlas.glyphs.iter().collect();
+        for i in 0..glyphs.len() {
+            for j in (i + 1)..glyphs.len() {
+                let (&c1, g1) = glyphs[i];
+                let (&c2, g2) = glyphs[j];
+                // Skip zero-size glyphs (like space)
+                if g1.width == 0 || g1.height == 0 || g2.width == 0 || g2.height == 0 {
+                    continue;
+                }
+                // Characters sharing the missing glyph point at the same slot
+                if g1.atlas_x == g2.atlas_x && g1.atlas_y == g2.atlas_y {
+                    continue;
+                }
+                let overlap_x =
+                    g1.atlas_x < g2.atlas_x + g2.width && g2.atlas_x < g1.atlas_x + g1.width;
+                let overlap_y =
+                    g1.atlas_y < g2.atlas_y + g2.height && g2.atlas_y < g1.atlas_y + g1.height;
+                assert!(
+                    !(overlap_x && overlap_y),
+                    "Glyphs '{}' and '{}' overlap",
+                    c1,
+                    c2
+                );
+            }
+        }
+    }
+
+    fn assert_in_bounds(atlas: &GlyphAtlas) {
+        for (&c, glyph) in &atlas.glyphs {
+            assert!(
+                glyph.atlas_x + glyph.width <= atlas.width,
+                "Glyph '{}' exceeds atlas width: {} + {} > {}",
+                c,
+                glyph.atlas_x,
+                glyph.width,
+                atlas.width
+            );
+            assert!(
+                glyph.atlas_y + glyph.height <= atlas.height,
+                "Glyph '{}' exceeds atlas height: {} + {} > {}",
+                c,
+                glyph.atlas_y,
+                glyph.height,
+                atlas.height
+            );
+        }
+    }
+
+    #[test]
+    fn test_atlas_contains_all_ascii_printable() {
+        let atlas = build_test_atlas();
+        for b in 32u8..=126 {
+            let c = b as char;
+            assert!(
+                atlas.glyphs.contains_key(&c),
+                "Atlas missing char '{}' ({})",
+                c,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn test_atlas_width_is_512() {
+        let atlas = build_test_atlas();
+        assert_eq!(atlas.width, 512);
+    }
+
+    #[test]
+    fn test_atlas_height_valid() {
+        let atlas = build_test_atlas();
+        assert!(atlas.height > 0);
+        assert!(atlas.height >= 64, "Atlas height should be >= 64");
+    }
+
+    #[test]
+    fn test_atlas_pixel_buffer_size() {
+        let atlas = build_test_atlas();
+        assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+    }
+
+    #[test]
+    fn test_glyph_advance_positive() {
+        let atlas = build_test_atlas();
+        for (&c, glyph) in &atlas.glyphs {
+            assert!(
+                glyph.advance_x > 0.0,
+                "Glyph '{}' has non-positive advance_x: {}",
+                c,
+                glyph.advance_x
+            );
+        }
+    }
+
+    #[test]
+    fn test_glyphs_within_atlas_bounds() {
+        let atlas = build_test_atlas();
+        assert_in_bounds(&atlas);
+    }
+
+    #[test]
+    fn test_no_overlapping_glyphs() {
+        let atlas = build_test_atlas();
+        assert_no_overlap(&atlas);
+    }
+
+    #[test]
+    fn test_large_font_sizes_pack_without_overlap() {
+        let font = test_font();
+        for size in [12.0, 24.0, 48.0, 72.0] {
+            let atlas = GlyphAtlas::build(&font, size);
+            assert_no_overlap(&atlas);
+            assert_in_bounds(&atlas);
+            assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+            assert!(
+                atlas.height.is_power_of_two(),
+                "size {size}: height {}",
+                atlas.height
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_pixels_match_rasterized_glyph() {
+        // Les pixels écrits en passe 2 sont ceux du glyphe, à sa position finale
+        let font = test_font();
+        let atlas = GlyphAtlas::build(&font, 72.0);
+        let glyph = atlas.glyphs[&'W'];
+        let (_, bitmap) = font.rasterize('W', 72.0);
+        let region = atlas.region_pixels(glyph.atlas_x, glyph.atlas_y, glyph.width, glyph.height);
+        assert_eq!(region, bitmap);
+    }
+
+    #[test]
+    fn test_shelf_wraps_to_next_row() {
+        let mut shelf = Shelf::default();
+        assert_eq!(shelf.place(300, 20, 512), (0, 0));
+        assert_eq!(shelf.place(300, 10, 512), (0, 20 + GLYPH_PADDING));
+        assert_eq!(shelf.used_height(), 20 + GLYPH_PADDING + 10 + GLYPH_PADDING);
+    }
+
+    #[test]
+    fn test_atlas_height_for() {
+        assert_eq!(atlas_height_for(1), MIN_ATLAS_HEIGHT);
+        assert_eq!(atlas_height_for(65), 128);
+        assert_eq!(atlas_height_for(512), 512);
+    }
+
+    #[test]
+    fn test_space_has_zero_dimensions() {
+        let atlas = build_test_atlas();
+        let space = atlas.glyphs.get(&' ').expect("Space glyph missing");
+        assert_eq!(space.width, 0, "Space should have width 0");
+        assert_eq!(space.height, 0, "Space should have height 0");
+        assert!(space.advance_x > 0.0, "Space should have positive advance");
+    }
+
+    #[test]
+    fn test_ensure_existing_glyph_is_noop() {
+        let font = test_font();
+        let mut atlas = GlyphAtlas::build(&font, 16.0);
+        assert_eq!(atlas.ensure_glyph(&font, 'a'), AtlasUpdate::None);
+    }
+
+    #[test]
+    fn test_ensure_accented_glyph_rasterized() {
+        let font = test_font();
+        let mut atlas = GlyphAtlas::build(&font, 16.0);
+        let update = atlas.ensure_glyph(&font, 'é');
+        assert_ne!(update, AtlasUpdate::None);
+        let glyph = atlas.glyphs.get(&'é').expect("'é' should be cached");
+        assert!(glyph.width > 0 && glyph.height > 0);
+    }
+
+    #[test]
+    fn test_japanese_url_populates_atlas() {
+        let font = test_font();
+        let mut atlas = GlyphAtlas::build(&font, 16.0);
+        let url = "https://例え.jp/東京/ページ?q=日本語";
+        for c in url.chars() {
+            atlas.ensure_glyph(&font, c);
+        }
+        for c in url.chars() {
+            let glyph = atlas.glyphs.get(&c).expect("char should be cached");
+            assert!(glyph.advance_x > 0.0, "'{}' must advance the pen", c);
+        }
+        assert_in_bounds(&atlas);
+        assert_no_overlap(&atlas);
+        assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+    }
+
+    #[test]
+    fn test_kerning_matches_font() {
+        let font = test_font();
+        let mut atlas = GlyphAtlas::build(&font, 16.0);
+        atlas.ensure_glyph(&font, 'é');
+        for (left, right) in [('A', 'V'), ('T', 'o'), ('V', 'é'), ('é', 'T')] {
+            let expected = font.horizontal_kern(left, right, 16.0).unwrap_or(0.0);
+            assert_eq!(atlas.kern(left, right), expected, "{left}{right}");
+        }
+    }
+
+    #[test]
+    fn test_kern_unknown_pair_is_zero() {
+        let atlas = build_test_atlas();
+        assert_eq!(atlas.kern('ß', '€'), 0.0);
+    }
+
+    #[test]
+    fn test_missing_glyph_is_visible_box() {
+        let font = test_font();
+        let mut atlas = GlyphAtlas::build(&font, 16.0);
+        // Inter has no CJK coverage
+        assert_eq!(font.lookup_glyph_index('東'), 0);
+        atlas.ensure_glyph(&font, '東');
+        let glyph = atlas.glyphs[&'東'];
+        assert!(
+            glyph.width > 0 && glyph.height > 0,
+            "Missing glyph must be drawn"
+        );
+        let pixels = atlas.region_pixels(glyph.atlas_x, glyph.atlas_y, glyph.width, glyph.height);
+        assert!(pixels.iter().any(|&p| p > 0), "Missing glyph must have ink");
+    }
+
+    #[test]
+    fn test_missing_glyph_shared_between_chars() {
+        let font = test_font();
+        let mut atlas = GlyphAtlas::build(&font, 16.0);
+        atlas.ensure_glyph(&font, '東');
+        let height_before = atlas.height;
+        assert_eq!(atlas.ensure_glyph(&font, '京'), AtlasUpdate::None);
+        let a = atlas.glyphs[&'東'];
+        let b = atlas.glyphs[&'京'];
+        assert_eq!((a.atlas_x, a.atlas_y), (b.atlas_x, b.atlas_y));
+        assert_eq!(atlas.height, height_before);
+    }
+
+    #[test]
+    fn test_atlas_grows_when_full() {
+        let font = test_font();
+        let mut atlas = GlyphAtlas::build(&font, 16.0);
+        let initial_height = atlas.height;
+        let mut resized = false;
+        for c in ('À'..='ÿ').chain('Ā'..='ž') {
+            if atlas.ensure_glyph(&font, c) == AtlasUpdate::Resized {
+                resized = true;
+            }
+        }
+        assert!(resized, "Atlas should report a resize");
+        assert!(atlas.height > initial_height);
+        assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+        assert_in_bounds(&atlas);
+        assert_no_overlap(&atlas);
+    }
+
+    #[test]
+    fn test_region_pixels_size() {
+        let atlas = build_test_atlas();
+        let glyph = atlas.glyphs[&'W'];
+        let pixels = atlas.region_pixels(glyph.atlas_x, glyph.atlas_y, glyph.width, glyph.height);
+        assert_eq!(pixels.len(), (glyph.width * glyph.height) as usize);
+    }
+
+    #[test]
+    fn test_hollow_box_has_border_and_hole() {
+        let (w, h, bitmap) = hollow_box_bitmap(16.0);
+        assert_eq!(bitmap.len(), (w * h) as usize);
+        assert_eq!(bitmap[0], 255, "Corner should be inked");
+        let center = ((h / 2) * w + w / 2) as usize;
+        assert_eq!(bitmap[center], 0, "Center should be hollow");
+    }
+}
@@ -0,0 +1,28 @@
+//! Client HTTP(S) des requêtes faites hors de Servo : mise à jour des
+//! listes de filtres. Utilisé sur un runtime tokio `current_thread` d'un
+//! thread de fond ; certificats racines webpki.
+
+use http_body_util::Empty;
+use hyper::body::Bytes;
+use hyper_rustls::HttpsConnector;
+use hyper_util::client::legacy::Client as LegacyClient;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::TokioExecutor;
+
+/// Client HTTP/1.1 pour des requêtes sans corps (GET).
+pub type Client = LegacyClient<HttpsConnector<HttpConnector>, Empty<Bytes>>;
+
+/// Client HTTP(S) validant les certificats avec les racines webpki.
+pub fn client() -> Client {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let tls = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let connector = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_tls_config(tls)
+        .https_or_http()
+        .enable_http1()
+        .build();
+    LegacyClient::builder(TokioExecutor::new()).build(connector)
+}
@@ -0,0 +1,265 @@
+//! Mode HTTPS uniquement (`[privacy] https_only`) : navigations `http://`
+//! réécrites en `https://`, repli en clair sur demande.
+//!
+//! `request_navigation` refuse une navigation `http://` et la relance en
+//! `https://` ([`HttpsUpgrades::upgrade`]). Si la version https échoue
+//! (connexion refusée, échec TLS, certificat invalide…) ou renvoie vers la
+//! même page en http, la page est remplacée par [`interstitial_html`], qui
+//! propose de continuer en http :
+//! son lien `http://suribrows.httpsonly/continue?token=…` est intercepté dans
+//! `load_web_resource` et autorise l'hôte en http pour la session. Les
+//! exceptions ne vivent qu'en mémoire, comme celles des certificats.
+//!
+//! Ne sont jamais réécrits : les hôtes locaux (`localhost`, adresses IP,
+//! noms sans point), les ports explicites (un service https y écoute
+//! rarement) et les domaines internes `suribrows.*` (paramètres, pages
+//! d'erreur), interceptés avant d'atteindre le réseau.
+
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use url::Url;
+
+use crate::pages;
+use crate::settings::html_escape;
+use crate::token::{self, PendingTokens};
+
+/// Domaine des requêtes interceptées (lien « continue over http »).
+const DOMAIN: &str = "suribrows.httpsonly";
+
+/// Préfixe des domaines internes, interceptés dans `load_web_resource`.
+const INTERNAL_PREFIX: &str = "suribrows.";
+
+/// Nombre maximal de navigations réécrites mémorisées. Au-delà, elles sont
+/// oubliées (une vieille réécriture n'offre plus de repli).
+const MAX_TRACKED: usize = 64;
+
+/// `true` si `host` peut être réécrit en https.
+fn is_upgradable_host(host: &str) -> bool {
+    let host = host.trim_end_matches('.');
+    host.contains('.')
+        && host.trim_matches(['[', ']']).parse::<IpAddr>().is_err()
+        && !host.ends_with(".localhost")
+        && !host.starts_with(INTERNAL_PREFIX)
+}
+
+/// Hôte en minuscules sans point final.
+fn normalize(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Jeton d'un lien `http://suribrows.httpsonly/continue?token=…`.
+pub fn continue_token(url: &Url) -> Option<String> {
+    token::url_token(url, DOMAIN, "/continue")
+}
+
+/// Décision de [`HttpsUpgrades::upgrade`] pour une navigation http.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Upgrade {
+    /// Navigation relancée à cette URL https.
+    Https(Url),
+    /// La version https renvoie vers cette même URL http : avertissement,
+    /// ni boucle ni chargement en clair.
+    Downgraded,
+}
+
+/// Raison affichée quand la version https renvoie vers http.
+pub const DOWNGRADE_REASON: &str = "the HTTPS site redirects back to HTTP";
+
+/// Réécritures https de la session et hôtes autorisés en http.
+#[derive(Debug, Default)]
+pub struct HttpsUpgrades {
+    /// Hôtes que l'utilisateur a choisi d'ouvrir en http.
+    allowed: HashSet<String>,
+    /// Dernière URL http réécrite, par hôte : une navigation https échouée
+    /// vers cet hôte propose le repli.
+    upgraded: HashMap<String, Url>,
+    /// Avertissements affichés, par jeton du lien « continue over http ».
+    pending: PendingTokens<Url>,
+}
+
+impl HttpsUpgrades {
+    /// Version https de la navigation `url`, `None` si elle ne doit pas être
+    /// réécrite : pas `http://`, hôte local ou autorisé en http, port
+    /// explicite. La même URL réécrite deux fois de suite (le site https
+    /// renvoie vers http) donne [`Upgrade::Downgraded`] ; la réécriture est
+    /// oubliée.
+    pub fn upgrade(&mut self, url: &Url) -> Option<Upgrade> {
+        if url.scheme() != "http" || url.port().is_some() {
+            return None;
+        }
+        let host = normalize(url.host_str()?);
+        if !is_upgradable_host(&host) || self.allowed.contains(&host) {
+            return None;
+        }
+        if self.upgraded.get(&host) == Some(url) {
+            self.upgraded.remove(&host);
+            return Some(Upgrade::Downgraded);
+        }
+        let mut https = url.clone();
+        https.set_scheme("https").ok()?;
+        if self.upgraded.len() >= MAX_TRACKED {
+            self.upgraded.clear();
+        }
+        self.upgraded.insert(host, url.clone());
+        Some(Upgrade::Https(https))
+    }
+
+    /// La navigation `failed` a échoué (réseau, TLS, certificat). Si son
+    /// hôte vient d'être réécrit en https, retourne la même page en http à
+    /// proposer ; la réécriture est oubliée.
+    pub fn fallback_for(&mut self, failed: &Url) -> Option<Url> {
+        if failed.scheme() != "https" {
+            return None;
+        }
+        self.upgraded.remove(&normalize(failed.host_str()?))?;
+        let mut http = failed.clone();
+        http.set_scheme("http").ok()?;
+        Some(http)
+    }
+
+    /// `true` si `host` est autorisé en http pour la session.
+    pub fn is_allowed(&self, host: &str) -> bool {
+        self.allowed.contains(&normalize(host))
+    }
+
+    /// Enregistre l'avertissement affiché pour `http_url` ; retourne le jeton
+    /// de son lien.
+    pub fn propose(&mut self, http_url: Url) -> String {
+        self.pending.propose(http_url)
+    }
+
+    /// L'utilisateur continue en http : l'hôte est autorisé pour la session.
+    /// Retourne la page à ouvrir, `None` pour un jeton inconnu.
+    pub fn accept(&mut self, token: &str) -> Option<Url> {
+        let url = self.pending.take(token)?;
+        self.allowed.insert(normalize(url.host_str()?));
+        Some(url)
+    }
+}
+
+/// Avertissement : la version https de `http_url` est inaccessible. Bouton
+/// « Go back » (au-delà de la page en échec, comme l'avertissement de
+/// certificat) et lien pour continuer en http.
+pub fn interstitial_html(http_url: &Url, reason: &str, token: &str) -> String {
+    pages::shell(
+        "Secure connection unavailable",
+        "h1 { color: #ffb35c; }",
+        &format!(
+            r#"<h1>{host} does not support a secure connection</h1>
+<p>HTTPS-only mode tried to load this site over HTTPS, but it failed. Over
+plain HTTP, anyone on the network can read or change what you send and
+receive.</p>
+<p class="detail">Error: <code>{reason}</code></p>
+<p class="detail"><code>{url}</code></p>
+<div class="actions">
+<button onclick="if (history.length > 2) history.go(-2); else location.replace('about:blank')">Go back</button>
+<a href="http://{DOMAIN}/continue?token={token}">Continue to the HTTP site</a>
+</div>"#,
+            host = html_escape(http_url.host_str().unwrap_or_default()),
+            reason = html_escape(reason),
+            url = html_escape(http_url.as_str()),
+            token = html_escape(token),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_upgrade_rewrites_http_navigations() {
+        let mut upgrades = HttpsUpgrades::default();
+        assert_eq!(
+            upgrades.upgrade(&url("http://example.com/a?b=1#c")),
+            Some(Upgrade::Https(url("https://example.com/a?b=1#c")))
+        );
+        assert_eq!(upgrades.upgrade(&url("https://example.com/")), None);
+        assert_eq!(upgrades.upgrade(&url("http://example.com:8080/")), None);
+        assert_eq!(upgrades.upgrade(&url("http://localhost/")), None);
+        assert_eq!(upgrades.upgrade(&url("http://app.localhost/")), None);
+        assert_eq!(upgrades.upgrade(&url("http://192.168.1.1/")), None);
+        assert_eq!(upgrades.upgrade(&url("http://[::1]/")), None);
+        assert_eq!(upgrades.upgrade(&url("http://printer/")), None);
+        assert_eq!(
+            upgrades.upgrade(&url("http://suribrows.settings/save?a=1")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_upgrade_loop_shows_warning() {
+        let mut upgrades = HttpsUpgrades::default();
+        let http = url("http://downgrade.example/");
+        assert!(matches!(upgrades.upgrade(&http), Some(Upgrade::Https(_))));
+        // Le site https renvoie vers la même URL http : avertissement, jamais
+        // de chargement en clair
+        assert_eq!(upgrades.upgrade(&http), Some(Upgrade::Downgraded));
+        assert!(matches!(upgrades.upgrade(&http), Some(Upgrade::Https(_))));
+
+        // Continuer en http autorise l'hôte : plus de réécriture
+        let token = upgrades.propose(http.clone());
+        assert_eq!(upgrades.accept(&token), Some(http.clone()));
+        assert_eq!(upgrades.upgrade(&http), None);
+    }
+
+    #[test]
+    fn test_failed_upgrade_offers_fallback_once() {
+        let mut upgrades = HttpsUpgrades::default();
+        let Some(Upgrade::Https(https)) = upgrades.upgrade(&url("http://old.example/page")) else {
+            panic!("not upgraded");
+        };
+
+        // Échec simulé de la navigation https
+        let fallback = upgrades.fallback_for(&https);
+        assert_eq!(fallback, Some(url("http://old.example/page")));
+        assert_eq!(upgrades.fallback_for(&https), None);
+        // Un hôte jamais réécrit n'a pas de repli
+        assert_eq!(upgrades.fallback_for(&url("https://other.example/")), None);
+    }
+
+    #[test]
+    fn test_exception_allows_http_for_the_session() {
+        let mut upgrades = HttpsUpgrades::default();
+        let Some(Upgrade::Https(https)) = upgrades.upgrade(&url("http://old.example/page")) else {
+            panic!("not upgraded");
+        };
+        let http = upgrades.fallback_for(&https).unwrap();
+        assert_eq!(upgrades.accept("forged"), None);
+
+        let token = upgrades.propose(http.clone());
+        assert!(!upgrades.is_allowed("old.example"));
+        assert_eq!(upgrades.accept(&token), Some(http.clone()));
+        assert!(upgrades.is_allowed("OLD.example"));
+        assert_eq!(upgrades.accept(&token), None);
+
+        // L'hôte autorisé n'est plus réécrit ; les autres le sont toujours
+        assert_eq!(upgrades.upgrade(&http), None);
+        assert_eq!(upgrades.upgrade(&url("http://old.example/other")), None);
+        assert!(upgrades.upgrade(&url("http://new.example/")).is_some());
+    }
+
+    #[test]
+    fn test_interstitial_links_token() {
+        let html = interstitial_html(
+            &url("http://old.example/?a=1&b=2"),
+            "connection refused",
+            "abc123",
+        );
+        assert!(html.contains("<h1>old.example does not support a secure connection</h1>"));
+        assert!(html.contains("<code>http://old.example/?a=1&amp;b=2</code>"));
+        assert!(html.contains(r#"href="http://suribrows.httpsonly/continue?token=abc123""#));
+
+        let link = url("http://suribrows.httpsonly/continue?token=abc123");
+        assert_eq!(continue_token(&link).as_deref(), Some("abc123"));
+        assert_eq!(
+            continue_token(&url("http://example.com/continue?token=abc123")),
+            None
+        );
+    }
+}
@@ -0,0 +1,607 @@
+//! Raccourcis clavier globaux, configurables dans la section `[keybindings]`.
+//!
+//! Chaque action a des combinaisons par défaut ; la configuration peut les
+//! remplacer action par action :
+//!
+//! ```toml
+//! [keybindings]
+//! focus_url_bar = "Ctrl+K"
+//! reload = ["Ctrl+R", "F5"]
+//! go_home = []            # désactive le raccourci
+//! ```
+//!
+//! Un nom d'action inconnu ou une combinaison illisible est signalé dans les
+//! logs et l'action garde ses raccourcis par défaut.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use tracing::warn;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::config::KeyChords;
+
+/// Action déclenchable par un raccourci global.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    FocusUrlBar,
+    Find,
+    ToggleBookmark,
+    OpenSettings,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    Reload,
+    GoHome,
+    GoBack,
+    GoForward,
+    PrintPdf,
+    SavePage,
+    ViewSource,
+    /// Affiche (ou masque) la marche à suivre pour connecter les devtools
+    /// Firefox ; n'ouvre pas d'inspecteur.
+    DevtoolsInfo,
+    ToggleFullscreen,
+    /// Reconstruit le moteur adblock (listes et `user-filters.txt` relus).
+    ReloadFilters,
+    /// Efface cache, cookies et stockage des sites (voir
+    /// [`crate::browsing_data`]).
+    ClearBrowsingData,
+    /// Bloque ou rétablit les scripts de l'hôte courant (voir
+    /// [`crate::no_js`]).
+    ToggleJavaScript,
+    Quit,
+}
+
+impl Action {
+    /// Toutes les actions, dans l'ordre de priorité en cas de conflit.
+    pub const ALL: &[Action] = &[
+        Action::FocusUrlBar,
+        Action::Find,
+        Action::ToggleBookmark,
+        Action::OpenSettings,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ZoomReset,
+        Action::Reload,
+        Action::GoHome,
+        Action::GoBack,
+        Action::GoForward,
+        Action::PrintPdf,
+        Action::SavePage,
+        Action::ViewSource,
+        Action::DevtoolsInfo,
+        Action::ToggleFullscreen,
+        Action::ReloadFilters,
+        Action::ClearBrowsingData,
+        Action::ToggleJavaScript,
+        Action::Quit,
+    ];
+
+    /// Nom de l'action dans `[keybindings]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::FocusUrlBar => "focus_url_bar",
+            Action::Find => "find",
+            Action::ToggleBookmark => "toggle_bookmark",
+            Action::OpenSettings => "open_settings",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::ZoomReset => "zoom_reset",
+            Action::Reload => "reload",
+            Action::GoHome => "go_home",
+            Action::GoBack => "go_back",
+            Action::GoForward => "go_forward",
+            Action::PrintPdf => "print_pdf",
+            Action::SavePage => "save_page",
+            Action::ViewSource => "view_source",
+            Action::DevtoolsInfo => "devtools_info",
+            Action::ToggleFullscreen => "toggle_fullscreen",
+            Action::ReloadFilters => "reload_filters",
+            Action::ClearBrowsingData => "clear_browsing_data",
+            Action::ToggleJavaScript => "toggle_javascript",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// Action correspondant à un nom de `[keybindings]`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+
+    /// Raccourcis par défaut.
+    pub fn default_chords(self) -> &'static [&'static str] {
+        match self {
+            Action::FocusUrlBar => &["Ctrl+L"],
+            Action::Find => &["Ctrl+F"],
+            Action::ToggleBookmark => &["Ctrl+D"],
+            Action::OpenSettings => &["Ctrl+,"],
+            Action::ZoomIn => &["Ctrl+=", "Ctrl++", "ZoomIn"],
+            Action::ZoomOut => &["Ctrl+-", "ZoomOut"],
+            Action::ZoomReset => &["Ctrl+0"],
+            Action::Reload => &["Ctrl+R", "F5"],
+            Action::GoHome => &["Alt+Home"],
+            Action::GoBack => &["Alt+Left"],
+            Action::GoForward => &["Alt+Right"],
+            Action::PrintPdf => &["Ctrl+P"],
+            Action::SavePage => &["Ctrl+S"],
+            Action::ViewSource => &["Ctrl+U"],
+            Action::DevtoolsInfo => &["F12"],
+            Action::ToggleFullscreen => &["F11"],
+            Action::ReloadFilters => &["Ctrl+Shift+F"],
+            Action::ClearBrowsingData => &["Ctrl+Shift+Delete"],
+            Action::ToggleJavaScript => &["Ctrl+Shift+J"],
+            Action::Quit => &["Ctrl+Q"],
+        }
+    }
+
+    /// `true` si l'action se répète quand la touche reste enfoncée. Seules
+    /// les actions idempotentes le font : une touche maintenue ne doit pas
+    /// enchaîner les rechargements, l'historique ou les paliers de zoom.
+    pub fn allows_repeat(self) -> bool {
+        matches!(self, Action::FocusUrlBar | Action::Find)
+    }
+
+    /// `true` si l'action cède la touche à un champ de saisie focusé (barre
+    /// d'URL, recherche) : Alt+Home y reste « début de ligne ».
+    pub fn yields_to_text_input(self) -> bool {
+        matches!(self, Action::GoHome)
+    }
+}
+
+/// Touche d'une combinaison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ChordKey {
+    /// Caractère, en minuscule.
+    Char(char),
+    Named(NamedKey),
+}
+
+/// Combinaison de touches (`"Ctrl+Shift+L"`, `"F5"`).
+///
+/// Pour un caractère non alphabétique, Shift est ignoré : il fait partie du
+/// caractère lui-même (`+` s'obtient avec Shift+= sur un clavier QWERTY).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    logo: bool,
+    key: ChordKey,
+}
+
+/// Combinaison illisible dans `[keybindings]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordError {
+    Empty,
+    UnknownModifier(String),
+    UnknownKey(String),
+}
+
+impl fmt::Display for ChordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChordError::Empty => write!(f, "empty key chord"),
+            ChordError::UnknownModifier(m) => write!(f, "unknown modifier '{m}'"),
+            ChordError::UnknownKey(k) => write!(f, "unknown key '{k}'"),
+        }
+    }
+}
+
+impl std::error::Error for ChordError {}
+
+/// Touches nommées reconnues dans les combinaisons (insensible à la casse).
+const NAMED_KEYS: &[(&str, NamedKey)] = &[
+    ("left", NamedKey::ArrowLeft),
+    ("arrowleft", NamedKey::ArrowLeft),
+    ("right", NamedKey::ArrowRight),
+    ("arrowright", NamedKey::ArrowRight),
+    ("up", NamedKey::ArrowUp),
+    ("arrowup", NamedKey::ArrowUp),
+    ("down", NamedKey::ArrowDown),
+    ("arrowdown", NamedKey::ArrowDown),
+    ("home", NamedKey::Home),
+    ("end", NamedKey::End),
+    ("pageup", NamedKey::PageUp),
+    ("pagedown", NamedKey::PageDown),
+    ("enter", NamedKey::Enter),
+    ("tab", NamedKey::Tab),
+    ("escape", NamedKey::Escape),
+    ("esc", NamedKey::Escape),
+    ("backspace", NamedKey::Backspace),
+    ("delete", NamedKey::Delete),
+    ("insert", NamedKey::Insert),
+    ("space", NamedKey::Space),
+    ("zoomin", NamedKey::ZoomIn),
+    ("zoomout", NamedKey::ZoomOut),
+    ("browserback", NamedKey::BrowserBack),
+    ("browserforward", NamedKey::BrowserForward),
+    ("browserrefresh", NamedKey::BrowserRefresh),
+    ("browserhome", NamedKey::BrowserHome),
+    ("browsersearch", NamedKey::BrowserSearch),
+    ("f1", NamedKey::F1),
+    ("f2", NamedKey::F2),
+    ("f3", NamedKey::F3),
+    ("f4", NamedKey::F4),
+    ("f5", NamedKey::F5),
+    ("f6", NamedKey::F6),
+    ("f7", NamedKey::F7),
+    ("f8", NamedKey::F8),
+    ("f9", NamedKey::F9),
+    ("f10", NamedKey::F10),
+    ("f11", NamedKey::F11),
+    ("f12", NamedKey::F12),
+];
+
+impl Chord {
+    /// Lit une combinaison `"Mod+Mod+Touche"` (`Ctrl`, `Alt`, `Shift`,
+    /// `Super`/`Cmd`). `"Ctrl++"` désigne la touche `+`.
+    pub fn parse(text: &str) -> Result<Self, ChordError> {
+        let text = text.trim();
+        let (mods, key) = if text == "+" {
+            ("", "+")
+        } else if let Some(mods) = text.strip_suffix("++") {
+            (mods, "+")
+        } else {
+            text.rsplit_once('+').unwrap_or(("", text))
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(ChordError::Empty);
+        }
+
+        let mut chord = Chord {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            logo: false,
+            key: parse_key(key)?,
+        };
+        for modifier in mods.split('+').map(str::trim).filter(|m| !m.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "alt" | "option" => chord.alt = true,
+                "shift" => chord.shift = true,
+                "super" | "meta" | "cmd" | "logo" => chord.logo = true,
+                _ => return Err(ChordError::UnknownModifier(modifier.to_string())),
+            }
+        }
+        Ok(chord.normalized())
+    }
+
+    /// Combinaison d'une touche pressée. `None` pour les touches sans
+    /// équivalent (touche morte, caractères composés…).
+    pub fn from_key(mods: ModifiersState, key: &Key) -> Option<Self> {
+        let key = match key {
+            Key::Character(c) => {
+                let mut chars = c.chars();
+                let ch = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                ChordKey::Char(lowercase(ch))
+            }
+            Key::Named(named) => ChordKey::Named(*named),
+            _ => return None,
+        };
+        let chord = Chord {
+            ctrl: mods.control_key(),
+            alt: mods.alt_key(),
+            shift: mods.shift_key(),
+            logo: mods.super_key(),
+            key,
+        };
+        Some(chord.normalized())
+    }
+
+    fn normalized(mut self) -> Self {
+        if let ChordKey::Char(c) = self.key
+            && !c.is_alphabetic()
+        {
+            self.shift = false;
+        }
+        self
+    }
+}
+
+fn parse_key(key: &str) -> Result<ChordKey, ChordError> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(ChordKey::Char(lowercase(c)));
+    }
+    let lower = key.to_ascii_lowercase();
+    NAMED_KEYS
+        .iter()
+        .find(|(name, _)| *name == lower)
+        .map(|&(_, named)| ChordKey::Named(named))
+        .ok_or_else(|| ChordError::UnknownKey(key.to_string()))
+}
+
+/// Minuscule d'un caractère (inchangé s'il n'a pas de minuscule simple).
+fn lowercase(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+/// Table combinaison → action.
+#[derive(Debug, Clone)]
+pub struct Keybindings {
+    bindings: HashMap<Chord, Action>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new())
+    }
+}
+
+impl Keybindings {
+    /// Raccourcis par défaut, remplacés action par action par `overrides`
+    /// (la section `[keybindings]`).
+    pub fn new(overrides: &BTreeMap<String, KeyChords>) -> Self {
+        for name in overrides.keys() {
+            if Action::from_name(name).is_none() {
+                warn!(action = %name, "Unknown action in [keybindings], ignoring");
+            }
+        }
+
+        let mut bindings = HashMap::new();
+        // Ordre inverse : en cas de conflit, l'action la plus prioritaire gagne
+        for &action in Action::ALL.iter().rev() {
+            let chords = match overrides.get(action.name()) {
+                Some(chords) => {
+                    parse_chords(action, chords).unwrap_or_else(|| parse_defaults(action))
+                }
+                None => parse_defaults(action),
+            };
+            for chord in chords {
+                if let Some(other) = bindings.insert(chord, action) {
+                    warn!(
+                        kept = action.name(),
+                        dropped = other.name(),
+                        "Key chord bound to two actions"
+                    );
+                }
+            }
+        }
+        Self { bindings }
+    }
+
+    /// Action liée à la touche pressée, s'il y en a une.
+    pub fn action_for(&self, mods: ModifiersState, key: &Key) -> Option<Action> {
+        let chord = Chord::from_key(mods, key)?;
+        self.bindings.get(&chord).copied()
+    }
+}
+
+/// Combinaisons configurées pour `action` ; `None` (avec un avertissement)
+/// si l'une d'elles est illisible.
+fn parse_chords(action: Action, chords: &KeyChords) -> Option<Vec<Chord>> {
+    chords
+        .iter()
+        .map(|text| {
+            Chord::parse(text)
+                .inspect_err(|e| {
+                    warn!(
+                        action = action.name(),
+                        chord = text,
+                        error = %e,
+                        "Invalid key chord in [keybindings], keeping defaults"
+                    );
+                })
+                .ok()
+        })
+        .collect()
+}
+
+fn parse_defaults(action: Action) -> Vec<Chord> {
+    action
+        .default_chords()
+        .iter()
+        .map(|text| Chord::parse(text).expect("default key chord must parse"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ch(c: &str) -> Key {
+        Key::Character(c.into())
+    }
+
+    fn ctrl() -> ModifiersState {
+        ModifiersState::CONTROL
+    }
+
+    #[test]
+    fn test_parse_chords() {
+        let chord = Chord::parse("Ctrl+Shift+L").unwrap();
+        assert!(chord.ctrl && chord.shift && !chord.alt);
+        assert_eq!(chord.key, ChordKey::Char('l'));
+
+        assert_eq!(
+            Chord::parse("f5").unwrap().key,
+            ChordKey::Named(NamedKey::F5)
+        );
+        assert_eq!(
+            Chord::parse("alt + left").unwrap(),
+            Chord::parse("Alt+ArrowLeft").unwrap()
+        );
+        assert_eq!(Chord::parse("Ctrl++").unwrap().key, ChordKey::Char('+'));
+        assert_eq!(Chord::parse("Ctrl+-").unwrap().key, ChordKey::Char('-'));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Chord::parse(""), Err(ChordError::Empty));
+        assert_eq!(Chord::parse("Ctrl+"), Err(ChordError::Empty));
+        assert_eq!(
+            Chord::parse("Hyper+L"),
+            Err(ChordError::UnknownModifier("Hyper".to_string()))
+        );
+        assert_eq!(
+            Chord::parse("Ctrl+Banana"),
+            Err(ChordError::UnknownKey("Banana".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_every_default_chord_parses() {
+        for &action in Action::ALL {
+            assert_eq!(Action::from_name(action.name()), Some(action));
+            assert_eq!(parse_defaults(action).len(), action.default_chords().len());
+        }
+    }
+
+    #[test]
+    fn test_navigation_actions_ignore_repeat() {
+        for action in [
+            Action::Reload,
+            Action::GoBack,
+            Action::GoForward,
+            Action::ZoomIn,
+            Action::ZoomOut,
+            Action::ToggleBookmark,
+            Action::ToggleFullscreen,
+            Action::ReloadFilters,
+            Action::ClearBrowsingData,
+            Action::ToggleJavaScript,
+            Action::Quit,
+        ] {
+            assert!(!action.allows_repeat(), "{}", action.name());
+        }
+        assert!(Action::FocusUrlBar.allows_repeat());
+    }
+
+    #[test]
+    fn test_home_key_priority() {
+        let bindings = Keybindings::default();
+        let home = Key::Named(NamedKey::Home);
+        // Home seul n'est pas un raccourci : il reste au champ de saisie
+        assert_eq!(bindings.action_for(ModifiersState::empty(), &home), None);
+        // Alt+Home va à l'accueil, sauf si un champ de saisie a le focus
+        let action = bindings.action_for(ModifiersState::ALT, &home).unwrap();
+        assert_eq!(action, Action::GoHome);
+        assert!(action.yields_to_text_input());
+        assert!(!Action::Reload.yields_to_text_input());
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let bindings = Keybindings::default();
+        assert_eq!(
+            bindings.action_for(ctrl(), &ch("l")),
+            Some(Action::FocusUrlBar)
+        );
+        // Verrouillage majuscule : le caractère arrive en majuscule
+        assert_eq!(
+            bindings.action_for(ctrl(), &ch("L")),
+            Some(Action::FocusUrlBar)
+        );
+        assert_eq!(
+            bindings.action_for(ModifiersState::empty(), &Key::Named(NamedKey::F5)),
+            Some(Action::Reload)
+        );
+        assert_eq!(
+            bindings.action_for(ModifiersState::ALT, &Key::Named(NamedKey::ArrowLeft)),
+            Some(Action::GoBack)
+        );
+        assert_eq!(
+            bindings.action_for(ModifiersState::empty(), &Key::Named(NamedKey::F11)),
+            Some(Action::ToggleFullscreen)
+        );
+        assert_eq!(
+            bindings.action_for(ctrl() | ModifiersState::SHIFT, &ch("F")),
+            Some(Action::ReloadFilters)
+        );
+        assert_eq!(
+            bindings.action_for(
+                ctrl() | ModifiersState::SHIFT,
+                &Key::Named(NamedKey::Delete)
+            ),
+            Some(Action::ClearBrowsingData)
+        );
+        // Sans Ctrl, « l » n'est qu'une lettre
+        assert_eq!(bindings.action_for(ModifiersState::empty(), &ch("l")), None);
+        assert_eq!(
+            bindings.action_for(ctrl() | ModifiersState::SHIFT, &ch("L")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_shift_ignored_for_symbols() {
+        let bindings = Keybindings::default();
+        // Ctrl+Shift+= produit « + » sur un clavier QWERTY
+        assert_eq!(
+            bindings.action_for(ctrl() | ModifiersState::SHIFT, &ch("+")),
+            Some(Action::ZoomIn)
+        );
+        assert_eq!(bindings.action_for(ctrl(), &ch("=")), Some(Action::ZoomIn));
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert(
+            "focus_url_bar".to_string(),
+            KeyChords::One("Ctrl+K".to_string()),
+        );
+        overrides.insert("go_home".to_string(), KeyChords::Many(Vec::new()));
+        overrides.insert(
+            "quit".to_string(),
+            KeyChords::One("Ctrl+Shift+Q".to_string()),
+        );
+        let bindings = Keybindings::new(&overrides);
+
+        assert_eq!(
+            bindings.action_for(ctrl(), &ch("k")),
+            Some(Action::FocusUrlBar)
+        );
+        assert_eq!(bindings.action_for(ctrl(), &ch("l")), None);
+        assert_eq!(
+            bindings.action_for(ModifiersState::ALT, &Key::Named(NamedKey::Home)),
+            None
+        );
+        assert_eq!(bindings.action_for(ctrl(), &ch("q")), None);
+        assert_eq!(
+            bindings.action_for(ctrl() | ModifiersState::SHIFT, &ch("Q")),
+            Some(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn test_invalid_overrides_keep_defaults() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert(
+            "reload".to_string(),
+            KeyChords::Many(vec!["Ctrl+R".to_string(), "Ctrl+Nope".to_string()]),
+        );
+        overrides.insert("teleport".to_string(), KeyChords::One("Ctrl+T".to_string()));
+        let bindings = Keybindings::new(&overrides);
+
+        assert_eq!(
+            bindings.action_for(ModifiersState::empty(), &Key::Named(NamedKey::F5)),
+            Some(Action::Reload)
+        );
+        assert_eq!(bindings.action_for(ctrl(), &ch("t")), None);
+    }
+
+    #[test]
+    fn test_conflict_resolved_by_priority() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert("reload".to_string(), KeyChords::One("Ctrl+L".to_string()));
+        let bindings = Keybindings::new(&overrides);
+        assert_eq!(
+            bindings.action_for(ctrl(), &ch("l")),
+            Some(Action::FocusUrlBar)
+        );
+    }
+}
@@ -18,6 +18,127 @@
 //! - [`config`] : Système de configuration TOML — charge les paramètres depuis
 //!   un fichier `config.toml` avec fallback sur les valeurs par défaut.
 //!
+//! - [`blocking`] : Type des requêtes filtrées par adblock et réponse neutre
+//!   (GIF transparent, CSS/JS vide) pour celles qui sont bloquées.
+//!
+//! - [`block_log`] : Journal des requêtes bloquées (`[privacy] block_log`),
+//!   écrit par un thread de fond, avec rotation à 10 Mo.
+//!
+//! - [`bookmarks`] : Favoris persistés dans `bookmarks.json` (étoile de la
+//!   barre d'URL, Ctrl+D).
+//!
+//! - [`browsing_data`] : Effacement du cache HTTP, des cookies et du stockage
+//!   des sites (Ctrl+Shift+Suppr, bouton des paramètres), résumé affiché.
+//!
+//! - [`cert_error`] : Page d'erreur de certificat TLS et exceptions
+//!   acceptées pour la session (jamais écrites sur disque).
+//!
+//! - [`chrome`] : Rendu OpenGL du chrome (barre d'URL) au-dessus du webview.
+//!
+//! - [`glyph_atlas`] : Atlas de glyphes `fontdue` alimenté à la demande
+//!   (caractères non-ASCII rastérisés au premier affichage).
+//!
+//! - [`clipboard`] : Presse-papiers système (arboard), une seule instance
+//!   partagée par la page, la barre d'URL et le menu contextuel.
+//!
+//! - [`config_reload`] : Rechargement à chaud de tout `config.toml`
+//!   (`[general] watch_config`) : clés modifiées, appliquées ou à redémarrer.
+//!
+//! - [`config_watcher`] : Surveillance de `config.toml` (polling, empreinte du
+//!   contenu) pour le rechargement à chaud des couleurs du chrome.
+//!
+//! - [`console`] : Console JavaScript des pages vers `tracing` (niveaux,
+//!   URL et ligne source, plafond par seconde).
+//!
+//! - [`context_menu`] : Menu contextuel de la page (clic droit) : entrées
+//!   selon le lien ou l'image sous le pointeur.
+//!
+//! - [`crash`] : Page plantée (panic Servo) ou figée (plus de frame) :
+//!   chien de garde et page « This page crashed ».
+//!
+//! - [`delegate_guard`] : Panics rattrapées dans les callbacks du delegate
+//!   Servo : journalisées et comptées par callback.
+//!
+//! - [`dialog`] : Dialogues JavaScript (`alert`, `confirm`, `prompt`)
+//!   dessinés par le chrome, sans bloquer la boucle d'événements.
+//!
+//! - [`download`] : Téléchargement des réponses que Servo n'affiche pas
+//!   (archives, PDF…) : nom, collisions, avancement, annulation.
+//!
+//! - [`error_page`] : Page d'erreur réseau (nom introuvable, connexion
+//!   refusée, échec TLS, délai dépassé) avec bouton Retry.
+//!
+//! - [`export`] : Export de la page sur disque (Ctrl+P → PDF d'une page,
+//!   Ctrl+S → HTML).
+//!
+//! - [`external`] : Liens vers des applications externes (`mailto:`,
+//!   `magnet:`…), confiés au système après confirmation.
+//!
+//! - [`favicon`] : Décodage des favicons (PNG, ICO, pixels Servo) en RGBA
+//!   et favicon de chaque webview, pour l'affichage dans la barre d'URL.
+//!
+//! - [`file_picker`] : Filtres du sélecteur de fichiers (`<input
+//!   type="file">`) : types MIME de `accept` traduits en extensions.
+//!
+//! - [`find`] : Recherche dans la page (Ctrl+F) — état de la barre et
+//!   script injecté dans la page.
+//!
+//! - [`http`] : Client HTTP(S) des requêtes faites hors de Servo
+//!   (téléchargements, mise à jour des listes de filtres).
+//!
+//! - [`https_only`] : Mode HTTPS uniquement — navigations `http://`
+//!   réécrites en https, repli en clair accordé par hôte pour la session.
+//!
+//! - [`load_state`] : Chargement en cours et position dans l'historique de
+//!   chaque webview (recharger/arrêter, précédent/suivant).
+//!
+//! - [`match_cost`] : Coût du filtrage adblock (nombre, total, maximum,
+//!   95e centile), cache de verdicts et moteur séparés.
+//!
+//! - [`media`] : Touches média (lecture/pause, pistes, stop) envoyées à la
+//!   session média de la page en lecture.
+//!
+//! - [`menu`] : État du menu déroulant « ≡ » (entrées, sélection clavier).
+//!
+//! - [`permissions`] : Décisions de permission par site (refus par défaut),
+//!   mémorisées dans `permissions.json`.
+//!
+//! - [`navigation`] : Barre d'URL et titre mis à jour seulement pour les
+//!   navigations engagées par Servo (« Loading — url », puis le titre).
+//!
+//! - [`no_js`] : JavaScript désactivé par site (`[privacy] no_js_hosts`,
+//!   Ctrl+Shift+J) : scripts externes de la page bloqués.
+//!
+//! - [`pages`] : Pages internes (historique, favoris, à propos) en `data:` URLs,
+//!   gabarit et échappement JavaScript communs aux pages générées.
+//!
+//! - [`tracking_params`] : Paramètres de suivi (`utm_*`, `fbclid`…)
+//!   retirés des URL de navigation, liste extensible par `strip_params`.
+//!
+//! - [`text_input`] : Champ de saisie générique (curseur, sélection totale),
+//!   partagé par la barre d'URL et la recherche dans la page.
+//!
+//! - [`third_party`] : Domaine enregistrable (eTLD+1) et effacement des
+//!   cookies des sites tiers (`[privacy] block_third_party_cookies`).
+//!
+//! - [`token`] : Jetons aléatoires des liens internes d'acceptation
+//!   (avertissements de certificat, HTTPS uniquement, liste de sécurité).
+//!
+//! - [`webview_list`] : Webviews de la fenêtre et webview active (celle qui
+//!   reçoit l'entrée), suivie explicitement.
+//!
+//! - [`window_state`] : Taille, position et état maximisé de la fenêtre
+//!   mémorisés dans `window.json` entre deux lancements.
+//!
+//! - [`zoom`] : Zoom de page (Ctrl+= / Ctrl+- / Ctrl+0) mémorisé par hôte
+//!   dans `zoom.json`.
+//!
+//! - [`fullscreen`] : Plein écran demandé par l'utilisateur (F11) ou par la
+//!   page (vidéo HTML5), chrome masqué dans le second cas.
+//!
+//! - [`keybindings`] : Raccourcis clavier globaux (actions, combinaisons
+//!   `"Ctrl+L"`), configurables dans `[keybindings]`.
+//!
 //! - [`keyutils`] : Conversion des événements clavier Winit vers les types Servo.
 //!
 //! - [`preferences`] : Configuration du moteur Servo — performance tuning et
@@ -26,6 +147,17 @@
 //! - [`privacy`] : Middleware d'interception réseau — ad-blocking et tracker blocking
 //!   via le crate `adblock` (Brave). Intégré dans `WebViewDelegate::load_web_resource()`.
 //!
+//! - [`privacy_signals`] : Global Privacy Control et Do Not Track, exposés aux pages.
+//!
+//! - [`regional_lists`] : Listes de filtres régionales (Liste FR, EasyList
+//!   Germany…) ajoutées d'après la langue au premier lancement.
+//!
+//! - [`referrer`] : Politique de referrer (`[privacy] referrer_policy`)
+//!   appliquée au document, comparaison d'origines.
+//!
+//! - [`safe_browsing`] : Liste de sécurité (hameçonnage, logiciels
+//!   malveillants) et avertissement plein écran avant d'ouvrir un site listé.
+//!
 //! - [`settings`] : Page de paramètres HTML — génère un formulaire rendu par
 //!   Servo via `data:` URLs avec interception du save via `load_web_resource`.
 //!
@@ -38,15 +170,61 @@
 //! - `ui` : Overlay GPU pour le chrome du navigateur (barre d'URL, onglets)
 //! - `plugins` : Hôte WebAssembly pour extensions natives (wasmtime)
 
+pub mod block_log;
+pub mod blocking;
+pub mod bookmarks;
 pub mod browser;
+pub mod browsing_data;
+pub mod cert_error;
 pub mod chrome;
+pub mod clipboard;
 pub mod config;
+pub mod config_reload;
+pub mod config_watcher;
+pub mod console;
+pub mod context_menu;
+pub mod crash;
+pub mod delegate_guard;
+pub mod dialog;
+pub mod download;
+pub mod error_page;
+pub mod export;
+pub mod external;
+pub mod favicon;
+pub mod file_picker;
+pub mod find;
+pub mod fullscreen;
+pub mod glyph_atlas;
+pub mod http;
+pub mod https_only;
+pub mod keybindings;
 pub mod keyutils;
+pub mod load_state;
+pub mod match_cost;
+pub mod media;
+pub mod menu;
+pub mod navigation;
+pub mod no_js;
+pub mod pages;
+pub mod permissions;
 pub mod preferences;
 pub mod privacy;
+pub mod privacy_signals;
+pub mod referrer;
+pub mod regional_lists;
 pub mod rendering;
 pub mod resources;
+pub mod safe_browsing;
 pub mod security;
 pub mod servo_glue;
 pub mod settings;
+#[cfg(test)]
+mod test_util;
+pub mod text_input;
+pub mod third_party;
+pub mod token;
+pub mod tracking_params;
 pub mod urlbar;
+pub mod webview_list;
+pub mod window_state;
+pub mod zoom;
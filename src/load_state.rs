@@ -0,0 +1,85 @@
+//! État de chargement et de navigation d'une webview.
+//!
+//! Alimenté par les delegates Servo (`notify_load_status_changed`,
+//! `notify_history_changed`) et lu par le chrome : bouton recharger ↔
+//! arrêter, boutons précédent/suivant. Ce module ne dépend pas de Servo.
+
+/// Chargement en cours et position dans l'historique d'une webview.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadState {
+    /// `true` entre le début d'un chargement et sa fin (`Complete`).
+    pub loading: bool,
+    /// Une entrée précède la page courante dans l'historique.
+    pub can_go_back: bool,
+    /// Une entrée suit la page courante dans l'historique.
+    pub can_go_forward: bool,
+}
+
+impl LoadState {
+    /// Début ou fin de chargement. Retourne `true` si l'état a changé.
+    pub fn set_loading(&mut self, loading: bool) -> bool {
+        let changed = self.loading != loading;
+        self.loading = loading;
+        changed
+    }
+
+    /// Historique de `len` entrées dont la `current`-ième est affichée.
+    /// Un index hors limites est ramené sur la dernière entrée.
+    /// Retourne `true` si l'état a changé.
+    pub fn set_history(&mut self, len: usize, current: usize) -> bool {
+        let before = *self;
+        let current = current.min(len.saturating_sub(1));
+        self.can_go_back = current > 0;
+        self.can_go_forward = current + 1 < len;
+        *self != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loading_transitions() {
+        let mut state = LoadState::default();
+        assert!(!state.set_loading(false));
+        assert!(state.set_loading(true));
+        // `HeadParsed` après `Started` : toujours en cours, rien à redessiner
+        assert!(!state.set_loading(true));
+        assert!(state.set_loading(false));
+        assert!(!state.loading);
+    }
+
+    #[test]
+    fn test_history_transitions() {
+        let mut state = LoadState::default();
+        // Première page
+        assert!(!state.set_history(1, 0));
+        // Navigation vers une deuxième page
+        assert!(state.set_history(2, 1));
+        assert!(state.can_go_back && !state.can_go_forward);
+        // Retour arrière
+        assert!(state.set_history(2, 0));
+        assert!(!state.can_go_back && state.can_go_forward);
+        // Nouvelle navigation depuis le milieu : l'avant est tronqué
+        assert!(state.set_history(2, 1));
+        assert!(!state.set_history(2, 1));
+        assert_eq!(
+            state,
+            LoadState {
+                loading: false,
+                can_go_back: true,
+                can_go_forward: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_history_clamps_out_of_range_index() {
+        let mut state = LoadState::default();
+        state.set_history(0, 0);
+        assert_eq!(state, LoadState::default());
+        state.set_history(3, usize::MAX);
+        assert!(state.can_go_back && !state.can_go_forward);
+    }
+}
@@ -1,13 +1,16 @@
 //! Point d'entrée de SuriBrows.
 //!
 //! Usage :
-//!   suribrows [URL] [--secure-mode]
+//!   suribrows [URL] [--secure-mode] [--private] [--config <fichier>]
 //!
 //! Exemples :
 //!   cargo run                              → charge https://example.com
 //!   cargo run -- https://servo.org         → charge servo.org
 //!   cargo run -- wikipedia.org             → ajoute https:// automatiquement
 //!   cargo run -- --secure-mode             → mode sécurisé (JIT désactivé, ACG activé)
+//!   cargo run -- --private                 → navigation privée (rien n'est conservé)
+//!   cargo run -- --config ~/suri.toml      → ce fichier de configuration, erreur s'il manque
+//!   SURIBROWS_WINDOW_WIDTH=1920 cargo run  → remplace une valeur du fichier
 
 use std::env;
 use std::error::Error;
@@ -15,12 +18,20 @@ use std::error::Error;
 use url::Url;
 use winit::event_loop::EventLoop;
 
-use suribrows::config::Config;
+use suribrows::config::{self, Config};
 
 fn main() -> Result<(), Box<dyn Error>> {
     // ── 0. Parse command-line flags ────────────────────────────────────
     let args: Vec<String> = env::args().collect();
     let secure_mode = args.contains(&"--secure-mode".to_string());
+    let private = args.contains(&"--private".to_string());
+    let config_path = match config::config_flag(&args[1..]) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("suribrows: {e}");
+            std::process::exit(2);
+        }
+    };
 
     // ── 1. Logging / Tracing (init early so all modules can use it) ───
     tracing_subscriber::fmt()
@@ -38,6 +49,10 @@ fn main() -> Result<(), Box<dyn Error>> {
         tracing::warn!("  Arbitrary Code Guard (ACG) will be enabled (blocks shellcode)");
     }
 
+    if private {
+        tracing::info!("Private browsing: no cache, history, zoom levels or permissions kept");
+    }
+
     // ── 2. Windows Security Hardening (BEFORE any DLLs load) ──────────
     suribrows::security::apply_process_mitigations(secure_mode);
 
@@ -47,7 +62,44 @@ fn main() -> Result<(), Box<dyn Error>> {
         .expect("Échec de l'installation du provider crypto rustls");
 
     // ── 4. Load configuration ──────────────────────────────────────────
-    let config = Config::load();
+    // Fichier nommé par `--config` : absent ou invalide, on s'arrête
+    // plutôt que de démarrer avec les valeurs par défaut
+    let mut config = match Config::load_from(config_path.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("suribrows: {e}");
+            std::process::exit(2);
+        }
+    };
+    if let Some(path) = config_path {
+        config::set_config_path(path);
+    }
+    let first_run = config::find_config_path().is_none();
+    // Variables SURIBROWS_<SECTION>_<CLÉ> : priment sur le fichier
+    for e in config.apply_env_overrides(config::env_vars()) {
+        tracing::warn!(error = %e, "Ignoring config override variable");
+    }
+    // Listes régionales de la langue : au premier lancement, ou à chaque
+    // démarrage si `auto_regional_lists`. Ajoutées aussi à la config du
+    // disque, relue sans les variables d'environnement ; en navigation
+    // privée, seulement pour cette session
+    if (first_run || config.privacy.auto_regional_lists)
+        && !suribrows::regional_lists::enable_for_locale(&mut config).is_empty()
+        && !private
+    {
+        match Config::load_saved() {
+            Ok(mut saved) => {
+                if !suribrows::regional_lists::enable_for_locale(&mut saved).is_empty()
+                    && let Err(e) = saved.save()
+                {
+                    tracing::warn!(error = %e, "Failed to save regional filter lists");
+                }
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Config not loaded, regional filter lists not saved");
+            }
+        }
+    }
 
     // ── 5. Lecteur de ressources Servo ─────────────────────────────────
     suribrows::resources::init();
@@ -60,20 +112,27 @@ fn main() -> Result<(), Box<dyn Error>> {
         .build()
         .expect("Échec de la création du EventLoop Winit");
 
-    let mut app = suribrows::browser::App::new(&event_loop, url, config);
+    let mut app = suribrows::browser::App::new(&event_loop, url, config, private);
 
     Ok(event_loop.run_app(&mut app)?)
 }
 
 /// Parse le premier argument CLI comme URL.
 /// Si l'argument ne contient pas de schéma (http/https), on ajoute "https://".
-/// Ignore le flag --secure-mode.
+/// Ignore les flags (--secure-mode, --private) et la valeur de `--config`.
 fn parse_url_from_args(default_url: &str) -> Url {
     // Filter out flags (starting with --) and get first non-flag argument
-    let input = env::args()
-        .skip(1) // Skip binary name
-        .find(|arg| !arg.starts_with("--"))
-        .unwrap_or_else(|| default_url.to_string());
+    let mut args = env::args().skip(1); // Skip binary name
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            args.next();
+        } else if !arg.starts_with("--") {
+            input = Some(arg);
+            break;
+        }
+    }
+    let input = input.unwrap_or_else(|| default_url.to_string());
 
     // Essaie de parser directement (fonctionne si l'utilisateur a mis le schéma)
     if let Ok(url) = Url::parse(&input) {
@@ -0,0 +1,171 @@
+//! Coût du filtrage adblock : durée de chaque vérification de requête,
+//! réponses du cache de verdicts et appels au moteur comptés à part.
+//!
+//! Chaque vérification ne coûte qu'une paire d'`Instant::now()` et quelques
+//! additions : pas d'allocation, pas de tri. Le 95e centile est estimé à
+//! partir d'un histogramme à classes de puissances de deux (en
+//! nanosecondes) : la valeur rendue est la borne haute de la classe, donc au
+//! plus deux fois la vraie valeur, jamais au-delà du maximum observé.
+
+use std::time::Duration;
+
+/// Nombre de classes de l'histogramme : la dernière reçoit tout ce qui
+/// dépasse 2^39 ns (~9 minutes).
+const BUCKETS: usize = 40;
+
+/// Durées d'une famille de vérifications (cache ou moteur).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timings {
+    count: u64,
+    total: Duration,
+    max: Duration,
+    /// `buckets[i]` : vérifications de durée dans `[2^i, 2^(i+1))` ns.
+    buckets: [u64; BUCKETS],
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+            buckets: [0; BUCKETS],
+        }
+    }
+}
+
+impl Timings {
+    /// Ajoute une vérification.
+    pub fn record(&mut self, elapsed: Duration) {
+        self.count = self.count.saturating_add(1);
+        self.total = self.total.saturating_add(elapsed);
+        self.max = self.max.max(elapsed);
+        self.buckets[bucket(elapsed)] += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Durée moyenne, zéro sans vérification.
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.count) {
+            Ok(0) => Duration::ZERO,
+            Ok(count) => self.total / count,
+            Err(_) => Duration::from_nanos(
+                u64::try_from(self.total.as_nanos() / u128::from(self.count)).unwrap_or(u64::MAX),
+            ),
+        }
+    }
+
+    /// Estimation du 95e centile (voir la doc du module).
+    pub fn p95(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        // Rang de la vérification au 95e centile, arrondi au-dessus
+        let rank = self.count.saturating_mul(95).div_ceil(100);
+        let mut seen = 0u64;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= rank && i < BUCKETS - 1 {
+                let upper = Duration::from_nanos(1u64 << (i + 1));
+                return upper.min(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+/// Classe de `elapsed` dans l'histogramme.
+fn bucket(elapsed: Duration) -> usize {
+    let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX).max(1);
+    (nanos.ilog2() as usize).min(BUCKETS - 1)
+}
+
+/// Coût du filtrage depuis la construction du moteur.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchCost {
+    /// Verdicts tirés du cache.
+    pub hits: Timings,
+    /// Requêtes passées au moteur (URL illisibles comprises).
+    pub misses: Timings,
+}
+
+impl MatchCost {
+    /// Temps total passé à filtrer.
+    pub fn total(&self) -> Duration {
+        self.hits.total.saturating_add(self.misses.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(us: u64) -> Duration {
+        Duration::from_micros(us)
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let mut timings = Timings::default();
+        assert_eq!(timings.mean(), Duration::ZERO);
+        assert_eq!(timings.p95(), Duration::ZERO);
+
+        for us in [10, 20, 30, 40] {
+            timings.record(micros(us));
+        }
+        assert_eq!(timings.count(), 4);
+        assert_eq!(timings.total(), micros(100));
+        assert_eq!(timings.max(), micros(40));
+        assert_eq!(timings.mean(), micros(25));
+    }
+
+    #[test]
+    fn test_p95_estimate_bounds() {
+        let mut timings = Timings::default();
+        // 95 vérifications rapides, 5 lentes : le 95e centile reste rapide
+        for _ in 0..95 {
+            timings.record(micros(3));
+        }
+        for _ in 0..5 {
+            timings.record(Duration::from_millis(2));
+        }
+        let p95 = timings.p95();
+        assert!(p95 >= micros(3) && p95 <= micros(6), "{p95:?}");
+
+        // Une vérification de plus en lent : le centile bascule, borné par le max
+        timings.record(Duration::from_millis(2));
+        assert_eq!(timings.p95(), Duration::from_millis(2));
+    }
+
+    #[test]
+    fn test_extreme_durations_fit_the_histogram() {
+        let mut timings = Timings::default();
+        timings.record(Duration::ZERO);
+        timings.record(Duration::from_secs(3_600));
+        assert_eq!(bucket(Duration::ZERO), 0);
+        assert_eq!(bucket(Duration::from_secs(3_600)), BUCKETS - 1);
+        assert_eq!(timings.p95(), Duration::from_secs(3_600));
+    }
+
+    #[test]
+    fn test_total_adds_hits_and_misses() {
+        let mut cost = MatchCost::default();
+        cost.hits.record(micros(1));
+        cost.misses.record(micros(50));
+        cost.misses.record(micros(70));
+        assert_eq!(cost.hits.count(), 1);
+        assert_eq!(cost.misses.count(), 2);
+        assert_eq!(cost.total(), micros(121));
+    }
+}
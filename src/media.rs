@@ -0,0 +1,172 @@
+//! Touches média (lecture/pause, piste suivante/précédente, stop) et
+//! sessions média des pages.
+//!
+//! Une page qui lit un média déclare une session (`navigator.mediaSession`)
+//! et Servo notifie son état de lecture. La webview « en lecture » la plus
+//! récente reçoit les touches média sous forme d'actions de session, au lieu
+//! d'événements clavier que les pages ignorent. Sans session, les touches
+//! restent des touches ordinaires.
+//!
+//! Winit ne propose pas de raccourcis globaux : les touches média ne sont
+//! reçues que lorsque la fenêtre a le focus.
+//!
+//! Ce module ne dépend pas de Servo.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use winit::keyboard::NamedKey;
+
+/// Touche média reconnue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKey {
+    PlayPause,
+    Play,
+    Pause,
+    NextTrack,
+    PreviousTrack,
+    Stop,
+}
+
+impl MediaKey {
+    /// Touche média correspondant à une touche nommée Winit.
+    pub fn from_named(key: NamedKey) -> Option<Self> {
+        Some(match key {
+            NamedKey::MediaPlayPause => Self::PlayPause,
+            NamedKey::MediaPlay => Self::Play,
+            NamedKey::MediaPause => Self::Pause,
+            NamedKey::MediaTrackNext => Self::NextTrack,
+            NamedKey::MediaTrackPrevious => Self::PreviousTrack,
+            NamedKey::MediaStop => Self::Stop,
+            _ => return None,
+        })
+    }
+}
+
+/// Action envoyée à la session média de la page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaAction {
+    Play,
+    Pause,
+    NextTrack,
+    PreviousTrack,
+    Stop,
+}
+
+/// État de lecture d'une session média.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Playback {
+    /// Session déclarée, état non précisé par la page.
+    #[default]
+    None,
+    Playing,
+    Paused,
+}
+
+/// Action à envoyer pour `key` dans l'état `playback` : lecture/pause
+/// bascule selon l'état courant.
+pub fn action_for(key: MediaKey, playback: Playback) -> MediaAction {
+    match key {
+        MediaKey::PlayPause if playback == Playback::Playing => MediaAction::Pause,
+        MediaKey::PlayPause | MediaKey::Play => MediaAction::Play,
+        MediaKey::Pause => MediaAction::Pause,
+        MediaKey::NextTrack => MediaAction::NextTrack,
+        MediaKey::PreviousTrack => MediaAction::PreviousTrack,
+        MediaKey::Stop => MediaAction::Stop,
+    }
+}
+
+/// Sessions média des webviews et webview « en lecture ».
+#[derive(Debug)]
+pub struct MediaSessions<K> {
+    /// État de lecture de chaque webview ayant déclaré une session.
+    sessions: HashMap<K, Playback>,
+    /// Dernière webview passée en lecture : cible des touches média.
+    now_playing: Option<K>,
+}
+
+impl<K> Default for MediaSessions<K> {
+    fn default() -> Self {
+        Self {
+            sessions: HashMap::new(),
+            now_playing: None,
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> MediaSessions<K> {
+    /// Nouvel état de lecture de `key`. Une webview qui passe en lecture
+    /// devient la cible des touches média.
+    pub fn set_playback(&mut self, key: K, playback: Playback) {
+        self.sessions.insert(key, playback);
+        if playback == Playback::Playing {
+            self.now_playing = Some(key);
+        }
+    }
+
+    /// Session de `key` terminée (page fermée ou quittée).
+    pub fn remove(&mut self, key: K) {
+        self.sessions.remove(&key);
+        if self.now_playing == Some(key) {
+            self.now_playing = None;
+        }
+    }
+
+    /// Webview à qui envoyer les touches média, avec son état : celle en
+    /// lecture, sinon une session en pause ou sans état.
+    pub fn target(&self) -> Option<(K, Playback)> {
+        self.now_playing
+            .and_then(|key| Some((key, *self.sessions.get(&key)?)))
+            .or_else(|| self.sessions.iter().next().map(|(k, p)| (*k, *p)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_key_mapping() {
+        assert_eq!(
+            MediaKey::from_named(NamedKey::MediaPlayPause),
+            Some(MediaKey::PlayPause)
+        );
+        assert_eq!(
+            MediaKey::from_named(NamedKey::MediaTrackPrevious),
+            Some(MediaKey::PreviousTrack)
+        );
+        assert_eq!(MediaKey::from_named(NamedKey::Enter), None);
+    }
+
+    #[test]
+    fn test_play_pause_toggles() {
+        assert_eq!(
+            action_for(MediaKey::PlayPause, Playback::Playing),
+            MediaAction::Pause
+        );
+        assert_eq!(
+            action_for(MediaKey::PlayPause, Playback::Paused),
+            MediaAction::Play
+        );
+        assert_eq!(
+            action_for(MediaKey::Stop, Playback::Playing),
+            MediaAction::Stop
+        );
+    }
+
+    #[test]
+    fn test_latest_playing_session_is_target() {
+        let mut sessions = MediaSessions::default();
+        assert_eq!(sessions.target(), None);
+        sessions.set_playback(1, Playback::Playing);
+        sessions.set_playback(2, Playback::Playing);
+        sessions.set_playback(2, Playback::Paused);
+        // La dernière webview lancée reste la cible, même en pause
+        assert_eq!(sessions.target(), Some((2, Playback::Paused)));
+
+        sessions.remove(2);
+        assert_eq!(sessions.target(), Some((1, Playback::Playing)));
+        sessions.remove(1);
+        assert_eq!(sessions.target(), None);
+    }
+}
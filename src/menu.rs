@@ -0,0 +1,158 @@
+//! Menu déroulant du bouton « ≡ » du chrome.
+//!
+//! Ce module ne contient que l'état (ouvert/fermé, ligne sélectionnée) et la
+//! liste des entrées ; le dessin est fait par [`crate::chrome::ChromeRenderer`]
+//! et les actions sont dispatchées par `browser.rs`.
+
+/// Entrées du menu, dans l'ordre d'affichage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    NewTab,
+    History,
+    Bookmarks,
+    Settings,
+    About,
+    Quit,
+}
+
+impl MenuItem {
+    /// Toutes les entrées, de haut en bas.
+    pub const ALL: [MenuItem; 6] = [
+        MenuItem::NewTab,
+        MenuItem::History,
+        MenuItem::Bookmarks,
+        MenuItem::Settings,
+        MenuItem::About,
+        MenuItem::Quit,
+    ];
+
+    /// Libellé affiché dans le panneau.
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuItem::NewTab => "New Tab",
+            MenuItem::History => "History",
+            MenuItem::Bookmarks => "Bookmarks",
+            MenuItem::Settings => "Settings",
+            MenuItem::About => "About",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+/// État du menu déroulant.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Menu {
+    open: bool,
+    /// Ligne surlignée (souris ou flèches), index dans [`MenuItem::ALL`].
+    selected: Option<usize>,
+}
+
+impl Menu {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Index de la ligne surlignée.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Entrée surlignée.
+    pub fn selected_item(&self) -> Option<MenuItem> {
+        self.selected.map(|i| MenuItem::ALL[i])
+    }
+
+    /// Ouvre ou ferme le menu (clic sur « ≡ »).
+    pub fn toggle(&mut self) {
+        if self.open {
+            self.close();
+        } else {
+            self.open = true;
+            self.selected = None;
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.selected = None;
+    }
+
+    /// Surligne `item` (survol souris). Retourne `true` si la sélection a changé.
+    pub fn hover(&mut self, item: Option<MenuItem>) -> bool {
+        let index = item.and_then(|item| MenuItem::ALL.iter().position(|&i| i == item));
+        let changed = index.is_some() && self.selected != index;
+        if index.is_some() {
+            self.selected = index;
+        }
+        changed
+    }
+
+    /// Flèche bas : ligne suivante, en bouclant.
+    pub fn select_next(&mut self) {
+        let len = MenuItem::ALL.len();
+        self.selected = Some(self.selected.map_or(0, |i| (i + 1) % len));
+    }
+
+    /// Flèche haut : ligne précédente, en bouclant.
+    pub fn select_prev(&mut self) {
+        let len = MenuItem::ALL.len();
+        self.selected = Some(self.selected.map_or(len - 1, |i| (i + len - 1) % len));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_opens_without_selection() {
+        let mut menu = Menu::default();
+        menu.toggle();
+        assert!(menu.is_open());
+        assert_eq!(menu.selected(), None);
+        menu.toggle();
+        assert!(!menu.is_open());
+    }
+
+    #[test]
+    fn test_select_next_wraps() {
+        let mut menu = Menu::default();
+        menu.toggle();
+        menu.select_next();
+        assert_eq!(menu.selected_item(), Some(MenuItem::NewTab));
+        for _ in 0..MenuItem::ALL.len() {
+            menu.select_next();
+        }
+        assert_eq!(menu.selected_item(), Some(MenuItem::NewTab));
+    }
+
+    #[test]
+    fn test_select_prev_from_nothing_picks_last() {
+        let mut menu = Menu::default();
+        menu.toggle();
+        menu.select_prev();
+        assert_eq!(menu.selected_item(), Some(MenuItem::Quit));
+        menu.select_prev();
+        assert_eq!(menu.selected_item(), Some(MenuItem::About));
+    }
+
+    #[test]
+    fn test_close_clears_selection() {
+        let mut menu = Menu::default();
+        menu.toggle();
+        menu.select_next();
+        menu.close();
+        assert!(!menu.is_open());
+        assert_eq!(menu.selected(), None);
+    }
+
+    #[test]
+    fn test_hover_keeps_selection_outside_rows() {
+        let mut menu = Menu::default();
+        menu.toggle();
+        assert!(menu.hover(Some(MenuItem::Settings)));
+        assert!(!menu.hover(Some(MenuItem::Settings)));
+        assert!(!menu.hover(None));
+        assert_eq!(menu.selected_item(), Some(MenuItem::Settings));
+    }
+}
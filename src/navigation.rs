@@ -0,0 +1,123 @@
+//! Affichage de la navigation : barre d'URL et titre de la fenêtre.
+//!
+//! Servo demande d'abord l'autorisation de naviguer (`request_navigation`) :
+//! une navigation refusée (adblock) ou jamais engagée ne touche pas à
+//! l'affichage. L'URL n'apparaît qu'une fois la navigation engagée
+//! (`notify_url_changed`), précédée de « Loading — » tant que le document
+//! charge sans titre. Ce module ne dépend pas de Servo.
+
+use url::Url;
+
+/// Partie « page » du titre de la fenêtre pour la navigation engagée `url` :
+/// le titre du document, sinon « Loading — url » pendant le chargement,
+/// sinon l'URL.
+pub fn page_label(url: &Url, title: Option<&str>, loading: bool) -> String {
+    match title.filter(|title| !title.trim().is_empty()) {
+        Some(title) => title.to_string(),
+        None if loading => format!("Loading — {url}"),
+        None => url.to_string(),
+    }
+}
+
+/// Page affichée : URL de la dernière navigation engagée et titre du
+/// document. Seuls les delegates d'engagement (`notify_url_changed`,
+/// `notify_page_title_changed`, `notify_load_status_changed`) la modifient ;
+/// `request_navigation` n'y touche pas.
+#[derive(Debug, Default)]
+pub struct PageDisplay {
+    url: Option<Url>,
+    title: Option<String>,
+}
+
+impl PageDisplay {
+    pub fn url(&self) -> Option<&Url> {
+        self.url.as_ref()
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Nouveau document : le titre de l'ancienne page disparaît.
+    pub fn load_started(&mut self) {
+        self.title = None;
+    }
+
+    /// Navigation engagée vers `url`.
+    pub fn commit(&mut self, url: Url) {
+        self.url = Some(url);
+    }
+
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.title = title;
+    }
+
+    /// Reprend l'état d'une autre webview devenue active.
+    pub fn show(&mut self, url: Option<Url>, title: Option<String>) {
+        self.url = url;
+        self.title = title;
+    }
+
+    /// Partie « page » du titre de la fenêtre (voir [`page_label`]), `None`
+    /// tant qu'aucune navigation n'est engagée.
+    pub fn label(&self, loading: bool) -> Option<String> {
+        let url = self.url.as_ref()?;
+        Some(page_label(url, self.title.as_deref(), loading))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_prefers_title_then_loading() {
+        let url = Url::parse("https://example.com/").unwrap();
+        assert_eq!(
+            page_label(&url, None, true),
+            "Loading — https://example.com/"
+        );
+        assert_eq!(page_label(&url, None, false), "https://example.com/");
+        assert_eq!(page_label(&url, Some("Example"), true), "Example");
+        assert_eq!(page_label(&url, Some("  "), false), "https://example.com/");
+    }
+
+    #[test]
+    fn test_committed_navigation_updates_display() {
+        let mut page = PageDisplay::default();
+        assert_eq!(page.label(true), None);
+        page.load_started();
+        page.commit(Url::parse("https://example.com/").unwrap());
+        assert_eq!(
+            page.label(true).as_deref(),
+            Some("Loading — https://example.com/")
+        );
+        page.set_title(Some("Example".to_string()));
+        assert_eq!(page.label(false).as_deref(), Some("Example"));
+    }
+
+    #[test]
+    fn test_new_load_drops_old_title_until_commit() {
+        let mut page = PageDisplay::default();
+        page.commit(Url::parse("https://example.com/").unwrap());
+        page.set_title(Some("Example".to_string()));
+        // Le chargement démarre avant l'engagement de la nouvelle URL :
+        // l'ancienne reste affichée, sans son titre
+        page.load_started();
+        assert_eq!(page.url().unwrap().as_str(), "https://example.com/");
+        assert_eq!(
+            page.label(true).as_deref(),
+            Some("Loading — https://example.com/")
+        );
+    }
+
+    #[test]
+    fn test_show_switches_to_other_webview() {
+        let mut page = PageDisplay::default();
+        page.commit(Url::parse("https://example.com/").unwrap());
+        page.show(None, Some("Blank".to_string()));
+        assert_eq!(page.url(), None);
+        assert_eq!(page.title(), Some("Blank"));
+        assert_eq!(page.label(false), None);
+    }
+}
@@ -0,0 +1,132 @@
+//! JavaScript désactivé par site (`[privacy] no_js_hosts`).
+//!
+//! Une navigation principale vers un hôte listé (ou l'un de ses
+//! sous-domaines) marque la webview : jusqu'à la navigation suivante, ses
+//! requêtes de script (`<script src>`, workers) reçoivent un script vide
+//! dans `load_web_resource`. Servo n'a pas d'interrupteur JavaScript par
+//! webview : les scripts écrits dans la page elle-même s'exécutent encore.
+//!
+//! Ctrl+Shift+J ajoute ou retire l'hôte courant de la liste, enregistre
+//! `config.toml` et recharge la page ; le chrome affiche « JS » barré tant
+//! que les scripts de la page sont bloqués.
+
+use crate::blocking::RequestType;
+
+/// Hôte en minuscules sans point final.
+fn normalize(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Entrée de `list` qui couvre `host` : l'hôte lui-même ou l'un de ses
+/// domaines parents. `None` si les scripts y sont autorisés.
+pub fn matched<'a>(list: &'a [String], host: &str) -> Option<&'a str> {
+    let host = normalize(host);
+    list.iter().map(String::as_str).find(|entry| {
+        let entry = normalize(entry);
+        !entry.is_empty()
+            && host
+                .strip_suffix(entry.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.ends_with('.'))
+    })
+}
+
+/// Bascule `host` : retire les entrées qui le couvrent, ou l'ajoute s'il
+/// n'y en a aucune. Retourne `true` si les scripts y sont désormais bloqués.
+pub fn toggle(list: &mut Vec<String>, host: &str) -> bool {
+    let host = normalize(host);
+    let before = list.len();
+    list.retain(|entry| matched(std::slice::from_ref(entry), &host).is_none());
+    if list.len() != before {
+        return false;
+    }
+    list.push(host);
+    true
+}
+
+/// Applique à `list` le choix fait ailleurs pour `host` (liste de la
+/// session → liste du fichier de configuration) : ajouté s'il doit être
+/// bloqué et qu'aucune entrée ne le couvre, entrées qui le couvrent retirées
+/// sinon.
+pub fn set(list: &mut Vec<String>, host: &str, blocked: bool) {
+    let host = normalize(host);
+    if !blocked {
+        list.retain(|entry| matched(std::slice::from_ref(entry), &host).is_none());
+    } else if matched(list, &host).is_none() {
+        list.push(host);
+    }
+}
+
+/// `true` si une requête de ce type est bloquée sur une page sans
+/// JavaScript.
+pub fn blocks(request_type: RequestType) -> bool {
+    request_type == RequestType::Script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matches_host_and_subdomains() {
+        let hosts = list(&["Example.com", "news.test."]);
+        assert_eq!(matched(&hosts, "example.com"), Some("Example.com"));
+        assert_eq!(matched(&hosts, "www.EXAMPLE.com."), Some("Example.com"));
+        assert_eq!(matched(&hosts, "news.test"), Some("news.test."));
+        assert_eq!(matched(&hosts, "notexample.com"), None);
+        assert_eq!(matched(&hosts, "example.com.evil.test"), None);
+        assert_eq!(matched(&hosts, "test"), None);
+        assert_eq!(matched(&list(&["", " "]), "example.com"), None);
+    }
+
+    #[test]
+    fn test_toggle_adds_then_removes() {
+        let mut hosts = list(&["other.test"]);
+        assert!(toggle(&mut hosts, "WWW.Example.com"));
+        assert_eq!(hosts, list(&["other.test", "www.example.com"]));
+        assert!(!toggle(&mut hosts, "www.example.com"));
+        assert_eq!(hosts, list(&["other.test"]));
+    }
+
+    #[test]
+    fn test_toggle_on_subdomain_removes_parent_entry() {
+        let mut hosts = list(&["example.com", "cdn.example.com"]);
+        assert!(!toggle(&mut hosts, "cdn.example.com"));
+        assert!(hosts.is_empty());
+    }
+
+    #[test]
+    fn test_set_follows_session_choice() {
+        // Liste du fichier, sans l'entrée ajoutée par la variable d'environnement
+        let mut saved = list(&["other.test"]);
+        set(&mut saved, "Example.com", true);
+        assert_eq!(saved, list(&["other.test", "example.com"]));
+        set(&mut saved, "example.com", true);
+        assert_eq!(saved, list(&["other.test", "example.com"]));
+        set(&mut saved, "www.example.com", false);
+        assert_eq!(saved, list(&["other.test"]));
+        set(&mut saved, "absent.test", false);
+        assert_eq!(saved, list(&["other.test"]));
+    }
+
+    #[test]
+    fn test_only_scripts_are_blocked() {
+        assert!(blocks(RequestType::Script));
+        for request_type in [
+            RequestType::Document,
+            RequestType::Subdocument,
+            RequestType::Stylesheet,
+            RequestType::Image,
+            RequestType::XmlHttpRequest,
+        ] {
+            assert!(!blocks(request_type), "{request_type:?}");
+        }
+        assert_eq!(
+            RequestType::Script.blocked_response(),
+            Some(("application/javascript", &b""[..]))
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! Pages internes (historique, favoris, à propos, source) servies en `data:` URLs.
+//!
+//! Même principe que [`crate::settings`] : le HTML est généré côté Rust puis
+//! chargé dans la webview active via une URL `data:text/html`.
+//!
+//! Les autres pages générées (erreurs, avertissements, état de l'adblock…)
+//! partagent le gabarit [`shell`] et, pour les valeurs insérées dans du
+//! JavaScript, [`js_string`].
+
+use url::Url;
+
+use crate::bookmarks::Bookmark;
+use crate::settings::{html_escape, url_encode};
+
+/// Feuille de style commune aux pages internes (thème sombre de `settings`).
+const BASE_STYLE: &str = r#"
+body {
+    font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif;
+    background: #1a1a1a; color: #e0e0e0;
+    max-width: 700px; margin: 0 auto; padding: 24px;
+}
+h1 { font-size: 22px; margin-bottom: 20px; color: #fff; }
+h2 { font-size: 16px; margin-top: 28px; color: #fff; }
+p { font-size: 14px; }
+a { color: #6a9eff; text-decoration: none; }
+ul { list-style: none; padding: 0; }
+li { padding: 6px 0; border-bottom: 1px solid #333; }
+code { font-family: monospace; word-break: break-all; color: #e0e0e0; }
+.detail { color: #888; font-size: 12px; }
+.actions { display: flex; gap: 16px; align-items: center; margin-top: 24px; }
+.actions a { color: #888; font-size: 13px; text-decoration: underline; }
+button {
+    padding: 8px 24px; border: none; border-radius: 4px;
+    font-size: 14px; cursor: pointer; background: #6a9eff; color: #fff;
+}
+"#;
+
+/// Page complète au thème sombre commun. `style` s'ajoute aux règles
+/// communes (et les remplace au besoin) ; `body` est du HTML déjà échappé.
+pub fn shell(title: &str, style: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>{BASE_STYLE}{style}</style>
+</head>
+<body>
+{body}
+</body>
+</html>"#,
+        title = html_escape(title),
+    )
+}
+
+/// Littéral de chaîne JavaScript pour `s` (JSON), `<` compris échappé : la
+/// valeur ne peut ni sortir de la chaîne ni fermer un `<script>`. Dans un
+/// attribut HTML (`onclick`), le résultat doit encore passer par
+/// [`html_escape`].
+pub fn js_string(s: &str) -> String {
+    serde_json::to_string(s)
+        .unwrap_or_else(|_| "\"\"".to_string())
+        .replace('<', "\\u003c")
+}
+
+/// Règles propres aux listes (historique, favoris).
+const LIST_STYLE: &str = "
+p { color: #888; }
+.url { display: block; font-size: 12px; color: #888; }
+";
+
+/// Schéma des pages internes tapées dans la barre d'URL (`suri://adblock`).
+pub const INTERNAL_SCHEME: &str = "suri";
+
+/// Page interne ouverte par une URL `suri://`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InternalPage {
+    /// État de l'adblock (voir [`crate::privacy::status_html`]).
+    Adblock,
+}
+
+/// Page interne de `url`, `None` si ce n'est pas une page `suri://` connue.
+pub fn internal_page(url: &Url) -> Option<InternalPage> {
+    if url.scheme() != INTERNAL_SCHEME {
+        return None;
+    }
+    match url.host_str()? {
+        "adblock" => Some(InternalPage::Adblock),
+        _ => None,
+    }
+}
+
+/// Encode une page HTML en URL `data:`.
+pub fn data_url(html: &str) -> Option<Url> {
+    Url::parse(&format!(
+        "data:text/html;charset=utf-8,{}",
+        url_encode(html)
+    ))
+    .ok()
+}
+
+/// Liste des pages visitées pendant la session, la plus récente en premier.
+pub fn history_html(visited: &[Url]) -> String {
+    let items: String = visited
+        .iter()
+        .rev()
+        .map(|url| link_item(url.as_str(), url.as_str()))
+        .collect();
+    page("History", &items, "No pages visited yet.")
+}
+
+/// Liste des favoris, dans l'ordre d'ajout.
+pub fn bookmarks_html(bookmarks: &[Bookmark]) -> String {
+    let items: String = bookmarks
+        .iter()
+        .map(|b| link_item(&b.title, &b.url))
+        .collect();
+    page(
+        "Bookmarks",
+        &items,
+        "No bookmarks yet. Press Ctrl+D to add one.",
+    )
+}
+
+/// Page « À propos ».
+pub fn about_html() -> String {
+    shell(
+        "About SuriBrows",
+        LIST_STYLE,
+        &format!(
+            "<h1>SuriBrows {version}</h1>\n\
+             <p>A privacy-first browser built on the Servo engine.</p>",
+            version = env!("CARGO_PKG_VERSION"),
+        ),
+    )
+}
+
+/// Taille maximale du source affiché par Ctrl+U ; au-delà, il est tronqué
+/// (l'échappement et l'URL `data:` figeraient l'interface).
+pub const MAX_SOURCE_BYTES: usize = 2 * 1024 * 1024;
+
+/// Script renvoyant le source brut de la page : la requête repasse par le
+/// réseau de Servo (adblock, User-Agent configuré). Si elle échoue, le DOM
+/// sérialisé sert de repli.
+pub const FETCH_SOURCE_SCRIPT: &str = r#"(function() {
+  try {
+    const request = new XMLHttpRequest();
+    request.open("GET", location.href, false);
+    request.send();
+    if (request.status === 0 || (request.status >= 200 && request.status < 400)) {
+      return request.responseText;
+    }
+  } catch (e) {}
+  return document.documentElement.outerHTML;
+})()"#;
+
+/// Source de `url` avec numéros de ligne, titre `view-source: <url>`.
+pub fn view_source_html(url: &str, source: &str) -> String {
+    let (source, truncated) = if source.len() > MAX_SOURCE_BYTES {
+        let mut end = MAX_SOURCE_BYTES;
+        while !source.is_char_boundary(end) {
+            end -= 1;
+        }
+        (&source[..end], true)
+    } else {
+        (source, false)
+    };
+
+    let lines: Vec<&str> = source.lines().collect();
+    let width = lines.len().max(1).to_string().len();
+    let mut body = String::with_capacity(source.len() * 2);
+    for (n, line) in lines.iter().enumerate() {
+        body.push_str(&format!(
+            "<span class=\"ln\">{:>width$}</span> {}\n",
+            n + 1,
+            html_escape(line)
+        ));
+    }
+    let notice = if truncated {
+        format!(
+            "<p class=\"notice\">Source truncated to the first {} MB.</p>",
+            MAX_SOURCE_BYTES / (1024 * 1024)
+        )
+    } else {
+        String::new()
+    };
+
+    shell(
+        &format!("view-source: {url}"),
+        "
+body { max-width: none; margin: 0; padding: 0; }
+pre { font: 13px/1.4 ui-monospace, Consolas, monospace; margin: 0; padding: 8px; }
+.ln { color: #666; user-select: none; }
+.notice { color: #e0a040; padding: 0 8px; }
+",
+        &format!("{notice}<pre>{body}</pre>"),
+    )
+}
+
+fn link_item(title: &str, url: &str) -> String {
+    format!(
+        r#"<li><a href="{url}">{title}<span class="url">{url}</span></a></li>"#,
+        url = html_escape(url),
+        title = html_escape(title),
+    )
+}
+
+fn page(title: &str, items: &str, empty: &str) -> String {
+    let body = if items.is_empty() {
+        format!("<p>{empty}</p>")
+    } else {
+        format!("<ul>{items}</ul>")
+    };
+    shell(title, LIST_STYLE, &format!("<h1>{title}</h1>\n{body}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_most_recent_first() {
+        let visited = [
+            Url::parse("https://first.example/").unwrap(),
+            Url::parse("https://second.example/").unwrap(),
+        ];
+        let html = history_html(&visited);
+        let first = html.find("first.example").unwrap();
+        let second = html.find("second.example").unwrap();
+        assert!(second < first);
+    }
+
+    #[test]
+    fn test_bookmarks_titles_escaped() {
+        let bookmarks = [Bookmark {
+            url: "https://example.com/?a=1&b=2".to_string(),
+            title: "<script>alert(1)</script>".to_string(),
+            added_at: 0,
+        }];
+        let html = bookmarks_html(&bookmarks);
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("a=1&amp;b=2"));
+    }
+
+    #[test]
+    fn test_empty_lists_show_placeholder() {
+        assert!(history_html(&[]).contains("No pages visited yet."));
+        assert!(bookmarks_html(&[]).contains("No bookmarks yet."));
+    }
+
+    #[test]
+    fn test_view_source_numbers_and_escapes() {
+        let html = view_source_html("https://example.com/?a&b", "<p>\n  hi\n</p>");
+        assert!(html.contains("<title>view-source: https://example.com/?a&amp;b</title>"));
+        assert!(html.contains(r#"<span class="ln">1</span> &lt;p&gt;"#));
+        assert!(html.contains(r#"<span class="ln">3</span> &lt;/p&gt;"#));
+        assert!(!html.contains("notice\">Source truncated"));
+    }
+
+    #[test]
+    fn test_view_source_truncates_large_documents() {
+        // Caractère multi-octets à cheval sur la limite
+        let source = format!("{}é{}", "a".repeat(MAX_SOURCE_BYTES - 1), "b".repeat(10));
+        let html = view_source_html("https://example.com/", &source);
+        assert!(html.contains("Source truncated to the first 2 MB."));
+        assert!(!html.contains('é'));
+        assert!(!html.contains(&"b".repeat(10)));
+    }
+
+    #[test]
+    fn test_shell_escapes_title() {
+        let html = shell("a <b> & c", "h1 { color: red; }", "<h1>Body</h1>");
+        assert!(html.contains("<title>a &lt;b&gt; &amp; c</title>"));
+        assert!(html.contains("h1 { color: red; }</style>"));
+        assert!(html.contains("<body>\n<h1>Body</h1>\n</body>"));
+    }
+
+    #[test]
+    fn test_js_string_cannot_escape_string_or_script() {
+        assert_eq!(js_string(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(
+            js_string("</script><script>alert(1)"),
+            r#""\u003c/script>\u003cscript>alert(1)""#
+        );
+    }
+
+    #[test]
+    fn test_data_url_roundtrip() {
+        let url = data_url(&about_html()).unwrap();
+        assert_eq!(url.scheme(), "data");
+    }
+
+    #[test]
+    fn test_internal_pages() {
+        let page = |s: &str| internal_page(&Url::parse(s).unwrap());
+        assert_eq!(page("suri://adblock"), Some(InternalPage::Adblock));
+        assert_eq!(page("suri://adblock/"), Some(InternalPage::Adblock));
+        assert_eq!(page("suri://unknown"), None);
+        assert_eq!(page("https://adblock/"), None);
+    }
+}
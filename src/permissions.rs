@@ -0,0 +1,185 @@
+//! Décisions de permission par site, mémorisées dans `permissions.json`.
+//!
+//! Le fichier est un objet JSON `{ "https://example.com": { "ClipboardRead":
+//! "deny" }, … }`, indexé par origine puis par nom de permission. Il n'y a pas
+//! encore d'interface pour le modifier : supprimer le fichier oublie toutes
+//! les décisions.
+//!
+//! Sans décision mémorisée, une demande est refusée ; avec `[privacy]
+//! permission_prompts = true`, le chrome demande à l'utilisateur et sa
+//! réponse est mémorisée.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use url::Url;
+
+use crate::config;
+
+/// Nom du fichier de permissions dans [`config::data_dir`].
+pub const PERMISSIONS_FILE: &str = "permissions.json";
+
+/// Réponse mémorisée pour une permission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Allow,
+    Deny,
+}
+
+/// Suite à donner à une demande de permission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Allow,
+    Deny,
+    /// Demander à l'utilisateur (barre du chrome).
+    Ask,
+}
+
+/// Origine servant de clé (`https://example.com:8443`). `None` pour une
+/// origine opaque (`data:`, `file:`…) : rien n'est mémorisé pour elle.
+pub fn origin_key(url: &Url) -> Option<String> {
+    let origin = url.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+/// Décisions par origine, puis par permission.
+#[derive(Debug)]
+pub struct PermissionStore {
+    path: PathBuf,
+    decisions: BTreeMap<String, BTreeMap<String, Decision>>,
+}
+
+impl PermissionStore {
+    /// Charge `permissions.json` depuis le dossier de données.
+    pub fn load_default() -> Self {
+        Self::load(config::data_dir().join(PERMISSIONS_FILE))
+    }
+
+    /// Charge le fichier `path`. Un fichier absent ou illisible donne une
+    /// table vide (avec un avertissement s'il est invalide).
+    pub fn load(path: PathBuf) -> Self {
+        let decisions = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "Invalid permissions file, starting empty");
+                BTreeMap::new()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Cannot read permissions");
+                BTreeMap::new()
+            }
+        };
+        Self { path, decisions }
+    }
+
+    /// Écrit les décisions sur disque (écriture atomique).
+    pub fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(&self.decisions).map_err(io::Error::other)?;
+        config::write_atomic(&self.path, &json)
+    }
+
+    /// Chemin du fichier de permissions.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Décision mémorisée pour `permission` sur `origin`.
+    pub fn get(&self, origin: &str, permission: &str) -> Option<Decision> {
+        self.decisions.get(origin)?.get(permission).copied()
+    }
+
+    /// Mémorise la décision de l'utilisateur.
+    pub fn set(&mut self, origin: &str, permission: &str, decision: Decision) {
+        self.decisions
+            .entry(origin.to_string())
+            .or_default()
+            .insert(permission.to_string(), decision);
+    }
+
+    /// Suite à donner à une demande : la décision mémorisée, sinon une
+    /// question si `prompts` est activé, sinon un refus.
+    pub fn resolve(&self, origin: &str, permission: &str, prompts: bool) -> Outcome {
+        match self.get(origin, permission) {
+            Some(Decision::Allow) => Outcome::Allow,
+            Some(Decision::Deny) => Outcome::Deny,
+            None if prompts => Outcome::Ask,
+            None => Outcome::Deny,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn temp_store(dir: &TempDir) -> PermissionStore {
+        PermissionStore::load(dir.join(PERMISSIONS_FILE))
+    }
+
+    #[test]
+    fn test_resolve_denies_by_default() {
+        let dir = TempDir::new("perms");
+        let store = temp_store(&dir);
+        assert_eq!(
+            store.resolve("https://example.com", "ClipboardRead", false),
+            Outcome::Deny
+        );
+        assert_eq!(
+            store.resolve("https://example.com", "ClipboardRead", true),
+            Outcome::Ask
+        );
+    }
+
+    #[test]
+    fn test_remembered_decision_wins_over_prompt() {
+        let dir = TempDir::new("perms");
+        let mut store = temp_store(&dir);
+        store.set("https://example.com", "Midi", Decision::Allow);
+        store.set("https://tracker.test", "Midi", Decision::Deny);
+        assert_eq!(
+            store.resolve("https://example.com", "Midi", false),
+            Outcome::Allow
+        );
+        assert_eq!(
+            store.resolve("https://tracker.test", "Midi", true),
+            Outcome::Deny
+        );
+        // Par origine et par permission
+        assert_eq!(store.get("https://example.com", "Camera"), None);
+        assert_eq!(store.get("http://example.com", "Midi"), None);
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let dir = TempDir::new("perms");
+        let mut store = temp_store(&dir);
+        store.set("https://example.com", "Notifications", Decision::Deny);
+        store.save().unwrap();
+
+        let reloaded = PermissionStore::load(store.path().to_path_buf());
+        assert_eq!(
+            reloaded.get("https://example.com", "Notifications"),
+            Some(Decision::Deny)
+        );
+        let json = fs::read_to_string(store.path()).unwrap();
+        assert!(json.contains(r#""Notifications": "deny""#));
+    }
+
+    #[test]
+    fn test_origin_key() {
+        let url = Url::parse("https://example.com:8443/path?q=1").unwrap();
+        assert_eq!(
+            origin_key(&url).as_deref(),
+            Some("https://example.com:8443")
+        );
+        let url = Url::parse("https://example.com/").unwrap();
+        assert_eq!(origin_key(&url).as_deref(), Some("https://example.com"));
+        assert_eq!(origin_key(&Url::parse("data:text/html,hi").unwrap()), None);
+    }
+}
@@ -5,6 +5,7 @@
 //! - HTTPS enforcement, MIME sniff disabled
 //! - Tracking APIs disabled (geolocation, Bluetooth, WebRTC, notifications)
 //! - Generic Chrome user-agent to reduce fingerprinting
+//! - Private browsing (`--private`): no HTTP cache, no persistent storage
 //!
 //! All values are driven by [`crate::config::ServoConfig`] and
 //! [`crate::config::PrivacyConfig`] so users can tune them from `config.toml`.
@@ -112,6 +113,33 @@ pub fn build_servo_preferences(
     prefs
 }
 
+/// Enables Servo's devtools server on `port` (Firefox DevTools can attach to
+/// it via `about:debugging`). `0` leaves it disabled. Servo only starts the
+/// server at startup, so a port change needs a restart.
+pub fn apply_devtools(prefs: &mut servo::Preferences, port: u16) {
+    prefs.devtools_server_enabled = port != 0;
+    if port != 0 {
+        prefs.devtools_server_port = i64::from(port);
+        info!(port, "Devtools server enabled");
+    }
+}
+
+/// Private browsing (`--private`): nothing from the session may outlive it.
+///
+/// - HTTP cache disabled (`network_http_cache_size = 0`)
+/// - IndexedDB and the Cookie Store API disabled: sites can't stash data
+///   behind the user's back, at the cost of some web apps
+///
+/// Cookies and Web Storage stay enabled: Servo only writes them to disk when
+/// given a config directory, which SuriBrows never does, so they already
+/// vanish with the process.
+pub fn apply_private_mode(prefs: &mut servo::Preferences) {
+    prefs.network_http_cache_size = 0;
+    prefs.dom_indexeddb_enabled = false;
+    prefs.dom_cookiestore_enabled = false;
+    info!("Private browsing: HTTP cache and persistent storage disabled");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,6 +249,8 @@ mod tests {
             disable_bluetooth: false,
             disable_notifications: false,
             disable_webrtc: false,
+            permission_prompts: false,
+            ..Default::default()
         };
         let prefs = build_servo_preferences(&ServoConfig::default(), &privacy_cfg);
         assert!(!prefs.network_enforce_tls_enabled);
@@ -230,4 +260,48 @@ mod tests {
         assert!(prefs.dom_notification_enabled);
         assert!(prefs.dom_webrtc_enabled);
     }
+
+    #[test]
+    fn test_devtools_disabled_by_default() {
+        let mut prefs = default_prefs();
+        apply_devtools(&mut prefs, 0);
+        assert!(!prefs.devtools_server_enabled);
+    }
+
+    #[test]
+    fn test_devtools_port_applied() {
+        let mut prefs = default_prefs();
+        apply_devtools(&mut prefs, 6080);
+        assert!(prefs.devtools_server_enabled);
+        assert_eq!(prefs.devtools_server_port, 6080);
+    }
+
+    #[test]
+    fn test_private_mode_disables_cache_and_storage() {
+        let normal = default_prefs();
+        let mut private = default_prefs();
+        apply_private_mode(&mut private);
+
+        assert!(normal.network_http_cache_size > 0);
+        assert_eq!(private.network_http_cache_size, 0);
+        assert!(normal.dom_indexeddb_enabled);
+        assert!(!private.dom_indexeddb_enabled);
+        assert!(normal.dom_cookiestore_enabled);
+        assert!(!private.dom_cookiestore_enabled);
+    }
+
+    #[test]
+    fn test_private_mode_keeps_privacy_hardening() {
+        let normal = default_prefs();
+        let mut private = default_prefs();
+        apply_private_mode(&mut private);
+
+        assert_eq!(private.user_agent, normal.user_agent);
+        assert_eq!(
+            private.network_enforce_tls_enabled,
+            normal.network_enforce_tls_enabled
+        );
+        assert_eq!(private.dom_webrtc_enabled, normal.dom_webrtc_enabled);
+        assert_eq!(private.layout_threads, normal.layout_threads);
+    }
 }
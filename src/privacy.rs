@@ -6,23 +6,106 @@
 //!
 //! ## Utilisation
 //!
-//! 1. Placer les fichiers de filtres (`.txt`) dans `resources/filters/`
-//! 2. `AdblockEngine::new()` les charge automatiquement au démarrage
-//! 3. Si le dossier est vide ou absent, le filtrage est désactivé
+//! 1. Les listes sont déclarées dans `config.toml` (`[[privacy.filter_lists]]` :
+//!    `name`, `url`, `enabled`, `file`) ; par défaut EasyList et EasyPrivacy
+//! 2. `CompiledEngine::new()` charge les listes activées présentes dans
+//!    `resources/filters/` ; les autres fichiers du dossier sont ignorés.
+//!    Au démarrage, c'est fait sur un thread de fond : en attendant, les
+//!    requêtes passent sans filtrage.
+//!    Une liste au format hosts (`0.0.0.0 ads.example.com`, extension
+//!    `.hosts` ou reconnue à son contenu) est convertie en règles `||domaine^`
+//! 3. Une liste activée absente est téléchargée au démarrage (voir
+//!    [`spawn_filter_updater`])
+//! 4. Les règles de l'utilisateur (`user-filters.txt`, dans le dossier de
+//!    `config.toml` pour survivre aux mises à jour) sont ajoutées après les
+//!    listes : ses exceptions `@@` l'emportent sur les règles distribuées
+//! 5. Si aucune liste activée n'est disponible, le filtrage est désactivé
 //!
-//! ## Listes de filtres recommandées
+//! ## Ressources de substitution
 //!
-//! - EasyList : <https://easylist.to/easylist/easylist.txt>
-//! - EasyPrivacy : <https://easylist.to/easylist/easyprivacy.txt>
+//! Une règle `$redirect=noopjs` remplace la requête bloquée par une ressource
+//! neutre (script vide, faux `analytics.js`…) : la page qui l'attend ne
+//! casse pas. Les ressources viennent de uBlock Origin, celles que les listes
+//! EasyList et uBO nomment : une copie de `src/web_accessible_resources/` et
+//! `src/js/redirect-resources.js` de son dépôt, placée dans
+//! `resources/ublock/` (voir [`load_surrogates`]). Sans cette copie,
+//! `resources/adblock-resources.json` n'en fournit qu'une poignée ; une règle
+//! qui nomme une ressource absente bloque seulement.
+//!
+//! ## Filtrage cosmétique
+//!
+//! Les règles `##` masquent les éléments publicitaires que le blocage réseau
+//! laisse en place (emplacements vides, encarts du site lui-même). Dès que
+//! l'en-tête de la page est parsé (`LoadStatus::HeadParsed`), puis une fois
+//! la page chargée, [`COLLECT_CLASS_ID_SCRIPT`] relève les classes et ids du
+//! document ; [`AdblockEngine::hiding_css`] en déduit la feuille de style
+//! (règles génériques et propres au site), injectée par
+//! [`inject_style_script`]. Les règles propres au site s'appliquent ainsi
+//! avant le premier rendu du corps ; les règles génériques, qui dépendent
+//! des classes présentes, sont complétées au chargement. Désactivé par
+//! `[privacy] cosmetic_filtering`.
+//!
+//! ## Mise à jour automatique
+//!
+//! Les listes activées de `[privacy] filter_lists` sont retéléchargées toutes les
+//! `filter_update_hours` heures par un thread de fond, avec des requêtes
+//! conditionnelles (`If-None-Match`, `If-Modified-Since`). Une liste modifiée
+//! est écrite de façon atomique dans le dossier des filtres, puis le moteur
+//! est reconstruit sur un thread de fond et échangé sur le thread principal
+//! ([`EngineSlot`]), sans redémarrage. Ctrl+Shift+F fait de même après une
+//! modification de `user-filters.txt` ou d'une liste. En cas d'échec,
+//! l'ancienne liste reste en place. L'état des mises à jour (ETag, date,
+//! dernière vérification) est gardé dans `updates.json`, à côté des listes ;
+//! la liste de sécurité passe par le même thread, seul à réécrire ce fichier.
+//!
+//! ## Exceptions `$document`
+//!
+//! Une règle `@@||example.com^$document` exempte tout le site : à chaque
+//! navigation principale, [`AdblockEngine::is_document_excepted`] est
+//! interrogé une fois, et tant que la page est exemptée ses requêtes ne sont
+//! pas vérifiées et le filtrage cosmétique est sauté. Le badge affiche
+//! « off ».
+//!
+//! ## Journal
+//!
+//! Avec `[privacy] block_log`, chaque requête bloquée est notée dans un
+//! fichier (voir [`crate::block_log`]) : de quoi retrouver le filtre qui
+//! casse un site.
+//!
+//! ## Coût du filtrage
+//!
+//! Chaque vérification est chronométrée ([`crate::match_cost`]) : nombre,
+//! total, maximum et 95e centile, réponses du cache et appels au moteur
+//! séparés. Un résumé part en `debug` à chaque navigation et au plus tard
+//! toutes les [`MATCH_COST_LOG_INTERVAL`] ; la page `suri://adblock`
+//! affiche les mêmes chiffres.
 
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::hash::Hash;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use adblock::Engine;
 use adblock::lists::{FilterSet, ParseOptions};
-use tracing::{info, warn};
+use adblock::resources::Resource;
+use adblock::resources::resource_assembler::assemble_web_accessible_resources;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::block_log::{self, BlockLog};
+use crate::config::{self, FilterListConfig};
+use crate::http;
+use crate::match_cost::{MatchCost, Timings};
+use crate::pages::{self, js_string};
+use crate::settings::{html_escape, url_encode};
 
 /// Wrapper autour du moteur `adblock::Engine`.
 ///
@@ -31,71 +114,354 @@ use tracing::{info, warn};
 /// `should_block()` qui prend l'URL, l'URL source, et le type de requête.
 pub struct AdblockEngine {
     engine: Engine,
-    /// Cache of (url, source_url) → blocked? to avoid redundant filter matching.
-    /// Cleared on navigation via `clear_cache()`.
-    cache: RefCell<HashMap<(String, String), bool>>,
+    /// Moteur relu depuis `engine.dat` ou compilé depuis les listes.
+    source: EngineSource,
+    /// Cache of (url, source_url, request_type) → blocked? to avoid redundant
+    /// filter matching. Cleared on navigation via `clear_cache()`.
+    cache: RefCell<HashMap<(String, String, &'static str), Verdict>>,
+    /// Journal des requêtes bloquées (`[privacy] block_log`). Passé au
+    /// moteur suivant alors que celui-ci peut encore servir ailleurs.
+    block_log: RefCell<Option<BlockLog>>,
+    /// Durée des vérifications, et date du dernier résumé journalisé.
+    match_cost: RefCell<MatchCost>,
+    match_cost_logged: Cell<Instant>,
 }
 
-impl AdblockEngine {
-    /// Charge les listes de filtres depuis `resources/filters/` et construit le moteur.
+/// Délai maximal entre deux résumés du coût du filtrage, sans navigation.
+pub const MATCH_COST_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Moteur en service, remplacé d'un bloc quand une reconstruction aboutit.
+///
+/// Le moteur est partagé par `Rc` : une vérification en cours garde sa
+/// copie et finit avec l'ancien moteur, même si le nouveau est installé
+/// entre-temps. Le cache de verdicts appartient au moteur : il repart vide
+/// avec le nouveau, au moment même de l'échange.
+#[derive(Default)]
+pub struct EngineSlot {
+    current: RefCell<Option<Rc<AdblockEngine>>>,
+    /// Numéro de la dernière reconstruction lancée : seule celle-ci est
+    /// installée, une plus ancienne qui finit après est ignorée.
+    generation: Cell<u64>,
+}
+
+impl EngineSlot {
+    /// Moteur en service, `None` tant qu'aucun n'est chargé.
+    pub fn get(&self) -> Option<Rc<AdblockEngine>> {
+        self.current.borrow().clone()
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.current.borrow().is_some()
+    }
+
+    /// Note le lancement d'une reconstruction ; retourne son numéro, à
+    /// passer à [`Self::swap`].
+    pub fn begin_rebuild(&self) -> u64 {
+        let generation = self.generation.get().wrapping_add(1);
+        self.generation.set(generation);
+        generation
+    }
+
+    /// Moteur compilé au démarrage : installé seulement si aucun moteur
+    /// n'est en service (une reconstruction a pu finir avant lui).
+    pub fn install_initial(&self, engine: AdblockEngine) -> bool {
+        let mut current = self.current.borrow_mut();
+        if current.is_some() {
+            return false;
+        }
+        *current = Some(Rc::new(engine));
+        true
+    }
+
+    /// Moteur compilé au démarrage sur un thread de fond
+    /// (`WakerEvent::AdblockReady`) : mis en service avec le journal ouvert
+    /// par `block_log`, sauf si une reconstruction a déjà installé un moteur
+    /// plus récent. Il est alors abandonné, sans ouvrir de journal, et la
+    /// fonction retourne `false`.
+    pub fn install_compiled(
+        &self,
+        compiled: CompiledEngine,
+        block_log: impl FnOnce() -> Option<BlockLog>,
+    ) -> bool {
+        if self.is_loaded() {
+            return false;
+        }
+        self.install_initial(AdblockEngine::from_compiled(compiled).with_block_log(block_log()))
+    }
+
+    /// Remplace le moteur par celui de la reconstruction `generation`, qui
+    /// reprend le journal de l'ancien. Retourne `false` (moteur ignoré) si
+    /// une reconstruction plus récente a été lancée depuis.
+    pub fn swap(&self, generation: u64, engine: AdblockEngine) -> bool {
+        if generation != self.generation.get() {
+            return false;
+        }
+        let mut current = self.current.borrow_mut();
+        if let Some(log) = current.as_ref().and_then(|old| old.take_block_log()) {
+            *engine.block_log.borrow_mut() = Some(log);
+        }
+        *current = Some(Rc::new(engine));
+        true
+    }
+}
+
+/// Décision de l'adblock pour une requête.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Allow,
+    Block,
+    /// Bloquée et remplacée par une ressource de substitution (`$redirect`).
+    Redirect(Rc<Surrogate>),
+}
+
+/// Ressource de substitution servie à la place d'une requête bloquée : un
+/// script neutre garde fonctionnelles les pages qui l'appellent.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Surrogate {
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+impl Surrogate {
+    /// Lit l'URL `data:<type>;base64,<contenu>` donnée par le moteur.
+    fn from_data_url(url: &str) -> Option<Self> {
+        let (content_type, data) = url.strip_prefix("data:")?.split_once(";base64,")?;
+        let body = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .ok()?;
+        Some(Self {
+            content_type: content_type.to_string(),
+            body,
+        })
+    }
+}
+
+/// Statistiques de l'adblock : compteurs de la session ([`BlockCounters`])
+/// et coût du filtrage depuis la construction du moteur.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockStats {
+    /// Requêtes vérifiées, réponses en cache comprises.
+    pub checked: u64,
+    pub blocked: u64,
+    /// Requêtes bloquées sur la page affichée depuis sa dernière navigation
+    /// du main frame.
+    pub page_blocked: u32,
+    /// Requêtes bloquées par hôte (hôte de la requête, pas de la page).
+    pub blocked_by_host: BTreeMap<String, u64>,
+    /// Durée des vérifications.
+    pub match_cost: MatchCost,
+}
+
+/// Compteurs de l'adblock pour la session, par page (`K` : identifiant de
+/// webview). Gardés hors du moteur : une reconstruction ne les remet pas à
+/// zéro. Thread principal uniquement.
+#[derive(Debug)]
+pub struct BlockCounters<K> {
+    checked: Cell<u64>,
+    blocked: Cell<u64>,
+    blocked_by_host: RefCell<BTreeMap<String, u64>>,
+    /// Requêtes bloquées depuis la dernière navigation du main frame, par
+    /// webview.
+    page_blocked: RefCell<HashMap<K, u32>>,
+}
+
+impl<K> Default for BlockCounters<K> {
+    fn default() -> Self {
+        Self {
+            checked: Cell::new(0),
+            blocked: Cell::new(0),
+            blocked_by_host: RefCell::new(BTreeMap::new()),
+            page_blocked: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> BlockCounters<K> {
+    /// Compte une vérification de la requête `url` faite pour la page `page`.
+    pub fn record(&self, page: K, url: &str, blocked: bool) {
+        self.checked.set(self.checked.get().saturating_add(1));
+        if !blocked {
+            return;
+        }
+        self.blocked.set(self.blocked.get().saturating_add(1));
+        let mut pages = self.page_blocked.borrow_mut();
+        let count = pages.entry(page).or_insert(0);
+        *count = count.saturating_add(1);
+        if let Some(host) = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_owned))
+        {
+            let mut by_host = self.blocked_by_host.borrow_mut();
+            let count = by_host.entry(host).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+    }
+
+    /// Requêtes bloquées sur `page` depuis sa dernière navigation.
+    pub fn page_blocked(&self, page: K) -> u32 {
+        self.page_blocked.borrow().get(&page).copied().unwrap_or(0)
+    }
+
+    /// Nouvelle navigation du main frame de `page`, ou page fermée : son
+    /// compteur repart de zéro.
+    pub fn reset_page(&self, page: K) {
+        self.page_blocked.borrow_mut().remove(&page);
+    }
+
+    /// Instantané des compteurs, avec ceux de la page affichée `page` et le
+    /// coût du filtrage du moteur courant.
+    pub fn stats(&self, page: Option<K>, match_cost: MatchCost) -> BlockStats {
+        BlockStats {
+            checked: self.checked.get(),
+            blocked: self.blocked.get(),
+            page_blocked: page.map_or(0, |page| self.page_blocked(page)),
+            blocked_by_host: self.blocked_by_host.borrow().clone(),
+            match_cost,
+        }
+    }
+}
+
+/// Moteur compilé, pas encore prêt à filtrer. Au démarrage, il est construit
+/// sur un thread de fond puis confié au thread principal
+/// ([`AdblockEngine::from_compiled`]) : contrairement à [`AdblockEngine`] et
+/// à son cache de verdicts, il est `Send`.
+pub struct CompiledEngine(Engine, EngineSource);
+
+impl std::fmt::Debug for CompiledEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompiledEngine").finish_non_exhaustive()
+    }
+}
+
+impl CompiledEngine {
+    /// Charge les listes activées de `subscriptions` depuis
+    /// `resources/filters/` et construit le moteur.
     ///
-    /// Retourne `None` si aucun fichier de filtres n'est trouvé (le navigateur
+    /// Retourne `None` si aucune liste activée n'est disponible (le navigateur
     /// fonctionnera sans ad-blocking).
-    pub fn new() -> Option<Self> {
+    ///
+    /// Le moteur compilé est mis en cache dans `engine.dat` (voir
+    /// [`load_engine`]) : tant que les listes ne changent pas, il est relu
+    /// au lieu d'être recompilé.
+    pub fn new(subscriptions: &[FilterListConfig]) -> Option<Self> {
         let filters_dir = find_filters_dir()?;
 
-        let entries: Vec<_> = fs::read_dir(&filters_dir)
-            .ok()?
-            .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().is_some_and(|ext| ext == "txt"))
-            .collect();
-
-        if entries.is_empty() {
+        let lists = enabled_list_paths(&filters_dir, subscriptions);
+        let user_filters = Some(config::data_dir().join(USER_FILTERS_FILE)).filter(|p| p.is_file());
+        if lists.is_empty() && user_filters.is_none() {
             warn!(
-                "Dossier filters/ trouvé mais vide ({}). Ad-blocking désactivé.",
+                "Aucune liste de filtres activée dans {}. Ad-blocking désactivé.",
                 filters_dir.display()
             );
             return None;
         }
 
-        let mut filter_set = FilterSet::new(false);
-
-        for entry in &entries {
-            let path = entry.path();
-            match fs::read_to_string(&path) {
-                Ok(content) => {
-                    let line_count = content.lines().count();
-                    filter_set.add_filter_list(&content, ParseOptions::default());
-                    info!(
-                        "Liste de filtres chargée : {} ({} lignes)",
-                        path.display(),
-                        line_count
-                    );
-                }
-                Err(e) => {
-                    warn!("Impossible de lire {} : {}", path.display(), e);
-                }
-            }
+        let started = Instant::now();
+        // Ancien emplacement du cache, à côté de la configuration
+        let _ = fs::remove_file(config::data_dir().join(ENGINE_CACHE_FILE));
+        let cache_path = config::cache_dir().join(ENGINE_CACHE_FILE);
+        let (mut engine, source) = load_engine(&lists, user_filters.as_deref(), &cache_path);
+        // Hors du cache : les ressources ne font pas partie du moteur sérialisé
+        if let Some(resources_dir) = filters_dir.parent() {
+            engine.use_resources(load_surrogates(resources_dir));
         }
+        info!(
+            "Moteur adblock initialisé avec {} liste(s) ({:?}, {:?})",
+            lists.len() + usize::from(user_filters.is_some()),
+            source,
+            started.elapsed()
+        );
+
+        Some(Self(engine, source))
+    }
+}
 
-        let engine = Engine::from_filter_set(filter_set, true);
-        info!("Moteur adblock initialisé avec {} liste(s)", entries.len());
+impl AdblockEngine {
+    /// Construit le moteur sur le thread appelant (voir [`CompiledEngine::new`]).
+    pub fn new(subscriptions: &[FilterListConfig]) -> Option<Self> {
+        CompiledEngine::new(subscriptions).map(Self::from_compiled)
+    }
 
-        Some(Self {
+    /// Moteur prêt à filtrer à partir d'un moteur compilé sur un autre thread.
+    pub fn from_compiled(compiled: CompiledEngine) -> Self {
+        Self {
+            source: compiled.1,
+            ..Self::with_engine(compiled.0)
+        }
+    }
+
+    fn with_engine(engine: Engine) -> Self {
+        Self {
             engine,
+            source: EngineSource::Lists,
             cache: RefCell::new(HashMap::new()),
-        })
+            block_log: RefCell::new(None),
+            match_cost: RefCell::new(MatchCost::default()),
+            match_cost_logged: Cell::new(Instant::now()),
+        }
+    }
+
+    /// Note chaque requête bloquée dans `log`.
+    pub fn with_block_log(self, log: Option<BlockLog>) -> Self {
+        *self.block_log.borrow_mut() = log;
+        self
+    }
+
+    /// Origine du moteur : cache `engine.dat` ou listes compilées.
+    pub fn source(&self) -> EngineSource {
+        self.source
+    }
+
+    /// Retire le journal, pour le passer au moteur qui remplace celui-ci.
+    pub fn take_block_log(&self) -> Option<BlockLog> {
+        self.block_log.borrow_mut().take()
     }
 
     /// Vérifie si une requête doit être bloquée.
     ///
     /// - `url` : URL de la ressource demandée
     /// - `source_url` : URL de la page qui a initié la requête
-    /// - `request_type` : type de ressource ("document", "script", "image", "stylesheet", "other")
-    pub fn should_block(&self, url: &str, source_url: &str, request_type: &str) -> bool {
-        let key = (url.to_owned(), source_url.to_owned());
-        if let Some(&cached) = self.cache.borrow().get(&key) {
-            return cached;
+    /// - `request_type` : type de ressource (voir [`crate::blocking::RequestType`])
+    pub fn should_block(&self, url: &str, source_url: &str, request_type: &'static str) -> bool {
+        self.check_request(url, source_url, request_type) != Verdict::Allow
+    }
+
+    /// Comme [`Self::should_block`], avec la ressource de substitution d'une
+    /// règle `$redirect`.
+    pub fn check_request(
+        &self,
+        url: &str,
+        source_url: &str,
+        request_type: &'static str,
+    ) -> Verdict {
+        let started = Instant::now();
+        let (verdict, cached) = self.check(url, source_url, request_type);
+        let elapsed = started.elapsed();
+        {
+            let mut cost = self.match_cost.borrow_mut();
+            if cached {
+                cost.hits.record(elapsed);
+            } else {
+                cost.misses.record(elapsed);
+            }
+        }
+        if self.match_cost_logged.get().elapsed() >= MATCH_COST_LOG_INTERVAL {
+            self.log_match_cost();
+        }
+        if verdict != Verdict::Allow
+            && let Some(log) = self.block_log.borrow().as_ref()
+        {
+            log.record(source_url, url, request_type);
+        }
+        verdict
+    }
+
+    /// Verdict de la requête, et `true` s'il vient du cache.
+    fn check(&self, url: &str, source_url: &str, request_type: &'static str) -> (Verdict, bool) {
+        // Le type fait partie de la clé : `$image` ne doit pas bloquer un script
+        let key = (url.to_owned(), source_url.to_owned(), request_type);
+        if let Some(cached) = self.cache.borrow().get(&key) {
+            return (cached.clone(), true);
         }
 
         let request = match adblock::request::Request::new(url, source_url, request_type)
@@ -104,19 +470,803 @@ impl AdblockEngine {
             Ok(r) => r,
             Err(_) => {
                 // URL unparseable by adblock (data URI, blob, etc.) — allow it.
-                self.cache.borrow_mut().insert(key, false);
-                return false;
+                self.cache.borrow_mut().insert(key, Verdict::Allow);
+                return (Verdict::Allow, false);
             }
         };
-        let blocked = self.engine.check_network_request(&request).matched;
-        self.cache.borrow_mut().insert(key, blocked);
-        blocked
+        let result = self.engine.check_network_request(&request);
+        let verdict = match result.redirect {
+            _ if !result.matched => Verdict::Allow,
+            Some(redirect) => match Surrogate::from_data_url(&redirect) {
+                Some(surrogate) => Verdict::Redirect(Rc::new(surrogate)),
+                None => Verdict::Block,
+            },
+            None => Verdict::Block,
+        };
+        self.cache.borrow_mut().insert(key, verdict.clone());
+        (verdict, false)
+    }
+
+    /// `true` si une exception `$document` (`@@||example.com^$document`)
+    /// couvre la page `url` : rien n'y est filtré, ni requêtes ni éléments.
+    ///
+    /// Le moteur ne cherche les exceptions que pour une requête déjà
+    /// bloquée : la vérification de chaque requête ne les voit donc pas,
+    /// d'où cette question posée une fois par navigation.
+    pub fn is_document_excepted(&self, url: &str) -> bool {
+        let Ok(request) = adblock::request::Request::new(url, url, "document") else {
+            return false;
+        };
+        self.engine
+            .check_network_request_subset(&request, false, true)
+            .exception
+            .is_some()
     }
 
     /// Clears the result cache. Call on navigation to avoid unbounded growth.
     pub fn clear_cache(&self) {
         self.cache.borrow_mut().clear();
     }
+
+    /// Durée des vérifications depuis la construction du moteur.
+    pub fn match_cost(&self) -> MatchCost {
+        self.match_cost.borrow().clone()
+    }
+
+    /// Journalise (`debug`) le coût du filtrage depuis la construction du
+    /// moteur. Appelé à chaque navigation et par [`Self::check_request`]
+    /// passé [`MATCH_COST_LOG_INTERVAL`].
+    pub fn log_match_cost(&self) {
+        self.match_cost_logged.set(Instant::now());
+        let cost = self.match_cost.borrow();
+        if cost.hits.count() == 0 && cost.misses.count() == 0 {
+            return;
+        }
+        let summary = |timings: &Timings| {
+            format!(
+                "{} in {:?} (max {:?}, p95 ≤ {:?})",
+                timings.count(),
+                timings.total(),
+                timings.max(),
+                timings.p95()
+            )
+        };
+        debug!(
+            total = ?cost.total(),
+            engine = %summary(&cost.misses),
+            cache = %summary(&cost.hits),
+            "Adblock matching cost"
+        );
+    }
+
+    /// Feuille de style masquant les éléments publicitaires de la page `url` :
+    /// sélecteurs propres au site, génériques complexes, et génériques
+    /// `.classe` / `#id` présents dans `page`. Vide si aucune règle ne
+    /// s'applique. `$generichide` et les exceptions `#@#` sont respectés.
+    pub fn hiding_css(&self, url: &str, page: &PageClassIds) -> String {
+        let resources = self.engine.url_cosmetic_resources(url);
+        let mut selectors: Vec<String> = resources.hide_selectors.into_iter().collect();
+        if !resources.generichide {
+            selectors.extend(self.engine.hidden_class_id_selectors(
+                &page.classes,
+                &page.ids,
+                &resources.exceptions,
+            ));
+        }
+        hiding_stylesheet(selectors)
+    }
+}
+
+/// Script qui relève les classes et ids du document, renvoyés en JSON
+/// (`{"classes": [...], "ids": [...]}`, voir [`PageClassIds`]).
+pub const COLLECT_CLASS_ID_SCRIPT: &str = r#"(function () {
+    const classes = new Set();
+    const ids = new Set();
+    for (const el of document.querySelectorAll("[class], [id]")) {
+        for (const name of el.classList) classes.add(name);
+        if (el.id) ids.add(el.id);
+    }
+    return JSON.stringify({ classes: [...classes], ids: [...ids] });
+})()"#;
+
+/// Classes et ids d'un document, relevés par [`COLLECT_CLASS_ID_SCRIPT`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct PageClassIds {
+    pub classes: Vec<String>,
+    pub ids: Vec<String>,
+}
+
+impl PageClassIds {
+    /// Lit le JSON renvoyé par [`COLLECT_CLASS_ID_SCRIPT`].
+    pub fn parse(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+}
+
+/// Une règle par sélecteur : un sélecteur que le moteur CSS ne comprend pas
+/// (`:has-text()`…) n'invalide que la sienne, pas toute la liste.
+fn hiding_stylesheet(mut selectors: Vec<String>) -> String {
+    selectors.sort();
+    selectors.dedup();
+    selectors
+        .iter()
+        .filter(|selector| !selector.contains(['{', '}']))
+        .map(|selector| format!("{selector} {{ display: none !important; }}\n"))
+        .collect()
+}
+
+/// Script qui applique la feuille de style `css` au document, dans un
+/// élément `<style>` remplacé à chaque appel.
+pub fn inject_style_script(css: &str) -> String {
+    let css = js_string(css);
+    format!(
+        r#"(function () {{
+    let style = document.getElementById("suribrows-cosmetic");
+    if (!style) {{
+        style = document.createElement("style");
+        style.id = "suribrows-cosmetic";
+        (document.head || document.documentElement).appendChild(style);
+    }}
+    style.textContent = {css};
+}})()"#
+    )
+}
+
+/// Cache du moteur compilé, dans [`config::cache_dir`].
+const ENGINE_CACHE_FILE: &str = "engine.dat";
+
+/// En-tête de `engine.dat`, suivi de la clé des listes (SHA-256) puis du
+/// moteur sérialisé par `adblock`.
+const ENGINE_CACHE_MAGIC: &[u8] = b"SURIBROWS-ADBLOCK-1\n";
+
+/// Origine du moteur retourné par [`load_engine`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineSource {
+    /// Relu depuis `engine.dat`.
+    Cache,
+    /// Compilé depuis les listes (cache absent, périmé ou illisible).
+    Lists,
+}
+
+/// Ressources de substitution de secours des règles `$redirect` (format
+/// JSON des ressources de Brave : nom, alias, type MIME, contenu en base64),
+/// dans le dossier des ressources.
+const SURROGATES_FILE: &str = "adblock-resources.json";
+
+/// Copie des ressources de uBlock Origin dans le dossier des ressources :
+/// `web_accessible_resources/` et `redirect-resources.js`, tels que dans
+/// `src/` de son dépôt.
+const UBLOCK_RESOURCES_DIR: &str = "ublock";
+
+/// Ressources de substitution de `resources_dir` : celles de uBlock Origin
+/// si elles y sont copiées, sinon [`SURROGATES_FILE`]. Sans aucune, une
+/// règle `$redirect` bloque seulement.
+fn load_surrogates(resources_dir: &Path) -> Vec<Resource> {
+    let ublock = resources_dir.join(UBLOCK_RESOURCES_DIR);
+    let war_dir = ublock.join("web_accessible_resources");
+    let redirects = ublock.join("redirect-resources.js");
+    if war_dir.is_dir() && redirects.is_file() {
+        let resources = assemble_web_accessible_resources(&war_dir, &redirects);
+        if !resources.is_empty() {
+            return resources;
+        }
+        warn!(path = %ublock.display(), "No uBlock Origin redirect resources found, using the bundled set");
+    }
+    let path = resources_dir.join(SURROGATES_FILE);
+    let parsed = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+    match parsed {
+        Ok(resources) => resources,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Cannot load adblock redirect resources");
+            Vec::new()
+        }
+    }
+}
+
+/// Règles personnelles de l'utilisateur, dans [`config::data_dir`].
+pub const USER_FILTERS_FILE: &str = "user-filters.txt";
+
+/// Moteur des listes `lists` puis des règles `user_filters` : relu depuis
+/// `cache_path` si le cache a été écrit pour ces fichiers (chemin, date de
+/// modification, taille), sinon compilé puis mis en cache. Un cache corrompu
+/// est ignoré et réécrit.
+pub fn load_engine(
+    lists: &[PathBuf],
+    user_filters: Option<&Path>,
+    cache_path: &Path,
+) -> (Engine, EngineSource) {
+    let key = lists_key(lists, user_filters);
+    if let Some(engine) = read_engine_cache(cache_path, &key) {
+        return (engine, EngineSource::Cache);
+    }
+    let engine = build_engine(lists, user_filters);
+    match engine.serialize() {
+        Ok(blob) => {
+            let mut data = Vec::with_capacity(ENGINE_CACHE_MAGIC.len() + key.len() + blob.len());
+            data.extend_from_slice(ENGINE_CACHE_MAGIC);
+            data.extend_from_slice(&key);
+            data.extend_from_slice(&blob);
+            if let Err(e) = config::write_atomic(cache_path, &data) {
+                warn!(path = %cache_path.display(), error = %e, "Cannot write adblock engine cache");
+            }
+        }
+        Err(e) => warn!(error = ?e, "Cannot serialize adblock engine"),
+    }
+    (engine, EngineSource::Lists)
+}
+
+/// Compile les listes `lists`, puis les règles `user_filters`.
+fn build_engine(lists: &[PathBuf], user_filters: Option<&Path>) -> Engine {
+    let mut filter_set = FilterSet::new(false);
+    for path in lists {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                let line_count = content.lines().count();
+                if is_hosts_format(path, &content) {
+                    filter_set.add_filter_list(&hosts_to_rules(&content), ParseOptions::default());
+                } else {
+                    filter_set.add_filter_list(&content, ParseOptions::default());
+                }
+                info!(
+                    "Liste de filtres chargée : {} ({} lignes)",
+                    path.display(),
+                    line_count
+                );
+            }
+            Err(e) => {
+                warn!("Impossible de lire {} : {}", path.display(), e);
+            }
+        }
+    }
+    if let Some(path) = user_filters {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                for line in add_user_filters(&mut filter_set, &content) {
+                    warn!(path = %path.display(), line, "Invalid user filter rule, skipped");
+                }
+            }
+            Err(e) => warn!(path = %path.display(), error = %e, "Cannot read user filters"),
+        }
+    }
+    Engine::from_filter_set(filter_set, true)
+}
+
+/// Noms d'hôtes locaux des fichiers hosts, jamais convertis en règles.
+const LOCAL_HOST_NAMES: &[&str] = &[
+    "localhost",
+    "localhost.localdomain",
+    "local",
+    "broadcasthost",
+    "ip6-localhost",
+    "ip6-loopback",
+    "ip6-localnet",
+    "ip6-mcastprefix",
+    "ip6-allnodes",
+    "ip6-allrouters",
+    "ip6-allhosts",
+    "0.0.0.0",
+];
+
+/// Nombre de lignes examinées pour reconnaître un fichier hosts.
+const HOSTS_SNIFF_LINES: usize = 20;
+
+/// `true` si la liste `path` est au format hosts (`0.0.0.0 ads.example.com`) :
+/// extension `.hosts`, ou premières lignes utiles toutes de cette forme.
+fn is_hosts_format(path: &Path, content: &str) -> bool {
+    if path.extension().is_some_and(|ext| ext == "hosts") {
+        return true;
+    }
+    let mut entries = content
+        .lines()
+        .map(|line| line.trim())
+        // Commentaires hosts (`#`) et ABP (`!`, en-tête `[Adblock Plus]`)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '!', '[']))
+        .take(HOSTS_SNIFF_LINES)
+        .peekable();
+    entries.peek().is_some() && entries.all(|line| hosts_entry(line).is_some())
+}
+
+/// Adresse et noms d'une ligne hosts (sans son commentaire), `None` si ce
+/// n'en est pas une.
+fn hosts_entry(line: &str) -> Option<Vec<&str>> {
+    let line = line.split('#').next().unwrap_or_default();
+    let mut tokens = line.split_whitespace();
+    tokens.next()?.parse::<std::net::IpAddr>().ok()?;
+    let names: Vec<&str> = tokens.collect();
+    (!names.is_empty()).then_some(names)
+}
+
+/// Règles `||domaine^` équivalentes à la liste hosts `content`. Commentaires,
+/// noms locaux (`localhost`, `broadcasthost`…) et noms invalides sont ignorés.
+fn hosts_to_rules(content: &str) -> String {
+    let mut rules = String::new();
+    for name in content.lines().filter_map(hosts_entry).flatten() {
+        let name = name.trim_end_matches('.').to_ascii_lowercase();
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+        if valid && !LOCAL_HOST_NAMES.contains(&name.as_str()) {
+            rules.push_str("||");
+            rules.push_str(&name);
+            rules.push_str("^\n");
+        }
+    }
+    rules
+}
+
+/// Ajoute les règles de `content` une par une : contrairement aux listes
+/// distribuées, une règle illisible est signalée. Retourne les numéros
+/// (à partir de 1) des lignes ignorées.
+fn add_user_filters(filter_set: &mut FilterSet, content: &str) -> Vec<usize> {
+    let mut skipped = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let rule = line.trim();
+        // Ligne vide, commentaire, en-tête `[Adblock Plus 2.0]`
+        if rule.is_empty() || rule.starts_with('!') || rule.starts_with('[') {
+            continue;
+        }
+        if filter_set
+            .add_filter(rule, ParseOptions::default())
+            .is_err()
+        {
+            skipped.push(index + 1);
+        }
+    }
+    skipped
+}
+
+/// Moteur de `engine.dat` s'il a été écrit pour la clé `key`.
+fn read_engine_cache(path: &Path, key: &[u8]) -> Option<Engine> {
+    let data = fs::read(path).ok()?;
+    let blob = data.strip_prefix(ENGINE_CACHE_MAGIC)?.strip_prefix(key)?;
+    let mut engine = Engine::new(true);
+    match engine.deserialize(blob) {
+        Ok(()) => Some(engine),
+        Err(e) => {
+            warn!(error = ?e, "Corrupted adblock engine cache, rebuilding");
+            None
+        }
+    }
+}
+
+/// Clé des listes : SHA-256 de la version de SuriBrows (le format sérialisé
+/// suit la version d'`adblock`) et, pour chaque fichier, de son chemin, sa
+/// date de modification et sa taille.
+fn lists_key(lists: &[PathBuf], user_filters: Option<&Path>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update([u8::from(user_filters.is_some())]);
+    for path in lists.iter().map(PathBuf::as_path).chain(user_filters) {
+        let metadata = fs::metadata(path).ok();
+        let modified = metadata
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos());
+        let size = metadata.map_or(0, |m| m.len());
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(modified.to_le_bytes());
+        hasher.update(size.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Fichier d'état des mises à jour, dans le dossier des filtres (pas en
+/// `.txt` : il n'est pas chargé comme une liste).
+const UPDATE_STATE_FILE: &str = "updates.json";
+
+/// Période de réveil du thread de mise à jour. Chaque liste n'est
+/// retéléchargée qu'une fois son délai écoulé ; une liste en échec est
+/// retentée au réveil suivant.
+const UPDATE_POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Taille maximale d'une liste téléchargée.
+const MAX_LIST_BYTES: usize = 32 * 1024 * 1024;
+
+/// Nombre maximal de redirections suivies pour une liste.
+const MAX_LIST_REDIRECTS: usize = 5;
+
+/// État de mise à jour d'une liste.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListUpdate {
+    /// `ETag` de la dernière version téléchargée.
+    pub etag: Option<String>,
+    /// `Last-Modified` de la dernière version téléchargée.
+    pub last_modified: Option<String>,
+    /// Dernière vérification réussie (secondes Unix).
+    pub checked_at: u64,
+}
+
+/// Contenu de `updates.json` : état de chaque liste, par URL.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateState {
+    pub lists: BTreeMap<String, ListUpdate>,
+}
+
+impl UpdateState {
+    /// Lit l'état ; absent ou illisible, toutes les listes sont à vérifier.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        config::write_atomic(path, &json)
+    }
+
+    /// `true` si la liste `url` n'a pas été vérifiée depuis `interval`.
+    pub fn is_due(&self, url: &str, now: u64, interval: Duration) -> bool {
+        self.lists
+            .get(url)
+            .is_none_or(|list| now.saturating_sub(list.checked_at) >= interval.as_secs())
+    }
+}
+
+/// Fichiers des listes activées de `subscriptions` présents dans `dir`,
+/// triés et sans doublon.
+fn enabled_list_paths(dir: &Path, subscriptions: &[FilterListConfig]) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = subscriptions
+        .iter()
+        .filter(|list| list.enabled)
+        .filter_map(|list| {
+            let path = dir.join(list_file(list)?);
+            if path.is_file() {
+                Some(path)
+            } else {
+                info!(list = %list.name, "Filter list not downloaded yet");
+                None
+            }
+        })
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Chemin local de `list` dans `resources/filters/`, `None` sans dossier
+/// des filtres ou sans nom de fichier valide.
+pub fn list_path(list: &FilterListConfig) -> Option<PathBuf> {
+    Some(find_filters_dir()?.join(list_file(list)?))
+}
+
+/// Nom du fichier local de `list` : `file` s'il est donné (un simple nom de
+/// fichier, sans chemin), sinon déduit de l'URL.
+pub fn list_file(list: &FilterListConfig) -> Option<String> {
+    match &list.file {
+        Some(file) => {
+            let plain = !file.is_empty()
+                && !file.contains(['/', '\\'])
+                && file != "."
+                && file != ".."
+                && file != UPDATE_STATE_FILE;
+            if !plain {
+                warn!(list = %list.name, file, "Invalid filter list file name");
+            }
+            plain.then(|| file.clone())
+        }
+        None => list_file_name(&Url::parse(&list.url).ok()?),
+    }
+}
+
+/// Nom du fichier local de la liste `url` : dernier segment du chemin,
+/// réduit à `[A-Za-z0-9._-]`, avec l'extension `.txt` (`.hosts` conservée).
+pub fn list_file_name(url: &Url) -> Option<String> {
+    let segment = url.path_segments()?.next_back()?;
+    let name: String = segment
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        return None;
+    }
+    Some(if name.ends_with(".txt") || name.ends_with(".hosts") {
+        name.to_string()
+    } else {
+        format!("{name}.txt")
+    })
+}
+
+/// `true` si `body` ressemble à une liste de filtres : texte UTF-8 non
+/// vide, pas une page HTML (portail captif, page d'erreur servie en 200).
+pub fn is_plausible_list(body: &[u8]) -> bool {
+    std::str::from_utf8(body).is_ok_and(|text| {
+        let text = text.trim_start();
+        !text.is_empty() && !text.starts_with('<')
+    })
+}
+
+/// Lance le thread de mise à jour des listes activées de `subscriptions`
+/// (celles qui ont une URL), vérifiées toutes les `hours` heures. Une liste
+/// absente du dossier est téléchargée tout de suite, même avec `hours` à 0
+/// (aucune vérification ensuite). `on_updated` reçoit les listes réécrites
+/// après chaque passe qui en a écrit au moins une. Le thread s'arrête quand
+/// il retourne `false` (boucle d'événements fermée).
+///
+/// Un seul thread met à jour toutes les listes (filtres et liste de
+/// sécurité) : il est le seul à lire et réécrire `updates.json`. La
+/// [`FilterUpdater`] retournée lui demande une vérification immédiate ; le
+/// thread s'arrête aussi quand elle est détruite.
+pub fn spawn_filter_updater(
+    subscriptions: Vec<FilterListConfig>,
+    hours: u32,
+    on_updated: impl Fn(&[FilterListConfig]) -> bool + Send + 'static,
+) -> FilterUpdater {
+    let lists = updatable(subscriptions);
+    let Some(dir) = find_filters_dir() else {
+        return FilterUpdater::default();
+    };
+    let interval = (hours > 0).then(|| Duration::from_secs(u64::from(hours) * 3600));
+    let (sender, receiver) = mpsc::channel();
+    spawn_update_thread(dir, lists, interval, receiver, on_updated);
+    FilterUpdater {
+        sender: Some(sender),
+    }
+}
+
+/// Listes de `subscriptions` que le thread met à jour : activées, avec une
+/// URL.
+fn updatable(subscriptions: Vec<FilterListConfig>) -> Vec<FilterListConfig> {
+    subscriptions
+        .into_iter()
+        .filter(|list| list.enabled && !list.url.is_empty())
+        .collect()
+}
+
+/// Demande adressée au thread de mise à jour.
+#[derive(Debug, PartialEq, Eq)]
+enum UpdaterMessage {
+    /// Vérifier toutes les listes tout de suite, délai écoulé ou non.
+    CheckNow,
+    /// Nouvel ensemble de listes (liste activée dans les paramètres) : les
+    /// listes absentes sont téléchargées tout de suite.
+    Subscriptions(Vec<FilterListConfig>),
+}
+
+/// Poignée du thread de mise à jour des listes. Vide si le thread n'a pas
+/// pu démarrer (pas de dossier des filtres).
+#[derive(Debug, Default)]
+pub struct FilterUpdater {
+    sender: Option<mpsc::Sender<UpdaterMessage>>,
+}
+
+impl FilterUpdater {
+    /// Vérifie tout de suite les listes (bouton « Update lists now » de
+    /// [`status_html`]). Retourne `false` si le thread ne tourne pas.
+    pub fn check_now(&self) -> bool {
+        self.send(UpdaterMessage::CheckNow)
+    }
+
+    /// Remplace les listes mises à jour par `subscriptions` (filtres et
+    /// liste de sécurité). Retourne `false` si le thread ne tourne pas.
+    pub fn set_subscriptions(&self, subscriptions: Vec<FilterListConfig>) -> bool {
+        self.send(UpdaterMessage::Subscriptions(subscriptions))
+    }
+
+    fn send(&self, message: UpdaterMessage) -> bool {
+        self.sender
+            .as_ref()
+            .is_some_and(|sender| sender.send(message).is_ok())
+    }
+}
+
+/// Délai de la passe suivant `message` : `Some(interval)` ou zéro pour une
+/// vérification demandée, `None` quand la poignée est détruite. Un nouvel
+/// ensemble de listes remplace `lists`.
+fn next_pass(
+    message: Result<UpdaterMessage, mpsc::RecvTimeoutError>,
+    interval: Option<Duration>,
+    lists: &mut Vec<FilterListConfig>,
+) -> Option<Option<Duration>> {
+    match message {
+        Ok(UpdaterMessage::CheckNow) => Some(Some(Duration::ZERO)),
+        Ok(UpdaterMessage::Subscriptions(subscriptions)) => {
+            *lists = updatable(subscriptions);
+            Some(interval)
+        }
+        Err(mpsc::RecvTimeoutError::Timeout) => Some(interval),
+        Err(mpsc::RecvTimeoutError::Disconnected) => None,
+    }
+}
+
+/// Thread de mise à jour : une passe sur `lists` au démarrage, puis toutes
+/// les [`UPDATE_POLL_INTERVAL`] (sans `interval`, seulement sur demande).
+fn spawn_update_thread(
+    dir: PathBuf,
+    mut lists: Vec<FilterListConfig>,
+    interval: Option<Duration>,
+    receiver: mpsc::Receiver<UpdaterMessage>,
+    on_updated: impl Fn(&[FilterListConfig]) -> bool + Send + 'static,
+) {
+    let spawned = std::thread::Builder::new()
+        .name("filter-updater".to_string())
+        .spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    warn!(error = %e, "Cannot start filter list updater");
+                    return;
+                }
+            };
+            let mut pass_interval = interval;
+            loop {
+                let updated = runtime.block_on(update_lists(&dir, &lists, pass_interval));
+                if !updated.is_empty() && !on_updated(&updated) {
+                    return;
+                }
+                let message = match interval {
+                    Some(_) => receiver.recv_timeout(UPDATE_POLL_INTERVAL),
+                    None => receiver
+                        .recv()
+                        .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+                };
+                match next_pass(message, interval, &mut lists) {
+                    Some(next) => pass_interval = next,
+                    None => return,
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        warn!(error = %e, "Cannot start filter list updater thread");
+    }
+}
+
+/// Télécharge les listes absentes de `dir` et vérifie celles dont le délai
+/// `interval` est écoulé. Retourne les listes réécrites.
+async fn update_lists(
+    dir: &Path,
+    lists: &[FilterListConfig],
+    interval: Option<Duration>,
+) -> Vec<FilterListConfig> {
+    let client = http::client();
+    let state_path = dir.join(UPDATE_STATE_FILE);
+    let mut state = UpdateState::load(&state_path);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut updated = Vec::new();
+    for list in lists {
+        let url = list.url.as_str();
+        let Some((parsed, name)) = Url::parse(url).ok().and_then(|parsed| {
+            let name = list_file(list)?;
+            Some((parsed, name))
+        }) else {
+            warn!(url, "Invalid filter list URL");
+            continue;
+        };
+        let missing = !dir.join(&name).is_file();
+        if !missing && !interval.is_some_and(|interval| state.is_due(url, now, interval)) {
+            continue;
+        }
+        // Fichier absent : pas de requête conditionnelle, il faut le corps
+        let known = if missing {
+            ListUpdate::default()
+        } else {
+            state.lists.get(url).cloned().unwrap_or_default()
+        };
+        let entry = match fetch_list(&client, parsed, &known).await {
+            Ok(None) => {
+                info!(url, "Filter list up to date");
+                ListUpdate {
+                    checked_at: now,
+                    ..known
+                }
+            }
+            Ok(Some(fetched)) if !is_plausible_list(&fetched.body) => {
+                warn!(
+                    url,
+                    "Downloaded file is not a filter list, keeping the old one"
+                );
+                continue;
+            }
+            Ok(Some(fetched)) => {
+                if let Err(e) = config::write_atomic(&dir.join(&name), &fetched.body) {
+                    warn!(url, error = %e, "Cannot write filter list, keeping the old one");
+                    continue;
+                }
+                info!(url, bytes = fetched.body.len(), "Filter list updated");
+                updated.push(list.clone());
+                ListUpdate {
+                    etag: fetched.etag,
+                    last_modified: fetched.last_modified,
+                    checked_at: now,
+                }
+            }
+            Err(e) => {
+                warn!(url, error = %e, "Filter list update failed, keeping the old one");
+                continue;
+            }
+        };
+        state.lists.insert(url.to_string(), entry);
+    }
+    if let Err(e) = state.save(&state_path) {
+        warn!(error = %e, "Cannot save filter list update state");
+    }
+    updated
+}
+
+/// Nouvelle version d'une liste.
+struct FetchedList {
+    body: Vec<u8>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Requête conditionnelle de la liste `url` (redirections comprises).
+/// `None` si elle n'a pas changé depuis `known` (304).
+async fn fetch_list(
+    client: &http::Client,
+    mut url: Url,
+    known: &ListUpdate,
+) -> Result<Option<FetchedList>, String> {
+    use http_body_util::{BodyExt, Empty};
+    use hyper::StatusCode;
+    use hyper::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LOCATION};
+
+    for _ in 0..=MAX_LIST_REDIRECTS {
+        let mut request = hyper::Request::get(url.as_str());
+        if let Some(etag) = &known.etag {
+            request = request.header(IF_NONE_MATCH, etag.as_str());
+        }
+        if let Some(date) = &known.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, date.as_str());
+        }
+        let request = request.body(Empty::new()).map_err(|e| e.to_string())?;
+        let response = client.request(request).await.map_err(|e| e.to_string())?;
+        let status = response.status();
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if status.is_redirection()
+            && let Some(location) = header(LOCATION)
+        {
+            url = url.join(&location).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if !status.is_success() {
+            return Err(format!("HTTP {status}"));
+        }
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+
+        let mut body = response.into_body();
+        let mut bytes = Vec::new();
+        while let Some(frame) = body.frame().await {
+            let frame = frame.map_err(|e| e.to_string())?;
+            if let Some(chunk) = frame.data_ref() {
+                if bytes.len() + chunk.len() > MAX_LIST_BYTES {
+                    return Err("filter list too large".to_string());
+                }
+                bytes.extend_from_slice(chunk);
+            }
+        }
+        return Ok(Some(FetchedList {
+            body: bytes,
+            etag,
+            last_modified,
+        }));
+    }
+    Err("too many redirects".to_string())
 }
 
 /// Cherche le dossier `resources/filters/` selon la même logique que `resources.rs`.
@@ -157,23 +1307,288 @@ fn find_filters_dir() -> Option<PathBuf> {
         }
     }
 
-    // 3. Répertoire courant
-    if let Ok(cwd) = std::env::current_dir() {
-        let mut path = cwd;
-        path.push("resources");
-        path.push("filters");
-        if path.is_dir() {
-            return Some(path);
+    // 3. Répertoire courant
+    if let Ok(cwd) = std::env::current_dir() {
+        let mut path = cwd;
+        path.push("resources");
+        path.push("filters");
+        if path.is_dir() {
+            return Some(path);
+        }
+    }
+
+    warn!("Dossier resources/filters/ introuvable. Ad-blocking désactivé.");
+    None
+}
+
+/// Domaine des boutons de la page d'état (interceptés dans
+/// `load_web_resource`, comme l'enregistrement des paramètres).
+const STATUS_DOMAIN: &str = "suribrows.adblock";
+
+/// Nombre d'hôtes affichés dans le classement des requêtes bloquées.
+const STATUS_TOP_HOSTS: usize = 10;
+
+/// Bouton de la page d'état de l'adblock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusAction {
+    /// Vérifier tout de suite les listes activées.
+    UpdateLists,
+    /// Reconstruire le moteur depuis les fichiers des listes.
+    RebuildEngine,
+}
+
+impl StatusAction {
+    /// Bouton dont `url` est le lien, `None` pour toute autre URL.
+    pub fn from_url(url: &Url) -> Option<Self> {
+        if !matches!(url.scheme(), "http" | "https") || url.host_str() != Some(STATUS_DOMAIN) {
+            return None;
+        }
+        match url.path() {
+            "/update-lists" => Some(Self::UpdateLists),
+            "/rebuild" => Some(Self::RebuildEngine),
+            _ => None,
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            Self::UpdateLists => "/update-lists",
+            Self::RebuildEngine => "/rebuild",
+        }
+    }
+}
+
+/// Liste de filtres telle qu'affichée par la page d'état.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListStatus {
+    pub name: String,
+    pub enabled: bool,
+    /// Règles du fichier (lignes hors commentaires), `None` s'il n'est pas
+    /// téléchargé.
+    pub rules: Option<usize>,
+    /// Date de modification du fichier (secondes Unix).
+    pub modified: Option<u64>,
+    /// Dernière vérification de mise à jour réussie (secondes Unix).
+    pub checked: Option<u64>,
+}
+
+/// État des listes de `subscriptions` : fichiers dans `resources/filters/`
+/// et `updates.json`.
+pub fn list_statuses(subscriptions: &[FilterListConfig]) -> Vec<ListStatus> {
+    let updates = find_filters_dir()
+        .map(|dir| UpdateState::load(&dir.join(UPDATE_STATE_FILE)))
+        .unwrap_or_default();
+    subscriptions
+        .iter()
+        .map(|list| {
+            let path = list_path(list).filter(|path| path.is_file());
+            let content = path
+                .as_deref()
+                .and_then(|path| fs::read_to_string(path).ok());
+            ListStatus {
+                name: list.name.clone(),
+                enabled: list.enabled,
+                rules: content.as_deref().map(count_rules),
+                modified: path
+                    .and_then(|path| fs::metadata(path).ok())
+                    .and_then(|meta| meta.modified().ok())
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs()),
+                checked: updates
+                    .lists
+                    .get(&list.url)
+                    .map(|update| update.checked_at)
+                    .filter(|&checked| checked > 0),
+            }
+        })
+        .collect()
+}
+
+/// Règles d'une liste : lignes non vides hors commentaires (`!`, `[Adblock]`,
+/// `#` des fichiers hosts ; pas les règles cosmétiques `##`, `#@#`…).
+fn count_rules(content: &str) -> usize {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            let comment = line.starts_with(['!', '['])
+                || (line.starts_with('#') && !line[1..].starts_with(['#', '@', '?', '$', '%']));
+            !line.is_empty() && !comment
+        })
+        .count()
+}
+
+/// Sites où l'adblock ne bloque rien : hôtes des exceptions
+/// `@@||hôte^$document` de `user-filters.txt`, triés.
+pub fn allowlisted_sites(user_filters: &str) -> Vec<String> {
+    let mut sites: Vec<String> = user_filters
+        .lines()
+        .filter_map(|line| {
+            let (pattern, options) = line.trim().strip_prefix("@@||")?.split_once('$')?;
+            if !options.split(',').any(|option| option.trim() == "document") {
+                return None;
+            }
+            let host = pattern.split(['^', '/', '*', '|', ':']).next()?;
+            (!host.is_empty()).then(|| host.to_ascii_lowercase())
+        })
+        .collect();
+    sites.sort();
+    sites.dedup();
+    sites
+}
+
+/// Contenu de la page d'état de l'adblock (`suri://adblock`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdblockStatus {
+    /// Compteurs, `None` si le moteur n'est pas (encore) chargé.
+    pub stats: Option<BlockStats>,
+    /// Origine du moteur chargé.
+    pub source: Option<EngineSource>,
+    pub lists: Vec<ListStatus>,
+    pub allowlisted: Vec<String>,
+    /// Message affiché en tête après un bouton.
+    pub notice: Option<String>,
+}
+
+/// Page d'état : moteur, compteurs, listes, sites exemptés, et les boutons
+/// « Update lists now » et « Rebuild engine ». Les liens des boutons portent
+/// le jeton de session `token` (voir [`crate::token::has_token`]).
+pub fn status_html(status: &AdblockStatus, token: &str) -> String {
+    let notice = status
+        .notice
+        .as_deref()
+        .map(|notice| format!("<p class=\"notice\">{}</p>", html_escape(notice)))
+        .unwrap_or_default();
+    let engine = match (&status.stats, status.source) {
+        (None, _) => "Not loaded: no enabled filter list is available yet.",
+        (Some(_), Some(EngineSource::Cache)) => "Loaded from the engine cache (engine.dat).",
+        (Some(_), _) => "Compiled from the filter lists.",
+    };
+    let counters = match &status.stats {
+        None => String::new(),
+        Some(stats) => {
+            let mut hosts: Vec<(&String, &u64)> = stats.blocked_by_host.iter().collect();
+            hosts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+            let top: String = hosts
+                .into_iter()
+                .take(STATUS_TOP_HOSTS)
+                .map(|(host, count)| {
+                    format!("<tr><td>{}</td><td>{count}</td></tr>", html_escape(host))
+                })
+                .collect();
+            let top = if top.is_empty() {
+                String::new()
+            } else {
+                format!("<h2>Most blocked hosts</h2>\n<table>{top}</table>")
+            };
+            let cost_row = |name: &str, timings: &Timings| {
+                format!(
+                    "<tr><td>{name}</td><td>{count}</td><td>{total}</td><td>{mean}</td>\
+                     <td>{p95}</td><td>{max}</td></tr>",
+                    count = timings.count(),
+                    total = format_micros(timings.total()),
+                    mean = format_micros(timings.mean()),
+                    p95 = format_micros(timings.p95()),
+                    max = format_micros(timings.max()),
+                )
+            };
+            format!(
+                "<table>\
+                 <tr><td>Requests checked</td><td>{checked}</td></tr>\
+                 <tr><td>Requests blocked</td><td>{blocked}</td></tr>\
+                 <tr><td>Blocked on the current page</td><td>{page}</td></tr>\
+                 </table>\n{top}\n\
+                 <h2>Matching cost</h2>\n\
+                 <table>\
+                 <tr><th></th><th>Checks</th><th>Total</th><th>Mean</th><th>p95 (≤)</th><th>Max</th></tr>\
+                 {engine_row}{cache_row}\
+                 </table>",
+                checked = stats.checked,
+                blocked = stats.blocked,
+                page = stats.page_blocked,
+                engine_row = cost_row("Filter engine", &stats.match_cost.misses),
+                cache_row = cost_row("Verdict cache", &stats.match_cost.hits),
+            )
         }
-    }
+    };
+    let date = |secs: Option<u64>| {
+        secs.map_or_else(
+            || "—".to_string(),
+            |secs| block_log::utc_timestamp(UNIX_EPOCH + Duration::from_secs(secs)),
+        )
+    };
+    let lists: String = status
+        .lists
+        .iter()
+        .map(|list| {
+            format!(
+                "<tr><td>{name}{disabled}</td><td>{rules}</td><td>{modified}</td><td>{checked}</td></tr>",
+                name = html_escape(&list.name),
+                disabled = if list.enabled { "" } else { " (disabled)" },
+                rules = list
+                    .rules
+                    .map_or_else(|| "not downloaded".to_string(), |rules| rules.to_string()),
+                modified = date(list.modified),
+                checked = date(list.checked),
+            )
+        })
+        .collect();
+    let allowlisted = if status.allowlisted.is_empty() {
+        "<p>None. Add <code>@@||example.com^$document</code> to user-filters.txt to \
+         turn blocking off on a site.</p>"
+            .to_string()
+    } else {
+        let items: String = status
+            .allowlisted
+            .iter()
+            .map(|site| format!("<li>{}</li>", html_escape(site)))
+            .collect();
+        format!("<ul>{items}</ul>")
+    };
+    pages::shell(
+        "Ad Blocker Status",
+        "
+body { max-width: 800px; }
+h1 { color: #6a9eff; }
+table { border-collapse: collapse; width: 100%; font-size: 13px; }
+th, td { text-align: left; padding: 6px 8px; border-bottom: 1px solid #333; }
+th { color: #888; font-weight: normal; }
+li { font-family: monospace; }
+p { color: #888; }
+.notice { color: #6a9eff; }
+",
+        &format!(
+            r#"<h1>Ad blocker status</h1>
+{notice}
+<p>{engine}</p>
+{counters}
+<h2>Filter lists</h2>
+<table>
+<tr><th>List</th><th>Rules</th><th>File updated (UTC)</th><th>Last checked (UTC)</th></tr>
+{lists}
+</table>
+<h2>Allowlisted sites</h2>
+{allowlisted}
+<div class="actions">
+<button onclick="location.href='http://{STATUS_DOMAIN}{update}?token={token}'">Update lists now</button>
+<button onclick="location.href='http://{STATUS_DOMAIN}{rebuild}?token={token}'">Rebuild engine</button>
+</div>"#,
+            update = StatusAction::UpdateLists.path(),
+            rebuild = StatusAction::RebuildEngine.path(),
+            token = url_encode(token),
+        ),
+    )
+}
 
-    warn!("Dossier resources/filters/ introuvable. Ad-blocking désactivé.");
-    None
+/// Durée en microsecondes pour la page d'état (`12.3 µs`).
+fn format_micros(duration: Duration) -> String {
+    format!("{:.1} µs", duration.as_secs_f64() * 1_000_000.0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::TempDir;
 
     /// Builds an AdblockEngine from raw filter rules (bypasses filesystem).
     fn engine_from_rules(rules: &[&str]) -> AdblockEngine {
@@ -181,10 +1596,33 @@ mod tests {
         for rule in rules {
             filter_set.add_filter_list(rule, ParseOptions::default());
         }
-        AdblockEngine {
-            engine: Engine::from_filter_set(filter_set, true),
-            cache: RefCell::new(HashMap::new()),
-        }
+        AdblockEngine::with_engine(Engine::from_filter_set(filter_set, true))
+    }
+
+    #[test]
+    fn test_compiled_engine_moves_to_main_thread() {
+        let compiled = std::thread::spawn(|| {
+            let mut filter_set = FilterSet::new(false);
+            filter_set.add_filter_list("||ads.example.com^", ParseOptions::default());
+            CompiledEngine(
+                Engine::from_filter_set(filter_set, true),
+                EngineSource::Cache,
+            )
+        })
+        .join()
+        .unwrap();
+        let engine = AdblockEngine::from_compiled(compiled);
+        assert_eq!(engine.source(), EngineSource::Cache);
+        assert!(engine.should_block(
+            "https://ads.example.com/banner.js",
+            "https://example.com/",
+            "script"
+        ));
+        assert!(!engine.should_block(
+            "https://example.com/app.js",
+            "https://example.com/",
+            "script"
+        ));
     }
 
     #[test]
@@ -237,6 +1675,117 @@ mod tests {
         assert!(engine.cache.borrow().is_empty());
     }
 
+    #[test]
+    fn test_counters_count_checks_blocks_and_hosts() {
+        let counters = BlockCounters::default();
+        counters.record(1, "https://ads.example.com/a.js", true);
+        counters.record(1, "https://ads.example.com/a.js", true);
+        counters.record(1, "https://tracker.example.org/t.gif", true);
+        counters.record(1, "https://example.com/app.js", false);
+        counters.record(1, "not-a-valid-url-at-all", true);
+
+        let stats = counters.stats(Some(1), MatchCost::default());
+        assert_eq!(stats.checked, 5);
+        assert_eq!(stats.blocked, 4);
+        assert_eq!(stats.page_blocked, 4);
+        assert_eq!(
+            stats.blocked_by_host.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("ads.example.com".to_string(), 2),
+                ("tracker.example.org".to_string(), 1),
+            ]
+        );
+
+        // Navigation: only the per-page counter starts over
+        counters.reset_page(1);
+        counters.record(1, "https://ads.example.com/b.js", true);
+        let stats = counters.stats(Some(1), MatchCost::default());
+        assert_eq!(stats.checked, 6);
+        assert_eq!(stats.blocked, 5);
+        assert_eq!(stats.page_blocked, 1);
+        assert_eq!(stats.blocked_by_host["ads.example.com"], 3);
+    }
+
+    #[test]
+    fn test_counters_per_page() {
+        let counters = BlockCounters::default();
+        counters.record("tab-a", "https://ads.example/a.js", true);
+        counters.record("tab-a", "https://ads.example/b.js", true);
+        counters.record("tab-b", "https://ads.example/c.js", true);
+        assert_eq!(counters.page_blocked("tab-a"), 2);
+        assert_eq!(counters.page_blocked("tab-b"), 1);
+        assert_eq!(counters.page_blocked("tab-c"), 0);
+
+        // Une navigation dans un onglet ne touche pas aux autres
+        counters.reset_page("tab-b");
+        assert_eq!(counters.page_blocked("tab-a"), 2);
+        assert_eq!(counters.page_blocked("tab-b"), 0);
+        assert_eq!(counters.stats(None, MatchCost::default()).page_blocked, 0);
+    }
+
+    fn page(classes: &[&str], ids: &[&str]) -> PageClassIds {
+        PageClassIds {
+            classes: classes.iter().map(|c| c.to_string()).collect(),
+            ids: ids.iter().map(|i| i.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_hiding_css_generic_and_site_specific() {
+        let engine = engine_from_rules(&[
+            "##.ad-banner",
+            "###sponsored",
+            "##div[data-ad]",
+            "example.com##.promo-box",
+            "other.com##.other-promo",
+        ]);
+        let page = page(&["ad-banner", "content"], &["sponsored", "main"]);
+        assert_eq!(
+            engine.hiding_css("https://www.example.com/article", &page),
+            "#sponsored { display: none !important; }\n\
+             .ad-banner { display: none !important; }\n\
+             .promo-box { display: none !important; }\n\
+             div[data-ad] { display: none !important; }\n"
+        );
+        // Generic class/id rules only apply to what the page contains
+        assert_eq!(
+            engine.hiding_css("https://other.com/", &PageClassIds::default()),
+            ".other-promo { display: none !important; }\n\
+             div[data-ad] { display: none !important; }\n"
+        );
+    }
+
+    #[test]
+    fn test_hiding_css_honours_exceptions() {
+        let engine = engine_from_rules(&[
+            "##.ad-banner",
+            "##.sidebar-ad",
+            "news.example.com#@#.ad-banner",
+        ]);
+        let page = page(&["ad-banner", "sidebar-ad"], &[]);
+        assert_eq!(
+            engine.hiding_css("https://news.example.com/", &page),
+            ".sidebar-ad { display: none !important; }\n"
+        );
+        let engine = engine_from_rules(&["||ads.example.com^"]);
+        assert_eq!(engine.hiding_css("https://example.com/", &page), "");
+    }
+
+    #[test]
+    fn test_class_ids_and_style_script() {
+        let parsed = PageClassIds::parse(r#"{"classes":["ad"],"ids":["top"]}"#).unwrap();
+        assert_eq!(parsed, page(&["ad"], &["top"]));
+        assert_eq!(PageClassIds::parse("{}"), Some(PageClassIds::default()));
+        assert_eq!(PageClassIds::parse("not json"), None);
+
+        let script = inject_style_script("a[title=\"x\"] { display: none !important; }\n");
+        assert!(
+            script.contains(
+                r#"style.textContent = "a[title=\"x\"] { display: none !important; }\n";"#
+            )
+        );
+    }
+
     #[test]
     fn test_malformed_url_not_blocked() {
         let engine = engine_from_rules(&["||ads.example.com^"]);
@@ -299,11 +1848,147 @@ mod tests {
         assert_eq!(engine.cache.borrow().len(), 2);
     }
 
+    #[test]
+    fn test_request_types_cached_separately() {
+        let engine = engine_from_rules(&["||ads.example.com^$image"]);
+        assert!(engine.should_block(
+            "https://ads.example.com/pixel",
+            "https://example.com",
+            "image",
+        ));
+        assert!(!engine.should_block(
+            "https://ads.example.com/pixel",
+            "https://example.com",
+            "script",
+        ));
+    }
+
+    #[test]
+    fn test_script_scoped_rule_depends_on_type() {
+        let engine = engine_from_rules(&["||cdn.example.com^$script"]);
+        let url = "https://cdn.example.com/widget";
+        // Same URL: allowed as a document, blocked as a script
+        assert!(!engine.should_block(url, "https://example.com", "document"));
+        assert!(engine.should_block(url, "https://example.com", "script"));
+        assert!(!engine.should_block(url, "https://example.com", "xmlhttprequest"));
+        assert!(!engine.should_block(url, "https://example.com", "document"));
+        assert_eq!(engine.cache.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_websocket_and_ping_scoped_rules() {
+        use crate::blocking::{RequestHeaders, RequestType};
+
+        let engine =
+            engine_from_rules(&["||live.tracker.example^$websocket", "||audit.example^$ping"]);
+        let page = "https://news.example/article";
+        let classify = |url: &str, headers: RequestHeaders<'_>| {
+            RequestType::classify(&Url::parse(url).unwrap(), headers, false).as_adblock_str()
+        };
+
+        let socket = "wss://live.tracker.example/socket";
+        assert!(engine.should_block(socket, page, classify(socket, RequestHeaders::default())));
+        let handshake = RequestHeaders {
+            upgrade: Some("websocket"),
+            ..RequestHeaders::default()
+        };
+        let socket = "https://live.tracker.example/socket";
+        assert!(engine.should_block(socket, page, classify(socket, handshake)));
+        // Même hôte, simple `fetch()` : la règle `$websocket` ne s'applique pas
+        let fetch = RequestHeaders {
+            fetch_dest: Some("empty"),
+            ..RequestHeaders::default()
+        };
+        assert!(!engine.should_block(socket, page, classify(socket, fetch)));
+
+        let ping = RequestHeaders {
+            fetch_dest: Some("empty"),
+            content_type: Some("text/ping"),
+            ..RequestHeaders::default()
+        };
+        let audit = "https://audit.example/click?id=1";
+        assert!(engine.should_block(audit, page, classify(audit, ping)));
+        assert!(!engine.should_block(audit, page, classify(audit, fetch)));
+    }
+
+    #[test]
+    fn test_redirect_rule_serves_surrogate() {
+        let mut filter_set = FilterSet::new(false);
+        filter_set.add_filter_list(
+            "||ads.example.com^$script,redirect=noopjs\n||ads.example.com^\n",
+            ParseOptions::default(),
+        );
+        let mut engine = Engine::from_filter_set(filter_set, true);
+        let resources: Vec<Resource> =
+            serde_json::from_str(include_str!("../resources/adblock-resources.json")).unwrap();
+        engine.use_resources(resources);
+        let engine = AdblockEngine::with_engine(engine);
+
+        let verdict = engine.check_request(
+            "https://ads.example.com/tag.js",
+            "https://example.com",
+            "script",
+        );
+        let Verdict::Redirect(surrogate) = verdict else {
+            panic!("expected a redirect, got {verdict:?}");
+        };
+        assert_eq!(surrogate.content_type, "application/javascript");
+        assert_eq!(
+            String::from_utf8(surrogate.body.clone()).unwrap(),
+            "(function() {\n    'use strict';\n})();\n"
+        );
+        // Cached, and still a block
+        assert!(engine.should_block(
+            "https://ads.example.com/tag.js",
+            "https://example.com",
+            "script"
+        ));
+        assert_eq!(engine.match_cost().hits.count(), 1);
+        // Other types: plain block
+        assert_eq!(
+            engine.check_request(
+                "https://ads.example.com/pixel",
+                "https://example.com",
+                "image"
+            ),
+            Verdict::Block
+        );
+    }
+
+    #[test]
+    fn test_surrogates_fall_back_to_bundled_file() {
+        let dir = TempDir::new("surrogates");
+        assert!(load_surrogates(dir.path()).is_empty());
+
+        // Dossier uBlock Origin incomplet : le jeu fourni sert de secours
+        fs::create_dir_all(
+            dir.join(UBLOCK_RESOURCES_DIR)
+                .join("web_accessible_resources"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join(SURROGATES_FILE),
+            include_str!("../resources/adblock-resources.json"),
+        )
+        .unwrap();
+        let resources = load_surrogates(dir.path());
+        assert!(resources.iter().any(|r| r.name == "noopjs"));
+    }
+
+    #[test]
+    fn test_surrogate_from_data_url() {
+        let surrogate = Surrogate::from_data_url("data:text/css;base64,Ym9keXt9").unwrap();
+        assert_eq!(surrogate.content_type, "text/css");
+        assert_eq!(surrogate.body, b"body{}");
+        assert_eq!(Surrogate::from_data_url("data:text/css,body{}"), None);
+        assert_eq!(Surrogate::from_data_url("https://example.com/"), None);
+    }
+
     #[test]
     fn test_new_returns_some_when_filters_exist() {
         // This test requires running from project root where resources/filters/ exists
         if std::path::Path::new("resources/filters").is_dir() {
-            let engine = AdblockEngine::new();
+            let engine = AdblockEngine::new(&config::default_filter_lists());
             assert!(engine.is_some());
         }
     }
@@ -321,4 +2006,563 @@ mod tests {
         }
         assert_eq!(engine.cache.borrow().len(), 1);
     }
+
+    #[test]
+    fn test_list_file_name() {
+        let name = |url: &str| list_file_name(&Url::parse(url).unwrap());
+        assert_eq!(
+            name("https://easylist.to/easylist/easylist.txt").as_deref(),
+            Some("easylist.txt")
+        );
+        assert_eq!(
+            name("https://example.com/lists/my%20list?v=2").as_deref(),
+            Some("my20list.txt")
+        );
+        assert_eq!(
+            name("https://example.com/ads.hosts").as_deref(),
+            Some("ads.hosts")
+        );
+        assert_eq!(name("https://example.com/..").as_deref(), None);
+        assert_eq!(name("https://example.com/").as_deref(), None);
+    }
+
+    fn subscription(url: &str, enabled: bool, file: Option<&str>) -> FilterListConfig {
+        FilterListConfig {
+            name: "test".to_string(),
+            url: url.to_string(),
+            enabled,
+            file: file.map(str::to_string),
+            auto_enabled_for: None,
+        }
+    }
+
+    #[test]
+    fn test_list_file_prefers_plain_file_name() {
+        let url = "https://easylist.to/easylist/easylist.txt";
+        assert_eq!(
+            list_file(&subscription(url, true, None)).as_deref(),
+            Some("easylist.txt")
+        );
+        assert_eq!(
+            list_file(&subscription(url, true, Some("mine.txt"))).as_deref(),
+            Some("mine.txt")
+        );
+        assert_eq!(list_file(&subscription("", true, Some("../x.txt"))), None);
+        assert_eq!(
+            list_file(&subscription("", true, Some(UPDATE_STATE_FILE))),
+            None
+        );
+        assert_eq!(list_file(&subscription("", true, None)), None);
+    }
+
+    #[test]
+    fn test_only_enabled_present_lists_are_loaded() {
+        let temp = TempDir::new("lists");
+        let dir = temp.path();
+        for name in ["easylist.txt", "easyprivacy.txt", "stray.txt", "local.txt"] {
+            fs::write(dir.join(name), "||ads.example.com^\n").unwrap();
+        }
+        let subscriptions = [
+            subscription("https://easylist.to/easylist/easylist.txt", true, None),
+            subscription("https://easylist.to/easylist/easyprivacy.txt", false, None),
+            subscription("https://example.com/missing.txt", true, None),
+            subscription("", true, Some("local.txt")),
+        ];
+        let paths = enabled_list_paths(dir, &subscriptions);
+        assert_eq!(paths, [dir.join("easylist.txt"), dir.join("local.txt")]);
+    }
+
+    #[test]
+    fn test_updater_messages() {
+        assert!(!FilterUpdater::default().check_now());
+
+        let (sender, receiver) = mpsc::channel();
+        let updater = FilterUpdater {
+            sender: Some(sender),
+        };
+        assert!(updater.check_now());
+        let day = Some(Duration::from_secs(24 * 3600));
+        let mut lists = vec![subscription(
+            "https://easylist.to/easylist/easylist.txt",
+            true,
+            None,
+        )];
+        // Vérification demandée : toutes les listes, délai écoulé ou non
+        let message = receiver.recv_timeout(Duration::ZERO);
+        assert_eq!(
+            next_pass(message, day, &mut lists),
+            Some(Some(Duration::ZERO))
+        );
+        // Réveil périodique : seulement les listes dont le délai est écoulé
+        let message = receiver.recv_timeout(Duration::ZERO);
+        assert_eq!(next_pass(message, day, &mut lists), Some(day));
+        // Liste activée dans les paramètres : elle rejoint la mise à jour,
+        // les listes désactivées ou locales en sortent
+        let added = subscription("https://example.com/extra.txt", true, None);
+        assert!(updater.set_subscriptions(vec![
+            subscription("https://easylist.to/easylist/easylist.txt", false, None),
+            added.clone(),
+            subscription("", true, Some("local.txt")),
+        ]));
+        let message = receiver.recv_timeout(Duration::ZERO);
+        assert_eq!(next_pass(message, day, &mut lists), Some(day));
+        assert_eq!(lists, [added]);
+        // Poignée détruite : le thread s'arrête
+        drop(updater);
+        let message = receiver.recv_timeout(Duration::ZERO);
+        assert_eq!(next_pass(message, day, &mut lists), None);
+    }
+
+    #[test]
+    fn test_update_state_due_and_roundtrip() {
+        let dir = TempDir::new("filters");
+        let path = dir.join(UPDATE_STATE_FILE);
+        let url = "https://easylist.to/easylist/easylist.txt";
+        let day = Duration::from_secs(24 * 3600);
+
+        // Missing or corrupt state: every list is due
+        fs::write(&path, "not json").unwrap();
+        let mut state = UpdateState::load(&path);
+        assert!(state.is_due(url, 1_000_000, day));
+
+        state.lists.insert(
+            url.to_string(),
+            ListUpdate {
+                etag: Some("\"abc\"".to_string()),
+                last_modified: Some("Tue, 01 Sep 2026 10:00:00 GMT".to_string()),
+                checked_at: 1_000_000,
+            },
+        );
+        state.save(&path).unwrap();
+        let reloaded = UpdateState::load(&path);
+        assert_eq!(reloaded, state);
+        assert!(!reloaded.is_due(url, 1_000_000 + 3600, day));
+        assert!(reloaded.is_due(url, 1_000_000 + 24 * 3600, day));
+        // Seul `updates.json` reste : pas de fichier temporaire
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_plausible_list() {
+        assert!(is_plausible_list(
+            b"[Adblock Plus 2.0]\n||ads.example.com^\n"
+        ));
+        assert!(is_plausible_list(b"||ads.example.com^"));
+        assert!(!is_plausible_list(b""));
+        assert!(!is_plausible_list(
+            b"  \n<!DOCTYPE html><title>Login</title>"
+        ));
+        assert!(!is_plausible_list(&[0xFF, 0xFE, 0x00]));
+    }
+
+    fn engine_cache_dir() -> (TempDir, Vec<PathBuf>, PathBuf) {
+        let dir = TempDir::new("engine");
+        let list = dir.join("list.txt");
+        fs::write(&list, "||ads.example.com^\n").unwrap();
+        let cache = dir.join(ENGINE_CACHE_FILE);
+        (dir, vec![list], cache)
+    }
+
+    fn blocks_ads(engine: Engine) -> bool {
+        AdblockEngine::with_engine(engine).should_block(
+            "https://ads.example.com/banner.js",
+            "https://example.com",
+            "script",
+        )
+    }
+
+    #[test]
+    fn test_engine_cache_miss_then_hit() {
+        let (_dir, lists, cache) = engine_cache_dir();
+
+        let (engine, source) = load_engine(&lists, None, &cache);
+        assert_eq!(source, EngineSource::Lists);
+        assert!(blocks_ads(engine));
+        assert!(cache.exists());
+
+        let (engine, source) = load_engine(&lists, None, &cache);
+        assert_eq!(source, EngineSource::Cache);
+        assert!(blocks_ads(engine));
+
+        // Changed list (different size): the cache is stale
+        fs::write(&lists[0], "||ads.example.com^\n||tracker.example.com^\n").unwrap();
+        let (_, source) = load_engine(&lists, None, &cache);
+        assert_eq!(source, EngineSource::Lists);
+    }
+
+    #[test]
+    fn test_corrupted_engine_cache_is_rebuilt() {
+        let (_dir, lists, cache) = engine_cache_dir();
+        let mut data = ENGINE_CACHE_MAGIC.to_vec();
+        data.extend_from_slice(&lists_key(&lists, None));
+        data.extend_from_slice(b"garbage");
+        fs::write(&cache, &data).unwrap();
+
+        let (engine, source) = load_engine(&lists, None, &cache);
+        assert_eq!(source, EngineSource::Lists);
+        assert!(blocks_ads(engine));
+        // Rewritten: the next start reads it back
+        assert_ne!(fs::read(&cache).unwrap(), data);
+        assert_eq!(load_engine(&lists, None, &cache).1, EngineSource::Cache);
+    }
+
+    #[test]
+    fn test_user_filters_add_rules_and_exceptions() {
+        let (dir, lists, cache) = engine_cache_dir();
+        fs::write(&lists[0], "||ads.example.com^\n||cdn.example.com^\n").unwrap();
+        let user = dir.join(USER_FILTERS_FILE);
+        fs::write(
+            &user,
+            "! My rules\n||tracker.example.org^\n@@||cdn.example.com^\n",
+        )
+        .unwrap();
+
+        let (engine, _) = load_engine(&lists, Some(&user), &cache);
+        let engine = AdblockEngine::with_engine(engine);
+        let blocked = |url: &str| engine.should_block(url, "https://example.com", "script");
+        assert!(blocked("https://ads.example.com/a.js"));
+        // Custom block rule
+        assert!(blocked("https://tracker.example.org/t.js"));
+        // Custom exception wins over the bundled rule
+        assert!(!blocked("https://cdn.example.com/lib.js"));
+
+        // Editing the user rules invalidates the cache
+        assert_eq!(
+            load_engine(&lists, Some(&user), &cache).1,
+            EngineSource::Cache
+        );
+        fs::write(&user, "||tracker.example.org^\n").unwrap();
+        assert_eq!(
+            load_engine(&lists, Some(&user), &cache).1,
+            EngineSource::Lists
+        );
+    }
+
+    #[test]
+    fn test_hosts_to_rules() {
+        let hosts = "# Blocklist\n\
+                     127.0.0.1 localhost\n\
+                     ::1 localhost ip6-localhost ip6-loopback\n\
+                     255.255.255.255 broadcasthost\n\
+                     0.0.0.0 0.0.0.0\n\
+                     0.0.0.0 ads.example.com # inline comment\n\
+                     0.0.0.0\tTracker.Example.org.  metrics.example.net\n\
+                     :: ipv6-ads.example.com\n\
+                     0.0.0.0 bad/name\n\
+                     0.0.0.0\n";
+        assert_eq!(
+            hosts_to_rules(hosts),
+            "||ads.example.com^\n||tracker.example.org^\n||metrics.example.net^\n\
+             ||ipv6-ads.example.com^\n"
+        );
+    }
+
+    #[test]
+    fn test_hosts_format_detection() {
+        let hosts = "# Title\n\n0.0.0.0 ads.example.com\n:: ads.example.com\n";
+        assert!(is_hosts_format(Path::new("list.txt"), hosts));
+        assert!(is_hosts_format(Path::new("list.hosts"), ""));
+        assert!(!is_hosts_format(
+            Path::new("easylist.txt"),
+            "[Adblock Plus 2.0]\n! Title\n||ads.example.com^\n##.ad\n"
+        ));
+        assert!(!is_hosts_format(
+            Path::new("empty.txt"),
+            "# only comments\n"
+        ));
+    }
+
+    #[test]
+    fn test_hosts_and_abp_lists_load_together() {
+        let (dir, mut lists, cache) = engine_cache_dir();
+        let hosts = dir.join("trackers.hosts");
+        fs::write(&hosts, "0.0.0.0 tracker.example.org\n").unwrap();
+        lists.push(hosts);
+
+        let (engine, _) = load_engine(&lists, None, &cache);
+        let engine = AdblockEngine::with_engine(engine);
+        let blocked = |url: &str| engine.should_block(url, "https://example.com", "script");
+        assert!(blocked("https://ads.example.com/a.js"));
+        assert!(blocked("https://tracker.example.org/t.js"));
+        assert!(!blocked("https://example.com/app.js"));
+    }
+
+    #[test]
+    fn test_malformed_user_filters_are_skipped() {
+        let mut filter_set = FilterSet::new(false);
+        let skipped = add_user_filters(
+            &mut filter_set,
+            "[Adblock Plus 2.0]\n! comment\n\n||ads.example.com^\n\
+             ||ads.example.net^$bogus-option\n##.sponsored\n",
+        );
+        assert_eq!(skipped, [5]);
+        let engine = AdblockEngine::with_engine(Engine::from_filter_set(filter_set, true));
+        assert!(engine.should_block(
+            "https://ads.example.com/a.js",
+            "https://example.com",
+            "script"
+        ));
+        assert!(!engine.should_block(
+            "https://ads.example.net/a.js",
+            "https://example.com",
+            "script"
+        ));
+    }
+
+    #[test]
+    fn test_count_rules_skips_comments() {
+        let abp = "[Adblock Plus 2.0]\n! Title: Test\n\n||ads.example.com^\n##.ad\n\
+                   example.com#@#.banner\n#?#div:has(.ad)\n";
+        assert_eq!(count_rules(abp), 4);
+        let hosts = "# hosts file\n127.0.0.1 localhost\n0.0.0.0 ads.example.com\n";
+        assert_eq!(count_rules(hosts), 2);
+    }
+
+    #[test]
+    fn test_allowlisted_sites_from_document_exceptions() {
+        let user_filters = "||ads.example.com^\n@@||News.example^$document\n\
+                            @@||shop.example/*$image,document\n@@||cdn.example^\n\
+                            @@||news.example^$document\n";
+        assert_eq!(
+            allowlisted_sites(user_filters),
+            ["news.example", "shop.example"]
+        );
+    }
+
+    #[test]
+    fn test_status_action_urls() {
+        let url = |s: &str| Url::parse(s).unwrap();
+        assert_eq!(
+            StatusAction::from_url(&url("http://suribrows.adblock/update-lists")),
+            Some(StatusAction::UpdateLists)
+        );
+        assert_eq!(
+            StatusAction::from_url(&url("http://suribrows.adblock/rebuild")),
+            Some(StatusAction::RebuildEngine)
+        );
+        assert_eq!(
+            StatusAction::from_url(&url("http://suribrows.adblock/other")),
+            None
+        );
+        assert_eq!(
+            StatusAction::from_url(&url("https://example.com/rebuild")),
+            None
+        );
+    }
+
+    fn timings(micros: &[u64]) -> Timings {
+        let mut timings = Timings::default();
+        for &us in micros {
+            timings.record(Duration::from_micros(us));
+        }
+        timings
+    }
+
+    #[test]
+    fn test_match_cost_separates_cache_hits() {
+        let engine = engine_from_rules(&["||ads.example.com^"]);
+        engine.should_block("https://ads.example.com/a.js", "https://site.com", "script");
+        engine.should_block("https://ads.example.com/a.js", "https://site.com", "script");
+        engine.should_block("https://ads.example.com/a.js", "https://site.com", "script");
+        engine.should_block("data:image/png;base64,AA==", "https://site.com", "image");
+        let cost = engine.match_cost();
+        assert_eq!(cost.misses.count(), 2);
+        assert_eq!(cost.hits.count(), 2);
+        assert!(cost.misses.max() <= cost.misses.total());
+        assert_eq!(cost.total(), cost.hits.total() + cost.misses.total());
+
+        // Le résumé ne touche pas aux chiffres
+        engine.log_match_cost();
+        assert_eq!(engine.match_cost(), cost);
+    }
+
+    #[test]
+    fn test_status_html() {
+        let status = AdblockStatus {
+            stats: Some(BlockStats {
+                checked: 120,
+                blocked: 30,
+                page_blocked: 4,
+                blocked_by_host: BTreeMap::from([
+                    ("ads.example".to_string(), 25),
+                    ("<t>.example".to_string(), 5),
+                ]),
+                match_cost: MatchCost {
+                    misses: timings(&[40, 60]),
+                    hits: timings(&[2]),
+                },
+            }),
+            source: Some(EngineSource::Cache),
+            lists: vec![
+                ListStatus {
+                    name: "EasyList".to_string(),
+                    enabled: true,
+                    rules: Some(1500),
+                    modified: Some(1_792_137_909),
+                    checked: None,
+                },
+                ListStatus {
+                    name: "Extra".to_string(),
+                    ..Default::default()
+                },
+            ],
+            allowlisted: vec!["news.example".to_string()],
+            notice: Some("Engine rebuilt.".to_string()),
+        };
+        let html = status_html(&status, "tok");
+        assert!(html.contains("<p class=\"notice\">Engine rebuilt.</p>"));
+        assert!(html.contains("Loaded from the engine cache"));
+        assert!(html.contains("<tr><td>Requests blocked</td><td>30</td></tr>"));
+        assert!(html.contains("<tr><td>ads.example</td><td>25</td></tr>"));
+        assert!(html.contains("<td>&lt;t&gt;.example</td>"));
+        assert!(
+            html.contains("<tr><td>Filter engine</td><td>2</td><td>100.0 µs</td><td>50.0 µs</td>")
+        );
+        assert!(html.contains("<tr><td>Verdict cache</td><td>1</td><td>2.0 µs</td>"));
+        assert!(html.contains(
+            "<tr><td>EasyList</td><td>1500</td><td>2026-10-16T08:05:09Z</td><td>—</td></tr>"
+        ));
+        assert!(html.contains("<td>Extra (disabled)</td><td>not downloaded</td>"));
+        assert!(html.contains("<li>news.example</li>"));
+        assert!(html.contains("http://suribrows.adblock/rebuild?token=tok"));
+
+        let html = status_html(&AdblockStatus::default(), "tok");
+        assert!(html.contains("Not loaded"));
+        assert!(html.contains("@@||example.com^$document"));
+    }
+
+    #[test]
+    fn test_engine_swap_keeps_in_flight_checks_on_old_engine() {
+        let slot = EngineSlot::default();
+        assert!(slot.install_initial(engine_from_rules(&["||old-ads.example^"])));
+        let in_flight = slot.get().unwrap();
+        assert!(in_flight.should_block("https://old-ads.example/a.js", "", "script"));
+
+        let generation = slot.begin_rebuild();
+        assert!(slot.swap(generation, engine_from_rules(&["||new-ads.example^"])));
+        // La vérification commencée avant l'échange finit avec l'ancien moteur
+        assert!(in_flight.should_block("https://old-ads.example/b.js", "", "script"));
+        // Les suivantes utilisent le nouveau, avec un cache vide
+        let current = slot.get().unwrap();
+        assert!(!current.should_block("https://old-ads.example/a.js", "", "script"));
+        assert!(current.should_block("https://new-ads.example/a.js", "", "script"));
+        assert_eq!(current.match_cost().misses.count(), 2);
+    }
+
+    #[test]
+    fn test_outdated_rebuild_is_dropped() {
+        let slot = EngineSlot::default();
+        let first = slot.begin_rebuild();
+        let second = slot.begin_rebuild();
+        assert!(!slot.swap(first, engine_from_rules(&["||first.example^"])));
+        assert!(!slot.is_loaded());
+        assert!(slot.swap(second, engine_from_rules(&["||second.example^"])));
+        // Moteur du démarrage arrivé après une reconstruction : ignoré
+        assert!(!slot.install_initial(engine_from_rules(&["||startup.example^"])));
+        let engine = slot.get().unwrap();
+        assert!(engine.should_block("https://second.example/", "", "script"));
+        assert!(!engine.should_block("https://startup.example/", "", "script"));
+    }
+
+    /// Moteur compilé sur un autre thread et transmis par un canal, comme
+    /// par `WakerEvent::AdblockReady`.
+    fn compiled_from_thread(rule: &'static str) -> CompiledEngine {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut filter_set = FilterSet::new(false);
+            filter_set.add_filter_list(rule, ParseOptions::default());
+            let compiled = CompiledEngine(
+                Engine::from_filter_set(filter_set, true),
+                EngineSource::Lists,
+            );
+            sender.send(Box::new(compiled)).unwrap();
+        });
+        *receiver.recv().unwrap()
+    }
+
+    #[test]
+    fn test_adblock_ready_installs_startup_engine() {
+        let dir = TempDir::new("ready-log");
+        let path = dir.join("blocked.log");
+        let slot = EngineSlot::default();
+        let compiled = compiled_from_thread("||ads.example^");
+        assert!(slot.install_compiled(compiled, || BlockLog::open(path.clone()).ok()));
+
+        let engine = slot.get().unwrap();
+        assert!(engine.should_block(
+            "https://ads.example/x.js",
+            "https://page.example/",
+            "script"
+        ));
+        drop((engine, slot));
+        assert!(
+            fs::read_to_string(&path)
+                .unwrap()
+                .contains("https://ads.example/x.js")
+        );
+    }
+
+    #[test]
+    fn test_adblock_ready_after_rebuild_drops_startup_engine() {
+        let slot = EngineSlot::default();
+        // Listes rechargées (Ctrl+Shift+F) avant la fin de la compilation
+        // du démarrage
+        assert!(slot.swap(
+            slot.begin_rebuild(),
+            engine_from_rules(&["||rebuilt.example^"])
+        ));
+        let compiled = compiled_from_thread("||startup.example^");
+        let mut log_opened = false;
+        assert!(!slot.install_compiled(compiled, || {
+            log_opened = true;
+            None
+        }));
+        assert!(!log_opened);
+
+        let engine = slot.get().unwrap();
+        assert!(engine.should_block("https://rebuilt.example/", "", "script"));
+        assert!(!engine.should_block("https://startup.example/", "", "script"));
+    }
+
+    #[test]
+    fn test_swap_moves_block_log() {
+        let dir = TempDir::new("swap-log");
+        let path = dir.join("blocked.log");
+        let slot = EngineSlot::default();
+        let log = BlockLog::open(path.clone()).unwrap();
+        slot.install_initial(engine_from_rules(&["||ads.example^"]).with_block_log(Some(log)));
+        let old = slot.get().unwrap();
+        assert!(slot.swap(slot.begin_rebuild(), engine_from_rules(&["||ads.example^"])));
+        assert!(old.take_block_log().is_none());
+        let current = slot.get().unwrap();
+        assert!(current.should_block(
+            "https://ads.example/x.js",
+            "https://page.example/",
+            "script"
+        ));
+        drop((old, current, slot));
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.ends_with("\thttps://page.example/\thttps://ads.example/x.js\tscript\n"));
+    }
+
+    #[test]
+    fn test_document_exception_covers_whole_site() {
+        let engine = engine_from_rules(&[
+            "||ads.example.net^",
+            "@@||news.example^$document",
+            "@@||ads.example.net^$image",
+        ]);
+        assert!(engine.is_document_excepted("https://news.example/article"));
+        assert!(engine.is_document_excepted("https://news.example/"));
+        assert!(!engine.is_document_excepted("https://other.example/"));
+        // Une exception sans `$document` ne vaut que pour ses requêtes
+        assert!(!engine.is_document_excepted("https://ads.example.net/"));
+        assert!(!engine.is_document_excepted("data:text/html,hi"));
+        // Sans exception de document, la page est filtrée normalement
+        assert!(engine.should_block(
+            "https://ads.example.net/a.js",
+            "https://other.example/",
+            "script"
+        ));
+    }
 }
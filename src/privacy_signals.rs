@@ -0,0 +1,94 @@
+//! Signaux de refus du suivi : Global Privacy Control (`[privacy] send_gpc`)
+//! et Do Not Track (`[privacy] send_dnt`).
+//!
+//! ## Ce que Servo permet
+//!
+//! Les requêtes de Servo passent en lecture seule dans `load_web_resource`
+//! (voir [`crate::referrer`]) et ses préférences ne règlent que le
+//! `User-Agent` : aucun moyen d'y ajouter `Sec-GPC: 1` ou `DNT: 1`.
+//!
+//! Le signal passe donc par JavaScript : une fois l'en-tête du
+//! document parsé, [`PrivacySignals::navigator_script`] définit
+//! `navigator.globalPrivacyControl` (exposé par la spécification GPC) et
+//! `navigator.doNotTrack`. Les scripts de l'en-tête, déjà exécutés, ne le
+//! voient pas ; les bandeaux de consentement, chargés plus tard, si.
+
+use crate::config::PrivacyConfig;
+
+/// Signaux à envoyer, d'après la section `[privacy]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrivacySignals {
+    pub gpc: bool,
+    pub dnt: bool,
+}
+
+impl PrivacySignals {
+    pub fn from_config(privacy: &PrivacyConfig) -> Self {
+        Self {
+            gpc: privacy.send_gpc,
+            dnt: privacy.send_dnt,
+        }
+    }
+
+    /// Script qui expose les signaux à la page, `None` si aucun n'est actif.
+    pub fn navigator_script(self) -> Option<String> {
+        if !self.gpc && !self.dnt {
+            return None;
+        }
+        let mut script = String::from("(function () {\n");
+        if self.gpc {
+            script.push_str(&define_property("globalPrivacyControl", "true"));
+        }
+        if self.dnt {
+            script.push_str(&define_property("doNotTrack", "\"1\""));
+        }
+        script.push_str("})();");
+        Some(script)
+    }
+}
+
+/// Ligne de script qui fixe `navigator.<name>` à `value` (littéral JS).
+fn define_property(name: &str, value: &str) -> String {
+    format!(
+        "    try {{ Object.defineProperty(Navigator.prototype, \"{name}\", \
+         {{ get: () => {value}, configurable: true }}); }} catch (e) {{}}\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config_defaults() {
+        // Défauts : GPC seulement
+        assert_eq!(
+            PrivacySignals::from_config(&PrivacyConfig::default()),
+            PrivacySignals {
+                gpc: true,
+                dnt: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_navigator_script() {
+        assert_eq!(PrivacySignals::default().navigator_script(), None);
+        let script = PrivacySignals {
+            gpc: true,
+            dnt: false,
+        }
+        .navigator_script()
+        .unwrap();
+        assert!(script.contains(r#""globalPrivacyControl", { get: () => true"#));
+        assert!(!script.contains("doNotTrack"));
+
+        let script = PrivacySignals {
+            gpc: false,
+            dnt: true,
+        }
+        .navigator_script()
+        .unwrap();
+        assert!(script.contains(r#""doNotTrack", { get: () => "1""#));
+    }
+}
@@ -0,0 +1,180 @@
+//! Politique de referrer (`[privacy] referrer_policy`) : referrer réduit à
+//! l'origine (`strict-origin`) ou supprimé pour les requêtes vers une autre
+//! origine (`no-referrer`).
+//!
+//! ## Ce que Servo permet
+//!
+//! `load_web_resource` voit passer chaque requête, mais en lecture seule :
+//! elle part telle quelle, ou l'embedder y répond entièrement à sa place
+//! (`intercept`). Ses en-têtes ne sont pas modifiables, et la refaire
+//! nous-mêmes perdrait cookies, cache et contrôles CORS. Servo n'a pas non
+//! plus de préférence de politique de referrer.
+//!
+//! La politique passe donc par le document : une fois l'en-tête de la page
+//! parsé (`LoadStatus::HeadParsed`), [`policy_script`] ajoute une balise
+//! `<meta name="referrer">`, appliquée par Servo aux requêtes suivantes de la
+//! page. Elle l'emporte sur l'en-tête `Referrer-Policy` et sur les balises de
+//! la page, retirées, y compris celles ajoutées plus tard par un script. Les
+//! requêtes parties avant (feuilles de style, scripts de l'en-tête) ne
+//! peuvent pas être corrigées : `load_web_resource` les journalise
+//! seulement, d'après [`referrer_for`].
+
+use url::Url;
+
+use crate::config::ReferrerPolicy;
+use crate::pages::js_string;
+
+/// `true` si `a` et `b` ont la même origine : schéma, hôte et port (port
+/// par défaut du schéma s'il est absent). Une URL sans hôte (`data:`…) n'a
+/// la même origine que rien.
+pub fn same_origin(a: &Url, b: &Url) -> bool {
+    a.host_str().is_some()
+        && a.scheme() == b.scheme()
+        && a.host_str() == b.host_str()
+        && a.port_or_known_default() == b.port_or_known_default()
+}
+
+/// Referer attendu pour une requête de `referrer` vers `target` selon
+/// `policy`, `None` s'il n'en faut aucun. L'URL complète est sans fragment ni
+/// identifiants ; `strict-origin` n'envoie que l'origine, même vers la même
+/// origine.
+pub fn referrer_for(policy: ReferrerPolicy, referrer: &Url, target: &Url) -> Option<String> {
+    if !matches!(referrer.scheme(), "http" | "https") {
+        return None;
+    }
+    let full = || {
+        let mut url = referrer.clone();
+        url.set_fragment(None);
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+        url.to_string()
+    };
+    match policy {
+        ReferrerPolicy::Default => Some(full()),
+        // Jamais de https vers http (`strict-`)
+        ReferrerPolicy::StrictOrigin
+            if referrer.scheme() == "https" && target.scheme() != "https" =>
+        {
+            None
+        }
+        ReferrerPolicy::StrictOrigin => {
+            Some(format!("{}/", referrer.origin().ascii_serialization()))
+        }
+        // `same-origin` : l'URL complète vers la même origine seulement
+        ReferrerPolicy::NoReferrer if same_origin(referrer, target) => Some(full()),
+        ReferrerPolicy::NoReferrer => None,
+    }
+}
+
+/// Valeur de `<meta name="referrer">` équivalente à `policy`, `None` pour
+/// `default` (la page garde sa politique).
+pub fn meta_content(policy: ReferrerPolicy) -> Option<&'static str> {
+    match policy {
+        ReferrerPolicy::Default => None,
+        ReferrerPolicy::StrictOrigin => Some("strict-origin"),
+        ReferrerPolicy::NoReferrer => Some("same-origin"),
+    }
+}
+
+/// Script qui impose `<meta name="referrer" content="…">` au document : les
+/// balises de la page sont retirées et la nôtre, insérée en dernier, prend
+/// le pas sur l'en-tête `Referrer-Policy`. Une balise ajoutée ensuite par la
+/// page est retirée de même.
+pub fn policy_script(content: &str) -> String {
+    let content = js_string(content);
+    format!(
+        r#"(function () {{
+    const content = {content};
+    const enforce = () => {{
+        const metas = document.querySelectorAll('meta[name="referrer" i]');
+        const own = [...metas].find((meta) => meta.dataset.suribrows !== undefined);
+        if (own && metas.length === 1 && own.content === content) return;
+        metas.forEach((meta) => meta.remove());
+        const meta = document.createElement("meta");
+        meta.name = "referrer";
+        meta.content = content;
+        meta.dataset.suribrows = "";
+        (document.head || document.documentElement).append(meta);
+    }};
+    enforce();
+    new MutationObserver(enforce).observe(document.documentElement, {{
+        childList: true,
+        subtree: true,
+        attributes: true,
+        attributeFilter: ["name", "content"],
+    }});
+}})();"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_same_origin_compares_scheme_host_and_port() {
+        let page = url("https://example.com/a/b?q=1");
+        assert!(same_origin(&page, &url("https://example.com:443/other")));
+        assert!(same_origin(&page, &url("https://EXAMPLE.com/")));
+        assert!(!same_origin(&page, &url("http://example.com/a/b")));
+        assert!(!same_origin(&page, &url("https://example.com:8443/")));
+        assert!(!same_origin(&page, &url("https://cdn.example.com/")));
+        let data = url("data:text/html,hi");
+        assert!(!same_origin(&data, &data));
+    }
+
+    #[test]
+    fn test_referrer_trimmed_cross_origin_only() {
+        let page = url("https://user:pw@example.com/account/orders?id=42#top");
+        let same = url("https://example.com/api/cart");
+        let cross = url("https://tracker.example/pixel.gif");
+        let full = "https://example.com/account/orders?id=42";
+
+        for policy in [ReferrerPolicy::Default, ReferrerPolicy::NoReferrer] {
+            assert_eq!(referrer_for(policy, &page, &same).as_deref(), Some(full));
+        }
+        // `strict-origin` : l'origine seule, même vers la même origine
+        assert_eq!(
+            referrer_for(ReferrerPolicy::StrictOrigin, &page, &same).as_deref(),
+            Some("https://example.com/")
+        );
+        assert_eq!(
+            referrer_for(ReferrerPolicy::Default, &page, &cross).as_deref(),
+            Some(full)
+        );
+        assert_eq!(
+            referrer_for(ReferrerPolicy::StrictOrigin, &page, &cross).as_deref(),
+            Some("https://example.com/")
+        );
+        assert_eq!(
+            referrer_for(ReferrerPolicy::NoReferrer, &page, &cross),
+            None
+        );
+        // Pas d'origine envoyée de https vers http
+        assert_eq!(
+            referrer_for(
+                ReferrerPolicy::StrictOrigin,
+                &page,
+                &url("http://tracker.example/")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_meta_policy_script() {
+        assert_eq!(meta_content(ReferrerPolicy::Default), None);
+        assert_eq!(
+            meta_content(ReferrerPolicy::StrictOrigin),
+            Some("strict-origin")
+        );
+        let script = policy_script(meta_content(ReferrerPolicy::NoReferrer).unwrap());
+        assert!(script.contains(r#"const content = "same-origin";"#));
+        // Les balises de la page sont retirées, pas respectées
+        assert!(script.contains("metas.forEach((meta) => meta.remove());"));
+    }
+}
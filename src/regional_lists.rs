@@ -0,0 +1,260 @@
+//! Listes de filtres régionales choisies d'après la langue.
+//!
+//! EasyList vise surtout les sites anglophones : les régies françaises,
+//! allemandes… passent au travers. [`CATALOG`] associe les listes
+//! régionales connues aux codes de langue ; au premier lancement (aucun
+//! `config.toml`), ou à chaque démarrage avec `[privacy]
+//! auto_regional_lists = true`, celles de la langue de l'utilisateur sont
+//! ajoutées aux abonnements ([`enable_for_locale`]). Le thread de mise à
+//! jour les télécharge ensuite comme toute liste activée absente.
+//!
+//! La langue vient de `[general] locale`, sinon de `LC_ALL`,
+//! `LC_MESSAGES` ou `LANG` (rarement définies sous Windows : rien n'est
+//! ajouté sans réglage explicite).
+
+use std::env;
+
+use tracing::info;
+
+use crate::config::{Config, FilterListConfig};
+
+/// Liste régionale du catalogue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionalList {
+    /// Codes de langue ISO 639 couverts.
+    pub languages: &'static [&'static str],
+    pub name: &'static str,
+    pub url: &'static str,
+}
+
+/// Listes régionales intégrées.
+pub const CATALOG: &[RegionalList] = &[
+    RegionalList {
+        languages: &["ar"],
+        name: "Liste AR",
+        url: "https://easylist-downloads.adblockplus.org/Liste_AR.txt",
+    },
+    RegionalList {
+        languages: &["bg"],
+        name: "Bulgarian List",
+        url: "https://stanev.org/abp/adblock_bg.txt",
+    },
+    RegionalList {
+        languages: &["zh"],
+        name: "EasyList China",
+        url: "https://easylist-downloads.adblockplus.org/easylistchina.txt",
+    },
+    RegionalList {
+        languages: &["cs", "sk"],
+        name: "EasyList Czech and Slovak",
+        url: "https://raw.githubusercontent.com/tomasko126/easylistczechandslovak/master/filters.txt",
+    },
+    RegionalList {
+        languages: &["nl"],
+        name: "EasyList Dutch",
+        url: "https://easylist-downloads.adblockplus.org/easylistdutch.txt",
+    },
+    RegionalList {
+        languages: &["fr"],
+        name: "Liste FR",
+        url: "https://easylist-downloads.adblockplus.org/liste_fr.txt",
+    },
+    RegionalList {
+        languages: &["de"],
+        name: "EasyList Germany",
+        url: "https://easylist.to/easylistgermany/easylistgermany.txt",
+    },
+    RegionalList {
+        languages: &["he"],
+        name: "EasyList Hebrew",
+        url: "https://raw.githubusercontent.com/easylist/EasyListHebrew/master/EasyListHebrew.txt",
+    },
+    RegionalList {
+        languages: &["hi", "bn", "ta", "te", "mr", "gu", "kn", "ml", "pa"],
+        name: "IndianList",
+        url: "https://easylist-downloads.adblockplus.org/indianlist.txt",
+    },
+    RegionalList {
+        languages: &["it"],
+        name: "EasyList Italy",
+        url: "https://easylist-downloads.adblockplus.org/easylistitaly.txt",
+    },
+    RegionalList {
+        languages: &["ko"],
+        name: "KoreanList",
+        url: "https://easylist-downloads.adblockplus.org/koreanlist.txt",
+    },
+    RegionalList {
+        languages: &["lt"],
+        name: "EasyList Lithuania",
+        url: "https://easylist-downloads.adblockplus.org/easylistlithuania.txt",
+    },
+    RegionalList {
+        languages: &["pl"],
+        name: "EasyList Polish",
+        url: "https://easylist-downloads.adblockplus.org/easylistpolish.txt",
+    },
+    RegionalList {
+        languages: &["pt"],
+        name: "EasyList Portuguese",
+        url: "https://easylist-downloads.adblockplus.org/easylistportuguese.txt",
+    },
+    RegionalList {
+        languages: &["ru", "uk", "be"],
+        name: "RU AdList",
+        url: "https://easylist-downloads.adblockplus.org/advblock.txt",
+    },
+    RegionalList {
+        languages: &["es"],
+        name: "EasyList Spanish",
+        url: "https://easylist-downloads.adblockplus.org/easylistspanish.txt",
+    },
+    RegionalList {
+        languages: &["vi"],
+        name: "ABPVN List",
+        url: "https://raw.githubusercontent.com/abpvn/abpvn/master/filter/abpvn.txt",
+    },
+];
+
+/// Code de langue d'une locale : `fr-FR` → `fr`, `de_DE.UTF-8` → `de`,
+/// `zh-Hans-CN` → `zh`. `None` pour `C`, `POSIX` ou une valeur vide.
+pub fn language(locale: &str) -> Option<String> {
+    let language = locale
+        .trim()
+        .split(['-', '_', '.', '@'])
+        .next()?
+        .to_ascii_lowercase();
+    let valid =
+        (2..=3).contains(&language.len()) && language.bytes().all(|b| b.is_ascii_lowercase());
+    valid.then_some(language)
+}
+
+/// Listes du catalogue pour `language` (code ISO 639 en minuscules).
+pub fn lists_for(language: &str) -> impl Iterator<Item = &'static RegionalList> + '_ {
+    CATALOG
+        .iter()
+        .filter(move |list| list.languages.contains(&language))
+}
+
+/// Locale du système, d'après les variables POSIX.
+pub fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+}
+
+/// Ajoute à `subscriptions` les listes de `language` pas encore présentes
+/// (même URL), activées. Une liste déjà là, même désactivée par
+/// l'utilisateur, n'est pas touchée. Retourne les noms ajoutés.
+pub fn merge(subscriptions: &mut Vec<FilterListConfig>, language: &str) -> Vec<&'static str> {
+    let mut added = Vec::new();
+    for list in lists_for(language) {
+        if subscriptions.iter().any(|s| s.url == list.url) {
+            continue;
+        }
+        subscriptions.push(FilterListConfig {
+            name: list.name.to_string(),
+            url: list.url.to_string(),
+            enabled: true,
+            file: None,
+            auto_enabled_for: Some(language.to_string()),
+        });
+        added.push(list.name);
+    }
+    added
+}
+
+/// Abonne `config` aux listes régionales de sa langue (`[general] locale`,
+/// sinon celle du système). Retourne les noms ajoutés ; `config.toml` est à
+/// enregistrer s'il y en a.
+pub fn enable_for_locale(config: &mut Config) -> Vec<&'static str> {
+    let locale = match config.general.locale.trim() {
+        "" => system_locale(),
+        locale => Some(locale.to_string()),
+    };
+    let Some(language) = locale.as_deref().and_then(language) else {
+        return Vec::new();
+    };
+    let added = merge(&mut config.privacy.filter_lists, &language);
+    if !added.is_empty() {
+        info!(language, lists = ?added, "Regional filter lists enabled");
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_filter_lists;
+
+    #[test]
+    fn test_language_from_locale() {
+        assert_eq!(language("fr-FR").as_deref(), Some("fr"));
+        assert_eq!(language("de_DE.UTF-8").as_deref(), Some("de"));
+        assert_eq!(language("PT_br").as_deref(), Some("pt"));
+        assert_eq!(language("zh-Hans-CN").as_deref(), Some("zh"));
+        assert_eq!(language("fil").as_deref(), Some("fil"));
+        assert_eq!(language(" it "), Some("it".to_string()));
+        assert_eq!(language("ca_ES@valencia").as_deref(), Some("ca"));
+        assert_eq!(language("C"), None);
+        assert_eq!(language("C.UTF-8"), None);
+        assert_eq!(language("POSIX"), None);
+        assert_eq!(language(""), None);
+    }
+
+    #[test]
+    fn test_catalog_lookup() {
+        let names: Vec<&str> = lists_for("fr").map(|list| list.name).collect();
+        assert_eq!(names, ["Liste FR"]);
+        assert_eq!(
+            lists_for("sk").next().unwrap().name,
+            "EasyList Czech and Slovak"
+        );
+        assert_eq!(lists_for("uk").next().unwrap().name, "RU AdList");
+        assert_eq!(lists_for("en").count(), 0);
+        assert_eq!(lists_for("FR").count(), 0);
+        // Une URL par liste
+        for (i, list) in CATALOG.iter().enumerate() {
+            assert!(list.url.starts_with("https://"), "{}", list.name);
+            assert!(CATALOG[i + 1..].iter().all(|other| other.url != list.url));
+        }
+    }
+
+    #[test]
+    fn test_merge_adds_missing_lists_once() {
+        let mut lists = default_filter_lists();
+        assert_eq!(merge(&mut lists, "de"), ["EasyList Germany"]);
+        let added = lists.last().unwrap();
+        assert!(added.enabled);
+        assert_eq!(added.auto_enabled_for.as_deref(), Some("de"));
+        assert_eq!(lists.len(), default_filter_lists().len() + 1);
+
+        // Déjà abonné : rien à ajouter
+        assert!(merge(&mut lists, "de").is_empty());
+        assert!(merge(&mut lists, "en").is_empty());
+        assert_eq!(lists.len(), default_filter_lists().len() + 1);
+    }
+
+    #[test]
+    fn test_merge_keeps_list_disabled_by_user() {
+        let mut lists = vec![FilterListConfig {
+            name: "Mine".to_string(),
+            url: "https://easylist-downloads.adblockplus.org/liste_fr.txt".to_string(),
+            enabled: false,
+            ..FilterListConfig::default()
+        }];
+        assert!(merge(&mut lists, "fr").is_empty());
+        assert_eq!(lists.len(), 1);
+        assert!(!lists[0].enabled);
+        assert_eq!(lists[0].auto_enabled_for, None);
+    }
+
+    #[test]
+    fn test_enable_for_configured_locale() {
+        let mut config = Config::default();
+        config.general.locale = "it_IT.UTF-8".to_string();
+        assert_eq!(enable_for_locale(&mut config), ["EasyList Italy"]);
+        assert_eq!(enable_for_locale(&mut config), Vec::<&str>::new());
+    }
+}
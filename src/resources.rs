@@ -31,6 +31,16 @@ struct ResourceReader;
 
 impl resources::ResourceReaderMethods for ResourceReader {
     fn read(&self, file: Resource) -> Vec<u8> {
+        // Erreur de certificat : la page d'avertissement est générée par
+        // SuriBrows (voir `cert_error`)
+        if let Resource::BadCertHTML = file {
+            return crate::cert_error::BOUNCE_HTML.as_bytes().to_vec();
+        }
+        // Erreur réseau : idem, page générée par `error_page`
+        if let Resource::NetErrorHTML = file {
+            return crate::error_page::BOUNCE_HTML.as_bytes().to_vec();
+        }
+
         let mut path = resources_dir_path();
         path.push(file.filename());
 
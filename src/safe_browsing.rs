@@ -0,0 +1,278 @@
+//! Liste de sécurité (hameçonnage, logiciels malveillants) et avertissement
+//! plein écran avant d'ouvrir un site listé.
+//!
+//! `[privacy] security_blocklist` désigne une liste d'hôtes : un fichier
+//! (chemin relatif au dossier de configuration) ou une URL `http(s)`,
+//! téléchargée dans `resources/filters/` et tenue à jour comme les listes de
+//! filtres. Chaque ligne contient un nom d'hôte ou une entrée hosts
+//! (`0.0.0.0 evil.example`) ; `#` commence un commentaire. Un hôte listé
+//! vaut aussi pour ses sous-domaines.
+//!
+//! Seules les navigations principales sont vérifiées, dans
+//! `load_web_resource` : la requête est annulée et la webview affiche
+//! l'avertissement de [`interstitial_html`] (URL `data:`). Son lien « ignore
+//! the warning » pointe vers `http://suribrows.blocked/proceed?token=…`,
+//! intercepté lui aussi. Les exceptions ne vivent qu'en mémoire, comme celles
+//! des certificats.
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use url::Url;
+
+use crate::config::FilterListConfig;
+use crate::pages;
+use crate::settings::html_escape;
+use crate::token::{self, PendingTokens};
+
+/// Domaine des requêtes interceptées (lien « ignore the warning »).
+const DOMAIN: &str = "suribrows.blocked";
+
+/// Fichier local d'une liste téléchargée, dans `resources/filters/`.
+/// L'extension `.hosts` la garde hors des listes adblock.
+pub const DOWNLOADED_FILE: &str = "security-blocklist.hosts";
+
+/// Abonnement équivalent au réglage `setting` s'il s'agit d'une URL
+/// `http(s)`, mis à jour par le thread des listes de filtres. `None` pour un
+/// fichier local ou un réglage vide.
+pub fn subscription(setting: &str) -> Option<FilterListConfig> {
+    let setting = setting.trim();
+    let url = Url::parse(setting).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| FilterListConfig {
+        name: "Security blocklist".to_string(),
+        url: setting.to_string(),
+        enabled: true,
+        file: Some(DOWNLOADED_FILE.to_string()),
+        auto_enabled_for: None,
+    })
+}
+
+/// Fichier local du réglage `setting` (chemin relatif à `data_dir`), `None`
+/// pour une URL ou un réglage vide.
+pub fn local_path(setting: &str, data_dir: &Path) -> Option<PathBuf> {
+    let setting = setting.trim();
+    if setting.is_empty() || subscription(setting).is_some() {
+        return None;
+    }
+    Some(data_dir.join(setting))
+}
+
+/// Hôtes de la liste de sécurité, normalisés (minuscules, sans point final).
+#[derive(Debug, Default)]
+pub struct HostBlocklist {
+    hosts: HashSet<String>,
+}
+
+impl HostBlocklist {
+    /// Lit une liste d'hôtes ou un fichier hosts. Les lignes sans nom
+    /// utilisable (adresses, noms locaux sans point comme `localhost`) sont
+    /// ignorées.
+    pub fn parse(content: &str) -> Self {
+        let mut hosts = HashSet::new();
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let names = match tokens.split_first() {
+                // Entrée hosts : l'adresse est suivie des noms
+                Some((first, rest)) if first.parse::<IpAddr>().is_ok() => rest,
+                _ => &tokens[..tokens.len().min(1)],
+            };
+            for name in names {
+                let name = normalize(name);
+                let valid = name.contains('.')
+                    && name != "localhost.localdomain"
+                    && name.parse::<IpAddr>().is_err()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+                if valid {
+                    hosts.insert(name);
+                }
+            }
+        }
+        Self { hosts }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hosts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    /// Entrée de la liste qui couvre `host` : l'hôte lui-même ou l'un de ses
+    /// domaines parents. `None` s'il n'est pas listé.
+    pub fn matched(&self, host: &str) -> Option<&str> {
+        let host = normalize(host);
+        let mut candidate = host.as_str();
+        loop {
+            if let Some(entry) = self.hosts.get(candidate) {
+                return Some(entry);
+            }
+            candidate = candidate.split_once('.')?.1;
+        }
+    }
+}
+
+/// Hôte en minuscules sans point final.
+fn normalize(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Jeton d'un lien `http://suribrows.blocked/proceed?token=…`.
+pub fn proceed_token(url: &Url) -> Option<String> {
+    token::url_token(url, DOMAIN, "/proceed")
+}
+
+/// Hôtes que l'utilisateur a choisi d'ouvrir malgré l'avertissement.
+#[derive(Debug, Default)]
+pub struct SessionExceptions {
+    allowed: HashSet<String>,
+    /// Pages bloquées affichées, par jeton du lien « ignore the warning ».
+    pending: PendingTokens<Url>,
+}
+
+impl SessionExceptions {
+    /// `true` si l'avertissement a été ignoré pour `host` pendant la session.
+    pub fn is_allowed(&self, host: &str) -> bool {
+        self.allowed.contains(&normalize(host))
+    }
+
+    /// Enregistre l'avertissement affiché pour `url` ; retourne le jeton de
+    /// son lien.
+    pub fn propose(&mut self, url: Url) -> String {
+        self.pending.propose(url)
+    }
+
+    /// L'utilisateur ignore l'avertissement : l'hôte est autorisé pour la
+    /// session. Retourne la page à ouvrir, `None` pour un jeton inconnu.
+    pub fn accept(&mut self, token: &str) -> Option<Url> {
+        let url = self.pending.take(token)?;
+        self.allowed.insert(normalize(url.host_str()?));
+        Some(url)
+    }
+}
+
+/// Avertissement plein écran : hôte, entrée de la liste, bouton « Go back »
+/// et, repliés sous « Details », le lien qui ouvre quand même la page.
+pub fn interstitial_html(url: &Url, entry: &str, token: &str) -> String {
+    // Thème commun, sur fond rouge
+    pages::shell(
+        "Dangerous site blocked",
+        "
+html { background: #a31515; }
+body { background: #a31515; color: #fff; padding: 64px 24px; }
+h1 { font-size: 26px; }
+p { font-size: 15px; line-height: 1.5; }
+code { color: #fff; }
+button { padding: 10px 28px; font-size: 15px; font-weight: bold; background: #fff; color: #a31515; }
+details { margin-top: 48px; font-size: 12px; color: #f0c0c0; }
+summary { cursor: pointer; }
+details a { color: #f0c0c0; text-decoration: underline; }
+",
+        &format!(
+            r#"<h1>Dangerous site ahead</h1>
+<p><strong>{host}</strong> is on your security blocklist. It may try to steal
+your passwords or payment details, or install malicious software.</p>
+<p><code>{url}</code></p>
+<div class="actions">
+<button onclick="if (history.length > 1) history.back(); else location.replace('about:blank')">Go back</button>
+</div>
+<details>
+<summary>Details</summary>
+<p>Listed as <code>{entry}</code> in <code>[privacy] security_blocklist</code>.</p>
+<p><a href="http://{DOMAIN}/proceed?token={token}">I understand the risks, visit this unsafe site anyway</a></p>
+</details>"#,
+            host = html_escape(url.host_str().unwrap_or_default()),
+            url = html_escape(url.as_str()),
+            entry = html_escape(entry),
+            token = html_escape(token),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain_and_hosts_lines() {
+        let list = HostBlocklist::parse(
+            "# Phishing hosts\n\
+             Evil.Example.\n\
+             0.0.0.0 phish.test malware.test # two names\n\
+             127.0.0.1 localhost\n\
+             127.0.0.1 localhost.localdomain\n\
+             0.0.0.0 0.0.0.0\n\
+             ::1 ip6-localhost\n\
+             bad/host.example\n",
+        );
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.matched("evil.example"), Some("evil.example"));
+        assert_eq!(list.matched("phish.test"), Some("phish.test"));
+        assert_eq!(list.matched("malware.test"), Some("malware.test"));
+        assert_eq!(list.matched("localhost"), None);
+        assert!(HostBlocklist::parse("").is_empty());
+    }
+
+    #[test]
+    fn test_matches_subdomains_only() {
+        let list = HostBlocklist::parse("evil.example\n");
+        assert_eq!(list.matched("login.Evil.Example"), Some("evil.example"));
+        assert_eq!(list.matched("evil.example."), Some("evil.example"));
+        assert_eq!(list.matched("notevil.example"), None);
+        assert_eq!(list.matched("example"), None);
+        assert_eq!(list.matched("evil.example.com"), None);
+    }
+
+    #[test]
+    fn test_setting_is_url_or_local_path() {
+        let list = subscription("https://lists.example/phishing.txt").unwrap();
+        assert_eq!(list.file.as_deref(), Some(DOWNLOADED_FILE));
+        assert!(list.enabled);
+        assert_eq!(
+            local_path("https://lists.example/phishing.txt", Path::new("/cfg")),
+            None
+        );
+
+        assert!(subscription("phishing.txt").is_none());
+        assert_eq!(
+            local_path("phishing.txt", Path::new("/cfg")),
+            Some(PathBuf::from("/cfg/phishing.txt"))
+        );
+        assert_eq!(local_path("  ", Path::new("/cfg")), None);
+    }
+
+    #[test]
+    fn test_session_exception_requires_known_token() {
+        let mut exceptions = SessionExceptions::default();
+        let url = Url::parse("https://Login.Evil.Example/account").unwrap();
+        assert!(!exceptions.is_allowed("login.evil.example"));
+        assert_eq!(exceptions.accept("forged"), None);
+
+        let token = exceptions.propose(url.clone());
+        assert!(!exceptions.is_allowed("login.evil.example"));
+        assert_eq!(exceptions.accept(&token), Some(url));
+        assert!(exceptions.is_allowed("LOGIN.evil.example"));
+        // Le jeton ne sert qu'une fois ; l'exception ne couvre que cet hôte
+        assert_eq!(exceptions.accept(&token), None);
+        assert!(!exceptions.is_allowed("evil.example"));
+        assert!(!exceptions.is_allowed("other.evil.example"));
+    }
+
+    #[test]
+    fn test_interstitial_escapes_and_links_token() {
+        let url = Url::parse("https://evil.example/?a=1&b=<2>").unwrap();
+        let html = interstitial_html(&url, "evil.example", "abc123");
+        assert!(html.contains("<strong>evil.example</strong>"));
+        assert!(html.contains("<code>https://evil.example/?a=1&amp;b=%3C2%3E</code>"));
+        assert!(html.contains(r#"href="http://suribrows.blocked/proceed?token=abc123""#));
+
+        let link = Url::parse("http://suribrows.blocked/proceed?token=abc123").unwrap();
+        assert_eq!(proceed_token(&link).as_deref(), Some("abc123"));
+        let other = Url::parse("http://example.com/proceed?token=abc123").unwrap();
+        assert_eq!(proceed_token(&other), None);
+    }
+}
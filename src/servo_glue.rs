@@ -12,25 +12,108 @@
 //! 3. **[`SuriBrowsServoDelegate`]** : Callbacks moteur de niveau global (erreurs,
 //!    chargement de ressources hors-webview).
 
-use servo::{WebResourceLoad, WebResourceResponse, WebView, WebViewDelegate};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use embedder_traits::{MediaSessionEvent, MediaSessionPlaybackState};
+use hyper::header::{ACCEPT, CONTENT_TYPE, COOKIE, HeaderMap, HeaderValue, REFERER, UPGRADE};
+use ipc_channel::ipc::IpcSender;
+use servo::{
+    AuthenticationRequest, ConsoleLogLevel, ContextMenu, Cursor, FilterPattern, LoadStatus,
+    NavigationRequest, PermissionRequest, SimpleDialog, WebResourceLoad, WebResourceResponse,
+    WebView, WebViewDelegate, WebViewId,
+};
 use tracing::{debug, warn};
 use url::Url;
 use winit::event_loop::{EventLoop, EventLoopProxy};
+use winit::window::CursorIcon;
 
+use crate::blocking::{RequestHeaders, RequestType, TypeBlocking};
 use crate::browser::AppState;
+use crate::chrome::SiteSecurity;
+use crate::config::{ChromeConfig, Config};
+use crate::console::{self, Admit, ConsoleLevel};
+use crate::context_menu::ContextTarget;
+use crate::delegate_guard;
+use crate::download::{DownloadResult, Progress};
+use crate::favicon::{Favicon, FaviconError};
+use crate::https_only::{DOWNGRADE_REASON, Upgrade};
+use crate::media::Playback;
+use crate::privacy::{CompiledEngine, PageClassIds, StatusAction, Verdict};
+use crate::zoom::DEFAULT_ZOOM;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Waker : pont Servo → Winit
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Événement marqueur envoyé à travers le `EventLoopProxy` de Winit.
+/// Événement envoyé à travers le `EventLoopProxy` de Winit.
 ///
 /// Quand un thread interne de Servo a terminé un travail (page chargée,
 /// frame prête, réponse réseau reçue), il appelle `Waker::wake()`,
-/// qui envoie ce `WakerEvent`. La boucle Winit le reçoit dans
+/// qui envoie `WakerEvent::Servo`. La boucle Winit le reçoit dans
 /// `user_event()` et appelle `servo.spin_event_loop()` en réponse.
+///
+/// Les threads de l'embedder (surveillance de config, etc.) utilisent
+/// [`Waker::send`] pour transmettre leurs résultats au thread principal.
 #[derive(Debug)]
-pub struct WakerEvent;
+pub enum WakerEvent {
+    /// Servo a du travail à traiter sur le thread principal.
+    Servo,
+    /// `config.toml` a été modifié : nouvelle section `[chrome]` à appliquer.
+    ChromeConfigReloaded(Box<ChromeConfig>),
+    /// `config.toml` a été modifié, avec `[general] watch_config` : fichier
+    /// complet, comparé à la version appliquée (voir `crate::config_reload`).
+    ConfigReloaded(Box<Config>),
+    /// Un export (PDF…) écrit sur un thread de fond est terminé : chemin du
+    /// fichier, ou message d'erreur.
+    ExportFinished(Result<PathBuf, String>),
+    /// Favicon converti sur un thread de fond (`None` = icône ignorée).
+    /// `token` vient de [`crate::favicon::FaviconStore::start_decode`].
+    FaviconDecoded {
+        webview: WebViewId,
+        token: u64,
+        favicon: Option<Favicon>,
+    },
+    /// Moteur adblock compilé au démarrage sur un thread de fond.
+    AdblockReady(Box<CompiledEngine>),
+    /// Le thread de mise à jour a réécrit au moins une liste de filtres, ou
+    /// les listes activées ont changé : reconstruire le moteur adblock.
+    FilterListsUpdated,
+    /// Moteur adblock reconstruit sur un thread de fond (`None` : aucune
+    /// liste utilisable). `generation` vient de
+    /// [`crate::privacy::EngineSlot::begin_rebuild`].
+    AdblockRebuilt {
+        generation: u64,
+        compiled: Option<Box<CompiledEngine>>,
+    },
+    /// Le thread de mise à jour a réécrit la liste de sécurité.
+    SecurityBlocklistUpdated,
+    /// Bouton « Clear Browsing Data » des paramètres : effacement différé
+    /// hors du callback de Servo qui l'a intercepté.
+    ClearBrowsingData,
+    /// Bouton de la page d'état de l'adblock (`suri://adblock`).
+    AdblockStatusAction(StatusAction),
+    /// Classes et ids d'une page chargée, pour le filtrage cosmétique.
+    CosmeticTargets {
+        webview: WebViewId,
+        url: Url,
+        page: PageClassIds,
+    },
+    /// La webview affiche une réponse que Servo ne sait pas rendre : elle
+    /// est téléchargée.
+    DownloadResponse { webview: WebViewId, url: Url },
+    /// Le thread d'un téléchargement attend le relevé suivant de la page.
+    DownloadPoll { id: u64 },
+    /// Le fichier d'un téléchargement est créé : nom définitif.
+    DownloadStarted { id: u64, name: String },
+    /// Avancement d'un téléchargement (après chaque relevé écrit).
+    DownloadProgress { id: u64, progress: Progress },
+    /// Téléchargement terminé, annulé ou échoué.
+    DownloadFinished { id: u64, result: DownloadResult },
+    /// La dernière webview s'est fermée (`window.close()`, arrêt côté Servo) :
+    /// rouvrir la page d'accueil ou quitter selon `exit_on_last_close`.
+    LastWebViewClosed,
+}
 
 /// Pont thread-safe entre les threads internes de Servo et la boucle
 /// d'événements Winit sur le thread principal.
@@ -53,6 +136,12 @@ impl Waker {
     pub fn new(event_loop: &EventLoop<WakerEvent>) -> Self {
         Self(event_loop.create_proxy())
     }
+
+    /// Envoie un événement à la boucle Winit depuis n'importe quel thread.
+    /// Retourne `false` si la boucle d'événements est fermée.
+    pub fn send(&self, event: WakerEvent) -> bool {
+        self.0.send_event(event).is_ok()
+    }
 }
 
 /// Implémentation du trait Servo `EventLoopWaker`.
@@ -65,7 +154,7 @@ impl embedder_traits::EventLoopWaker for Waker {
     }
 
     fn wake(&self) {
-        if let Err(error) = self.0.send_event(WakerEvent) {
+        if let Err(error) = self.0.send_event(WakerEvent::Servo) {
             warn!(?error, "Échec du réveil de la boucle d'événements Winit");
         }
     }
@@ -75,6 +164,14 @@ impl embedder_traits::EventLoopWaker for Waker {
 // WebViewDelegate : callbacks Servo → embedder (par webview)
 // ─────────────────────────────────────────────────────────────────────────────
 
+impl AppState {
+    /// Exécute le corps d'un callback du delegate : une panic est rattrapée
+    /// (jamais propagée dans Servo), journalisée et comptée.
+    fn guard(&self, callback: &'static str, body: impl FnOnce()) {
+        delegate_guard::guard(&self.delegate_panics, callback, body);
+    }
+}
+
 /// Implémentation du `WebViewDelegate` pour `AppState`.
 ///
 /// Le trait `WebViewDelegate` a 34 méthodes, toutes avec des implémentations
@@ -83,14 +180,28 @@ impl embedder_traits::EventLoopWaker for Waker {
 /// ## Méthodes implémentées
 ///
 /// - `notify_new_frame_ready` : déclenche un redraw Winit quand Servo a composité
+/// - `notify_focus_changed` / `notify_closed` : webview active
+/// - `notify_crashed` : page « This page crashed » à la place de l'onglet planté
+/// - `show_console_message` : console des pages vers `tracing` (`log_console`)
+/// - `notify_media_session_event` : page en lecture, cible des touches média
+/// - `request_navigation` : navigations refusées par adblock avant chargement,
+///   liens externes (`mailto:`…) confiés au système après confirmation
 /// - `notify_url_changed` : met à jour le titre de la fenêtre
 /// - `notify_page_title_changed` : idem, depuis la balise `<title>`
-///
-/// ## Points d'extension futurs
-///
-/// - `load_web_resource()` → middleware privacy (adblock, tracker blocking)
-/// - `notify_cursor_changed()` → changement de curseur souris
-/// - `request_navigation()` → contrôle de navigation (filtrage d'URLs)
+/// - `notify_status_text_changed` : bulle de statut (cible du lien survolé)
+/// - `notify_favicon_changed` : favicon affiché à gauche de la barre d'URL
+/// - `notify_load_status_changed` : bouton recharger ↔ arrêter
+/// - `notify_history_changed` : précédent/suivant possibles
+/// - `notify_animating_changed` : cadence de redraw pendant les animations
+/// - `notify_cursor_changed` : curseur souris au-dessus de la page
+/// - `show_simple_dialog` : `alert()`, `confirm()`, `prompt()`
+/// - `request_permission` : permissions par site (refus par défaut)
+/// - `request_authentication` : identifiants HTTP (basique, digest)
+/// - `notify_fullscreen_state_changed` : plein écran demandé par la page
+/// - `show_context_menu` : menu du clic droit, dessiné par le chrome
+/// - `show_file_selection_dialog` : sélecteur natif des `<input type="file">`
+/// - `get/set/clear_clipboard_contents` : copier/coller dans la page
+/// - `load_web_resource` : adblock, réponses neutres, pages internes
 impl WebViewDelegate for AppState {
     /// Appelé quand Servo a composité un nouveau frame prêt à être affiché.
     ///
@@ -98,28 +209,144 @@ impl WebViewDelegate for AppState {
     /// → `webview.paint()` + `rendering_context.present()`.
     ///
     /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
-    fn notify_new_frame_ready(&self, _webview: WebView) {
-        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            self.window.request_redraw();
-        }));
+    fn notify_new_frame_ready(&self, webview: WebView) {
+        self.guard("notify_new_frame_ready", || {
+            self.frame_watchdog.borrow_mut().frame_ready(webview.id());
+            self.redraw_webview();
+        });
         // Panic recovery: if window access fails, skip this frame redraw
     }
 
+    /// Appelé quand le script ou le layout d'une page a paniqué. Servo isole
+    /// le plantage : seul cet onglet affiche la page d'erreur.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_crashed(&self, webview: WebView, reason: String, backtrace: Option<String>) {
+        self.guard("notify_crashed", || {
+            self.show_crash_page(&webview, &reason, backtrace.as_deref());
+        });
+    }
+
+    /// Appelé pour chaque `console.*` d'une page et chaque exception non
+    /// rattrapée. Journalisé seulement avec `[general] log_console`, dans la
+    /// limite de [`console::MAX_MESSAGES_PER_SECOND`].
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn show_console_message(&self, webview: WebView, level: ConsoleLogLevel, message: String) {
+        self.guard("show_console_message", || {
+            if !self.config.general.log_console {
+                return;
+            }
+            match self.console_limiter.borrow_mut().admit(Instant::now()) {
+                Admit::Log { suppressed } if suppressed > 0 => {
+                    warn!(target: console::TARGET, suppressed, "Console messages dropped (rate limit)");
+                }
+                Admit::Log { .. } => {}
+                Admit::Drop => return,
+            }
+            let page_url = webview.url().map(String::from).unwrap_or_default();
+            console::log(level.into(), &page_url, &message);
+        });
+    }
+
+    /// Appelé quand la session média d'une page change (métadonnées,
+    /// position, état de lecture). Seul l'état de lecture est suivi : il
+    /// désigne la webview qui reçoit les touches média.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_media_session_event(&self, webview: WebView, event: MediaSessionEvent) {
+        self.guard("notify_media_session_event", || {
+            if let MediaSessionEvent::PlaybackStateChange(state) = event {
+                let playback = match state {
+                    MediaSessionPlaybackState::None_ => Playback::None,
+                    MediaSessionPlaybackState::Playing => Playback::Playing,
+                    MediaSessionPlaybackState::Paused => Playback::Paused,
+                };
+                debug!(?playback, "Media session playback state changed");
+                self.media_sessions
+                    .borrow_mut()
+                    .set_playback(webview.id(), playback);
+            }
+        });
+    }
+
+    /// Appelé quand Servo donne ou retire le focus à une webview (clic,
+    /// `window.focus()`). Une webview qui reçoit le focus devient l'active.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_focus_changed(&self, webview: WebView, focused: bool) {
+        self.guard("notify_focus_changed", || {
+            if focused {
+                self.activate_webview(&webview);
+            }
+        });
+    }
+
+    /// Appelé quand une webview est fermée (`window.close()`, ou détruite
+    /// par Servo). L'active passe à sa voisine ; après la dernière, la page
+    /// d'accueil est rouverte ou l'application quitte (`exit_on_last_close`).
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_closed(&self, webview: WebView) {
+        self.guard("notify_closed", || {
+            if self.shutting_down.get() {
+                return;
+            }
+            let was_active = self.is_active(&webview);
+            self.remove_webview(&webview);
+            match self.active_webview() {
+                Some(active) if was_active => self.show_active(&active),
+                Some(_) => {}
+                // Le delegate n'a pas accès à la boucle d'événements
+                None => {
+                    self.waker.send(WakerEvent::LastWebViewClosed);
+                }
+            }
+        });
+    }
+
     /// Appelé quand l'URL de la page change (navigation, redirection).
     /// Servo fournit la nouvelle URL directement en paramètre.
     ///
     /// SECURITY (V-4): Wrapped with panic safety to prevent UB if concurrent
     /// access to Rc<RefCell<>> causes a panic across the FFI boundary.
-    fn notify_url_changed(&self, _webview: WebView, url: Url) {
-        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            let title = &self.config.general.window_title;
-            self.window.set_title(&format!("{title} — {url}"));
+    fn notify_url_changed(&self, webview: WebView, url: Url) {
+        self.guard("notify_url_changed", || {
+            // Webview en arrière-plan : resynchronisée quand elle devient active
+            if !self.is_active(&webview) {
+                return;
+            }
             self.urlbar.borrow_mut().set_url(&url);
-            *self.current_url.borrow_mut() = Some(url.clone());
-            if let Some(ref engine) = self.adblock_engine {
+            self.page.borrow_mut().commit(url.clone());
+            self.refresh_page_label();
+            // Historique de session (pages internes `data:` exclues, rien en
+            // navigation privée)
+            if url.scheme() != "data" && !self.private {
+                let mut visited = self.visited.borrow_mut();
+                if visited.last() != Some(&url) {
+                    visited.push(url.clone());
+                }
+            }
+            self.is_bookmarked
+                .set(self.bookmarks.borrow().contains(url.as_str()));
+            // Un downgrade https → http arrive ici avec le schéma http :
+            // l'indicateur repasse en « non sécurisé ».
+            self.site_security.set(SiteSecurity::for_url(&url));
+            // Les occurrences de la recherche appartenaient à l'ancienne page
+            self.find.borrow_mut().set_matches(None);
+            // Zoom mémorisé pour cet hôte
+            let zoom = url
+                .host_str()
+                .map_or(DEFAULT_ZOOM, |host| self.zoom_levels.borrow().get(host));
+            if self.zoom.replace(zoom) != zoom {
+                webview.set_zoom(zoom);
+                self.refresh_title();
+            }
+            if let Some(engine) = self.adblock_engine.get() {
+                engine.log_match_cost();
                 engine.clear_cache();
             }
-        }));
+        });
         // If panic occurs (e.g., RefCell already borrowed), silently recover
         // instead of allowing undefined behavior across FFI boundary
     }
@@ -128,88 +355,772 @@ impl WebViewDelegate for AppState {
     /// Servo fournit le nouveau titre en paramètre (None si pas de `<title>`).
     ///
     /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
-    fn notify_page_title_changed(&self, _webview: WebView, title: Option<String>) {
-        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            if let Some(page_title) = title {
-                let app_title = &self.config.general.window_title;
-                self.window
-                    .set_title(&format!("{app_title} — {page_title}"));
+    fn notify_page_title_changed(&self, webview: WebView, title: Option<String>) {
+        self.guard("notify_page_title_changed", || {
+            if !self.is_active(&webview) {
+                return;
             }
-        }));
+            self.page.borrow_mut().set_title(title);
+            self.refresh_page_label();
+        });
         // Panic recovery: prevent UB if window access causes panic
     }
 
-    /// Intercepte les requêtes réseau pour le filtrage adblock.
+    /// Appelé quand le texte de statut change — en pratique la cible du lien
+    /// survolé. `None` quand le survol se termine : la bulle disparaît.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_status_text_changed(&self, webview: WebView, status: Option<String>) {
+        self.guard("notify_status_text_changed", || {
+            if !self.is_active(&webview) {
+                return;
+            }
+            let status = status.filter(|text| !text.is_empty());
+            let mut current = self.status_text.borrow_mut();
+            if *current != status {
+                *current = status;
+                self.redraw_chrome();
+            }
+        });
+    }
+
+    /// Appelé quand Servo a chargé et décodé le favicon de la page.
     ///
-    /// Appelé pour chaque requête HTTP émise par Servo. Si le moteur adblock
-    /// est actif et que l'URL match un filtre, la requête est annulée.
-    /// Sinon, on ne fait rien et Servo procède normalement.
+    /// Les pixels sont copiés puis convertis en RGBA (et réduits) sur un
+    /// thread de fond ; le résultat revient par `WakerEvent::FaviconDecoded`.
+    /// L'upload GL est différé au redraw suivant, seul moment où le contexte
+    /// de la fenêtre est actif.
     ///
-    /// SECURITY (V-7 partial fix): Also updates URL bar immediately for main frame
-    /// navigations to reduce TOCTOU window for phishing attacks.
     /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
-    fn load_web_resource(&self, _webview: WebView, load: WebResourceLoad) {
-        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    fn notify_favicon_changed(&self, webview: WebView) {
+        self.guard("notify_favicon_changed", || {
+            let id = webview.id();
+            let token = self.favicons.borrow_mut().start_decode(id);
+            let source = webview.favicon().map(|image| FaviconSource {
+                width: image.width,
+                height: image.height,
+                format: image.format,
+                data: image.data().to_vec(),
+            });
+            let waker = self.waker.clone();
+            let spawned = std::thread::Builder::new()
+                .name("favicon".to_string())
+                .spawn(move || {
+                    let favicon = source.and_then(|source| match source.decode() {
+                        Ok(favicon) => Some(favicon),
+                        Err(e) => {
+                            debug!(reason = %e, "Favicon skipped");
+                            None
+                        }
+                    });
+                    waker.send(WakerEvent::FaviconDecoded {
+                        webview: id,
+                        token,
+                        favicon,
+                    });
+                });
+            if let Err(e) = spawned {
+                warn!(error = %e, "Cannot start favicon thread");
+            }
+        });
+    }
+
+    /// Appelé à chaque étape du chargement (`Started`, `HeadParsed`, `Complete`).
+    /// Tant que la page n'est pas complète, le bouton recharger affiche « × ».
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_load_status_changed(&self, webview: WebView, status: LoadStatus) {
+        self.guard("notify_load_status_changed", || {
+            let loading = status != LoadStatus::Complete;
+            let active = self.is_active(&webview);
+            // Nouveau document, navigation engagée par Servo
+            if status == LoadStatus::Started {
+                // L'ancien favicon disparaît jusqu'à ce que la page fournisse
+                // le sien, ainsi que la session média de l'ancienne page
+                self.favicons.borrow_mut().clear(&webview.id());
+                self.media_sessions.borrow_mut().remove(webview.id());
+                self.block_counters.reset_page(webview.id());
+                // Titre : celui de la webview affichée seulement
+                if active {
+                    self.page.borrow_mut().load_started();
+                }
+            }
+            // Document parsé : une frame doit suivre, sinon la page est figée
+            if status == LoadStatus::HeadParsed {
+                self.frame_watchdog
+                    .borrow_mut()
+                    .expect_frame(webview.id(), Instant::now());
+                self.apply_referrer_policy(&webview);
+                self.apply_privacy_signals(&webview);
+            }
+            // Masquage des éléments publicitaires dès l'en-tête (règles du
+            // site, avant le premier rendu du corps), complété une fois la
+            // page chargée (classes et ids de tout le document)
+            if matches!(status, LoadStatus::HeadParsed | LoadStatus::Complete) {
+                self.collect_cosmetic_targets(&webview);
+            }
+            // Réponse que Servo ne sait pas afficher (archive, PDF…)
+            if status == LoadStatus::Complete {
+                self.check_download(&webview);
+            }
+            self.update_load_state(&webview, |state| state.set_loading(loading));
+            if active {
+                self.refresh_page_label();
+            }
+        });
+    }
+
+    /// Appelé avant toute navigation de premier niveau (lien, formulaire,
+    /// script, barre d'URL). Une navigation vers un document bloqué par
+    /// adblock est refusée ici, avant tout chargement : la barre d'URL et le
+    /// titre ne changent qu'à l'engagement (`notify_url_changed`).
+    ///
+    /// SECURITY (V-7): Nothing is displayed for a navigation until Servo
+    /// commits it, so a denied or never-committed navigation cannot spoof
+    /// the URL bar.
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn request_navigation(&self, webview: WebView, navigation_request: NavigationRequest) {
+        self.guard("request_navigation", || {
+            // Lien `mailto:`, `magnet:`… : Servo ne le chargerait pas
+            if crate::external::is_external(&navigation_request.url) {
+                let url = navigation_request.url.clone();
+                navigation_request.deny();
+                self.open_external(url);
+                return;
+            }
+            // Paramètres de suivi : navigation relancée sans eux. L'URL
+            // nettoyée n'en contient plus et passe au tour suivant.
+            if let Some(clean) = crate::tracking_params::strip(
+                &navigation_request.url,
+                &self.config.privacy.strip_params,
+            ) {
+                debug!(from = %navigation_request.url, to = %clean, "Tracking parameters removed");
+                navigation_request.deny();
+                webview.load(clean);
+                return;
+            }
+            // HTTPS uniquement : navigation relancée en https, ou
+            // avertissement si la version https renvoie vers http
+            if self.config.privacy.https_only {
+                let upgrade = self
+                    .https_upgrades
+                    .borrow_mut()
+                    .upgrade(&navigation_request.url);
+                match upgrade {
+                    Some(Upgrade::Https(https)) => {
+                        debug!(from = %navigation_request.url, to = %https, "Navigation upgraded to HTTPS");
+                        navigation_request.deny();
+                        webview.load(https);
+                        return;
+                    }
+                    Some(Upgrade::Downgraded) => {
+                        let http_url = navigation_request.url.clone();
+                        navigation_request.deny();
+                        self.show_https_interstitial(&webview, http_url, DOWNGRADE_REASON);
+                        return;
+                    }
+                    None => {}
+                }
+            }
+            // Copie du moteur : une reconstruction installée pendant la
+            // vérification ne la perturbe pas
+            let Some(engine) = self.adblock_engine.get() else {
+                navigation_request.allow();
+                return;
+            };
+            let source_url = self
+                .page
+                .borrow()
+                .url()
+                .map(|u| u.to_string())
+                .unwrap_or_default();
+            let url = navigation_request.url.as_str();
+            let blocked = engine.should_block(url, &source_url, "document");
+            self.block_counters.record(webview.id(), url, blocked);
+            if blocked {
+                debug!(url, "Navigation bloquée par adblock");
+                navigation_request.deny();
+                self.redraw_chrome();
+            } else {
+                navigation_request.allow();
+            }
+        });
+    }
+
+    /// Appelé quand l'historique de session change (navigation, retour…) :
+    /// `current` est l'index de la page affichée dans `entries`.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_history_changed(&self, webview: WebView, entries: Vec<Url>, current: usize) {
+        self.guard("notify_history_changed", || {
+            self.update_load_state(&webview, |state| state.set_history(entries.len(), current));
+        });
+    }
+
+    /// Appelé quand la page commence ou cesse d'animer (CSS, vidéo,
+    /// `requestAnimationFrame`). Tant que la page affichée anime,
+    /// `about_to_wait` réveille la boucle à chaque frame ; sinon la boucle
+    /// reste au repos, même si un onglet en arrière-plan anime.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_animating_changed(&self, webview: WebView, animating: bool) {
+        self.guard("notify_animating_changed", || {
+            let mut pages = self.animating.borrow_mut();
+            if animating {
+                pages.insert(webview.id());
+            } else {
+                pages.remove(&webview.id());
+            }
+            drop(pages);
+            if animating && self.is_active(&webview) {
+                self.redraw_webview();
+            }
+        });
+    }
+
+    /// Appelé quand l'élément survolé demande un autre curseur (lien, champ
+    /// texte…). Il n'est appliqué que si le pointeur est sur la page : le
+    /// chrome garde la flèche.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_cursor_changed(&self, webview: WebView, cursor: Cursor) {
+        self.guard("notify_cursor_changed", || {
+            if !self.is_active(&webview) {
+                return;
+            }
+            self.page_cursor.set(cursor);
+            self.apply_cursor();
+        });
+    }
+
+    /// Appelé pour `alert()`, `confirm()` et `prompt()`. Le dialogue est
+    /// dessiné par le chrome ; la réponse part à la validation, sans bloquer
+    /// la boucle d'événements pendant que le script de la page attend.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn show_simple_dialog(&self, _webview: WebView, dialog: SimpleDialog) {
+        self.guard("show_simple_dialog", || {
+            self.show_dialog(dialog);
+        });
+    }
+
+    /// Appelé après un clic droit que la page n'a pas intercepté
+    /// (`contextmenu` non annulé). Servo indique le lien et l'image sous le
+    /// pointeur ; le menu est dessiné par le chrome et ses actions exécutées
+    /// par l'embedder, la requête Servo est seulement fermée ensuite.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn show_context_menu(&self, _webview: WebView, menu: ContextMenu) {
+        self.guard("show_context_menu", || {
+            let info = menu.element_info();
+            let target = ContextTarget {
+                link: info.link_url.clone(),
+                image: info.image_url.clone(),
+            };
+            self.open_context_menu(menu, target);
+        });
+    }
+
+    /// Appelé pour un `<input type="file">` : ouvre le sélecteur natif avec
+    /// les types de l'attribut `accept`, sans bloquer la boucle d'événements.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn show_file_selection_dialog(
+        &self,
+        _webview: WebView,
+        filter_pattern: Vec<FilterPattern>,
+        allow_select_multiple: bool,
+        response_sender: IpcSender<Option<Vec<PathBuf>>>,
+    ) {
+        self.guard("show_file_selection_dialog", || {
+            self.show_file_picker(&filter_pattern, allow_select_multiple, response_sender);
+        });
+    }
+
+    /// Appelé quand la page lit le presse-papiers (Ctrl+V dans un champ,
+    /// `navigator.clipboard.readText()` autorisé).
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn get_clipboard_contents(
+        &self,
+        _webview: WebView,
+        result_sender: IpcSender<Result<String, String>>,
+    ) {
+        self.guard("get_clipboard_contents", || {
+            let contents = self.clipboard.borrow_mut().get_text();
+            if let Err(e) = result_sender.send(contents) {
+                debug!(error = %e, "Clipboard contents dropped");
+            }
+        });
+    }
+
+    /// Appelé quand la page copie du texte (Ctrl+C sur une sélection).
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn set_clipboard_contents(&self, _webview: WebView, contents: String) {
+        self.guard("set_clipboard_contents", || {
+            if let Err(e) = self.clipboard.borrow_mut().set_text(&contents) {
+                warn!(error = %e, "Cannot write to the clipboard");
+            }
+        });
+    }
+
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn clear_clipboard_contents(&self, _webview: WebView) {
+        self.guard("clear_clipboard_contents", || {
+            if let Err(e) = self.clipboard.borrow_mut().clear() {
+                warn!(error = %e, "Cannot clear the clipboard");
+            }
+        });
+    }
+
+    /// Appelé quand un élément de la page entre en plein écran ou en sort
+    /// (bouton plein écran d'un lecteur vidéo, `document.exitFullscreen()`).
+    /// La fenêtre passe en plein écran sans bordure et le chrome est masqué ;
+    /// en sortant, la fenêtre revient à l'état choisi avec F11.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn notify_fullscreen_state_changed(&self, _webview: WebView, fullscreen: bool) {
+        self.guard("notify_fullscreen_state_changed", || {
+            let mut state = self.fullscreen.get();
+            state.set_page(fullscreen);
+            self.set_fullscreen(state);
+        });
+    }
+
+    /// Appelé quand la page demande une permission (notifications, caméra…).
+    /// La clé est l'origine de la page qui demande : une décision prise pour
+    /// `https://example.com` ne vaut pas pour ses sous-domaines.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn request_permission(&self, webview: WebView, request: PermissionRequest) {
+        self.guard("request_permission", || {
+            let origin = webview
+                .url()
+                .as_ref()
+                .and_then(crate::permissions::origin_key);
+            self.handle_permission_request(request, origin);
+        });
+    }
+
+    /// Appelé quand un serveur (ou le proxy) répond 401/407 : le chrome
+    /// demande un nom d'utilisateur et un mot de passe.
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn request_authentication(
+        &self,
+        _webview: WebView,
+        authentication_request: AuthenticationRequest,
+    ) {
+        self.guard("request_authentication", || {
+            self.show_authentication(authentication_request);
+        });
+    }
+
+    /// Intercepte les requêtes réseau pour le filtrage adblock. Une requête
+    /// visée par une règle `$redirect` reçoit la ressource de substitution ;
+    /// une image, feuille de style ou script bloqué reçoit une réponse neutre
+    /// (voir [`crate::blocking`]) ; les autres requêtes bloquées sont
+    /// annulées. Une requête autorisée n'est pas touchée.
+    ///
+    /// L'enregistrement des paramètres (`http://suribrows.settings/save?…`)
+    /// est traité ici, sans jamais atteindre le réseau.
+    ///
+    /// Les navigations issues d'un formulaire (`POST`) sont notées ici : leur
+    /// réponse n'est pas relue pour un téléchargement (voir
+    /// `AppState::check_download`, appelé une fois la page chargée).
+    ///
+    /// L'affichage (barre d'URL, titre) n'est jamais modifié ici : une requête
+    /// de premier niveau peut encore être bloquée ou ne jamais être engagée
+    /// (voir `request_navigation`).
+    ///
+    /// SECURITY (V-4): Wrapped with panic safety for FFI boundary protection.
+    fn load_web_resource(&self, webview: WebView, load: WebResourceLoad) {
+        self.guard("load_web_resource", || {
             let request = load.request();
             let url = request.url.as_str();
 
-            // ── Settings save intercept ─────────────────────────────────
-            if crate::settings::is_settings_save_url(url) {
-                if let Some(new_config) = crate::settings::parse_settings_url(url)
-                    && let Err(e) = new_config.save()
-                {
-                    warn!("Failed to save config: {e}");
+            // ── Settings save / clear-data / adblock status intercept ───
+            // Jamais envoyée sur le réseau. Seules les pages internes
+            // peuvent enregistrer, effacer ou reconstruire : leurs liens
+            // portent le jeton de session, qu'une page web ne connaît pas.
+            let clear_data = crate::settings::is_clear_data_url(url);
+            let status_action = StatusAction::from_url(&request.url);
+            if clear_data || status_action.is_some() || crate::settings::is_settings_save_url(url) {
+                if !crate::token::has_token(&request.url, &self.session_token) {
+                    warn!(
+                        url,
+                        "Internal page action not sent by an internal page, ignored"
+                    );
+                    let response = WebResourceResponse::new(request.url.clone());
+                    load.intercept(response).cancel();
+                    return;
+                }
+                if clear_data {
+                    let response = WebResourceResponse::new(request.url.clone());
+                    load.intercept(response).cancel();
+                    self.waker.send(WakerEvent::ClearBrowsingData);
+                    return;
                 }
-                // Cancel the network request
+                if let Some(action) = status_action {
+                    let response = WebResourceResponse::new(request.url.clone());
+                    load.intercept(response).cancel();
+                    self.waker.send(WakerEvent::AdblockStatusAction(action));
+                    return;
+                }
+                // Appliqué sur la config du disque : raccourcis, couleurs…
+                // absents du formulaire sont conservés. Un fichier illisible
+                // ou invalide n'est pas remplacé par les valeurs par défaut.
+                let saved = match Config::load_saved() {
+                    Err(e) => Err(e.to_string()),
+                    Ok(base) => match crate::settings::apply_settings_url(&base, url) {
+                        None => Ok(()),
+                        Some(new_config) => {
+                            new_config.save().map_err(|e| e.to_string()).map(|()| {
+                                // Liste activée ou désactivée : moteur
+                                // reconstruit, nouvelles listes téléchargées
+                                if new_config.privacy.filter_lists != base.privacy.filter_lists {
+                                    let mut live = self.live_config.borrow_mut();
+                                    live.privacy.filter_lists = crate::config::session_filter_lists(
+                                        &live.privacy.filter_lists,
+                                        &base.privacy.filter_lists,
+                                        &new_config.privacy.filter_lists,
+                                    );
+                                    self.set_filter_subscriptions(&live.privacy.filter_lists);
+                                    drop(live);
+                                    self.waker.send(WakerEvent::FilterListsUpdated);
+                                }
+                            })
+                        }
+                    },
+                };
+                let page = match saved {
+                    Ok(()) => crate::settings::generate_saved_html(),
+                    Err(e) => {
+                        warn!(error = %e, "Settings not saved");
+                        crate::settings::generate_save_failed_html(&e)
+                    }
+                };
+                // Confirmation servie comme réponse de la requête interceptée
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static("text/html; charset=utf-8"),
+                );
+                let response = WebResourceResponse::new(request.url.clone()).headers(headers);
+                let intercepted = load.intercept(response);
+                intercepted.send_body_data(page.into_bytes());
+                intercepted.finish();
+                return;
+            }
+
+            // ── Erreur de certificat (page `badcert.html` de SuriBrows) ──
+            if request.is_for_main_frame {
+                let report = crate::cert_error::CertErrorReport::parse(&request.url);
+                let token = crate::cert_error::accept_token(&request.url);
+                if report.is_some() || token.is_some() {
+                    let response = WebResourceResponse::new(request.url.clone());
+                    load.intercept(response).cancel();
+                    if let Some(report) = report {
+                        self.show_cert_error(&webview, report);
+                    } else if let Some(token) = token {
+                        self.accept_cert_exception(&webview, &token);
+                    }
+                    return;
+                }
+            }
+
+            // ── HTTPS uniquement : « continue to the HTTP site » ──
+            if request.is_for_main_frame
+                && let Some(token) = crate::https_only::continue_token(&request.url)
+            {
+                let response = WebResourceResponse::new(request.url.clone());
+                load.intercept(response).cancel();
+                self.continue_over_http(&webview, &token);
+                return;
+            }
+
+            // ── Erreur réseau (page `neterror.html` de SuriBrows) ──
+            if request.is_for_main_frame
+                && let Some(report) = crate::error_page::NetErrorReport::parse(&request.url)
+            {
                 let response = WebResourceResponse::new(request.url.clone());
                 load.intercept(response).cancel();
-                // Show confirmation page
-                let html = crate::settings::generate_saved_html();
-                let encoded = crate::settings::url_encode(&html);
-                let data_url = format!("data:text/html;charset=utf-8,{encoded}");
-                if let Ok(confirm_url) = Url::parse(&data_url)
-                    && let Some(webview) = self.webviews.borrow().last()
+                self.show_net_error(&webview, report);
+                return;
+            }
+
+            // ── Liste de sécurité : avertissement avant un site dangereux ──
+            if request.is_for_main_frame {
+                if let Some(token) = crate::safe_browsing::proceed_token(&request.url) {
+                    let response = WebResourceResponse::new(request.url.clone());
+                    load.intercept(response).cancel();
+                    self.proceed_to_blocked_site(&webview, &token);
+                    return;
+                }
+                if let Some(entry) = self.security_blocklist_entry(&request.url) {
+                    let response = WebResourceResponse::new(request.url.clone());
+                    load.intercept(response).cancel();
+                    self.show_security_warning(&webview, request.url.clone(), &entry);
+                    return;
+                }
+            }
+
+            // ── Réponse d'un formulaire : pas relue pour un téléchargement ──
+            if request.is_for_main_frame {
+                let mut posted = self.posted_pages.borrow_mut();
+                if request.method.as_str() == "GET" {
+                    posted.remove(&webview.id());
+                } else {
+                    posted.insert(webview.id());
+                }
+            }
+
+            // ── Cookies tiers : ceux des sites qui n'ont été que des
+            // ressources tierces sont effacés avant la page suivante (voir
+            // `crate::third_party`). Un cookie tiers déjà posé part quand même
+            // avec la requête : il est seulement signalé.
+            if self.config.privacy.block_third_party_cookies {
+                if request.is_for_main_frame {
+                    self.purge_third_party_cookies(&request.url);
+                } else if let Some(top) = webview.url()
+                    && crate::third_party::is_cross_site(&top, &request.url)
                 {
-                    webview.load(confirm_url);
+                    self.site_roles.borrow_mut().embed(&top, &request.url);
+                    if request.headers.contains_key(COOKIE) {
+                        debug!(url, "Third-party cookie sent before the next purge");
+                    }
                 }
-                return;
             }
 
-            // SECURITY (V-7): Update URL bar immediately for main frame navigations
-            // This reduces (but doesn't eliminate) the TOCTOU window where the displayed
-            // URL doesn't match the loading content.
+            // ── Referer : en-têtes en lecture seule, la politique est appliquée
+            // au document (voir `crate::referrer`). Les requêtes parties avant
+            // sont seulement signalées.
+            if !request.is_for_main_frame
+                && let Some(referer) = request
+                    .headers
+                    .get(REFERER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| Url::parse(value).ok())
+                && crate::referrer::referrer_for(
+                    self.config.privacy.referrer_policy,
+                    &referer,
+                    &request.url,
+                )
+                .as_deref()
+                    != Some(referer.as_str())
+            {
+                debug!(url, "Referrer sent before the page referrer policy applied");
+            }
+
+            let header = |name: &str| {
+                request
+                    .headers
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+            };
+            let headers = RequestHeaders {
+                fetch_dest: header("sec-fetch-dest"),
+                accept: header(ACCEPT.as_str()),
+                upgrade: header(UPGRADE.as_str()),
+                content_type: header(CONTENT_TYPE.as_str()),
+                ping_to: header("ping-to"),
+            };
+            let request_type =
+                RequestType::classify(&request.url, headers, request.is_for_main_frame);
+
+            // ── JavaScript désactivé par site : script vide pour les pages
+            // des hôtes de `[privacy] no_js_hosts` (voir `crate::no_js`).
+            // La navigation principale décide aussi de l'exception `$document`
             if request.is_for_main_frame {
-                // Optimistically update URL bar before the page loads
-                self.urlbar.borrow_mut().set_url(&request.url);
-                let title = &self.config.general.window_title;
-                self.window
-                    .set_title(&format!("{title} — Loading — {}", request.url));
+                self.update_scripts_blocked(&webview, &request.url);
+                self.update_adblock_exception(&webview, &request.url);
+            } else if crate::no_js::blocks(request_type)
+                && self.no_js_pages.borrow().contains(&webview.id())
+                && let Some((content_type, body)) = request_type.blocked_response()
+            {
+                debug!(url, "Script blocked on a no-JS page");
+                let mut headers = HeaderMap::new();
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+                let response = WebResourceResponse::new(request.url.clone()).headers(headers);
+                let intercepted = load.intercept(response);
+                intercepted.send_body_data(body.to_vec());
+                intercepted.finish();
+                return;
+            }
+
+            // ── Économie de données : images, polices ou médias bloqués
+            // partout, y compris sur les pages exemptées par les listes
+            let type_blocking = TypeBlocking {
+                images: self.config.privacy.block_images,
+                fonts: self.config.privacy.block_fonts,
+                media: self.config.privacy.block_media,
+            };
+            if !request.is_for_main_frame && type_blocking.blocks(request_type) {
+                debug!(url, ?request_type, "Request blocked by low-bandwidth mode");
+                let response = WebResourceResponse::new(request.url.clone());
+                match request_type.blocked_response() {
+                    Some((content_type, body)) => {
+                        let mut headers = HeaderMap::new();
+                        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+                        let intercepted = load.intercept(response.headers(headers));
+                        intercepted.send_body_data(body.to_vec());
+                        intercepted.finish();
+                    }
+                    None => load.intercept(response).cancel(),
+                }
+                return;
             }
 
-            // Ad-blocking logic
-            let Some(ref engine) = self.adblock_engine else {
+            // Ad-blocking logic. Page exemptée par une règle `$document` :
+            // rien n'y est filtré
+            if self.is_adblock_excepted(webview.id()) {
+                return;
+            }
+            let Some(engine) = self.adblock_engine.get() else {
                 return;
             };
 
             let source_url = self
-                .current_url
+                .page
                 .borrow()
-                .as_ref()
+                .url()
                 .map(|u| u.to_string())
                 .unwrap_or_default();
-            let request_type = if request.is_for_main_frame {
-                "document"
-            } else {
-                "other"
+            let verdict = engine.check_request(url, &source_url, request_type.as_adblock_str());
+            self.block_counters
+                .record(webview.id(), url, verdict != Verdict::Allow);
+            // Règle `$redirect` : ressource de substitution (script neutre…).
+            // Sinon image, CSS, script : réponse neutre plutôt qu'une erreur
+            // qui casse la mise en page ; documents et types inconnus annulés
+            let replacement = match &verdict {
+                Verdict::Allow => return,
+                Verdict::Redirect(surrogate) => {
+                    Some((surrogate.content_type.as_str(), surrogate.body.as_slice()))
+                }
+                Verdict::Block => request_type.blocked_response(),
             };
-
-            if engine.should_block(url, &source_url, request_type) {
-                debug!(url, "Requête bloquée par adblock");
-                let response = WebResourceResponse::new(request.url.clone());
-                load.intercept(response).cancel();
+            debug!(
+                url,
+                ?request_type,
+                redirected = matches!(verdict, Verdict::Redirect(_)),
+                "Requête bloquée par adblock"
+            );
+            let response = WebResourceResponse::new(request.url.clone());
+            match replacement {
+                Some((content_type, body)) => {
+                    let mut headers = HeaderMap::new();
+                    if let Ok(value) = HeaderValue::from_str(content_type) {
+                        headers.insert(CONTENT_TYPE, value);
+                    }
+                    let intercepted = load.intercept(response.headers(headers));
+                    intercepted.send_body_data(body.to_vec());
+                    intercepted.finish();
+                }
+                None => load.intercept(response).cancel(),
             }
-        }));
-        // Panic recovery: if RefCell borrow fails or adblock panics, silently continue
-        // This prevents crashes but allows the request to proceed (fail-open for safety)
+            // Mettre à jour le badge du chrome
+            self.redraw_chrome();
+        });
+        // Panic recovery: if RefCell borrow fails or adblock panics, the panic
+        // is logged and the request proceeds (fail-open for safety)
+    }
+}
+
+impl From<ConsoleLogLevel> for ConsoleLevel {
+    fn from(level: ConsoleLogLevel) -> Self {
+        match level {
+            ConsoleLogLevel::Log => Self::Log,
+            ConsoleLogLevel::Debug => Self::Debug,
+            ConsoleLogLevel::Info => Self::Info,
+            ConsoleLogLevel::Warn => Self::Warn,
+            ConsoleLogLevel::Error => Self::Error,
+            ConsoleLogLevel::Trace => Self::Trace,
+        }
     }
 }
+
+/// Icône Winit du curseur demandé par la page (`None` = curseur masqué).
+pub fn cursor_icon(cursor: Cursor) -> Option<CursorIcon> {
+    Some(match cursor {
+        Cursor::None => return None,
+        Cursor::Default => CursorIcon::Default,
+        Cursor::Pointer => CursorIcon::Pointer,
+        Cursor::ContextMenu => CursorIcon::ContextMenu,
+        Cursor::Help => CursorIcon::Help,
+        Cursor::Progress => CursorIcon::Progress,
+        Cursor::Wait => CursorIcon::Wait,
+        Cursor::Cell => CursorIcon::Cell,
+        Cursor::Crosshair => CursorIcon::Crosshair,
+        Cursor::Text => CursorIcon::Text,
+        Cursor::VerticalText => CursorIcon::VerticalText,
+        Cursor::Alias => CursorIcon::Alias,
+        Cursor::Copy => CursorIcon::Copy,
+        Cursor::Move => CursorIcon::Move,
+        Cursor::NoDrop => CursorIcon::NoDrop,
+        Cursor::NotAllowed => CursorIcon::NotAllowed,
+        Cursor::Grab => CursorIcon::Grab,
+        Cursor::Grabbing => CursorIcon::Grabbing,
+        Cursor::EResize => CursorIcon::EResize,
+        Cursor::NResize => CursorIcon::NResize,
+        Cursor::NeResize => CursorIcon::NeResize,
+        Cursor::NwResize => CursorIcon::NwResize,
+        Cursor::SResize => CursorIcon::SResize,
+        Cursor::SeResize => CursorIcon::SeResize,
+        Cursor::SwResize => CursorIcon::SwResize,
+        Cursor::WResize => CursorIcon::WResize,
+        Cursor::EwResize => CursorIcon::EwResize,
+        Cursor::NsResize => CursorIcon::NsResize,
+        Cursor::NeswResize => CursorIcon::NeswResize,
+        Cursor::NwseResize => CursorIcon::NwseResize,
+        Cursor::ColResize => CursorIcon::ColResize,
+        Cursor::RowResize => CursorIcon::RowResize,
+        Cursor::AllScroll => CursorIcon::AllScroll,
+        Cursor::ZoomIn => CursorIcon::ZoomIn,
+        Cursor::ZoomOut => CursorIcon::ZoomOut,
+    })
+}
+
+/// Copie des pixels d'un favicon décodé par Servo, envoyée au thread de
+/// conversion.
+struct FaviconSource {
+    width: u32,
+    height: u32,
+    format: embedder_traits::PixelFormat,
+    data: Vec<u8>,
+}
+
+impl FaviconSource {
+    /// Convertit les pixels en [`Favicon`] RGBA.
+    fn decode(self) -> Result<Favicon, FaviconError> {
+        favicon_from_servo(self.width, self.height, self.format, &self.data)
+    }
+}
+
+/// Convertit une image décodée par Servo en [`Favicon`] RGBA.
+fn favicon_from_servo(
+    width: u32,
+    height: u32,
+    format: embedder_traits::PixelFormat,
+    data: &[u8],
+) -> Result<Favicon, FaviconError> {
+    use embedder_traits::PixelFormat;
+
+    let rgba: Vec<u8> = match format {
+        PixelFormat::RGBA8 => data.to_vec(),
+        PixelFormat::BGRA8 => data
+            .chunks_exact(4)
+            .flat_map(|p| [p[2], p[1], p[0], p[3]])
+            .collect(),
+        PixelFormat::RGB8 => data
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        PixelFormat::KA8 => data
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        PixelFormat::K8 => data.iter().flat_map(|&k| [k, k, k, 255]).collect(),
+    };
+    Favicon::from_rgba(width, height, rgba)
+}
@@ -1,10 +1,12 @@
 //! HTML settings page generation and save URL intercept.
 //!
 //! Generates a dark-themed settings form rendered by Servo via `data:` URLs.
-//! The save action is intercepted in [`crate::servo_glue`] via the
-//! `suribrows.settings` domain pattern.
+//! The save and "clear browsing data" actions are intercepted in
+//! [`crate::servo_glue`] via the `suribrows.settings` domain pattern. Both
+//! URLs carry the per-session token the page was generated with, so a web
+//! page can neither rewrite the configuration nor clear browsing data.
 
-use crate::config::Config;
+use crate::config::{Config, FilterListConfig};
 
 /// Domain used for the settings save action (intercepted in load_web_resource).
 const SAVE_DOMAIN: &str = "suribrows.settings";
@@ -15,14 +17,32 @@ pub fn is_settings_save_url(url: &str) -> bool {
         || url.starts_with(&format!("https://{SAVE_DOMAIN}/save"))
 }
 
+/// Returns `true` if the URL is the "Clear Browsing Data" button's request.
+pub fn is_clear_data_url(url: &str) -> bool {
+    ["http", "https"].iter().any(|scheme| {
+        url.strip_prefix(&format!("{scheme}://{SAVE_DOMAIN}/clear-data"))
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('?'))
+    })
+}
+
 /// Extracts query params from a save URL and builds a Config.
 pub fn parse_settings_url(url: &str) -> Option<Config> {
     let query = url.split('?').nth(1)?;
     Some(Config::from_query_params(query))
 }
 
+/// Applies the query params of a save URL on top of `base` (the config on
+/// disk), so settings the form does not show are kept.
+pub fn apply_settings_url(base: &Config, url: &str) -> Option<Config> {
+    let query = url.split('?').nth(1)?;
+    let mut config = base.clone();
+    config.apply_query_params(query);
+    Some(config)
+}
+
 /// Generates the settings HTML page with current config values pre-filled.
-pub fn generate_settings_html(config: &Config) -> String {
+/// `token` is the session token checked on the save and clear-data URLs.
+pub fn generate_settings_html(config: &Config, token: &str) -> String {
     let c = config;
     format!(
         r#"<!DOCTYPE html>
@@ -75,6 +95,7 @@ button {{
 .btn-cancel {{ background: #333; color: #e0e0e0; }}
 .btn-cancel:hover {{ background: #444; }}
 .note {{ font-size: 12px; color: #666; margin-top: 4px; }}
+.toggle small {{ color: #666; }}
 </style>
 </head>
 <body>
@@ -83,8 +104,24 @@ button {{
 <h2>General</h2>
 <label><span>Default URL</span>
 <input type="text" id="default_url" value="{default_url}"></label>
-<label><span>Window Title</span>
+<label><span>Homepage</span>
+<input type="text" id="homepage" value="{homepage}"></label>
+<label><span>Window Title ({{title}} = page title)</span>
 <input type="text" id="window_title" value="{window_title}"></label>
+<label><span>Download Folder (empty = system Downloads)</span>
+<input type="text" id="download_dir" value="{download_dir}"></label>
+<label><span>DevTools Port (0 = disabled, F12 for instructions; restart required)</span>
+<input type="number" id="devtools_port" value="{devtools_port}" min="0" max="65535"></label>
+<div class="toggle"><span>Mouse Back/Forward Buttons Navigate</span>
+<input type="checkbox" id="mouse_history_buttons" {mouse_history_buttons_checked}></div>
+<label><span>Scroll Speed (px per wheel notch)</span>
+<input type="number" id="scroll_line_height" value="{scroll_line_height}" min="1" max="400" step="any"></label>
+<div class="toggle"><span>Invert Scrolling (natural)</span>
+<input type="checkbox" id="invert_scroll" {invert_scroll_checked}></div>
+<div class="toggle"><span>Log Page Console Messages</span>
+<input type="checkbox" id="log_console" {log_console_checked}></div>
+<div class="toggle"><span>Quit When the Last Page Closes Itself</span>
+<input type="checkbox" id="exit_on_last_close" {exit_on_last_close_checked}></div>
 
 <h2>Window</h2>
 <label><span>Width</span>
@@ -118,6 +155,15 @@ button {{
 <h2>Privacy</h2>
 <div class="toggle"><span>Enforce TLS (HTTPS)</span>
 <input type="checkbox" id="enforce_tls" {enforce_tls_checked}></div>
+<div class="toggle"><span>HTTPS-Only Mode (Upgrade HTTP Links)</span>
+<input type="checkbox" id="https_only" {https_only_checked}></div>
+<div class="toggle"><span>Clear Third-Party Cookies</span>
+<input type="checkbox" id="block_third_party_cookies" {block_third_party_cookies_checked}></div>
+<p class="note">Deletes the cookies of sites only seen embedded in other pages this session. A site you logged into in an earlier session can lose its login.</p>
+<div class="toggle"><span>Send Global Privacy Control</span>
+<input type="checkbox" id="send_gpc" {send_gpc_checked}></div>
+<div class="toggle"><span>Send Do Not Track</span>
+<input type="checkbox" id="send_dnt" {send_dnt_checked}></div>
 <div class="toggle"><span>Disable MIME Sniffing</span>
 <input type="checkbox" id="disable_mime_sniff" {disable_mime_sniff_checked}></div>
 <div class="toggle"><span>Disable Geolocation</span>
@@ -128,7 +174,30 @@ button {{
 <input type="checkbox" id="disable_notifications" {disable_notifications_checked}></div>
 <div class="toggle"><span>Disable WebRTC</span>
 <input type="checkbox" id="disable_webrtc" {disable_webrtc_checked}></div>
+<div class="toggle"><span>Ask Before Granting Site Permissions</span>
+<input type="checkbox" id="permission_prompts" {permission_prompts_checked}></div>
+<div class="toggle"><span>Hide Ad Elements (Cosmetic Filtering)</span>
+<input type="checkbox" id="cosmetic_filtering" {cosmetic_filtering_checked}></div>
+<div class="toggle"><span>Block All Images (Low Bandwidth)</span>
+<input type="checkbox" id="block_images" {block_images_checked}></div>
+<div class="toggle"><span>Block Web Fonts (Low Bandwidth)</span>
+<input type="checkbox" id="block_fonts" {block_fonts_checked}></div>
+<div class="toggle"><span>Block Audio and Video (Low Bandwidth)</span>
+<input type="checkbox" id="block_media" {block_media_checked}></div>
+<p class="note">Applies on every site, including sites allowlisted by filter lists.</p>
+<label><span>Filter List Updates (hours, 0 = never)</span>
+<input type="number" id="filter_update_hours" value="{filter_update_hours}" min="0"></label>
 
+<h2>Filter Lists</h2>
+{filter_list_toggles}
+<div class="toggle"><span>Add Regional Lists for My Language at Startup</span>
+<input type="checkbox" id="auto_regional_lists" {auto_regional_lists_checked}></div>
+<p class="note">Regional lists are always added on the first run. Language: <code>[general] locale</code>, or the system locale.</p>
+
+<h2>Browsing Data</h2>
+<div class="toggle"><span>HTTP cache, cookies and site storage</span>
+<button class="btn-cancel" onclick="clearData()">Clear Browsing Data</button></div>
+<p class="note">Open tabs are reloaded afterwards. Shortcut: Ctrl+Shift+Delete.</p>
 <div class="save-bar">
 <button class="btn-save" onclick="save()">Save Settings</button>
 <button class="btn-cancel" onclick="history.back()">Cancel</button>
@@ -138,9 +207,23 @@ button {{
 function enc(s) {{ return encodeURIComponent(s); }}
 function val(id) {{ return document.getElementById(id).value; }}
 function chk(id) {{ return document.getElementById(id).checked; }}
+function lists() {{
+    return Array.from(document.querySelectorAll(".filter-list"))
+        .filter(function (box) {{ return box.checked; }})
+        .map(function (box) {{ return box.dataset.index; }})
+        .join(",");
+}}
 function save() {{
     var q = "default_url=" + enc(val("default_url"))
+        + "&homepage=" + enc(val("homepage"))
         + "&window_title=" + enc(val("window_title"))
+        + "&download_dir=" + enc(val("download_dir"))
+        + "&devtools_port=" + val("devtools_port")
+        + "&mouse_history_buttons=" + chk("mouse_history_buttons")
+        + "&scroll_line_height=" + val("scroll_line_height")
+        + "&invert_scroll=" + chk("invert_scroll")
+        + "&log_console=" + chk("log_console")
+        + "&exit_on_last_close=" + chk("exit_on_last_close")
         + "&window_width=" + val("window_width")
         + "&window_height=" + val("window_height")
         + "&chrome_height=" + val("chrome_height")
@@ -151,18 +234,56 @@ function save() {{
         + "&user_agent=" + enc(val("user_agent"))
         + "&precache_shaders=" + chk("precache_shaders")
         + "&enforce_tls=" + chk("enforce_tls")
+        + "&https_only=" + chk("https_only")
+        + "&block_third_party_cookies=" + chk("block_third_party_cookies")
+        + "&send_gpc=" + chk("send_gpc")
+        + "&send_dnt=" + chk("send_dnt")
         + "&disable_mime_sniff=" + chk("disable_mime_sniff")
         + "&disable_geolocation=" + chk("disable_geolocation")
         + "&disable_bluetooth=" + chk("disable_bluetooth")
         + "&disable_notifications=" + chk("disable_notifications")
-        + "&disable_webrtc=" + chk("disable_webrtc");
-    window.location.href = "http://{save_domain}/save?" + q;
+        + "&disable_webrtc=" + chk("disable_webrtc")
+        + "&permission_prompts=" + chk("permission_prompts")
+        + "&cosmetic_filtering=" + chk("cosmetic_filtering")
+        + "&block_images=" + chk("block_images")
+        + "&block_fonts=" + chk("block_fonts")
+        + "&block_media=" + chk("block_media")
+        + "&filter_update_hours=" + val("filter_update_hours")
+        + "&auto_regional_lists=" + chk("auto_regional_lists")
+        + "&filter_lists_enabled=" + lists();
+    window.location.href = "http://{save_domain}/save?token={token}&" + q;
+}}
+function clearData() {{
+    if (confirm("Clear the HTTP cache, cookies and site storage?")) {{
+        window.location.href = "http://{save_domain}/clear-data?token={token}";
+    }}
 }}
 </script>
 </body>
 </html>"#,
+        token = url_encode(token),
         default_url = html_escape(&c.general.default_url),
+        homepage = html_escape(&c.general.homepage),
         window_title = html_escape(&c.general.window_title),
+        download_dir = html_escape(&c.general.download_dir),
+        devtools_port = c.general.devtools_port,
+        mouse_history_buttons_checked = if c.general.mouse_history_buttons {
+            "checked"
+        } else {
+            ""
+        },
+        scroll_line_height = c.general.scroll_line_height,
+        invert_scroll_checked = if c.general.invert_scroll {
+            "checked"
+        } else {
+            ""
+        },
+        log_console_checked = if c.general.log_console { "checked" } else { "" },
+        exit_on_last_close_checked = if c.general.exit_on_last_close {
+            "checked"
+        } else {
+            ""
+        },
         window_width = c.window.width,
         window_height = c.window.height,
         chrome_height = c.chrome.height,
@@ -202,10 +323,74 @@ function save() {{
         } else {
             ""
         },
+        permission_prompts_checked = if c.privacy.permission_prompts {
+            "checked"
+        } else {
+            ""
+        },
+        cosmetic_filtering_checked = if c.privacy.cosmetic_filtering {
+            "checked"
+        } else {
+            ""
+        },
+        https_only_checked = if c.privacy.https_only { "checked" } else { "" },
+        block_third_party_cookies_checked = if c.privacy.block_third_party_cookies {
+            "checked"
+        } else {
+            ""
+        },
+        send_gpc_checked = if c.privacy.send_gpc { "checked" } else { "" },
+        send_dnt_checked = if c.privacy.send_dnt { "checked" } else { "" },
+        block_images_checked = if c.privacy.block_images {
+            "checked"
+        } else {
+            ""
+        },
+        block_fonts_checked = if c.privacy.block_fonts { "checked" } else { "" },
+        block_media_checked = if c.privacy.block_media { "checked" } else { "" },
+        filter_update_hours = c.privacy.filter_update_hours,
+        filter_list_toggles = filter_list_toggles(&c.privacy.filter_lists),
+        auto_regional_lists_checked = if c.privacy.auto_regional_lists {
+            "checked"
+        } else {
+            ""
+        },
         save_domain = SAVE_DOMAIN,
     )
 }
 
+/// One checkbox per filter list subscription, identified by its index.
+fn filter_list_toggles(lists: &[FilterListConfig]) -> String {
+    lists
+        .iter()
+        .enumerate()
+        .map(|(index, list)| {
+            let label = [Some(&list.name), Some(&list.url), list.file.as_ref()]
+                .into_iter()
+                .flatten()
+                .find(|label| !label.is_empty())
+                .map_or("", String::as_str);
+            let auto = list
+                .auto_enabled_for
+                .as_deref()
+                .map(|language| {
+                    format!(
+                        " <small>(auto-enabled for language: {})</small>",
+                        html_escape(language)
+                    )
+                })
+                .unwrap_or_default();
+            format!(
+                "<div class=\"toggle\"><span>{}{auto}</span>\n\
+                 <input type=\"checkbox\" class=\"filter-list\" id=\"filter_list_{index}\" \
+                 data-index=\"{index}\" {}></div>\n",
+                html_escape(label),
+                if list.enabled { "checked" } else { "" },
+            )
+        })
+        .collect()
+}
+
 /// Generates a confirmation page shown after settings are saved.
 pub fn generate_saved_html() -> String {
     r#"<!DOCTYPE html>
@@ -232,6 +417,20 @@ p { font-size: 16px; color: #888; }
         .to_string()
 }
 
+/// Generates the page shown when settings could not be saved: `error`
+/// explains why (e.g. config.toml is invalid and was left untouched).
+pub fn generate_save_failed_html(error: &str) -> String {
+    crate::pages::shell(
+        "Settings Not Saved",
+        "",
+        &format!(
+            "<h1>Settings not saved</h1>\n<p class=\"detail\">{}</p>\n\
+             <p>Fix the configuration file, then save again.</p>",
+            html_escape(error)
+        ),
+    )
+}
+
 /// Percent-encodes a string for safe embedding in data: URLs.
 pub fn url_encode(input: &str) -> String {
     let mut result = String::with_capacity(input.len() * 3);
@@ -262,7 +461,7 @@ pub fn url_encode(input: &str) -> String {
 const HEX_CHARS: &[u8; 16] = b"0123456789ABCDEF";
 
 /// Escapes HTML special characters in attribute values.
-fn html_escape(s: &str) -> String {
+pub(crate) fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('"', "&quot;")
         .replace('<', "&lt;")
@@ -272,6 +471,7 @@ fn html_escape(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::TempDir;
 
     #[test]
     fn test_is_settings_save_url() {
@@ -285,6 +485,28 @@ mod tests {
         assert!(!is_settings_save_url("http://suribrows.settings/other"));
     }
 
+    #[test]
+    fn test_save_url_carries_session_token() {
+        let html = generate_settings_html(&Config::default(), "abc123");
+        assert!(html.contains(r#""http://suribrows.settings/save?token=abc123&" + q"#));
+    }
+
+    #[test]
+    fn test_is_clear_data_url() {
+        assert!(is_clear_data_url("http://suribrows.settings/clear-data"));
+        assert!(is_clear_data_url("https://suribrows.settings/clear-data?"));
+        assert!(!is_clear_data_url(
+            "http://suribrows.settings/clear-data-now"
+        ));
+        assert!(!is_clear_data_url("http://suribrows.settings/save?x=1"));
+        assert!(!is_clear_data_url("https://example.com/clear-data"));
+
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(
+            r#"window.location.href = "http://suribrows.settings/clear-data?token=tok";"#
+        ));
+    }
+
     #[test]
     fn test_parse_settings_url() {
         let url = "http://suribrows.settings/save?window_width=1920&enforce_tls=false";
@@ -301,7 +523,7 @@ mod tests {
     #[test]
     fn test_generate_settings_html_contains_values() {
         let config = Config::default();
-        let html = generate_settings_html(&config);
+        let html = generate_settings_html(&config, "tok");
         assert!(html.contains("https://example.com"));
         assert!(html.contains("SuriBrows"));
         assert!(html.contains("1280"));
@@ -309,12 +531,287 @@ mod tests {
         assert!(html.contains("duckduckgo"));
     }
 
+    #[test]
+    fn test_homepage_round_trips_through_settings_page() {
+        let mut config = Config::default();
+        config.general.homepage = "https://servo.org/".to_string();
+        let html = generate_settings_html(&config, "tok");
+        assert!(html.contains(r#"id="homepage" value="https://servo.org/""#));
+        assert!(html.contains(r#""&homepage=" + enc(val("homepage"))"#));
+
+        let url = "http://suribrows.settings/save?homepage=https%3A%2F%2Fservo.org%2F";
+        let saved = parse_settings_url(url).unwrap();
+        assert_eq!(saved.general.homepage, "https://servo.org/");
+    }
+
+    #[test]
+    fn test_mouse_history_buttons_toggle() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="mouse_history_buttons" checked"#));
+
+        let url = "http://suribrows.settings/save?mouse_history_buttons=false";
+        let saved = parse_settings_url(url).unwrap();
+        assert!(!saved.general.mouse_history_buttons);
+    }
+
+    #[test]
+    fn test_permission_prompts_toggle() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="permission_prompts" >"#));
+
+        let url = "http://suribrows.settings/save?permission_prompts=true";
+        let saved = parse_settings_url(url).unwrap();
+        assert!(saved.privacy.permission_prompts);
+    }
+
+    #[test]
+    fn test_exit_on_last_close_toggle() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="exit_on_last_close" >"#));
+
+        let url = "http://suribrows.settings/save?exit_on_last_close=true";
+        let saved = parse_settings_url(url).unwrap();
+        assert!(saved.general.exit_on_last_close);
+    }
+
+    #[test]
+    fn test_cosmetic_filtering_toggle() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="cosmetic_filtering" checked>"#));
+
+        let url = "http://suribrows.settings/save?cosmetic_filtering=false";
+        let saved = parse_settings_url(url).unwrap();
+        assert!(!saved.privacy.cosmetic_filtering);
+    }
+
+    #[test]
+    fn test_https_only_toggle() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="https_only" checked>"#));
+
+        let url = "http://suribrows.settings/save?https_only=false";
+        let saved = parse_settings_url(url).unwrap();
+        assert!(!saved.privacy.https_only);
+    }
+
+    #[test]
+    fn test_block_third_party_cookies_toggle() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="block_third_party_cookies" >"#));
+
+        let url = "http://suribrows.settings/save?block_third_party_cookies=true";
+        let saved = parse_settings_url(url).unwrap();
+        assert!(saved.privacy.block_third_party_cookies);
+    }
+
+    #[test]
+    fn test_privacy_signals_toggles() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="send_gpc" checked>"#));
+        assert!(html.contains(r#"id="send_dnt" >"#));
+
+        let url = "http://suribrows.settings/save?send_gpc=false&send_dnt=true";
+        let saved = parse_settings_url(url).unwrap();
+        assert!(!saved.privacy.send_gpc);
+        assert!(saved.privacy.send_dnt);
+    }
+
+    #[test]
+    fn test_filter_update_hours_roundtrip() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="filter_update_hours" value="24""#));
+
+        let url = "http://suribrows.settings/save?filter_update_hours=0";
+        assert_eq!(
+            parse_settings_url(url).unwrap().privacy.filter_update_hours,
+            0
+        );
+        let url = "http://suribrows.settings/save?filter_update_hours=-1";
+        assert_eq!(
+            parse_settings_url(url).unwrap().privacy.filter_update_hours,
+            24
+        );
+    }
+
+    #[test]
+    fn test_filter_list_checkboxes() {
+        let mut config = Config::default();
+        config.privacy.filter_lists[0].name = "Easy<List>".to_string();
+        let html = generate_settings_html(&config, "tok");
+        assert!(html.contains(
+            "<span>Easy&lt;List&gt;</span>\n<input type=\"checkbox\" class=\"filter-list\" \
+             id=\"filter_list_0\" data-index=\"0\" checked>"
+        ));
+        assert!(html.contains(r#"id="filter_list_2" data-index="2" >"#));
+
+        // Applied on top of the lists on disk
+        let url = "http://suribrows.settings/save?filter_lists_enabled=2";
+        let saved = apply_settings_url(&config, url).unwrap();
+        let enabled: Vec<bool> = saved
+            .privacy
+            .filter_lists
+            .iter()
+            .map(|list| list.enabled)
+            .collect();
+        assert_eq!(enabled, [false, false, true]);
+        assert_eq!(saved.privacy.filter_lists[0].name, "Easy<List>");
+    }
+
+    #[test]
+    fn test_regional_lists_shown_as_auto_enabled() {
+        let mut config = Config::default();
+        crate::regional_lists::merge(&mut config.privacy.filter_lists, "fr");
+        let html = generate_settings_html(&config, "tok");
+        assert!(html.contains(
+            "<span>Liste FR <small>(auto-enabled for language: fr)</small></span>\n\
+             <input type=\"checkbox\" class=\"filter-list\" id=\"filter_list_3\" \
+             data-index=\"3\" checked>"
+        ));
+        assert!(!html.contains("<span>EasyList <small>"));
+        assert!(html.contains(r#"id="auto_regional_lists" >"#));
+
+        let url = "http://suribrows.settings/save?auto_regional_lists=true&filter_lists_enabled=0";
+        let saved = apply_settings_url(&config, url).unwrap();
+        assert!(saved.privacy.auto_regional_lists);
+        // Disabled by hand, the list still shows where it came from
+        assert!(!saved.privacy.filter_lists[3].enabled);
+        assert_eq!(
+            saved.privacy.filter_lists[3].auto_enabled_for.as_deref(),
+            Some("fr")
+        );
+    }
+
+    #[test]
+    fn test_scroll_settings_roundtrip() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="scroll_line_height" value="38""#));
+        assert!(html.contains(r#"id="invert_scroll" >"#));
+
+        let url = "http://suribrows.settings/save?scroll_line_height=50.5&invert_scroll=true";
+        let saved = parse_settings_url(url).unwrap();
+        assert_eq!(saved.general.scroll_line_height, 50.5);
+        assert!(saved.general.invert_scroll);
+
+        // Non-positive values keep the default
+        let url = "http://suribrows.settings/save?scroll_line_height=-3";
+        let saved = parse_settings_url(url).unwrap();
+        assert_eq!(saved.general.scroll_line_height, 38.0);
+    }
+
+    #[test]
+    fn test_low_bandwidth_toggles_roundtrip() {
+        let mut config = Config::default();
+        config.privacy.block_images = true;
+        let html = generate_settings_html(&config, "tok");
+        assert!(html.contains(r#"id="block_images" checked>"#));
+        assert!(html.contains(r#"id="block_fonts" >"#));
+        assert!(html.contains(r#"+ "&block_media=" + chk("block_media")"#));
+
+        let url = "http://suribrows.settings/save?block_fonts=true&block_media=true";
+        let parsed = parse_settings_url(url).unwrap();
+        assert!(!parsed.privacy.block_images);
+        assert!(parsed.privacy.block_fonts);
+        assert!(parsed.privacy.block_media);
+    }
+
+    #[test]
+    fn test_log_console_roundtrip() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="log_console" >"#));
+
+        let url = "http://suribrows.settings/save?log_console=true";
+        assert!(parse_settings_url(url).unwrap().general.log_console);
+    }
+
+    /// Value attribute of the `<input id="{id}">` of a generated page.
+    fn input_value(html: &str, id: &str) -> String {
+        let attribute = format!(r#"id="{id}" value=""#);
+        let start = html.find(&attribute).unwrap() + attribute.len();
+        html[start..].split('"').next().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_save_roundtrip_keeps_env_overrides_out_of_file() {
+        let dir = TempDir::new("settings-env");
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "[general]\nhomepage = \"https://disk.example/\"\n").unwrap();
+        let disk = Config::load_from(Some(&path)).unwrap();
+        let mut effective = disk.clone();
+        let errors = effective.apply_env_overrides([(
+            "SURIBROWS_GENERAL_HOMEPAGE".to_string(),
+            "https://env.example/".to_string(),
+        )]);
+        assert!(errors.is_empty());
+        assert_eq!(effective.general.homepage, "https://env.example/");
+
+        // Page rendered from the file, submitted unchanged
+        let html = generate_settings_html(&disk, "tok");
+        let homepage = input_value(&html, "homepage");
+        assert_eq!(homepage, "https://disk.example/");
+        let url = format!(
+            "http://suribrows.settings/save?token=tok&homepage={}",
+            url_encode(&homepage)
+        );
+        apply_settings_url(&disk, &url)
+            .unwrap()
+            .save_to(&path)
+            .unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("https://disk.example/"));
+        assert!(!saved.contains("env.example"));
+    }
+
+    #[test]
+    fn test_save_url_roundtrip_keeps_unlisted_settings() {
+        let mut base = Config::default();
+        base.keybindings.insert(
+            "reload".to_string(),
+            crate::config::KeyChords::One("Ctrl+Shift+R".to_string()),
+        );
+        base.chrome.colors.text = [0.1, 0.2, 0.3, 1.0];
+
+        let url =
+            "http://suribrows.settings/save?homepage=https%3A%2F%2Fexample.org&invert_scroll=true";
+        let saved = apply_settings_url(&base, url).unwrap();
+
+        let dir = TempDir::new("settings");
+        let path = dir.join("config.toml");
+        saved.save_to(&path).unwrap();
+        let reloaded: Config = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(reloaded.general.homepage, "https://example.org");
+        assert!(reloaded.general.invert_scroll);
+        assert_eq!(reloaded.chrome.colors.text, [0.1, 0.2, 0.3, 1.0]);
+        let chords: Vec<&str> = reloaded.keybindings["reload"].iter().collect();
+        assert_eq!(chords, ["Ctrl+Shift+R"]);
+    }
+
+    #[test]
+    fn test_devtools_port_roundtrip() {
+        let html = generate_settings_html(&Config::default(), "tok");
+        assert!(html.contains(r#"id="devtools_port" value="0""#));
+
+        let url = "http://suribrows.settings/save?devtools_port=6080";
+        assert_eq!(parse_settings_url(url).unwrap().general.devtools_port, 6080);
+        // Out of range: keep the default
+        let url = "http://suribrows.settings/save?devtools_port=70000";
+        assert_eq!(parse_settings_url(url).unwrap().general.devtools_port, 0);
+    }
+
     #[test]
     fn test_generate_saved_html_not_empty() {
         let html = generate_saved_html();
         assert!(html.contains("Settings saved"));
     }
 
+    #[test]
+    fn test_generate_save_failed_html_escapes_error() {
+        let html = generate_save_failed_html("invalid config file <x>: expected `=`");
+        assert!(html.contains("Settings not saved"));
+        assert!(html.contains("invalid config file &lt;x&gt;"));
+    }
+
     #[test]
     fn test_url_encode_basic() {
         assert_eq!(url_encode("hello"), "hello");
@@ -0,0 +1,57 @@
+//! Outils communs aux tests unitaires.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Dossier temporaire propre à un test, supprimé avec son contenu quand la
+/// garde sort de portée (même si le test échoue).
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Crée un dossier vide `suribrows-<name>-<pid>-<n>` dans le dossier
+    /// temporaire du système ; `n` sépare les tests lancés en parallèle.
+    pub fn new(name: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let path =
+            std::env::temp_dir().join(format!("suribrows-{name}-{}-{n}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Chemin de `name` dans le dossier.
+    pub fn join(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_dir_removed_on_drop() {
+        let first = TempDir::new("temp-dir");
+        let second = TempDir::new("temp-dir");
+        assert_ne!(first.path(), second.path());
+        fs::write(first.join("file.txt"), "x").unwrap();
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+        assert!(second.path().is_dir());
+    }
+}
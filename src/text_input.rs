@@ -0,0 +1,381 @@
+//! Champ de saisie sur une ligne — texte, curseur, « tout sélectionner » et
+//! composition IME.
+//!
+//! Machinerie d'édition partagée par la barre d'URL et la barre de recherche
+//! dans la page. Aucune dépendance graphique — ce module est purement logique.
+
+use std::ops::Range;
+
+/// Texte éditable avec curseur.
+///
+/// Juste après un focus, tout le texte est sélectionné : la première frappe
+/// (ou Backspace/Delete) remplace tout le contenu, une flèche annule la
+/// sélection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    /// Texte saisi.
+    text: String,
+    /// Position du curseur en offset d'octets dans `text`.
+    cursor: usize,
+    /// `true` si tout le texte est sélectionné.
+    all_selected: bool,
+    /// Composition IME en cours (pré-édition), affichée au curseur mais pas
+    /// encore insérée.
+    preedit: String,
+    /// Curseur dans `preedit`, en octets (`None` = curseur masqué par l'IME).
+    preedit_cursor: Option<usize>,
+}
+
+/// Texte tel qu'affiché : saisie et composition IME en cours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Composed {
+    pub text: String,
+    /// Curseur, en caractères.
+    pub cursor: usize,
+    /// Caractères de la composition IME (à souligner), s'il y en a une.
+    pub preedit: Option<Range<usize>>,
+}
+
+impl TextInput {
+    /// Remplace le texte ; curseur à la fin, sans sélection.
+    pub fn set_text(&mut self, text: String) {
+        self.cursor = text.len();
+        self.text = text;
+        self.all_selected = false;
+    }
+
+    /// Vide le champ.
+    pub fn clear(&mut self) {
+        self.set_text(String::new());
+    }
+
+    /// Sélectionne tout le texte (curseur à la fin).
+    pub fn select_all(&mut self) {
+        self.all_selected = true;
+        self.cursor = self.text.len();
+    }
+
+    /// `true` si tout le texte est sélectionné.
+    pub fn is_all_selected(&self) -> bool {
+        self.all_selected
+    }
+
+    /// Texte sélectionné (Ctrl+C), `None` sans sélection.
+    pub fn selected_text(&self) -> Option<&str> {
+        (self.all_selected && !self.text.is_empty()).then_some(self.text.as_str())
+    }
+
+    /// Colle `s` au curseur (Ctrl+V). Le champ tient sur une ligne : les
+    /// retours à la ligne et tabulations deviennent des espaces, les autres
+    /// caractères de contrôle sont retirés.
+    pub fn paste(&mut self, s: &str) {
+        let line: String = s
+            .trim_end_matches(['\r', '\n'])
+            .chars()
+            .filter_map(|c| match c {
+                '\r' | '\n' | '\t' => Some(' '),
+                c if c.is_control() => None,
+                c => Some(c),
+            })
+            .collect();
+        self.insert_str(&line);
+    }
+
+    /// Efface la sélection totale si elle est active ; retourne `true` si
+    /// c'était le cas.
+    fn take_selection(&mut self) -> bool {
+        if self.all_selected {
+            self.text.clear();
+            self.cursor = 0;
+            self.all_selected = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Insère un caractère à la position du curseur.
+    /// Si tout est sélectionné, remplace tout le texte d'abord.
+    pub fn insert_char(&mut self, c: char) {
+        self.take_selection();
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Insère une chaîne (texte validé par l'IME) à la position du curseur
+    /// et termine la composition.
+    pub fn insert_str(&mut self, s: &str) {
+        self.take_selection();
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+        self.set_preedit(String::new(), None);
+    }
+
+    /// Remplace la composition IME en cours (`cursor` en octets dans `text`).
+    /// Un texte vide termine la composition sans rien insérer.
+    pub fn set_preedit(&mut self, text: String, cursor: Option<usize>) {
+        self.preedit_cursor = cursor.filter(|&c| text.is_char_boundary(c));
+        self.preedit = text;
+    }
+
+    /// `true` pendant une composition IME : les touches vont à l'IME.
+    pub fn is_composing(&self) -> bool {
+        !self.preedit.is_empty()
+    }
+
+    /// Texte affiché, composition IME comprise. Si tout est sélectionné, la
+    /// composition remplacera le texte : elle est affichée seule.
+    pub fn composed(&self) -> Composed {
+        if self.preedit.is_empty() {
+            return Composed {
+                text: self.text.clone(),
+                cursor: self.cursor_char_offset(),
+                preedit: None,
+            };
+        }
+        let (before, after) = if self.all_selected {
+            ("", "")
+        } else {
+            self.text.split_at(self.cursor)
+        };
+        let start = before.chars().count();
+        let len = self.preedit.chars().count();
+        let cursor_in_preedit = self
+            .preedit_cursor
+            .map_or(len, |c| self.preedit[..c].chars().count());
+        Composed {
+            text: format!("{before}{}{after}", self.preedit),
+            cursor: start + cursor_in_preedit,
+            preedit: Some(start..start + len),
+        }
+    }
+
+    /// Supprime le caractère avant le curseur (Backspace).
+    pub fn backspace(&mut self) {
+        // Select-all + backspace = tout effacer
+        if self.take_selection() {
+            return;
+        }
+        if self.cursor > 0 {
+            // Reculer au début du caractère précédent
+            let prev = self.prev_boundary();
+            self.text.drain(prev..self.cursor);
+            self.cursor = prev;
+        }
+    }
+
+    /// Supprime le caractère après le curseur (Delete).
+    pub fn delete(&mut self) {
+        if self.take_selection() {
+            return;
+        }
+        if self.cursor < self.text.len() {
+            let next = self.next_boundary();
+            self.text.drain(self.cursor..next);
+        }
+    }
+
+    /// Déplace le curseur d'un caractère vers la gauche.
+    /// Depuis une sélection totale, va au début du texte.
+    pub fn move_cursor_left(&mut self) {
+        if self.all_selected {
+            self.all_selected = false;
+            self.cursor = 0;
+            return;
+        }
+        self.cursor = self.prev_boundary();
+    }
+
+    /// Déplace le curseur d'un caractère vers la droite.
+    /// Depuis une sélection totale, reste à la fin du texte.
+    pub fn move_cursor_right(&mut self) {
+        if self.all_selected {
+            self.all_selected = false;
+            return;
+        }
+        self.cursor = self.next_boundary();
+    }
+
+    /// Place le curseur au début du texte (Home).
+    pub fn home(&mut self) {
+        self.all_selected = false;
+        self.cursor = 0;
+    }
+
+    /// Place le curseur à la fin du texte (End).
+    pub fn end(&mut self) {
+        self.all_selected = false;
+        self.cursor = self.text.len();
+    }
+
+    /// Place le curseur après le `offset`-ième caractère (clic dans le champ).
+    /// L'offset est borné à la longueur du texte.
+    pub fn set_cursor_char_offset(&mut self, offset: usize) {
+        self.all_selected = false;
+        self.cursor = self
+            .text
+            .char_indices()
+            .nth(offset)
+            .map_or(self.text.len(), |(i, _)| i);
+    }
+
+    /// Texte saisi.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Position du curseur en octets.
+    pub fn cursor_pos(&self) -> usize {
+        self.cursor
+    }
+
+    /// Nombre de caractères avant le curseur (pour le rendu).
+    pub fn cursor_char_offset(&self) -> usize {
+        self.text[..self.cursor].chars().count()
+    }
+
+    /// Offset du début du caractère précédant le curseur.
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Offset de la fin du caractère suivant le curseur.
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .char_indices()
+            .nth(1)
+            .map_or(self.text.len(), |(i, _)| self.cursor + i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        input.set_text(text.to_string());
+        input
+    }
+
+    #[test]
+    fn test_insert_at_cursor() {
+        let mut input = input("ac");
+        input.move_cursor_left();
+        input.insert_char('b');
+        assert_eq!(input.text(), "abc");
+        assert_eq!(input.cursor_char_offset(), 2);
+    }
+
+    #[test]
+    fn test_select_all_then_type_replaces() {
+        let mut input = input("old");
+        input.select_all();
+        input.insert_char('n');
+        assert_eq!(input.text(), "n");
+        assert!(!input.is_all_selected());
+    }
+
+    #[test]
+    fn test_select_all_then_backspace_clears() {
+        let mut input = input("old");
+        input.select_all();
+        input.backspace();
+        assert_eq!(input.text(), "");
+        assert_eq!(input.cursor_pos(), 0);
+    }
+
+    #[test]
+    fn test_arrows_cancel_selection() {
+        let mut input = input("abc");
+        input.select_all();
+        input.move_cursor_left();
+        assert!(!input.is_all_selected());
+        assert_eq!(input.cursor_pos(), 0);
+
+        input.select_all();
+        input.move_cursor_right();
+        assert!(!input.is_all_selected());
+        assert_eq!(input.cursor_pos(), 3);
+    }
+
+    #[test]
+    fn test_multibyte_editing() {
+        let mut input = input("héllo");
+        input.home();
+        input.move_cursor_right();
+        input.move_cursor_right();
+        assert_eq!(input.cursor_pos(), "hé".len());
+        input.backspace();
+        assert_eq!(input.text(), "hllo");
+        input.delete();
+        assert_eq!(input.text(), "hlo");
+    }
+
+    #[test]
+    fn test_insert_str_ends_composition() {
+        let mut input = input("ab");
+        input.move_cursor_left();
+        input.set_preedit("にほ".to_string(), Some("に".len()));
+        assert!(input.is_composing());
+        assert_eq!(input.text(), "ab");
+
+        input.insert_str("日本");
+        assert!(!input.is_composing());
+        assert_eq!(input.text(), "a日本b");
+        assert_eq!(input.cursor_char_offset(), 3);
+    }
+
+    #[test]
+    fn test_composed_inserts_preedit_at_cursor() {
+        let mut input = input("ab");
+        input.move_cursor_left();
+        input.set_preedit("にほ".to_string(), Some("に".len()));
+        let composed = input.composed();
+        assert_eq!(composed.text, "aにほb");
+        assert_eq!(composed.cursor, 2);
+        assert_eq!(composed.preedit, Some(1..3));
+
+        // Sans curseur IME : curseur en fin de composition
+        input.set_preedit("にほ".to_string(), None);
+        assert_eq!(input.composed().cursor, 3);
+
+        input.set_preedit(String::new(), None);
+        assert_eq!(input.composed().text, "ab");
+        assert_eq!(input.composed().preedit, None);
+    }
+
+    #[test]
+    fn test_composed_replaces_selection() {
+        let mut input = input("https://example.com");
+        input.select_all();
+        input.set_preedit("検索".to_string(), None);
+        assert_eq!(input.composed().text, "検索");
+        input.insert_str("検索");
+        assert_eq!(input.text(), "検索");
+    }
+
+    #[test]
+    fn test_set_cursor_char_offset() {
+        let mut input = input("héllo");
+        input.set_cursor_char_offset(2);
+        assert_eq!(input.cursor_pos(), "hé".len());
+        input.set_cursor_char_offset(99);
+        assert_eq!(input.cursor_char_offset(), 5);
+    }
+
+    #[test]
+    fn test_paste_flattens_lines_and_copies_selection() {
+        let mut input = input("https://example.com");
+        assert_eq!(input.selected_text(), None);
+        input.select_all();
+        assert_eq!(input.selected_text(), Some("https://example.com"));
+        // Le collage remplace la sélection, sur une seule ligne
+        input.paste("rust\tlang\r\nbook\u{7}\n");
+        assert_eq!(input.text(), "rust lang  book");
+        assert_eq!(input.cursor_pos(), input.text().len());
+    }
+}
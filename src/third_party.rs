@@ -0,0 +1,182 @@
+//! Cookies tiers (`[privacy] block_third_party_cookies`, désactivé par
+//! défaut) : à chaque navigation, les cookies des sites qui n'ont jamais été
+//! que des ressources tierces sont effacés.
+//!
+//! ## Ce que Servo permet
+//!
+//! Servo n'a pas de préférence « cookies tiers », et `load_web_resource`
+//! voit les requêtes en lecture seule (voir [`crate::referrer`]) : ni l'en-tête
+//! `Cookie` d'une requête ni le `Set-Cookie` d'une réponse ne peuvent être
+//! retirés. Le gestionnaire de données de Servo, lui, liste les sites qui
+//! ont des cookies et sait effacer ceux d'un site.
+//!
+//! [`SiteRoles`] note, pour la session, les sites (domaine enregistrable,
+//! eTLD+1) ouverts en premier plan et ceux chargés comme ressource d'un
+//! autre site. Avant chaque navigation principale, les cookies des seconds
+//! sont effacés, sauf s'ils ont aussi été visités ([`SiteRoles::to_clear`]) :
+//! un site où l'utilisateur s'est rendu garde sa session.
+//!
+//! Limites : pendant la visite, un traqueur reçoit ses cookies (la requête
+//! ne peut pas être modifiée), et un site visité dans la session n'est plus
+//! jamais effacé, même inclus ailleurs. Sans historique, SuriBrows ne sait
+//! pas quels sites ont été visités lors des sessions précédentes : un site
+//! où l'utilisateur est connecté depuis une session passée perd ses cookies
+//! s'il est inclus dans une page avant d'être rouvert. D'où l'option
+//! désactivée par défaut.
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+use url::Url;
+
+/// Site de `host` : son domaine enregistrable (`www.example.co.uk` →
+/// `example.co.uk`), d'après la Public Suffix List. Une adresse IP, un nom
+/// sans suffixe connu (`localhost`) ou un suffixe public seul restent tels
+/// quels. Minuscules, sans point final.
+pub fn registrable_domain(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+        return host;
+    }
+    psl::domain_str(&host).map_or_else(|| host.clone(), str::to_string)
+}
+
+/// `true` si `request` part vers un autre site que la page `top` : domaines
+/// enregistrables différents. Le schéma et le port ne comptent pas (comme
+/// pour les cookies). Une URL sans hôte (`data:`…) n'est d'aucun site.
+pub fn is_cross_site(top: &Url, request: &Url) -> bool {
+    match (top.host_str(), request.host_str()) {
+        (Some(top), Some(request)) => registrable_domain(top) != registrable_domain(request),
+        _ => true,
+    }
+}
+
+/// Rôles des sites rencontrés pendant la session.
+#[derive(Debug, Default)]
+pub struct SiteRoles {
+    /// Sites ouverts en premier plan.
+    visited: HashSet<String>,
+    /// Sites chargés comme ressource d'une page d'un autre site.
+    embedded: HashSet<String>,
+}
+
+impl SiteRoles {
+    /// Navigation principale vers `url` : son site garde ses cookies pour
+    /// le reste de la session.
+    pub fn visit(&mut self, url: &Url) {
+        if let Some(host) = url.host_str() {
+            self.visited.insert(registrable_domain(host));
+        }
+    }
+
+    /// Ressource `request` chargée par la page `top` : son site est noté
+    /// s'il est tiers.
+    pub fn embed(&mut self, top: &Url, request: &Url) {
+        if let Some(host) = request.host_str()
+            && is_cross_site(top, request)
+        {
+            self.embedded.insert(registrable_domain(host));
+        }
+    }
+
+    /// Sites de `with_cookies` (hôtes ou domaines) dont les cookies sont à
+    /// effacer : ceux qui n'ont été que des ressources tierces.
+    pub fn to_clear<'a>(&self, with_cookies: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+        with_cookies
+            .into_iter()
+            .filter(|site| {
+                let site = registrable_domain(site);
+                self.embedded.contains(&site) && !self.visited.contains(&site)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(registrable_domain("www.Example.com."), "example.com");
+        assert_eq!(registrable_domain("a.b.example.co.uk"), "example.co.uk");
+        assert_eq!(registrable_domain("example.co.uk"), "example.co.uk");
+        assert_eq!(registrable_domain("co.uk"), "co.uk");
+        assert_eq!(registrable_domain("localhost"), "localhost");
+        assert_eq!(registrable_domain("192.168.1.10"), "192.168.1.10");
+        assert_eq!(registrable_domain("[::1]"), "[::1]");
+    }
+
+    #[test]
+    fn test_subdomains_are_same_site() {
+        let top = url("https://www.example.com/article");
+        assert!(!is_cross_site(
+            &top,
+            &url("https://static.example.com/app.js")
+        ));
+        assert!(!is_cross_site(&top, &url("https://example.com/api")));
+        // Schéma et port ignorés
+        assert!(!is_cross_site(
+            &top,
+            &url("http://cdn.example.com:8080/img.png")
+        ));
+        assert!(is_cross_site(
+            &top,
+            &url("https://tracker.example/pixel.gif")
+        ));
+        assert!(is_cross_site(&top, &url("https://example.com.evil.test/")));
+        assert!(is_cross_site(&url("data:text/html,hi"), &top));
+    }
+
+    #[test]
+    fn test_public_suffix_separates_sites() {
+        let top = url("https://shop.example.co.uk/");
+        assert!(!is_cross_site(
+            &top,
+            &url("https://img.example.co.uk/a.png")
+        ));
+        // Deux sites distincts sous le même suffixe public
+        assert!(is_cross_site(&top, &url("https://other.co.uk/")));
+        assert!(is_cross_site(
+            &url("https://alice.github.io/"),
+            &url("https://bob.github.io/")
+        ));
+    }
+
+    #[test]
+    fn test_only_embedded_sites_are_cleared() {
+        let mut roles = SiteRoles::default();
+        let news = url("https://www.news.example/");
+        roles.visit(&news);
+        roles.embed(&news, &url("https://ads.tracker.example/pixel.gif"));
+        roles.embed(&news, &url("https://static.news.example/app.js"));
+        // Site jamais rencontré (session passée) : gardé
+        assert_eq!(
+            roles.to_clear(["news.example", "tracker.example", "login.provider.co.uk"]),
+            ["tracker.example"]
+        );
+    }
+
+    #[test]
+    fn test_visited_site_keeps_cookies_when_left_or_embedded() {
+        let mut roles = SiteRoles::default();
+        let mail = url("https://mail.provider.example/inbox");
+        let news = url("https://news.example/");
+        roles.visit(&mail);
+        roles.visit(&news);
+        // Boîte mail quittée, puis incluse dans une autre page : la
+        // connexion survit
+        roles.embed(&news, &url("https://provider.example/widget.js"));
+        assert_eq!(roles.to_clear(["provider.example"]), Vec::<&str>::new());
+
+        // Un site d'abord inclus puis visité n'est plus effacé
+        roles.embed(&news, &url("https://social.example/button.js"));
+        assert_eq!(roles.to_clear(["social.example"]), ["social.example"]);
+        roles.visit(&url("https://social.example/feed"));
+        assert_eq!(roles.to_clear(["social.example"]), Vec::<&str>::new());
+    }
+}
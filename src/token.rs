@@ -0,0 +1,137 @@
+//! Jetons des liens internes `http://suribrows.*/…?token=…`.
+//!
+//! Les pages d'avertissement (certificat, HTTPS uniquement, liste de
+//! sécurité) proposent un lien d'acceptation intercepté dans
+//! `load_web_resource`. Son jeton est aléatoire et connu du seul embedder :
+//! une page ne peut pas forger une acceptation. [`PendingTokens`] garde
+//! l'objet de chaque avertissement affiché jusqu'à la réponse.
+//!
+//! Les pages internes (paramètres, état de l'adblock) portent un jeton de
+//! session tiré au démarrage, vérifié par [`has_token`] sur leurs liens
+//! d'action.
+
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use url::Url;
+
+/// Nombre maximal d'avertissements en attente de réponse. Au-delà, ils sont
+/// tous oubliés : leurs liens ne font plus rien.
+const MAX_PENDING: usize = 16;
+
+/// Jeton aléatoire de 128 bits en hexadécimal.
+pub fn new_token() -> String {
+    let mut token = String::new();
+    for _ in 0..2 {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos()),
+        );
+        token.push_str(&format!("{:016x}", hasher.finish()));
+    }
+    token
+}
+
+/// Paramètre `token` de `url` si elle vise `http://{domain}{path}`.
+pub fn url_token(url: &Url, domain: &str, path: &str) -> Option<String> {
+    if url.host_str() != Some(domain) || url.path() != path {
+        return None;
+    }
+    url.query_pairs()
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// `true` si le paramètre `token` de `url` vaut `expected` : lien d'une page
+/// interne générée par l'embedder (jeton de session).
+pub fn has_token(url: &Url, expected: &str) -> bool {
+    !expected.is_empty()
+        && url
+            .query_pairs()
+            .any(|(key, value)| key == "token" && value == expected)
+}
+
+/// Avertissements affichés, par jeton de leur lien d'acceptation.
+#[derive(Debug)]
+pub struct PendingTokens<T> {
+    pending: HashMap<String, T>,
+}
+
+impl<T> Default for PendingTokens<T> {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+        }
+    }
+}
+
+impl<T> PendingTokens<T> {
+    /// Enregistre `value` ; retourne le jeton de son lien.
+    pub fn propose(&mut self, value: T) -> String {
+        if self.pending.len() >= MAX_PENDING {
+            self.pending.clear();
+        }
+        let token = new_token();
+        self.pending.insert(token.clone(), value);
+        token
+    }
+
+    /// Objet du jeton, retiré : un lien ne sert qu'une fois. `None` pour un
+    /// jeton inconnu.
+    pub fn take(&mut self, token: &str) -> Option<T> {
+        self.pending.remove(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_random_and_single_use() {
+        assert_ne!(new_token(), new_token());
+        assert_eq!(new_token().len(), 32);
+
+        let mut pending = PendingTokens::default();
+        let token = pending.propose("page");
+        assert_eq!(pending.take("forged"), None);
+        assert_eq!(pending.take(&token), Some("page"));
+        assert_eq!(pending.take(&token), None);
+    }
+
+    #[test]
+    fn test_pending_tokens_are_bounded() {
+        let mut pending = PendingTokens::default();
+        let first = pending.propose(0);
+        for i in 1..=MAX_PENDING {
+            pending.propose(i);
+        }
+        assert_eq!(pending.take(&first), None);
+    }
+
+    #[test]
+    fn test_has_token() {
+        let url = Url::parse("http://suribrows.settings/save?token=abc&x=1").unwrap();
+        assert!(has_token(&url, "abc"));
+        assert!(!has_token(&url, "abd"));
+        assert!(!has_token(&url, ""));
+        let forged = Url::parse("http://suribrows.settings/save?x=1").unwrap();
+        assert!(!has_token(&forged, "abc"));
+    }
+
+    #[test]
+    fn test_url_token_checks_domain_and_path() {
+        let url = Url::parse("http://suribrows.blocked/proceed?token=abc").unwrap();
+        assert_eq!(
+            url_token(&url, "suribrows.blocked", "/proceed").as_deref(),
+            Some("abc")
+        );
+        assert_eq!(url_token(&url, "suribrows.blocked", "/accept"), None);
+        assert_eq!(url_token(&url, "example.com", "/proceed"), None);
+        let no_token = Url::parse("http://suribrows.blocked/proceed").unwrap();
+        assert_eq!(url_token(&no_token, "suribrows.blocked", "/proceed"), None);
+    }
+}
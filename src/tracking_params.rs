@@ -0,0 +1,166 @@
+//! Paramètres de suivi (`utm_*`, `fbclid`, `gclid`…) retirés des URL de
+//! navigation.
+//!
+//! Le blocage des requêtes n'empêche pas ces identifiants de suivre
+//! l'utilisateur de page en page : ils voyagent dans l'URL elle-même. Une
+//! navigation principale dont l'URL en contient est refusée dans
+//! `request_navigation` et relancée sans eux. Les autres paramètres gardent
+//! leur ordre et leur encodage d'origine ; une URL sans paramètre de suivi
+//! n'est jamais relancée (pas de boucle).
+
+use url::Url;
+use url::form_urlencoded;
+
+/// Paramètres retirés d'office. Un `*` final désigne un préfixe.
+pub const TRACKING_PARAMS: &[&str] = &[
+    "utm_*",
+    "fbclid",
+    "gclid",
+    "dclid",
+    "gbraid",
+    "wbraid",
+    "msclkid",
+    "yclid",
+    "twclid",
+    "ttclid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "li_fat_id",
+    "oly_anon_id",
+    "oly_enc_id",
+    "vero_id",
+];
+
+/// `true` si le paramètre `name` correspond au motif `pattern` (nom exact
+/// ou préfixe terminé par `*`, casse ignorée).
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+        None => name.eq_ignore_ascii_case(pattern),
+    }
+}
+
+/// `true` si `name` est un paramètre de suivi, de la liste intégrée ou de
+/// `extra` (`[privacy] strip_params`).
+pub fn is_tracking_param(name: &str, extra: &[String]) -> bool {
+    TRACKING_PARAMS
+        .iter()
+        .copied()
+        .chain(extra.iter().map(|pattern| pattern.trim()))
+        .filter(|pattern| !pattern.is_empty() && *pattern != "*")
+        .any(|pattern| pattern_matches(pattern, name))
+}
+
+/// `url` sans ses paramètres de suivi, `None` s'il n'y en a aucun (ou si
+/// l'URL n'est pas `http(s)`). Les noms sont comparés décodés ; les
+/// paramètres gardés restent tels quels, dans leur ordre, et le fragment est
+/// conservé.
+pub fn strip(url: &Url, extra: &[String]) -> Option<Url> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let query = url.query()?;
+    let mut removed = false;
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let name = form_urlencoded::parse(pair.as_bytes())
+                .next()
+                .map(|(name, _)| name);
+            let tracking = name.is_some_and(|name| is_tracking_param(&name, extra));
+            removed |= tracking;
+            !tracking
+        })
+        .collect();
+    if !removed {
+        return None;
+    }
+    let mut clean = url.clone();
+    if kept.is_empty() {
+        clean.set_query(None);
+    } else {
+        clean.set_query(Some(&kept.join("&")));
+    }
+    (clean != *url).then_some(clean)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_str(url: &str, extra: &[&str]) -> Option<String> {
+        let extra: Vec<String> = extra.iter().map(|s| s.to_string()).collect();
+        strip(&Url::parse(url).unwrap(), &extra).map(String::from)
+    }
+
+    #[test]
+    fn test_strip_keeps_order_of_survivors() {
+        assert_eq!(
+            strip_str(
+                "https://example.com/a?b=2&utm_source=news&a=1&fbclid=XyZ&c=3",
+                &[]
+            )
+            .as_deref(),
+            Some("https://example.com/a?b=2&a=1&c=3")
+        );
+        assert_eq!(
+            strip_str("https://example.com/?gclid=1&UTM_Medium=email", &[]).as_deref(),
+            Some("https://example.com/")
+        );
+    }
+
+    #[test]
+    fn test_strip_preserves_encoding_and_fragment() {
+        assert_eq!(
+            strip_str(
+                "https://example.com/search?q=caf%C3%A9+cr%C3%A8me&utm_campaign=a%26b&x=%3D#results",
+                &[]
+            )
+            .as_deref(),
+            Some("https://example.com/search?q=caf%C3%A9+cr%C3%A8me&x=%3D#results")
+        );
+        // Nom encodé : comparé décodé
+        assert_eq!(
+            strip_str("https://example.com/?utm%5Fsource=x&id=7#top", &[]).as_deref(),
+            Some("https://example.com/?id=7#top")
+        );
+    }
+
+    #[test]
+    fn test_strip_skips_clean_urls() {
+        assert_eq!(
+            strip_str("https://example.com/?q=utm_source&id=1", &[]),
+            None
+        );
+        assert_eq!(strip_str("https://example.com/#utm_source=x", &[]), None);
+        assert_eq!(strip_str("https://example.com/", &[]), None);
+        assert_eq!(strip_str("data:text/html,?utm_source=x", &[]), None);
+        // Une URL nettoyée ne l'est pas une seconde fois
+        let clean = strip_str("https://example.com/?a=1&fbclid=2", &[]).unwrap();
+        assert_eq!(strip_str(&clean, &[]), None);
+    }
+
+    #[test]
+    fn test_user_params_extend_builtin_list() {
+        assert_eq!(
+            strip_str(
+                "https://shop.example/p?ref=mail&id=3&aff_id=9",
+                &["ref", "aff_*"]
+            )
+            .as_deref(),
+            Some("https://shop.example/p?id=3")
+        );
+        assert!(!is_tracking_param("ref", &[]));
+        // Un motif `*` seul ne vide pas toutes les URL
+        assert!(!is_tracking_param(
+            "id",
+            &["*".to_string(), " ".to_string()]
+        ));
+    }
+}
@@ -7,6 +7,9 @@
 
 use url::Url;
 
+use crate::pages::INTERNAL_SCHEME;
+use crate::text_input::{Composed, TextInput};
+
 const DEFAULT_SEARCH_URL: &str = "https://duckduckgo.com/?q=";
 
 /// Normalizes URL for safe display (V-8: Homograph Attack Prevention).
@@ -58,26 +61,13 @@ fn normalize_url_for_display(url: &Url) -> String {
     cleaned
 }
 
-/// État du focus de la barre d'URL.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum UrlBarFocus {
-    /// La barre n'a pas le focus — les événements clavier vont à Servo.
-    Unfocused,
-    /// Vient d'être focusée (Ctrl+L ou clic) — tout le texte est sélectionné.
-    /// La prochaine frappe remplace tout le contenu.
-    Focused,
-    /// L'utilisateur est en train de taper — édition caractère par caractère.
-    Editing,
-}
-
 /// Machine à états de la barre d'URL.
 pub struct UrlBar {
     /// Texte affiché / édité dans la barre.
-    text: String,
-    /// Position du curseur en offset d'octets dans `text`.
-    cursor: usize,
-    /// État de focus actuel.
-    focus: UrlBarFocus,
+    input: TextInput,
+    /// `false` : les événements clavier vont à Servo. Juste après le focus
+    /// (Ctrl+L ou clic), tout le texte est sélectionné.
+    focused: bool,
     /// URL courante de la page (mise à jour par `notify_url_changed`).
     current_url: Option<Url>,
     /// Search engine URL (query appended at the end).
@@ -93,177 +83,152 @@ impl Default for UrlBar {
 impl UrlBar {
     pub fn new(search_url: String) -> Self {
         Self {
-            text: String::new(),
-            cursor: 0,
-            focus: UrlBarFocus::Unfocused,
+            input: TextInput::default(),
+            focused: false,
             current_url: None,
             search_url,
         }
     }
 
+    /// Remplace le moteur de recherche (hot-reload de `config.toml`).
+    pub fn set_search_url(&mut self, search_url: String) {
+        self.search_url = search_url;
+    }
+
     /// Met à jour l'URL affichée depuis une notification Servo.
     /// Ne modifie le texte que si la barre n'est pas en cours d'édition.
     ///
     /// SECURITY (V-8): Uses `normalize_url_for_display()` to prevent homograph attacks.
     pub fn set_url(&mut self, url: &Url) {
         self.current_url = Some(url.clone());
-        if self.focus == UrlBarFocus::Unfocused {
-            self.text = normalize_url_for_display(url); // Security: normalized display
-            self.cursor = self.text.len();
+        if !self.focused {
+            self.input.set_text(normalize_url_for_display(url)); // Security: normalized display
         }
     }
 
     /// Focus la barre (Ctrl+L ou clic). Sélectionne tout le texte.
     pub fn focus(&mut self) {
-        self.focus = UrlBarFocus::Focused;
-        self.cursor = self.text.len();
+        self.focused = true;
+        self.input.select_all();
     }
 
     /// Retire le focus (Escape). Restaure l'URL courante.
     ///
     /// SECURITY (V-8): Uses normalized display to prevent homograph attacks.
     pub fn unfocus(&mut self) {
-        self.focus = UrlBarFocus::Unfocused;
+        self.focused = false;
+        self.input.set_preedit(String::new(), None);
         if let Some(ref url) = self.current_url {
-            self.text = normalize_url_for_display(url); // Security: normalized display
-            self.cursor = self.text.len();
+            self.input.set_text(normalize_url_for_display(url)); // Security: normalized display
         }
     }
 
     /// Insère un caractère à la position du curseur.
-    /// Si on est en mode Focused (select-all), remplace tout le texte d'abord.
+    /// Si tout est sélectionné (juste après le focus), remplace tout le texte d'abord.
     pub fn insert_char(&mut self, c: char) {
-        if self.focus == UrlBarFocus::Focused {
-            self.text.clear();
-            self.cursor = 0;
-            self.focus = UrlBarFocus::Editing;
-        }
-        self.text.insert(self.cursor, c);
-        self.cursor += c.len_utf8();
+        self.input.insert_char(c);
+    }
+
+    /// Insère le texte validé par l'IME et termine la composition.
+    pub fn insert_str(&mut self, s: &str) {
+        self.input.insert_str(s);
+    }
+
+    /// Colle le texte du presse-papiers au curseur.
+    pub fn paste(&mut self, s: &str) {
+        self.input.paste(s);
+    }
+
+    /// Texte sélectionné, à copier.
+    pub fn selected_text(&self) -> Option<&str> {
+        self.input.selected_text()
+    }
+
+    /// Met à jour la composition IME affichée au curseur.
+    pub fn set_preedit(&mut self, text: String, cursor: Option<usize>) {
+        self.input.set_preedit(text, cursor);
+    }
+
+    /// `true` pendant une composition IME.
+    pub fn is_composing(&self) -> bool {
+        self.input.is_composing()
+    }
+
+    /// Texte affiché pendant l'édition, composition IME comprise.
+    pub fn composed(&self) -> Composed {
+        self.input.composed()
     }
 
     /// Supprime le caractère avant le curseur (Backspace).
     pub fn backspace(&mut self) {
-        if self.focus == UrlBarFocus::Focused {
-            // Select-all + backspace = tout effacer
-            self.text.clear();
-            self.cursor = 0;
-            self.focus = UrlBarFocus::Editing;
-            return;
-        }
-        if self.cursor > 0 {
-            // Reculer au début du caractère précédent
-            let prev = self.text[..self.cursor]
-                .char_indices()
-                .next_back()
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-            self.text.drain(prev..self.cursor);
-            self.cursor = prev;
-        }
+        self.input.backspace();
     }
 
     /// Supprime le caractère après le curseur (Delete).
     pub fn delete(&mut self) {
-        if self.focus == UrlBarFocus::Focused {
-            self.text.clear();
-            self.cursor = 0;
-            self.focus = UrlBarFocus::Editing;
-            return;
-        }
-        if self.cursor < self.text.len() {
-            let next = self.text[self.cursor..]
-                .char_indices()
-                .nth(1)
-                .map(|(i, _)| self.cursor + i)
-                .unwrap_or(self.text.len());
-            self.text.drain(self.cursor..next);
-        }
+        self.input.delete();
     }
 
     /// Déplace le curseur d'un caractère vers la gauche.
     pub fn move_cursor_left(&mut self) {
-        if self.focus == UrlBarFocus::Focused {
-            self.focus = UrlBarFocus::Editing;
-            self.cursor = 0;
-            return;
-        }
-        if self.cursor > 0 {
-            self.cursor = self.text[..self.cursor]
-                .char_indices()
-                .next_back()
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-        }
+        self.input.move_cursor_left();
     }
 
     /// Déplace le curseur d'un caractère vers la droite.
     pub fn move_cursor_right(&mut self) {
-        if self.focus == UrlBarFocus::Focused {
-            self.focus = UrlBarFocus::Editing;
-            // cursor already at end
-            return;
-        }
-        if self.cursor < self.text.len() {
-            self.cursor = self.text[self.cursor..]
-                .char_indices()
-                .nth(1)
-                .map(|(i, _)| self.cursor + i)
-                .unwrap_or(self.text.len());
-        }
+        self.input.move_cursor_right();
+    }
+
+    /// Place le curseur après le `offset`-ième caractère (clic dans la barre).
+    /// L'offset est borné à la longueur du texte.
+    pub fn set_cursor_char_offset(&mut self, offset: usize) {
+        self.input.set_cursor_char_offset(offset);
     }
 
     /// Place le curseur au début du texte (Home).
     pub fn home(&mut self) {
-        if self.focus == UrlBarFocus::Focused {
-            self.focus = UrlBarFocus::Editing;
-        }
-        self.cursor = 0;
+        self.input.home();
     }
 
     /// Place le curseur à la fin du texte (End).
     pub fn end(&mut self) {
-        if self.focus == UrlBarFocus::Focused {
-            self.focus = UrlBarFocus::Editing;
-        }
-        self.cursor = self.text.len();
+        self.input.end();
     }
 
     /// Sélectionne tout le texte (Ctrl+A).
     pub fn select_all(&mut self) {
-        self.focus = UrlBarFocus::Focused;
-        self.cursor = self.text.len();
+        self.input.select_all();
     }
 
     /// Valide la saisie (Enter). Retourne l'URL vers laquelle naviguer.
     pub fn submit(&mut self) -> Option<Url> {
-        let input = self.text.trim();
+        let input = self.input.text().trim();
         if input.is_empty() {
             return None;
         }
         let url = resolve_input(input, &self.search_url);
-        self.focus = UrlBarFocus::Unfocused;
+        self.focused = false;
         url
     }
 
     /// Retourne `true` si la barre a le focus (doit consommer le clavier).
     pub fn is_focused(&self) -> bool {
-        self.focus != UrlBarFocus::Unfocused
+        self.focused
     }
 
     /// Texte à afficher dans la barre.
     pub fn display_text(&self) -> &str {
-        &self.text
+        self.input.text()
     }
 
     /// Position du curseur en octets.
     pub fn cursor_pos(&self) -> usize {
-        self.cursor
+        self.input.cursor_pos()
     }
 
     /// Nombre de caractères avant le curseur (pour le rendu).
     pub fn cursor_char_offset(&self) -> usize {
-        self.text[..self.cursor].chars().count()
+        self.input.cursor_char_offset()
     }
 }
 
@@ -274,9 +239,9 @@ impl UrlBar {
 ///   on la traite comme une URL et on ajoute `https://`.
 /// - Sinon, on fait une recherche via le configured search engine.
 fn resolve_input(input: &str, search_url: &str) -> Option<Url> {
-    // Déjà une URL valide avec schéma ?
+    // Déjà une URL valide avec schéma ? (ou page interne `suri://`)
     if let Ok(url) = Url::parse(input)
-        && (url.scheme() == "http" || url.scheme() == "https")
+        && matches!(url.scheme(), "http" | "https" | INTERNAL_SCHEME)
     {
         return Some(url);
     }
@@ -406,6 +371,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_cursor_char_offset() {
+        let mut urlbar = UrlBar::default();
+        urlbar.focus();
+        for c in "héllo".chars() {
+            urlbar.insert_char(c);
+        }
+        urlbar.set_cursor_char_offset(2);
+        assert_eq!(urlbar.cursor_char_offset(), 2);
+        assert_eq!(urlbar.cursor_pos(), "hé".len());
+        urlbar.insert_char('x');
+        assert_eq!(urlbar.display_text(), "héxllo");
+
+        urlbar.set_cursor_char_offset(99);
+        assert_eq!(urlbar.cursor_char_offset(), 6);
+    }
+
     #[test]
     fn test_resolve_input_https() {
         // Test URL resolution adds https:// prefix
@@ -428,4 +410,13 @@ mod tests {
         assert!(result.as_str().starts_with("https://google.com/search?q="));
         assert!(result.as_str().contains("hello"));
     }
+
+    #[test]
+    fn test_resolve_input_internal_page() {
+        let result = resolve_input("suri://adblock", DEFAULT_SEARCH_URL).unwrap();
+        assert_eq!(result.as_str(), "suri://adblock");
+        // Autres schémas : toujours une recherche
+        let result = resolve_input("ftp://example", DEFAULT_SEARCH_URL).unwrap();
+        assert!(result.as_str().starts_with("https://duckduckgo.com/?q="));
+    }
 }
@@ -0,0 +1,153 @@
+//! Liste des webviews de la fenêtre et webview active.
+//!
+//! La webview active reçoit le clavier, la souris et les redraws. Elle est
+//! suivie explicitement plutôt que déduite de l'ordre de création : une
+//! popup ouverte en arrière-plan ne doit pas prendre l'entrée. Ce module ne
+//! dépend pas de Servo.
+
+/// Webviews dans l'ordre de création, et index de l'active.
+#[derive(Debug)]
+pub struct WebViewList<T> {
+    items: Vec<T>,
+    /// Index de la webview active dans `items` (`None` si la liste est vide).
+    active: Option<usize>,
+}
+
+impl<T> Default for WebViewList<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            active: None,
+        }
+    }
+}
+
+impl<T> WebViewList<T> {
+    /// Ajoute une webview. Avec `activate`, elle devient l'active ; sinon
+    /// elle n'est active que si c'est la première.
+    pub fn push(&mut self, item: T, activate: bool) {
+        self.items.push(item);
+        if activate || self.active.is_none() {
+            self.active = Some(self.items.len() - 1);
+        }
+    }
+
+    /// Retire la webview d'index `index`. L'active garde sa place ; si c'est
+    /// elle qui part, sa voisine de droite (sinon de gauche) lui succède.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.items.len() {
+            return None;
+        }
+        let item = self.items.remove(index);
+        self.active = match self.active {
+            _ if self.items.is_empty() => None,
+            Some(active) if active > index => Some(active - 1),
+            Some(active) if active == index => Some(index.min(self.items.len() - 1)),
+            active => active,
+        };
+        Some(item)
+    }
+
+    /// Active la webview d'index `index`. Retourne `true` si l'active a changé.
+    pub fn activate(&mut self, index: usize) -> bool {
+        if index >= self.items.len() || self.active == Some(index) {
+            return false;
+        }
+        self.active = Some(index);
+        true
+    }
+
+    /// Webview active.
+    pub fn active(&self) -> Option<&T> {
+        self.items.get(self.active?)
+    }
+
+    /// Index de la webview active.
+    pub fn active_index(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// Index de la première webview qui satisfait `predicate`.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<usize> {
+        self.items.iter().position(predicate)
+    }
+
+    /// Première webview qui satisfait `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<&T> {
+        self.items.iter().find(|item| predicate(item))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Ferme toutes les webviews (arrêt).
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.active = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: &[char], active: usize) -> WebViewList<char> {
+        let mut list = WebViewList::default();
+        for &item in items {
+            list.push(item, false);
+        }
+        list.activate(active);
+        list
+    }
+
+    #[test]
+    fn test_push_activates_only_when_asked() {
+        let mut list = WebViewList::default();
+        list.push('a', false);
+        assert_eq!(list.active(), Some(&'a'));
+        // Popup en arrière-plan : l'active ne change pas
+        list.push('b', false);
+        assert_eq!(list.active(), Some(&'a'));
+        list.push('c', true);
+        assert_eq!(list.active(), Some(&'c'));
+        assert!(!list.activate(2));
+        assert!(!list.activate(7));
+    }
+
+    #[test]
+    fn test_remove_before_active_shifts_index() {
+        let mut list = list(&['a', 'b', 'c', 'd'], 2);
+        assert_eq!(list.remove(1), Some('b'));
+        assert_eq!(list.active_index(), Some(1));
+        assert_eq!(list.active(), Some(&'c'));
+    }
+
+    #[test]
+    fn test_remove_after_active_keeps_index() {
+        let mut list = list(&['a', 'b', 'c', 'd'], 1);
+        assert_eq!(list.remove(2), Some('c'));
+        assert_eq!(list.active(), Some(&'b'));
+    }
+
+    #[test]
+    fn test_remove_active_from_middle_picks_right_neighbour() {
+        let mut list = list(&['a', 'b', 'c'], 1);
+        assert_eq!(list.remove(1), Some('b'));
+        assert_eq!(list.active(), Some(&'c'));
+        // Dernière position : la voisine de gauche prend le relais
+        assert_eq!(list.remove(1), Some('c'));
+        assert_eq!(list.active(), Some(&'a'));
+        assert_eq!(list.remove(0), Some('a'));
+        assert_eq!(list.active(), None);
+        assert_eq!(list.remove(0), None);
+    }
+}
@@ -0,0 +1,195 @@
+//! Géométrie de la fenêtre mémorisée entre deux lancements (`window.json`).
+//!
+//! Taille, position et état maximisé sont relevés à la fermeture et
+//! réappliqués au démarrage. Tout est en pixels physiques. Une position qui
+//! ne tombe plus sur aucun écran (moniteur débranché) est ramenée sur le
+//! premier écran disponible.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config;
+
+/// Nom du fichier d'état dans [`config::data_dir`].
+pub const WINDOW_STATE_FILE: &str = "window.json";
+
+/// Partie de la fenêtre (en pixels, depuis le coin haut-gauche) qui doit
+/// rester sur un écran pour que la position soit jugée utilisable : de quoi
+/// attraper la barre de titre.
+const MIN_VISIBLE: i32 = 64;
+
+/// Dernière géométrie connue de la fenêtre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// Rectangle d'un écran, en pixels physiques.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MonitorRect {
+    /// `true` si le coin haut-gauche de la fenêtre (`MIN_VISIBLE` pixels de
+    /// côté, ou moins pour une très petite fenêtre) est sur cet écran.
+    fn shows(&self, x: i32, y: i32, width: u32, height: u32) -> bool {
+        let grab_w = MIN_VISIBLE.min(width as i32);
+        let grab_h = MIN_VISIBLE.min(height as i32);
+        x + grab_w > self.x
+            && x < self.x + self.width as i32
+            && y + grab_h > self.y
+            && y < self.y + self.height as i32
+    }
+}
+
+impl WindowState {
+    /// Charge l'état depuis l'emplacement par défaut.
+    pub fn load_default() -> Option<Self> {
+        Self::load(&Self::default_path())
+    }
+
+    /// Charge l'état depuis `path`. Absent ou invalide : `None` (avec un
+    /// avertissement s'il est invalide). Une taille nulle est ignorée.
+    pub fn load(path: &Path) -> Option<Self> {
+        let state: Self = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .inspect_err(|e| {
+                    warn!(path = %path.display(), error = %e, "Invalid window state file, ignoring");
+                })
+                .ok(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Cannot read window state");
+                None
+            }
+        }?;
+        (state.width > 0 && state.height > 0).then_some(state)
+    }
+
+    /// Écrit l'état à l'emplacement par défaut.
+    pub fn save_default(&self) -> io::Result<()> {
+        self.save(&Self::default_path())
+    }
+
+    /// Écrit l'état dans `path` (écriture atomique).
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        config::write_atomic(path, &json)
+    }
+
+    /// Chemin par défaut du fichier d'état.
+    pub fn default_path() -> PathBuf {
+        config::data_dir().join(WINDOW_STATE_FILE)
+    }
+
+    /// Position à restaurer sur les écrans `monitors`.
+    ///
+    /// La position mémorisée est gardée si la fenêtre reste attrapable sur
+    /// un écran ; sinon elle est ramenée dans le premier écran. Sans écran
+    /// connu (Wayland ne les expose pas toujours), `None` : le système place
+    /// la fenêtre.
+    pub fn position_on(&self, monitors: &[MonitorRect]) -> Option<(i32, i32)> {
+        if monitors
+            .iter()
+            .any(|m| m.shows(self.x, self.y, self.width, self.height))
+        {
+            return Some((self.x, self.y));
+        }
+        let m = monitors.first()?;
+        let max_x = m.x + (m.width as i32 - self.width as i32).max(0);
+        let max_y = m.y + (m.height as i32 - self.height as i32).max(0);
+        Some((self.x.clamp(m.x, max_x), self.y.clamp(m.y, max_y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    const LEFT: MonitorRect = MonitorRect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+    const RIGHT: MonitorRect = MonitorRect {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+    };
+
+    fn state(x: i32, y: i32) -> WindowState {
+        WindowState {
+            x,
+            y,
+            width: 1280,
+            height: 800,
+            maximized: false,
+        }
+    }
+
+    #[test]
+    fn test_visible_position_kept() {
+        assert_eq!(state(100, 50).position_on(&[LEFT]), Some((100, 50)));
+        // Sur le second écran
+        assert_eq!(
+            state(2500, 200).position_on(&[LEFT, RIGHT]),
+            Some((2500, 200))
+        );
+        // Débordement partiel : la barre de titre reste attrapable
+        assert_eq!(state(1800, 900).position_on(&[LEFT]), Some((1800, 900)));
+    }
+
+    #[test]
+    fn test_offscreen_position_clamped() {
+        // Le second écran a été débranché
+        assert_eq!(state(2500, 200).position_on(&[LEFT]), Some((640, 200)));
+        assert_eq!(state(-5000, -300).position_on(&[LEFT]), Some((0, 0)));
+        // Fenêtre plus grande que l'écran : collée au coin
+        let big = WindowState {
+            width: 4000,
+            height: 3000,
+            ..state(3000, 2000)
+        };
+        assert_eq!(big.position_on(&[LEFT]), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_no_monitor_lets_system_decide() {
+        assert_eq!(state(100, 50).position_on(&[]), None);
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let dir = TempDir::new("window");
+        let path = dir.join(WINDOW_STATE_FILE);
+        let saved = WindowState {
+            maximized: true,
+            ..state(-10, 20)
+        };
+        saved.save(&path).unwrap();
+        assert_eq!(WindowState::load(&path), Some(saved));
+
+        let empty = WindowState { width: 0, ..saved };
+        empty.save(&path).unwrap();
+        assert_eq!(WindowState::load(&path), None);
+
+        fs::write(&path, "{").unwrap();
+        assert_eq!(WindowState::load(&path), None);
+        assert_eq!(WindowState::load(&dir.join("missing.json")), None);
+    }
+}
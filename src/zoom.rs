@@ -0,0 +1,328 @@
+//! Niveaux de zoom mémorisés par hôte dans `zoom.json`, à côté de `config.toml`.
+//!
+//! Le fichier est un objet JSON `{ "example.com": 1.25, … }`. Seuls les hôtes
+//! dont le zoom diffère de 100 % y figurent : revenir à 100 % efface l'entrée.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
+
+use crate::config;
+
+/// Nom du fichier de zoom dans [`config::data_dir`].
+pub const ZOOM_FILE: &str = "zoom.json";
+
+/// Zoom par défaut (100 %).
+pub const DEFAULT_ZOOM: f32 = 1.0;
+
+/// Paliers de zoom parcourus par Ctrl+= / Ctrl+-, de 25 % à 500 %.
+pub const ZOOM_STEPS: &[f32] = &[
+    0.25, 0.33, 0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 4.0, 5.0,
+];
+
+/// Palier strictement supérieur à `current` (ou le maximum).
+pub fn zoom_in(current: f32) -> f32 {
+    ZOOM_STEPS
+        .iter()
+        .copied()
+        .find(|&step| step > current + f32::EPSILON)
+        .unwrap_or(ZOOM_STEPS[ZOOM_STEPS.len() - 1])
+}
+
+/// Palier strictement inférieur à `current` (ou le minimum).
+pub fn zoom_out(current: f32) -> f32 {
+    ZOOM_STEPS
+        .iter()
+        .rev()
+        .copied()
+        .find(|&step| step < current - f32::EPSILON)
+        .unwrap_or(ZOOM_STEPS[0])
+}
+
+/// Borne un niveau lu sur disque dans la plage des paliers.
+pub fn clamp_zoom(level: f32) -> f32 {
+    if level.is_finite() {
+        level.clamp(ZOOM_STEPS[0], ZOOM_STEPS[ZOOM_STEPS.len() - 1])
+    } else {
+        DEFAULT_ZOOM
+    }
+}
+
+/// Niveau après un geste de pincement : `delta` est la variation relative
+/// rapportée par Winit (`PinchGesture`), cumulée multiplicativement.
+pub fn pinch(current: f32, delta: f64) -> f32 {
+    clamp_zoom(current * (1.0 + delta as f32))
+}
+
+/// Niveau après `steps` paliers (positif = zoom avant), borné aux extrêmes.
+pub fn step(current: f32, steps: i32) -> f32 {
+    let mut level = current;
+    for _ in 0..steps.unsigned_abs() {
+        level = if steps > 0 {
+            zoom_in(level)
+        } else {
+            zoom_out(level)
+        };
+    }
+    level
+}
+
+/// Défilement (pixels CSS) qui garde sous le curseur le point de la page
+/// qu'il survolait quand le zoom passe de `old` à `new`. `scroll` : défilement
+/// actuel ; `cursor` : position dans la webview en pixels CSS à 100 %.
+///
+/// Le point du document sous le curseur est `scroll + cursor / zoom` : il
+/// doit rester le même avant et après.
+pub fn anchored_scroll(scroll: (f32, f32), cursor: (f32, f32), old: f32, new: f32) -> (f32, f32) {
+    (
+        scroll.0 + cursor.0 / old - cursor.0 / new,
+        scroll.1 + cursor.1 / old - cursor.1 / new,
+    )
+}
+
+/// Script appliquant [`anchored_scroll`] dans la page. Le zoom de page de
+/// libservo n'a pas de point d'ancrage (il reste calé en haut à gauche) :
+/// la page est défilée pour compenser. Le défilement actuel est lu tout de
+/// suite, le nouveau est appliqué à la frame suivante, une fois la page
+/// remise en page au nouveau zoom.
+pub fn anchor_script(cursor: (f32, f32), old: f32, new: f32) -> String {
+    let (x, y) = cursor;
+    format!(
+        "(() => {{ const x = scrollX + {x} / {old} - {x} / {new}, \
+         y = scrollY + {y} / {old} - {y} / {new}; \
+         requestAnimationFrame(() => scrollTo(x, y)); }})();"
+    )
+}
+
+/// Défilement en pixels (pavé tactile) équivalent à un cran de molette.
+pub const PIXELS_PER_NOTCH: f64 = 50.0;
+
+/// Convertit Ctrl+molette en paliers de zoom. Les fractions de cran (pavés
+/// tactiles, molettes haute résolution) s'accumulent : un léger glissé à
+/// deux doigts ne saute pas plusieurs paliers d'un coup.
+#[derive(Debug, Default)]
+pub struct WheelZoom {
+    pending: f64,
+}
+
+impl WheelZoom {
+    /// Ajoute `notches` crans (positif = vers le haut) et retourne le nombre
+    /// de paliers entiers à appliquer. Un changement de sens repart de zéro.
+    pub fn scroll(&mut self, notches: f64) -> i32 {
+        if !notches.is_finite() || notches == 0.0 {
+            return 0;
+        }
+        if self.pending * notches < 0.0 {
+            self.pending = 0.0;
+        }
+        self.pending += notches;
+        let steps = self.pending.trunc();
+        self.pending -= steps;
+        steps as i32
+    }
+}
+
+/// Pourcentage affiché dans le titre (`1.25` → `125`).
+pub fn zoom_percent(level: f32) -> u32 {
+    (level * 100.0).round() as u32
+}
+
+/// Niveaux de zoom par hôte et fichier associé.
+#[derive(Debug, Default)]
+pub struct ZoomStore {
+    path: PathBuf,
+    levels: BTreeMap<String, f32>,
+}
+
+impl ZoomStore {
+    /// Charge les niveaux depuis l'emplacement par défaut.
+    pub fn load_default() -> Self {
+        Self::load(config::data_dir().join(ZOOM_FILE))
+    }
+
+    /// Charge les niveaux depuis `path`. Un fichier absent ou invalide
+    /// donne une table vide (avec un avertissement s'il est invalide).
+    pub fn load(path: PathBuf) -> Self {
+        let levels = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "Invalid zoom file, starting empty");
+                BTreeMap::new()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Cannot read zoom levels");
+                BTreeMap::new()
+            }
+        };
+        Self { path, levels }
+    }
+
+    /// Écrit les niveaux sur disque (écriture atomique).
+    pub fn save(&self) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(&self.levels).map_err(io::Error::other)?;
+        config::write_atomic(&self.path, &json)
+    }
+
+    /// Chemin du fichier de zoom.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Zoom mémorisé pour `host` ([`DEFAULT_ZOOM`] si inconnu).
+    pub fn get(&self, host: &str) -> f32 {
+        self.levels
+            .get(host)
+            .copied()
+            .map_or(DEFAULT_ZOOM, clamp_zoom)
+    }
+
+    /// Mémorise le zoom de `host` ; 100 % efface l'entrée.
+    pub fn set(&mut self, host: &str, level: f32) {
+        if (level - DEFAULT_ZOOM).abs() < f32::EPSILON {
+            self.levels.remove(host);
+        } else {
+            self.levels.insert(host.to_string(), level);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_zoom_in_and_out_follow_steps() {
+        assert_eq!(zoom_in(1.0), 1.1);
+        assert_eq!(zoom_out(1.0), 0.9);
+        assert_eq!(zoom_in(1.25), 1.5);
+        // Un niveau hors palier rejoint le palier voisin
+        assert_eq!(zoom_in(1.3), 1.5);
+        assert_eq!(zoom_out(1.3), 1.25);
+    }
+
+    #[test]
+    fn test_zoom_clamped_at_bounds() {
+        assert_eq!(zoom_in(5.0), 5.0);
+        assert_eq!(zoom_out(0.25), 0.25);
+        assert_eq!(clamp_zoom(12.0), 5.0);
+        assert_eq!(clamp_zoom(0.01), 0.25);
+        assert_eq!(clamp_zoom(f32::NAN), DEFAULT_ZOOM);
+    }
+
+    #[test]
+    fn test_pinch_accumulates_and_clamps() {
+        let mut level = DEFAULT_ZOOM;
+        for _ in 0..10 {
+            level = pinch(level, 0.1);
+        }
+        assert!((level - 1.1f32.powi(10)).abs() < 1e-4);
+        assert_eq!(pinch(4.9, 0.5), 5.0);
+        assert_eq!(pinch(0.3, -0.5), 0.25);
+        // Un pincement puis l'inverse ramène (presque) au départ
+        assert!((pinch(pinch(1.0, 0.25), -0.2) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_step_follows_keyboard_steps() {
+        assert_eq!(step(1.0, 1), 1.1);
+        assert_eq!(step(1.0, -2), 0.8);
+        assert_eq!(step(1.0, 0), 1.0);
+        assert_eq!(step(4.0, 5), 5.0);
+    }
+
+    #[test]
+    fn test_anchored_scroll_keeps_point_under_cursor() {
+        let document_point = |scroll: (f32, f32), cursor: (f32, f32), zoom: f32| {
+            (scroll.0 + cursor.0 / zoom, scroll.1 + cursor.1 / zoom)
+        };
+        let cursor = (400.0, 300.0);
+        for (old, new) in [(1.0, 1.1), (1.0, 0.5), (2.0, 1.25), (0.25, 5.0)] {
+            let scroll = (120.0, 800.0);
+            let after = anchored_scroll(scroll, cursor, old, new);
+            let before = document_point(scroll, cursor, old);
+            let kept = document_point(after, cursor, new);
+            assert!((before.0 - kept.0).abs() < 1e-3, "{old} → {new}");
+            assert!((before.1 - kept.1).abs() < 1e-3, "{old} → {new}");
+        }
+        // Zoom avant sous le curseur : la page défile vers lui
+        let after = anchored_scroll((0.0, 0.0), cursor, 1.0, 2.0);
+        assert_eq!(after, (200.0, 150.0));
+        // Curseur au coin de la webview : comme le zoom sans ancrage
+        assert_eq!(
+            anchored_scroll((5.0, 7.0), (0.0, 0.0), 1.0, 3.0),
+            (5.0, 7.0)
+        );
+    }
+
+    #[test]
+    fn test_anchor_script_uses_cursor_and_levels() {
+        let script = anchor_script((400.0, 300.5), 1.0, 1.5);
+        assert!(script.contains("scrollX + 400 / 1 - 400 / 1.5"), "{script}");
+        assert!(
+            script.contains("scrollY + 300.5 / 1 - 300.5 / 1.5"),
+            "{script}"
+        );
+        assert!(script.contains("requestAnimationFrame"));
+    }
+
+    #[test]
+    fn test_wheel_zoom_accumulates_pixel_deltas() {
+        let mut wheel = WheelZoom::default();
+        // Crans de molette : un palier chacun
+        assert_eq!(wheel.scroll(1.0), 1);
+        assert_eq!(wheel.scroll(-2.0), -2);
+        // Pavé tactile : rien avant un cran complet
+        let px = 10.0 / PIXELS_PER_NOTCH;
+        assert_eq!(wheel.scroll(px), 0);
+        assert_eq!(wheel.scroll(px * 3.0), 0);
+        assert_eq!(wheel.scroll(px), 1);
+        // Le changement de sens oublie le reliquat
+        assert_eq!(wheel.scroll(0.9), 0);
+        assert_eq!(wheel.scroll(-0.5), 0);
+        assert_eq!(wheel.scroll(-0.5), -1);
+    }
+
+    #[test]
+    fn test_zoom_percent() {
+        assert_eq!(zoom_percent(1.0), 100);
+        assert_eq!(zoom_percent(1.25), 125);
+        assert_eq!(zoom_percent(0.33), 33);
+    }
+
+    #[test]
+    fn test_default_zoom_not_stored() {
+        let mut store = ZoomStore::default();
+        store.set("example.com", 1.5);
+        assert_eq!(store.get("example.com"), 1.5);
+        store.set("example.com", DEFAULT_ZOOM);
+        assert!(store.levels.is_empty());
+        assert_eq!(store.get("example.com"), DEFAULT_ZOOM);
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let dir = TempDir::new("zoom");
+        let path = dir.join("roundtrip.json");
+        let mut store = ZoomStore::load(path.clone());
+        store.set("example.com", 1.25);
+        store.set("servo.org", 0.8);
+        store.save().unwrap();
+
+        let reloaded = ZoomStore::load(path);
+        assert_eq!(reloaded.get("example.com"), 1.25);
+        assert_eq!(reloaded.get("servo.org"), 0.8);
+        assert_eq!(reloaded.get("unknown.org"), DEFAULT_ZOOM);
+    }
+
+    #[test]
+    fn test_load_invalid_file_is_empty() {
+        let dir = TempDir::new("zoom");
+        let path = dir.join("invalid.json");
+        fs::write(&path, "[1, 2").unwrap();
+        assert_eq!(ZoomStore::load(path).get("example.com"), DEFAULT_ZOOM);
+    }
+}